@@ -4,7 +4,7 @@ use notion_wasi::{ids::PageId, NotionApi};
 
 #[tokio::main(flavor = "current_thread")]
 async fn main() {
-    let token = env!("NOTION_TOKEN");
+    let token = std::env::var("NOTION_TOKEN").expect("NOTION_TOKEN must be set");
 
     let api = NotionApi::new(token).unwrap();
 
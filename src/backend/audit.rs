@@ -0,0 +1,331 @@
+//! An audit trail of the changes a client makes to a workspace.
+//!
+//! ```no_run
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! use std::io::Write;
+//! use std::sync::Mutex;
+//!
+//! use notion_wasi::{AuditEntry, Auditing, Client, NotionApi};
+//!
+//! let log = Mutex::new(std::fs::File::create("audit.jsonl")?);
+//! let client = Client::new(std::env::var("NOTION_API_TOKEN")?)?;
+//! let client = Auditing::new(client, move |entry: &AuditEntry| {
+//!     let line = serde_json::to_string(entry).unwrap();
+//!     writeln!(log.lock().unwrap(), "{}", line).ok();
+//! });
+//! let api = NotionApi::with_client(client);
+//! # Ok(())
+//! # }
+//! ```
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use super::stats::endpoint;
+use crate::models::Object;
+use crate::{ApiVersion, Error, Method, MultipartForm, RateLimitStatus, Result, TClient};
+
+/// Path segments followed by the id of the object a request changes.
+const RESOURCES: &[&str] = &["pages", "blocks", "databases", "data_sources", "comments"];
+
+/// One write sent by an [Auditing] client.
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct AuditEntry {
+    /// When the response arrived.
+    pub timestamp: DateTime<Utc>,
+    pub method: Method,
+    /// The path with ids replaced, see [RequestStats::endpoint](crate::RequestStats::endpoint).
+    pub endpoint: String,
+    /// The page, block or database that was changed, or created if the API answered.
+    pub target: Option<String>,
+    /// The shape of the request body without its text, so page content doesn't
+    /// end up in audit logs: the field names of objects, such as the names of the
+    /// changed properties, the length of arrays, such as the number of appended
+    /// blocks, and booleans, such as `archived`.
+    pub summary: Value,
+    pub outcome: AuditOutcome,
+}
+
+/// Whether an audited write was applied.
+#[derive(Serialize, Debug, Eq, PartialEq, Clone)]
+#[serde(tag = "result", rename_all = "snake_case")]
+pub enum AuditOutcome {
+    Applied,
+    /// The API answered with an error.
+    Rejected {
+        status: u16,
+        code: String,
+    },
+    /// No answer arrived, so the write may or may not have been applied.
+    Failed {
+        error: String,
+    },
+}
+
+/// Receives the [AuditEntry] of each write, see [Auditing].
+pub trait AuditSink: Send + Sync {
+    fn record(
+        &self,
+        entry: &AuditEntry,
+    );
+}
+
+impl<F: Fn(&AuditEntry) + Send + Sync> AuditSink for F {
+    fn record(
+        &self,
+        entry: &AuditEntry,
+    ) {
+        self(entry)
+    }
+}
+
+/// A [TClient] that passes every request on to another client and reports
+/// the ones that change the workspace to an [AuditSink].
+///
+/// `GET` requests, searches and database queries are not reported.
+pub struct Auditing<C, S> {
+    inner: C,
+    sink: S,
+}
+
+impl<C: TClient, S: AuditSink> Auditing<C, S> {
+    pub fn new(
+        inner: C,
+        sink: S,
+    ) -> Self {
+        Auditing { inner, sink }
+    }
+
+    fn audit(
+        &self,
+        method: Method,
+        uri: &str,
+        body: Option<&[u8]>,
+        result: &Result<Object>,
+    ) {
+        if !is_write(uri) {
+            return;
+        }
+
+        let body = body.and_then(|body| serde_json::from_slice(body).ok());
+        let target = target(uri).or_else(|| match result {
+            Ok(response) => serde_json::to_value(response).ok()?["id"]
+                .as_str()
+                .map(str::to_string),
+            Err(_) => None,
+        });
+        let outcome = match result {
+            Ok(_) => AuditOutcome::Applied,
+            Err(Error::ApiError { error }) => AuditOutcome::Rejected {
+                status: error.status.code(),
+                code: serde_json::to_value(&error.code)
+                    .ok()
+                    .and_then(|code| code.as_str().map(str::to_string))
+                    .unwrap_or_default(),
+            },
+            Err(error) => AuditOutcome::Failed {
+                error: error.to_string(),
+            },
+        };
+
+        self.sink.record(&AuditEntry {
+            timestamp: Utc::now(),
+            method,
+            endpoint: endpoint(uri),
+            target,
+            summary: body.as_ref().map_or(Value::Null, summarize),
+            outcome,
+        });
+    }
+}
+
+#[async_trait]
+impl<C: TClient + Send + Sync, S: AuditSink> TClient for Auditing<C, S> {
+    fn api_version(&self) -> ApiVersion {
+        self.inner.api_version()
+    }
+
+    fn rate_limit_status(&self) -> Option<RateLimitStatus> {
+        self.inner.rate_limit_status()
+    }
+
+    async fn get<U: Into<String> + Send>(
+        &self,
+        uri: U,
+    ) -> Result<Object> {
+        self.inner.get(uri).await
+    }
+
+    async fn get_fresh<U: Into<String> + Send>(
+        &self,
+        uri: U,
+    ) -> Result<Object> {
+        self.inner.get_fresh(uri).await
+    }
+
+    async fn post<U: Into<String> + Send>(
+        &self,
+        uri: U,
+    ) -> Result<Object> {
+        let uri = uri.into();
+        let result = self.inner.post(uri.clone()).await;
+        self.audit(Method::Post, &uri, None, &result);
+        result
+    }
+
+    async fn post_json<U: Into<String> + Send>(
+        &self,
+        uri: U,
+        body: Vec<u8>,
+    ) -> Result<Object> {
+        let uri = uri.into();
+        let result = self.inner.post_json(uri.clone(), body.clone()).await;
+        self.audit(Method::Post, &uri, Some(&body), &result);
+        result
+    }
+
+    async fn patch_json<U: Into<String> + Send>(
+        &self,
+        uri: U,
+        body: Vec<u8>,
+    ) -> Result<Object> {
+        let uri = uri.into();
+        let result = self.inner.patch_json(uri.clone(), body.clone()).await;
+        self.audit(Method::Patch, &uri, Some(&body), &result);
+        result
+    }
+
+    /// Recorded without a summary, the form holds the contents of a file.
+    async fn post_multipart<U: Into<String> + Send>(
+        &self,
+        uri: U,
+        form: MultipartForm,
+    ) -> Result<Object> {
+        let uri = uri.into();
+        let result = self.inner.post_multipart(uri.clone(), form).await;
+        self.audit(Method::Post, &uri, None, &result);
+        result
+    }
+
+    async fn delete<U: Into<String> + Send>(
+        &self,
+        uri: U,
+    ) -> Result<Object> {
+        let uri = uri.into();
+        let result = self.inner.delete(uri.clone()).await;
+        self.audit(Method::Delete, &uri, None, &result);
+        result
+    }
+}
+
+/// Searches and queries are sent as `POST` but only read.
+fn is_write(uri: &str) -> bool {
+    let endpoint = endpoint(uri);
+    !(endpoint.ends_with("/search") || endpoint.ends_with("/query"))
+}
+
+fn target(uri: &str) -> Option<String> {
+    let path = uri.split(['?', '#']).next().unwrap_or_default();
+    let mut segments = path.split('/');
+    segments.find(|segment| RESOURCES.contains(segment))?;
+    segments
+        .next()
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+}
+
+/// See [AuditEntry::summary].
+fn summarize(body: &Value) -> Value {
+    let fields = match body {
+        Value::Object(fields) => fields,
+        _ => return Value::Null,
+    };
+    let summary: Map<String, Value> = fields
+        .iter()
+        .map(|(name, value)| {
+            let summary = match value {
+                Value::Object(fields) => {
+                    Value::Array(fields.keys().cloned().map(Value::String).collect())
+                }
+                Value::Array(values) => Value::from(values.len()),
+                Value::Bool(_) | Value::Null => value.clone(),
+                Value::Number(_) | Value::String(_) => Value::Null,
+            };
+            (name.clone(), summary)
+        })
+        .collect();
+    Value::Object(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_write, summarize, target};
+    use crate::models::search::NotionSearch;
+    use crate::models::PageCreateRequest;
+    use crate::test_util::{MockNotion, PageFixture, MOCK_API_TOKEN};
+    use crate::{AuditEntry, AuditOutcome, Auditing, Client, NotionApi};
+    use serde_json::json;
+
+    #[test]
+    fn summaries_leave_out_content() {
+        let body = json!({
+            "properties": { "Name": { "title": [] }, "Done": { "checkbox": true } },
+            "children": [{}, {}],
+            "archived": false,
+            "icon": "🚀",
+        });
+        assert_eq!(
+            summarize(&body),
+            json!({
+                "properties": ["Done", "Name"],
+                "children": 2,
+                "archived": false,
+                "icon": null,
+            })
+        );
+    }
+
+    #[test]
+    fn targets_and_reads() {
+        assert_eq!(
+            target("https://api.notion.com/v1/blocks/abc/children").as_deref(),
+            Some("abc")
+        );
+        assert_eq!(target("https://api.notion.com/v1/pages"), None);
+        assert!(is_write("https://api.notion.com/v1/pages"));
+        assert!(!is_write("https://api.notion.com/v1/databases/abc/query"));
+        assert!(!is_write("https://api.notion.com/v1/search"));
+    }
+
+    #[tokio::test]
+    async fn audit_writes() {
+        let mock = MockNotion::start().await;
+        let page = PageFixture::new().database("tasks").title("Audit").build();
+        mock.mount_page_writes(&page).await;
+
+        let entries = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+        let sink = {
+            let entries = entries.clone();
+            move |entry: &AuditEntry| entries.lock().unwrap().push(entry.clone())
+        };
+        let client = Auditing::new(Client::new(MOCK_API_TOKEN.to_string()).unwrap(), sink);
+        let api = NotionApi::with_client(client).with_base_url(mock.base_url());
+        // Searches and queries are reads, even though they are POSTed.
+        api.search(NotionSearch::query("Audit")).await.unwrap();
+        api.create_page(PageCreateRequest::new(
+            page.parent.clone(),
+            page.properties.clone(),
+        ))
+        .await
+        .unwrap();
+
+        let entries = entries.lock().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].endpoint, "/v1/pages");
+        assert_eq!(entries[0].target, Some(page.id.to_string()));
+        assert_eq!(entries[0].summary["properties"], json!(["Name"]));
+        assert_eq!(entries[0].outcome, AuditOutcome::Applied);
+    }
+}
@@ -0,0 +1,171 @@
+//! Applying several writes as one best-effort unit.
+//!
+//! Notion has no transactions, so a batch that fails partway leaves the
+//! earlier writes in place. [NotionApi::run_batch()] either archives the pages
+//! the batch created, or hands back the writes that were not applied as a
+//! [WriteQueue] that can be saved and sent later with
+//! [flush_writes()](NotionApi::flush_writes()).
+
+use crate::ids::PageId;
+use crate::models::{Object, PageUpdateRequest};
+use crate::{Error, Method, NotionApi, QueuedWrite, TClient, WriteQueue};
+
+/// What [NotionApi::run_batch()] does when a write fails.
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Default)]
+pub enum OnBatchFailure {
+    /// Stop and keep the writes that were applied.
+    /// The failed write and the ones after it are left in [BatchOutcome::remaining].
+    #[default]
+    Stop,
+    /// Stop and archive the pages created by the batch.
+    ///
+    /// Updates and appended blocks can't be undone and stay applied.
+    ArchiveCreated,
+}
+
+/// A write of a batch that failed.
+#[derive(Debug)]
+pub struct BatchFailure {
+    /// The position of the write in the batch.
+    pub index: usize,
+    pub error: Error,
+}
+
+/// The result of [NotionApi::run_batch()].
+#[derive(Debug, Default)]
+pub struct BatchOutcome {
+    /// The responses to the writes that were applied, in batch order.
+    pub applied: Vec<Object>,
+    /// The write that failed, if the batch didn't complete.
+    pub failure: Option<BatchFailure>,
+    /// The failed write and the ones after it, which were not sent.
+    pub remaining: WriteQueue,
+    /// Pages created by the batch and archived again, see [OnBatchFailure::ArchiveCreated].
+    pub archived: Vec<PageId>,
+    /// Created pages that could not be archived.
+    pub not_archived: Vec<(PageId, Error)>,
+}
+
+impl BatchOutcome {
+    /// Whether every write was applied.
+    pub fn is_complete(&self) -> bool {
+        self.failure.is_none()
+    }
+}
+
+impl<C: TClient> NotionApi<C> {
+    /// Sends `writes` in order, stopping at the first one that fails.
+    ///
+    /// See [OnBatchFailure] for what happens to the writes already applied.
+    pub async fn run_batch<I>(
+        &self,
+        writes: I,
+        on_failure: OnBatchFailure,
+    ) -> BatchOutcome
+    where
+        I: IntoIterator<Item = QueuedWrite>,
+    {
+        let mut outcome = BatchOutcome::default();
+        let mut created = vec![];
+        let mut writes = writes.into_iter().enumerate();
+
+        for (index, write) in writes.by_ref() {
+            match self.send_write(&write).await {
+                Ok(response) => {
+                    if let (Method::Post, Object::Page { page }) = (write.method, &response) {
+                        if write.path == "pages" {
+                            created.push(page.id.clone());
+                        }
+                    }
+                    outcome.applied.push(response);
+                }
+                Err(error) => {
+                    debug!(index, path = %write.path, %error, "Batch write failed");
+                    outcome.failure = Some(BatchFailure { index, error });
+                    outcome.remaining.push(write);
+                    break;
+                }
+            }
+        }
+        if outcome.is_complete() {
+            return outcome;
+        }
+        for (_, write) in writes {
+            outcome.remaining.push(write);
+        }
+
+        if on_failure == OnBatchFailure::ArchiveCreated {
+            for page_id in created.into_iter().rev() {
+                match self
+                    .update_page(&page_id, PageUpdateRequest::archive())
+                    .await
+                {
+                    Ok(_) => outcome.archived.push(page_id),
+                    Err(error) => outcome.not_archived.push((page_id, error)),
+                }
+            }
+        }
+        outcome
+    }
+}
+
+#[cfg(all(test, not(target_os = "wasi")))]
+mod tests {
+    use crate::ids::PageId;
+    use crate::models::{Object, PageCreateRequest, Parent};
+    use crate::test_util::{MockNotion, PageFixture};
+    use crate::{OnBatchFailure, WriteQueue};
+    use serde_json::json;
+    use std::str::FromStr;
+    use wiremock::matchers::{body_json, method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    #[tokio::test]
+    async fn failed_batches_archive_created_pages() {
+        let mock = MockNotion::start().await;
+        let page = PageFixture::new().title("Created").build();
+        Mock::given(method("POST"))
+            .and(path("/v1/pages"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(Object::Page { page: page.clone() }),
+            )
+            .expect(1)
+            .mount(mock.server())
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path(format!("/v1/pages/{}", page.id)))
+            .and(body_json(json!({"properties": {}, "archived": true})))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(Object::Page { page: page.clone() }),
+            )
+            .expect(1)
+            .mount(mock.server())
+            .await;
+        let create = WriteQueue::create_page(PageCreateRequest::new(
+            Parent::Page {
+                page_id: PageId::from_str("parent").unwrap(),
+            },
+            page.properties.clone(),
+        ))
+        .unwrap();
+        let update = WriteQueue::update_page(
+            PageId::from_str("missing").unwrap(),
+            page.properties.clone(),
+        );
+        let writes = vec![create.clone(), update.clone(), create.clone()];
+
+        let outcome = mock
+            .api()
+            .run_batch(writes.clone(), OnBatchFailure::ArchiveCreated)
+            .await;
+        assert!(!outcome.is_complete());
+        assert_eq!(outcome.applied.len(), 1);
+        assert_eq!(outcome.failure.as_ref().unwrap().index, 1);
+        assert_eq!(
+            outcome.remaining.writes().collect::<Vec<_>>(),
+            vec![&update, &create]
+        );
+        assert_eq!(outcome.archived, vec![page.id.clone()]);
+        assert!(outcome.not_archived.is_empty());
+    }
+}
@@ -0,0 +1,116 @@
+//! Trace logging of request and response bodies, enabled by the `log-bodies` feature.
+//!
+//! Bodies are logged with every string and number replaced by `"[redacted]"`,
+//! except for the fields below, so logs show the shape of the traffic and the
+//! ids involved without the content of pages, property values or OAuth tokens.
+
+use serde_json::Value;
+
+const REDACTED: &str = "[redacted]";
+
+/// Fields that identify objects or describe errors rather than hold content.
+const KEPT_FIELDS: &[&str] = &[
+    "object",
+    "id",
+    "type",
+    "created_time",
+    "last_edited_time",
+    "next_cursor",
+    "start_cursor",
+    "page_size",
+    "page_id",
+    "database_id",
+    "block_id",
+    "data_source_id",
+    "workspace_id",
+    "user_id",
+    "status",
+    "code",
+    "message",
+    "request_id",
+];
+
+pub(crate) fn log_request(
+    method: &str,
+    url: &str,
+    body: &[u8],
+) {
+    tracing::trace!(method, url, body = %redact(body), "Request body");
+}
+
+pub(crate) fn log_response(body: &[u8]) {
+    tracing::trace!(body = %redact(body), "Response body");
+}
+
+/// The redacted body as JSON, or only its length if it isn't JSON.
+fn redact(body: &[u8]) -> String {
+    match serde_json::from_slice::<Value>(body) {
+        Ok(mut value) => {
+            redact_value(&mut value);
+            value.to_string()
+        }
+        Err(_) => format!("<{} bytes>", body.len()),
+    }
+}
+
+fn redact_value(value: &mut Value) {
+    match value {
+        Value::String(_) | Value::Number(_) => *value = Value::from(REDACTED),
+        Value::Array(values) => values.iter_mut().for_each(redact_value),
+        Value::Object(fields) => {
+            for (name, field) in fields.iter_mut() {
+                let kept = KEPT_FIELDS.contains(&name.as_str())
+                    && matches!(field, Value::String(_) | Value::Number(_));
+                if !kept {
+                    redact_value(field);
+                }
+            }
+        }
+        Value::Bool(_) | Value::Null => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::redact;
+    use serde_json::json;
+
+    #[test]
+    fn redacts_content_but_keeps_ids() {
+        let body = json!({
+            "object": "page",
+            "id": "b55c9c91-384d-452b-81db-d1ef79372b75",
+            "properties": {
+                "Name": {
+                    "id": "title",
+                    "type": "title",
+                    "title": [{ "type": "text", "plain_text": "Salary review" }]
+                },
+                "Amount": { "id": "%3AQ%3E", "type": "number", "number": 120000 },
+                "Done": { "id": "a%3Cq", "type": "checkbox", "checkbox": true }
+            },
+            "access_token": "secret_abc"
+        });
+        let redacted: serde_json::Value =
+            serde_json::from_str(&redact(body.to_string().as_bytes())).unwrap();
+
+        assert_eq!(
+            redacted,
+            json!({
+                "object": "page",
+                "id": "b55c9c91-384d-452b-81db-d1ef79372b75",
+                "properties": {
+                    "Name": {
+                        "id": "title",
+                        "type": "title",
+                        "title": [{ "type": "text", "plain_text": "[redacted]" }]
+                    },
+                    "Amount": { "id": "%3AQ%3E", "type": "number", "number": "[redacted]" },
+                    "Done": { "id": "a%3Cq", "type": "checkbox", "checkbox": true }
+                },
+                "access_token": "[redacted]"
+            })
+        );
+        assert_eq!(redact(b"not json"), "<8 bytes>");
+    }
+}
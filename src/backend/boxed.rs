@@ -0,0 +1,199 @@
+//! Choosing the client at runtime.
+
+use async_trait::async_trait;
+
+use crate::models::Object;
+use crate::{ApiVersion, MultipartForm, RateLimitStatus, Result, TClient};
+
+/// The object safe counterpart of [TClient], implemented for every [TClient].
+///
+/// [TClient] has generic methods and can't be made into a trait object,
+/// use a [BoxedClient] where the client is picked at runtime instead:
+///
+/// ```no_run
+/// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// use notion_wasi::{BoxedClient, Client, Coalescing, NotionApi};
+///
+/// let client = Client::new(std::env::var("NOTION_API_TOKEN")?)?;
+/// let client: BoxedClient = if std::env::var("NOTION_COALESCE").is_ok() {
+///     Box::new(Coalescing::new(client))
+/// } else {
+///     Box::new(client)
+/// };
+/// let api = NotionApi::with_client(client);
+/// # Ok(())
+/// # }
+/// ```
+#[async_trait]
+pub trait DynClient {
+    fn api_version(&self) -> ApiVersion;
+
+    fn rate_limit_status(&self) -> Option<RateLimitStatus>;
+
+    async fn get(
+        &self,
+        uri: String,
+    ) -> Result<Object>;
+
+    async fn get_fresh(
+        &self,
+        uri: String,
+    ) -> Result<Object>;
+
+    async fn post(
+        &self,
+        uri: String,
+    ) -> Result<Object>;
+
+    async fn post_json(
+        &self,
+        uri: String,
+        body: Vec<u8>,
+    ) -> Result<Object>;
+
+    async fn patch_json(
+        &self,
+        uri: String,
+        body: Vec<u8>,
+    ) -> Result<Object>;
+
+    async fn post_multipart(
+        &self,
+        uri: String,
+        form: MultipartForm,
+    ) -> Result<Object>;
+
+    async fn delete(
+        &self,
+        uri: String,
+    ) -> Result<Object>;
+}
+
+/// Any client behind a pointer, see [DynClient].
+pub type BoxedClient = Box<dyn DynClient + Send + Sync>;
+
+#[async_trait]
+impl<C: TClient + Send + Sync> DynClient for C {
+    fn api_version(&self) -> ApiVersion {
+        TClient::api_version(self)
+    }
+
+    fn rate_limit_status(&self) -> Option<RateLimitStatus> {
+        TClient::rate_limit_status(self)
+    }
+
+    async fn get(
+        &self,
+        uri: String,
+    ) -> Result<Object> {
+        TClient::get(self, uri).await
+    }
+
+    async fn get_fresh(
+        &self,
+        uri: String,
+    ) -> Result<Object> {
+        TClient::get_fresh(self, uri).await
+    }
+
+    async fn post(
+        &self,
+        uri: String,
+    ) -> Result<Object> {
+        TClient::post(self, uri).await
+    }
+
+    async fn post_json(
+        &self,
+        uri: String,
+        body: Vec<u8>,
+    ) -> Result<Object> {
+        TClient::post_json(self, uri, body).await
+    }
+
+    async fn patch_json(
+        &self,
+        uri: String,
+        body: Vec<u8>,
+    ) -> Result<Object> {
+        TClient::patch_json(self, uri, body).await
+    }
+
+    async fn post_multipart(
+        &self,
+        uri: String,
+        form: MultipartForm,
+    ) -> Result<Object> {
+        TClient::post_multipart(self, uri, form).await
+    }
+
+    async fn delete(
+        &self,
+        uri: String,
+    ) -> Result<Object> {
+        TClient::delete(self, uri).await
+    }
+}
+
+#[async_trait]
+impl TClient for BoxedClient {
+    fn api_version(&self) -> ApiVersion {
+        DynClient::api_version(self.as_ref())
+    }
+
+    fn rate_limit_status(&self) -> Option<RateLimitStatus> {
+        DynClient::rate_limit_status(self.as_ref())
+    }
+
+    async fn get<S: Into<String> + Send>(
+        &self,
+        uri: S,
+    ) -> Result<Object> {
+        DynClient::get(self.as_ref(), uri.into()).await
+    }
+
+    async fn get_fresh<S: Into<String> + Send>(
+        &self,
+        uri: S,
+    ) -> Result<Object> {
+        DynClient::get_fresh(self.as_ref(), uri.into()).await
+    }
+
+    async fn post<S: Into<String> + Send>(
+        &self,
+        uri: S,
+    ) -> Result<Object> {
+        DynClient::post(self.as_ref(), uri.into()).await
+    }
+
+    async fn post_json<S: Into<String> + Send>(
+        &self,
+        uri: S,
+        body: Vec<u8>,
+    ) -> Result<Object> {
+        DynClient::post_json(self.as_ref(), uri.into(), body).await
+    }
+
+    async fn patch_json<S: Into<String> + Send>(
+        &self,
+        uri: S,
+        body: Vec<u8>,
+    ) -> Result<Object> {
+        DynClient::patch_json(self.as_ref(), uri.into(), body).await
+    }
+
+    async fn post_multipart<S: Into<String> + Send>(
+        &self,
+        uri: S,
+        form: MultipartForm,
+    ) -> Result<Object> {
+        DynClient::post_multipart(self.as_ref(), uri.into(), form).await
+    }
+
+    async fn delete<S: Into<String> + Send>(
+        &self,
+        uri: S,
+    ) -> Result<Object> {
+        DynClient::delete(self.as_ref(), uri.into()).await
+    }
+}
@@ -0,0 +1,491 @@
+//! Answering repeated GET requests for pages, databases and blocks from a cache.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::Object;
+use crate::{ApiVersion, MultipartForm, RateLimitStatus, Result, TClient};
+
+/// A cached response of [Caching].
+///
+/// Entries can be serialized, to keep them in a store outside the process.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CacheEntry {
+    pub object: Object,
+    /// The `last_edited_time` of the object, compared with the one
+    /// in list responses to find out whether the entry is stale.
+    pub last_edited_time: DateTime<Utc>,
+    pub stored_at: DateTime<Utc>,
+}
+
+/// Where [Caching] keeps its responses.
+///
+/// Keys look like `pages/{id}`, with the id in lowercase and without dashes.
+pub trait Cache: Send + Sync {
+    fn get(
+        &self,
+        key: &str,
+    ) -> Option<CacheEntry>;
+
+    fn insert(
+        &self,
+        key: String,
+        entry: CacheEntry,
+    );
+
+    fn remove(
+        &self,
+        key: &str,
+    );
+}
+
+/// Keeps up to `capacity` entries in memory, dropping the least recently used one.
+#[derive(Debug)]
+pub struct LruCache {
+    capacity: usize,
+    state: Mutex<LruState>,
+}
+
+#[derive(Debug, Default)]
+struct LruState {
+    entries: HashMap<String, (CacheEntry, u64)>,
+    /// The keys by when they were last used, oldest first.
+    used: BTreeMap<u64, String>,
+    clock: u64,
+}
+
+impl LruState {
+    fn touch(
+        &mut self,
+        key: &str,
+    ) -> Option<&CacheEntry> {
+        self.clock += 1;
+        let (entry, used) = self.entries.get_mut(key)?;
+        self.used.remove(used);
+        *used = self.clock;
+        self.used.insert(self.clock, key.to_string());
+        Some(entry)
+    }
+}
+
+impl LruCache {
+    pub fn new(capacity: usize) -> Self {
+        LruCache {
+            capacity: capacity.max(1),
+            state: Mutex::default(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Cache for LruCache {
+    fn get(
+        &self,
+        key: &str,
+    ) -> Option<CacheEntry> {
+        self.state.lock().unwrap().touch(key).cloned()
+    }
+
+    fn insert(
+        &self,
+        key: String,
+        entry: CacheEntry,
+    ) {
+        let mut state = self.state.lock().unwrap();
+        state.clock += 1;
+        let clock = state.clock;
+        if let Some((_, used)) = state.entries.insert(key.clone(), (entry, clock)) {
+            state.used.remove(&used);
+        }
+        state.used.insert(clock, key);
+        while state.entries.len() > self.capacity {
+            let oldest = match state.used.pop_first() {
+                Some((_, oldest)) => oldest,
+                None => break,
+            };
+            state.entries.remove(&oldest);
+        }
+    }
+
+    fn remove(
+        &self,
+        key: &str,
+    ) {
+        let mut state = self.state.lock().unwrap();
+        if let Some((_, used)) = state.entries.remove(key) {
+            state.used.remove(&used);
+        }
+    }
+}
+
+/// A [TClient] that answers GET requests for single pages, databases and blocks
+/// from a [Cache], created with [NotionApi::with_cache()](crate::NotionApi::with_cache()).
+///
+/// ```no_run
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// use notion_wasi::{LruCache, NotionApi};
+///
+/// let api = NotionApi::new(std::env::var("NOTION_API_TOKEN")?)?
+///     .with_cache(Arc::new(LruCache::new(1000)), Some(Duration::from_secs(300)));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// An entry is dropped when the object is written through the client, and when a
+/// list response, such as the result of a query or search, reports a later
+/// `last_edited_time` for it. Edits made elsewhere are otherwise only noticed once
+/// the entry is older than the maximum age.
+/// Reads that must see the current version, such as the check of
+/// [update_page_if_unchanged()](crate::NotionApi::update_page_if_unchanged()) and the
+/// polls of [watch_page()](crate::NotionApi::watch_page()), skip the cache with
+/// [TClient::get_fresh()], and store the response they get.
+/// Requests with a query string, such as the ones with `filter_properties`, aren't cached.
+pub struct Caching<C> {
+    inner: C,
+    cache: Arc<dyn Cache>,
+    max_age: Option<Duration>,
+}
+
+impl<C: TClient> Caching<C> {
+    /// Keeps entries until they are stale, or for at most `max_age`.
+    pub fn new(
+        inner: C,
+        cache: Arc<dyn Cache>,
+        max_age: Option<Duration>,
+    ) -> Self {
+        Caching {
+            inner,
+            cache,
+            max_age,
+        }
+    }
+
+    pub fn cache(&self) -> &Arc<dyn Cache> {
+        &self.cache
+    }
+
+    fn cached(
+        &self,
+        key: &str,
+    ) -> Option<Object> {
+        let entry = self.cache.get(key)?;
+        let age = (Utc::now() - entry.stored_at).to_std().unwrap_or_default();
+        if self.max_age.is_some_and(|max_age| age > max_age) {
+            self.cache.remove(key);
+            return None;
+        }
+        Some(entry.object)
+    }
+
+    /// Drops the entries that `response` reports as edited since they were stored.
+    fn invalidate(
+        &self,
+        response: &Object,
+    ) {
+        if let Object::List { list } = response {
+            for (key, edited) in list.results.iter().filter_map(edited) {
+                let stale = self
+                    .cache
+                    .get(&key)
+                    .is_some_and(|entry| entry.last_edited_time < edited);
+                if stale {
+                    self.cache.remove(&key);
+                }
+            }
+        }
+    }
+
+    /// Keeps the response to a GET request of `uri`, if it can be cached.
+    fn store(
+        &self,
+        uri: &str,
+        response: &Object,
+    ) {
+        self.invalidate(response);
+        if let (Some(key), Some((_, last_edited_time))) = (cache_key(uri), edited(response)) {
+            let entry = CacheEntry {
+                object: response.clone(),
+                last_edited_time,
+                stored_at: Utc::now(),
+            };
+            self.cache.insert(key, entry);
+        }
+    }
+
+    /// Drops the entry of the object a write to `uri` changes.
+    fn written(
+        &self,
+        uri: &str,
+    ) {
+        let path = uri.split('?').next().unwrap_or_default();
+        let path = path.strip_suffix("/children").unwrap_or(path);
+        if let Some(key) = cache_key(path) {
+            self.cache.remove(&key);
+        }
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn write(
+        &self,
+        uri: &str,
+        response: Result<Object>,
+    ) -> Result<Object> {
+        self.written(uri);
+        if let Ok(response) = &response {
+            self.invalidate(response);
+        }
+        response
+    }
+}
+
+/// The cache key of `uri` if it is a single page, database, data source or block.
+fn cache_key(uri: &str) -> Option<String> {
+    if uri.contains('?') {
+        return None;
+    }
+    let mut segments = uri.rsplit('/');
+    let id = segments.next()?;
+    let kind = segments.next()?;
+    match kind {
+        "pages" | "databases" | "data_sources" | "blocks" if !id.is_empty() => Some(key(kind, id)),
+        _ => None,
+    }
+}
+
+fn key(
+    kind: &str,
+    id: &str,
+) -> String {
+    format!("{}/{}", kind, id.replace('-', "").to_lowercase())
+}
+
+/// The cache key and `last_edited_time` of an object that can be cached.
+fn edited(object: &Object) -> Option<(String, DateTime<Utc>)> {
+    match object {
+        Object::Page { page } => Some((key("pages", &page.id.to_string()), page.last_edited_time)),
+        #[cfg(feature = "databases")]
+        Object::Database { database } => Some((
+            key("databases", &database.id.to_string()),
+            database.last_edited_time,
+        )),
+        #[cfg(feature = "databases")]
+        Object::DataSource { data_source } => Some((
+            key("data_sources", &data_source.id.to_string()),
+            data_source.last_edited_time,
+        )),
+        #[cfg(feature = "blocks")]
+        Object::Block { block } => {
+            let common = block.common()?;
+            Some((
+                key("blocks", &common.id.to_string()),
+                common.last_edited_time,
+            ))
+        }
+        _ => None,
+    }
+}
+
+#[async_trait]
+impl<C: TClient + Send + Sync> TClient for Caching<C> {
+    fn api_version(&self) -> ApiVersion {
+        self.inner.api_version()
+    }
+
+    fn rate_limit_status(&self) -> Option<RateLimitStatus> {
+        self.inner.rate_limit_status()
+    }
+
+    async fn get<S: Into<String> + Send>(
+        &self,
+        uri: S,
+    ) -> Result<Object> {
+        let uri = uri.into();
+        if let Some(object) = cache_key(&uri).and_then(|key| self.cached(&key)) {
+            trace!(%uri, "Answered from cache");
+            return Ok(object);
+        }
+
+        let response = self.inner.get(uri.clone()).await?;
+        self.store(&uri, &response);
+        Ok(response)
+    }
+
+    async fn get_fresh<S: Into<String> + Send>(
+        &self,
+        uri: S,
+    ) -> Result<Object> {
+        let uri = uri.into();
+        let response = self.inner.get_fresh(uri.clone()).await?;
+        self.store(&uri, &response);
+        Ok(response)
+    }
+
+    async fn post<S: Into<String> + Send>(
+        &self,
+        uri: S,
+    ) -> Result<Object> {
+        let uri = uri.into();
+        let response = self.inner.post(uri.clone()).await;
+        self.write(&uri, response)
+    }
+
+    async fn post_json<S: Into<String> + Send>(
+        &self,
+        uri: S,
+        body: Vec<u8>,
+    ) -> Result<Object> {
+        let uri = uri.into();
+        let response = self.inner.post_json(uri.clone(), body).await;
+        self.write(&uri, response)
+    }
+
+    async fn patch_json<S: Into<String> + Send>(
+        &self,
+        uri: S,
+        body: Vec<u8>,
+    ) -> Result<Object> {
+        let uri = uri.into();
+        let response = self.inner.patch_json(uri.clone(), body).await;
+        self.write(&uri, response)
+    }
+
+    async fn post_multipart<S: Into<String> + Send>(
+        &self,
+        uri: S,
+        form: MultipartForm,
+    ) -> Result<Object> {
+        let uri = uri.into();
+        let response = self.inner.post_multipart(uri.clone(), form).await;
+        self.write(&uri, response)
+    }
+
+    async fn delete<S: Into<String> + Send>(
+        &self,
+        uri: S,
+    ) -> Result<Object> {
+        let uri = uri.into();
+        let response = self.inner.delete(uri.clone()).await;
+        self.write(&uri, response)
+    }
+}
+
+#[cfg(all(test, feature = "databases", not(target_os = "wasi")))]
+mod tests {
+    use std::sync::Arc;
+
+    use super::{Cache, CacheEntry, LruCache};
+    use crate::models::search::DatabaseQuery;
+    use crate::models::{Object, PageUpdateRequest};
+    use crate::test_util::{DatabaseFixture, MockClient, PageFixture};
+    use crate::ConditionalUpdate;
+
+    #[tokio::test]
+    async fn stale_and_written_pages_are_fetched_again() {
+        let database = DatabaseFixture::new().build();
+        let page = PageFixture::new().title("Dashboard").build();
+        let mut edited = page.clone();
+        edited.last_edited_time = page.last_edited_time + chrono::Duration::minutes(1);
+        let client = MockClient::new();
+        client.mount_page(&page);
+        client.mount_page(&edited);
+        client.mount_query(&database.id, vec![edited.clone()]);
+        client.mount(
+            "PATCH",
+            format!("/pages/{}", page.id),
+            Object::Page {
+                page: edited.clone(),
+            },
+        );
+        let api = client.api().with_cache(Arc::new(LruCache::new(10)), None);
+        let gets = |client: &MockClient| {
+            client
+                .requests()
+                .iter()
+                .filter(|request| request.method == "GET")
+                .count()
+        };
+
+        assert_eq!(api.get_page(&page.id).await.unwrap(), page);
+        assert_eq!(api.get_page(&page.id).await.unwrap(), page);
+        assert_eq!(gets(&api.client().inner), 1);
+
+        // The query reports a later edit, so the cached page is stale.
+        api.query_database(&database.id, DatabaseQuery::default())
+            .await
+            .unwrap();
+        assert_eq!(api.get_page(&page.id).await.unwrap(), edited);
+        assert_eq!(api.get_page(&page.id).await.unwrap(), edited);
+        assert_eq!(gets(&api.client().inner), 2);
+
+        api.update_page(&page.id, PageUpdateRequest::archive())
+            .await
+            .unwrap();
+        api.get_page(&page.id).await.unwrap();
+        assert_eq!(gets(&api.client().inner), 3);
+    }
+
+    #[tokio::test]
+    async fn conditional_updates_bypass_the_cache() {
+        let page = PageFixture::new().title("Dashboard").build();
+        let mut edited = page.clone();
+        edited.last_edited_time = page.last_edited_time + chrono::Duration::minutes(1);
+        let client = MockClient::new();
+        client.mount_page(&page);
+        client.mount_page(&edited);
+        let api = client.api().with_cache(Arc::new(LruCache::new(10)), None);
+
+        assert_eq!(api.get_page(&page.id).await.unwrap(), page);
+        let update = api
+            .update_page_if_unchanged(
+                &page.id,
+                page.last_edited_time,
+                PageUpdateRequest::archive(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            update,
+            ConditionalUpdate::Conflict {
+                current: edited.clone()
+            }
+        );
+        assert_eq!(api.get_page(&page.id).await.unwrap(), edited);
+    }
+
+    #[test]
+    fn lru_cache_drops_least_recently_used() {
+        let cache = LruCache::new(2);
+        let entry = CacheEntry {
+            object: Object::Page {
+                page: PageFixture::new().build(),
+            },
+            last_edited_time: chrono::Utc::now(),
+            stored_at: chrono::Utc::now(),
+        };
+        cache.insert("pages/a".to_string(), entry.clone());
+        cache.insert("pages/b".to_string(), entry.clone());
+        assert!(cache.get("pages/a").is_some());
+        cache.insert("pages/c".to_string(), entry);
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get("pages/a").is_some());
+        assert!(cache.get("pages/b").is_none());
+        cache.remove("pages/a");
+        assert!(cache.get("pages/a").is_none());
+    }
+}
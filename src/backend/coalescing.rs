@@ -0,0 +1,215 @@
+//! Sharing the responses of identical concurrent GET requests.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use futures::channel::oneshot;
+
+use crate::models::error::ErrorResponse;
+use crate::models::Object;
+use crate::{ApiVersion, Error, MultipartForm, RateLimitStatus, Result, TClient};
+
+/// What a waiting request receives from the request it joined.
+/// `None` if the error can't be shared, in which case the waiter sends its own request.
+type Shared = Option<std::result::Result<Object, ErrorResponse>>;
+
+/// A [TClient] that sends GET requests for a URI only once while one is in flight,
+/// and hands its response to every caller that asked for the same URI meanwhile.
+///
+/// ```no_run
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// use notion_wasi::{Client, Coalescing, NotionApi};
+///
+/// let client = Client::new(std::env::var("NOTION_API_TOKEN")?)?;
+/// let api = NotionApi::with_client(Coalescing::new(client));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// API errors are shared as well. Transport errors are not, so callers
+/// waiting on a request that failed to send retry on their own.
+/// Only requests that overlap in time are combined, nothing is cached.
+pub struct Coalescing<C> {
+    inner: C,
+    in_flight: Mutex<HashMap<String, Vec<oneshot::Sender<Shared>>>>,
+}
+
+impl<C: TClient> Coalescing<C> {
+    pub fn new(inner: C) -> Self {
+        Coalescing {
+            inner,
+            in_flight: Mutex::default(),
+        }
+    }
+}
+
+/// Removes the in-flight entry even if the leading request is dropped,
+/// which wakes up the waiters so they can send their own requests.
+struct InFlight<'a> {
+    in_flight: &'a Mutex<HashMap<String, Vec<oneshot::Sender<Shared>>>>,
+    uri: &'a str,
+    finished: bool,
+}
+
+impl InFlight<'_> {
+    fn finish(
+        mut self,
+        result: &Result<Object>,
+    ) {
+        self.finished = true;
+        let waiters = self.in_flight.lock().unwrap().remove(self.uri);
+        for waiter in waiters.into_iter().flatten() {
+            let shared = match result {
+                Ok(object) => Some(Ok(object.clone())),
+                Err(Error::ApiError { error }) => Some(Err(error.clone())),
+                Err(_) => None,
+            };
+            let _ = waiter.send(shared);
+        }
+    }
+}
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.in_flight.lock().unwrap().remove(self.uri);
+        }
+    }
+}
+
+#[async_trait]
+impl<C: TClient + Send + Sync> TClient for Coalescing<C> {
+    fn api_version(&self) -> ApiVersion {
+        self.inner.api_version()
+    }
+
+    fn rate_limit_status(&self) -> Option<RateLimitStatus> {
+        self.inner.rate_limit_status()
+    }
+
+    async fn get<S: Into<String> + Send>(
+        &self,
+        uri: S,
+    ) -> Result<Object> {
+        let uri = uri.into();
+
+        let waiting = {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            match in_flight.get_mut(&uri) {
+                Some(waiters) => {
+                    let (sender, receiver) = oneshot::channel();
+                    waiters.push(sender);
+                    Some(receiver)
+                }
+                None => {
+                    in_flight.insert(uri.clone(), vec![]);
+                    None
+                }
+            }
+        };
+
+        if let Some(receiver) = waiting {
+            trace!(%uri, "Joining in-flight request");
+            return match receiver.await {
+                Ok(Some(Ok(object))) => Ok(object),
+                Ok(Some(Err(error))) => Err(Error::ApiError { error }),
+                Ok(None) | Err(oneshot::Canceled) => self.inner.get(uri).await,
+            };
+        }
+
+        let guard = InFlight {
+            in_flight: &self.in_flight,
+            uri: &uri,
+            finished: false,
+        };
+        let result = self.inner.get(uri.clone()).await;
+        guard.finish(&result);
+        result
+    }
+
+    /// Not joined with a request in flight, which may be answered from a cache.
+    async fn get_fresh<S: Into<String> + Send>(
+        &self,
+        uri: S,
+    ) -> Result<Object> {
+        self.inner.get_fresh(uri).await
+    }
+
+    async fn post<S: Into<String> + Send>(
+        &self,
+        uri: S,
+    ) -> Result<Object> {
+        self.inner.post(uri).await
+    }
+
+    async fn post_json<S: Into<String> + Send>(
+        &self,
+        uri: S,
+        body: Vec<u8>,
+    ) -> Result<Object> {
+        self.inner.post_json(uri, body).await
+    }
+
+    async fn patch_json<S: Into<String> + Send>(
+        &self,
+        uri: S,
+        body: Vec<u8>,
+    ) -> Result<Object> {
+        self.inner.patch_json(uri, body).await
+    }
+
+    async fn post_multipart<S: Into<String> + Send>(
+        &self,
+        uri: S,
+        form: MultipartForm,
+    ) -> Result<Object> {
+        self.inner.post_multipart(uri, form).await
+    }
+
+    async fn delete<S: Into<String> + Send>(
+        &self,
+        uri: S,
+    ) -> Result<Object> {
+        self.inner.delete(uri).await
+    }
+}
+
+#[cfg(all(test, not(target_os = "wasi")))]
+mod tests {
+    use crate::ids::PageId;
+    use crate::models::Object;
+    use crate::test_util::{MockNotion, PageFixture, MOCK_API_TOKEN};
+    use crate::{Client, Coalescing, Error, NotionApi};
+    use std::str::FromStr;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    #[tokio::test]
+    async fn coalesces_concurrent_gets() {
+        let mock = MockNotion::start().await;
+        let page = PageFixture::new().build();
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/pages/{}", page.id)))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(Object::Page { page: page.clone() })
+                    .set_delay(std::time::Duration::from_millis(100)),
+            )
+            .expect(1)
+            .mount(mock.server())
+            .await;
+        let missing = PageId::from_str("missing").unwrap();
+
+        let client = Client::new(MOCK_API_TOKEN.to_string()).unwrap();
+        let api = NotionApi::with_client(Coalescing::new(client)).with_base_url(mock.base_url());
+        let (first, second, third) = futures::join!(
+            api.get_page(&page.id),
+            api.get_page(&page.id),
+            api.get_page(&missing)
+        );
+        assert_eq!(first.unwrap(), page);
+        assert_eq!(second.unwrap(), page);
+        assert!(matches!(third, Err(Error::ApiError { .. })));
+    }
+}
@@ -0,0 +1,329 @@
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::models::error::{HttpErrorResponse, StatusCode, MAX_ERROR_BODY};
+use crate::models::lenient::{self, SkippedItem};
+use crate::models::Object;
+use crate::{Error, Result};
+
+/// Decodes response bodies, set with [ClientBuilder::codec()](crate::ClientBuilder::codec()).
+///
+/// The default is [SerdeJsonCodec], or [SimdJsonCodec] with the `simd-json` feature.
+/// Other parsers, such as `serde_path_to_error` for the path of the field that
+/// failed to parse, can be plugged in by implementing this trait and reporting
+/// their errors as [Error::DecodeFailed].
+pub trait JsonCodec: fmt::Debug + Send + Sync {
+    #[allow(clippy::result_large_err)]
+    fn decode(
+        &self,
+        body: Vec<u8>,
+    ) -> Result<Object>;
+}
+
+/// The headers Notion's request id may be sent in, in the order they are checked.
+pub(crate) const REQUEST_ID_HEADERS: [&str; 2] = ["x-notion-request-id", "x-request-id"];
+
+/// What the clients know about a response besides its body.
+pub(crate) struct ResponseHead {
+    pub status: u16,
+    pub request_id: Option<String>,
+    pub retry_after: Option<Duration>,
+}
+
+/// Decodes a response with `codec`, turning Notion errors into [Error::ApiError]
+/// and bodies that can't be decoded into errors that keep the start of the body.
+#[allow(clippy::result_large_err)]
+pub(crate) fn decode_response(
+    codec: &dyn JsonCodec,
+    head: ResponseHead,
+    body: &[u8],
+) -> Result<Object> {
+    match codec.decode(body.to_vec()) {
+        Ok(Object::Error { mut error }) => {
+            error.retry_after = head.retry_after;
+            error.request_id = error.request_id.or(head.request_id);
+            Err(Error::ApiError { error })
+        }
+        Ok(response) => Ok(response),
+        Err(_) if !(200..300).contains(&head.status) => Err(Error::HttpError {
+            response: HttpErrorResponse {
+                status: StatusCode::from(head.status),
+                request_id: head.request_id,
+                retry_after: head.retry_after,
+                body: excerpt(body),
+            },
+        }),
+        Err(Error::JsonParseError { source, body: None }) => Err(Error::JsonParseError {
+            source,
+            body: Some(excerpt(body)),
+        }),
+        #[cfg(all(feature = "simd-json", not(target_os = "wasi")))]
+        Err(Error::SimdJsonParseError { source, body: None }) => Err(Error::SimdJsonParseError {
+            source,
+            body: Some(excerpt(body)),
+        }),
+        Err(error) => Err(error),
+    }
+}
+
+/// The start of `body`, cut at a character boundary.
+fn excerpt(body: &[u8]) -> String {
+    let mut text = String::from_utf8_lossy(&body[..body.len().min(MAX_ERROR_BODY)]).into_owned();
+    if body.len() > MAX_ERROR_BODY {
+        // The cut may have split the last character.
+        text = text
+            .trim_end_matches(char::REPLACEMENT_CHARACTER)
+            .to_string();
+    }
+    text
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SerdeJsonCodec;
+
+impl JsonCodec for SerdeJsonCodec {
+    fn decode(
+        &self,
+        body: Vec<u8>,
+    ) -> Result<Object> {
+        serde_json::from_slice(&body).map_err(|source| Error::JsonParseError { source, body: None })
+    }
+}
+
+/// Parses with serde_json, leaving out the items of list responses that fail to parse
+/// instead of failing the whole request, see [models::lenient](crate::models::lenient).
+///
+/// Skipped items are logged as warnings and passed to the callback set with
+/// [on_skipped()](Self::on_skipped()).
+#[derive(Default, Clone)]
+pub struct LenientJsonCodec {
+    on_skipped: Option<OnSkipped>,
+}
+
+type OnSkipped = Arc<dyn Fn(&SkippedItem) + Send + Sync>;
+
+impl LenientJsonCodec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn on_skipped<F>(
+        mut self,
+        on_skipped: F,
+    ) -> Self
+    where
+        F: Fn(&SkippedItem) + Send + Sync + 'static,
+    {
+        self.on_skipped = Some(Arc::new(on_skipped));
+        self
+    }
+}
+
+impl fmt::Debug for LenientJsonCodec {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        f.debug_struct("LenientJsonCodec")
+            .field("on_skipped", &self.on_skipped.is_some())
+            .finish()
+    }
+}
+
+impl JsonCodec for LenientJsonCodec {
+    fn decode(
+        &self,
+        body: Vec<u8>,
+    ) -> Result<Object> {
+        let parsed = lenient::parse_object(&body)
+            .map_err(|source| Error::JsonParseError { source, body: None })?;
+        for item in &parsed.skipped {
+            warn!(
+                index = item.index,
+                object = ?item.object,
+                id = ?item.id,
+                error = %item.error,
+                "Skipping list item that failed to parse"
+            );
+            if let Some(on_skipped) = &self.on_skipped {
+                on_skipped(item);
+            }
+        }
+        Ok(parsed.value)
+    }
+}
+
+/// Parses with simd-json, in place in the response buffer.
+#[cfg(all(feature = "simd-json", not(target_os = "wasi")))]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SimdJsonCodec;
+
+#[cfg(all(feature = "simd-json", not(target_os = "wasi")))]
+impl JsonCodec for SimdJsonCodec {
+    fn decode(
+        &self,
+        mut body: Vec<u8>,
+    ) -> Result<Object> {
+        simd_json::serde::from_slice(&mut body)
+            .map_err(|source| Error::SimdJsonParseError { source, body: None })
+    }
+}
+
+#[cfg(not(all(feature = "simd-json", not(target_os = "wasi"))))]
+pub(crate) fn default_codec() -> Arc<dyn JsonCodec> {
+    Arc::new(SerdeJsonCodec)
+}
+
+#[cfg(all(feature = "simd-json", not(target_os = "wasi")))]
+pub(crate) fn default_codec() -> Arc<dyn JsonCodec> {
+    Arc::new(SimdJsonCodec)
+}
+
+#[cfg(all(test, not(target_os = "wasi")))]
+mod tests {
+    use crate::models::search::NotionSearch;
+    use crate::models::Object;
+    use crate::test_util::{person, MockNotion, PageFixture, MOCK_API_TOKEN};
+    use crate::{Client, Error, JsonCodec, LenientJsonCodec, NotionApi, Result, SerdeJsonCodec};
+    use serde_json::json;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    #[tokio::test]
+    async fn errors_keep_status_request_id_and_body() {
+        let mock = MockNotion::start().await;
+        let page = PageFixture::new().build();
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/pages/{}", page.id)))
+            .respond_with(
+                ResponseTemplate::new(502)
+                    .insert_header("x-request-id", "2f1d0b1e")
+                    .insert_header("retry-after", "3")
+                    .set_body_string("<html><body>502 Bad Gateway</body></html>"),
+            )
+            .mount(mock.server())
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/search"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("{\"object\": \"list\""))
+            .mount(mock.server())
+            .await;
+
+        let api = mock.api();
+        let error = api.get_page(&page.id).await.unwrap_err();
+        assert!(matches!(error, Error::HttpError { .. }), "{:?}", error);
+        assert_eq!(error.status(), Some(502));
+        assert_eq!(error.request_id(), Some("2f1d0b1e"));
+        assert_eq!(error.retry_after(), Some(std::time::Duration::from_secs(3)));
+        assert!(error.response_body().unwrap().contains("502 Bad Gateway"));
+        assert!(error.is_retryable());
+        assert!(!error.is_not_found());
+
+        let error = api
+            .get_user(person("Ada", "ada@example.com").id())
+            .await
+            .unwrap_err();
+        assert!(error.is_not_found());
+        assert_eq!(error.status(), Some(404));
+
+        let error = api
+            .search(NotionSearch::Query("Roadmap".to_string()))
+            .await
+            .unwrap_err();
+        #[cfg(not(feature = "simd-json"))]
+        assert!(matches!(error, Error::JsonParseError { .. }));
+        #[cfg(feature = "simd-json")]
+        assert!(matches!(error, Error::SimdJsonParseError { .. }));
+        assert_eq!(error.response_body(), Some("{\"object\": \"list\""));
+        assert_eq!(error.status(), None);
+    }
+
+    #[tokio::test]
+    async fn custom_codec_decodes_responses() {
+        #[derive(Debug)]
+        struct Strict;
+
+        impl JsonCodec for Strict {
+            fn decode(
+                &self,
+                body: Vec<u8>,
+            ) -> Result<Object> {
+                let object = SerdeJsonCodec.decode(body)?;
+                match object {
+                    Object::User { .. } => Err(Error::DecodeFailed {
+                        source: "users are not expected here".into(),
+                    }),
+                    object => Ok(object),
+                }
+            }
+        }
+
+        let mock = MockNotion::start().await;
+        let ada = person("Ada", "ada@example.com");
+        mock.mount_user(&ada).await;
+
+        let client = Client::builder(MOCK_API_TOKEN)
+            .codec(Strict)
+            .build()
+            .unwrap();
+        let api = NotionApi::with_client(client).with_base_url(mock.base_url());
+        match api.get_user(ada.id()).await {
+            Err(Error::DecodeFailed { source }) => {
+                assert_eq!(source.to_string(), "users are not expected here")
+            }
+            result => panic!("Expected the codec to fail, got {:?}", result),
+        }
+    }
+
+    #[tokio::test]
+    async fn lenient_codec_skips_broken_items() {
+        let mock = MockNotion::start().await;
+        let page = PageFixture::new().build();
+        Mock::given(method("POST"))
+            .and(path("/v1/search"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "object": "list",
+                "results": [
+                    Object::Page { page: page.clone() },
+                    { "object": "page", "id": "not-a-page" },
+                ],
+                "next_cursor": null,
+                "has_more": false,
+            })))
+            .mount(mock.server())
+            .await;
+
+        let strict = Client::builder(MOCK_API_TOKEN)
+            .codec(SerdeJsonCodec)
+            .build()
+            .unwrap();
+        let strict = NotionApi::with_client(strict).with_base_url(mock.base_url());
+        assert!(matches!(
+            strict
+                .search(NotionSearch::Query("Roadmap".to_string()))
+                .await,
+            Err(Error::JsonParseError { .. })
+        ));
+
+        let skipped = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+        let codec = LenientJsonCodec::new().on_skipped({
+            let skipped = skipped.clone();
+            move |item| skipped.lock().unwrap().push(item.id.clone())
+        });
+        let client = Client::builder(MOCK_API_TOKEN)
+            .codec(codec)
+            .build()
+            .unwrap();
+        let api = NotionApi::with_client(client).with_base_url(mock.base_url());
+        let results = api
+            .search(NotionSearch::Query("Roadmap".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(results.results, vec![Object::Page { page }]);
+        assert_eq!(
+            *skipped.lock().unwrap(),
+            vec![Some("not-a-page".to_string())]
+        );
+    }
+}
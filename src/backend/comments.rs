@@ -0,0 +1,101 @@
+use crate::ids::{AsIdentifier, BlockId};
+use crate::models::comments::{self, Comment, CommentCreateRequest, Thread};
+use crate::models::paging::PagingCursor;
+use crate::models::{ListResponse, Object};
+use crate::{CollectAll, Error, NotionApi, Result, TClient};
+
+impl<C: TClient> NotionApi<C> {
+    /// Adds a comment to a page, or replies to a discussion,
+    /// see [CommentCreateRequest::reply()].
+    pub async fn create_comment<T: Into<CommentCreateRequest>>(
+        &self,
+        comment: T,
+    ) -> Result<Comment> {
+        let comment = serde_json::to_vec(&comment.into()).unwrap();
+        let uri = format!("{}/comments", self.base_url);
+
+        match self.client.post_json(uri, comment).await? {
+            Object::Comment { comment } => Ok(comment),
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
+
+    /// The unresolved comments on a page or block, one page of results
+    /// starting at `cursor`.
+    pub async fn list_comments<B: AsIdentifier<BlockId>>(
+        &self,
+        block_id: B,
+        cursor: Option<PagingCursor>,
+    ) -> Result<ListResponse<Comment>> {
+        let mut uri = format!(
+            "{base_url}/comments?block_id={block_id}&page_size=100",
+            base_url = self.base_url,
+            block_id = block_id.as_id()
+        );
+        if let Some(cursor) = &cursor {
+            uri.push_str("&start_cursor=");
+            uri.push_str(cursor.value());
+        }
+
+        match self.client.get(uri).await? {
+            Object::List { list } => Ok(list.expect_comments()?),
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
+
+    /// Every unresolved comment on a page or block, grouped into discussions,
+    /// see [comments::threads()].
+    pub async fn get_comment_threads<B: AsIdentifier<BlockId>>(
+        &self,
+        block_id: B,
+    ) -> Result<Vec<Thread>> {
+        let comments = self.comments_stream(block_id).collect_all().await?;
+        Ok(comments::threads(comments))
+    }
+}
+
+#[cfg(all(test, not(target_os = "wasi")))]
+mod tests {
+    use crate::ids::{BlockId, PageId};
+    use crate::test_util::{rich_text, MockNotion};
+    use serde_json::json;
+    use std::str::FromStr;
+    use wiremock::matchers::{body_partial_json, method, path, query_param};
+    use wiremock::{Mock, ResponseTemplate};
+
+    #[tokio::test]
+    async fn reply_to_comment_thread() {
+        let mock = MockNotion::start().await;
+        let list: serde_json::Value =
+            serde_json::from_str(include_str!("../models/tests/comments.json")).unwrap();
+        let page_id = PageId::from_str("5c6a2821-6bb1-4a7e-b6e1-c50111515c3d").unwrap();
+        Mock::given(method("GET"))
+            .and(path("/v1/comments"))
+            .and(query_param("block_id", page_id.to_string()))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&list))
+            .mount(mock.server())
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/comments"))
+            .and(body_partial_json(json!({
+                "discussion_id": "a0b2a6f4-7c11-4a52-9d1f-0e0c8d6b7f21",
+                "rich_text": [{"type": "text", "text": {"content": "Answer"}}]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&list["results"][1]))
+            .expect(1)
+            .mount(mock.server())
+            .await;
+
+        let api = mock.api();
+        let threads = api
+            .get_comment_threads(BlockId::from(page_id))
+            .await
+            .unwrap();
+        assert_eq!(threads.len(), 2);
+        let reply = api
+            .create_comment(threads[1].reply(vec![rich_text("Answer")]))
+            .await
+            .unwrap();
+        assert_eq!(reply.discussion_id, threads[1].discussion_id);
+    }
+}
@@ -0,0 +1,155 @@
+use chrono::{DateTime, Utc};
+
+use crate::ids::{AsIdentifier, PageId};
+use crate::models::{Page, PageUpdateRequest};
+use crate::{NotionApi, Result, TClient};
+
+/// The outcome of [NotionApi::update_page_if_unchanged()] and [NotionApi::modify_page()].
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum ConditionalUpdate {
+    /// The update was applied; contains the updated page.
+    Updated(Page),
+    /// The page was edited by someone else first, so nothing was written.
+    Conflict {
+        /// The page as it is now.
+        current: Page,
+    },
+}
+
+impl ConditionalUpdate {
+    pub fn is_conflict(&self) -> bool {
+        matches!(self, ConditionalUpdate::Conflict { .. })
+    }
+
+    /// The updated page, or the current one after a conflict.
+    pub fn page(&self) -> &Page {
+        match self {
+            ConditionalUpdate::Updated(page) | ConditionalUpdate::Conflict { current: page } => {
+                page
+            }
+        }
+    }
+}
+
+impl<C: TClient> NotionApi<C> {
+    /// Applies `update` only if the page was not edited since `last_edited_time`,
+    /// usually the [Page::last_edited_time] of the copy the update is based on.
+    ///
+    /// The API has no conditional writes, so the page is fetched and compared
+    /// right before updating, bypassing the cache of a [Caching](crate::Caching) client.
+    /// Notion rounds `last_edited_time` to the minute, so edits within the same
+    /// minute, or between the check and the write, go unnoticed.
+    pub async fn update_page_if_unchanged<P, T>(
+        &self,
+        page_id: P,
+        last_edited_time: DateTime<Utc>,
+        update: T,
+    ) -> Result<ConditionalUpdate>
+    where
+        P: AsIdentifier<PageId>,
+        T: Into<PageUpdateRequest>,
+    {
+        let page_id = page_id.as_id();
+        let current = self.get_current_page(page_id).await?;
+        if current.last_edited_time != last_edited_time {
+            debug!(%page_id, "Page changed, skipping update");
+            return Ok(ConditionalUpdate::Conflict { current });
+        }
+
+        let page = self.update_page(page_id, update).await?;
+        Ok(ConditionalUpdate::Updated(page))
+    }
+
+    /// Reads the page, computes an update from it with `modify` and applies it
+    /// with [update_page_if_unchanged()](Self::update_page_if_unchanged()).
+    ///
+    /// After a conflict `modify` runs again on the current page, so the update
+    /// merges with the other edit, up to `max_attempts` times in total.
+    pub async fn modify_page<P, F>(
+        &self,
+        page_id: P,
+        max_attempts: usize,
+        mut modify: F,
+    ) -> Result<ConditionalUpdate>
+    where
+        P: AsIdentifier<PageId>,
+        F: FnMut(&Page) -> PageUpdateRequest + Send,
+    {
+        let mut page = self.get_current_page(page_id.as_id()).await?;
+        let mut attempt = 1;
+
+        loop {
+            let update = modify(&page);
+            match self
+                .update_page_if_unchanged(&page.id, page.last_edited_time, update)
+                .await?
+            {
+                ConditionalUpdate::Conflict { current } if attempt < max_attempts => {
+                    page = current;
+                    attempt += 1;
+                }
+                result => return Ok(result),
+            }
+        }
+    }
+}
+
+#[cfg(all(test, not(target_os = "wasi")))]
+mod tests {
+    use crate::models::Object;
+    use crate::test_util::{MockNotion, PageFixture};
+    use crate::ConditionalUpdate;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    #[tokio::test]
+    async fn conditional_updates() {
+        let mock = MockNotion::start().await;
+        let stale = PageFixture::new().id("shared").title("stale").build();
+        let current = PageFixture::new()
+            .id("shared")
+            .title("current")
+            .last_edited_time(stale.last_edited_time + chrono::Duration::minutes(1))
+            .build();
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/pages/{}", stale.id)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(Object::Page {
+                page: stale.clone(),
+            }))
+            .up_to_n_times(1)
+            .with_priority(1)
+            .mount(mock.server())
+            .await;
+        mock.mount_page(&current).await;
+        Mock::given(method("PATCH"))
+            .and(path(format!("/v1/pages/{}", current.id)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(Object::Page {
+                page: current.clone(),
+            }))
+            .expect(1)
+            .mount(mock.server())
+            .await;
+
+        let api = mock.api();
+        let mut seen = vec![];
+        let result = api
+            .modify_page(&stale.id, 3, |page| {
+                seen.push(page.title());
+                page.properties.clone().into()
+            })
+            .await
+            .unwrap();
+        assert_eq!(result, ConditionalUpdate::Updated(current.clone()));
+        assert_eq!(
+            seen,
+            vec![Some("stale".to_string()), Some("current".to_string())]
+        );
+
+        let conflict = api
+            .update_page_if_unchanged(&stale.id, stale.last_edited_time, stale.properties.clone())
+            .await
+            .unwrap();
+        assert!(conflict.is_conflict());
+        assert_eq!(conflict.page(), &current);
+    }
+}
@@ -0,0 +1,83 @@
+use crate::ids::{AsIdentifier, DataSourceId};
+use crate::models::search::DatabaseQuery;
+use crate::models::{DataSource, ListResponse, Object, Page};
+use crate::{Error, NotionApi, Result, TClient};
+
+impl<C: TClient> NotionApi<C> {
+    /// Get a data source by [DataSourceId].
+    ///
+    /// Data sources need API version 2025-09-03,
+    /// see [Client::with_version()](crate::Client::with_version()).
+    pub async fn get_data_source<T: AsIdentifier<DataSourceId>>(
+        &self,
+        data_source_id: T,
+    ) -> Result<DataSource> {
+        let uri = format!("{}/data_sources/{}", self.base_url, data_source_id.as_id());
+        let result = self.client.get(uri).await?;
+
+        match result {
+            Object::DataSource { data_source } => Ok(data_source),
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
+
+    /// Query a data source and return the matching pages.
+    ///
+    /// This replaces [query_database()](Self::query_database()) from API version 2025-09-03 on.
+    pub async fn query_data_source<D, T>(
+        &self,
+        data_source: D,
+        query: T,
+    ) -> Result<ListResponse<Page>>
+    where
+        T: Into<DatabaseQuery>,
+        D: AsIdentifier<DataSourceId>,
+    {
+        let query = query.into();
+        query
+            .validate()
+            .map_err(|source| Error::InvalidQuery { source })?;
+        let uri = format!(
+            "{base_url}/data_sources/{data_source_id}/query{filter}",
+            base_url = self.base_url,
+            data_source_id = data_source.as_id(),
+            filter = super::filter_properties(&query.filter_properties)
+        );
+        let query = serde_json::to_vec(&query).unwrap();
+
+        let result = self.client.post_json(uri, query).await?;
+
+        match result {
+            Object::List { list } => Ok(list.expect_pages()?),
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
+}
+
+#[cfg(all(test, not(target_os = "wasi")))]
+mod tests {
+    use crate::models::search::DatabaseQuery;
+    use crate::models::DataSource;
+    use crate::test_util::{MockNotion, PageFixture};
+
+    #[tokio::test]
+    async fn query_data_source() {
+        let mock = MockNotion::start().await;
+        let data_source: DataSource =
+            serde_json::from_str(include_str!("../models/tests/data_source.json")).unwrap();
+        let page = PageFixture::new().build();
+        mock.mount_data_source(&data_source, vec![page.clone()])
+            .await;
+
+        let api = mock.api();
+        assert_eq!(
+            api.get_data_source(&data_source.id).await.unwrap(),
+            data_source
+        );
+        let pages = api
+            .query_data_source(&data_source.id, DatabaseQuery::default())
+            .await
+            .unwrap();
+        assert_eq!(pages.results, vec![page]);
+    }
+}
@@ -0,0 +1,380 @@
+//! Previewing the writes of a migration before making them.
+//!
+//! ```no_run
+//! # async fn example(request: notion_wasi::models::PageCreateRequest) -> Result<(), Box<dyn std::error::Error>> {
+//! use notion_wasi::{Client, DryRun, NotionApi};
+//!
+//! let client = Client::new(std::env::var("NOTION_API_TOKEN")?)?;
+//! let api = NotionApi::with_client(DryRun::new(client));
+//! api.create_page(request).await?;
+//!
+//! let mut plan = api.client().plan();
+//! for write in plan.writes() {
+//!     println!("{} {}", write.method, write.path);
+//! }
+//!
+//! // Happy with the plan? Send it.
+//! let api = NotionApi::new(std::env::var("NOTION_API_TOKEN")?)?;
+//! api.flush_writes(&mut plan).await;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use serde_json::{json, Value};
+
+use super::stats::endpoint;
+use crate::models::{ListResponse, Object};
+use crate::{
+    ApiVersion, Error, Method, MultipartForm, QueuedWrite, RateLimitStatus, Result, TClient,
+    WriteQueue,
+};
+
+/// The objects created by `POST` to each endpoint.
+const CREATED: &[(&str, &str)] = &[
+    ("pages", "page"),
+    ("databases", "database"),
+    ("data_sources", "data_source"),
+    ("comments", "comment"),
+];
+
+/// A [TClient] that sends reads to another client and only records writes,
+/// so the changes a run would make can be reviewed first.
+///
+/// The recorded writes are available as a [WriteQueue] from [plan()](Self::plan()),
+/// with paths relative to the `/v1` of the base URL, ready to be sent with
+/// [NotionApi::flush_writes()](crate::NotionApi::flush_writes()).
+///
+/// Writes are answered as closely as possible without making them:
+/// - updates return the current object fetched through the inner client, with
+///   the fields and properties of the request applied,
+/// - appends return the current children, without the appended blocks,
+/// - deletes return the current object, moved to the trash,
+/// - creates return the request body as the new object, with a placeholder id
+///   starting with `ffffffff-ffff-`.
+///   Later writes using that id fail when the plan is sent. Creates at endpoints
+///   other than pages, databases, data sources and comments, such as file
+///   uploads, fail with [Error::UnexpectedResponse].
+///
+/// With the `tracing` feature, each write is also logged at the info level.
+pub struct DryRun<C> {
+    inner: C,
+    plan: Mutex<WriteQueue>,
+    created: AtomicU64,
+}
+
+impl<C: TClient> DryRun<C> {
+    pub fn new(inner: C) -> Self {
+        DryRun {
+            inner,
+            plan: Mutex::new(WriteQueue::default()),
+            created: AtomicU64::new(0),
+        }
+    }
+
+    /// The writes recorded so far, oldest first.
+    pub fn plan(&self) -> WriteQueue {
+        self.plan.lock().unwrap().clone()
+    }
+
+    /// Forgets the recorded writes.
+    pub fn clear(&self) {
+        *self.plan.lock().unwrap() = WriteQueue::default();
+    }
+
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+
+    fn record(
+        &self,
+        method: Method,
+        uri: &str,
+        body: Value,
+    ) {
+        let path = relative_path(uri);
+        info!(%method, %path, "Dry run, not sending write");
+        self.plan
+            .lock()
+            .unwrap()
+            .push(QueuedWrite { method, path, body });
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn created(
+        &self,
+        uri: &str,
+        body: &Value,
+    ) -> Result<Object> {
+        let endpoint = endpoint(uri);
+        let object = CREATED
+            .iter()
+            .find(|(path, _)| endpoint.ends_with(&format!("/{}", path)))
+            .map(|(_, object)| *object)
+            // Other endpoints don't create objects this client knows how to fake.
+            .ok_or_else(|| Error::UnexpectedResponse {
+                response: Object::List {
+                    list: ListResponse {
+                        results: vec![],
+                        next_cursor: None,
+                        has_more: false,
+                    },
+                },
+            })?;
+
+        let number = self.created.fetch_add(1, Ordering::Relaxed) + 1;
+        let now = Utc::now();
+        let user = json!({ "object": "user", "id": PLACEHOLDER_USER });
+        let mut created = json!({
+            "object": object,
+            "id": format!("{}{:012x}", PLACEHOLDER_ID_PREFIX, number),
+            "created_time": now,
+            "last_edited_time": now,
+            "created_by": user,
+            "last_edited_by": user,
+        });
+        merge(&mut created, body);
+        serde_json::from_value(created)
+            .map_err(|source| Error::JsonParseError { source, body: None })
+    }
+}
+
+/// The start of the ids of objects created in a dry run.
+const PLACEHOLDER_ID_PREFIX: &str = "ffffffff-ffff-4fff-bfff-";
+
+/// The id of the user creating objects in a dry run.
+const PLACEHOLDER_USER: &str = "ffffffff-ffff-4fff-bfff-000000000000";
+
+#[async_trait]
+impl<C: TClient + Send + Sync> TClient for DryRun<C> {
+    fn api_version(&self) -> ApiVersion {
+        self.inner.api_version()
+    }
+
+    fn rate_limit_status(&self) -> Option<RateLimitStatus> {
+        self.inner.rate_limit_status()
+    }
+
+    async fn get<U: Into<String> + Send>(
+        &self,
+        uri: U,
+    ) -> Result<Object> {
+        self.inner.get(uri).await
+    }
+
+    async fn get_fresh<U: Into<String> + Send>(
+        &self,
+        uri: U,
+    ) -> Result<Object> {
+        self.inner.get_fresh(uri).await
+    }
+
+    async fn post<U: Into<String> + Send>(
+        &self,
+        uri: U,
+    ) -> Result<Object> {
+        let uri = uri.into();
+        if !is_write(&uri) {
+            return self.inner.post(uri).await;
+        }
+        self.record(Method::Post, &uri, Value::Null);
+        self.created(&uri, &Value::Null)
+    }
+
+    async fn post_json<U: Into<String> + Send>(
+        &self,
+        uri: U,
+        body: Vec<u8>,
+    ) -> Result<Object> {
+        let uri = uri.into();
+        if !is_write(&uri) {
+            return self.inner.post_json(uri, body).await;
+        }
+        let body: Value = serde_json::from_slice(&body)
+            .map_err(|source| Error::JsonParseError { source, body: None })?;
+        self.record(Method::Post, &uri, body.clone());
+        self.created(&uri, &body)
+    }
+
+    async fn patch_json<U: Into<String> + Send>(
+        &self,
+        uri: U,
+        body: Vec<u8>,
+    ) -> Result<Object> {
+        let uri = uri.into();
+        let body: Value = serde_json::from_slice(&body)
+            .map_err(|source| Error::JsonParseError { source, body: None })?;
+        self.record(Method::Patch, &uri, body.clone());
+
+        let current = self.inner.get(uri.clone()).await?;
+        if endpoint(&uri).ends_with("/children") {
+            return Ok(current);
+        }
+        let mut updated = serde_json::to_value(current)
+            .map_err(|source| Error::JsonParseError { source, body: None })?;
+        merge(&mut updated, &body);
+        serde_json::from_value(updated)
+            .map_err(|source| Error::JsonParseError { source, body: None })
+    }
+
+    /// Recorded without the form, which holds the contents of a file.
+    async fn post_multipart<U: Into<String> + Send>(
+        &self,
+        uri: U,
+        _form: MultipartForm,
+    ) -> Result<Object> {
+        let uri = uri.into();
+        self.record(Method::Post, &uri, Value::Null);
+        self.created(&uri, &Value::Null)
+    }
+
+    async fn delete<U: Into<String> + Send>(
+        &self,
+        uri: U,
+    ) -> Result<Object> {
+        let uri = uri.into();
+        self.record(Method::Delete, &uri, Value::Null);
+
+        let current = self.inner.get(uri).await?;
+        let mut deleted = serde_json::to_value(current)
+            .map_err(|source| Error::JsonParseError { source, body: None })?;
+        merge(&mut deleted, &json!({"archived": true, "in_trash": true}));
+        serde_json::from_value(deleted)
+            .map_err(|source| Error::JsonParseError { source, body: None })
+    }
+}
+
+/// Searches and queries are sent as `POST` but only read.
+fn is_write(uri: &str) -> bool {
+    let endpoint = endpoint(uri);
+    !(endpoint.ends_with("/search") || endpoint.ends_with("/query"))
+}
+
+/// The part of `uri` after `/v1/`, or all of it for other base URLs.
+fn relative_path(uri: &str) -> String {
+    match uri.find("/v1/") {
+        Some(start) => uri[start + "/v1/".len()..].to_string(),
+        None => uri.to_string(),
+    }
+}
+
+/// Applies the fields of a request body to an object. Properties are
+/// changed one by one and removed when set to `null`, like the API does.
+fn merge(
+    object: &mut Value,
+    body: &Value,
+) {
+    let (object, body) = match (object.as_object_mut(), body.as_object()) {
+        (Some(object), Some(body)) => (object, body),
+        _ => return,
+    };
+    for (name, value) in body {
+        match (name.as_str(), object.get_mut(name.as_str()), value) {
+            ("properties", Some(Value::Object(properties)), Value::Object(changes)) => {
+                for (property, change) in changes {
+                    if change.is_null() {
+                        properties.remove(property);
+                    } else {
+                        properties.insert(property.clone(), change.clone());
+                    }
+                }
+            }
+            _ => {
+                object.insert(name.clone(), value.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{merge, relative_path};
+    use crate::models::PageCreateRequest;
+    use crate::test_util::{MockNotion, PageFixture, MOCK_API_TOKEN};
+    use crate::{Client, DryRun, Method, NotionApi};
+    use serde_json::json;
+
+    #[test]
+    fn updates_are_merged_into_the_current_object() {
+        let mut page = json!({
+            "archived": false,
+            "properties": { "Name": { "title": [] }, "Old": { "checkbox": true } },
+        });
+        merge(
+            &mut page,
+            &json!({
+                "archived": true,
+                "properties": { "Done": { "checkbox": true }, "Old": null },
+            }),
+        );
+        assert_eq!(
+            page,
+            json!({
+                "archived": true,
+                "properties": { "Name": { "title": [] }, "Done": { "checkbox": true } },
+            })
+        );
+    }
+
+    #[test]
+    fn paths_are_relative_to_the_api_version() {
+        assert_eq!(
+            relative_path("https://api.notion.com/v1/blocks/abc/children"),
+            "blocks/abc/children"
+        );
+        assert_eq!(
+            relative_path("http://localhost/pages"),
+            "http://localhost/pages"
+        );
+    }
+
+    #[tokio::test]
+    async fn dry_run_plans_writes() {
+        let mock = MockNotion::start().await;
+        let page = PageFixture::new()
+            .database("tasks")
+            .title("Migrate")
+            .checkbox("Done", false)
+            .build();
+        mock.mount_page(&page).await;
+
+        let client = DryRun::new(Client::new(MOCK_API_TOKEN.to_string()).unwrap());
+        let api = NotionApi::with_client(client).with_base_url(mock.base_url());
+        let created = api
+            .create_page(PageCreateRequest::new(
+                page.parent.clone(),
+                page.properties.clone(),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(created.title().as_deref(), Some("Migrate"));
+        assert_ne!(created.id, page.id);
+
+        let done = PageFixture::new().checkbox("Done", true).build();
+        let updated = api.update_page(&page.id, done.properties).await.unwrap();
+        assert_eq!(updated.id, page.id);
+        assert_eq!(updated.checkbox("Done"), Some(true));
+        assert_eq!(updated.title().as_deref(), Some("Migrate"));
+
+        let plan = api.client().plan();
+        let writes: Vec<_> = plan
+            .writes()
+            .map(|write| (write.method, write.path.clone()))
+            .collect();
+        assert_eq!(
+            writes,
+            vec![
+                (Method::Post, "pages".to_string()),
+                (Method::Patch, format!("pages/{}", page.id)),
+            ]
+        );
+        let requests = mock.server().received_requests().await.unwrap();
+        assert!(requests
+            .iter()
+            .all(|request| request.method.to_string() == "GET"));
+    }
+}
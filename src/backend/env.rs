@@ -0,0 +1,97 @@
+use std::env::{self, VarError};
+
+use crate::{ApiVersion, Client, Error, NotionApi, Result};
+
+/// The integration token read by [NotionApi::from_env()].
+pub const TOKEN_ENV: &str = "NOTION_TOKEN";
+/// The optional `Notion-Version` read by [NotionApi::from_env()].
+pub const API_VERSION_ENV: &str = "NOTION_API_VERSION";
+
+impl NotionApi {
+    /// Creates a client with the token in `NOTION_TOKEN`, sending the
+    /// `Notion-Version` in `NOTION_API_VERSION` if it is set.
+    ///
+    /// Fails with [Error::MissingEnvVar] if there is no token and with
+    /// [Error::InvalidEnvVar] if a variable isn't unicode or the version isn't supported.
+    /// Outside of WASI, requests go through the proxies in the proxy variables,
+    /// see [ClientBuilder::proxy_from_env()](crate::ClientBuilder::proxy_from_env()).
+    #[allow(clippy::result_large_err)]
+    pub fn from_env() -> Result<Self> {
+        let token = var(TOKEN_ENV)?.ok_or_else(|| Error::MissingEnvVar {
+            name: TOKEN_ENV.to_string(),
+        })?;
+        let version = match var(API_VERSION_ENV)? {
+            Some(version) => version.parse().map_err(|error| Error::InvalidEnvVar {
+                name: API_VERSION_ENV.to_string(),
+                reason: format!("{}", error),
+            })?,
+            None => ApiVersion::default(),
+        };
+
+        let builder = Client::builder(token).version(version);
+        #[cfg(not(target_arch = "wasm32"))]
+        let builder = builder.proxy_from_env(true);
+        Ok(NotionApi::with_client(builder.build()?))
+    }
+}
+
+/// The value of `name`, `None` if it is unset or empty.
+#[allow(clippy::result_large_err)]
+fn var(name: &str) -> Result<Option<String>> {
+    match env::var(name) {
+        Ok(value) if value.trim().is_empty() => Ok(None),
+        Ok(value) => Ok(Some(value.trim().to_string())),
+        Err(VarError::NotPresent) => Ok(None),
+        Err(VarError::NotUnicode(_)) => Err(Error::InvalidEnvVar {
+            name: name.to_string(),
+            reason: "not valid unicode".to_string(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{API_VERSION_ENV, TOKEN_ENV};
+    use crate::{ApiVersion, Error, NotionApi};
+    use std::env;
+
+    // One test, so no other test sees the variables change.
+    #[test]
+    fn from_env() {
+        env::remove_var(TOKEN_ENV);
+        env::remove_var(API_VERSION_ENV);
+        match NotionApi::from_env() {
+            Err(error @ Error::MissingEnvVar { .. }) => {
+                assert_eq!(
+                    error.to_string(),
+                    "Environment variable NOTION_TOKEN is not set"
+                )
+            }
+            result => panic!("Expected a missing variable, got {:?}", result),
+        }
+
+        env::set_var(TOKEN_ENV, "secret_abc\n");
+        assert_eq!(
+            NotionApi::from_env().unwrap().api_version(),
+            ApiVersion::default()
+        );
+
+        env::set_var(API_VERSION_ENV, "2025-09-03");
+        assert_eq!(
+            NotionApi::from_env().unwrap().api_version(),
+            ApiVersion::V2025_09_03
+        );
+
+        env::set_var(API_VERSION_ENV, "2021-05-13");
+        match NotionApi::from_env() {
+            Err(error @ Error::InvalidEnvVar { .. }) => assert_eq!(
+                error.to_string(),
+                "Invalid environment variable NOTION_API_VERSION: Unsupported Notion-Version \"2021-05-13\""
+            ),
+            result => panic!("Expected an invalid variable, got {:?}", result),
+        }
+
+        env::remove_var(TOKEN_ENV);
+        env::remove_var(API_VERSION_ENV);
+    }
+}
@@ -0,0 +1,252 @@
+use std::collections::{HashSet, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{download, PageWithContent};
+
+use crate::ids::{AsIdentifier, PageId};
+use crate::models::block::{Block, BlockTree, FileFields, FileObject};
+use crate::render::markdown::{page_to_markdown, MarkdownRenderer};
+use crate::render::print::page_to_html;
+use crate::{NotionApi, Result, TClient};
+
+/// The files written by [NotionApi::export_markdown_tree()].
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct MarkdownExport {
+    /// The Markdown file of each page, starting with the root page.
+    pub pages: Vec<ExportedPage>,
+    /// Downloaded images.
+    pub files: Vec<PathBuf>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct ExportedPage {
+    pub id: PageId,
+    pub path: PathBuf,
+}
+
+impl<C: TClient> NotionApi<C> {
+    /// Writes `root` and every page below it to `dir` as Markdown files.
+    ///
+    /// Each page becomes `<title>.md`, and its child pages and images go into
+    /// a folder of the same name next to it, so the tree can be published as is.
+    /// Links to child pages and images uploaded to Notion point to the written files,
+    /// external images stay linked to their URL.
+    /// Titles are made safe for file names, and duplicate titles get a numbered suffix.
+    pub async fn export_markdown_tree<P: AsIdentifier<PageId>, D: AsRef<Path>>(
+        &self,
+        root: P,
+        dir: D,
+    ) -> Result<MarkdownExport> {
+        let mut export = MarkdownExport::default();
+        let mut queue = VecDeque::new();
+        queue.push_back((root.as_id().clone(), dir.as_ref().to_path_buf(), None));
+
+        while let Some((page_id, dir, stem)) = queue.pop_front() {
+            let PageWithContent { page, blocks } =
+                self.get_page_with_content(&page_id, None).await?;
+            let stem = stem.unwrap_or_else(|| file_stem(page.title(), &mut HashSet::new()));
+            let folder = dir.join(&stem);
+
+            let mut renderer = MarkdownRenderer::new();
+            let mut stems = HashSet::new();
+            for tree in descendants(&blocks) {
+                match &tree.block {
+                    Block::ChildPage { common, child_page } if !common.archived => {
+                        let child_stem = file_stem(Some(child_page.title.clone()), &mut stems);
+                        let target = relative_link(&stem, &format!("{}.md", child_stem));
+                        renderer = renderer.link(common.id.clone(), target);
+                        queue.push_back((
+                            common.id.clone().into(),
+                            folder.clone(),
+                            Some(child_stem),
+                        ));
+                    }
+                    Block::Image {
+                        common,
+                        image:
+                            FileFields {
+                                file: FileObject::File { file },
+                                ..
+                            },
+                    } => {
+                        let name = format!("{}{}", common.id, extension(&file.url));
+                        let bytes = download(&file.url).await?;
+                        fs::create_dir_all(&folder)?;
+                        let path = folder.join(&name);
+                        fs::write(&path, bytes)?;
+
+                        renderer = renderer.link(common.id.clone(), relative_link(&stem, &name));
+                        export.files.push(path);
+                    }
+                    _ => {}
+                }
+            }
+
+            fs::create_dir_all(&dir)?;
+            let path = dir.join(format!("{}.md", stem));
+            fs::write(&path, renderer.render_page(&page, &blocks))?;
+            export.pages.push(ExportedPage { id: page.id, path });
+        }
+
+        Ok(export)
+    }
+
+    /// Fetches a page with all of its blocks as a Markdown document,
+    /// see [page_to_markdown()].
+    ///
+    /// Images, files and child pages link to Notion;
+    /// [export_markdown_tree()](Self::export_markdown_tree()) writes child pages and images to files instead.
+    pub async fn export_markdown<P: AsIdentifier<PageId>>(
+        &self,
+        page_id: P,
+    ) -> Result<String> {
+        let PageWithContent { page, blocks } = self.get_page_with_content(page_id, None).await?;
+        Ok(page_to_markdown(&page, &blocks))
+    }
+
+    /// Fetches a page with all of its blocks as a standalone, print-ready HTML
+    /// document, see [render::print](crate::render::print).
+    pub async fn export_print_html<P: AsIdentifier<PageId>>(
+        &self,
+        page_id: P,
+    ) -> Result<String> {
+        let PageWithContent { page, blocks } = self.get_page_with_content(page_id, None).await?;
+        Ok(page_to_html(&page, &blocks))
+    }
+}
+
+/// Every block of the trees, parents before their children.
+fn descendants(blocks: &[BlockTree]) -> Vec<&BlockTree> {
+    let mut all = vec![];
+    for tree in blocks {
+        all.push(tree);
+        all.extend(descendants(&tree.children));
+    }
+    all
+}
+
+/// A file name for `title` that isn't in `taken` yet.
+fn file_stem(
+    title: Option<String>,
+    taken: &mut HashSet<String>,
+) -> String {
+    let sanitized: String = title
+        .unwrap_or_default()
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
+            c if c.is_control() => '-',
+            c => c,
+        })
+        .collect();
+    let sanitized = sanitized.trim().trim_matches('.');
+    let base = if sanitized.is_empty() {
+        "Untitled"
+    } else {
+        sanitized
+    };
+
+    let mut stem = base.to_string();
+    let mut n = 1;
+    while !taken.insert(stem.to_lowercase()) {
+        n += 1;
+        stem = format!("{} {}", base, n);
+    }
+    stem
+}
+
+/// The link from `<folder>.md` to `name` inside `folder`.
+fn relative_link(
+    folder: &str,
+    name: &str,
+) -> String {
+    format!("{}/{}", folder, name).replace(' ', "%20")
+}
+
+/// The extension of the file at `url`, with a leading dot, or empty if there isn't one.
+fn extension(url: &str) -> String {
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    let name = path.rsplit('/').next().unwrap_or_default();
+    match name.rsplit_once('.') {
+        Some((_, extension))
+            if !extension.is_empty()
+                && extension.len() <= 5
+                && extension.chars().all(|c| c.is_ascii_alphanumeric()) =>
+        {
+            format!(".{}", extension.to_lowercase())
+        }
+        _ => String::new(),
+    }
+}
+
+#[cfg(all(test, not(target_os = "wasi")))]
+mod tests {
+    use crate::ids::BlockId;
+    use crate::test_util::{BlockFixture, MockNotion, PageFixture};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    #[tokio::test]
+    async fn export_markdown_tree_writes_pages_and_images() {
+        let mock = MockNotion::start().await;
+        let root = PageFixture::new().title("Docs").build();
+        let child = PageFixture::new().title("Guide: Setup").build();
+        let image = BlockFixture::new().image(&format!(
+            "{}/files/diagram.png?X-Amz=1",
+            mock.server().uri()
+        ));
+        let image_id = image.id().unwrap().clone();
+        mock.mount_page(&root).await;
+        mock.mount_page(&child).await;
+        mock.mount_block_children(
+            BlockId::from(root.id.clone()),
+            vec![
+                BlockFixture::new().heading_1("Intro"),
+                image,
+                BlockFixture::new()
+                    .id(&child.id.to_string())
+                    .child_page("Guide: Setup"),
+            ],
+        )
+        .await;
+        mock.mount_block_children(
+            BlockId::from(child.id.clone()),
+            vec![BlockFixture::new().paragraph("Hello")],
+        )
+        .await;
+        Mock::given(method("GET"))
+            .and(path("/files/diagram.png"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"png".to_vec()))
+            .expect(1)
+            .mount(mock.server())
+            .await;
+
+        let dir = std::env::temp_dir().join(format!("notion-export-{}", root.id));
+        let export = mock
+            .api()
+            .export_markdown_tree(&root.id, &dir)
+            .await
+            .unwrap();
+
+        let image_path = dir.join("Docs").join(format!("{}.png", image_id));
+        assert_eq!(export.files, vec![image_path.clone()]);
+        assert_eq!(std::fs::read(&image_path).unwrap(), b"png");
+        assert_eq!(
+            export.pages.iter().map(|page| &page.id).collect::<Vec<_>>(),
+            vec![&root.id, &child.id]
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join("Docs.md")).unwrap(),
+            format!(
+                "# Docs\n\n# Intro\n\n![](Docs/{}.png)\n\n[Guide: Setup](Docs/Guide-%20Setup.md)\n",
+                image_id
+            )
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join("Docs").join("Guide- Setup.md")).unwrap(),
+            "# Guide: Setup\n\nHello\n"
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,169 @@
+use std::io::Write;
+
+use chrono::Duration;
+
+use crate::models::files::{FileSource, HostedFile};
+use crate::{Error, NotionApi, Result, TClient};
+
+/// How long a URL must stay valid for [NotionApi::download_file()] to use it
+/// without refreshing it first.
+const DOWNLOAD_MARGIN_SECONDS: i64 = 60;
+
+impl HostedFile {
+    /// Fetches the block or page the file is attached to again,
+    /// taking its new URL and expiry time.
+    ///
+    /// Fails with [Error::FileRemoved] if the file is no longer there.
+    pub async fn refresh<C: TClient>(
+        &mut self,
+        api: &NotionApi<C>,
+    ) -> Result<()> {
+        let fresh = match &self.source {
+            #[cfg(feature = "blocks")]
+            FileSource::Block { block_id } => {
+                let block = api.get_block(block_id).await?;
+                HostedFile::from_block(&block)
+            }
+            FileSource::Property {
+                page_id,
+                property,
+                name,
+            } => {
+                let page = api.get_page(page_id).await?;
+                HostedFile::from_property(&page, property)
+                    .into_iter()
+                    .find(|file| {
+                        matches!(&file.source, FileSource::Property { name: fresh, .. } if fresh == name)
+                    })
+            }
+        };
+
+        match fresh {
+            Some(fresh) => {
+                self.file = fresh.file;
+                Ok(())
+            }
+            None => Err(Error::FileRemoved {
+                location: self.source.clone(),
+            }),
+        }
+    }
+
+    /// The URL of the file, refreshed first if it expires within `margin`.
+    pub async fn fresh_url<C: TClient>(
+        &mut self,
+        api: &NotionApi<C>,
+        margin: Duration,
+    ) -> Result<&str> {
+        if self.expires_within(margin) {
+            self.refresh(api).await?;
+        }
+        Ok(self.url())
+    }
+}
+
+impl<C: TClient> NotionApi<C> {
+    /// Downloads `file` into `writer`, refreshing its URL first if it is about
+    /// to expire. Returns the number of bytes written.
+    ///
+    /// The file host is not the Notion API, so the request is sent without
+    /// the client's authentication headers or retries.
+    pub async fn download_file<W: Write>(
+        &self,
+        file: &mut HostedFile,
+        writer: &mut W,
+    ) -> Result<u64> {
+        let url = file
+            .fresh_url(self, Duration::seconds(DOWNLOAD_MARGIN_SECONDS))
+            .await?;
+        super::download_to(url, writer).await
+    }
+}
+
+#[cfg(all(test, feature = "blocks", not(target_os = "wasi")))]
+mod tests {
+    use crate::ids::BlockId;
+    use crate::models::block::Block;
+    use crate::models::files::{FileObject, FileSource, HostedFile, InternalFileObject};
+    use crate::models::Object;
+    use crate::test_util::{BlockFixture, MockNotion};
+    use crate::Error;
+    use std::str::FromStr;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    #[tokio::test]
+    async fn refresh_expired_file_urls() {
+        let mock = MockNotion::start().await;
+        let stale = BlockFixture::new()
+            .id("image")
+            .image("https://files.test/old.png");
+        let mut fresh = BlockFixture::new()
+            .id("image")
+            .image("https://files.test/new.png");
+        if let Block::Image { image, .. } = &mut fresh {
+            if let FileObject::File { file } = &mut image.file {
+                file.expiry_time = chrono::Utc::now() + chrono::Duration::hours(1);
+            }
+        }
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/blocks/{}", stale.id().unwrap())))
+            .respond_with(ResponseTemplate::new(200).set_body_json(Object::Block { block: fresh }))
+            .expect(1)
+            .mount(mock.server())
+            .await;
+
+        let api = mock.api();
+        let mut file = HostedFile::from_block(&stale).unwrap();
+        assert!(file.is_expired());
+        let margin = chrono::Duration::minutes(5);
+        assert_eq!(
+            file.fresh_url(&api, margin).await.unwrap(),
+            "https://files.test/new.png"
+        );
+        assert!(!file.expires_within(margin));
+        // Still fresh, so no second request.
+        file.fresh_url(&api, margin).await.unwrap();
+
+        let mut removed = HostedFile::from_block(&stale).unwrap();
+        removed.source = FileSource::Block {
+            block_id: BlockId::from_str("gone").unwrap(),
+        };
+        assert!(removed.refresh(&api).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn download_hosted_files() {
+        let mock = MockNotion::start().await;
+        Mock::given(method("GET"))
+            .and(path("/files/report.pdf"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"%PDF-1.7".to_vec()))
+            .expect(1)
+            .mount(mock.server())
+            .await;
+        let hosted = |name: &str| HostedFile {
+            file: InternalFileObject {
+                url: format!("{}/files/{}", mock.server().uri(), name),
+                expiry_time: chrono::Utc::now() + chrono::Duration::hours(1),
+            },
+            source: FileSource::Block {
+                block_id: BlockId::from_str("report").unwrap(),
+            },
+        };
+
+        let api = mock.api();
+        let mut bytes = vec![];
+        let written = api
+            .download_file(&mut hosted("report.pdf"), &mut bytes)
+            .await
+            .unwrap();
+        assert_eq!(written, 8);
+        assert_eq!(bytes, b"%PDF-1.7");
+
+        assert!(matches!(
+            api.download_file(&mut hosted("missing.pdf"), &mut vec![])
+                .await,
+            Err(Error::DownloadFailed { status: 404, .. })
+        ));
+    }
+}
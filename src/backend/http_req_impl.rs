@@ -1,7 +1,20 @@
 use std::convert::{Infallible, TryFrom};
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use crate::models::{error::ErrorResponse, Object};
-use crate::{TClient, NOTION_API_VERSION};
+use super::codec::{decode_response, ResponseHead, REQUEST_ID_HEADERS};
+use super::stats::{RateLimitStatus, RequestStats, Stats};
+use crate::ids::PageId;
+use crate::models::{
+    error::{ErrorResponse, HttpErrorResponse},
+    files::FileSource,
+    oauth::OAuthErrorResponse,
+    rows::RowError,
+    search::InvalidQuery,
+    InvalidPage, Object,
+};
+use crate::{ApiVersion, ClientBuilder, JsonCodec, MultipartForm, SecretString, TClient};
 
 use async_trait::async_trait;
 use http_req::error as hr_error;
@@ -17,6 +30,9 @@ pub enum Error {
     #[error("Unable to build reqwest HTTP client: {}", source)]
     ErrorBuildingClient { source: hr_error::Error },
 
+    #[error("Invalid header {}", name)]
+    InvalidHeader { name: String },
+
     #[error("Error sending HTTP request: {}", source)]
     RequestFailed {
         #[from]
@@ -27,7 +43,16 @@ pub enum Error {
     ResponseIoError { source: hr_error::Error },
 
     #[error("Error parsing json response: {}", source)]
-    JsonParseError { source: serde_json::Error },
+    JsonParseError {
+        source: serde_json::Error,
+        /// The start of the response that failed to parse, if it was one.
+        body: Option<String>,
+    },
+
+    #[error("Error decoding response: {}", source)]
+    DecodeFailed {
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
 
     #[error("Unexpected API Response")]
     UnexpectedResponse { response: Object },
@@ -35,6 +60,57 @@ pub enum Error {
     #[error("API Error {}({}): {}", .error.code, .error.status, .error.message)]
     ApiError { error: ErrorResponse },
 
+    /// A response with an error status that isn't a Notion error,
+    /// such as the HTML page of a gateway error.
+    #[error("Unexpected HTTP status {}", .response.status)]
+    HttpError { response: HttpErrorResponse },
+
+    #[error("OAuth error {}", .error.error)]
+    OAuthError { error: OAuthErrorResponse },
+
+    #[error("Invalid query: {}", source)]
+    InvalidQuery { source: InvalidQuery },
+
+    #[error("Invalid page: {}", source)]
+    InvalidPage { source: InvalidPage },
+
+    #[error("Unable to read page {}: {}", page, source)]
+    InvalidRow { page: PageId, source: RowError },
+
+    #[error("Found {} pages where `{}` matches the upsert key", .pages.len(), .property)]
+    DuplicatePages {
+        property: String,
+        pages: Vec<PageId>,
+    },
+
+    #[error(
+        "The file is {} bytes, but the workspace accepts at most {} bytes",
+        size,
+        limit
+    )]
+    FileTooLarge { size: u64, limit: u64 },
+
+    #[error("Environment variable {} is not set", name)]
+    MissingEnvVar { name: String },
+
+    #[error("Invalid environment variable {}: {}", name, reason)]
+    InvalidEnvVar { name: String, reason: String },
+
+    #[error("No recorded response for {} {}", method, uri)]
+    NoRecordedResponse { method: String, uri: String },
+
+    #[error("The file is no longer attached to {:?}", location)]
+    FileRemoved { location: FileSource },
+
+    #[error("Downloading {} failed with status {}", url, status)]
+    DownloadFailed { url: String, status: u16 },
+
+    #[error("IO error: {}", source)]
+    Io {
+        #[from]
+        source: std::io::Error,
+    },
+
     #[error("Infallible")]
     Infallible(#[from] Infallible),
 }
@@ -43,29 +119,114 @@ pub enum Error {
 /// Create a client by using [new(api_token: String)](Self::new()).
 #[derive(Clone)]
 pub struct Client {
-    token: String,
+    token: SecretString,
+    version: ApiVersion,
+    user_agent: String,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    headers: Vec<(String, String)>,
+    codec: Arc<dyn JsonCodec>,
+    pub(crate) stats: Stats,
 }
 
 impl Client {
     /// Creates an instance of NotionApi.
     /// Never fail.
-    pub fn new(api_token: String) -> Result<Self, Infallible> {
-        Ok(Self { token: api_token })
+    pub fn new<S: Into<SecretString>>(api_token: S) -> Result<Self, Infallible> {
+        Self::with_version(api_token, ApiVersion::default())
+    }
+
+    /// Creates a client that sends `Notion-Version: {version}`.
+    /// Never fail.
+    pub fn with_version<S: Into<SecretString>>(
+        api_token: S,
+        version: ApiVersion,
+    ) -> Result<Self, Infallible> {
+        Ok(Self::configured(
+            ClientBuilder::new(api_token).version(version),
+        ))
+    }
+
+    /// The same client sending `Notion-Version: {version}`, for the requests
+    /// that need another version than the rest.
+    /// It shares the connections and the [stats()](Self::stats()) of this client.
+    pub fn for_version(
+        &self,
+        version: ApiVersion,
+    ) -> Self {
+        Client {
+            version,
+            ..self.clone()
+        }
+    }
+
+    #[allow(clippy::result_large_err)]
+    pub(crate) fn from_builder(builder: ClientBuilder) -> Result<Self, Error> {
+        for (name, value) in &builder.headers {
+            let valid_name = !name.is_empty()
+                && name
+                    .bytes()
+                    .all(|byte| byte.is_ascii_graphic() && byte != b':');
+            if !valid_name || value.contains(&['\r', '\n'][..]) {
+                return Err(Error::InvalidHeader { name: name.clone() });
+            }
+        }
+        Ok(Self::configured(builder))
+    }
+
+    fn configured(builder: ClientBuilder) -> Self {
+        Self {
+            user_agent: builder.user_agent_header(),
+            timeout: builder.timeout,
+            connect_timeout: builder.connect_timeout,
+            headers: builder.headers,
+            token: builder.token,
+            version: builder.version,
+            codec: builder.codec,
+            stats: Stats::new(builder.on_request),
+        }
+    }
+}
+
+impl fmt::Debug for Client {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        f.debug_struct("Client")
+            .field("token", &self.token)
+            .field("version", &self.version)
+            .field("user_agent", &self.user_agent)
+            .field("timeout", &self.timeout)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("headers", &self.headers)
+            .field("codec", &self.codec)
+            .field("stats", &self.stats)
+            .finish()
     }
 }
 
 #[async_trait]
 impl TClient for Client {
+    fn api_version(&self) -> ApiVersion {
+        self.version
+    }
+
+    fn rate_limit_status(&self) -> Option<RateLimitStatus> {
+        Some(Client::rate_limit_status(self))
+    }
+
     async fn get<S: Into<String> + Send>(
         &self,
         uri: S,
     ) -> crate::Result<Object> {
         let raw: String = uri.into();
 
-        let uri = Uri::try_from(raw.as_str()).unwrap();
+        let uri = Uri::try_from(raw.as_str()).map_err(|source| Error::RequestFailed { source })?;
         let mut request = Request::new(&uri);
         request.method(Method::GET);
-        self.make_json_request(&mut request).await
+        let stats = RequestStats::new("GET", &raw, 0);
+        self.make_json_request(&mut request, stats).await
     }
 
     async fn post<S: Into<String> + Send>(
@@ -74,28 +235,86 @@ impl TClient for Client {
     ) -> crate::Result<Object> {
         let raw: String = uri.into();
 
-        let uri = Uri::try_from(raw.as_str()).unwrap();
+        let uri = Uri::try_from(raw.as_str()).map_err(|source| Error::RequestFailed { source })?;
         let mut request = Request::new(&uri);
         request.method(Method::POST);
-        self.make_json_request(&mut request).await
+        let stats = RequestStats::new("POST", &raw, 0);
+        self.make_json_request(&mut request, stats).await
     }
 
     async fn post_json<S: Into<String> + Send>(
         &self,
         uri: S,
-        body: &[u8],
+        body: Vec<u8>,
     ) -> crate::Result<Object> {
         let raw: String = uri.into();
 
-        let uri = Uri::try_from(raw.as_str()).unwrap();
+        let uri = Uri::try_from(raw.as_str()).map_err(|source| Error::RequestFailed { source })?;
+        #[cfg(feature = "log-bodies")]
+        super::body_log::log_request("POST", &raw, &body);
         let mut request = Request::new(&uri);
         request
             .method(Method::POST)
             .header("Content-Type", "application/json")
             .header("Content-Length", &body.len())
-            .body(body);
+            .body(&body);
 
-        self.make_json_request(&mut request).await
+        let stats = RequestStats::new("POST", &raw, body.len());
+        self.make_json_request(&mut request, stats).await
+    }
+
+    async fn patch_json<S: Into<String> + Send>(
+        &self,
+        uri: S,
+        body: Vec<u8>,
+    ) -> crate::Result<Object> {
+        let raw: String = uri.into();
+
+        let uri = Uri::try_from(raw.as_str()).map_err(|source| Error::RequestFailed { source })?;
+        #[cfg(feature = "log-bodies")]
+        super::body_log::log_request("PATCH", &raw, &body);
+        let mut request = Request::new(&uri);
+        request
+            .method(Method::PATCH)
+            .header("Content-Type", "application/json")
+            .header("Content-Length", &body.len())
+            .body(&body);
+
+        let stats = RequestStats::new("PATCH", &raw, body.len());
+        self.make_json_request(&mut request, stats).await
+    }
+
+    async fn post_multipart<S: Into<String> + Send>(
+        &self,
+        uri: S,
+        form: MultipartForm,
+    ) -> crate::Result<Object> {
+        let raw: String = uri.into();
+
+        let uri = Uri::try_from(raw.as_str()).map_err(|source| Error::RequestFailed { source })?;
+        let (content_type, body) = form.encode();
+        let mut request = Request::new(&uri);
+        request
+            .method(Method::POST)
+            .header("Content-Type", &content_type)
+            .header("Content-Length", &body.len())
+            .body(&body);
+
+        let stats = RequestStats::new("POST", &raw, body.len());
+        self.make_json_request(&mut request, stats).await
+    }
+
+    async fn delete<S: Into<String> + Send>(
+        &self,
+        uri: S,
+    ) -> crate::Result<Object> {
+        let raw: String = uri.into();
+
+        let uri = Uri::try_from(raw.as_str()).map_err(|source| Error::RequestFailed { source })?;
+        let mut request = Request::new(&uri);
+        request.method(Method::DELETE);
+        let stats = RequestStats::new("DELETE", &raw, 0);
+        self.make_json_request(&mut request, stats).await
     }
 }
 
@@ -103,28 +322,110 @@ impl Client {
     async fn make_json_request(
         &self,
         request: &mut Request<'_>,
+        mut stats: RequestStats,
     ) -> Result<Object, Error> {
         let mut writer = Vec::new();
+        for (name, value) in &self.headers {
+            request.header(name, value);
+        }
+        if self.timeout.is_some() {
+            request.timeout(self.timeout);
+        }
+        if self.connect_timeout.is_some() {
+            request.connect_timeout(self.connect_timeout);
+        }
+        let start = Instant::now();
         let resp = request
-            .header("Notion-Version", NOTION_API_VERSION)
-            .header("Authorization", &format!("Bearer {}", self.token))
-            .send(&mut writer)
-            .map_err(|source| Error::RequestFailed { source })?;
-
-        let text = String::from_utf8_lossy(&writer);
+            .header("Notion-Version", self.version.as_str())
+            .header("User-Agent", &self.user_agent)
+            .header(
+                "Authorization",
+                &format!("Bearer {}", self.token.expose_secret()),
+            )
+            .send(&mut writer);
+        stats.elapsed = start.elapsed();
+        stats.response_bytes = writer.len();
+        stats.status = resp.as_ref().ok().map(|resp| resp.status_code().into());
+        self.stats.record(stats);
+        #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+        let resp = resp.map_err(|source| Error::RequestFailed { source })?;
 
-        tracing::debug!("Response: {:?}", resp);
+        debug!("Response: {:?}", resp);
+        #[cfg(feature = "log-bodies")]
+        super::body_log::log_response(&writer);
         #[cfg(test)]
         {
-            dbg!(serde_json::from_str::<serde_json::Value>(&text)
-                .map_err(|source| Error::JsonParseError { source })?);
+            dbg!(serde_json::from_slice::<serde_json::Value>(&writer).ok());
         }
-        let result =
-            serde_json::from_str(&text).map_err(|source| Error::JsonParseError { source })?;
+        let headers = resp.headers();
+        let head = ResponseHead {
+            status: resp.status_code().into(),
+            request_id: REQUEST_ID_HEADERS
+                .iter()
+                .find_map(|name| headers.get(name))
+                .cloned(),
+            retry_after: headers
+                .get("Retry-After")
+                .and_then(|value| super::retry::parse_retry_after(value)),
+        };
+        decode_response(self.codec.as_ref(), head, &writer)
+    }
+}
 
-        match result {
-            Object::Error { error } => Err(Error::ApiError { error }),
-            response => Ok(response),
-        }
+/// Sends `body` as JSON with HTTP Basic authentication instead of the integration token,
+/// and returns the status and the body of the response.
+pub(crate) async fn post_with_basic_auth(
+    url: &str,
+    username: &str,
+    password: &SecretString,
+    body: Vec<u8>,
+) -> Result<(u16, Vec<u8>), Error> {
+    let uri = Uri::try_from(url).map_err(|source| Error::RequestFailed { source })?;
+    let credentials =
+        super::oauth::base64(format!("{}:{}", username, password.expose_secret()).as_bytes());
+    let mut writer = Vec::new();
+    let response = Request::new(&uri)
+        .method(Method::POST)
+        .header("Authorization", &format!("Basic {}", credentials))
+        .header("Content-Type", "application/json")
+        .header("Content-Length", &body.len())
+        .body(&body)
+        .send(&mut writer)
+        .map_err(|source| Error::RequestFailed { source })?;
+    Ok((response.status_code().into(), writer))
+}
+
+/// Downloads `url` without the Notion headers, which the presigned URLs
+/// of uploaded files reject.
+pub(crate) async fn download(url: &str) -> Result<Vec<u8>, Error> {
+    let uri = Uri::try_from(url).map_err(|source| Error::RequestFailed { source })?;
+    let mut body = Vec::new();
+    let response = Request::new(&uri)
+        .method(Method::GET)
+        .send(&mut body)
+        .map_err(|source| Error::RequestFailed { source })?;
+
+    let status = response.status_code();
+    if !status.is_success() {
+        return Err(Error::DownloadFailed {
+            url: url.to_string(),
+            status: status.into(),
+        });
     }
+    Ok(body)
+}
+
+/// Like [download()], writing the body to `writer`.
+/// Returns the number of bytes written.
+///
+/// `http_req` reads the whole body before the status can be checked,
+/// so it is buffered and only written once the download succeeded.
+pub(crate) async fn download_to<W: std::io::Write>(
+    url: &str,
+    writer: &mut W,
+) -> Result<u64, Error> {
+    let body = download(url).await?;
+    writer.write_all(&body)?;
+    writer.flush()?;
+    Ok(body.len() as u64)
 }
@@ -0,0 +1,221 @@
+use std::collections::VecDeque;
+
+use serde_json::Value;
+
+use crate::ids::{AsIdentifier, BlockId};
+use crate::models::block::{Block, CreateBlock, CreateBlockTree};
+use crate::models::{Object, Page, PageCreateRequest, Parent};
+use crate::render::markdown;
+use crate::{Error, NotionApi, Result, TClient};
+
+/// The most blocks the API appends in one request.
+const MAX_APPENDED_BLOCKS: usize = 100;
+
+impl<C: TClient> NotionApi<C> {
+    /// Appends blocks with any depth of nesting to the children of a page or block,
+    /// and returns the created top-level blocks.
+    ///
+    /// Blocks are sent at most 100 per request, and the children of each created
+    /// block are appended to it afterwards. Table rows are sent with their table,
+    /// which the API requires.
+    pub async fn append_block_trees<B: AsIdentifier<BlockId>>(
+        &self,
+        block_id: B,
+        trees: Vec<CreateBlockTree>,
+    ) -> Result<Vec<Block>> {
+        let mut created_top_level = vec![];
+        let mut queue = VecDeque::new();
+        queue.push_back((block_id.as_id().clone(), trees));
+
+        while let Some((parent, trees)) = queue.pop_front() {
+            let mut trees = trees.into_iter().peekable();
+            while trees.peek().is_some() {
+                let chunk: Vec<CreateBlockTree> =
+                    trees.by_ref().take(MAX_APPENDED_BLOCKS).collect();
+                let created = self.append_chunk(&parent, &chunk).await?;
+
+                for (block, tree) in created.iter().zip(chunk) {
+                    let is_table = matches!(tree.block, CreateBlock::Table { .. });
+                    match block.id() {
+                        Some(id) if !is_table && !tree.children.is_empty() => {
+                            queue.push_back((id.clone(), tree.children))
+                        }
+                        _ => {}
+                    }
+                }
+                if &parent == block_id.as_id() {
+                    created_top_level.extend(created);
+                }
+            }
+        }
+
+        Ok(created_top_level)
+    }
+
+    /// Creates a page titled `title` under `parent` with the content of a Markdown document,
+    /// see [markdown::parse()] for the supported syntax.
+    ///
+    /// The page is created first and its content appended with
+    /// [append_block_trees()](Self::append_block_trees()).
+    pub async fn import_markdown(
+        &self,
+        parent: Parent,
+        title: &str,
+        document: &str,
+    ) -> Result<Page> {
+        let page = self
+            .create_page(PageCreateRequest::builder(parent).title(title))
+            .await?;
+
+        self.append_markdown(BlockId::from(page.id.clone()), document)
+            .await?;
+        Ok(page)
+    }
+
+    /// Appends the content of a Markdown document to a page or block,
+    /// for example to keep adding to a log, and returns the created top-level blocks.
+    /// See [markdown::parse()] for the supported syntax.
+    pub async fn append_markdown<B: AsIdentifier<BlockId>>(
+        &self,
+        block_id: B,
+        document: &str,
+    ) -> Result<Vec<Block>> {
+        self.append_block_trees(block_id, markdown::parse(document))
+            .await
+    }
+
+    async fn append_chunk(
+        &self,
+        parent: &BlockId,
+        chunk: &[CreateBlockTree],
+    ) -> Result<Vec<Block>> {
+        let children: Vec<Value> = chunk.iter().map(block_value).collect();
+        let body = serde_json::to_vec(&serde_json::json!({ "children": children })).unwrap();
+        let uri = format!(
+            "{base_url}/blocks/{block_id}/children",
+            base_url = self.base_url,
+            block_id = parent
+        );
+
+        match self.client.patch_json(uri, body).await? {
+            Object::List { list } => Ok(list.expect_blocks()?.results),
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
+}
+
+/// The JSON of a block, with the rows of a table nested in it.
+fn block_value(tree: &CreateBlockTree) -> Value {
+    let mut value = serde_json::to_value(&tree.block).unwrap();
+    if let (CreateBlock::Table { .. }, Some(table)) = (&tree.block, value.get_mut("table")) {
+        let rows = tree
+            .children
+            .iter()
+            .map(|row| serde_json::to_value(&row.block).unwrap())
+            .collect();
+        table["children"] = Value::Array(rows);
+    }
+    value
+}
+
+#[cfg(all(test, not(target_os = "wasi")))]
+mod tests {
+    use crate::ids::{BlockId, PageId};
+    use crate::models::{Object, Parent};
+    use crate::test_util::{list_response, BlockFixture, MockNotion, PageFixture};
+    use serde_json::json;
+    use std::str::FromStr;
+    use wiremock::matchers::{body_partial_json, method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    #[tokio::test]
+    async fn import_markdown_appends_nested_blocks() {
+        let mock = MockNotion::start().await;
+        let page = PageFixture::new().title("Notes").build();
+        let heading = BlockFixture::new().heading_1("Notes");
+        let item = BlockFixture::new()
+            .has_children(true)
+            .bulleted_list_item("one");
+        let nested = BlockFixture::new().bulleted_list_item("nested");
+        Mock::given(method("POST"))
+            .and(path("/v1/pages"))
+            .and(body_partial_json(json!({
+                "properties": {"title": {"title": [{"text": {"content": "Notes"}}]}},
+            })))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(Object::Page { page: page.clone() }),
+            )
+            .expect(1)
+            .mount(mock.server())
+            .await;
+        for (parent, sent, created) in [
+            (
+                BlockId::from(page.id.clone()),
+                json!([{"type": "heading_1"}, {"type": "bulleted_list_item"}]),
+                vec![heading, item.clone()],
+            ),
+            (
+                item.id().unwrap().clone(),
+                json!([{"bulleted_list_item": {"rich_text": [{"plain_text": "nested"}]}}]),
+                vec![nested],
+            ),
+        ] {
+            Mock::given(method("PATCH"))
+                .and(path(format!("/v1/blocks/{}/children", parent)))
+                .and(body_partial_json(json!({ "children": sent })))
+                .respond_with(
+                    ResponseTemplate::new(200).set_body_json(Object::List {
+                        list: list_response(
+                            created
+                                .into_iter()
+                                .map(|block| Object::Block { block })
+                                .collect(),
+                        ),
+                    }),
+                )
+                .expect(1)
+                .mount(mock.server())
+                .await;
+        }
+
+        let imported = mock
+            .api()
+            .import_markdown(
+                Parent::Page {
+                    page_id: PageId::from_str("parent").unwrap(),
+                },
+                "Notes",
+                "# Notes\n\n- one\n  - nested\n",
+            )
+            .await
+            .unwrap();
+        assert_eq!(imported, page);
+    }
+
+    #[tokio::test]
+    async fn append_markdown_to_a_page() {
+        let mock = MockNotion::start().await;
+        let page_block = BlockId::from(PageFixture::new().build().id);
+        let entry = BlockFixture::new().to_do("Deploy", false);
+        Mock::given(method("PATCH"))
+            .and(path(format!("/v1/blocks/{}/children", page_block)))
+            .and(body_partial_json(json!({
+                "children": [{"to_do": {"checked": false}}],
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(Object::List {
+                list: list_response(vec![Object::Block {
+                    block: entry.clone(),
+                }]),
+            }))
+            .expect(1)
+            .mount(mock.server())
+            .await;
+
+        let created = mock
+            .api()
+            .append_markdown(&page_block, "- [ ] Deploy\n")
+            .await
+            .unwrap();
+        assert_eq!(created, vec![entry]);
+    }
+}
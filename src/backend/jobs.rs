@@ -0,0 +1,496 @@
+//! Running many operations, such as the writes of a migration, concurrently and resumably.
+//!
+//! ```no_run
+//! # async fn example(rows: Vec<(String, notion_wasi::models::PageCreateRequest)>) -> Result<(), Box<dyn std::error::Error>> {
+//! use std::time::Duration;
+//!
+//! use notion_wasi::{JobRunner, NotionApi};
+//!
+//! let api = NotionApi::new(std::env::var("NOTION_API_TOKEN")?)?;
+//! let report = JobRunner::new()
+//!     .concurrency(3)
+//!     .start_interval(Duration::from_millis(350))
+//!     .checkpoint("migration.json")
+//!     .on_progress(|progress| eprintln!("{}/{}", progress.finished(), progress.total))
+//!     // Each row is keyed by its id in the old system.
+//!     .run(&api, rows, |api, request| api.create_page(request))
+//!     .await?;
+//!
+//! for (key, error) in &report.failed {
+//!     eprintln!("{} failed: {}", key, error);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Running the same jobs again skips the keys the checkpoint lists as done,
+//! so an interrupted migration picks up where it stopped and failed jobs are tried again.
+
+use std::collections::BTreeSet;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use futures::{stream, StreamExt};
+use serde::{Deserialize, Serialize};
+
+use super::retry::{sleep, Instant};
+use crate::{Error, NotionApi, Result, RetryPolicy, TClient};
+
+/// The keys of the jobs that succeeded, stored as a JSON file.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Default)]
+pub struct JobCheckpoint {
+    completed: BTreeSet<String>,
+}
+
+impl JobCheckpoint {
+    pub fn load<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// Like [load()](Self::load()), but starts empty if the file doesn't exist yet.
+    pub fn load_or_default<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        match Self::load(path) {
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            result => result,
+        }
+    }
+
+    pub fn save<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> std::io::Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, json)
+    }
+
+    pub fn is_completed(
+        &self,
+        key: &str,
+    ) -> bool {
+        self.completed.contains(key)
+    }
+
+    pub fn completed(&self) -> impl Iterator<Item = &str> {
+        self.completed.iter().map(String::as_str)
+    }
+}
+
+/// How far a [JobRunner::run()] got, passed to [JobRunner::on_progress()]
+/// after every job that finished.
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Default)]
+pub struct JobProgress {
+    /// All jobs, including the skipped ones.
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    /// Jobs the checkpoint listed as done, which were not run again.
+    pub skipped: usize,
+}
+
+impl JobProgress {
+    /// Jobs that succeeded, failed or were skipped.
+    pub fn finished(&self) -> usize {
+        self.succeeded + self.failed + self.skipped
+    }
+}
+
+/// The result of [JobRunner::run()].
+#[derive(Debug)]
+pub struct JobReport<R> {
+    /// The results of the jobs in the order they finished.
+    pub succeeded: Vec<(String, R)>,
+    /// The last error of each job that failed, in the order they finished.
+    pub failed: Vec<(String, Error)>,
+    /// The keys the checkpoint listed as done.
+    pub skipped: Vec<String>,
+}
+
+impl<R> Default for JobReport<R> {
+    fn default() -> Self {
+        JobReport {
+            succeeded: vec![],
+            failed: vec![],
+            skipped: vec![],
+        }
+    }
+}
+
+impl<R> JobReport<R> {
+    /// Whether no job failed.
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+type ProgressHandler<'a> = Box<dyn Fn(&JobProgress) + Send + Sync + 'a>;
+
+/// Runs keyed jobs against a [NotionApi], see the [module docs](self).
+///
+/// By default 3 jobs run at a time, as many as the API allows requests per second
+/// on average, and a job is only repeated when the API answered `429`,
+/// because such requests were rejected before anything was applied.
+pub struct JobRunner<'a> {
+    concurrency: usize,
+    retry: RetryPolicy,
+    start_interval: Duration,
+    checkpoint: Option<PathBuf>,
+    on_progress: Option<ProgressHandler<'a>>,
+}
+
+impl<'a> Default for JobRunner<'a> {
+    fn default() -> Self {
+        JobRunner {
+            concurrency: 3,
+            retry: RetryPolicy {
+                status_codes: vec![429],
+                retry_transport_errors: false,
+                ..RetryPolicy::default()
+            },
+            start_interval: Duration::ZERO,
+            checkpoint: None,
+            on_progress: None,
+        }
+    }
+}
+
+impl<'a> JobRunner<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Jobs running at the same time, at least 1.
+    pub fn concurrency(
+        mut self,
+        concurrency: usize,
+    ) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// When a failed job is run again.
+    ///
+    /// A job usually sends several requests, so [RetryPolicy::methods] is ignored:
+    /// only retry errors after which the whole job is safe to repeat.
+    pub fn retry(
+        mut self,
+        policy: RetryPolicy,
+    ) -> Self {
+        self.retry = policy;
+        self
+    }
+
+    /// The shortest time between the starts of two attempts, shared by all running jobs.
+    pub fn start_interval(
+        mut self,
+        interval: Duration,
+    ) -> Self {
+        self.start_interval = interval;
+        self
+    }
+
+    /// Records the keys of succeeded jobs in the file at `path` after each one,
+    /// and skips the keys it already lists.
+    pub fn checkpoint<P: Into<PathBuf>>(
+        mut self,
+        path: P,
+    ) -> Self {
+        self.checkpoint = Some(path.into());
+        self
+    }
+
+    pub fn on_progress<F>(
+        mut self,
+        handler: F,
+    ) -> Self
+    where
+        F: Fn(&JobProgress) + Send + Sync + 'a,
+    {
+        self.on_progress = Some(Box::new(handler));
+        self
+    }
+
+    /// Runs `job` for every `(key, item)` of `jobs` whose key is not in the checkpoint.
+    ///
+    /// Keys identify the jobs across runs and should be unique.
+    /// Failed jobs don't stop the others and are listed in [JobReport::failed].
+    /// Only reading or writing the checkpoint fails the run, which drops the jobs in flight.
+    pub async fn run<'b, C, I, T, F, Fut, R>(
+        &self,
+        api: &'b NotionApi<C>,
+        jobs: I,
+        job: F,
+    ) -> Result<JobReport<R>>
+    where
+        C: TClient,
+        I: IntoIterator<Item = (String, T)>,
+        T: Clone,
+        F: Fn(&'b NotionApi<C>, T) -> Fut,
+        Fut: Future<Output = Result<R>>,
+    {
+        let mut checkpoint = match &self.checkpoint {
+            Some(path) => JobCheckpoint::load_or_default(path)?,
+            None => JobCheckpoint::default(),
+        };
+        let mut report = JobReport::default();
+        let mut pending = vec![];
+        for (key, item) in jobs {
+            if checkpoint.is_completed(&key) {
+                report.skipped.push(key);
+            } else {
+                pending.push((key, item));
+            }
+        }
+        let mut progress = JobProgress {
+            total: pending.len() + report.skipped.len(),
+            skipped: report.skipped.len(),
+            ..JobProgress::default()
+        };
+
+        let next_start = Mutex::new(None);
+        let (job, next_start) = (&job, &next_start);
+        let mut finished = stream::iter(pending)
+            .map(|(key, item)| async move {
+                let result = self.attempt(api, job, item, next_start).await;
+                (key, result)
+            })
+            .buffer_unordered(self.concurrency);
+
+        while let Some((key, result)) = finished.next().await {
+            match result {
+                Ok(value) => {
+                    checkpoint.completed.insert(key.clone());
+                    if let Some(path) = &self.checkpoint {
+                        checkpoint.save(path)?;
+                    }
+                    report.succeeded.push((key, value));
+                    progress.succeeded += 1;
+                }
+                Err(error) => {
+                    warn!(key = %key, error = %error, "job failed");
+                    report.failed.push((key, error));
+                    progress.failed += 1;
+                }
+            }
+            if let Some(on_progress) = &self.on_progress {
+                on_progress(&progress);
+            }
+        }
+        Ok(report)
+    }
+
+    /// Runs `job` for every item of `items` like [run()](Self::run()), keeping up to
+    /// the configured number of jobs in flight and retrying them with the same policy,
+    /// and returns the results in the order of `items`.
+    ///
+    /// The items have no keys, so the checkpoint and the progress handler are not used.
+    pub async fn run_in_order<'b, C, I, T, F, Fut, R>(
+        &self,
+        api: &'b NotionApi<C>,
+        items: I,
+        job: F,
+    ) -> Vec<Result<R>>
+    where
+        C: TClient,
+        I: IntoIterator<Item = T>,
+        T: Clone,
+        F: Fn(&'b NotionApi<C>, T) -> Fut,
+        Fut: Future<Output = Result<R>>,
+    {
+        let next_start = Mutex::new(None);
+        let (job, next_start) = (&job, &next_start);
+        stream::iter(items)
+            .map(|item| self.attempt(api, job, item, next_start))
+            .buffered(self.concurrency)
+            .collect()
+            .await
+    }
+
+    async fn attempt<'b, C, T, F, Fut, R>(
+        &self,
+        api: &'b NotionApi<C>,
+        job: &F,
+        item: T,
+        next_start: &Mutex<Option<Instant>>,
+    ) -> Result<R>
+    where
+        C: TClient,
+        T: Clone,
+        F: Fn(&'b NotionApi<C>, T) -> Fut,
+        Fut: Future<Output = Result<R>>,
+    {
+        let started = Instant::now();
+        let mut attempt = 1;
+        loop {
+            self.wait_for_turn(next_start).await;
+            match job(api, item.clone()).await {
+                Err(error) if self.should_retry(&error, attempt) => {
+                    let backoff = self.retry.delay(attempt, &error);
+                    if !self.retry.within_deadline(started.elapsed(), backoff) {
+                        return Err(error);
+                    }
+                    debug!(attempt, ?backoff, error = %error, "retrying job");
+                    sleep(backoff).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    fn should_retry(
+        &self,
+        error: &Error,
+        attempt: u32,
+    ) -> bool {
+        attempt < self.retry.max_attempts && self.retry.is_transient(error)
+    }
+
+    async fn wait_for_turn(
+        &self,
+        next_start: &Mutex<Option<Instant>>,
+    ) {
+        if self.start_interval.is_zero() {
+            return;
+        }
+        let wait = {
+            let mut next_start = next_start.lock().unwrap();
+            let now = Instant::now();
+            let start = next_start.map_or(now, |next: Instant| next.max(now));
+            *next_start = Some(start + self.start_interval);
+            start - now
+        };
+        if !wait.is_zero() {
+            sleep(wait).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{JobProgress, JobRunner};
+    use crate::ids::PageId;
+    use crate::models::error::{ErrorCode, ErrorResponse, StatusCode};
+    use crate::models::Page;
+    use crate::test_util::{MockNotion, PageFixture};
+    use crate::{Error, NotionApi, RetryPolicy};
+
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Mutex;
+    use std::time::{Duration, Instant};
+
+    fn rate_limited() -> Error {
+        Error::ApiError {
+            error: ErrorResponse {
+                status: StatusCode::from(429),
+                code: ErrorCode::RateLimited,
+                message: String::new(),
+                request_id: None,
+                developer_survey: None,
+                additional_data: None,
+                retry_after: None,
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn jobs_retry_and_report_progress() {
+        let api = NotionApi::new("token").unwrap();
+        let attempts = AtomicU32::new(0);
+        let progress = Mutex::new(vec![]);
+        let runner = JobRunner::new()
+            .retry(RetryPolicy {
+                initial_backoff: Duration::from_millis(1),
+                ..RetryPolicy::default()
+            })
+            .start_interval(Duration::from_millis(10))
+            .on_progress(|update| progress.lock().unwrap().push(*update));
+
+        let started = Instant::now();
+        let jobs = (1..=3).map(|n: u32| (n.to_string(), n));
+        let report = runner
+            .run(&api, jobs, |_, n| {
+                let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    match n {
+                        // Rate limited once, then succeeds.
+                        1 if attempt == 0 => Err(rate_limited()),
+                        3 => Err(Error::MissingEnvVar {
+                            name: "NOTION_API_TOKEN".to_string(),
+                        }),
+                        n => Ok(n * 10),
+                    }
+                }
+            })
+            .await
+            .unwrap();
+
+        let mut succeeded = report.succeeded.clone();
+        succeeded.sort();
+        assert_eq!(
+            succeeded,
+            vec![("1".to_string(), 10), ("2".to_string(), 20)]
+        );
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].0, "3");
+        assert!(!report.is_complete());
+        assert_eq!(attempts.load(Ordering::SeqCst), 4);
+        // Four attempts started at least 10ms apart.
+        assert!(started.elapsed() >= Duration::from_millis(30));
+
+        drop(runner);
+        let progress = progress.into_inner().unwrap();
+        assert_eq!(progress.len(), 3);
+        assert_eq!(
+            progress[2],
+            JobProgress {
+                total: 3,
+                succeeded: 2,
+                failed: 1,
+                skipped: 0,
+            }
+        );
+        assert_eq!(progress[2].finished(), 3);
+    }
+
+    #[tokio::test]
+    async fn resume_jobs_from_checkpoint() {
+        let mock = MockNotion::start().await;
+        let pages: Vec<Page> = (0..3).map(|_| PageFixture::new().build()).collect();
+        for page in &pages[..2] {
+            mock.mount_page(page).await;
+        }
+        let checkpoint =
+            std::env::temp_dir().join(format!("notion-jobs-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&checkpoint);
+        let jobs: Vec<(String, PageId)> = pages
+            .iter()
+            .map(|page| (page.id.to_string(), page.id.clone()))
+            .collect();
+        let runner = JobRunner::new().checkpoint(&checkpoint);
+        let api = mock.api();
+
+        let report = runner
+            .run(&api, jobs.clone(), |api, page_id| api.get_page(page_id))
+            .await
+            .unwrap();
+        assert_eq!(report.succeeded.len(), 2);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].0, pages[2].id.to_string());
+
+        mock.mount_page(&pages[2]).await;
+        let report = runner
+            .run(&api, jobs, |api, page_id| api.get_page(page_id))
+            .await
+            .unwrap();
+        assert_eq!(report.skipped.len(), 2);
+        assert_eq!(
+            report.succeeded,
+            vec![(pages[2].id.to_string(), pages[2].clone())]
+        );
+        assert!(report.is_complete());
+        std::fs::remove_file(&checkpoint).unwrap();
+    }
+}
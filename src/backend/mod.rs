@@ -1,32 +1,286 @@
-use crate::ids::{AsIdentifier, BlockId, DatabaseId, PageId};
+#[cfg(feature = "blocks")]
+use crate::ids::BlockId;
+#[cfg(feature = "databases")]
+use crate::ids::DatabaseId;
+use crate::ids::{AsIdentifier, PageId, PropertyId, UserId};
+#[cfg(feature = "blocks")]
+use crate::models::block::{Block, BlockAppendRequest, BlockUpdateRequest};
+use crate::models::error::ErrorCode;
+use crate::models::users::{User, WorkspaceLimits};
+#[cfg(feature = "databases")]
 use crate::models::{
-    block::Block,
-    search::{DatabaseQuery, SearchRequest},
-    Database, ListResponse, Object, Page, PageCreateRequest,
+    aggregate::Aggregate,
+    columns::Columns,
+    csv::{self, CsvOptions},
+    ical::{self, IcalOptions},
+    paging::Pageable,
+    properties::Color,
+    search::{DatabaseQuery, FilterCondition, PropertyCondition},
+    Database, Parent, Properties, Trashable,
 };
+use crate::models::{
+    search::{FilterValue, SearchRequest},
+    ListResponse, Object, Page, PageCreateRequest, PageUpdateRequest, SearchResult,
+};
+use crate::{ApiVersion, SecretString, NOTION_API_BASE_URL};
 use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+mod audit;
+mod batch;
+#[cfg(feature = "log-bodies")]
+mod body_log;
+mod boxed;
+mod builder;
+mod caching;
+mod coalescing;
+mod codec;
+#[cfg(feature = "comments")]
+mod comments;
+mod conditional;
+#[cfg(feature = "databases")]
+mod data_sources;
+mod dry_run;
+mod env;
+#[cfg(feature = "blocks")]
+mod export;
+mod files;
+#[cfg(feature = "blocks")]
+mod import;
+mod jobs;
+mod multi_select;
+mod oauth;
+mod pagination;
+#[cfg(all(feature = "pdf", not(target_arch = "wasm32")))]
+mod pdf;
+mod property_items;
+mod recording;
+mod relations;
+mod retry;
+#[cfg(feature = "databases")]
+mod schema;
+#[cfg(feature = "databases")]
+mod select_options;
+mod stats;
+#[cfg(all(
+    test,
+    feature = "blocks",
+    feature = "databases",
+    not(target_os = "wasi")
+))]
+mod tests;
+#[cfg(feature = "blocks")]
+mod tree;
+#[cfg(feature = "databases")]
+mod typed_database;
+mod uploads;
+mod users;
+#[cfg(feature = "databases")]
+mod watch;
+mod write_queue;
+pub use audit::{AuditEntry, AuditOutcome, AuditSink, Auditing};
+pub use batch::{BatchFailure, BatchOutcome, OnBatchFailure};
+pub use boxed::{BoxedClient, DynClient};
+pub use builder::{ClientBuilder, DEFAULT_USER_AGENT};
+pub use caching::{Cache, CacheEntry, Caching, LruCache};
+pub use coalescing::Coalescing;
+#[cfg(all(feature = "simd-json", not(target_os = "wasi")))]
+pub use codec::SimdJsonCodec;
+pub use codec::{JsonCodec, LenientJsonCodec, SerdeJsonCodec};
+pub use conditional::ConditionalUpdate;
+pub use dry_run::DryRun;
+pub use env::{API_VERSION_ENV, TOKEN_ENV};
+#[cfg(feature = "blocks")]
+pub use export::{ExportedPage, MarkdownExport};
+pub use jobs::{JobCheckpoint, JobProgress, JobReport, JobRunner};
+pub use oauth::OAuthClient;
+pub use pagination::{CollectAll, PageIter, PaginationOptions};
+#[cfg(all(feature = "pdf", not(target_arch = "wasm32")))]
+pub use pdf::{PdfConverter, PDF_INPUT, PDF_OUTPUT};
+pub use recording::{Cassette, Interaction, Recorder, Replayer};
+pub use relations::{RelationEdge, RelationGraph};
+pub use retry::{Method, RetryPolicy, Retrying};
+pub use stats::{EndpointStats, RateLimitStatus, RequestStats};
+#[cfg(feature = "blocks")]
+pub use tree::{BlockTreeOptions, PageWithContent};
+#[cfg(feature = "databases")]
+pub use typed_database::{Row, TypedDatabase};
+pub use uploads::MultipartForm;
+#[cfg(feature = "databases")]
+pub use watch::{
+    Change, CursorStore, FileCursorStore, MemoryCursorStore, WatchCursor, WatchOptions,
+};
+pub use write_queue::{QueuedWrite, WriteOutcome, WriteQueue};
 
 #[cfg(not(target_os = "wasi"))]
 mod reqwest_impl;
 
+#[cfg(all(feature = "blocks", not(target_os = "wasi")))]
+pub(crate) use reqwest_impl::download;
+#[cfg(not(target_os = "wasi"))]
+pub(crate) use reqwest_impl::{download_to, post_with_basic_auth};
 #[cfg(not(target_os = "wasi"))]
 pub use reqwest_impl::{Client, Error};
 
-#[cfg(target_os = "wasi")]
+#[cfg(all(target_os = "wasi", not(feature = "wasi-http")))]
 mod http_req_impl;
 
-#[cfg(target_os = "wasi")]
+#[cfg(all(feature = "blocks", target_os = "wasi", not(feature = "wasi-http")))]
+pub(crate) use http_req_impl::download;
+#[cfg(all(target_os = "wasi", not(feature = "wasi-http")))]
+pub(crate) use http_req_impl::{download_to, post_with_basic_auth};
+#[cfg(all(target_os = "wasi", not(feature = "wasi-http")))]
 pub use http_req_impl::{Client, Error};
 
+#[cfg(all(target_os = "wasi", feature = "wasi-http"))]
+mod wasi_http_impl;
+
+#[cfg(all(feature = "blocks", target_os = "wasi", feature = "wasi-http"))]
+pub(crate) use wasi_http_impl::download;
+#[cfg(all(target_os = "wasi", feature = "wasi-http"))]
+pub(crate) use wasi_http_impl::{download_to, post_with_basic_auth};
+#[cfg(all(target_os = "wasi", feature = "wasi-http"))]
+pub use wasi_http_impl::{Client, Error};
+
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// The `filter_properties` query string for `property_ids`, empty for no ids.
+fn filter_properties(property_ids: &[PropertyId]) -> String {
+    property_ids
+        .iter()
+        .enumerate()
+        .map(|(index, id)| {
+            let separator = if index == 0 { '?' } else { '&' };
+            format!("{}filter_properties={}", separator, id)
+        })
+        .collect()
+}
+
+impl Error {
+    /// The code of an error answered by the API.
+    pub fn api_error_code(&self) -> Option<&ErrorCode> {
+        match self {
+            Error::ApiError { error } => Some(&error.code),
+            _ => None,
+        }
+    }
+
+    /// The HTTP status of the response the error was made from.
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            Error::ApiError { error } => Some(error.status.code()),
+            Error::HttpError { response } => Some(response.status.code()),
+            _ => None,
+        }
+    }
+
+    /// How long the `Retry-After` header of the response asked to wait.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Error::ApiError { error } => error.retry_after,
+            Error::HttpError { response } => response.retry_after,
+            _ => None,
+        }
+    }
+
+    /// The id Notion gave the request, to be quoted to Notion support.
+    pub fn request_id(&self) -> Option<&str> {
+        match self {
+            Error::ApiError { error } => error.request_id.as_deref(),
+            Error::HttpError { response } => response.request_id.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// The start of the body of a response that isn't a Notion error
+    /// or that failed to parse, to see what was answered instead.
+    pub fn response_body(&self) -> Option<&str> {
+        match self {
+            Error::HttpError { response } => Some(&response.body),
+            Error::JsonParseError { body, .. } => body.as_deref(),
+            #[cfg(all(feature = "simd-json", not(target_os = "wasi")))]
+            Error::SimdJsonParseError { body, .. } => body.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Whether the request may succeed if it is sent again later:
+    /// rate limits, conflicts, server errors and requests that failed to send.
+    ///
+    /// Writes that failed to send may have reached the API anyway,
+    /// so make sure repeating them is safe.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::ApiError { error } => error.is_retryable(),
+            Error::HttpError { response } => response.is_retryable(),
+            Error::RequestFailed { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// The API answered `rate_limited` or status `429`;
+    /// slow down before sending more requests.
+    pub fn is_rate_limited(&self) -> bool {
+        self.api_error_code() == Some(&ErrorCode::RateLimited) || self.status() == Some(429)
+    }
+
+    /// The API answered `object_not_found` or status `404`, also when the
+    /// object exists but wasn't shared with the integration.
+    pub fn is_not_found(&self) -> bool {
+        self.api_error_code() == Some(&ErrorCode::ObjectNotFound) || self.status() == Some(404)
+    }
+
+    /// The API answered `conflict_error`, usually because the object
+    /// was changed by someone else at the same time.
+    pub fn is_conflict(&self) -> bool {
+        self.api_error_code() == Some(&ErrorCode::ConflictError)
+    }
+}
+
 #[async_trait]
 pub trait TClient {
+    /// The `Notion-Version` the requests are sent with,
+    /// which decides the shape of version specific request bodies.
+    fn api_version(&self) -> ApiVersion {
+        ApiVersion::default()
+    }
+
+    /// The recent requests and rate limiting of the client,
+    /// `None` if it doesn't keep track of them.
+    fn rate_limit_status(&self) -> Option<RateLimitStatus> {
+        None
+    }
+
     async fn get<S: Into<String> + Send>(
         &self,
         uri: S,
     ) -> Result<Object>;
 
+    /// Like [get()](Self::get()), but never answered from a cache such as the one
+    /// of [Caching], for reads that must see the current version of an object.
+    /// Clients wrapping another client forward it to the inner one.
+    ///
+    /// Spelled out the way `#[async_trait]` expands an `async fn`, so that the
+    /// default doesn't require the client to be `Sync`; implement it with
+    /// `async fn get_fresh` as any other method.
+    fn get_fresh<'life0, 'async_trait, S>(
+        &'life0 self,
+        uri: S,
+    ) -> Pin<Box<dyn Future<Output = Result<Object>> + Send + 'async_trait>>
+    where
+        S: Into<String> + Send + 'async_trait,
+        'life0: 'async_trait,
+        Self: 'async_trait,
+    {
+        self.get(uri)
+    }
+
     async fn post<S: Into<String> + Send>(
         &self,
         uri: S,
@@ -35,30 +289,212 @@ pub trait TClient {
     async fn post_json<S: Into<String> + Send>(
         &self,
         uri: S,
-        body: &[u8],
+        body: Vec<u8>,
+    ) -> Result<Object>;
+
+    async fn patch_json<S: Into<String> + Send>(
+        &self,
+        uri: S,
+        body: Vec<u8>,
+    ) -> Result<Object>;
+
+    /// Posts a `multipart/form-data` body, the way file contents are uploaded.
+    async fn post_multipart<S: Into<String> + Send>(
+        &self,
+        uri: S,
+        form: MultipartForm,
+    ) -> Result<Object>;
+
+    async fn delete<S: Into<String> + Send>(
+        &self,
+        uri: S,
     ) -> Result<Object>;
 }
 
-pub struct NotionApi {
-    client: Client,
+/// The Notion API client.
+///
+/// Requests are sent through `C`, which defaults to the HTTP [Client]
+/// selected for the target platform.
+/// Any other [TClient] can be used with [with_client()](Self::with_client()),
+/// and [boxed()](Self::boxed()) hides which one it is.
+pub struct NotionApi<C = Client> {
+    client: C,
+    base_url: String,
+    /// Users fetched by [resolve_people()](Self::resolve_people()).
+    users: Mutex<HashMap<UserId, User>>,
+    /// The ids of people by lowercase email, once [find_user_by_email()](Self::find_user_by_email())
+    /// listed all users.
+    emails: Mutex<Option<HashMap<String, UserId>>>,
+    /// Fetched by [workspace_limits()](Self::workspace_limits()).
+    limits: Mutex<Option<WorkspaceLimits>>,
+    /// Set by [create_missing_options()](Self::create_missing_options()).
+    #[cfg(feature = "databases")]
+    option_color: Option<Color>,
 }
 
-impl NotionApi {
-    pub fn new<S: Into<String>>(api_token: S) -> Result<Self> {
-        let client = Client::new(api_token.into())?;
+/// What [NotionApi::upsert_page()] should do when more than one page matches the key.
+#[cfg(feature = "databases")]
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Default)]
+pub enum OnDuplicate {
+    /// Update the first matching page and report the others in [Upserted::duplicates].
+    #[default]
+    UpdateFirst,
+    /// Fail with [Error::DuplicatePages] without writing anything.
+    Fail,
+}
+
+/// The outcome of [NotionApi::upsert_page()].
+#[cfg(feature = "databases")]
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Upserted {
+    /// The page as returned by the create or update call.
+    pub page: Page,
+    /// `true` if no page matched the key and a new one was created.
+    pub created: bool,
+    /// Other pages that matched the key but were left untouched.
+    pub duplicates: Vec<PageId>,
+}
 
-        Ok(Self { client })
+/// Shows the client, whose token is redacted.
+impl<C: fmt::Debug> fmt::Debug for NotionApi<C> {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        f.debug_struct("NotionApi")
+            .field("client", &self.client)
+            .field("base_url", &self.base_url)
+            .finish_non_exhaustive()
     }
 }
 
 impl NotionApi {
+    #[allow(clippy::result_large_err)]
+    pub fn new<S: Into<SecretString>>(api_token: S) -> Result<Self> {
+        let client = Client::new(api_token)?;
+
+        Ok(Self::with_client(client))
+    }
+
+    /// A NotionApi for the same workspace that sends `Notion-Version: {version}`,
+    /// for example to use a newer version for a few requests:
+    ///
+    /// ```no_run
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// use notion_wasi::{ApiVersion, NotionApi};
+    ///
+    /// # let page_id: notion_wasi::ids::PageId = "b55c9c91-384d-452b-81db-d1ef79372b75".parse()?;
+    /// let api = NotionApi::new(std::env::var("NOTION_API_TOKEN")?)?;
+    /// let page = api
+    ///     .for_version(ApiVersion::V2022_06_28)
+    ///     .get_page(&page_id)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// The connections and request statistics are shared, the cached users are not.
+    pub fn for_version(
+        &self,
+        version: ApiVersion,
+    ) -> Self {
+        Self::with_client(self.client.for_version(version)).with_base_url(self.base_url.clone())
+    }
+}
+
+impl<C: TClient> NotionApi<C> {
+    /// Creates a NotionApi that sends its requests through `client`.
+    pub fn with_client(client: C) -> Self {
+        Self {
+            client,
+            base_url: NOTION_API_BASE_URL.to_string(),
+            users: Mutex::default(),
+            emails: Mutex::default(),
+            limits: Mutex::default(),
+            #[cfg(feature = "databases")]
+            option_color: None,
+        }
+    }
+
+    /// Send requests to `base_url` instead of `https://api.notion.com/v1`,
+    /// for example to point the client at a mock server.
+    pub fn with_base_url<S: Into<String>>(
+        mut self,
+        base_url: S,
+    ) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Repeats failed requests according to `policy`, for example after
+    /// the API answered `429`, see [Retrying].
+    pub fn with_retries(
+        self,
+        policy: RetryPolicy,
+    ) -> NotionApi<Retrying<C>> {
+        self.map_client(|client| Retrying::new(client, policy))
+    }
+
+    /// Answers GET requests for pages, databases and blocks from `cache` while they
+    /// are neither stale nor older than `max_age`, see [Caching].
+    pub fn with_cache(
+        self,
+        cache: Arc<dyn Cache>,
+        max_age: Option<Duration>,
+    ) -> NotionApi<Caching<C>> {
+        self.map_client(|client| Caching::new(client, cache, max_age))
+    }
+
+    /// Erases the type of the client, so that differently configured
+    /// clients can be used in the same place, see [DynClient].
+    pub fn boxed(self) -> NotionApi<BoxedClient>
+    where
+        C: Send + Sync + 'static,
+    {
+        self.map_client(|client| Box::new(client) as BoxedClient)
+    }
+
+    /// Wraps or replaces the client, keeping the base URL and the cached users.
+    fn map_client<D>(
+        self,
+        map: impl FnOnce(C) -> D,
+    ) -> NotionApi<D> {
+        NotionApi {
+            client: map(self.client),
+            base_url: self.base_url,
+            users: self.users,
+            emails: self.emails,
+            limits: self.limits,
+            #[cfg(feature = "databases")]
+            option_color: self.option_color,
+        }
+    }
+
+    /// The client requests are sent through.
+    pub fn client(&self) -> &C {
+        &self.client
+    }
+
+    /// The `Notion-Version` of the underlying client.
+    pub fn api_version(&self) -> ApiVersion {
+        self.client.api_version()
+    }
+
+    /// How close the underlying client is to the rate limit, see [TClient::rate_limit_status()].
+    pub fn rate_limit_status(&self) -> Option<RateLimitStatus> {
+        self.client.rate_limit_status()
+    }
+}
+
+impl<C: TClient> NotionApi<C> {
     /// List all the databases shared with the supplied integration token.
     /// > This method is apparently deprecated/"not recommended" and
     /// > [search()](Self::search()) should be used instead.
+    #[cfg(feature = "databases")]
     pub async fn list_databases(&self) -> Result<ListResponse<Database>> {
         match self
             .client
-            .get("https://api.notion.com/v1/databases")
+            .get(format!("{}/databases", self.base_url))
             .await?
         {
             Object::List { list } => Ok(list.expect_databases()?),
@@ -73,11 +509,11 @@ impl NotionApi {
         &self,
         query: T,
     ) -> Result<ListResponse<Object>> {
-        let query = serde_json::to_string(&query.into()).unwrap();
+        let query = serde_json::to_vec(&query.into()).unwrap();
 
         let result = self
             .client
-            .post_json("https://api.notion.com/v1/search", query.as_bytes())
+            .post_json(format!("{}/search", self.base_url), query)
             .await?;
 
         match result {
@@ -86,15 +522,47 @@ impl NotionApi {
         }
     }
 
+    /// Like [search()](Self::search()), but returns only the objects search can find,
+    /// so callers don't have to handle the other [Object] variants.
+    pub async fn search_results<T: Into<SearchRequest>>(
+        &self,
+        query: T,
+    ) -> Result<ListResponse<SearchResult>> {
+        self.search(query).await?.expect_search_results()
+    }
+
+    /// Search the pages shared with the integration.
+    ///
+    /// Any object filter in `query` is replaced with one for pages.
+    pub async fn search_pages<T: Into<SearchRequest>>(
+        &self,
+        query: T,
+    ) -> Result<ListResponse<Page>> {
+        let query = query.into().only(FilterValue::Page);
+        self.search(query).await?.expect_pages()
+    }
+
+    /// Search the databases shared with the integration.
+    ///
+    /// Any object filter in `query` is replaced with one for databases.
+    /// API versions from 2025-09-03 on search data sources instead,
+    /// use [search_results()](Self::search_results()) with those.
+    #[cfg(feature = "databases")]
+    pub async fn search_databases<T: Into<SearchRequest>>(
+        &self,
+        query: T,
+    ) -> Result<ListResponse<Database>> {
+        let query = query.into().only(FilterValue::Database);
+        self.search(query).await?.expect_databases()
+    }
+
     /// Get a database by [DatabaseId].
+    #[cfg(feature = "databases")]
     pub async fn get_database<T: AsIdentifier<DatabaseId>>(
         &self,
         database_id: T,
     ) -> Result<Database> {
-        let uri = format!(
-            "https://api.notion.com/v1/databases/{}",
-            database_id.as_id()
-        );
+        let uri = format!("{}/databases/{}", self.base_url, database_id.as_id());
         let result = self.client.get(uri).await?;
 
         match result {
@@ -108,7 +576,7 @@ impl NotionApi {
         &self,
         page_id: T,
     ) -> Result<Page> {
-        let uri = format!("https://api.notion.com/v1/pages/{}", page_id.as_id());
+        let uri = format!("{}/pages/{}", self.base_url, page_id.as_id());
         let result = self.client.get(uri).await?;
 
         match result {
@@ -117,16 +585,174 @@ impl NotionApi {
         }
     }
 
+    /// Like [get_page()](Self::get_page()), but bypassing any cache,
+    /// for checks that must not compare against a stale copy.
+    pub(crate) async fn get_current_page(
+        &self,
+        page_id: &PageId,
+    ) -> Result<Page> {
+        let uri = format!("{}/pages/{}", self.base_url, page_id);
+        let result = self.client.get_fresh(uri).await?;
+
+        match result {
+            Object::Page { page } => Ok(page),
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
+
+    /// Get a page with only the given properties,
+    /// see [Database::property_id()](crate::models::Database::property_id()).
+    pub async fn get_page_with_properties<T: AsIdentifier<PageId>>(
+        &self,
+        page_id: T,
+        property_ids: &[PropertyId],
+    ) -> Result<Page> {
+        let uri = format!(
+            "{}/pages/{}{}",
+            self.base_url,
+            page_id.as_id(),
+            filter_properties(property_ids)
+        );
+        let result = self.client.get(uri).await?;
+
+        match result {
+            Object::Page { page } => Ok(page),
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
+
+    /// Get many pages, running up to `concurrency` requests at a time.
+    /// Results are returned in the same order as `page_ids`,
+    /// so a failure for one page does not affect the others.
+    ///
+    /// The requests are not spaced out, so a large batch can be rate limited.
+    /// Use an API [with retries](Self::with_retries()) so those requests are
+    /// repeated instead of failing, or space them with [get_pages_with()](Self::get_pages_with()).
+    pub async fn get_pages<I, T>(
+        &self,
+        page_ids: I,
+        concurrency: usize,
+    ) -> Vec<Result<Page>>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsIdentifier<PageId>,
+    {
+        stream::iter(page_ids)
+            .map(|page_id| self.get_page(page_id))
+            .buffered(concurrency.max(1))
+            .collect()
+            .await
+    }
+
+    /// Get many pages with `runner`, which spaces and retries the requests,
+    /// see [JobRunner::run_in_order()]. Results are returned in the order of `page_ids`.
+    ///
+    /// ```no_run
+    /// # async fn example(api: notion_wasi::NotionApi, ids: Vec<notion_wasi::ids::PageId>) {
+    /// use std::time::Duration;
+    ///
+    /// use notion_wasi::JobRunner;
+    ///
+    /// // Three requests per second on average, as many as Notion allows.
+    /// let runner = JobRunner::new().start_interval(Duration::from_millis(334));
+    /// let pages = api.get_pages_with(ids, &runner).await;
+    /// # }
+    /// ```
+    pub async fn get_pages_with<I, T>(
+        &self,
+        page_ids: I,
+        runner: &JobRunner<'_>,
+    ) -> Vec<Result<Page>>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsIdentifier<PageId>,
+    {
+        let page_ids = page_ids.into_iter().map(|id| id.as_id().clone());
+        runner
+            .run_in_order(self, page_ids, |api, page_id| api.get_page(page_id))
+            .await
+    }
+
     /// Creates a new page and return the created page
     pub async fn create_page<T: Into<PageCreateRequest>>(
         &self,
         page: T,
     ) -> Result<Page> {
-        let page = serde_json::to_string(&page.into()).unwrap();
+        let page = page.into();
+        page.validate()
+            .map_err(|source| Error::InvalidPage { source })?;
+        #[cfg(feature = "databases")]
+        if let (Some(color), Parent::Database { database_id }) = (self.option_color, &page.parent) {
+            self.add_missing_options(database_id, &page.properties, color)
+                .await?;
+        }
+        let page = serde_json::to_vec(&page).unwrap();
+
+        let result = self
+            .client
+            .post_json(format!("{}/pages", self.base_url), page)
+            .await?;
+
+        match result {
+            Object::Page { page } => Ok(page),
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
+
+    /// Updates the properties of a page and returns the updated page.
+    pub async fn update_page<P, T>(
+        &self,
+        page_id: P,
+        update: T,
+    ) -> Result<Page>
+    where
+        P: AsIdentifier<PageId>,
+        T: Into<PageUpdateRequest>,
+    {
+        let update = update.into();
+        #[cfg(feature = "databases")]
+        if let Some(color) = self.option_color {
+            self.add_missing_options_of_page(page_id.as_id(), &update.properties, color)
+                .await?;
+        }
+        let update = serde_json::to_vec(&update).unwrap();
+
+        let uri = format!(
+            "{base_url}/pages/{page_id}",
+            base_url = self.base_url,
+            page_id = page_id.as_id()
+        );
+
+        let result = self.client.patch_json(uri, update).await?;
+
+        match result {
+            Object::Page { page } => Ok(page),
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
+
+    /// Checks or unchecks the checkbox property `property` of a page
+    /// and returns the updated page.
+    pub async fn set_checkbox<P: AsIdentifier<PageId>>(
+        &self,
+        page_id: P,
+        property: &str,
+        checked: bool,
+    ) -> Result<Page> {
+        // Addressed by name, so the property id doesn't need to be known.
+        let update = serde_json::json!({
+            "properties": { property: { "checkbox": checked } }
+        });
+
+        let uri = format!(
+            "{base_url}/pages/{page_id}",
+            base_url = self.base_url,
+            page_id = page_id.as_id()
+        );
 
         let result = self
             .client
-            .post_json("https://api.notion.com/v1/pages", page.as_bytes())
+            .patch_json(uri, serde_json::to_vec(&update).unwrap())
             .await?;
 
         match result {
@@ -135,7 +761,78 @@ impl NotionApi {
         }
     }
 
+    /// Updates the page of `database` whose `key_property` matches `key_value`,
+    /// or creates it when no such page exists.
+    ///
+    /// Every matching page is read to find duplicates, following `next_cursor`
+    /// when more than one page of query results matches.
+    #[cfg(feature = "databases")]
+    pub async fn upsert_page<D, K>(
+        &self,
+        database: D,
+        key_property: K,
+        key_value: PropertyCondition,
+        properties: Properties,
+        on_duplicate: OnDuplicate,
+    ) -> Result<Upserted>
+    where
+        D: AsIdentifier<DatabaseId>,
+        K: Into<String>,
+    {
+        let property = key_property.into();
+        let query = DatabaseQuery {
+            filter: Some(FilterCondition::Property {
+                property: property.clone(),
+                condition: key_value,
+            }),
+            ..Default::default()
+        };
+
+        let mut matches = self
+            .query_database_stream(database.as_id(), query)
+            .collect_all()
+            .await?
+            .into_iter()
+            .map(|page| page.id);
+
+        let existing = match matches.next() {
+            Some(existing) => existing,
+            None => {
+                let page = self
+                    .create_page(PageCreateRequest::new(
+                        Parent::Database {
+                            database_id: database.as_id().clone(),
+                        },
+                        properties,
+                    ))
+                    .await?;
+
+                return Ok(Upserted {
+                    page,
+                    created: true,
+                    duplicates: vec![],
+                });
+            }
+        };
+
+        let duplicates: Vec<PageId> = matches.collect();
+        if on_duplicate == OnDuplicate::Fail && !duplicates.is_empty() {
+            let mut pages = vec![existing];
+            pages.extend(duplicates);
+            return Err(Error::DuplicatePages { property, pages });
+        }
+
+        let page = self.update_page(existing, properties).await?;
+
+        Ok(Upserted {
+            page,
+            created: false,
+            duplicates,
+        })
+    }
+
     /// Query a database and return the matching pages.
+    #[cfg(feature = "databases")]
     pub async fn query_database<D, T>(
         &self,
         database: D,
@@ -145,14 +842,19 @@ impl NotionApi {
         T: Into<DatabaseQuery>,
         D: AsIdentifier<DatabaseId>,
     {
-        let query = serde_json::to_string(&query.into()).unwrap();
-
+        let query = query.into();
+        query
+            .validate()
+            .map_err(|source| Error::InvalidQuery { source })?;
         let uri = format!(
-            "https://api.notion.com/v1/databases/{database_id}/query",
-            database_id = database.as_id()
+            "{base_url}/databases/{database_id}/query{filter}",
+            base_url = self.base_url,
+            database_id = database.as_id(),
+            filter = filter_properties(&query.filter_properties)
         );
+        let query = serde_json::to_vec(&query).unwrap();
 
-        let result = self.client.post_json(uri, query.as_bytes()).await?;
+        let result = self.client.post_json(uri, query).await?;
 
         match result {
             Object::List { list } => Ok(list.expect_pages()?),
@@ -160,12 +862,156 @@ impl NotionApi {
         }
     }
 
+    /// Query every page of a database matching `query` and return them as
+    /// [Columns], ready to be loaded into a dataframe.
+    #[cfg(feature = "databases")]
+    pub async fn query_to_columns<D, T>(
+        &self,
+        database: D,
+        query: T,
+    ) -> Result<Columns>
+    where
+        T: Into<DatabaseQuery>,
+        D: AsIdentifier<DatabaseId>,
+    {
+        let pages = self.query_all_pages(database, query).await?;
+        Ok(Columns::from_pages(&pages))
+    }
+
+    /// Query every page of a database matching `query` and return them as a polars
+    /// DataFrame, see [Columns::to_dataframe()].
+    #[cfg(all(feature = "databases", feature = "polars", not(target_os = "wasi")))]
+    pub async fn query_to_dataframe<D, T>(
+        &self,
+        database: D,
+        query: T,
+    ) -> Result<polars::prelude::DataFrame>
+    where
+        T: Into<DatabaseQuery>,
+        D: AsIdentifier<DatabaseId>,
+    {
+        let columns = self.query_to_columns(database, query).await?;
+        columns
+            .to_dataframe()
+            .map_err(|source| Error::DataFrame { source })
+    }
+
+    /// Query every page of a database matching `query` and write them as CSV,
+    /// see [csv::to_csv()].
+    #[cfg(feature = "databases")]
+    pub async fn query_to_csv<D, T>(
+        &self,
+        database: D,
+        query: T,
+        options: &CsvOptions,
+    ) -> Result<String>
+    where
+        T: Into<DatabaseQuery>,
+        D: AsIdentifier<DatabaseId>,
+    {
+        let pages = self.query_all_pages(database, query).await?;
+        Ok(csv::to_csv(&pages, options))
+    }
+
+    /// Query every page of a database matching `query` and write the ones with a date
+    /// as an iCalendar feed, see [ical::to_ical()].
+    #[cfg(feature = "databases")]
+    pub async fn query_to_ical<D, T>(
+        &self,
+        database: D,
+        query: T,
+        options: &IcalOptions,
+    ) -> Result<String>
+    where
+        T: Into<DatabaseQuery>,
+        D: AsIdentifier<DatabaseId>,
+    {
+        let pages = self.query_all_pages(database, query).await?;
+        Ok(ical::to_ical(&pages, options))
+    }
+
+    /// Aggregates `property` over every page matching `query`,
+    /// fetching all pages of results but keeping one page of them at a time.
+    #[cfg(feature = "databases")]
+    pub async fn aggregate_query<D, T>(
+        &self,
+        database: D,
+        query: T,
+        property: &str,
+    ) -> Result<Aggregate>
+    where
+        T: Into<DatabaseQuery>,
+        D: AsIdentifier<DatabaseId>,
+    {
+        let database = database.as_id();
+        let mut query = query.into();
+        let mut aggregate = Aggregate::new(property);
+
+        loop {
+            let list = self.query_database(database, query.clone()).await?;
+            list.results.iter().for_each(|page| aggregate.add(page));
+            match list.next_cursor {
+                Some(next) if list.has_more => query = query.start_from(Some(next)),
+                _ => break,
+            }
+        }
+
+        Ok(aggregate)
+    }
+
+    /// The archived and trashed pages of a database, for restoring them with
+    /// [update_page()](Self::update_page()).
+    ///
+    /// Relies on [DatabaseQuery::in_trash], so with API versions that don't
+    /// support it only archived pages are found.
+    #[cfg(feature = "databases")]
+    pub async fn list_trashed_pages<D: AsIdentifier<DatabaseId>>(
+        &self,
+        database: D,
+    ) -> Result<Vec<Page>> {
+        let query = DatabaseQuery::builder().include_trashed().build();
+        let mut pages = self.query_all_pages(database, query).await?;
+        pages.retain(Trashable::is_trashed);
+        Ok(pages)
+    }
+
+    #[cfg(feature = "databases")]
+    async fn query_all_pages<D, T>(
+        &self,
+        database: D,
+        query: T,
+    ) -> Result<Vec<Page>>
+    where
+        T: Into<DatabaseQuery>,
+        D: AsIdentifier<DatabaseId>,
+    {
+        self.query_database_stream(database, query)
+            .collect_all()
+            .await
+    }
+
+    /// Get a block by [BlockId].
+    #[cfg(feature = "blocks")]
+    pub async fn get_block<T: AsIdentifier<BlockId>>(
+        &self,
+        block_id: T,
+    ) -> Result<Block> {
+        let uri = format!("{}/blocks/{}", self.base_url, block_id.as_id());
+
+        match self.client.get(uri).await? {
+            Object::Block { block } => Ok(block),
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
+
+    #[cfg(feature = "blocks")]
     pub async fn get_block_children<T: AsIdentifier<BlockId>>(
         &self,
         block_id: T,
     ) -> Result<ListResponse<Block>> {
         let uri = format!(
-            "https://api.notion.com/v1/blocks/{block_id}/children",
+            "{base_url}/blocks/{block_id}/children",
+            base_url = self.base_url,
             block_id = block_id.as_id()
         );
 
@@ -176,4 +1022,132 @@ impl NotionApi {
             response => Err(Error::UnexpectedResponse { response }),
         }
     }
+
+    /// Get every child of a block, following `next_cursor` until all
+    /// pages of results have been fetched.
+    #[cfg(feature = "blocks")]
+    pub async fn get_all_block_children<T: AsIdentifier<BlockId>>(
+        &self,
+        block_id: T,
+    ) -> Result<Vec<Block>> {
+        self.block_children_stream(block_id).collect_all().await
+    }
+
+    /// Get every child of many blocks, such as the pages returned by a query,
+    /// with `runner`, like [get_pages_with()](Self::get_pages_with()).
+    /// Results are returned in the order of `block_ids`.
+    #[cfg(feature = "blocks")]
+    pub async fn get_many_block_children<I, T>(
+        &self,
+        block_ids: I,
+        runner: &JobRunner<'_>,
+    ) -> Vec<Result<Vec<Block>>>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsIdentifier<BlockId>,
+    {
+        let block_ids = block_ids.into_iter().map(|id| id.as_id().clone());
+        runner
+            .run_in_order(self, block_ids, |api, block_id| {
+                api.get_all_block_children(block_id)
+            })
+            .await
+    }
+
+    /// Checks or unchecks a to-do block and returns the updated block.
+    ///
+    /// Only `checked` is sent, so the text of the to-do is left as it is.
+    #[cfg(feature = "blocks")]
+    pub async fn set_todo_checked<B: AsIdentifier<BlockId>>(
+        &self,
+        block_id: B,
+        checked: bool,
+    ) -> Result<Block> {
+        let update = serde_json::json!({ "to_do": { "checked": checked } });
+
+        let uri = format!(
+            "{base_url}/blocks/{block_id}",
+            base_url = self.base_url,
+            block_id = block_id.as_id()
+        );
+
+        let result = self
+            .client
+            .patch_json(uri, serde_json::to_vec(&update).unwrap())
+            .await?;
+
+        match result {
+            Object::Block { block } => Ok(block),
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
+
+    /// Changes the content of a block, or archives it, and returns the updated block.
+    #[cfg(feature = "blocks")]
+    pub async fn update_block<B, T>(
+        &self,
+        block_id: B,
+        update: T,
+    ) -> Result<Block>
+    where
+        B: AsIdentifier<BlockId>,
+        T: Into<BlockUpdateRequest>,
+    {
+        let uri = format!(
+            "{base_url}/blocks/{block_id}",
+            base_url = self.base_url,
+            block_id = block_id.as_id()
+        );
+        let update = serde_json::to_vec(&update.into()).unwrap();
+
+        match self.client.patch_json(uri, update).await? {
+            Object::Block { block } => Ok(block),
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
+
+    /// Moves a block to the trash and returns it.
+    #[cfg(feature = "blocks")]
+    pub async fn delete_block<B: AsIdentifier<BlockId>>(
+        &self,
+        block_id: B,
+    ) -> Result<Block> {
+        let uri = format!(
+            "{base_url}/blocks/{block_id}",
+            base_url = self.base_url,
+            block_id = block_id.as_id()
+        );
+
+        match self.client.delete(uri).await? {
+            Object::Block { block } => Ok(block),
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
+
+    /// Appends blocks to the children of a page or block and returns the created blocks.
+    ///
+    /// Blocks go at the end unless the request names a sibling with
+    /// [BlockAppendRequest::after()].
+    #[cfg(feature = "blocks")]
+    pub async fn append_block_children<B, T>(
+        &self,
+        block_id: B,
+        request: T,
+    ) -> Result<ListResponse<Block>>
+    where
+        B: AsIdentifier<BlockId>,
+        T: Into<BlockAppendRequest>,
+    {
+        let uri = format!(
+            "{base_url}/blocks/{block_id}/children",
+            base_url = self.base_url,
+            block_id = block_id.as_id()
+        );
+        let body = serde_json::to_vec(&request.into()).unwrap();
+
+        match self.client.patch_json(uri, body).await? {
+            Object::List { list } => Ok(list.expect_blocks()?),
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
 }
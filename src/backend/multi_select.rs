@@ -0,0 +1,155 @@
+use serde_json::{json, Value};
+
+use crate::ids::{AsIdentifier, PageId};
+use crate::models::properties::{Color, SelectedValue};
+use crate::models::{Object, Page};
+use crate::{Error, NotionApi, Result, TClient};
+
+impl<C: TClient> NotionApi<C> {
+    /// Adds `options` to the multi-select property `property` of a page,
+    /// keeping the options that are already selected.
+    ///
+    /// Options are matched by name, names that are already selected are skipped.
+    /// Unknown names are created by Notion.
+    /// Returns the page without writing if nothing changes.
+    pub async fn add_multi_select_options<P: AsIdentifier<PageId>>(
+        &self,
+        page_id: P,
+        property: &str,
+        options: &[&str],
+    ) -> Result<Page> {
+        let page = self.get_page(page_id).await?;
+        let mut selected = page.multi_select(property).unwrap_or_default().to_vec();
+
+        let before = selected.len();
+        for option in options {
+            if !selected.iter().any(|value| has_name(value, option)) {
+                selected.push(SelectedValue {
+                    id: None,
+                    name: Some(option.to_string()),
+                    color: Color::Default,
+                });
+            }
+        }
+        if selected.len() == before {
+            return Ok(page);
+        }
+
+        self.write_multi_select(&page, property, &selected).await
+    }
+
+    /// Removes `options` from the multi-select property `property` of a page,
+    /// keeping all other selected options.
+    ///
+    /// Options are matched by name.
+    /// Returns the page without writing if none of them are selected.
+    pub async fn remove_multi_select_options<P: AsIdentifier<PageId>>(
+        &self,
+        page_id: P,
+        property: &str,
+        options: &[&str],
+    ) -> Result<Page> {
+        let page = self.get_page(page_id).await?;
+        let mut selected = page.multi_select(property).unwrap_or_default().to_vec();
+
+        let before = selected.len();
+        selected.retain(|value| !options.iter().any(|option| has_name(value, option)));
+        if selected.len() == before {
+            return Ok(page);
+        }
+
+        self.write_multi_select(&page, property, &selected).await
+    }
+
+    async fn write_multi_select(
+        &self,
+        page: &Page,
+        property: &str,
+        selected: &[SelectedValue],
+    ) -> Result<Page> {
+        let update = json!({
+            "properties": {
+                property: { "multi_select": selected.iter().map(reference).collect::<Vec<_>>() }
+            }
+        });
+
+        let uri = format!("{}/pages/{}", self.base_url, page.id);
+        let result = self
+            .client
+            .patch_json(uri, serde_json::to_vec(&update).unwrap())
+            .await?;
+
+        match result {
+            Object::Page { page } => Ok(page),
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
+}
+
+fn has_name(
+    value: &SelectedValue,
+    name: &str,
+) -> bool {
+    value.name.as_deref() == Some(name)
+}
+
+/// Existing options are referenced by id, so their name and color are left alone.
+fn reference(value: &SelectedValue) -> Value {
+    match (&value.id, &value.name) {
+        (Some(id), _) => json!({ "id": id }),
+        (None, name) => json!({ "name": name }),
+    }
+}
+
+#[cfg(all(test, not(target_os = "wasi")))]
+mod tests {
+    use crate::models::Object;
+    use crate::test_util::{MockNotion, PageFixture};
+    use serde_json::json;
+    use wiremock::matchers::{body_json, method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    #[tokio::test]
+    async fn multi_select_options() {
+        let mock = MockNotion::start().await;
+        let page = PageFixture::new().multi_select("Tags", &["a", "b"]).build();
+        mock.mount_page(&page).await;
+        let patch = |tags: serde_json::Value| {
+            Mock::given(method("PATCH"))
+                .and(path(format!("/v1/pages/{}", page.id)))
+                .and(body_json(
+                    json!({"properties": {"Tags": {"multi_select": tags}}}),
+                ))
+                .respond_with(
+                    ResponseTemplate::new(200).set_body_json(Object::Page { page: page.clone() }),
+                )
+                .expect(1)
+        };
+        patch(json!([{"name": "a"}, {"name": "b"}, {"name": "c"}]))
+            .mount(mock.server())
+            .await;
+        patch(json!([{"name": "b"}])).mount(mock.server()).await;
+
+        let api = mock.api();
+        api.add_multi_select_options(&page.id, "Tags", &["b", "c"])
+            .await
+            .unwrap();
+        api.remove_multi_select_options(&page.id, "Tags", &["a", "x"])
+            .await
+            .unwrap();
+
+        let unchanged = api
+            .add_multi_select_options(&page.id, "Tags", &["a"])
+            .await
+            .unwrap();
+        assert_eq!(unchanged, page);
+        assert_eq!(
+            page.multi_select("Tags")
+                .unwrap()
+                .iter()
+                .map(|value| value.name.as_deref().unwrap())
+                .collect::<Vec<_>>(),
+            vec!["a", "b"]
+        );
+    }
+}
@@ -0,0 +1,731 @@
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::stream::{Stream, TryCollect, TryStreamExt};
+
+#[cfg(feature = "databases")]
+use crate::ids::DatabaseId;
+#[cfg(any(feature = "blocks", feature = "comments"))]
+use crate::ids::{AsIdentifier, BlockId};
+#[cfg(feature = "blocks")]
+use crate::models::block::Block;
+#[cfg(feature = "comments")]
+use crate::models::comments::Comment;
+#[cfg(feature = "databases")]
+use crate::models::paging::Paging;
+use crate::models::paging::{Pageable, PagingCursor};
+#[cfg(feature = "databases")]
+use crate::models::search::DatabaseQuery;
+use crate::models::search::SearchRequest;
+#[cfg(feature = "databases")]
+use crate::models::Database;
+use crate::models::Page;
+use crate::models::{ListResponse, Object};
+#[cfg(any(feature = "blocks", feature = "databases"))]
+use crate::Error;
+use crate::{NotionApi, Result, TClient};
+
+/// The most results Notion returns per request.
+pub(crate) const MAX_PAGE_SIZE: u8 = 100;
+
+/// Controls how paginated streams such as [NotionApi::search_stream_with()](crate::NotionApi::search_stream_with())
+/// request their pages.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct PaginationOptions {
+    /// Results per request, at most 100.
+    pub page_size: u8,
+    /// Request the next page as soon as the current one arrived,
+    /// so it downloads while the current one is processed.
+    ///
+    /// There is never more than one request in flight, but one more page
+    /// is held in memory and it is fetched even if the stream is dropped early.
+    pub prefetch: bool,
+}
+
+impl Default for PaginationOptions {
+    fn default() -> Self {
+        PaginationOptions {
+            page_size: MAX_PAGE_SIZE,
+            prefetch: false,
+        }
+    }
+}
+
+/// Streams the results of a paginated endpoint, calling `fetch` with the
+/// cursor of each page.
+///
+/// Without [PaginationOptions::prefetch] a page is only requested once the
+/// results of the previous one have been consumed, so no more than one page
+/// is held in memory however slowly the stream is read.
+/// The stream ends after the first error.
+pub(crate) fn paginate<T, F, Fut>(
+    prefetch: bool,
+    fetch: F,
+) -> impl Stream<Item = Result<T>>
+where
+    T: Unpin,
+    F: FnMut(Option<PagingCursor>) -> Fut + Unpin,
+    Fut: Future<Output = Result<ListResponse<T>>>,
+{
+    Paginated {
+        fetch,
+        prefetch,
+        buffer: VecDeque::new(),
+        next: Some(None),
+        pending: None,
+        fetched: None,
+    }
+}
+
+/// Keeps `page_size` within what Notion accepts.
+pub(crate) fn clamp_page_size(page_size: u8) -> u8 {
+    page_size.clamp(1, MAX_PAGE_SIZE)
+}
+
+struct Paginated<T, F, Fut> {
+    fetch: F,
+    prefetch: bool,
+    /// Results of the current page that have not been returned yet.
+    buffer: VecDeque<T>,
+    /// The cursor of the page to request next, `None` after the last page.
+    next: Option<Option<PagingCursor>>,
+    /// The request for the next page, once it was sent.
+    pending: Option<Pin<Box<Fut>>>,
+    /// The response to `pending`, kept until `buffer` is drained.
+    fetched: Option<Result<ListResponse<T>>>,
+}
+
+impl<T, F, Fut> Paginated<T, F, Fut>
+where
+    F: FnMut(Option<PagingCursor>) -> Fut,
+{
+    fn request_next(&mut self) {
+        if let Some(cursor) = self.next.take() {
+            self.pending = Some(Box::pin((self.fetch)(cursor)));
+        }
+    }
+}
+
+impl<T, F, Fut> Stream for Paginated<T, F, Fut>
+where
+    T: Unpin,
+    F: FnMut(Option<PagingCursor>) -> Fut + Unpin,
+    Fut: Future<Output = Result<ListResponse<T>>>,
+{
+    type Item = Result<T>;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if let Some(pending) = this.pending.as_mut() {
+                if let Poll::Ready(result) = pending.as_mut().poll(cx) {
+                    this.pending = None;
+                    this.fetched = Some(result);
+                }
+            }
+
+            if let Some(result) = this.buffer.pop_front() {
+                return Poll::Ready(Some(Ok(result)));
+            }
+
+            match this.fetched.take() {
+                Some(Ok(list)) => {
+                    this.next = match list.next_cursor {
+                        Some(next) if list.has_more => Some(Some(next)),
+                        _ => None,
+                    };
+                    this.buffer = list.results.into();
+                    if this.prefetch {
+                        this.request_next();
+                    }
+                    continue;
+                }
+                Some(Err(error)) => {
+                    this.next = None;
+                    return Poll::Ready(Some(Err(error)));
+                }
+                None => {}
+            }
+
+            if this.pending.is_some() {
+                return Poll::Pending;
+            }
+            if this.next.is_none() {
+                return Poll::Ready(None);
+            }
+            this.request_next();
+        }
+    }
+}
+
+/// Iterates over the results of a paginated endpoint, calling a blocking
+/// `fetch` with the cursor of each page.
+///
+/// The synchronous counterpart of the paginated streams: a page is only
+/// requested once the results of the previous one have been consumed,
+/// and the iterator ends after the first error.
+///
+/// ```
+/// use notion_wasi::models::ListResponse;
+/// use notion_wasi::PageIter;
+///
+/// let numbers = PageIter::new(|cursor| {
+///     Ok(ListResponse {
+///         results: if cursor.is_none() { vec![1, 2] } else { vec![3] },
+///         next_cursor: Some(serde_json::from_str(r#""next""#).unwrap()),
+///         has_more: cursor.is_none(),
+///     })
+/// });
+/// assert_eq!(numbers.collect::<Result<Vec<u32>, _>>().unwrap(), vec![1, 2, 3]);
+/// ```
+pub struct PageIter<T, F> {
+    fetch: F,
+    /// Results of the current page that have not been returned yet.
+    buffer: VecDeque<T>,
+    /// The cursor of the page to request next, `None` after the last page.
+    next: Option<Option<PagingCursor>>,
+}
+
+impl<T, F> PageIter<T, F>
+where
+    F: FnMut(Option<PagingCursor>) -> Result<ListResponse<T>>,
+{
+    pub fn new(fetch: F) -> Self {
+        PageIter {
+            fetch,
+            buffer: VecDeque::new(),
+            next: Some(None),
+        }
+    }
+}
+
+impl<T, F> Iterator for PageIter<T, F>
+where
+    F: FnMut(Option<PagingCursor>) -> Result<ListResponse<T>>,
+{
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(result) = self.buffer.pop_front() {
+                return Some(Ok(result));
+            }
+            let cursor = self.next.take()?;
+            match (self.fetch)(cursor) {
+                Ok(list) => {
+                    self.next = match list.next_cursor {
+                        Some(next) if list.has_more => Some(Some(next)),
+                        _ => None,
+                    };
+                    self.buffer = list.results.into();
+                }
+                Err(error) => return Some(Err(error)),
+            }
+        }
+    }
+}
+
+/// Reads every result of a paginated stream into a [Vec], stopping at the first error.
+///
+/// ```no_run
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// use notion_wasi::models::search::NotionSearch;
+/// use notion_wasi::{CollectAll, NotionApi};
+///
+/// let api = NotionApi::new(std::env::var("NOTION_API_TOKEN")?)?;
+/// let results = api
+///     .search_stream(NotionSearch::Query("Roadmap".to_string()))
+///     .collect_all()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait CollectAll<T>: Stream<Item = Result<T>> + Sized {
+    fn collect_all(self) -> TryCollect<Self, Vec<T>> {
+        self.try_collect()
+    }
+}
+
+impl<T, S: Stream<Item = Result<T>>> CollectAll<T> for S {}
+
+impl<C: TClient> NotionApi<C> {
+    /// Stream every page of a database matching `query`, following `next_cursor`.
+    ///
+    /// The page size of `query` is kept, and the next page of results is only
+    /// requested once the current one has been read.
+    #[cfg(feature = "databases")]
+    pub fn query_database_stream<'a, D, T>(
+        &'a self,
+        database: D,
+        query: T,
+    ) -> impl Stream<Item = Result<Page>> + 'a
+    where
+        T: Into<DatabaseQuery>,
+        D: AsIdentifier<DatabaseId>,
+    {
+        self.paginate_query(database.as_id().clone(), query.into(), false)
+    }
+
+    /// Like [query_database_stream()](Self::query_database_stream()),
+    /// with the page size and prefetching set by `options`.
+    /// The page size of `options` replaces the one of `query`.
+    #[cfg(feature = "databases")]
+    pub fn query_database_stream_with<'a, D, T>(
+        &'a self,
+        database: D,
+        query: T,
+        options: PaginationOptions,
+    ) -> impl Stream<Item = Result<Page>> + 'a
+    where
+        T: Into<DatabaseQuery>,
+        D: AsIdentifier<DatabaseId>,
+    {
+        let mut query = query.into();
+        query.paging.get_or_insert_with(Paging::default).page_size =
+            Some(clamp_page_size(options.page_size));
+        self.paginate_query(database.as_id().clone(), query, options.prefetch)
+    }
+
+    #[cfg(feature = "databases")]
+    fn paginate_query(
+        &self,
+        database: DatabaseId,
+        query: DatabaseQuery,
+        prefetch: bool,
+    ) -> impl Stream<Item = Result<Page>> + '_ {
+        paginate(prefetch, move |cursor| {
+            let query = query.clone().start_from(cursor);
+            let database = database.clone();
+            async move { self.query_database(&database, query).await }
+        })
+    }
+
+    /// Stream every result of a search, following `next_cursor`.
+    pub fn search_stream<'a, T: Into<SearchRequest>>(
+        &'a self,
+        query: T,
+    ) -> impl Stream<Item = Result<Object>> + 'a {
+        self.paginate_search(query.into(), false)
+    }
+
+    /// Like [search_stream()](Self::search_stream()), with the page size and
+    /// prefetching set by `options`. The page size of `options` replaces the one of `query`.
+    pub fn search_stream_with<'a, T: Into<SearchRequest>>(
+        &'a self,
+        query: T,
+        options: PaginationOptions,
+    ) -> impl Stream<Item = Result<Object>> + 'a {
+        let query = query.into().page_size(clamp_page_size(options.page_size));
+        self.paginate_search(query, options.prefetch)
+    }
+
+    fn paginate_search(
+        &self,
+        query: SearchRequest,
+        prefetch: bool,
+    ) -> impl Stream<Item = Result<Object>> + '_ {
+        paginate(prefetch, move |cursor| {
+            let query = query.clone().start_from(cursor);
+            async move { self.search(query).await }
+        })
+    }
+
+    /// Stream every page a search finds, see [search_pages()](Self::search_pages()).
+    pub fn search_pages_stream<'a, T: Into<SearchRequest>>(
+        &'a self,
+        query: T,
+    ) -> impl Stream<Item = Result<Page>> + 'a {
+        let query = query.into();
+
+        paginate(false, move |cursor| {
+            let query = query.clone().start_from(cursor);
+            async move { self.search_pages(query).await }
+        })
+    }
+
+    /// Stream every database a search finds, see [search_databases()](Self::search_databases()).
+    #[cfg(feature = "databases")]
+    pub fn search_databases_stream<'a, T: Into<SearchRequest>>(
+        &'a self,
+        query: T,
+    ) -> impl Stream<Item = Result<Database>> + 'a {
+        let query = query.into();
+
+        paginate(false, move |cursor| {
+            let query = query.clone().start_from(cursor);
+            async move { self.search_databases(query).await }
+        })
+    }
+
+    /// Stream every child of a block, 100 per request, following `next_cursor`.
+    #[cfg(feature = "blocks")]
+    pub fn block_children_stream<'a, B: AsIdentifier<BlockId>>(
+        &'a self,
+        block_id: B,
+    ) -> impl Stream<Item = Result<Block>> + 'a {
+        self.block_children_stream_with(block_id, PaginationOptions::default())
+    }
+
+    /// Like [block_children_stream()](Self::block_children_stream()),
+    /// with the page size and prefetching set by `options`.
+    #[cfg(feature = "blocks")]
+    pub fn block_children_stream_with<'a, B: AsIdentifier<BlockId>>(
+        &'a self,
+        block_id: B,
+        options: PaginationOptions,
+    ) -> impl Stream<Item = Result<Block>> + 'a {
+        let block_id = block_id.as_id().clone();
+        let page_size = clamp_page_size(options.page_size);
+
+        paginate(options.prefetch, move |cursor| {
+            let block_id = block_id.clone();
+            async move { self.block_children_page(&block_id, cursor, page_size).await }
+        })
+    }
+
+    /// One page of the children of a block, starting at `cursor`.
+    #[cfg(feature = "blocks")]
+    pub(crate) async fn block_children_page(
+        &self,
+        block_id: &BlockId,
+        cursor: Option<PagingCursor>,
+        page_size: u8,
+    ) -> Result<ListResponse<Block>> {
+        let mut uri = format!(
+            "{base_url}/blocks/{block_id}/children?page_size={page_size}",
+            base_url = self.base_url,
+            block_id = block_id,
+            page_size = page_size
+        );
+        if let Some(cursor) = &cursor {
+            uri.push_str("&start_cursor=");
+            uri.push_str(cursor.value());
+        }
+
+        match self.client.get(uri).await? {
+            Object::List { list } => list.expect_blocks(),
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
+
+    /// Stream every unresolved comment on a page or block, see [list_comments()](Self::list_comments()).
+    #[cfg(feature = "comments")]
+    pub fn comments_stream<'a, B: AsIdentifier<BlockId>>(
+        &'a self,
+        block_id: B,
+    ) -> impl Stream<Item = Result<Comment>> + 'a {
+        let block_id = block_id.as_id().clone();
+
+        paginate(false, move |cursor| {
+            let block_id = block_id.clone();
+            async move { self.list_comments(block_id, cursor).await }
+        })
+    }
+
+    /// Stream every database shared with the integration, see [list_databases()](Self::list_databases()).
+    #[cfg(feature = "databases")]
+    pub fn list_databases_stream(&self) -> impl Stream<Item = Result<Database>> + '_ {
+        self.list_databases_stream_with(PaginationOptions::default())
+    }
+
+    /// Like [list_databases_stream()](Self::list_databases_stream()),
+    /// with the page size and prefetching set by `options`.
+    #[cfg(feature = "databases")]
+    pub fn list_databases_stream_with(
+        &self,
+        options: PaginationOptions,
+    ) -> impl Stream<Item = Result<Database>> + '_ {
+        let page_size = clamp_page_size(options.page_size);
+
+        paginate(options.prefetch, move |cursor| {
+            let mut uri = format!(
+                "{base_url}/databases?page_size={page_size}",
+                base_url = self.base_url,
+                page_size = page_size
+            );
+            if let Some(cursor) = &cursor {
+                uri.push_str("&start_cursor=");
+                uri.push_str(cursor.value());
+            }
+            async move {
+                match self.client.get(uri).await? {
+                    Object::List { list } => list.expect_databases(),
+                    response => Err(Error::UnexpectedResponse { response }),
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{paginate, PageIter};
+    #[cfg(feature = "blocks")]
+    use crate::ids::BlockId;
+    #[cfg(feature = "databases")]
+    use crate::ids::DatabaseId;
+    #[cfg(feature = "databases")]
+    use crate::models::search::DatabaseQuery;
+    use crate::models::search::NotionSearch;
+    use crate::models::{ListResponse, Object};
+    #[cfg(feature = "blocks")]
+    use crate::test_util::BlockFixture;
+    #[cfg(feature = "databases")]
+    use crate::test_util::DatabaseFixture;
+    use crate::test_util::{MockClient, MockRequest, PageFixture};
+    use crate::PaginationOptions;
+    use futures::{StreamExt, TryStreamExt};
+    use std::cell::Cell;
+    #[cfg(any(feature = "blocks", feature = "databases"))]
+    use std::str::FromStr;
+
+    /// Pages `[0, 1]` and `[2, 3]`, counting the requests.
+    fn pages(
+        prefetch: bool,
+        requests: &Cell<usize>,
+    ) -> impl futures::Stream<Item = crate::Result<u32>> + '_ {
+        paginate(prefetch, move |cursor| {
+            requests.set(requests.get() + 1);
+            let first = cursor.is_none();
+            async move {
+                Ok(ListResponse {
+                    results: if first { vec![0, 1] } else { vec![2, 3] },
+                    next_cursor: Some(serde_json::from_str(r#""next""#).unwrap()),
+                    has_more: first,
+                })
+            }
+        })
+    }
+
+    #[test]
+    fn fetches_lazily() {
+        let requests = Cell::new(0);
+        let mut stream = pages(false, &requests);
+
+        futures::executor::block_on(async {
+            assert_eq!(stream.next().await.unwrap().unwrap(), 0);
+            assert_eq!(stream.next().await.unwrap().unwrap(), 1);
+            assert_eq!(requests.get(), 1);
+            assert_eq!(stream.next().await.unwrap().unwrap(), 2);
+            assert_eq!(requests.get(), 2);
+        });
+    }
+
+    #[test]
+    fn prefetches_next_page() {
+        let requests = Cell::new(0);
+        let mut stream = pages(true, &requests);
+
+        futures::executor::block_on(async {
+            assert_eq!(stream.next().await.unwrap().unwrap(), 0);
+            assert_eq!(requests.get(), 2);
+            let rest: Vec<u32> = stream.try_collect().await.unwrap();
+            assert_eq!(rest, vec![1, 2, 3]);
+            assert_eq!(requests.get(), 2);
+        });
+    }
+
+    #[test]
+    #[allow(clippy::result_large_err)]
+    fn iterates_lazily() {
+        let requests = Cell::new(0);
+        let mut iter = PageIter::new(|cursor| {
+            requests.set(requests.get() + 1);
+            match cursor {
+                None => Ok(ListResponse {
+                    results: vec![0, 1],
+                    next_cursor: Some(serde_json::from_str(r#""next""#).unwrap()),
+                    has_more: true,
+                }),
+                Some(_) => Err(std::io::Error::other("offline").into()),
+            }
+        });
+
+        assert_eq!(iter.next().unwrap().unwrap(), 0);
+        assert_eq!(iter.next().unwrap().unwrap(), 1);
+        assert_eq!(requests.get(), 1);
+        assert!(iter.next().unwrap().is_err());
+        assert!(iter.next().is_none());
+        assert_eq!(requests.get(), 2);
+    }
+
+    /// Mounts two pages of `first` and `second` answering `method` requests to `path`.
+    fn mount_two_pages(
+        client: &MockClient,
+        method: &str,
+        path: &str,
+        first: Object,
+        second: Object,
+    ) {
+        let page = |results, has_more| Object::List {
+            list: ListResponse {
+                results,
+                next_cursor: Some(serde_json::from_str(r#""next""#).unwrap()),
+                has_more,
+            },
+        };
+        client.mount(method, path, page(vec![first], true));
+        client.mount(method, path, page(vec![second], false));
+    }
+
+    /// Reads the first result of `stream` and returns the requests sent by then.
+    async fn requests_after_first<S, T>(
+        client: &MockClient,
+        stream: S,
+    ) -> Vec<MockRequest>
+    where
+        S: futures::Stream<Item = crate::Result<T>>,
+    {
+        futures::pin_mut!(stream);
+        stream.next().await.unwrap().unwrap();
+        client.requests()
+    }
+
+    fn options() -> PaginationOptions {
+        PaginationOptions {
+            page_size: 1,
+            prefetch: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn search_stream_with_options() {
+        let api = MockClient::default().api();
+        let page = |title| Object::Page {
+            page: PageFixture::new().title(title).build(),
+        };
+        mount_two_pages(
+            api.client(),
+            "POST",
+            "/search",
+            page("First"),
+            page("Second"),
+        );
+
+        let stream = api.search_stream_with(NotionSearch::Query("".to_string()), options());
+        let requests = requests_after_first(api.client(), stream).await;
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].body.as_ref().unwrap()["page_size"], 1);
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "databases")]
+    async fn query_database_stream_with_options() {
+        let api = MockClient::default().api();
+        let page = |title| Object::Page {
+            page: PageFixture::new().database("tasks").title(title).build(),
+        };
+        let route = "/databases/tasks/query";
+        mount_two_pages(api.client(), "POST", route, page("First"), page("Second"));
+
+        let database_id = DatabaseId::from_str("tasks").unwrap();
+        let stream =
+            api.query_database_stream_with(&database_id, DatabaseQuery::default(), options());
+        let requests = requests_after_first(api.client(), stream).await;
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].body.as_ref().unwrap()["page_size"], 1);
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "blocks")]
+    async fn block_children_stream_with_options() {
+        let api = MockClient::default().api();
+        let block = |text| Object::Block {
+            block: BlockFixture::new().paragraph(text),
+        };
+        let route = "/blocks/parent/children";
+        mount_two_pages(api.client(), "GET", route, block("First"), block("Second"));
+
+        let block_id = BlockId::from_str("parent").unwrap();
+        let stream = api.block_children_stream_with(&block_id, options());
+        let requests = requests_after_first(api.client(), stream).await;
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].path, "/blocks/parent/children?page_size=1");
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "databases")]
+    async fn list_databases_stream_with_options() {
+        let api = MockClient::default().api();
+        let database = || Object::Database {
+            database: DatabaseFixture::new().build(),
+        };
+        mount_two_pages(api.client(), "GET", "/databases", database(), database());
+
+        let stream = api.list_databases_stream_with(options());
+        let requests = requests_after_first(api.client(), stream).await;
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].path, "/databases?page_size=1");
+    }
+
+    #[cfg(feature = "databases")]
+    mod query_database_stream {
+        use crate::ids::DatabaseId;
+        use crate::models::paging::Paging;
+        use crate::models::search::DatabaseQuery;
+        use crate::models::{ListResponse, Object, Page};
+        use crate::test_util::{MockNotion, PageFixture};
+        use crate::CollectAll;
+        use serde_json::json;
+        use std::str::FromStr;
+        use wiremock::matchers::{body_partial_json, path};
+        use wiremock::{Mock, ResponseTemplate};
+
+        #[tokio::test]
+        async fn follows_cursor() {
+            let mock = MockNotion::start().await;
+            let database_id = DatabaseId::from_str("tasks").unwrap();
+            let pages: Vec<Page> = (0..3)
+                .map(|_| PageFixture::new().database("tasks").build())
+                .collect();
+            let list = |pages: &[Page], next_cursor: Option<&str>| Object::List {
+                list: ListResponse {
+                    results: pages
+                        .iter()
+                        .map(|page| Object::Page { page: page.clone() })
+                        .collect(),
+                    next_cursor: next_cursor
+                        .map(|cursor| serde_json::from_value(cursor.into()).unwrap()),
+                    has_more: next_cursor.is_some(),
+                },
+            };
+            let route = "/v1/databases/tasks/query";
+            Mock::given(path(route))
+                .respond_with(
+                    ResponseTemplate::new(200).set_body_json(list(&pages[..2], Some("next"))),
+                )
+                .mount(mock.server())
+                .await;
+            Mock::given(path(route))
+                .and(body_partial_json(
+                    json!({"start_cursor": "next", "page_size": 2}),
+                ))
+                .respond_with(ResponseTemplate::new(200).set_body_json(list(&pages[2..], None)))
+                .with_priority(1)
+                .mount(mock.server())
+                .await;
+
+            let api = mock.api();
+            let query = DatabaseQuery {
+                paging: Some(Paging {
+                    start_cursor: None,
+                    page_size: Some(2),
+                }),
+                ..DatabaseQuery::default()
+            };
+            let streamed = api
+                .query_database_stream(&database_id, query)
+                .collect_all()
+                .await
+                .unwrap();
+            assert_eq!(streamed, pages);
+            assert_eq!(mock.server().received_requests().await.unwrap().len(), 2);
+        }
+    }
+}
@@ -0,0 +1,168 @@
+//! Archival PDFs of pages, enabled with the `pdf` feature.
+//!
+//! Pages are rendered with [render::print](crate::render::print) and converted
+//! by a headless browser or another HTML to PDF tool, which has to be installed:
+//!
+//! ```no_run
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! use notion_wasi::ids::PageId;
+//! use notion_wasi::{NotionApi, PdfConverter};
+//!
+//! let api = NotionApi::new(std::env::var("NOTION_API_TOKEN")?)?;
+//! let page_id: PageId = "b55c9c91-384d-452b-81db-d1ef79372b75".parse()?;
+//! api.export_pdf(&page_id, "contract.pdf", &PdfConverter::chromium())
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::path::Path;
+use std::process::Command;
+
+use crate::ids::{AsIdentifier, PageId};
+use crate::{Error, NotionApi, Result, TClient};
+
+/// Replaced by the path of the HTML file in the arguments of a [PdfConverter].
+pub const PDF_INPUT: &str = "{input}";
+
+/// Replaced by the path of the PDF file in the arguments of a [PdfConverter].
+pub const PDF_OUTPUT: &str = "{output}";
+
+/// A command that converts an HTML file to PDF.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct PdfConverter {
+    program: String,
+    args: Vec<String>,
+}
+
+/// [PdfConverter::chromium()].
+impl Default for PdfConverter {
+    fn default() -> Self {
+        Self::chromium()
+    }
+}
+
+impl PdfConverter {
+    /// Runs `program` with the arguments added by [arg()](Self::arg()).
+    pub fn new<S: Into<String>>(program: S) -> Self {
+        PdfConverter {
+            program: program.into(),
+            args: vec![],
+        }
+    }
+
+    /// Adds an argument, in which [PDF_INPUT] and [PDF_OUTPUT] are replaced by the file paths.
+    pub fn arg<S: Into<String>>(
+        mut self,
+        arg: S,
+    ) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Prints with headless Chromium, without the date and URL in the margins.
+    pub fn chromium() -> Self {
+        PdfConverter::new("chromium")
+            .arg("--headless")
+            .arg("--disable-gpu")
+            .arg("--no-pdf-header-footer")
+            .arg(format!("--print-to-pdf={}", PDF_OUTPUT))
+            .arg(PDF_INPUT)
+    }
+
+    pub fn wkhtmltopdf() -> Self {
+        PdfConverter::new("wkhtmltopdf")
+            .arg("--quiet")
+            .arg("--enable-local-file-access")
+            .arg(PDF_INPUT)
+            .arg(PDF_OUTPUT)
+    }
+
+    /// Converts `html` to the PDF file `output`.
+    ///
+    /// The HTML is written next to `output` while the command runs, and removed afterwards.
+    /// The command blocks the current thread.
+    #[allow(clippy::result_large_err)]
+    pub fn convert<P: AsRef<Path>>(
+        &self,
+        html: &str,
+        output: P,
+    ) -> Result<()> {
+        let output = output.as_ref();
+        let input = output.with_extension("print.html");
+        std::fs::write(&input, html)?;
+        let result = self.run(&input, output);
+        std::fs::remove_file(&input)?;
+        result
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn run(
+        &self,
+        input: &Path,
+        output: &Path,
+    ) -> Result<()> {
+        let (input, output) = (input.to_string_lossy(), output.to_string_lossy());
+        let args = self
+            .args
+            .iter()
+            .map(|arg| arg.replace(PDF_INPUT, &input).replace(PDF_OUTPUT, &output));
+        let result = Command::new(&self.program).args(args).output()?;
+        if !result.status.success() {
+            let stderr = String::from_utf8_lossy(&result.stderr);
+            return Err(Error::PdfConversionFailed {
+                reason: format!(
+                    "{} exited with {}: {}",
+                    self.program,
+                    result.status,
+                    stderr.trim()
+                ),
+            });
+        }
+        if !Path::new(output.as_ref()).exists() {
+            return Err(Error::PdfConversionFailed {
+                reason: format!("{} wrote no file", self.program),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl<C: TClient> NotionApi<C> {
+    /// Writes a page with all of its blocks to the PDF file `output`,
+    /// see [export_print_html()](Self::export_print_html()) and [PdfConverter].
+    pub async fn export_pdf<P: AsIdentifier<PageId>, O: AsRef<Path>>(
+        &self,
+        page_id: P,
+        output: O,
+        converter: &PdfConverter,
+    ) -> Result<()> {
+        let html = self.export_print_html(page_id).await?;
+        converter.convert(&html, output)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::{PdfConverter, PDF_INPUT, PDF_OUTPUT};
+    use crate::Error;
+
+    #[test]
+    fn converters_get_the_file_paths() {
+        let dir = std::env::temp_dir().join(format!("notion-pdf-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let output = dir.join("page.pdf");
+
+        let copy = PdfConverter::new("cp").arg(PDF_INPUT).arg(PDF_OUTPUT);
+        copy.convert("<p>Hi</p>", &output).unwrap();
+        assert_eq!(std::fs::read_to_string(&output).unwrap(), "<p>Hi</p>");
+        assert!(!dir.join("page.print.html").exists());
+
+        let failing = PdfConverter::new("false");
+        assert!(matches!(
+            failing.convert("", &output),
+            Err(Error::PdfConversionFailed { .. })
+        ));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,357 @@
+use futures::stream::{Stream, TryStreamExt};
+
+use super::pagination::{clamp_page_size, paginate, PaginationOptions, MAX_PAGE_SIZE};
+
+use crate::ids::{AsIdentifier, PageId, PropertyId};
+use crate::models::paging::PagingCursor;
+use crate::models::properties::{PropertyItem, PropertyValue, RollupPropertyValue};
+use crate::models::text::RichText;
+use crate::models::{ListResponse, Object, Page};
+use crate::{Error, NotionApi, Result, TClient};
+
+impl<C: TClient> NotionApi<C> {
+    /// Get every item of a page property from the property item endpoint,
+    /// following `next_cursor` until the property is exhausted.
+    ///
+    /// Properties that aren't paginated are returned as a single item.
+    /// <https://developers.notion.com/reference/retrieve-a-page-property>
+    pub async fn get_property_items<P: AsIdentifier<PageId>>(
+        &self,
+        page_id: P,
+        property_id: &PropertyId,
+    ) -> Result<Vec<PropertyItem>> {
+        let mut items = vec![];
+        let mut cursor: Option<PagingCursor> = None;
+
+        loop {
+            let list = match self
+                .get_property_item_page(
+                    page_id.as_id(),
+                    property_id,
+                    cursor.as_ref(),
+                    MAX_PAGE_SIZE,
+                )
+                .await?
+            {
+                Object::PropertyItem { property_item } => return Ok(vec![property_item]),
+                Object::List { list } => list.expect_property_items()?,
+                response => return Err(Error::UnexpectedResponse { response }),
+            };
+
+            items.extend(list.results);
+            match list.next_cursor {
+                Some(next) if list.has_more => cursor = Some(next),
+                _ => return Ok(items),
+            }
+        }
+    }
+
+    /// Get the complete array of a rollup property.
+    ///
+    /// [get_page()](Self::get_page()) only includes the first 25 items of a rollup,
+    /// this pages through all of them.
+    pub async fn get_rollup_array<P: AsIdentifier<PageId>>(
+        &self,
+        page_id: P,
+        property_id: &PropertyId,
+    ) -> Result<Vec<RollupPropertyValue>> {
+        let items = self.get_property_items(page_id, property_id).await?;
+
+        Ok(items.into_iter().map(RollupPropertyValue::from).collect())
+    }
+
+    /// The property `name` of `page` with all of its items.
+    ///
+    /// [get_page()](Self::get_page()) includes at most 25 related pages, people or
+    /// text elements, so paginated properties are fetched again with
+    /// [get_property_items()](Self::get_property_items()), see [PropertyValue::with_items()].
+    /// Returns `None` if `page` has no property with that name.
+    pub async fn get_full_property(
+        &self,
+        page: &Page,
+        name: &str,
+    ) -> Result<Option<PropertyValue>> {
+        let value = match page.properties.properties.get(name) {
+            Some(value) => value.clone(),
+            None => return Ok(None),
+        };
+        if !value.is_paginated() {
+            return Ok(Some(value));
+        }
+
+        let items = self.get_property_items(&page.id, value.id()).await?;
+        Ok(Some(value.with_items(items)))
+    }
+
+    /// Stream the rich text of a title or rich text property, one element at a time.
+    ///
+    /// Pages are requested as the stream is polled, so long texts that
+    /// [get_page()](Self::get_page()) truncates can be read without buffering them.
+    /// Items of any other property type end the stream with [Error::UnexpectedResponse].
+    pub fn rich_text_stream<'a, P: AsIdentifier<PageId>>(
+        &'a self,
+        page_id: P,
+        property_id: &PropertyId,
+    ) -> impl Stream<Item = Result<RichText>> + 'a {
+        self.rich_text_stream_with(page_id, property_id, PaginationOptions::default())
+    }
+
+    /// Like [rich_text_stream()](Self::rich_text_stream()), with the page size
+    /// and prefetching set by `options`.
+    ///
+    /// Without prefetching, the next page is only requested after the consumer
+    /// has read every item of the current one, so a small page size bounds
+    /// the memory used when the items are written to a slow sink.
+    pub fn rich_text_stream_with<'a, P: AsIdentifier<PageId>>(
+        &'a self,
+        page_id: P,
+        property_id: &PropertyId,
+        options: PaginationOptions,
+    ) -> impl Stream<Item = Result<RichText>> + 'a {
+        let page_id = page_id.as_id().clone();
+        let property_id = property_id.clone();
+        let page_size = clamp_page_size(options.page_size);
+
+        paginate(options.prefetch, move |cursor| {
+            let page_id = page_id.clone();
+            let property_id = property_id.clone();
+            async move {
+                match self
+                    .get_property_item_page(&page_id, &property_id, cursor.as_ref(), page_size)
+                    .await?
+                {
+                    Object::PropertyItem { property_item } => Ok(ListResponse {
+                        results: vec![property_item],
+                        next_cursor: None,
+                        has_more: false,
+                    }),
+                    Object::List { list } => list.expect_property_items(),
+                    response => Err(Error::UnexpectedResponse { response }),
+                }
+            }
+        })
+        .and_then(|item| async move { rich_text_item(item) })
+    }
+
+    pub(crate) async fn get_property_item_page(
+        &self,
+        page_id: &PageId,
+        property_id: &PropertyId,
+        cursor: Option<&PagingCursor>,
+        page_size: u8,
+    ) -> Result<Object> {
+        let mut uri = format!(
+            "{base_url}/pages/{page_id}/properties/{property_id}?page_size={page_size}",
+            base_url = self.base_url,
+            page_id = page_id,
+            property_id = property_id,
+            page_size = page_size
+        );
+        if let Some(cursor) = cursor {
+            uri.push_str("&start_cursor=");
+            uri.push_str(cursor.value());
+        }
+
+        self.client.get(uri).await
+    }
+}
+
+#[allow(clippy::result_large_err)]
+fn rich_text_item(item: PropertyItem) -> Result<RichText> {
+    match item {
+        PropertyItem::Title { title, .. } => Ok(title),
+        PropertyItem::Text { rich_text, .. } => Ok(rich_text),
+        property_item => Err(Error::UnexpectedResponse {
+            response: Object::PropertyItem { property_item },
+        }),
+    }
+}
+
+#[cfg(all(test, not(target_os = "wasi")))]
+mod tests {
+    use crate::ids::PropertyId;
+    use crate::models::properties::{
+        PropertyItem, PropertyValue, RelationValue, RollupPropertyValue,
+    };
+    use crate::models::{ListResponse, Object, Page};
+    use crate::test_util::{rich_text, MockNotion, PageFixture};
+    use crate::PaginationOptions;
+    use futures::TryStreamExt;
+    use std::str::FromStr;
+    use wiremock::matchers::{path, query_param};
+    use wiremock::{Mock, ResponseTemplate};
+
+    /// Serve `first` from the property item endpoint, followed by `second`.
+    async fn mount_two_pages(
+        mock: &MockNotion,
+        page: &Page,
+        property_id: &PropertyId,
+        first: PropertyItem,
+        second: PropertyItem,
+    ) {
+        let route = format!("/v1/pages/{}/properties/{}", page.id, property_id);
+        let list = |property_item, next_cursor: Option<&str>| Object::List {
+            list: ListResponse {
+                results: vec![Object::PropertyItem { property_item }],
+                next_cursor: next_cursor
+                    .map(|cursor| serde_json::from_value(cursor.into()).unwrap()),
+                has_more: next_cursor.is_some(),
+            },
+        };
+        Mock::given(path(route.clone()))
+            .respond_with(ResponseTemplate::new(200).set_body_json(list(first, Some("next"))))
+            .mount(mock.server())
+            .await;
+        Mock::given(path(route))
+            .and(query_param("start_cursor", "next"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(list(second, None)))
+            .with_priority(1)
+            .mount(mock.server())
+            .await;
+    }
+
+    #[tokio::test]
+    async fn get_rollup_array_follows_cursor() {
+        let mock = MockNotion::start().await;
+        let page = PageFixture::new().build();
+        let property_id = PropertyId::from_str("rollup").unwrap();
+        let number = |n: i32| PropertyItem::Number {
+            id: property_id.clone(),
+            number: Some(n.into()),
+        };
+        mount_two_pages(&mock, &page, &property_id, number(1), number(2)).await;
+
+        let array = mock
+            .api()
+            .get_rollup_array(&page.id, &property_id)
+            .await
+            .unwrap();
+        assert_eq!(
+            array,
+            vec![
+                RollupPropertyValue::Number {
+                    number: Some(1.into())
+                },
+                RollupPropertyValue::Number {
+                    number: Some(2.into())
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn get_full_property_follows_cursor() {
+        let mock = MockNotion::start().await;
+        let (first, second) = (PageFixture::new().build(), PageFixture::new().build());
+        let page = PageFixture::new()
+            .relation("Tasks", &[&first])
+            .checkbox("Done", true)
+            .build();
+        let property_id = PropertyId::from_str("Tasks").unwrap();
+        let related = |page: &Page| PropertyItem::Relation {
+            id: property_id.clone(),
+            relation: RelationValue {
+                id: page.id.clone(),
+            },
+        };
+        mount_two_pages(
+            &mock,
+            &page,
+            &property_id,
+            related(&first),
+            related(&second),
+        )
+        .await;
+
+        let api = mock.api();
+        let tasks = api.get_full_property(&page, "Tasks").await.unwrap();
+        assert_eq!(
+            tasks,
+            Some(PropertyValue::Relation {
+                id: property_id.clone(),
+                relation: Some(vec![
+                    RelationValue { id: first.id },
+                    RelationValue { id: second.id },
+                ]),
+                has_more: false,
+            })
+        );
+        assert_eq!(
+            api.get_full_property(&page, "Done").await.unwrap().as_ref(),
+            page.properties.properties.get("Done")
+        );
+        assert_eq!(api.get_full_property(&page, "Missing").await.unwrap(), None);
+        assert_eq!(mock.server().received_requests().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn get_property_items() {
+        let mock = MockNotion::start().await;
+        let page = PageFixture::new().build();
+        let property_id = PropertyId::from_str("title").unwrap();
+        let title = PropertyItem::Title {
+            id: property_id.clone(),
+            title: rich_text("Long title"),
+        };
+        mock.mount_property_items(&page, &property_id, vec![title.clone()])
+            .await;
+
+        let items = mock
+            .api()
+            .get_property_items(&page.id, &property_id)
+            .await
+            .unwrap();
+        assert_eq!(items, vec![title]);
+    }
+
+    #[tokio::test]
+    async fn rich_text_stream() {
+        let mock = MockNotion::start().await;
+        let page = PageFixture::new().build();
+        let property_id = PropertyId::from_str("notes").unwrap();
+        let text = |content| PropertyItem::Text {
+            id: property_id.clone(),
+            rich_text: rich_text(content),
+        };
+        mount_two_pages(&mock, &page, &property_id, text("Long "), text("notes")).await;
+
+        let api = mock.api();
+        let texts: Vec<String> = api
+            .rich_text_stream(&page.id, &property_id)
+            .map_ok(|rich_text| rich_text.plain_text().to_string())
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(texts, vec!["Long ", "notes"]);
+    }
+
+    #[tokio::test]
+    async fn rich_text_stream_fetches_pages_on_demand() {
+        let mock = MockNotion::start().await;
+        let page = PageFixture::new().build();
+        let property_id = PropertyId::from_str("notes").unwrap();
+        let text = |content| PropertyItem::Text {
+            id: property_id.clone(),
+            rich_text: rich_text(content),
+        };
+        mount_two_pages(&mock, &page, &property_id, text("Long "), text("notes")).await;
+
+        let api = mock.api();
+        let options = PaginationOptions {
+            page_size: 1,
+            ..PaginationOptions::default()
+        };
+        let stream = api.rich_text_stream_with(&page.id, &property_id, options);
+        futures::pin_mut!(stream);
+        let first = stream.try_next().await.unwrap().unwrap();
+        assert_eq!(first.plain_text(), "Long ");
+
+        let requests = mock.server().received_requests().await.unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].url.query(), Some("page_size=1"));
+
+        let second = stream.try_next().await.unwrap().unwrap();
+        assert_eq!(second.plain_text(), "notes");
+        assert!(stream.try_next().await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,381 @@
+//! Record real API traffic to a file and replay it later.
+//!
+//! ```no_run
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! use notion_wasi::{Client, NotionApi, Recorder, Replayer};
+//!
+//! // Capture the responses of a real run...
+//! let client = Client::new(std::env::var("NOTION_API_TOKEN")?)?;
+//! let api = NotionApi::with_client(Recorder::new(client, "tests/cassettes/search.json"));
+//! api.list_databases().await?;
+//!
+//! // ...and serve them back without network access.
+//! let api = NotionApi::with_client(Replayer::load("tests/cassettes/search.json")?);
+//! api.list_databases().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::models::Object;
+use crate::{ApiVersion, Error, MultipartForm, RateLimitStatus, Result, TClient};
+
+/// Placeholder for secrets removed from recorded bodies.
+const REDACTED: &str = "[REDACTED]";
+
+/// A recorded request and the response it received.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct Interaction {
+    pub method: String,
+    pub uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+    /// The response, including API errors as [Object::Error].
+    pub response: Object,
+}
+
+/// An ordered list of [Interaction]s, stored as a JSON file.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Default)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+    pub fn load<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    pub fn save<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> std::io::Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, json)
+    }
+}
+
+/// A [TClient] that forwards requests to another client and
+/// records every response into a [Cassette] file.
+///
+/// The cassette is rewritten after each request.
+/// Token-like strings in request and response bodies are redacted.
+/// Transport failures are not recorded.
+pub struct Recorder<C> {
+    inner: C,
+    path: PathBuf,
+    cassette: Mutex<Cassette>,
+}
+
+impl<C: TClient> Recorder<C> {
+    pub fn new<P: Into<PathBuf>>(
+        inner: C,
+        path: P,
+    ) -> Self {
+        Recorder {
+            inner,
+            path: path.into(),
+            cassette: Mutex::new(Cassette::default()),
+        }
+    }
+
+    /// The interactions recorded so far.
+    pub fn cassette(&self) -> Cassette {
+        self.cassette.lock().unwrap().clone()
+    }
+
+    fn record(
+        &self,
+        method: &str,
+        uri: String,
+        body: Option<Value>,
+        result: &Result<Object>,
+    ) {
+        let response = match result {
+            Ok(response) => response.clone(),
+            Err(Error::ApiError { error }) => Object::Error {
+                error: error.clone(),
+            },
+            Err(_) => return,
+        };
+
+        let mut response = serde_json::to_value(response).unwrap();
+        redact(&mut response);
+        let body = body.map(|mut body| {
+            redact(&mut body);
+            body
+        });
+
+        let mut cassette = self.cassette.lock().unwrap();
+        cassette.interactions.push(Interaction {
+            method: method.to_string(),
+            uri,
+            body,
+            response: serde_json::from_value(response).unwrap(),
+        });
+        #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+        if let Err(error) = cassette.save(&self.path) {
+            warn!(path = ?self.path, %error, "Unable to save cassette");
+        }
+    }
+}
+
+#[async_trait]
+impl<C: TClient + Send + Sync> TClient for Recorder<C> {
+    fn api_version(&self) -> ApiVersion {
+        self.inner.api_version()
+    }
+
+    fn rate_limit_status(&self) -> Option<RateLimitStatus> {
+        self.inner.rate_limit_status()
+    }
+
+    async fn get<S: Into<String> + Send>(
+        &self,
+        uri: S,
+    ) -> Result<Object> {
+        let uri = uri.into();
+        let result = self.inner.get(uri.clone()).await;
+        self.record("GET", uri, None, &result);
+        result
+    }
+
+    async fn get_fresh<S: Into<String> + Send>(
+        &self,
+        uri: S,
+    ) -> Result<Object> {
+        let uri = uri.into();
+        let result = self.inner.get_fresh(uri.clone()).await;
+        self.record("GET", uri, None, &result);
+        result
+    }
+
+    async fn post<S: Into<String> + Send>(
+        &self,
+        uri: S,
+    ) -> Result<Object> {
+        let uri = uri.into();
+        let result = self.inner.post(uri.clone()).await;
+        self.record("POST", uri, None, &result);
+        result
+    }
+
+    async fn post_json<S: Into<String> + Send>(
+        &self,
+        uri: S,
+        body: Vec<u8>,
+    ) -> Result<Object> {
+        let uri = uri.into();
+        let recorded = serde_json::from_slice(&body).ok();
+        let result = self.inner.post_json(uri.clone(), body).await;
+        self.record("POST", uri, recorded, &result);
+        result
+    }
+
+    async fn patch_json<S: Into<String> + Send>(
+        &self,
+        uri: S,
+        body: Vec<u8>,
+    ) -> Result<Object> {
+        let uri = uri.into();
+        let recorded = serde_json::from_slice(&body).ok();
+        let result = self.inner.patch_json(uri.clone(), body).await;
+        self.record("PATCH", uri, recorded, &result);
+        result
+    }
+
+    /// The form isn't recorded, it holds the contents of a file.
+    async fn post_multipart<S: Into<String> + Send>(
+        &self,
+        uri: S,
+        form: MultipartForm,
+    ) -> Result<Object> {
+        let uri = uri.into();
+        let result = self.inner.post_multipart(uri.clone(), form).await;
+        self.record("POST", uri, None, &result);
+        result
+    }
+
+    async fn delete<S: Into<String> + Send>(
+        &self,
+        uri: S,
+    ) -> Result<Object> {
+        let uri = uri.into();
+        let result = self.inner.delete(uri.clone()).await;
+        self.record("DELETE", uri, None, &result);
+        result
+    }
+}
+
+/// A [TClient] that answers from a [Cassette] instead of the network.
+///
+/// Each request is answered by the first unused interaction with the same
+/// method and URI, so repeated requests (e.g. pagination) replay in order.
+pub struct Replayer {
+    interactions: Mutex<Vec<Option<Interaction>>>,
+}
+
+impl Replayer {
+    pub fn new(cassette: Cassette) -> Self {
+        Replayer {
+            interactions: Mutex::new(cassette.interactions.into_iter().map(Some).collect()),
+        }
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        Ok(Self::new(Cassette::load(path)?))
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn replay(
+        &self,
+        method: &str,
+        uri: String,
+    ) -> Result<Object> {
+        let mut interactions = self.interactions.lock().unwrap();
+        let interaction = interactions
+            .iter_mut()
+            .find(|slot| {
+                slot.as_ref()
+                    .is_some_and(|recorded| recorded.method == method && recorded.uri == uri)
+            })
+            .and_then(Option::take);
+
+        match interaction.map(|interaction| interaction.response) {
+            Some(Object::Error { error }) => Err(Error::ApiError { error }),
+            Some(response) => Ok(response),
+            None => Err(Error::NoRecordedResponse {
+                method: method.to_string(),
+                uri,
+            }),
+        }
+    }
+}
+
+#[async_trait]
+impl TClient for Replayer {
+    async fn get<S: Into<String> + Send>(
+        &self,
+        uri: S,
+    ) -> Result<Object> {
+        self.replay("GET", uri.into())
+    }
+
+    async fn post<S: Into<String> + Send>(
+        &self,
+        uri: S,
+    ) -> Result<Object> {
+        self.replay("POST", uri.into())
+    }
+
+    async fn post_json<S: Into<String> + Send>(
+        &self,
+        uri: S,
+        _body: Vec<u8>,
+    ) -> Result<Object> {
+        self.replay("POST", uri.into())
+    }
+
+    async fn patch_json<S: Into<String> + Send>(
+        &self,
+        uri: S,
+        _body: Vec<u8>,
+    ) -> Result<Object> {
+        self.replay("PATCH", uri.into())
+    }
+
+    async fn post_multipart<S: Into<String> + Send>(
+        &self,
+        uri: S,
+        _form: MultipartForm,
+    ) -> Result<Object> {
+        self.replay("POST", uri.into())
+    }
+
+    async fn delete<S: Into<String> + Send>(
+        &self,
+        uri: S,
+    ) -> Result<Object> {
+        self.replay("DELETE", uri.into())
+    }
+}
+
+/// Blank out values that look like integration or OAuth tokens.
+fn redact(value: &mut Value) {
+    match value {
+        Value::String(string) if is_secret(string) => *string = REDACTED.to_string(),
+        Value::Array(values) => values.iter_mut().for_each(redact),
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if key.ends_with("token") || key == "client_secret" {
+                    *value = Value::String(REDACTED.to_string());
+                } else {
+                    redact(value);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_secret(string: &str) -> bool {
+    string.starts_with("secret_") || string.starts_with("ntn_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{redact, Cassette, Interaction, Replayer};
+    use crate::ids::PageId;
+    use crate::models::Object;
+    use crate::test_util::PageFixture;
+    use crate::{Error, NotionApi};
+    use serde_json::json;
+    use std::str::FromStr;
+
+    #[test]
+    fn redacts_tokens() {
+        let mut body = json!({
+            "access_token": "abc",
+            "owner": {"note": "secret_123"},
+            "title": "plain"
+        });
+        redact(&mut body);
+        assert_eq!(
+            body,
+            json!({
+                "access_token": "[REDACTED]",
+                "owner": {"note": "[REDACTED]"},
+                "title": "plain"
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn replays_in_order() {
+        let first = PageFixture::new().id("page").title("first").build();
+        let second = PageFixture::new().id("page").title("second").build();
+        let interaction = |page| Interaction {
+            method: "GET".to_string(),
+            uri: "https://api.notion.com/v1/pages/page".to_string(),
+            body: None,
+            response: Object::Page { page },
+        };
+        let api = NotionApi::with_client(Replayer::new(Cassette {
+            interactions: vec![interaction(first.clone()), interaction(second.clone())],
+        }));
+
+        let page_id = PageId::from_str("page").unwrap();
+        assert_eq!(api.get_page(&page_id).await.unwrap(), first);
+        assert_eq!(api.get_page(&page_id).await.unwrap(), second);
+        assert!(matches!(
+            api.get_page(&page_id).await,
+            Err(Error::NoRecordedResponse { .. })
+        ));
+    }
+}
@@ -0,0 +1,211 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::ids::{AsIdentifier, PageId};
+use crate::models::error::ErrorCode;
+use crate::models::properties::{PropertyItem, PropertyValue};
+use crate::models::Page;
+use crate::{NotionApi, Result, TClient};
+
+/// Pages fetched at once by [NotionApi::traverse_relations()].
+const CONCURRENCY: usize = 3;
+
+/// A relation from one page to another.
+#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+pub struct RelationEdge {
+    pub from: PageId,
+    /// The name of the relation property on `from`.
+    pub property: String,
+    pub to: PageId,
+}
+
+/// The pages reachable through relations, see [NotionApi::traverse_relations()].
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct RelationGraph {
+    pub start: PageId,
+    /// Every fetched page, including the start page.
+    pub pages: HashMap<PageId, Page>,
+    /// Relations in the order they were followed.
+    /// Targets the integration can't access are not in `pages`.
+    pub edges: Vec<RelationEdge>,
+}
+
+impl RelationGraph {
+    /// The fetched pages `page_id` links to through `property`.
+    pub fn related(
+        &self,
+        page_id: &PageId,
+        property: &str,
+    ) -> Vec<&Page> {
+        self.edges
+            .iter()
+            .filter(|edge| &edge.from == page_id && edge.property == property)
+            .filter_map(|edge| self.pages.get(&edge.to))
+            .collect()
+    }
+}
+
+impl<C: TClient> NotionApi<C> {
+    /// Follows relation properties outwards from `start_page`, up to `depth` hops.
+    ///
+    /// Hop `n` follows the property `property_path[n % property_path.len()]`,
+    /// so `&["Blocked by"]` walks a dependency chain and `&["Project", "Team"]`
+    /// goes from tasks to projects to teams. Pages without the property are leaves.
+    ///
+    /// Every page is fetched once, even if several relations or cycles lead to it,
+    /// and the pages of each hop are fetched concurrently.
+    /// Relations with more than 25 pages are read through the property item endpoint.
+    pub async fn traverse_relations<P: AsIdentifier<PageId>>(
+        &self,
+        start_page: P,
+        property_path: &[&str],
+        depth: usize,
+    ) -> Result<RelationGraph> {
+        let start = self.get_page(start_page).await?;
+        let mut graph = RelationGraph {
+            start: start.id.clone(),
+            pages: HashMap::new(),
+            edges: vec![],
+        };
+        let mut seen: HashSet<PageId> = HashSet::from([start.id.clone()]);
+        let mut frontier = vec![start.id.clone()];
+        graph.pages.insert(start.id.clone(), start);
+
+        for hop in 0..depth {
+            let property = match property_path.get(hop % property_path.len().max(1)) {
+                Some(property) => *property,
+                None => break,
+            };
+
+            let mut next = vec![];
+            for page_id in &frontier {
+                let page = &graph.pages[page_id];
+                for target in self.relation_targets(page, property).await? {
+                    if seen.insert(target.clone()) {
+                        next.push(target.clone());
+                    }
+                    graph.edges.push(RelationEdge {
+                        from: page_id.clone(),
+                        property: property.to_string(),
+                        to: target,
+                    });
+                }
+            }
+
+            frontier = vec![];
+            for result in self.get_pages(&next, CONCURRENCY).await {
+                match result {
+                    Ok(page) => {
+                        frontier.push(page.id.clone());
+                        graph.pages.insert(page.id.clone(), page);
+                    }
+                    Err(error) if error.api_error_code() == Some(&ErrorCode::ObjectNotFound) => {}
+                    Err(error) => return Err(error),
+                }
+            }
+        }
+
+        Ok(graph)
+    }
+
+    async fn relation_targets(
+        &self,
+        page: &Page,
+        property: &str,
+    ) -> Result<Vec<PageId>> {
+        match page.properties.properties.get(property) {
+            Some(PropertyValue::Relation {
+                id, has_more: true, ..
+            }) => Ok(self
+                .get_property_items(&page.id, id)
+                .await?
+                .into_iter()
+                .filter_map(|item| match item {
+                    PropertyItem::Relation { relation, .. } => Some(relation.id),
+                    _ => None,
+                })
+                .collect()),
+            Some(PropertyValue::Relation { relation, .. }) => Ok(relation
+                .iter()
+                .flatten()
+                .map(|related| related.id.clone())
+                .collect()),
+            _ => Ok(vec![]),
+        }
+    }
+}
+
+#[cfg(all(test, not(target_os = "wasi")))]
+mod tests {
+    use crate::ids::PropertyId;
+    use crate::models::properties::{PropertyItem, PropertyValue, RelationValue};
+    use crate::test_util::{MockNotion, PageFixture};
+    use std::str::FromStr;
+
+    #[tokio::test]
+    async fn traverse_relation_cycles() {
+        let mock = MockNotion::start().await;
+        let missing = PageFixture::new().build();
+        // The relation of the third page is too long to be included and
+        // has to be fetched separately.
+        let third = PageFixture::new()
+            .property(
+                "Blockers",
+                PropertyValue::Relation {
+                    id: PropertyId::from_str("Blockers").unwrap(),
+                    relation: Some(vec![]),
+                    has_more: true,
+                },
+            )
+            .build();
+        let second = PageFixture::new().relation("Blockers", &[&third]).build();
+        let first = PageFixture::new()
+            .relation("Blockers", &[&second, &missing])
+            .build();
+        for page in [&first, &second, &third] {
+            mock.mount_page(page).await;
+        }
+        mock.mount_property_items(
+            &third,
+            &PropertyId::from_str("Blockers").unwrap(),
+            vec![PropertyItem::Relation {
+                id: PropertyId::from_str("Blockers").unwrap(),
+                relation: RelationValue {
+                    id: first.id.clone(),
+                },
+            }],
+        )
+        .await;
+
+        let graph = mock
+            .api()
+            .traverse_relations(&first.id, &["Blockers"], 10)
+            .await
+            .unwrap();
+
+        assert_eq!(graph.start, first.id);
+        assert_eq!(graph.pages.len(), 3);
+        assert!(!graph.pages.contains_key(&missing.id));
+        let edges: Vec<_> = graph
+            .edges
+            .iter()
+            .map(|edge| (edge.from.clone(), edge.to.clone()))
+            .collect();
+        assert_eq!(
+            edges,
+            vec![
+                (first.id.clone(), second.id.clone()),
+                (first.id.clone(), missing.id.clone()),
+                (second.id.clone(), third.id.clone()),
+                (third.id.clone(), first.id.clone()),
+            ]
+        );
+        assert_eq!(graph.related(&second.id, "Blockers"), vec![&third]);
+
+        let shallow = mock
+            .api()
+            .traverse_relations(&first.id, &["Blockers"], 1)
+            .await
+            .unwrap();
+        assert_eq!(shallow.pages.len(), 2);
+    }
+}
@@ -1,13 +1,31 @@
-use crate::models::{error::ErrorResponse, Object};
-use crate::{TClient, NOTION_API_VERSION};
+use super::codec::{decode_response, ResponseHead, REQUEST_ID_HEADERS};
+use super::retry::Instant;
+use super::stats::{RateLimitStatus, RequestStats, Stats};
+use crate::ids::PageId;
+use crate::models::{
+    error::{ErrorResponse, HttpErrorResponse},
+    files::FileSource,
+    oauth::OAuthErrorResponse,
+    rows::RowError,
+    search::InvalidQuery,
+    InvalidPage, Object,
+};
+use crate::{ApiVersion, ClientBuilder, JsonCodec, MultipartForm, SecretString, TClient};
+
+use std::fmt;
+use std::sync::Arc;
 
 use async_trait::async_trait;
-use reqwest::header::{HeaderMap, HeaderValue};
-use reqwest::{header, Client as RClient, ClientBuilder, RequestBuilder};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+#[cfg(not(target_arch = "wasm32"))]
+use reqwest::Proxy;
+use reqwest::{header, Client as RClient, ClientBuilder as RClientBuilder, RequestBuilder};
+#[cfg(feature = "tracing")]
 use tracing::Instrument;
 
 /// An wrapper Error type for all errors produced by the [`NotionApi`](NotionApi) client.
 #[derive(Debug, thiserror::Error)]
+#[allow(clippy::large_enum_variant)]
 pub enum Error {
     #[error("Invalid Notion API Token: {}", source)]
     InvalidApiToken { source: header::InvalidHeaderValue },
@@ -15,6 +33,9 @@ pub enum Error {
     #[error("Unable to build reqwest HTTP client: {}", source)]
     ErrorBuildingClient { source: reqwest::Error },
 
+    #[error("Invalid header {}", name)]
+    InvalidHeader { name: String },
+
     #[error("Error sending HTTP request: {}", source)]
     RequestFailed {
         #[from]
@@ -25,13 +46,91 @@ pub enum Error {
     ResponseIoError { source: reqwest::Error },
 
     #[error("Error parsing json response: {}", source)]
-    JsonParseError { source: serde_json::Error },
+    JsonParseError {
+        source: serde_json::Error,
+        /// The start of the response that failed to parse, if it was one.
+        body: Option<String>,
+    },
+
+    #[cfg(feature = "simd-json")]
+    #[error("Error parsing json response: {}", source)]
+    SimdJsonParseError {
+        source: simd_json::Error,
+        /// The start of the response that failed to parse, if it was one.
+        body: Option<String>,
+    },
+
+    #[error("Error decoding response: {}", source)]
+    DecodeFailed {
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
 
     #[error("Unexpected API Response")]
     UnexpectedResponse { response: Object },
 
     #[error("API Error {}({}): {}", .error.code, .error.status, .error.message)]
     ApiError { error: ErrorResponse },
+
+    /// A response with an error status that isn't a Notion error,
+    /// such as the HTML page of a gateway error.
+    #[error("Unexpected HTTP status {}", .response.status)]
+    HttpError { response: HttpErrorResponse },
+
+    #[error("OAuth error {}", .error.error)]
+    OAuthError { error: OAuthErrorResponse },
+
+    #[error("Invalid query: {}", source)]
+    InvalidQuery { source: InvalidQuery },
+
+    #[error("Invalid page: {}", source)]
+    InvalidPage { source: InvalidPage },
+
+    #[error("Unable to read page {}: {}", page, source)]
+    InvalidRow { page: PageId, source: RowError },
+
+    #[error("Found {} pages where `{}` matches the upsert key", .pages.len(), .property)]
+    DuplicatePages {
+        property: String,
+        pages: Vec<PageId>,
+    },
+
+    #[error(
+        "The file is {} bytes, but the workspace accepts at most {} bytes",
+        size,
+        limit
+    )]
+    FileTooLarge { size: u64, limit: u64 },
+
+    #[error("Environment variable {} is not set", name)]
+    MissingEnvVar { name: String },
+
+    #[error("Invalid environment variable {}: {}", name, reason)]
+    InvalidEnvVar { name: String, reason: String },
+
+    #[error("No recorded response for {} {}", method, uri)]
+    NoRecordedResponse { method: String, uri: String },
+
+    #[error("The file is no longer attached to {:?}", location)]
+    FileRemoved { location: FileSource },
+
+    #[error("Downloading {} failed with status {}", url, status)]
+    DownloadFailed { url: String, status: u16 },
+
+    #[cfg(feature = "pdf")]
+    #[error("Converting to PDF failed: {}", reason)]
+    PdfConversionFailed { reason: String },
+
+    #[cfg(feature = "polars")]
+    #[error("Building the DataFrame failed: {}", source)]
+    DataFrame {
+        source: polars::prelude::PolarsError,
+    },
+
+    #[error("IO error: {}", source)]
+    Io {
+        #[from]
+        source: std::io::Error,
+    },
 }
 
 /// An API client for Notion.
@@ -39,32 +138,125 @@ pub enum Error {
 #[derive(Clone)]
 pub struct Client {
     client: RClient,
+    version: ApiVersion,
+    codec: Arc<dyn JsonCodec>,
+    pub(crate) stats: Stats,
 }
 
 impl Client {
-    pub fn new(api_token: String) -> Result<Self, Error> {
-        let mut headers = HeaderMap::new();
-        headers.insert(
-            "Notion-Version",
-            HeaderValue::from_static(NOTION_API_VERSION),
-        );
+    #[allow(clippy::result_large_err)]
+    pub fn new<S: Into<SecretString>>(api_token: S) -> Result<Self, Error> {
+        Self::with_version(api_token, ApiVersion::default())
+    }
+
+    /// Creates a client that sends `Notion-Version: {version}`.
+    #[allow(clippy::result_large_err)]
+    pub fn with_version<S: Into<SecretString>>(
+        api_token: S,
+        version: ApiVersion,
+    ) -> Result<Self, Error> {
+        Self::from_builder(ClientBuilder::new(api_token).version(version))
+    }
+
+    /// The same client sending `Notion-Version: {version}`, for the requests
+    /// that need another version than the rest.
+    /// It shares the connections and the [stats()](Self::stats()) of this client.
+    pub fn for_version(
+        &self,
+        version: ApiVersion,
+    ) -> Self {
+        Client {
+            version,
+            ..self.clone()
+        }
+    }
 
-        let mut auth_value = HeaderValue::from_str(&format!("Bearer {}", api_token))
-            .map_err(|source| Error::InvalidApiToken { source })?;
+    #[allow(clippy::result_large_err)]
+    pub(crate) fn from_builder(builder: ClientBuilder) -> Result<Self, Error> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &builder.headers {
+            let invalid = || Error::InvalidHeader { name: name.clone() };
+            headers.insert(
+                HeaderName::from_bytes(name.as_bytes()).map_err(|_| invalid())?,
+                HeaderValue::from_str(value).map_err(|_| invalid())?,
+            );
+        }
+        let mut auth_value =
+            HeaderValue::from_str(&format!("Bearer {}", builder.token.expose_secret()))
+                .map_err(|source| Error::InvalidApiToken { source })?;
         auth_value.set_sensitive(true);
         headers.insert(header::AUTHORIZATION, auth_value);
 
-        let client = ClientBuilder::new()
+        let client = RClientBuilder::new()
             .default_headers(headers)
+            .user_agent(builder.user_agent_header());
+        // Browsers handle timeouts and proxies themselves.
+        #[cfg(not(target_arch = "wasm32"))]
+        let client = with_connection_options(client, &builder)?;
+        let client = client
             .build()
             .map_err(|source| Error::ErrorBuildingClient { source })?;
 
-        Ok(Self { client })
+        Ok(Self {
+            client,
+            version: builder.version,
+            codec: builder.codec,
+            stats: Stats::new(builder.on_request),
+        })
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[allow(clippy::result_large_err)]
+fn with_connection_options(
+    mut client: RClientBuilder,
+    builder: &ClientBuilder,
+) -> Result<RClientBuilder, Error> {
+    if let Some(timeout) = builder.timeout {
+        client = client.timeout(timeout);
+    }
+    if let Some(timeout) = builder.connect_timeout {
+        client = client.connect_timeout(timeout);
+    }
+    match &builder.proxy {
+        Some(url) => {
+            let mut proxy =
+                Proxy::all(url).map_err(|source| Error::ErrorBuildingClient { source })?;
+            if let Some((username, password)) = &builder.proxy_auth {
+                proxy = proxy.basic_auth(username, password.expose_secret());
+            }
+            client = client.proxy(proxy);
+        }
+        None if !builder.proxy_from_env => client = client.no_proxy(),
+        None => {}
+    }
+    Ok(client)
+}
+
+/// Leaves out the default headers, which hold the token.
+impl fmt::Debug for Client {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        f.debug_struct("Client")
+            .field("version", &self.version)
+            .field("codec", &self.codec)
+            .field("stats", &self.stats)
+            .finish_non_exhaustive()
     }
 }
 
 #[async_trait]
 impl TClient for Client {
+    fn api_version(&self) -> ApiVersion {
+        self.version
+    }
+
+    fn rate_limit_status(&self) -> Option<RateLimitStatus> {
+        Some(Client::rate_limit_status(self))
+    }
+
     async fn get<S: Into<String> + Send>(
         &self,
         uri: S,
@@ -88,7 +280,7 @@ impl TClient for Client {
     async fn post_json<S: Into<String> + Send>(
         &self,
         uri: S,
-        body: &[u8],
+        body: Vec<u8>,
     ) -> crate::Result<Object> {
         let url: String = uri.into();
 
@@ -97,10 +289,55 @@ impl TClient for Client {
             .post(url)
             .header("Content-Type", "application/json")
             .header("Content-Length", body.len())
-            .body(body.to_owned());
+            .body(body);
+
+        self.make_json_request(request).await
+    }
+
+    async fn patch_json<S: Into<String> + Send>(
+        &self,
+        uri: S,
+        body: Vec<u8>,
+    ) -> crate::Result<Object> {
+        let url: String = uri.into();
+
+        let request = self
+            .client
+            .patch(url)
+            .header("Content-Type", "application/json")
+            .header("Content-Length", body.len())
+            .body(body);
+
+        self.make_json_request(request).await
+    }
+
+    async fn post_multipart<S: Into<String> + Send>(
+        &self,
+        uri: S,
+        form: MultipartForm,
+    ) -> crate::Result<Object> {
+        let url: String = uri.into();
+        let (content_type, body) = form.encode();
+
+        let request = self
+            .client
+            .post(url)
+            .header("Content-Type", content_type)
+            .header("Content-Length", body.len())
+            .body(body);
 
         self.make_json_request(request).await
     }
+
+    async fn delete<S: Into<String> + Send>(
+        &self,
+        uri: S,
+    ) -> crate::Result<Object> {
+        let url: String = uri.into();
+
+        let request = self.client.delete(url);
+        self.make_json_request(request).await
+    }
 }
 
 impl Client {
@@ -108,36 +345,155 @@ impl Client {
         &self,
         request: RequestBuilder,
     ) -> Result<Object, Error> {
-        let request = request.build()?;
+        let request = request
+            .header("Notion-Version", self.version.as_str())
+            .build()?;
         let url = request.url();
-        tracing::trace!(
+        trace!(
             method = request.method().as_str(),
             url = url.as_str(),
             "Sending request"
         );
-        let json = self
-            .client
-            .execute(request)
-            .instrument(tracing::trace_span!("Sending request"))
-            .await
-            .map_err(|source| Error::RequestFailed { source })?
-            .text()
-            .instrument(tracing::trace_span!("Reading response"))
-            .await
-            .map_err(|source| Error::ResponseIoError { source })?;
+        #[cfg(feature = "log-bodies")]
+        if let Some(body) = request.body().and_then(|body| body.as_bytes()) {
+            super::body_log::log_request(request.method().as_str(), url.as_str(), body);
+        }
+        let mut stats = RequestStats::new(
+            request.method().as_str(),
+            url.as_str(),
+            request
+                .body()
+                .and_then(|body| body.as_bytes())
+                .map_or(0, <[u8]>::len),
+        );
+        let start = Instant::now();
+        let response = local(self.client.execute(request));
+        #[cfg(feature = "tracing")]
+        let response = response.instrument(tracing::trace_span!("Sending request"));
+        let mut head = ResponseHead {
+            status: 0,
+            request_id: None,
+            retry_after: None,
+        };
+        let json = match response.await {
+            Ok(response) => {
+                stats.status = Some(response.status().as_u16());
+                let headers = response.headers();
+                let header = |name| headers.get(name).and_then(|value| value.to_str().ok());
+                head = ResponseHead {
+                    status: response.status().as_u16(),
+                    request_id: header(REQUEST_ID_HEADERS[0])
+                        .or_else(|| header(REQUEST_ID_HEADERS[1]))
+                        .map(str::to_string),
+                    retry_after: header(header::RETRY_AFTER.as_str())
+                        .and_then(super::retry::parse_retry_after),
+                };
+                stats.time_to_headers = Some(start.elapsed());
+                let bytes = local(response.bytes());
+                #[cfg(feature = "tracing")]
+                let bytes = bytes.instrument(tracing::trace_span!("Reading response"));
+                bytes
+                    .await
+                    .map_err(|source| Error::ResponseIoError { source })
+            }
+            Err(source) => Err(Error::RequestFailed { source }),
+        };
+        stats.elapsed = start.elapsed();
+        stats.response_bytes = json.as_ref().map_or(0, |json| json.len());
+        self.stats.record(stats);
+        let json = json?;
 
-        tracing::debug!("JSON Response: {}", json);
+        #[cfg(feature = "log-bodies")]
+        super::body_log::log_response(&json);
         #[cfg(test)]
         {
-            dbg!(serde_json::from_str::<serde_json::Value>(&json)
-                .map_err(|source| Error::JsonParseError { source })?);
+            dbg!(serde_json::from_slice::<serde_json::Value>(&json).ok());
         }
-        let result =
-            serde_json::from_str(&json).map_err(|source| Error::JsonParseError { source })?;
+        decode_response(self.codec.as_ref(), head, &json)
+    }
+}
 
-        match result {
-            Object::Error { error } => Err(Error::ApiError { error }),
-            response => Ok(response),
+/// Requests in browsers are JS promises, which aren't `Send`, but browsers poll them
+/// on the thread that created them. Wrapping them keeps [TClient] futures `Send`.
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+fn local<F: std::future::Future>(future: F) -> send_wrapper::SendWrapper<F> {
+    send_wrapper::SendWrapper::new(future)
+}
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+fn local<F>(future: F) -> F {
+    future
+}
+
+/// Sends `body` as JSON with HTTP Basic authentication instead of the integration token,
+/// and returns the status and the body of the response.
+pub(crate) async fn post_with_basic_auth(
+    url: &str,
+    username: &str,
+    password: &SecretString,
+    body: Vec<u8>,
+) -> Result<(u16, Vec<u8>), Error> {
+    let request = RClient::new()
+        .post(url)
+        .basic_auth(username, Some(password.expose_secret()))
+        .header("Content-Type", "application/json")
+        .body(body);
+    let response = local(request.send()).await?;
+    let status = response.status().as_u16();
+    let body = local(response.bytes())
+        .await
+        .map_err(|source| Error::ResponseIoError { source })?;
+    Ok((status, body.to_vec()))
+}
+
+/// Downloads `url` without the Notion headers, which the presigned URLs
+/// of uploaded files reject.
+#[cfg(feature = "blocks")]
+pub(crate) async fn download(url: &str) -> Result<Vec<u8>, Error> {
+    let mut bytes = vec![];
+    download_to(url, &mut bytes).await?;
+    Ok(bytes)
+}
+
+/// Like [download()], writing the body to `writer` as it arrives.
+/// Returns the number of bytes written.
+pub(crate) async fn download_to<W: std::io::Write>(
+    url: &str,
+    writer: &mut W,
+) -> Result<u64, Error> {
+    let response = local(reqwest::get(url)).await?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(Error::DownloadFailed {
+            url: url.to_string(),
+            status: status.as_u16(),
+        });
+    }
+
+    // Browsers hand over the whole body at once.
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    {
+        let bytes = local(response.bytes())
+            .await
+            .map_err(|source| Error::ResponseIoError { source })?;
+        writer.write_all(&bytes)?;
+        writer.flush()?;
+        Ok(bytes.len() as u64)
+    }
+
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    {
+        let mut response = response;
+        let mut written = 0;
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|source| Error::ResponseIoError { source })?
+        {
+            writer.write_all(&chunk)?;
+            written += chunk.len() as u64;
         }
+        writer.flush()?;
+        Ok(written)
     }
 }
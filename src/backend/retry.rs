@@ -0,0 +1,606 @@
+//! Retrying transient failures.
+//!
+//! ```no_run
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! use notion_wasi::{Client, Method, NotionApi, RetryPolicy, Retrying};
+//!
+//! let client = Client::new(std::env::var("NOTION_API_TOKEN")?)?;
+//! let policy = RetryPolicy {
+//!     max_attempts: 5,
+//!     // Searches and queries only read, so they are safe to repeat.
+//!     methods: vec![Method::Get, Method::Post],
+//!     ..RetryPolicy::default()
+//! };
+//! let api = NotionApi::with_client(Retrying::new(client, policy));
+//! api.list_databases().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::models::Object;
+use crate::{ApiVersion, Error, MultipartForm, RateLimitStatus, Result, TClient};
+
+/// The HTTP methods sent by a [TClient].
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Copy, Clone, Hash)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Method {
+    Get,
+    Post,
+    Patch,
+    Delete,
+}
+
+impl fmt::Display for Method {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        f.write_str(match self {
+            Method::Get => "GET",
+            Method::Post => "POST",
+            Method::Patch => "PATCH",
+            Method::Delete => "DELETE",
+        })
+    }
+}
+
+/// When and how often [Retrying] repeats a failed request.
+///
+/// The default retries `GET` requests up to 3 attempts in total when the API
+/// answers `429`, `500`, `502`, `503` or `504`, or the request could not be sent.
+/// `POST` and `PATCH` are not retried by default, because the API may have
+/// applied a write before failing and repeating it could duplicate pages or blocks.
+/// A `Retry-After` header of the failed response replaces the exponential backoff.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct RetryPolicy {
+    /// Attempts in total, including the first one. `1` disables retries.
+    pub max_attempts: u32,
+    /// Methods that are safe to send more than once.
+    pub methods: Vec<Method>,
+    /// API error statuses that count as transient.
+    pub status_codes: Vec<u16>,
+    /// Whether requests that failed before a response arrived are retried.
+    pub retry_transport_errors: bool,
+    /// The wait before the first retry.
+    pub initial_backoff: Duration,
+    /// Each retry waits `multiplier` times longer than the one before.
+    pub multiplier: u32,
+    /// The longest wait between two attempts.
+    pub max_backoff: Duration,
+    /// Gives up instead of retrying once the wait for the next attempt would end
+    /// more than `max_elapsed` after the first attempt was sent, returning the last error.
+    ///
+    /// Attempts that are already in flight are not cut short. `None`, the default,
+    /// only limits the number of attempts.
+    pub max_elapsed: Option<Duration>,
+    /// Whether the wait asked for by the `Retry-After` header of a `429` response
+    /// is used instead of the backoff, even when it is longer than [max_backoff](Self::max_backoff).
+    pub honor_retry_after: bool,
+    /// Waits a random time between half and all of the backoff, so that clients
+    /// rate limited together don't retry together.
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            methods: vec![Method::Get],
+            status_codes: vec![429, 500, 502, 503, 504],
+            retry_transport_errors: true,
+            initial_backoff: Duration::from_millis(500),
+            multiplier: 2,
+            max_backoff: Duration::from_secs(30),
+            max_elapsed: None,
+            honor_retry_after: true,
+            jitter: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that sends every request exactly once.
+    pub fn never() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            ..RetryPolicy::default()
+        }
+    }
+
+    /// Whether a `method` request that failed with `error` on attempt
+    /// number `attempt` (starting at 1) should be sent again.
+    pub fn should_retry(
+        &self,
+        method: Method,
+        error: &Error,
+        attempt: u32,
+    ) -> bool {
+        attempt < self.max_attempts && self.methods.contains(&method) && self.is_transient(error)
+    }
+
+    /// Whether `error` is one of the failures this policy retries,
+    /// regardless of the method and the attempts made so far.
+    pub fn is_transient(
+        &self,
+        error: &Error,
+    ) -> bool {
+        match error {
+            Error::ApiError { error } => self.status_codes.contains(&error.status.code()),
+            Error::HttpError { response } => self.status_codes.contains(&response.status.code()),
+            Error::RequestFailed { .. } => self.retry_transport_errors,
+            _ => false,
+        }
+    }
+
+    /// Whether waiting `backoff` after `elapsed` since the first attempt
+    /// stays within [max_elapsed](Self::max_elapsed).
+    pub fn within_deadline(
+        &self,
+        elapsed: Duration,
+        backoff: Duration,
+    ) -> bool {
+        self.max_elapsed
+            .is_none_or(|max_elapsed| elapsed + backoff <= max_elapsed)
+    }
+
+    /// The wait after attempt number `attempt` (starting at 1) failed with `error`:
+    /// the `Retry-After` of the response if there was one, otherwise the
+    /// [backoff()](Self::backoff()) with [jitter](Self::jitter) applied.
+    pub fn delay(
+        &self,
+        attempt: u32,
+        error: &Error,
+    ) -> Duration {
+        match error {
+            _ if self.honor_retry_after && error.retry_after().is_some() => {
+                error.retry_after().unwrap_or_default()
+            }
+            _ if self.jitter => {
+                let backoff = self.backoff(attempt);
+                backoff / 2 + backoff.mul_f64(random_fraction() / 2.0)
+            }
+            _ => self.backoff(attempt),
+        }
+    }
+
+    /// The wait after attempt number `attempt` (starting at 1) failed.
+    pub fn backoff(
+        &self,
+        attempt: u32,
+    ) -> Duration {
+        let factor = self
+            .multiplier
+            .checked_pow(attempt.saturating_sub(1))
+            .unwrap_or(u32::MAX);
+        self.initial_backoff
+            .checked_mul(factor)
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff))
+    }
+}
+
+/// A [TClient] that repeats failed requests of another client
+/// according to a [RetryPolicy].
+pub struct Retrying<C> {
+    inner: C,
+    policy: RetryPolicy,
+    /// When the wait before the next attempt of a request ends.
+    waiting_until: Mutex<Option<Instant>>,
+}
+
+impl<C: TClient> Retrying<C> {
+    pub fn new(
+        inner: C,
+        policy: RetryPolicy,
+    ) -> Self {
+        Retrying {
+            inner,
+            policy,
+            waiting_until: Mutex::default(),
+        }
+    }
+
+    pub fn policy(&self) -> &RetryPolicy {
+        &self.policy
+    }
+
+    /// Sends the request made by `request` until it succeeds
+    /// or the policy gives up.
+    async fn send<F, Fut>(
+        &self,
+        method: Method,
+        #[cfg_attr(not(feature = "tracing"), allow(unused_variables))] uri: &str,
+        request: F,
+    ) -> Result<Object>
+    where
+        F: Fn() -> Fut + Send,
+        Fut: Future<Output = Result<Object>> + Send,
+    {
+        let start = Instant::now();
+        let mut attempt = 1;
+        loop {
+            let result = request().await;
+
+            match result {
+                Err(error) if self.policy.should_retry(method, &error, attempt) => {
+                    let backoff = self.policy.delay(attempt, &error);
+                    if !self.policy.within_deadline(start.elapsed(), backoff) {
+                        return Err(error);
+                    }
+                    debug!(%method, %uri, attempt, ?backoff, %error, "Retrying request");
+                    self.wait(backoff).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn wait(
+        &self,
+        backoff: Duration,
+    ) {
+        let until = Instant::now() + backoff;
+        {
+            let mut waiting_until = self.waiting_until.lock().unwrap();
+            *waiting_until = Some(waiting_until.map_or(until, |other| other.max(until)));
+        }
+        sleep(backoff).await;
+
+        let mut waiting_until = self.waiting_until.lock().unwrap();
+        if waiting_until.is_some_and(|other| other <= Instant::now()) {
+            *waiting_until = None;
+        }
+    }
+}
+
+#[async_trait]
+impl<C: TClient + Send + Sync> TClient for Retrying<C> {
+    fn api_version(&self) -> ApiVersion {
+        self.inner.api_version()
+    }
+
+    /// The status of the inner client, with the [backoff](RateLimitStatus::backoff)
+    /// of the request that waits longest for its next attempt.
+    fn rate_limit_status(&self) -> Option<RateLimitStatus> {
+        let backoff = self
+            .waiting_until
+            .lock()
+            .unwrap()
+            .and_then(|until| until.checked_duration_since(Instant::now()))
+            .filter(|backoff| !backoff.is_zero());
+        match (self.inner.rate_limit_status(), backoff) {
+            (None, None) => None,
+            (status, backoff) => Some(RateLimitStatus {
+                backoff,
+                ..status.unwrap_or_default()
+            }),
+        }
+    }
+
+    async fn get<S: Into<String> + Send>(
+        &self,
+        uri: S,
+    ) -> Result<Object> {
+        let uri = uri.into();
+        self.send(Method::Get, &uri, || self.inner.get(uri.clone()))
+            .await
+    }
+
+    async fn get_fresh<S: Into<String> + Send>(
+        &self,
+        uri: S,
+    ) -> Result<Object> {
+        let uri = uri.into();
+        self.send(Method::Get, &uri, || self.inner.get_fresh(uri.clone()))
+            .await
+    }
+
+    async fn post<S: Into<String> + Send>(
+        &self,
+        uri: S,
+    ) -> Result<Object> {
+        let uri = uri.into();
+        self.send(Method::Post, &uri, || self.inner.post(uri.clone()))
+            .await
+    }
+
+    async fn post_json<S: Into<String> + Send>(
+        &self,
+        uri: S,
+        body: Vec<u8>,
+    ) -> Result<Object> {
+        let uri = uri.into();
+        self.send(Method::Post, &uri, || {
+            self.inner.post_json(uri.clone(), body.clone())
+        })
+        .await
+    }
+
+    async fn patch_json<S: Into<String> + Send>(
+        &self,
+        uri: S,
+        body: Vec<u8>,
+    ) -> Result<Object> {
+        let uri = uri.into();
+        self.send(Method::Patch, &uri, || {
+            self.inner.patch_json(uri.clone(), body.clone())
+        })
+        .await
+    }
+
+    async fn post_multipart<S: Into<String> + Send>(
+        &self,
+        uri: S,
+        form: MultipartForm,
+    ) -> Result<Object> {
+        let uri = uri.into();
+        self.send(Method::Post, &uri, || {
+            self.inner.post_multipart(uri.clone(), form.clone())
+        })
+        .await
+    }
+
+    async fn delete<S: Into<String> + Send>(
+        &self,
+        uri: S,
+    ) -> Result<Object> {
+        let uri = uri.into();
+        self.send(Method::Delete, &uri, || self.inner.delete(uri.clone()))
+            .await
+    }
+}
+
+/// The wait of a `Retry-After` header, which Notion sends in whole seconds.
+/// HTTP dates are not supported.
+pub(crate) fn parse_retry_after(value: &str) -> Option<Duration> {
+    value.trim().parse().ok().map(Duration::from_secs)
+}
+
+/// A number in `[0, 1)`, random enough to spread retries.
+fn random_fraction() -> f64 {
+    use std::collections::hash_map::RandomState;
+    use std::hash::{BuildHasher, Hasher};
+
+    let bits = RandomState::new().build_hasher().finish() >> 11;
+    bits as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub(crate) use std::time::Instant;
+/// `std::time::Instant` panics in browsers.
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+pub(crate) use web_time::Instant;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration).await
+}
+
+/// Browser timers are JS promises, which aren't `Send`,
+/// but browsers poll them on the thread that created them.
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+pub(crate) async fn sleep(duration: Duration) {
+    send_wrapper::SendWrapper::new(gloo_timers::future::sleep(duration)).await
+}
+
+/// The wasi client blocks on its requests as well.
+#[cfg(target_os = "wasi")]
+pub(crate) async fn sleep(duration: Duration) {
+    std::thread::sleep(duration)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_retry_after, Method, RetryPolicy};
+    use crate::models::error::{ErrorCode, ErrorResponse, StatusCode};
+    use crate::test_util::{MockNotion, PageFixture, MOCK_API_TOKEN};
+    use crate::{Client, Error, NotionApi, Retrying};
+    use serde_json::json;
+    use std::time::Duration;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    fn api_error(status: u16) -> Error {
+        Error::ApiError {
+            error: ErrorResponse {
+                status: StatusCode::from(status),
+                code: ErrorCode::Unknown,
+                message: String::new(),
+                request_id: None,
+                developer_survey: None,
+                additional_data: None,
+                retry_after: None,
+            },
+        }
+    }
+
+    #[test]
+    fn default_policy() {
+        let policy = RetryPolicy::default();
+
+        assert!(policy.should_retry(Method::Get, &api_error(503), 1));
+        assert!(policy.should_retry(Method::Get, &api_error(429), 2));
+        assert!(!policy.should_retry(Method::Get, &api_error(429), 3));
+        assert!(!policy.should_retry(Method::Get, &api_error(400), 1));
+        assert!(!policy.should_retry(Method::Patch, &api_error(503), 1));
+        assert!(!RetryPolicy::never().should_retry(Method::Get, &api_error(503), 1));
+    }
+
+    #[test]
+    fn exponential_backoff() {
+        let policy = RetryPolicy {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(5),
+            ..RetryPolicy::default()
+        };
+
+        assert_eq!(policy.backoff(1), Duration::from_secs(1));
+        assert_eq!(policy.backoff(2), Duration::from_secs(2));
+        assert_eq!(policy.backoff(3), Duration::from_secs(4));
+        assert_eq!(policy.backoff(4), Duration::from_secs(5));
+        assert_eq!(policy.backoff(100), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn retry_after_and_jitter() {
+        let policy = RetryPolicy {
+            initial_backoff: Duration::from_secs(4),
+            ..RetryPolicy::default()
+        };
+        let mut rate_limited = api_error(429);
+        if let Error::ApiError { error } = &mut rate_limited {
+            error.retry_after = parse_retry_after(" 60");
+        }
+
+        assert_eq!(policy.delay(1, &rate_limited), Duration::from_secs(60));
+        assert_eq!(policy.delay(1, &api_error(503)), Duration::from_secs(4));
+        let ignoring = RetryPolicy {
+            honor_retry_after: false,
+            ..policy.clone()
+        };
+        assert_eq!(ignoring.delay(1, &rate_limited), Duration::from_secs(4));
+
+        let jittered = RetryPolicy {
+            jitter: true,
+            ..policy
+        };
+        for _ in 0..20 {
+            let delay = jittered.delay(1, &api_error(503));
+            assert!(delay >= Duration::from_secs(2) && delay <= Duration::from_secs(4));
+        }
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), None);
+    }
+
+    #[test]
+    fn deadline() {
+        let policy = RetryPolicy {
+            max_elapsed: Some(Duration::from_secs(30)),
+            ..RetryPolicy::default()
+        };
+
+        assert!(policy.within_deadline(Duration::from_secs(20), Duration::from_secs(10)));
+        assert!(!policy.within_deadline(Duration::from_secs(25), Duration::from_secs(10)));
+        assert!(RetryPolicy::default().within_deadline(Duration::MAX / 2, Duration::from_secs(30)));
+    }
+
+    #[tokio::test]
+    async fn retries_transient_errors_of_safe_methods() {
+        let mock = MockNotion::start().await;
+        let page = PageFixture::new().build();
+        let unavailable = || {
+            ResponseTemplate::new(503).set_body_json(json!({
+                "object": "error",
+                "status": 503,
+                "code": "service_unavailable",
+                "message": "Unavailable",
+            }))
+        };
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/pages/{}", page.id)))
+            .respond_with(unavailable())
+            .up_to_n_times(1)
+            .with_priority(1)
+            .mount(mock.server())
+            .await;
+        mock.mount_page(&page).await;
+        Mock::given(method("PATCH"))
+            .respond_with(unavailable())
+            .expect(1)
+            .mount(mock.server())
+            .await;
+
+        let client = Client::new(MOCK_API_TOKEN.to_string()).unwrap();
+        let policy = RetryPolicy {
+            initial_backoff: std::time::Duration::ZERO,
+            ..RetryPolicy::default()
+        };
+        let api =
+            NotionApi::with_client(Retrying::new(client, policy)).with_base_url(mock.base_url());
+        assert_eq!(api.get_page(&page.id).await.unwrap(), page);
+        assert!(matches!(
+            api.set_checkbox(&page.id, "Done", true).await,
+            Err(Error::ApiError { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn retries_honor_retry_after() {
+        let mock = MockNotion::start().await;
+        let page = PageFixture::new().build();
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/pages/{}", page.id)))
+            .respond_with(
+                ResponseTemplate::new(429)
+                    .insert_header("Retry-After", "0")
+                    .set_body_json(json!({
+                        "object": "error",
+                        "status": 429,
+                        "code": "rate_limited",
+                        "message": "Slow down."
+                    })),
+            )
+            .up_to_n_times(2)
+            .with_priority(1)
+            .mount(mock.server())
+            .await;
+        mock.mount_page(&page).await;
+
+        match mock.api().get_page(&page.id).await {
+            Err(Error::ApiError { error }) => {
+                assert_eq!(error.retry_after, Some(std::time::Duration::ZERO))
+            }
+            result => panic!("Expected to be rate limited, got {:?}", result),
+        }
+
+        let policy = RetryPolicy {
+            initial_backoff: std::time::Duration::from_secs(60),
+            ..RetryPolicy::default()
+        };
+        let api = mock.api().with_retries(policy);
+        let start = std::time::Instant::now();
+        assert_eq!(api.get_page(&page.id).await.unwrap(), page);
+        assert!(start.elapsed() < std::time::Duration::from_secs(10));
+    }
+
+    #[tokio::test]
+    async fn retries_stop_at_the_deadline() {
+        let mock = MockNotion::start().await;
+        let page = PageFixture::new().build();
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/pages/{}", page.id)))
+            .respond_with(ResponseTemplate::new(503).set_body_json(json!({
+                "object": "error",
+                "status": 503,
+                "code": "service_unavailable",
+                "message": "Notion is unavailable."
+            })))
+            .expect(2)
+            .mount(mock.server())
+            .await;
+
+        let client = Client::new(MOCK_API_TOKEN.to_string()).unwrap();
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            initial_backoff: std::time::Duration::from_millis(50),
+            multiplier: 100,
+            max_elapsed: Some(std::time::Duration::from_secs(1)),
+            ..RetryPolicy::default()
+        };
+        let api =
+            NotionApi::with_client(Retrying::new(client, policy)).with_base_url(mock.base_url());
+        let start = std::time::Instant::now();
+        assert!(matches!(
+            api.get_page(&page.id).await,
+            Err(Error::ApiError { .. })
+        ));
+        assert!(start.elapsed() < std::time::Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+
+use serde_json::json;
+
+use crate::ids::{AsIdentifier, DatabaseId};
+use crate::models::properties::PropertyConfiguration;
+use crate::models::schema::{diff_schema, SchemaDiff};
+use crate::models::{Database, DatabaseCreateRequest, DatabaseUpdateRequest, Object};
+use crate::{ApiVersion, Error, NotionApi, Result, TClient};
+
+impl<C: TClient> NotionApi<C> {
+    /// Creates a database in a page and returns it.
+    ///
+    /// Relations are sent in the shape of the client's API version. From 2025-09-03 on
+    /// the properties become the schema of the database's first data source.
+    pub async fn create_database(
+        &self,
+        request: DatabaseCreateRequest,
+    ) -> Result<Database> {
+        let version = self.api_version();
+        let request = DatabaseCreateRequest {
+            properties: request.properties.for_version(version),
+            ..request
+        };
+        let mut body = serde_json::to_value(&request).unwrap();
+        if version >= ApiVersion::V2025_09_03 {
+            if let Some(properties) = body
+                .as_object_mut()
+                .and_then(|body| body.remove("properties"))
+            {
+                body["initial_data_source"] = json!({ "properties": properties });
+            }
+        }
+
+        let uri = format!("{}/databases", self.base_url);
+        let result = self
+            .client
+            .post_json(uri, serde_json::to_vec(&body).unwrap())
+            .await?;
+
+        match result {
+            Object::Database { database } => Ok(database),
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
+
+    /// Changes the title, description or properties of a database and returns it.
+    ///
+    /// From API version 2025-09-03 on the properties belong to data sources,
+    /// and changes to them are rejected here.
+    pub async fn update_database<D, T>(
+        &self,
+        database_id: D,
+        update: T,
+    ) -> Result<Database>
+    where
+        D: AsIdentifier<DatabaseId>,
+        T: Into<DatabaseUpdateRequest>,
+    {
+        let update = update.into().for_version(self.api_version());
+        let uri = format!("{}/databases/{}", self.base_url, database_id.as_id());
+        let result = self
+            .client
+            .patch_json(uri, serde_json::to_vec(&update).unwrap())
+            .await?;
+
+        match result {
+            Object::Database { database } => Ok(database),
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
+
+    /// Adds the properties and select options of `desired` that the database lacks,
+    /// in a single update, and returns what was added.
+    ///
+    /// Destructive differences, such as properties missing from `desired` or with
+    /// another type, are not applied but reported in [SchemaDiff::conflicts].
+    /// Nothing is written when nothing is missing.
+    ///
+    /// The schema is read from [Database::properties](crate::models::Database::properties),
+    /// which is empty from API version 2025-09-03 on.
+    pub async fn ensure_database_schema<D: AsIdentifier<DatabaseId>>(
+        &self,
+        database_id: D,
+        desired: &HashMap<String, PropertyConfiguration>,
+    ) -> Result<SchemaDiff> {
+        let database = self.get_database(database_id).await?;
+        let diff = diff_schema(&database.properties, desired);
+        if diff.is_empty() {
+            return Ok(diff);
+        }
+
+        let update = json!({ "properties": diff.update(&database.properties) });
+        let uri = format!("{}/databases/{}", self.base_url, database.id);
+        let result = self
+            .client
+            .patch_json(uri, serde_json::to_vec(&update).unwrap())
+            .await?;
+
+        match result {
+            Object::Database { .. } => Ok(diff),
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
+}
+
+#[cfg(all(test, not(target_os = "wasi")))]
+mod tests {
+    use crate::ids::PropertyId;
+    use crate::models::properties::definitions::DatabaseSchema;
+    use crate::models::properties::PropertyConfiguration;
+    use crate::models::schema::SchemaConflict;
+    use crate::models::{DatabaseCreateRequest, DatabaseUpdateRequest, Object};
+    use crate::test_util::{DatabaseFixture, MockNotion, PageFixture, MOCK_API_TOKEN};
+    use crate::{ApiVersion, Client, NotionApi};
+    use serde_json::json;
+    use std::str::FromStr;
+    use wiremock::matchers::{body_json, body_partial_json, header, method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    #[tokio::test]
+    async fn create_and_update_database() {
+        let mock = MockNotion::start().await;
+        let parent = PageFixture::new().build();
+        let projects = DatabaseFixture::new().build();
+        let database = DatabaseFixture::new().title("Tasks").build();
+        let schema = DatabaseSchema::new()
+            .title("Name")
+            .select("Status", ["Todo", "Done"])
+            .one_way_relation("Project", projects.id.clone());
+
+        Mock::given(method("POST"))
+            .and(path("/v1/databases"))
+            .and(header("Notion-Version", "2022-06-28"))
+            .and(body_partial_json(json!({
+                "parent": {"type": "page_id", "page_id": parent.id},
+                "is_inline": true,
+                "properties": {
+                    "Name": {"title": {}},
+                    "Status": {"select": {"options": [{"name": "Todo"}, {"name": "Done"}]}},
+                    "Project": {"relation": {
+                        "database_id": projects.id,
+                        "type": "single_property",
+                        "single_property": {},
+                    }},
+                },
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(Object::Database {
+                database: database.clone(),
+            }))
+            .expect(1)
+            .mount(mock.server())
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/databases"))
+            .and(header("Notion-Version", "2025-09-03"))
+            .and(body_partial_json(json!({
+                "initial_data_source": {"properties": {"Name": {"title": {}}}},
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(Object::Database {
+                database: database.clone(),
+            }))
+            .expect(1)
+            .mount(mock.server())
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path(format!("/v1/databases/{}", database.id)))
+            .and(body_json(json!({
+                "properties": {
+                    "Done": {"checkbox": {}},
+                    "Status": null,
+                },
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(Object::Database {
+                database: database.clone(),
+            }))
+            .expect(1)
+            .mount(mock.server())
+            .await;
+
+        for version in [ApiVersion::V2022_06_28, ApiVersion::V2025_09_03] {
+            let client = Client::with_version(MOCK_API_TOKEN.to_string(), version).unwrap();
+            let api = NotionApi::with_client(client).with_base_url(mock.base_url());
+            let request = DatabaseCreateRequest::new(&parent.id, "Tasks", schema.clone()).inline();
+            assert_eq!(api.create_database(request).await.unwrap(), database);
+        }
+
+        let update = DatabaseUpdateRequest::default()
+            .define(DatabaseSchema::new().checkbox("Done"))
+            .remove("Status");
+        assert_eq!(
+            mock.api()
+                .update_database(&database.id, update)
+                .await
+                .unwrap(),
+            database
+        );
+    }
+
+    #[tokio::test]
+    async fn ensure_database_schema_adds_missing_parts() {
+        let mock = MockNotion::start().await;
+        let database = DatabaseFixture::new()
+            .select("Status", &["Todo"])
+            .property(
+                "Done",
+                PropertyConfiguration::Checkbox {
+                    id: PropertyId::from_str("done").unwrap(),
+                },
+            )
+            .build();
+        mock.mount_database(&database).await;
+        Mock::given(method("PATCH"))
+            .and(path(format!("/v1/databases/{}", database.id)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(Object::Database {
+                database: database.clone(),
+            }))
+            .expect(1)
+            .mount(mock.server())
+            .await;
+
+        let desired = DatabaseFixture::new()
+            .select("Status", &["Todo", "Done"])
+            .property(
+                "Notes",
+                PropertyConfiguration::Text {
+                    id: PropertyId::from_str("notes").unwrap(),
+                },
+            )
+            .build()
+            .properties;
+        let diff = mock
+            .api()
+            .ensure_database_schema(&database.id, &desired)
+            .await
+            .unwrap();
+        assert_eq!(
+            diff.conflicts,
+            vec![SchemaConflict::ExtraProperty {
+                property: "Done".to_string()
+            }]
+        );
+
+        let requests = mock.server().received_requests().await.unwrap();
+        let update: serde_json::Value = serde_json::from_slice(&requests[1].body).unwrap();
+        assert_eq!(update["properties"]["Notes"], json!({"rich_text": {}}));
+        assert_eq!(
+            update["properties"]["Status"]["select"]["options"][1],
+            json!({"name": "Done", "color": "default"})
+        );
+        assert!(update["properties"].get("Done").is_none());
+    }
+}
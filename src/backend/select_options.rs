@@ -0,0 +1,188 @@
+use serde_json::json;
+
+use crate::ids::{DatabaseId, PageId};
+use crate::models::properties::{
+    Color, PropertyConfiguration, PropertyValue, SelectOption, SelectOptionId,
+};
+use crate::models::schema::SchemaDiff;
+use crate::models::{Object, Parent, Properties};
+use crate::{Error, NotionApi, Result, TClient};
+
+impl<C: TClient> NotionApi<C> {
+    /// Adds select and multi-select options that don't exist yet to the database
+    /// before [create_page()](Self::create_page()) and [update_page()](Self::update_page())
+    /// write them, giving them `color`.
+    ///
+    /// Without this Notion creates unknown options with a color of its choosing.
+    /// Each write that selects options by name reads the database schema first,
+    /// and updates of pages in a database read the page too.
+    /// Options of status properties can't be added through the API and are left alone.
+    pub fn create_missing_options(
+        mut self,
+        color: Color,
+    ) -> Self {
+        self.option_color = Some(color);
+        self
+    }
+
+    /// Adds the options `properties` selects by name that the database lacks.
+    pub(crate) async fn add_missing_options(
+        &self,
+        database_id: &DatabaseId,
+        properties: &Properties,
+        color: Color,
+    ) -> Result<()> {
+        if !selects_by_name(properties) {
+            return Ok(());
+        }
+
+        let database = self.get_database(database_id).await?;
+        let mut diff = SchemaDiff::default();
+        for (property, value) in &properties.properties {
+            let options = match database.properties.get(property) {
+                Some(PropertyConfiguration::Select { select, .. })
+                | Some(PropertyConfiguration::MultiSelect {
+                    multi_select: select,
+                    ..
+                }) => &select.options,
+                _ => continue,
+            };
+            let mut missing: Vec<SelectOption> = vec![];
+            for name in named(value) {
+                let known = options
+                    .iter()
+                    .chain(&missing)
+                    .any(|option| option.name == name);
+                if !known {
+                    missing.push(SelectOption {
+                        name: name.to_string(),
+                        // Not sent, Notion assigns the id.
+                        id: SelectOptionId::from(name.to_string()),
+                        color,
+                    });
+                }
+            }
+            if !missing.is_empty() {
+                diff.missing_options.insert(property.clone(), missing);
+            }
+        }
+        if diff.is_empty() {
+            return Ok(());
+        }
+
+        let update = json!({ "properties": diff.update(&database.properties) });
+        let uri = format!("{}/databases/{}", self.base_url, database.id);
+        let result = self
+            .client
+            .patch_json(uri, serde_json::to_vec(&update).unwrap())
+            .await?;
+
+        match result {
+            Object::Database { .. } => {
+                debug!(database = %database.id, options = ?diff.missing_options.keys(), "Added select options");
+                Ok(())
+            }
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
+
+    /// Like [add_missing_options()](Self::add_missing_options()) for the database
+    /// of the page `page_id`, which is fetched only if `properties` selects options by name.
+    pub(crate) async fn add_missing_options_of_page(
+        &self,
+        page_id: &PageId,
+        properties: &Properties,
+        color: Color,
+    ) -> Result<()> {
+        if !selects_by_name(properties) {
+            return Ok(());
+        }
+
+        match self.get_page(page_id).await?.parent {
+            Parent::Database { database_id } => {
+                self.add_missing_options(&database_id, properties, color)
+                    .await
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+fn selects_by_name(properties: &Properties) -> bool {
+    properties
+        .properties
+        .values()
+        .any(|value| !named(value).is_empty())
+}
+
+/// The names of the select and multi-select options `value` selects by name only.
+fn named(value: &PropertyValue) -> Vec<&str> {
+    let selected = match value {
+        PropertyValue::Select {
+            select: Some(selected),
+            ..
+        } => std::slice::from_ref(selected),
+        PropertyValue::MultiSelect {
+            multi_select: Some(selected),
+            ..
+        } => selected.as_slice(),
+        _ => &[],
+    };
+    selected
+        .iter()
+        .filter(|value| value.id.is_none())
+        .filter_map(|value| value.name.as_deref())
+        .collect()
+}
+
+#[cfg(all(test, not(target_os = "wasi")))]
+mod tests {
+    use crate::models::properties::Color;
+    use crate::models::{Object, PageCreateRequest};
+    use crate::test_util::{DatabaseFixture, MockNotion, PageFixture};
+    use serde_json::json;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    #[tokio::test]
+    async fn create_missing_select_options() {
+        let mock = MockNotion::start().await;
+        let database = DatabaseFixture::new().select("Status", &["Todo"]).build();
+        mock.mount_database(&database).await;
+        Mock::given(method("PATCH"))
+            .and(path(format!("/v1/databases/{}", database.id)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(Object::Database {
+                database: database.clone(),
+            }))
+            .expect(1)
+            .mount(mock.server())
+            .await;
+        let page = PageFixture::new()
+            .database(&database.id.to_string())
+            .title("Import")
+            .select("Status", "Done")
+            .build();
+        mock.mount_page_writes(&page).await;
+        mock.mount_page(&page).await;
+
+        let api = mock.api().create_missing_options(Color::Blue);
+        api.create_page(PageCreateRequest::new(
+            page.parent.clone(),
+            page.properties.clone(),
+        ))
+        .await
+        .unwrap();
+
+        let requests = mock.server().received_requests().await.unwrap();
+        let update: serde_json::Value = serde_json::from_slice(&requests[1].body).unwrap();
+        assert_eq!(
+            update["properties"]["Status"]["select"]["options"][1],
+            json!({"name": "Done", "color": "blue"})
+        );
+        assert_eq!(requests[2].url.path(), "/v1/pages");
+
+        // Options the database already has leave the schema alone.
+        let todo = PageFixture::new().select("Status", "Todo").build();
+        api.update_page(&page.id, todo.properties).await.unwrap();
+    }
+}
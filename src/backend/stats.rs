@@ -0,0 +1,436 @@
+use std::collections::{HashMap, VecDeque};
+use std::convert::TryFrom;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::retry::Instant;
+use crate::Client;
+
+/// The timing and size of a request sent by the [Client](crate::Client),
+/// passed to the callback set with [ClientBuilder::on_request()](crate::ClientBuilder::on_request()).
+///
+/// Neither backend reports DNS resolution and connection times separately;
+/// they are part of [time_to_headers](Self::time_to_headers) and [elapsed](Self::elapsed).
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct RequestStats {
+    pub method: String,
+    /// The path of the request with ids replaced by `{id}`, such as `/v1/pages/{id}`,
+    /// so requests to the same endpoint can be grouped.
+    pub endpoint: String,
+    /// The HTTP status, `None` if the request failed before a response was received.
+    pub status: Option<u16>,
+    pub request_bytes: usize,
+    pub response_bytes: usize,
+    /// Until the response headers were received, `None` with the wasi backend,
+    /// which reads the headers and the body in one go.
+    pub time_to_headers: Option<Duration>,
+    /// Until the response body was read.
+    pub elapsed: Duration,
+}
+
+impl RequestStats {
+    /// The stats of a request that is about to be sent to `url`.
+    pub(crate) fn new(
+        method: &str,
+        url: &str,
+        request_bytes: usize,
+    ) -> Self {
+        RequestStats {
+            method: method.to_string(),
+            endpoint: endpoint(url),
+            status: None,
+            request_bytes,
+            response_bytes: 0,
+            time_to_headers: None,
+            elapsed: Duration::ZERO,
+        }
+    }
+}
+
+/// The requests sent to one endpoint, returned by [Client::stats()](crate::Client::stats()).
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct EndpointStats {
+    pub method: String,
+    /// See [RequestStats::endpoint].
+    pub endpoint: String,
+    pub requests: u64,
+    /// The time spent on all requests, see [RequestStats::elapsed].
+    pub total: Duration,
+    pub max: Duration,
+    pub request_bytes: u64,
+    pub response_bytes: u64,
+}
+
+impl EndpointStats {
+    pub fn mean(&self) -> Duration {
+        match u32::try_from(self.requests) {
+            Ok(0) => Duration::ZERO,
+            Ok(requests) => self.total / requests,
+            Err(_) => self.total.div_f64(self.requests as f64),
+        }
+    }
+
+    fn record(
+        &mut self,
+        stats: &RequestStats,
+    ) {
+        self.requests += 1;
+        self.total += stats.elapsed;
+        self.max = self.max.max(stats.elapsed);
+        self.request_bytes += stats.request_bytes as u64;
+        self.response_bytes += stats.response_bytes as u64;
+    }
+}
+
+/// How close a client is to the rate limit, returned by [Client::rate_limit_status()]
+/// and [NotionApi::rate_limit_status()](crate::NotionApi::rate_limit_status()).
+///
+/// Notion allows an average of three requests per second per integration
+/// and answers `429` above that.
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct RateLimitStatus {
+    /// Requests sent within the last [window](Self::window).
+    pub recent_requests: usize,
+    pub window: Duration,
+    /// Responses with status `429` since the client was created or its stats were reset.
+    pub rate_limited: u64,
+    /// How long ago the last `429` was received.
+    pub since_rate_limited: Option<Duration>,
+    /// The remaining wait of a [Retrying](crate::Retrying) client before its next attempt,
+    /// `None` if it isn't waiting.
+    pub backoff: Option<Duration>,
+}
+
+impl RateLimitStatus {
+    /// The average rate of the requests within the [window](Self::window).
+    pub fn requests_per_second(&self) -> f64 {
+        if self.window.is_zero() {
+            return 0.0;
+        }
+        self.recent_requests as f64 / self.window.as_secs_f64()
+    }
+
+    pub fn is_backing_off(&self) -> bool {
+        self.backoff.is_some()
+    }
+}
+
+/// How far back [RateLimitStatus::recent_requests] looks.
+const RATE_WINDOW: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Default)]
+struct RateWindow {
+    sent: VecDeque<Instant>,
+    rate_limited: u64,
+    last_rate_limited: Option<Instant>,
+}
+
+impl RateWindow {
+    fn record(
+        &mut self,
+        now: Instant,
+        status: Option<u16>,
+    ) {
+        self.sent.push_back(now);
+        self.expire(now);
+        if status == Some(429) {
+            self.rate_limited += 1;
+            self.last_rate_limited = Some(now);
+        }
+    }
+
+    fn expire(
+        &mut self,
+        now: Instant,
+    ) {
+        while self
+            .sent
+            .front()
+            .is_some_and(|sent| now.duration_since(*sent) > RATE_WINDOW)
+        {
+            self.sent.pop_front();
+        }
+    }
+
+    fn status(
+        &mut self,
+        now: Instant,
+    ) -> RateLimitStatus {
+        self.expire(now);
+        RateLimitStatus {
+            recent_requests: self.sent.len(),
+            window: RATE_WINDOW,
+            rate_limited: self.rate_limited,
+            since_rate_limited: self.last_rate_limited.map(|last| now.duration_since(last)),
+            backoff: None,
+        }
+    }
+}
+
+/// The callback set with [ClientBuilder::on_request()](crate::ClientBuilder::on_request()).
+#[derive(Clone)]
+pub(crate) struct OnRequest(pub(crate) Arc<dyn Fn(&RequestStats) + Send + Sync>);
+
+impl fmt::Debug for OnRequest {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        f.write_str("OnRequest")
+    }
+}
+
+/// Shared by the clones of a client.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Stats {
+    endpoints: Arc<Mutex<HashMap<(String, String), EndpointStats>>>,
+    rate: Arc<Mutex<RateWindow>>,
+    on_request: Option<OnRequest>,
+}
+
+impl Stats {
+    pub(crate) fn new(on_request: Option<OnRequest>) -> Self {
+        Stats {
+            endpoints: Arc::default(),
+            rate: Arc::default(),
+            on_request,
+        }
+    }
+
+    pub(crate) fn record(
+        &self,
+        stats: RequestStats,
+    ) {
+        if let Some(on_request) = &self.on_request {
+            (on_request.0)(&stats);
+        }
+        self.rate
+            .lock()
+            .unwrap()
+            .record(Instant::now(), stats.status);
+        self.endpoints
+            .lock()
+            .unwrap()
+            .entry((stats.method.clone(), stats.endpoint.clone()))
+            .or_insert_with(|| EndpointStats {
+                method: stats.method.clone(),
+                endpoint: stats.endpoint.clone(),
+                ..EndpointStats::default()
+            })
+            .record(&stats);
+    }
+
+    /// Sorted by the total time spent, the slowest endpoint first.
+    pub(crate) fn snapshot(&self) -> Vec<EndpointStats> {
+        let mut endpoints: Vec<_> = self.endpoints.lock().unwrap().values().cloned().collect();
+        endpoints.sort_by(|a, b| {
+            b.total
+                .cmp(&a.total)
+                .then_with(|| a.endpoint.cmp(&b.endpoint))
+                .then_with(|| a.method.cmp(&b.method))
+        });
+        endpoints
+    }
+
+    pub(crate) fn rate_limit_status(&self) -> RateLimitStatus {
+        self.rate.lock().unwrap().status(Instant::now())
+    }
+
+    pub(crate) fn reset(&self) {
+        self.endpoints.lock().unwrap().clear();
+        *self.rate.lock().unwrap() = RateWindow::default();
+    }
+}
+
+impl Client {
+    /// The requests sent by this client and its clones, summed up by endpoint
+    /// and sorted by the total time spent, the slowest endpoint first.
+    pub fn stats(&self) -> Vec<EndpointStats> {
+        self.stats.snapshot()
+    }
+
+    /// The requests sent by this client and its clones within the last seconds
+    /// and the `429` responses they received.
+    pub fn rate_limit_status(&self) -> RateLimitStatus {
+        self.stats.rate_limit_status()
+    }
+
+    /// Forgets the requests counted by [stats()](Self::stats())
+    /// and [rate_limit_status()](Self::rate_limit_status()).
+    pub fn reset_stats(&self) {
+        self.stats.reset()
+    }
+}
+
+/// The path of `url` without the query, with ids replaced by `{id}`.
+pub(crate) fn endpoint(url: &str) -> String {
+    let path = url
+        .split_once("://")
+        .map_or(url, |(_, rest)| rest.find('/').map_or("/", |i| &rest[i..]));
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    path.split('/')
+        .map(|segment| if is_id(segment) { "{id}" } else { segment })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn is_id(segment: &str) -> bool {
+    let digits = segment.chars().filter(|c| *c != '-').count();
+    digits == 32 && segment.chars().all(|c| c == '-' || c.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{endpoint, EndpointStats, RateWindow, RequestStats, Stats, RATE_WINDOW};
+    use crate::test_util::{person, MockNotion, PageFixture, MOCK_API_TOKEN};
+    use crate::{Client, NotionApi, RetryPolicy, Retrying};
+    use serde_json::json;
+    use std::time::{Duration, Instant};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    #[test]
+    fn endpoints_replace_ids() {
+        assert_eq!(
+            endpoint("https://api.notion.com/v1/pages/b55c9c91-384d-452b-81db-d1ef79372b75"),
+            "/v1/pages/{id}"
+        );
+        assert_eq!(
+            endpoint("http://127.0.0.1:8080/v1/blocks/b55c9c91384d452b81dbd1ef79372b75/children?page_size=100"),
+            "/v1/blocks/{id}/children"
+        );
+        assert_eq!(
+            endpoint("https://api.notion.com/v1/users/me"),
+            "/v1/users/me"
+        );
+    }
+
+    #[test]
+    fn stats_group_by_endpoint() {
+        let stats = Stats::default();
+        for (endpoint, millis) in [
+            ("/v1/pages/{id}", 30),
+            ("/v1/search", 200),
+            ("/v1/pages/{id}", 90),
+        ] {
+            stats.record(RequestStats {
+                method: "GET".to_string(),
+                endpoint: endpoint.to_string(),
+                status: Some(200),
+                request_bytes: 0,
+                response_bytes: 1000,
+                time_to_headers: None,
+                elapsed: Duration::from_millis(millis),
+            });
+        }
+
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot[0].endpoint, "/v1/search");
+        assert_eq!(
+            snapshot[1],
+            EndpointStats {
+                method: "GET".to_string(),
+                endpoint: "/v1/pages/{id}".to_string(),
+                requests: 2,
+                total: Duration::from_millis(120),
+                max: Duration::from_millis(90),
+                request_bytes: 0,
+                response_bytes: 2000,
+            }
+        );
+        assert_eq!(snapshot[1].mean(), Duration::from_millis(60));
+    }
+
+    #[test]
+    fn rate_window_forgets_old_requests() {
+        let start = Instant::now();
+        let mut rate = RateWindow::default();
+        rate.record(start, Some(200));
+        rate.record(start + Duration::from_secs(2), Some(429));
+        rate.record(start + Duration::from_secs(3), None);
+
+        let status = rate.status(start + Duration::from_secs(4));
+        assert_eq!(status.recent_requests, 3);
+        assert_eq!(status.rate_limited, 1);
+        assert_eq!(status.since_rate_limited, Some(Duration::from_secs(2)));
+        assert_eq!(status.requests_per_second(), 0.3);
+
+        let later = rate.status(start + RATE_WINDOW + Duration::from_secs(1));
+        assert_eq!(later.recent_requests, 2);
+        assert_eq!(later.rate_limited, 1);
+    }
+
+    #[tokio::test]
+    async fn rate_limit_status_reports_backoff() {
+        let mock = MockNotion::start().await;
+        let page = PageFixture::new().build();
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/pages/{}", page.id)))
+            .respond_with(ResponseTemplate::new(429).set_body_json(json!({
+                "object": "error",
+                "status": 429,
+                "code": "rate_limited",
+                "message": "Slow down."
+            })))
+            .up_to_n_times(1)
+            .with_priority(1)
+            .mount(mock.server())
+            .await;
+        mock.mount_page(&page).await;
+
+        let client = Client::new(MOCK_API_TOKEN.to_string()).unwrap();
+        let policy = RetryPolicy {
+            initial_backoff: std::time::Duration::from_millis(300),
+            ..RetryPolicy::default()
+        };
+        let api =
+            NotionApi::with_client(Retrying::new(client, policy)).with_base_url(mock.base_url());
+        let (fetched, during) = tokio::join!(api.get_page(&page.id), async {
+            tokio::time::sleep(std::time::Duration::from_millis(150)).await;
+            api.rate_limit_status().unwrap()
+        });
+        fetched.unwrap();
+        assert!(during.is_backing_off());
+        assert_eq!(during.rate_limited, 1);
+
+        let after = api.rate_limit_status().unwrap();
+        assert_eq!(after.recent_requests, 2);
+        assert_eq!(after.rate_limited, 1);
+        assert_eq!(after.backoff, None);
+    }
+
+    #[tokio::test]
+    async fn client_reports_request_stats() {
+        let mock = MockNotion::start().await;
+        let ada = person("Ada", "ada@example.com");
+        mock.mount_user(&ada).await;
+
+        let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::<RequestStats>::new()));
+        let client = Client::builder(MOCK_API_TOKEN)
+            .on_request({
+                let seen = seen.clone();
+                move |stats| seen.lock().unwrap().push(stats.clone())
+            })
+            .build()
+            .unwrap();
+        let api = NotionApi::with_client(client.clone()).with_base_url(mock.base_url());
+        api.get_user(ada.id()).await.unwrap();
+        api.get_user(ada.id()).await.unwrap();
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 2);
+        assert_eq!(seen[0].method, "GET");
+        assert_eq!(seen[0].endpoint, "/v1/users/{id}");
+        assert_eq!(seen[0].status, Some(200));
+        assert!(seen[0].response_bytes > 0);
+        assert!(seen[0].time_to_headers.unwrap() <= seen[0].elapsed);
+
+        let stats = client.stats();
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].requests, 2);
+        assert_eq!(stats[0].response_bytes, 2 * seen[0].response_bytes as u64);
+        client.reset_stats();
+        assert!(client.stats().is_empty());
+    }
+}
@@ -0,0 +1,585 @@
+use crate::ids::{BlockId, DatabaseId, PageId};
+use crate::models::block::BlockAppendRequest;
+use crate::models::search::{
+    DatabaseQuery, FilterCondition, InvalidQuery, NotionSearch, PropertyCondition, TextCondition,
+};
+use crate::models::{
+    InvalidPage, ListResponse, Object, Page, PageCreateRequest, Parent, Properties, SearchResult,
+};
+use crate::test_util::{
+    list_response, BlockFixture, DatabaseFixture, MockClient, MockNotion, PageFixture,
+    MOCK_API_TOKEN,
+};
+use crate::{
+    ApiVersion, Client, Error, JobRunner, NotionApi, OnDuplicate, Result, RetryPolicy, Upserted,
+};
+use serde_json::json;
+use std::str::FromStr;
+use wiremock::matchers::{body_json, body_partial_json, header, method, path, query_param};
+use wiremock::{Mock, ResponseTemplate};
+
+#[tokio::test]
+async fn get_pages_keeps_input_order() {
+    let mock = MockNotion::start().await;
+    let first = PageFixture::new().build();
+    let second = PageFixture::new().build();
+    mock.mount_page(&first).await;
+    mock.mount_page(&second).await;
+    let missing = PageId::from_str("missing").unwrap();
+
+    let results = mock
+        .api()
+        .get_pages(vec![second.id.clone(), missing, first.id.clone()], 2)
+        .await;
+
+    assert_eq!(results[0].as_ref().unwrap(), &second);
+    assert!(results[1].is_err());
+    assert_eq!(results[2].as_ref().unwrap(), &first);
+}
+
+#[tokio::test]
+async fn get_pages_retries_rate_limited_pages() {
+    let mock = MockNotion::start().await;
+    let pages = vec![
+        PageFixture::new().build(),
+        PageFixture::new().build(),
+        PageFixture::new().build(),
+    ];
+    Mock::given(method("GET"))
+        .and(path(format!("/v1/pages/{}", pages[1].id)))
+        .respond_with(
+            ResponseTemplate::new(429)
+                .insert_header("Retry-After", "0")
+                .set_body_json(json!({
+                    "object": "error",
+                    "status": 429,
+                    "code": "rate_limited",
+                    "message": "Slow down."
+                })),
+        )
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(mock.server())
+        .await;
+    for page in &pages {
+        mock.mount_page(page).await;
+    }
+
+    let policy = RetryPolicy {
+        initial_backoff: std::time::Duration::ZERO,
+        ..RetryPolicy::default()
+    };
+    let results = mock
+        .api()
+        .with_retries(policy)
+        .get_pages(pages.iter().map(|page| &page.id), 2)
+        .await;
+
+    let results: Vec<_> = results.into_iter().map(Result::unwrap).collect();
+    assert_eq!(results, pages);
+    assert_eq!(mock.server().received_requests().await.unwrap().len(), 4);
+}
+
+#[tokio::test]
+async fn get_many_block_children_keeps_input_order() {
+    let mock = MockNotion::start().await;
+    let first = PageFixture::new().build();
+    let second = PageFixture::new().build();
+    let paragraph = BlockFixture::new().paragraph("First page");
+    let heading = BlockFixture::new().heading_1("Second page");
+    mock.mount_block_children(BlockId::from(first.id.clone()), vec![paragraph.clone()])
+        .await;
+    mock.mount_block_children(BlockId::from(second.id.clone()), vec![heading.clone()])
+        .await;
+    let missing = BlockId::from_str("missing").unwrap();
+
+    let runner = JobRunner::new()
+        .concurrency(2)
+        .start_interval(std::time::Duration::from_millis(5));
+    let ids = vec![
+        BlockId::from(second.id.clone()),
+        missing,
+        BlockId::from(first.id.clone()),
+    ];
+    let results = mock.api().get_many_block_children(ids, &runner).await;
+
+    assert_eq!(results[0].as_ref().unwrap(), &vec![heading]);
+    assert!(results[1].as_ref().unwrap_err().is_not_found());
+    assert_eq!(results[2].as_ref().unwrap(), &vec![paragraph]);
+
+    mock.mount_page(&second).await;
+    let pages = mock
+        .api()
+        .get_pages_with(vec![&first.id, &second.id], &runner)
+        .await;
+    assert!(pages[0].is_err());
+    assert_eq!(pages[1].as_ref().unwrap(), &second);
+}
+
+async fn upsert(
+    existing: Vec<Page>,
+    on_duplicate: OnDuplicate,
+) -> Result<Upserted> {
+    let mock = MockNotion::start().await;
+    let database_id = DatabaseId::from_str("db").unwrap();
+    mock.mount_page_writes(&PageFixture::new().database("db").build())
+        .await;
+    for page in &existing {
+        mock.mount_page_writes(page).await;
+    }
+    mock.mount_query(&database_id, existing).await;
+
+    mock.api()
+        .upsert_page(
+            &database_id,
+            "Name",
+            PropertyCondition::Title(TextCondition::Equals("Key".to_string())),
+            PageFixture::new().title("Key").build().properties,
+            on_duplicate,
+        )
+        .await
+}
+
+#[tokio::test]
+async fn upsert_creates_missing_page() {
+    let upserted = upsert(vec![], OnDuplicate::Fail).await.unwrap();
+    assert!(upserted.created);
+}
+
+#[tokio::test]
+async fn upsert_reports_duplicates() {
+    let existing = PageFixture::new().build();
+    let duplicate = PageFixture::new().build();
+
+    let upserted = upsert(
+        vec![existing.clone(), duplicate.clone()],
+        OnDuplicate::UpdateFirst,
+    )
+    .await
+    .unwrap();
+    assert!(!upserted.created);
+    assert_eq!(upserted.duplicates, vec![duplicate.id.clone()]);
+
+    let failed = upsert(vec![existing, duplicate], OnDuplicate::Fail).await;
+    assert!(matches!(failed, Err(Error::DuplicatePages { .. })));
+}
+
+#[tokio::test]
+async fn upsert_finds_duplicates_past_the_first_page() {
+    let database_id = DatabaseId::from_str("db").unwrap();
+    let existing = PageFixture::new().build();
+    let duplicate = PageFixture::new().build();
+    let client = MockClient::new();
+    for page in [&existing, &duplicate] {
+        let mut list = list_response(vec![Object::Page { page: page.clone() }]);
+        list.has_more = page == &existing;
+        list.next_cursor = list
+            .has_more
+            .then(|| serde_json::from_value(json!("cursor")).unwrap());
+        client.mount(
+            "POST",
+            format!("/databases/{}/query", database_id),
+            Object::List { list },
+        );
+    }
+
+    let failed = client
+        .api()
+        .upsert_page(
+            &database_id,
+            "Name",
+            PropertyCondition::Title(TextCondition::Equals("Key".to_string())),
+            Properties::default(),
+            OnDuplicate::Fail,
+        )
+        .await;
+    match failed {
+        Err(Error::DuplicatePages { pages, .. }) => {
+            assert_eq!(pages, vec![existing.id, duplicate.id])
+        }
+        other => panic!("expected duplicates, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn create_page_with_children() {
+    let mock = MockNotion::start().await;
+    let page = PageFixture::new().title("Report").build();
+    Mock::given(method("POST"))
+        .and(path("/v1/pages"))
+        .and(body_partial_json(json!({
+            "children": [
+                {"type": "heading_1", "heading_1": {"rich_text": [{"text": {"content": "Report"}}]}},
+                {"type": "to_do", "to_do": {"checked": false}},
+            ]
+        })))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(Object::Page { page: page.clone() }),
+        )
+        .expect(1)
+        .mount(mock.server())
+        .await;
+
+    let request = PageCreateRequest::new(
+        Parent::Page {
+            page_id: PageId::from_str("parent").unwrap(),
+        },
+        page.properties.clone(),
+    )
+    .with_children(crate::blocks![h1("Report"), todo("Ship it")]);
+    assert_eq!(mock.api().create_page(request).await.unwrap(), page);
+}
+
+#[tokio::test]
+async fn append_block_children_after() {
+    let mock = MockNotion::start().await;
+    let heading = BlockFixture::new().heading_1("Changelog");
+    let entry = BlockFixture::new().paragraph("v2");
+    let page_id = BlockId::from(PageId::from_str("changelog").unwrap());
+    Mock::given(method("PATCH"))
+        .and(path(format!("/v1/blocks/{}/children", page_id)))
+        .and(body_partial_json(json!({
+            "after": heading.id().unwrap(),
+            "children": [{"type": "paragraph"}],
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(Object::List {
+            list: ListResponse {
+                results: vec![Object::Block {
+                    block: entry.clone(),
+                }],
+                next_cursor: None,
+                has_more: false,
+            },
+        }))
+        .expect(1)
+        .mount(mock.server())
+        .await;
+
+    let request = BlockAppendRequest::from(crate::blocks![p("v2")]).after(heading.id().unwrap());
+    let appended = mock
+        .api()
+        .append_block_children(&page_id, request)
+        .await
+        .unwrap();
+    assert_eq!(appended.results, vec![entry]);
+}
+
+#[tokio::test]
+async fn set_todo_checked() {
+    let mock = MockNotion::start().await;
+    let to_do = BlockFixture::new().to_do("Ship it", true);
+    Mock::given(method("PATCH"))
+        .and(path(format!("/v1/blocks/{}", to_do.id().unwrap())))
+        .and(body_json(json!({"to_do": {"checked": true}})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(Object::Block {
+            block: to_do.clone(),
+        }))
+        .expect(1)
+        .mount(mock.server())
+        .await;
+
+    let updated = mock
+        .api()
+        .set_todo_checked(to_do.id().unwrap(), true)
+        .await
+        .unwrap();
+    assert_eq!(updated, to_do);
+}
+
+#[tokio::test]
+async fn update_and_delete_block() {
+    let mock = MockNotion::start().await;
+    let paragraph = BlockFixture::new().paragraph("Updated");
+    let route = format!("/v1/blocks/{}", paragraph.id().unwrap());
+    Mock::given(method("PATCH"))
+        .and(path(route.clone()))
+        .and(body_partial_json(json!({
+            "paragraph": {"rich_text": [{"text": {"content": "Updated"}}]}
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(Object::Block {
+            block: paragraph.clone(),
+        }))
+        .expect(1)
+        .mount(mock.server())
+        .await;
+    Mock::given(method("DELETE"))
+        .and(path(route))
+        .respond_with(ResponseTemplate::new(200).set_body_json(Object::Block {
+            block: paragraph.clone(),
+        }))
+        .expect(1)
+        .mount(mock.server())
+        .await;
+
+    let api = mock.api();
+    let block_id = paragraph.id().unwrap();
+    let updated = api
+        .update_block(block_id, crate::models::block::builder::p("Updated"))
+        .await
+        .unwrap();
+    assert_eq!(updated, paragraph);
+    assert_eq!(api.delete_block(block_id).await.unwrap(), paragraph);
+}
+
+#[tokio::test]
+async fn boxed_clients_share_one_type() {
+    let mock = MockNotion::start().await;
+    let page = PageFixture::new().build();
+    mock.mount_page(&page).await;
+
+    let apis = vec![
+        mock.api().boxed(),
+        mock.api().with_retries(RetryPolicy::default()).boxed(),
+    ];
+    for api in apis {
+        assert_eq!(api.get_page(&page.id).await.unwrap(), page);
+    }
+}
+
+#[tokio::test]
+async fn aggregate_query_results() {
+    let mock = MockNotion::start().await;
+    let database_id = DatabaseId::from_str("tasks").unwrap();
+    let pages = vec![
+        PageFixture::new().number("Points", 2).build(),
+        PageFixture::new().number("Points", 5).build(),
+    ];
+    mock.mount_query(&database_id, pages).await;
+
+    let points = mock
+        .api()
+        .aggregate_query(&database_id, DatabaseQuery::default(), "Points")
+        .await
+        .unwrap();
+    assert_eq!((points.count, points.sum), (2, 7.0));
+}
+
+#[tokio::test]
+async fn filter_properties_by_id() {
+    let mock = MockNotion::start().await;
+    let database = DatabaseFixture::new().select("Status", &["Done"]).build();
+    let name = database.property_id("Name").unwrap().clone();
+    let status = database.property_id("Status").unwrap().clone();
+    assert_eq!(database.property_name(&status), Some("Status"));
+    assert_eq!(database.property_id("Missing"), None);
+
+    let page = PageFixture::new().title("Filtered").build();
+    Mock::given(method("GET"))
+        .and(path(format!("/v1/pages/{}", page.id)))
+        .and(query_param("filter_properties", name.to_string()))
+        .and(query_param("filter_properties", status.to_string()))
+        .respond_with(ResponseTemplate::new(200).set_body_json(Object::Page { page: page.clone() }))
+        .expect(1)
+        .mount(mock.server())
+        .await;
+    Mock::given(method("POST"))
+        .and(path(format!("/v1/databases/{}/query", database.id)))
+        .and(query_param("filter_properties", name.to_string()))
+        .respond_with(ResponseTemplate::new(200).set_body_json(Object::List {
+            list: ListResponse {
+                results: vec![Object::Page { page: page.clone() }],
+                next_cursor: None,
+                has_more: false,
+            },
+        }))
+        .expect(1)
+        .mount(mock.server())
+        .await;
+
+    let api = mock.api();
+    let filtered = api
+        .get_page_with_properties(&page.id, &[name.clone(), status])
+        .await
+        .unwrap();
+    assert_eq!(filtered, page);
+    let query = DatabaseQuery {
+        filter_properties: vec![name],
+        ..Default::default()
+    };
+    let pages = api.query_database(&database.id, query).await.unwrap();
+    assert_eq!(pages.results, vec![page]);
+}
+
+#[tokio::test]
+async fn list_trashed_pages() {
+    let mock = MockNotion::start().await;
+    let database_id = DatabaseId::from_str("tasks").unwrap();
+    let kept = PageFixture::new().database("tasks").build();
+    let archived = PageFixture::new().database("tasks").archived(true).build();
+    let mut trashed = PageFixture::new().database("tasks").build();
+    trashed.in_trash = true;
+    mock.mount_query(&database_id, vec![kept, archived.clone(), trashed.clone()])
+        .await;
+
+    let pages = mock.api().list_trashed_pages(&database_id).await.unwrap();
+    assert_eq!(pages, vec![archived, trashed]);
+
+    let requests = mock.server().received_requests().await.unwrap();
+    let query: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+    assert_eq!(query, json!({"in_trash": true}));
+}
+
+#[tokio::test]
+async fn typed_search_results() {
+    let mock = MockNotion::start().await;
+    let page = PageFixture::new().build();
+    let database = DatabaseFixture::new().build();
+    mock.mount_search(vec![
+        Object::Page { page: page.clone() },
+        Object::Database {
+            database: database.clone(),
+        },
+    ])
+    .await;
+
+    let results = mock
+        .api()
+        .search_results(NotionSearch::Query("anything".to_string()))
+        .await
+        .unwrap()
+        .results;
+
+    assert_eq!(
+        results,
+        vec![SearchResult::Page(page), SearchResult::Database(database)]
+    );
+}
+
+#[tokio::test]
+async fn search_pages_and_databases() {
+    let mock = MockNotion::start().await;
+    let page = PageFixture::new().build();
+    let database = DatabaseFixture::new().build();
+    for (value, object) in [
+        ("page", Object::Page { page: page.clone() }),
+        (
+            "database",
+            Object::Database {
+                database: database.clone(),
+            },
+        ),
+    ] {
+        Mock::given(method("POST"))
+            .and(path("/v1/search"))
+            .and(body_json(json!({
+                "query": "Roadmap",
+                "filter": {"property": "object", "value": value},
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(Object::List {
+                list: list_response(vec![object]),
+            }))
+            .expect(1)
+            .mount(mock.server())
+            .await;
+    }
+
+    let api = mock.api();
+    let query = || NotionSearch::Query("Roadmap".to_string());
+    assert_eq!(api.search_pages(query()).await.unwrap().results, vec![page]);
+    assert_eq!(
+        api.search_databases(query()).await.unwrap().results,
+        vec![database]
+    );
+}
+
+#[tokio::test]
+async fn invalid_queries_are_not_sent() {
+    let mock = MockNotion::start().await;
+    let database = DatabaseFixture::new().build();
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(0)
+        .mount(mock.server())
+        .await;
+
+    let query = DatabaseQuery::from(FilterCondition::Or { or: vec![] });
+    assert!(matches!(
+        mock.api().query_database(&database.id, query).await,
+        Err(Error::InvalidQuery {
+            source: InvalidQuery::EmptyCompoundFilter
+        })
+    ));
+}
+
+#[tokio::test]
+async fn invalid_pages_are_not_sent() {
+    let mock = MockNotion::start().await;
+    let database = DatabaseFixture::new().build();
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(0)
+        .mount(mock.server())
+        .await;
+
+    let request = PageCreateRequest::new(
+        Parent::Database {
+            database_id: database.id,
+        },
+        Properties::default(),
+    );
+    assert!(matches!(
+        mock.api().create_page(request).await,
+        Err(Error::InvalidPage {
+            source: InvalidPage::MissingTitle
+        })
+    ));
+}
+
+#[tokio::test]
+async fn set_checkbox() {
+    let mock = MockNotion::start().await;
+    let page = PageFixture::new().checkbox("Done", true).build();
+    Mock::given(method("PATCH"))
+        .and(path(format!("/v1/pages/{}", page.id)))
+        .and(body_json(
+            json!({"properties": {"Done": {"checkbox": true}}}),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(Object::Page { page: page.clone() }))
+        .mount(mock.server())
+        .await;
+
+    let updated = mock
+        .api()
+        .set_checkbox(&page.id, "Done", true)
+        .await
+        .unwrap();
+    assert_eq!(updated.checkbox("Done"), Some(true));
+    assert_eq!(updated.checkbox("Missing"), None);
+}
+
+#[tokio::test]
+async fn sends_configured_api_version() {
+    let mock = MockNotion::start().await;
+    let page = PageFixture::new().build();
+    Mock::given(method("GET"))
+        .and(path(format!("/v1/pages/{}", page.id)))
+        .and(header("Notion-Version", "2022-06-28"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(Object::Page { page: page.clone() }))
+        .mount(mock.server())
+        .await;
+
+    let client = Client::with_version(MOCK_API_TOKEN.to_string(), ApiVersion::V2022_06_28);
+    let api = NotionApi::with_client(client.unwrap()).with_base_url(mock.base_url());
+    assert_eq!(api.api_version(), ApiVersion::V2022_06_28);
+    assert_eq!(api.get_page(&page.id).await.unwrap(), page);
+}
+
+#[tokio::test]
+async fn overrides_api_version_per_request() {
+    let mock = MockNotion::start().await;
+    let page = PageFixture::new().build();
+    Mock::given(method("GET"))
+        .and(path(format!("/v1/pages/{}", page.id)))
+        .and(header("Notion-Version", "2025-09-03"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(Object::Page { page: page.clone() }))
+        .expect(1)
+        .mount(mock.server())
+        .await;
+
+    let api = mock.api();
+    let newer = api.for_version(ApiVersion::V2025_09_03);
+    assert_eq!(newer.api_version(), ApiVersion::V2025_09_03);
+    assert_eq!(newer.get_page(&page.id).await.unwrap(), page);
+    assert_eq!(api.api_version(), ApiVersion::default());
+    assert_eq!(api.client().stats().len(), 1);
+}
@@ -0,0 +1,254 @@
+use std::collections::{HashMap, VecDeque};
+
+use futures::stream::{FuturesUnordered, StreamExt};
+
+use crate::ids::{AsIdentifier, BlockId, PageId};
+use crate::models::block::{Block, BlockTree};
+use crate::models::Page;
+use crate::render::outline::Outline;
+use crate::{NotionApi, Result, TClient};
+
+/// Controls how [NotionApi::get_block_tree()] walks nested blocks.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct BlockTreeOptions {
+    /// Maximum number of `get_block_children` requests in flight at once.
+    pub concurrency: usize,
+    /// How many levels of blocks to fetch; `Some(1)` fetches only the direct children.
+    /// Blocks below the limit are returned with empty `children`.
+    pub max_depth: Option<usize>,
+}
+
+impl Default for BlockTreeOptions {
+    fn default() -> Self {
+        BlockTreeOptions {
+            // Notion allows an average of three requests per second.
+            concurrency: 3,
+            max_depth: None,
+        }
+    }
+}
+
+/// A page together with its content,
+/// as returned by [NotionApi::get_page_with_content()].
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct PageWithContent {
+    pub page: Page,
+    pub blocks: Vec<BlockTree>,
+}
+
+impl PageWithContent {
+    /// The blocks of the page as an indented outline, for printing while debugging.
+    pub fn outline(&self) -> Outline<'_> {
+        Outline::new(&self.blocks)
+    }
+}
+
+impl<C: TClient> NotionApi<C> {
+    /// Fetches a page and its block tree concurrently.
+    ///
+    /// `depth` limits the levels of blocks like [BlockTreeOptions::max_depth]; `None` fetches all of them.
+    pub async fn get_page_with_content<T: AsIdentifier<PageId>>(
+        &self,
+        page_id: T,
+        depth: Option<usize>,
+    ) -> Result<PageWithContent> {
+        let page_id = page_id.as_id();
+        let options = BlockTreeOptions {
+            max_depth: depth,
+            ..BlockTreeOptions::default()
+        };
+
+        let (page, blocks) = futures::try_join!(
+            self.get_page(page_id),
+            self.get_block_tree(BlockId::from(page_id.clone()), options)
+        )?;
+        Ok(PageWithContent { page, blocks })
+    }
+
+    /// Recursively fetch the children of a block (or page).
+    ///
+    /// Up to `options.concurrency` requests are in flight at once, and a new one
+    /// starts whenever one finishes, whatever level of the tree it is on.
+    /// Children are kept in their original order.
+    /// Child pages and child databases are not descended into.
+    pub async fn get_block_tree<T: AsIdentifier<BlockId>>(
+        &self,
+        block_id: T,
+        options: BlockTreeOptions,
+    ) -> Result<Vec<BlockTree>> {
+        let roots = self.get_all_block_children(block_id).await?;
+
+        let mut children: HashMap<BlockId, Vec<Block>> = HashMap::new();
+        let mut queue: VecDeque<(BlockId, usize)> = expandable(&roots, 1, options).collect();
+        let mut in_flight = FuturesUnordered::new();
+
+        loop {
+            while in_flight.len() < options.concurrency.max(1) {
+                match queue.pop_front() {
+                    Some((parent, depth)) => in_flight.push(async move {
+                        let blocks = self.get_all_block_children(&parent).await;
+                        (parent, depth, blocks)
+                    }),
+                    None => break,
+                }
+            }
+            match in_flight.next().await {
+                Some((parent, depth, blocks)) => {
+                    let blocks = blocks?;
+                    queue.extend(expandable(&blocks, depth + 1, options));
+                    children.insert(parent, blocks);
+                }
+                None => break,
+            }
+        }
+
+        Ok(assemble(roots, &mut children))
+    }
+}
+
+/// The blocks at `depth` whose children are fetched, with the depth of those children.
+fn expandable(
+    blocks: &[Block],
+    depth: usize,
+    options: BlockTreeOptions,
+) -> impl Iterator<Item = (BlockId, usize)> + '_ {
+    blocks
+        .iter()
+        .filter(move |block| {
+            options.max_depth.is_none_or(|max| depth < max)
+                && block.has_children()
+                && !matches!(block, Block::ChildPage { .. } | Block::ChildDatabase { .. })
+        })
+        .map(move |block| (block.as_id().clone(), depth))
+}
+
+fn assemble(
+    blocks: Vec<Block>,
+    children: &mut HashMap<BlockId, Vec<Block>>,
+) -> Vec<BlockTree> {
+    blocks
+        .into_iter()
+        .map(|block| {
+            let nested = match block.common() {
+                Some(common) => children.remove(&common.id).unwrap_or_default(),
+                None => vec![],
+            };
+            BlockTree {
+                children: assemble(nested, children),
+                block,
+            }
+        })
+        .collect()
+}
+
+#[cfg(all(test, not(target_os = "wasi")))]
+mod tests {
+    use crate::ids::{AsIdentifier, BlockId};
+    use crate::test_util::{BlockFixture, MockNotion, PageFixture};
+    use crate::BlockTreeOptions;
+
+    #[tokio::test]
+    async fn get_block_tree() {
+        let mock = MockNotion::start().await;
+        let page_block = BlockId::from(PageFixture::new().build().id);
+        let parent = BlockFixture::new().has_children(true).paragraph("parent");
+        let child = BlockFixture::new().bulleted_list_item("child");
+        let sibling = BlockFixture::new().paragraph("sibling");
+        mock.mount_block_children(&page_block, vec![parent.clone(), sibling.clone()])
+            .await;
+        mock.mount_block_children(parent.as_id(), vec![child.clone()])
+            .await;
+
+        let api = mock.api();
+        let tree = api
+            .get_block_tree(&page_block, BlockTreeOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree[0].block, parent);
+        assert_eq!(tree[0].children[0].block, child);
+        assert_eq!(tree[1].block, sibling);
+
+        let shallow = api
+            .get_block_tree(
+                &page_block,
+                BlockTreeOptions {
+                    max_depth: Some(1),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert!(shallow[0].children.is_empty());
+    }
+
+    #[tokio::test]
+    async fn get_deep_block_tree_one_request_at_a_time() {
+        let mock = MockNotion::start().await;
+        let page_block = BlockId::from(PageFixture::new().build().id);
+        let item = BlockFixture::new()
+            .has_children(true)
+            .bulleted_list_item("item");
+        let nested = BlockFixture::new().has_children(true).paragraph("nested");
+        let leaf = BlockFixture::new().paragraph("leaf");
+        let step = BlockFixture::new()
+            .has_children(true)
+            .numbered_list_item("step");
+        let cited = BlockFixture::new().paragraph("cited");
+        mock.mount_block_children(&page_block, vec![item.clone(), step.clone()])
+            .await;
+        mock.mount_block_children(item.as_id(), vec![nested.clone()])
+            .await;
+        mock.mount_block_children(nested.as_id(), vec![leaf.clone()])
+            .await;
+        mock.mount_block_children(step.as_id(), vec![cited.clone()])
+            .await;
+
+        let options = BlockTreeOptions {
+            concurrency: 1,
+            max_depth: None,
+        };
+        let tree = mock
+            .api()
+            .get_block_tree(&page_block, options)
+            .await
+            .unwrap();
+        assert_eq!(tree[0].children[0].block, nested);
+        assert_eq!(tree[0].children[0].children[0].block, leaf);
+        assert_eq!(tree[1].children[0].block, cited);
+
+        let options = BlockTreeOptions {
+            max_depth: Some(2),
+            ..options
+        };
+        let tree = mock
+            .api()
+            .get_block_tree(&page_block, options)
+            .await
+            .unwrap();
+        assert_eq!(tree[0].children[0].block, nested);
+        assert!(tree[0].children[0].children.is_empty());
+    }
+
+    #[tokio::test]
+    async fn get_page_with_content() {
+        let mock = MockNotion::start().await;
+        let page = PageFixture::new().title("Rendered").build();
+        let parent = BlockFixture::new().has_children(true).paragraph("parent");
+        let child = BlockFixture::new().paragraph("child");
+        mock.mount_page(&page).await;
+        mock.mount_block_children(BlockId::from(page.id.clone()), vec![parent.clone()])
+            .await;
+        mock.mount_block_children(parent.as_id(), vec![child.clone()])
+            .await;
+
+        let api = mock.api();
+        let content = api.get_page_with_content(&page.id, None).await.unwrap();
+        assert_eq!(content.page, page);
+        assert_eq!(content.blocks[0].block, parent);
+        assert_eq!(content.blocks[0].children[0].block, child);
+
+        let shallow = api.get_page_with_content(&page.id, Some(1)).await.unwrap();
+        assert!(shallow.blocks[0].children.is_empty());
+    }
+}
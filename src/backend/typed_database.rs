@@ -0,0 +1,240 @@
+use std::marker::PhantomData;
+
+use crate::ids::{AsIdentifier, DatabaseId, PageId};
+use crate::models::rows::{FromPage, IntoProperties};
+use crate::models::search::{DatabaseQuery, PropertyCondition};
+use crate::models::{Page, PageCreateRequest, PageUpdateRequest, Parent};
+use crate::{Client, Error, NotionApi, OnDuplicate, Result, TClient};
+
+/// A page of a [TypedDatabase] and the row read from it.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Row<T> {
+    pub page: Page,
+    pub row: T,
+}
+
+/// A database whose pages are read as `T` and written from `T`,
+/// returned by [NotionApi::typed_database()].
+///
+/// See [models::rows](crate::models::rows) for implementing the conversions.
+pub struct TypedDatabase<'a, T, C = Client> {
+    api: &'a NotionApi<C>,
+    database_id: DatabaseId,
+    row: PhantomData<fn() -> T>,
+}
+
+impl<C: TClient> NotionApi<C> {
+    /// Reads and writes the pages of a database as `T`.
+    pub fn typed_database<T, D: AsIdentifier<DatabaseId>>(
+        &self,
+        database_id: D,
+    ) -> TypedDatabase<'_, T, C> {
+        TypedDatabase {
+            api: self,
+            database_id: database_id.as_id().clone(),
+            row: PhantomData,
+        }
+    }
+
+    /// Every page of a database matching `query` read as `T`,
+    /// see [TypedDatabase::query()].
+    pub async fn query_database_as<T, D, Q>(
+        &self,
+        database_id: D,
+        query: Q,
+    ) -> Result<Vec<T>>
+    where
+        T: FromPage,
+        D: AsIdentifier<DatabaseId>,
+        Q: Into<DatabaseQuery>,
+    {
+        let rows = self.typed_database(database_id).query(query).await?;
+        Ok(rows.into_iter().map(|row| row.row).collect())
+    }
+}
+
+impl<'a, T, C: TClient> TypedDatabase<'a, T, C> {
+    pub fn database_id(&self) -> &DatabaseId {
+        &self.database_id
+    }
+}
+
+impl<'a, T: FromPage, C: TClient> TypedDatabase<'a, T, C> {
+    /// Every page matching `query`, fetching all pages of results.
+    ///
+    /// Fails with [Error::InvalidRow] on the first page that can't be read as `T`.
+    pub async fn query<Q: Into<DatabaseQuery>>(
+        &self,
+        query: Q,
+    ) -> Result<Vec<Row<T>>> {
+        self.api
+            .query_all_pages(&self.database_id, query)
+            .await?
+            .into_iter()
+            .map(read)
+            .collect()
+    }
+}
+
+impl<'a, T: FromPage + IntoProperties, C: TClient> TypedDatabase<'a, T, C> {
+    /// Creates a page for `row`.
+    pub async fn insert(
+        &self,
+        row: T,
+    ) -> Result<Row<T>> {
+        let page = self
+            .api
+            .create_page(PageCreateRequest::new(
+                Parent::Database {
+                    database_id: self.database_id.clone(),
+                },
+                row.into_properties(),
+            ))
+            .await?;
+        read(page)
+    }
+
+    /// Sets the properties of `row` on an existing page.
+    pub async fn update<P: AsIdentifier<PageId>>(
+        &self,
+        page_id: P,
+        row: T,
+    ) -> Result<Row<T>> {
+        let update = PageUpdateRequest {
+            properties: row.into_properties(),
+            ..PageUpdateRequest::default()
+        };
+        read(self.api.update_page(page_id, update).await?)
+    }
+
+    /// Updates the page whose `key_property` matches `key_value` with `row`,
+    /// or inserts it, see [NotionApi::upsert_page()].
+    pub async fn upsert<K: Into<String>>(
+        &self,
+        key_property: K,
+        key_value: PropertyCondition,
+        row: T,
+        on_duplicate: OnDuplicate,
+    ) -> Result<Row<T>> {
+        let upserted = self
+            .api
+            .upsert_page(
+                &self.database_id,
+                key_property,
+                key_value,
+                row.into_properties(),
+                on_duplicate,
+            )
+            .await?;
+        read(upserted.page)
+    }
+}
+
+#[allow(clippy::result_large_err)]
+fn read<T: FromPage>(page: Page) -> Result<Row<T>> {
+    match T::from_page(&page) {
+        Ok(row) => Ok(Row { page, row }),
+        Err(source) => Err(Error::InvalidRow {
+            page: page.id,
+            source,
+        }),
+    }
+}
+
+#[cfg(all(test, not(target_os = "wasi")))]
+mod tests {
+    use crate::ids::DatabaseId;
+    use crate::models::rows::{IntoProperties, RowError};
+    use crate::models::search::DatabaseQuery;
+    use crate::models::Properties;
+    use crate::test_util::{MockNotion, PageFixture};
+    use crate::Error;
+    use serde_json::json;
+    use std::str::FromStr;
+
+    #[derive(Debug, PartialEq)]
+    struct Task {
+        name: String,
+        done: bool,
+    }
+
+    crate::from_page!(Task {
+        name: "Name",
+        done: "Done",
+    });
+
+    impl IntoProperties for Task {
+        fn into_properties(self) -> Properties {
+            PageFixture::new()
+                .title(&self.name)
+                .checkbox("Done", self.done)
+                .build()
+                .properties
+        }
+    }
+
+    #[tokio::test]
+    async fn typed_database_reads_and_writes_rows() {
+        let mock = MockNotion::start().await;
+        let database_id = DatabaseId::from_str("db").unwrap();
+        let task = PageFixture::new()
+            .database("db")
+            .title("Ship it")
+            .checkbox("Done", false)
+            .build();
+        let untitled = PageFixture::new().database("db").build();
+        mock.mount_query(&database_id, vec![task.clone()]).await;
+        mock.mount_page_writes(&task).await;
+        let api = mock.api();
+        let tasks = api.typed_database::<Task, _>(&database_id);
+
+        let rows = tasks.query(DatabaseQuery::default()).await.unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].page, task);
+        assert_eq!(
+            rows[0].row,
+            Task {
+                name: "Ship it".to_string(),
+                done: false
+            }
+        );
+
+        let inserted = tasks
+            .insert(Task {
+                name: "Ship it".to_string(),
+                done: false,
+            })
+            .await
+            .unwrap();
+        assert_eq!(inserted.page.id, task.id);
+        let requests = mock.server().received_requests().await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&requests[1].body).unwrap();
+        assert_eq!(
+            body["parent"]["database_id"],
+            json!(database_id.to_string())
+        );
+        assert_eq!(body["properties"]["Done"]["checkbox"], json!(false));
+        assert_eq!(
+            api.query_database_as::<Task, _, _>(&database_id, DatabaseQuery::default())
+                .await
+                .unwrap(),
+            vec![Task {
+                name: "Ship it".to_string(),
+                done: false
+            }]
+        );
+
+        let mock = MockNotion::start().await;
+        mock.mount_query(&database_id, vec![untitled.clone()]).await;
+        assert!(matches!(
+            mock.api()
+                .typed_database::<Task, _>(&database_id)
+                .query(DatabaseQuery::default())
+                .await,
+            Err(Error::InvalidRow {
+                page,
+                source: RowError::MissingProperty { .. }
+            }) if page == untitled.id
+        ));
+    }
+}
@@ -0,0 +1,674 @@
+//! Sending files to Notion and attaching them to pages.
+//! See <https://developers.notion.com/reference/file-upload>
+
+use std::path::Path;
+
+#[cfg(feature = "blocks")]
+use crate::ids::BlockId;
+use crate::ids::{AsIdentifier, FileUploadId, PageId};
+#[cfg(feature = "blocks")]
+use crate::models::block::Block;
+use crate::models::files::{FileUpload, FileUploadMode, FileUploadRequest, FileUploadStatus};
+use crate::models::{Object, Page};
+use crate::{Error, NotionApi, Result, TClient};
+
+/// Files up to this size are sent in one request, larger ones in parts.
+const SINGLE_PART_LIMIT: usize = 20 * 1024 * 1024;
+
+/// The size of the parts of larger files.
+/// Notion accepts parts of 5 to 20 MB, only the last part may be smaller.
+const PART_SIZE: usize = 10 * 1024 * 1024;
+
+/// A `multipart/form-data` request body, the way file contents are sent.
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct MultipartForm {
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+struct Part {
+    name: String,
+    file: Option<(String, String)>,
+    data: Vec<u8>,
+}
+
+impl MultipartForm {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a plain text field.
+    pub fn text<N: Into<String>, V: Into<String>>(
+        mut self,
+        name: N,
+        value: V,
+    ) -> Self {
+        self.parts.push(Part {
+            name: name.into(),
+            file: None,
+            data: value.into().into_bytes(),
+        });
+        self
+    }
+
+    /// Adds a file field with the name and content type of the file.
+    pub fn file<N: Into<String>, F: Into<String>, T: Into<String>>(
+        mut self,
+        name: N,
+        filename: F,
+        content_type: T,
+        data: Vec<u8>,
+    ) -> Self {
+        self.parts.push(Part {
+            name: name.into(),
+            file: Some((filename.into(), content_type.into())),
+            data,
+        });
+        self
+    }
+
+    /// The `Content-Type` header and the body of the request.
+    ///
+    /// The boundary is chosen so that it doesn't occur in any of the fields.
+    pub fn encode(&self) -> (String, Vec<u8>) {
+        let boundary = (0..)
+            .map(|n| format!("notion-wasi-boundary-{}", n))
+            .find(|boundary| !self.parts.iter().any(|part| part.contains(boundary)))
+            .unwrap();
+
+        let mut body = Vec::new();
+        for part in &self.parts {
+            body.extend_from_slice(format!("--{}\r\n", boundary).as_bytes());
+            let disposition = match &part.file {
+                Some((filename, content_type)) => format!(
+                    "Content-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\n\
+                     Content-Type: {}\r\n",
+                    escape(&part.name),
+                    escape(filename),
+                    content_type
+                ),
+                None => format!(
+                    "Content-Disposition: form-data; name=\"{}\"\r\n",
+                    escape(&part.name)
+                ),
+            };
+            body.extend_from_slice(disposition.as_bytes());
+            body.extend_from_slice(b"\r\n");
+            body.extend_from_slice(&part.data);
+            body.extend_from_slice(b"\r\n");
+        }
+        body.extend_from_slice(format!("--{}--\r\n", boundary).as_bytes());
+
+        (format!("multipart/form-data; boundary={}", boundary), body)
+    }
+}
+
+impl Part {
+    fn contains(
+        &self,
+        boundary: &str,
+    ) -> bool {
+        let boundary = boundary.as_bytes();
+        self.data
+            .windows(boundary.len())
+            .any(|window| window == boundary)
+            || self.file.as_ref().is_some_and(|(filename, _)| {
+                filename.contains(std::str::from_utf8(boundary).unwrap())
+            })
+    }
+}
+
+/// Quotes and line breaks would end the quoted names of the form.
+fn escape(name: &str) -> String {
+    name.replace('"', "%22")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// The content type of a file, guessed from its extension.
+/// Notion rejects files whose content type doesn't match the extension.
+pub(crate) fn content_type_for(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match extension.as_str() {
+        "txt" => "text/plain",
+        "csv" => "text/csv",
+        "json" => "application/json",
+        "pdf" => "application/pdf",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "heic" => "image/heic",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "mp4" => "video/mp4",
+        "mov" => "video/quicktime",
+        "webm" => "video/webm",
+        "doc" => "application/msword",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "xls" => "application/vnd.ms-excel",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        "ppt" => "application/vnd.ms-powerpoint",
+        "pptx" => "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+        _ => "application/octet-stream",
+    }
+}
+
+/// The type of block that displays a file of `content_type`.
+#[cfg(feature = "blocks")]
+fn block_type_for(content_type: &str) -> &'static str {
+    if content_type.starts_with("image/") {
+        "image"
+    } else if content_type.starts_with("video/") {
+        "video"
+    } else if content_type == "application/pdf" {
+        "pdf"
+    } else {
+        "file"
+    }
+}
+
+impl<C: TClient> NotionApi<C> {
+    /// Creates an upload to send the contents of a file to.
+    pub async fn create_file_upload(
+        &self,
+        request: FileUploadRequest,
+    ) -> Result<FileUpload> {
+        let uri = format!("{}/file_uploads", self.base_url);
+        let body = serde_json::to_vec(&request).unwrap();
+
+        match self.client.post_json(uri, body).await? {
+            Object::FileUpload { file_upload } => Ok(file_upload),
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
+
+    /// Sends the contents of a pending upload, or part `part_number`
+    /// (starting at 1) of a [multi-part](FileUploadMode::MultiPart) one.
+    pub async fn send_file_upload(
+        &self,
+        upload: &FileUpload,
+        contents: Vec<u8>,
+        part_number: Option<u32>,
+    ) -> Result<FileUpload> {
+        let uri = format!(
+            "{base_url}/file_uploads/{upload_id}/send",
+            base_url = self.base_url,
+            upload_id = upload.id
+        );
+        let filename = upload.filename.as_deref().unwrap_or("file");
+        let content_type = upload
+            .content_type
+            .as_deref()
+            .unwrap_or("application/octet-stream");
+        let mut form = MultipartForm::new().file("file", filename, content_type, contents);
+        if let Some(part_number) = part_number {
+            form = form.text("part_number", part_number.to_string());
+        }
+
+        match self.client.post_multipart(uri, form).await? {
+            Object::FileUpload { file_upload } => Ok(file_upload),
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
+
+    /// Completes a [multi-part](FileUploadMode::MultiPart) upload
+    /// once all of its parts are sent.
+    pub async fn complete_file_upload<U: AsIdentifier<FileUploadId>>(
+        &self,
+        upload_id: U,
+    ) -> Result<FileUpload> {
+        let uri = format!(
+            "{base_url}/file_uploads/{upload_id}/complete",
+            base_url = self.base_url,
+            upload_id = upload_id.as_id()
+        );
+
+        match self.client.post(uri).await? {
+            Object::FileUpload { file_upload } => Ok(file_upload),
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
+
+    /// Uploads `contents` as a file named `filename`, ready to be attached.
+    ///
+    /// The size is checked against the [workspace limits](Self::check_upload_size())
+    /// before anything is sent. Files larger than 20 MB are sent in parts
+    /// and completed once all parts arrived.
+    pub async fn upload_file(
+        &self,
+        filename: &str,
+        content_type: &str,
+        contents: Vec<u8>,
+    ) -> Result<FileUpload> {
+        self.check_upload_size(contents.len() as u64).await?;
+
+        let parts = if contents.len() > SINGLE_PART_LIMIT {
+            contents.chunks(PART_SIZE).map(<[u8]>::to_vec).collect()
+        } else {
+            vec![contents]
+        };
+        let multi_part = parts.len() > 1;
+        let request = FileUploadRequest {
+            mode: if multi_part {
+                FileUploadMode::MultiPart
+            } else {
+                FileUploadMode::SinglePart
+            },
+            filename: Some(filename.to_string()),
+            content_type: Some(content_type.to_string()),
+            number_of_parts: multi_part.then_some(parts.len() as u32),
+        };
+        let created = self.create_file_upload(request).await?;
+
+        let mut upload = created.clone();
+        for (part, contents) in parts.into_iter().enumerate() {
+            let part_number = multi_part.then_some(part as u32 + 1);
+            upload = self
+                .send_file_upload(&created, contents, part_number)
+                .await?;
+        }
+        if multi_part {
+            upload = self.complete_file_upload(&upload.id).await?;
+        }
+
+        match upload.status {
+            FileUploadStatus::Uploaded => Ok(upload),
+            _ => Err(Error::UnexpectedResponse {
+                response: Object::FileUpload {
+                    file_upload: upload,
+                },
+            }),
+        }
+    }
+
+    /// Reads the file at `path` and [uploads](Self::upload_file()) it under its
+    /// file name, with a content type guessed from the extension.
+    pub async fn upload_path<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> Result<FileUpload> {
+        let path = path.as_ref();
+        let contents = std::fs::read(path)?;
+        let filename = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "file".to_string());
+
+        self.upload_file(&filename, content_type_for(path), contents)
+            .await
+    }
+
+    /// Uploads the file at `path` and appends it to the content of `page`
+    /// as an image, video, PDF or file block, depending on its content type.
+    /// Returns the new block.
+    #[cfg(feature = "blocks")]
+    pub async fn attach_file_to_page<P, F>(
+        &self,
+        page: P,
+        path: F,
+    ) -> Result<Block>
+    where
+        P: AsIdentifier<PageId>,
+        F: AsRef<Path>,
+    {
+        let upload = self.upload_path(path).await?;
+        let block_type = block_type_for(upload.content_type.as_deref().unwrap_or_default());
+        // Uploads can't be expressed with the block models, which describe
+        // files as Notion returns them.
+        let request = serde_json::json!({
+            "children": [{
+                "type": block_type,
+                block_type: {
+                    "type": "file_upload",
+                    "file_upload": { "id": upload.id },
+                },
+            }]
+        });
+
+        let uri = format!(
+            "{base_url}/blocks/{block_id}/children",
+            base_url = self.base_url,
+            block_id = BlockId::from(page.as_id().clone())
+        );
+        let result = self
+            .client
+            .patch_json(uri, serde_json::to_vec(&request).unwrap())
+            .await?;
+
+        match result {
+            Object::List { list } if !list.results.is_empty() => {
+                Ok(list.expect_blocks()?.results.remove(0))
+            }
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
+
+    /// Replaces the files of the files property `property` of `page`
+    /// with `uploads`, named after their file names.
+    pub async fn set_files_property<P: AsIdentifier<PageId>>(
+        &self,
+        page: P,
+        property: &str,
+        uploads: &[FileUpload],
+    ) -> Result<Page> {
+        let files: Vec<_> = uploads
+            .iter()
+            .map(|upload| {
+                serde_json::json!({
+                    "type": "file_upload",
+                    "file_upload": { "id": upload.id },
+                    "name": upload.filename.clone().unwrap_or_else(|| upload.id.to_string()),
+                })
+            })
+            .collect();
+        // Addressed by name, so the property id doesn't need to be known.
+        let update = serde_json::json!({
+            "properties": { property: { "files": files } }
+        });
+
+        let uri = format!(
+            "{base_url}/pages/{page_id}",
+            base_url = self.base_url,
+            page_id = page.as_id()
+        );
+        let result = self
+            .client
+            .patch_json(uri, serde_json::to_vec(&update).unwrap())
+            .await?;
+
+        match result {
+            Object::Page { page } => Ok(page),
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{content_type_for, MultipartForm};
+    #[cfg(feature = "blocks")]
+    use crate::ids::BlockId;
+    use crate::ids::FileUploadId;
+    use crate::models::files::{FileUpload, FileUploadStatus};
+    use crate::models::Object;
+    #[cfg(feature = "blocks")]
+    use crate::test_util::{list_response, BlockFixture, PageFixture};
+    use crate::test_util::{person, MockNotion};
+    use crate::Error;
+    use serde_json::json;
+    use std::path::Path;
+    use std::str::FromStr;
+    #[cfg(feature = "blocks")]
+    use wiremock::matchers::body_json;
+    use wiremock::matchers::{body_partial_json, body_string_contains, method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    #[test]
+    fn encode_multipart_form() {
+        let form = MultipartForm::new()
+            .file("file", "notes.txt", "text/plain", b"hello".to_vec())
+            .text("part_number", "2");
+
+        let (content_type, body) = form.encode();
+        assert_eq!(
+            content_type,
+            "multipart/form-data; boundary=notion-wasi-boundary-0"
+        );
+        assert_eq!(
+            String::from_utf8(body).unwrap(),
+            "--notion-wasi-boundary-0\r\n\
+             Content-Disposition: form-data; name=\"file\"; filename=\"notes.txt\"\r\n\
+             Content-Type: text/plain\r\n\
+             \r\n\
+             hello\r\n\
+             --notion-wasi-boundary-0\r\n\
+             Content-Disposition: form-data; name=\"part_number\"\r\n\
+             \r\n\
+             2\r\n\
+             --notion-wasi-boundary-0--\r\n"
+        );
+
+        let tricky = MultipartForm::new().file(
+            "file",
+            "boundary.txt",
+            "text/plain",
+            b"--notion-wasi-boundary-0".to_vec(),
+        );
+        assert!(tricky
+            .encode()
+            .0
+            .ends_with("boundary=notion-wasi-boundary-1"));
+    }
+
+    #[test]
+    fn guess_content_types() {
+        assert_eq!(content_type_for(Path::new("report.PDF")), "application/pdf");
+        assert_eq!(content_type_for(Path::new("photo.jpeg")), "image/jpeg");
+        assert_eq!(
+            content_type_for(Path::new("archive")),
+            "application/octet-stream"
+        );
+    }
+
+    fn file_upload(
+        id: &str,
+        filename: &str,
+        content_type: &str,
+        status: FileUploadStatus,
+    ) -> FileUpload {
+        FileUpload {
+            id: FileUploadId::from_str(id).unwrap(),
+            status,
+            filename: Some(filename.to_string()),
+            content_type: Some(content_type.to_string()),
+            content_length: None,
+            expiry_time: None,
+            created_time: chrono::Utc::now(),
+            last_edited_time: chrono::Utc::now(),
+        }
+    }
+
+    #[cfg(feature = "blocks")]
+    #[tokio::test]
+    async fn attach_uploaded_files() {
+        let mock = MockNotion::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/users/me"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(Object::User {
+                user: person("Ada", "ada@example.com"),
+            }))
+            .mount(mock.server())
+            .await;
+        let page = PageFixture::new().title("Report").build();
+        let image = BlockFixture::new().image("https://files.example.com/chart.png");
+        let pending = file_upload("chart", "chart.png", "image/png", FileUploadStatus::Pending);
+        let uploaded = file_upload(
+            "chart",
+            "chart.png",
+            "image/png",
+            FileUploadStatus::Uploaded,
+        );
+
+        Mock::given(method("POST"))
+            .and(path("/v1/file_uploads"))
+            .and(body_partial_json(json!({
+                "mode": "single_part",
+                "filename": "chart.png",
+                "content_type": "image/png",
+            })))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(Object::FileUpload {
+                    file_upload: pending.clone(),
+                }),
+            )
+            .expect(1)
+            .mount(mock.server())
+            .await;
+        Mock::given(method("POST"))
+            .and(path(format!("/v1/file_uploads/{}/send", pending.id)))
+            .and(body_string_contains(r#"filename="chart.png""#))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(Object::FileUpload {
+                    file_upload: uploaded.clone(),
+                }),
+            )
+            .expect(1)
+            .mount(mock.server())
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path(format!(
+                "/v1/blocks/{}/children",
+                BlockId::from(page.id.clone())
+            )))
+            .and(body_partial_json(json!({
+                "children": [{
+                    "type": "image",
+                    "image": { "type": "file_upload", "file_upload": { "id": uploaded.id } },
+                }],
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(Object::List {
+                list: list_response(vec![Object::Block {
+                    block: image.clone(),
+                }]),
+            }))
+            .expect(1)
+            .mount(mock.server())
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path(format!("/v1/pages/{}", page.id)))
+            .and(body_json(json!({
+                "properties": { "Attachments": { "files": [{
+                    "type": "file_upload",
+                    "file_upload": { "id": uploaded.id },
+                    "name": "chart.png",
+                }] } }
+            })))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(Object::Page { page: page.clone() }),
+            )
+            .expect(1)
+            .mount(mock.server())
+            .await;
+
+        let file = std::env::temp_dir()
+            .join(format!("notion-upload-{}", page.id))
+            .join("chart.png");
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(&file, b"PNG").unwrap();
+
+        let api = mock.api();
+        let block = api.attach_file_to_page(&page.id, &file).await.unwrap();
+        assert_eq!(block, image);
+        let updated = api
+            .set_files_property(&page.id, "Attachments", &[uploaded])
+            .await
+            .unwrap();
+        assert_eq!(updated, page);
+        std::fs::remove_dir_all(file.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn upload_large_files_in_parts() {
+        let mock = MockNotion::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/users/me"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(Object::User {
+                user: person("Ada", "ada@example.com"),
+            }))
+            .mount(mock.server())
+            .await;
+        let pending = file_upload(
+            "archive",
+            "archive.zip",
+            "application/zip",
+            FileUploadStatus::Pending,
+        );
+        Mock::given(method("POST"))
+            .and(path("/v1/file_uploads"))
+            .and(body_partial_json(json!({
+                "mode": "multi_part",
+                "number_of_parts": 3,
+            })))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(Object::FileUpload {
+                    file_upload: pending.clone(),
+                }),
+            )
+            .expect(1)
+            .mount(mock.server())
+            .await;
+        Mock::given(method("POST"))
+            .and(path(format!("/v1/file_uploads/{}/send", pending.id)))
+            .and(body_string_contains(r#"name="part_number""#))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(Object::FileUpload {
+                    file_upload: pending.clone(),
+                }),
+            )
+            .expect(3)
+            .mount(mock.server())
+            .await;
+        Mock::given(method("POST"))
+            .and(path(format!("/v1/file_uploads/{}/complete", pending.id)))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(Object::FileUpload {
+                    file_upload: file_upload(
+                        "archive",
+                        "archive.zip",
+                        "application/zip",
+                        FileUploadStatus::Uploaded,
+                    ),
+                }),
+            )
+            .expect(1)
+            .mount(mock.server())
+            .await;
+
+        let upload = mock
+            .api()
+            .upload_file("archive.zip", "application/zip", vec![0; 21 * 1024 * 1024])
+            .await
+            .unwrap();
+        assert_eq!(upload.status, FileUploadStatus::Uploaded);
+    }
+
+    #[tokio::test]
+    async fn oversized_uploads_are_not_sent() {
+        let mock = MockNotion::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/users/me"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "object": "user",
+                "id": "16d84021-db59-4e4f-9e66-f2225ac12ec3",
+                "name": "Importer",
+                "avatar_url": null,
+                "type": "bot",
+                "bot": {
+                    "owner": { "type": "workspace", "workspace": true },
+                    "workspace_name": "Acme",
+                    "workspace_limits": { "max_file_upload_size_in_bytes": 4 }
+                }
+            })))
+            .mount(mock.server())
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/file_uploads"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(0)
+            .mount(mock.server())
+            .await;
+
+        assert!(matches!(
+            mock.api()
+                .upload_file("notes.txt", "text/plain", b"too long".to_vec())
+                .await,
+            Err(Error::FileTooLarge { size: 8, limit: 4 })
+        ));
+    }
+}
@@ -0,0 +1,319 @@
+use crate::ids::{AsIdentifier, UserId};
+use crate::models::paging::PagingCursor;
+use crate::models::properties::PropertyValue;
+use crate::models::text::RichText;
+use crate::models::users::{User, UserReference, WorkspaceLimits};
+use crate::models::{ListResponse, Object, Page};
+use crate::{Error, NotionApi, Result, TClient};
+
+impl<C: TClient> NotionApi<C> {
+    /// Get a user by [UserId].
+    pub async fn get_user<U: AsIdentifier<UserId>>(
+        &self,
+        user_id: U,
+    ) -> Result<User> {
+        let uri = format!("{}/users/{}", self.base_url, user_id.as_id());
+        let result = self.client.get(uri).await?;
+
+        match result {
+            Object::User { user } => Ok(user),
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
+
+    /// The users of the workspace, one page of results starting at `cursor`.
+    /// Guests are not included.
+    pub async fn list_users(
+        &self,
+        cursor: Option<PagingCursor>,
+    ) -> Result<ListResponse<User>> {
+        let mut uri = format!("{}/users?page_size=100", self.base_url);
+        if let Some(cursor) = &cursor {
+            uri.push_str("&start_cursor=");
+            uri.push_str(cursor.value());
+        }
+
+        match self.client.get(uri).await? {
+            Object::List { list } => Ok(list.expect_users()?),
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
+
+    /// The person with the email `email`, compared case-insensitively.
+    ///
+    /// The first lookup pages through [list_users()](Self::list_users()) and remembers
+    /// every user, later lookups are answered without requests until
+    /// [forget_users()](Self::forget_users()) is called, so people who joined
+    /// since aren't found before that.
+    /// Emails are only visible to integrations with the user information capability,
+    /// without it no one is found.
+    pub async fn find_user_by_email(
+        &self,
+        email: &str,
+    ) -> Result<Option<User>> {
+        if self.emails.lock().unwrap().is_none() {
+            let users = self.list_all_users().await?;
+            let emails = users
+                .iter()
+                .filter_map(|user| Some((email_of(user)?.to_lowercase(), user.id().clone())))
+                .collect();
+            self.users
+                .lock()
+                .unwrap()
+                .extend(users.into_iter().map(|user| (user.id().clone(), user)));
+            *self.emails.lock().unwrap() = Some(emails);
+        }
+
+        let id = self
+            .emails
+            .lock()
+            .unwrap()
+            .as_ref()
+            .and_then(|emails| emails.get(&email.to_lowercase()).cloned());
+        Ok(id.and_then(|id| self.users.lock().unwrap().get(&id).cloned()))
+    }
+
+    /// Forgets the users remembered by [find_user_by_email()](Self::find_user_by_email())
+    /// and [resolve_people()](Self::resolve_people()).
+    pub fn forget_users(&self) {
+        self.users.lock().unwrap().clear();
+        *self.emails.lock().unwrap() = None;
+    }
+
+    async fn list_all_users(&self) -> Result<Vec<User>> {
+        let mut users = vec![];
+        let mut cursor = None;
+        loop {
+            let list = self.list_users(cursor).await?;
+            users.extend(list.results);
+            match list.next_cursor {
+                Some(next) if list.has_more => cursor = Some(next),
+                _ => return Ok(users),
+            }
+        }
+    }
+
+    /// A mention of the person with the email `email`, see [find_user_by_email()](Self::find_user_by_email()).
+    pub async fn mention_user_by_email(
+        &self,
+        email: &str,
+    ) -> Result<Option<RichText>> {
+        Ok(self
+            .find_user_by_email(email)
+            .await?
+            .map(RichText::user_mention))
+    }
+
+    /// The bot user of the integration, with its owner and [WorkspaceLimits].
+    pub async fn get_self(&self) -> Result<User> {
+        let uri = format!("{}/users/me", self.base_url);
+        match self.client.get(uri).await? {
+            Object::User { user } => Ok(user),
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
+
+    /// The limits of the workspace, fetched with [get_self()](Self::get_self())
+    /// on the first call and remembered.
+    pub async fn workspace_limits(&self) -> Result<WorkspaceLimits> {
+        let cached = self.limits.lock().unwrap().clone();
+        if let Some(limits) = cached {
+            return Ok(limits);
+        }
+
+        let limits = match self.get_self().await? {
+            User::Bot { bot, .. } => bot.workspace_limits.unwrap_or_default(),
+            User::Person { .. } => WorkspaceLimits::default(),
+        };
+        *self.limits.lock().unwrap() = Some(limits.clone());
+        Ok(limits)
+    }
+
+    /// Fails with [Error::FileTooLarge] if a file of `size` bytes is larger
+    /// than the workspace accepts, so uploads fail before any bytes are sent.
+    /// Passes if Notion didn't report a limit.
+    pub async fn check_upload_size(
+        &self,
+        size: u64,
+    ) -> Result<()> {
+        match self.workspace_limits().await?.max_file_upload_size_in_bytes {
+            Some(limit) if size > limit => Err(Error::FileTooLarge { size, limit }),
+            _ => Ok(()),
+        }
+    }
+
+    /// The full users of the `property` people property of `page`.
+    ///
+    /// Users that Notion only referenced by id are fetched with [get_user()](Self::get_user())
+    /// and remembered, so each user is requested at most once per client.
+    /// Returns an empty list if `page` has no people property with that name.
+    pub async fn resolve_people(
+        &self,
+        page: &Page,
+        property: &str,
+    ) -> Result<Vec<User>> {
+        let people = match page.properties.properties.get(property) {
+            Some(PropertyValue::People { people, .. }) => people,
+            _ => return Ok(vec![]),
+        };
+
+        let mut users = Vec::with_capacity(people.len());
+        for person in people {
+            users.push(self.resolve_user(person).await?);
+        }
+        Ok(users)
+    }
+
+    async fn resolve_user(
+        &self,
+        user: &UserReference,
+    ) -> Result<User> {
+        if let Some(user) = user.user() {
+            return Ok(user.clone());
+        }
+
+        let cached = self.users.lock().unwrap().get(user.id()).cloned();
+        match cached {
+            Some(user) => Ok(user),
+            None => {
+                let fetched = self.get_user(user.id()).await?;
+                self.users
+                    .lock()
+                    .unwrap()
+                    .insert(fetched.id().clone(), fetched.clone());
+                Ok(fetched)
+            }
+        }
+    }
+}
+
+fn email_of(user: &User) -> Option<&str> {
+    match user {
+        User::Person { person, .. } => person.email.as_deref(),
+        User::Bot { .. } => None,
+    }
+}
+
+#[cfg(all(test, not(target_os = "wasi")))]
+mod tests {
+    use crate::models::users::UserReference;
+    use crate::models::Object;
+    use crate::test_util::{list_response, person, MockNotion, PageFixture};
+    use crate::Error;
+    use serde_json::json;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    #[tokio::test]
+    async fn resolve_people_fetches_partial_users_once() {
+        let mock = MockNotion::start().await;
+        let full = person("Ada", "ada@example.com");
+        let partial = person("Grace", "grace@example.com");
+        mock.mount_user(&partial).await;
+        let reference = UserReference::Partial {
+            id: partial.id().clone(),
+        };
+        let page = PageFixture::new()
+            .people("Owners", vec![full.clone().into(), reference.clone()])
+            .build();
+
+        let api = mock.api();
+        let people = api.resolve_people(&page, "Owners").await.unwrap();
+        assert_eq!(people, vec![full, partial.clone()]);
+
+        let page = PageFixture::new().people("Owners", vec![reference]).build();
+        assert_eq!(
+            api.resolve_people(&page, "Owners").await.unwrap(),
+            vec![partial]
+        );
+        assert_eq!(mock.server().received_requests().await.unwrap().len(), 1);
+        assert!(api
+            .resolve_people(&page, "Missing")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn mention_user_by_email() {
+        let mock = MockNotion::start().await;
+        let ada = person("Ada", "ada@example.com");
+        let users = list_response(vec![
+            Object::User {
+                user: person("Grace", "grace@example.com"),
+            },
+            Object::User { user: ada.clone() },
+        ]);
+        Mock::given(method("GET"))
+            .and(path("/v1/users"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(Object::List { list: users }))
+            .expect(2)
+            .mount(mock.server())
+            .await;
+
+        let api = mock.api();
+        let mention = api
+            .mention_user_by_email("ADA@example.com")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(mention.plain_text(), "@Ada");
+        assert_eq!(
+            serde_json::to_value(&mention).unwrap()["mention"]["user"]["id"],
+            json!(ada.id().to_string())
+        );
+        assert!(api
+            .find_user_by_email("nobody@example.com")
+            .await
+            .unwrap()
+            .is_none());
+
+        // Answered from the cache until the users are forgotten.
+        assert_eq!(
+            api.find_user_by_email("grace@example.com")
+                .await
+                .unwrap()
+                .unwrap()
+                .common()
+                .name
+                .as_deref(),
+            Some("Grace")
+        );
+        api.forget_users();
+        assert_eq!(
+            api.find_user_by_email("ada@example.com").await.unwrap(),
+            Some(ada)
+        );
+    }
+
+    #[tokio::test]
+    async fn workspace_limits_validate_uploads() {
+        let mock = MockNotion::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/users/me"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "object": "user",
+                "id": "16d84021-db59-4e4f-9e66-f2225ac12ec3",
+                "name": "Importer",
+                "avatar_url": null,
+                "type": "bot",
+                "bot": {
+                    "owner": { "type": "workspace", "workspace": true },
+                    "workspace_name": "Acme",
+                    "workspace_limits": { "max_file_upload_size_in_bytes": 5242880 }
+                }
+            })))
+            .expect(1)
+            .mount(mock.server())
+            .await;
+
+        let api = mock.api();
+        api.check_upload_size(5242880).await.unwrap();
+        match api.check_upload_size(5242881).await {
+            Err(Error::FileTooLarge { size, limit }) => {
+                assert_eq!((size, limit), (5242881, 5242880))
+            }
+            result => panic!("Expected the file to be too large, got {:?}", result),
+        }
+    }
+}
@@ -0,0 +1,517 @@
+use std::convert::{Infallible, TryFrom};
+use std::fmt;
+use std::io::{Read, Write};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use super::codec::{decode_response, ResponseHead, REQUEST_ID_HEADERS};
+use super::stats::{RateLimitStatus, RequestStats, Stats};
+use crate::ids::PageId;
+use crate::models::{
+    error::{ErrorResponse, HttpErrorResponse},
+    files::FileSource,
+    oauth::OAuthErrorResponse,
+    rows::RowError,
+    search::InvalidQuery,
+    InvalidPage, Object,
+};
+use crate::{ApiVersion, ClientBuilder, JsonCodec, MultipartForm, SecretString, TClient};
+
+use async_trait::async_trait;
+use wasip2::http::outgoing_handler;
+use wasip2::http::types::{
+    ErrorCode, Fields, IncomingBody, Method, OutgoingBody, OutgoingRequest, RequestOptions, Scheme,
+};
+
+/// An wrapper Error type for all errors produced by the [`NotionApi`](NotionApi) client.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Invalid header {}", name)]
+    InvalidHeader { name: String },
+
+    #[error("Error sending HTTP request: {}", source)]
+    RequestFailed {
+        #[from]
+        source: ErrorCode,
+    },
+
+    #[error("Error reading response: {}", source)]
+    ResponseIoError { source: std::io::Error },
+
+    #[error("Error parsing json response: {}", source)]
+    JsonParseError {
+        source: serde_json::Error,
+        /// The start of the response that failed to parse, if it was one.
+        body: Option<String>,
+    },
+
+    #[error("Error decoding response: {}", source)]
+    DecodeFailed {
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    #[error("Unexpected API Response")]
+    UnexpectedResponse { response: Object },
+
+    #[error("API Error {}({}): {}", .error.code, .error.status, .error.message)]
+    ApiError { error: ErrorResponse },
+
+    /// A response with an error status that isn't a Notion error,
+    /// such as the HTML page of a gateway error.
+    #[error("Unexpected HTTP status {}", .response.status)]
+    HttpError { response: HttpErrorResponse },
+
+    #[error("OAuth error {}", .error.error)]
+    OAuthError { error: OAuthErrorResponse },
+
+    #[error("Invalid query: {}", source)]
+    InvalidQuery { source: InvalidQuery },
+
+    #[error("Invalid page: {}", source)]
+    InvalidPage { source: InvalidPage },
+
+    #[error("Unable to read page {}: {}", page, source)]
+    InvalidRow { page: PageId, source: RowError },
+
+    #[error("Found {} pages where `{}` matches the upsert key", .pages.len(), .property)]
+    DuplicatePages {
+        property: String,
+        pages: Vec<PageId>,
+    },
+
+    #[error(
+        "The file is {} bytes, but the workspace accepts at most {} bytes",
+        size,
+        limit
+    )]
+    FileTooLarge { size: u64, limit: u64 },
+
+    #[error("Environment variable {} is not set", name)]
+    MissingEnvVar { name: String },
+
+    #[error("Invalid environment variable {}: {}", name, reason)]
+    InvalidEnvVar { name: String, reason: String },
+
+    #[error("No recorded response for {} {}", method, uri)]
+    NoRecordedResponse { method: String, uri: String },
+
+    #[error("The file is no longer attached to {:?}", location)]
+    FileRemoved { location: FileSource },
+
+    #[error("Downloading {} failed with status {}", url, status)]
+    DownloadFailed { url: String, status: u16 },
+
+    #[error("IO error: {}", source)]
+    Io {
+        #[from]
+        source: std::io::Error,
+    },
+
+    #[error("Infallible")]
+    Infallible(#[from] Infallible),
+}
+
+/// An API client for Notion, sending requests through the `wasi:http`
+/// outgoing handler of the host.
+/// Create a client by using [new(api_token: String)](Self::new()).
+#[derive(Clone)]
+pub struct Client {
+    token: SecretString,
+    version: ApiVersion,
+    user_agent: String,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    headers: Vec<(String, String)>,
+    codec: Arc<dyn JsonCodec>,
+    pub(crate) stats: Stats,
+}
+
+impl Client {
+    /// Creates an instance of NotionApi.
+    /// Never fail.
+    pub fn new<S: Into<SecretString>>(api_token: S) -> Result<Self, Infallible> {
+        Self::with_version(api_token, ApiVersion::default())
+    }
+
+    /// Creates a client that sends `Notion-Version: {version}`.
+    /// Never fail.
+    pub fn with_version<S: Into<SecretString>>(
+        api_token: S,
+        version: ApiVersion,
+    ) -> Result<Self, Infallible> {
+        Ok(Self::configured(
+            ClientBuilder::new(api_token).version(version),
+        ))
+    }
+
+    /// The same client sending `Notion-Version: {version}`, for the requests
+    /// that need another version than the rest.
+    /// It shares the [stats()](Self::stats()) of this client.
+    pub fn for_version(
+        &self,
+        version: ApiVersion,
+    ) -> Self {
+        Client {
+            version,
+            ..self.clone()
+        }
+    }
+
+    #[allow(clippy::result_large_err)]
+    pub(crate) fn from_builder(builder: ClientBuilder) -> Result<Self, Error> {
+        let headers = Fields::new();
+        for (name, value) in &builder.headers {
+            if headers.append(name, value.as_bytes()).is_err() {
+                return Err(Error::InvalidHeader { name: name.clone() });
+            }
+        }
+        Ok(Self::configured(builder))
+    }
+
+    fn configured(builder: ClientBuilder) -> Self {
+        Self {
+            user_agent: builder.user_agent_header(),
+            timeout: builder.timeout,
+            connect_timeout: builder.connect_timeout,
+            headers: builder.headers,
+            token: builder.token,
+            version: builder.version,
+            codec: builder.codec,
+            stats: Stats::new(builder.on_request),
+        }
+    }
+
+    /// The timeouts of the builder. Hosts only offer timeouts for connecting,
+    /// for the first byte of the response and between bytes, so the overall
+    /// timeout is applied to the latter two. Hosts that don't support them ignore them.
+    fn options(&self) -> RequestOptions {
+        let nanos = |duration: Duration| u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX);
+        let options = RequestOptions::new();
+        if let Some(timeout) = self.connect_timeout {
+            let _ = options.set_connect_timeout(Some(nanos(timeout)));
+        }
+        if let Some(timeout) = self.timeout {
+            let _ = options.set_first_byte_timeout(Some(nanos(timeout)));
+            let _ = options.set_between_bytes_timeout(Some(nanos(timeout)));
+        }
+        options
+    }
+}
+
+impl fmt::Debug for Client {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        f.debug_struct("Client")
+            .field("token", &self.token)
+            .field("version", &self.version)
+            .field("user_agent", &self.user_agent)
+            .field("timeout", &self.timeout)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("headers", &self.headers)
+            .field("codec", &self.codec)
+            .field("stats", &self.stats)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl TClient for Client {
+    fn api_version(&self) -> ApiVersion {
+        self.version
+    }
+
+    fn rate_limit_status(&self) -> Option<RateLimitStatus> {
+        Some(Client::rate_limit_status(self))
+    }
+
+    async fn get<S: Into<String> + Send>(
+        &self,
+        uri: S,
+    ) -> crate::Result<Object> {
+        self.make_json_request(Method::Get, &uri.into(), None)
+    }
+
+    async fn post<S: Into<String> + Send>(
+        &self,
+        uri: S,
+    ) -> crate::Result<Object> {
+        self.make_json_request(Method::Post, &uri.into(), None)
+    }
+
+    async fn post_json<S: Into<String> + Send>(
+        &self,
+        uri: S,
+        body: Vec<u8>,
+    ) -> crate::Result<Object> {
+        self.make_json_request(Method::Post, &uri.into(), Some((JSON, &body)))
+    }
+
+    async fn patch_json<S: Into<String> + Send>(
+        &self,
+        uri: S,
+        body: Vec<u8>,
+    ) -> crate::Result<Object> {
+        self.make_json_request(Method::Patch, &uri.into(), Some((JSON, &body)))
+    }
+
+    async fn post_multipart<S: Into<String> + Send>(
+        &self,
+        uri: S,
+        form: MultipartForm,
+    ) -> crate::Result<Object> {
+        let (content_type, body) = form.encode();
+        self.make_json_request(Method::Post, &uri.into(), Some((&content_type, &body)))
+    }
+
+    async fn delete<S: Into<String> + Send>(
+        &self,
+        uri: S,
+    ) -> crate::Result<Object> {
+        self.make_json_request(Method::Delete, &uri.into(), None)
+    }
+}
+
+const JSON: &str = "application/json";
+
+impl Client {
+    /// Sends a request with the Notion headers and a `body` of the given content type,
+    /// and reads the JSON response. The host blocks on it, like the `http_req` client.
+    #[allow(clippy::result_large_err)]
+    fn make_json_request(
+        &self,
+        method: Method,
+        url: &str,
+        body: Option<(&str, &[u8])>,
+    ) -> Result<Object, Error> {
+        let method_name = method_name(&method);
+        let mut stats = RequestStats::new(method_name, url, body.map_or(0, |(_, body)| body.len()));
+        #[cfg(feature = "log-bodies")]
+        if let Some((_, body)) = body {
+            super::body_log::log_request(method_name, url, body);
+        }
+        let authorization = format!("Bearer {}", self.token.expose_secret());
+        let mut headers: Vec<(&str, &str)> = self
+            .headers
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect();
+        headers.extend([
+            ("Notion-Version", self.version.as_str()),
+            ("User-Agent", &self.user_agent),
+            ("Authorization", &authorization),
+        ]);
+        if let Some((content_type, _)) = body {
+            headers.push(("Content-Type", content_type));
+        }
+
+        let start = Instant::now();
+        let response = send(
+            method,
+            url,
+            &headers,
+            body.map_or(&[], |(_, body)| body),
+            Some(self.options()),
+        );
+        stats.elapsed = start.elapsed();
+        stats.status = response.as_ref().ok().map(|response| response.status);
+        stats.response_bytes = response.as_ref().map_or(0, |response| response.body.len());
+        self.stats.record(stats);
+        let response = response?;
+
+        debug!(status = response.status, "Response");
+        #[cfg(feature = "log-bodies")]
+        super::body_log::log_response(&response.body);
+        let head = ResponseHead {
+            status: response.status,
+            request_id: REQUEST_ID_HEADERS
+                .iter()
+                .find_map(|name| response.header(name))
+                .map(str::to_string),
+            retry_after: response
+                .header("Retry-After")
+                .and_then(super::retry::parse_retry_after),
+        };
+        decode_response(self.codec.as_ref(), head, &response.body)
+    }
+}
+
+/// A response read to the end.
+struct Response {
+    status: u16,
+    headers: Vec<(String, Vec<u8>)>,
+    body: Vec<u8>,
+}
+
+impl Response {
+    fn header(
+        &self,
+        name: &str,
+    ) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .and_then(|(_, value)| std::str::from_utf8(value).ok())
+    }
+}
+
+fn method_name(method: &Method) -> &str {
+    match method {
+        Method::Get => "GET",
+        Method::Head => "HEAD",
+        Method::Post => "POST",
+        Method::Put => "PUT",
+        Method::Delete => "DELETE",
+        Method::Connect => "CONNECT",
+        Method::Options => "OPTIONS",
+        Method::Trace => "TRACE",
+        Method::Patch => "PATCH",
+        Method::Other(method) => method,
+    }
+}
+
+/// Sends a request through the outgoing handler and reads the whole response.
+#[allow(clippy::result_large_err)]
+fn send(
+    method: Method,
+    url: &str,
+    headers: &[(&str, &str)],
+    body: &[u8],
+    options: Option<RequestOptions>,
+) -> Result<Response, Error> {
+    let fields = Fields::new();
+    for (name, value) in headers {
+        fields
+            .append(name, value.as_bytes())
+            .map_err(|_| Error::InvalidHeader {
+                name: name.to_string(),
+            })?;
+    }
+    let request = outgoing_request(fields, &method, url)?;
+    let outgoing_body = request
+        .body()
+        .map_err(|()| internal_error("The request body was already taken"))?;
+
+    let future = outgoing_handler::handle(request, options)?;
+    if !body.is_empty() {
+        let mut stream = outgoing_body
+            .write()
+            .map_err(|()| internal_error("The request body stream was already taken"))?;
+        stream.write_all(body)?;
+        Write::flush(&mut stream)?;
+    }
+    OutgoingBody::finish(outgoing_body, None)?;
+
+    future.subscribe().block();
+    let response = match future.get() {
+        Some(Ok(response)) => response?,
+        Some(Err(())) | None => return Err(internal_error("The response was already taken")),
+    };
+    let status = response.status();
+    let headers = response.headers().entries();
+    let incoming_body = response
+        .consume()
+        .map_err(|()| internal_error("The response body was already taken"))?;
+    let mut body = Vec::new();
+    {
+        let mut stream = incoming_body
+            .stream()
+            .map_err(|()| internal_error("The response body stream was already taken"))?;
+        stream
+            .read_to_end(&mut body)
+            .map_err(|source| Error::ResponseIoError { source })?;
+    }
+    drop(IncomingBody::finish(incoming_body));
+
+    Ok(Response {
+        status,
+        headers,
+        body,
+    })
+}
+
+/// Splits `url` into the scheme, authority and path the host expects.
+#[allow(clippy::result_large_err)]
+fn outgoing_request(
+    headers: Fields,
+    method: &Method,
+    url: &str,
+) -> Result<OutgoingRequest, Error> {
+    let invalid = || Error::RequestFailed {
+        source: ErrorCode::HttpRequestUriInvalid,
+    };
+    let (scheme, rest) = url.split_once("://").ok_or_else(invalid)?;
+    let scheme = match scheme {
+        "https" => Scheme::Https,
+        "http" => Scheme::Http,
+        other => Scheme::Other(other.to_string()),
+    };
+    let (authority, path) = rest.split_at(rest.find(['/', '?']).unwrap_or(rest.len()));
+    let path = if path.starts_with('/') {
+        path.to_string()
+    } else {
+        format!("/{}", path)
+    };
+
+    let request = OutgoingRequest::new(headers);
+    request
+        .set_method(method)
+        .and_then(|()| request.set_scheme(Some(&scheme)))
+        .and_then(|()| request.set_authority(Some(authority)))
+        .and_then(|()| request.set_path_with_query(Some(&path)))
+        .map_err(|()| invalid())?;
+    Ok(request)
+}
+
+fn internal_error(message: &str) -> Error {
+    Error::RequestFailed {
+        source: ErrorCode::InternalError(Some(message.to_string())),
+    }
+}
+
+/// Sends `body` as JSON with HTTP Basic authentication instead of the integration token,
+/// and returns the status and the body of the response.
+pub(crate) async fn post_with_basic_auth(
+    url: &str,
+    username: &str,
+    password: &SecretString,
+    body: Vec<u8>,
+) -> Result<(u16, Vec<u8>), Error> {
+    let credentials =
+        super::oauth::base64(format!("{}:{}", username, password.expose_secret()).as_bytes());
+    let authorization = format!("Basic {}", credentials);
+    let headers = [
+        ("Authorization", authorization.as_str()),
+        ("Content-Type", "application/json"),
+    ];
+    let response = send(Method::Post, url, &headers, &body, None)?;
+    Ok((response.status, response.body))
+}
+
+/// Downloads `url` without the Notion headers, which the presigned URLs
+/// of uploaded files reject.
+pub(crate) async fn download(url: &str) -> Result<Vec<u8>, Error> {
+    let response = send(Method::Get, url, &[], &[], None)?;
+    if !(200..300).contains(&response.status) {
+        return Err(Error::DownloadFailed {
+            url: url.to_string(),
+            status: response.status,
+        });
+    }
+    Ok(response.body)
+}
+
+/// Like [download()], writing the body to `writer`.
+/// Returns the number of bytes written.
+///
+/// The body is buffered and only written once the download succeeded.
+pub(crate) async fn download_to<W: std::io::Write>(
+    url: &str,
+    writer: &mut W,
+) -> Result<u64, Error> {
+    let body = download(url).await?;
+    writer.write_all(&body)?;
+    writer.flush()?;
+    Ok(body.len() as u64)
+}
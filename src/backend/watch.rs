@@ -0,0 +1,524 @@
+//! Polling databases and pages for changes, since Notion has no webhooks.
+//!
+//! ```no_run
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//!
+//! use futures::StreamExt;
+//! use notion_wasi::ids::DatabaseId;
+//! use notion_wasi::{Change, FileCursorStore, NotionApi, WatchOptions};
+//!
+//! let api = NotionApi::new(std::env::var("NOTION_API_TOKEN")?)?;
+//! let database: DatabaseId = "0e7dd6b2-5702-4ae4-a6b2-1e0a2a3c6bcc".parse()?;
+//! let options = WatchOptions::new(Duration::from_secs(60))
+//!     .store(Arc::new(FileCursorStore::new("watch.json")));
+//!
+//! let mut changes = Box::pin(api.watch_database_with(&database, options));
+//! while let Some(change) = changes.next().await {
+//!     match change? {
+//!         Change::Created(page) => println!("created {}", page.id),
+//!         Change::Updated(page) => println!("updated {}", page.id),
+//!         Change::Archived(page) => println!("archived {}", page.id),
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Each poll queries the pages edited since the latest `last_edited_time` seen so far.
+//! Notion only records that time to the minute, so a page edited twice within a minute
+//! is only reported once, and a page is reported again once it was edited in a later minute.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use futures::stream::{self, Stream};
+use serde::{Deserialize, Serialize};
+
+use super::retry::sleep;
+use crate::ids::{AsIdentifier, DatabaseId, PageId};
+use crate::models::search::{
+    DatabaseQuery, DatabaseSortTimestamp, DateCondition, FilterCondition, SortDirection,
+};
+use crate::models::Page;
+use crate::{CollectAll, Error, NotionApi, Result, TClient};
+
+/// A change of a watched page.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum Change {
+    /// A page created since the last poll, or any page on the first poll
+    /// of a watch without a cursor, see [WatchOptions::since()].
+    Created(Page),
+    Updated(Page),
+    /// A page archived or moved to the trash since the last poll.
+    Archived(Page),
+}
+
+impl Change {
+    pub fn page(&self) -> &Page {
+        match self {
+            Change::Created(page) | Change::Updated(page) | Change::Archived(page) => page,
+        }
+    }
+
+    pub fn into_page(self) -> Page {
+        match self {
+            Change::Created(page) | Change::Updated(page) | Change::Archived(page) => page,
+        }
+    }
+}
+
+/// How far a watch got, kept in a [CursorStore] between polls.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct WatchCursor {
+    /// The latest `last_edited_time` reported.
+    pub last_edited_time: DateTime<Utc>,
+    /// The pages reported with exactly that time, which the next poll returns again.
+    #[serde(default)]
+    pub seen: Vec<PageId>,
+}
+
+/// Keeps the [WatchCursor] of each watch, so a watch started again continues
+/// where the last one stopped instead of reporting every page again.
+///
+/// Watches are keyed by the id of the database they watch.
+pub trait CursorStore: Send + Sync {
+    #[allow(clippy::result_large_err)]
+    fn load(
+        &self,
+        key: &str,
+    ) -> Result<Option<WatchCursor>>;
+
+    #[allow(clippy::result_large_err)]
+    fn save(
+        &self,
+        key: &str,
+        cursor: &WatchCursor,
+    ) -> Result<()>;
+}
+
+/// Keeps cursors for as long as the store lives, the default of [WatchOptions].
+#[derive(Debug, Default)]
+pub struct MemoryCursorStore {
+    cursors: Mutex<HashMap<String, WatchCursor>>,
+}
+
+impl CursorStore for MemoryCursorStore {
+    fn load(
+        &self,
+        key: &str,
+    ) -> Result<Option<WatchCursor>> {
+        Ok(self.cursors.lock().unwrap().get(key).cloned())
+    }
+
+    fn save(
+        &self,
+        key: &str,
+        cursor: &WatchCursor,
+    ) -> Result<()> {
+        self.cursors
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), cursor.clone());
+        Ok(())
+    }
+}
+
+/// Keeps cursors in a JSON file, which may be shared by several watches.
+#[derive(Debug, Clone)]
+pub struct FileCursorStore {
+    path: PathBuf,
+}
+
+impl FileCursorStore {
+    /// The file is created by the first save.
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        FileCursorStore { path: path.into() }
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn read(&self) -> Result<BTreeMap<String, WatchCursor>> {
+        match std::fs::read(&self.path) {
+            Ok(json) => serde_json::from_slice(&json)
+                .map_err(|source| Error::JsonParseError { source, body: None }),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+            Err(error) => Err(error.into()),
+        }
+    }
+}
+
+impl CursorStore for FileCursorStore {
+    fn load(
+        &self,
+        key: &str,
+    ) -> Result<Option<WatchCursor>> {
+        Ok(self.read()?.remove(key))
+    }
+
+    fn save(
+        &self,
+        key: &str,
+        cursor: &WatchCursor,
+    ) -> Result<()> {
+        let mut cursors = self.read()?;
+        cursors.insert(key.to_string(), cursor.clone());
+        let json = serde_json::to_vec_pretty(&cursors).unwrap();
+        Ok(std::fs::write(&self.path, json)?)
+    }
+}
+
+/// Configures [NotionApi::watch_database_with()].
+#[derive(Clone)]
+pub struct WatchOptions {
+    interval: Duration,
+    since: Option<DateTime<Utc>>,
+    store: Arc<dyn CursorStore>,
+}
+
+impl WatchOptions {
+    /// Polls every `interval`, keeping the cursor in memory.
+    pub fn new(interval: Duration) -> Self {
+        WatchOptions {
+            interval,
+            since: None,
+            store: Arc::new(MemoryCursorStore::default()),
+        }
+    }
+
+    /// Only reports pages edited at or after `since` when the store has no cursor
+    /// for the watch yet. Without it, the first poll reports every page of the
+    /// database as [Change::Created], leaving out the pages in the trash.
+    pub fn since(
+        mut self,
+        since: DateTime<Utc>,
+    ) -> Self {
+        self.since = Some(since);
+        self
+    }
+
+    pub fn store(
+        mut self,
+        store: Arc<dyn CursorStore>,
+    ) -> Self {
+        self.store = store;
+        self
+    }
+}
+
+impl fmt::Debug for WatchOptions {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        f.debug_struct("WatchOptions")
+            .field("interval", &self.interval)
+            .field("since", &self.since)
+            .finish_non_exhaustive()
+    }
+}
+
+struct DatabaseWatch<'a, C> {
+    api: &'a NotionApi<C>,
+    database: DatabaseId,
+    options: WatchOptions,
+    /// `None` until loaded from the store.
+    cursor: Option<Option<WatchCursor>>,
+    /// Whether `cursor` changed since it was saved.
+    unsaved: bool,
+    polled: bool,
+    changes: VecDeque<Change>,
+}
+
+impl<C: TClient> DatabaseWatch<'_, C> {
+    async fn next(&mut self) -> Result<Change> {
+        loop {
+            if let Some(change) = self.changes.pop_front() {
+                return Ok(change);
+            }
+            // Saved once all changes were handed out, so that none of them are lost
+            // when the process stops in between.
+            if self.unsaved {
+                if let Some(Some(cursor)) = &self.cursor {
+                    self.options
+                        .store
+                        .save(&self.database.to_string(), cursor)?;
+                }
+                self.unsaved = false;
+            }
+            if self.polled {
+                sleep(self.options.interval).await;
+            }
+            self.polled = true;
+            self.poll().await?;
+        }
+    }
+
+    async fn poll(&mut self) -> Result<()> {
+        let cursor = match self.cursor.take() {
+            Some(cursor) => cursor,
+            None => self.options.store.load(&self.database.to_string())?,
+        };
+        let since = cursor
+            .as_ref()
+            .map(|cursor| cursor.last_edited_time)
+            .or(self.options.since);
+        self.cursor = Some(cursor);
+
+        let mut query = DatabaseQuery::builder()
+            .sort_by_timestamp(
+                DatabaseSortTimestamp::LastEditedTime,
+                SortDirection::Ascending,
+            )
+            .include_trashed();
+        if let Some(since) = since {
+            query = query.filter(FilterCondition::last_edited_time(DateCondition::OnOrAfter(
+                since,
+            )));
+        }
+        let mut pages = self
+            .api
+            .query_database_stream(&self.database, query.build())
+            .collect_all()
+            .await?;
+        pages.sort_by_key(|page| page.last_edited_time);
+
+        let cursor = self.cursor.as_mut().unwrap();
+        let previous = cursor.clone();
+        let seen_at = |page: &Page, time: DateTime<Utc>| {
+            previous.as_ref().is_some_and(|previous| {
+                previous.last_edited_time == time && previous.seen.contains(&page.id)
+            })
+        };
+        for page in pages {
+            let reported = since.is_some_and(|since| page.last_edited_time < since)
+                || seen_at(&page, page.last_edited_time);
+            if reported {
+                continue;
+            }
+
+            match cursor {
+                Some(cursor) if cursor.last_edited_time == page.last_edited_time => {
+                    cursor.seen.push(page.id.clone())
+                }
+                _ => {
+                    *cursor = Some(WatchCursor {
+                        last_edited_time: page.last_edited_time,
+                        seen: vec![page.id.clone()],
+                    })
+                }
+            }
+            self.unsaved = true;
+
+            let created = since.is_none_or(|since| {
+                page.created_time > since || (page.created_time == since && !seen_at(&page, since))
+            });
+            let change = if page.archived || page.in_trash {
+                if since.is_none() {
+                    continue;
+                }
+                Change::Archived(page)
+            } else if created {
+                Change::Created(page)
+            } else {
+                Change::Updated(page)
+            };
+            self.changes.push_back(change);
+        }
+        Ok(())
+    }
+}
+
+impl<C: TClient> NotionApi<C> {
+    /// Reports the changes of the pages of a database every `interval` forever,
+    /// see [watch_database_with()](Self::watch_database_with()).
+    pub fn watch_database<D: AsIdentifier<DatabaseId>>(
+        &self,
+        database: D,
+        interval: Duration,
+    ) -> impl Stream<Item = Result<Change>> + '_ {
+        self.watch_database_with(database, WatchOptions::new(interval))
+    }
+
+    /// Reports the changes of the pages of a database, polling it as configured
+    /// by `options`, in the order the pages were edited.
+    ///
+    /// The cursor is saved to the store of `options` once the changes
+    /// of a poll were read, so a watch started again with the same store
+    /// reports the changes it missed meanwhile.
+    /// Errors, such as a failed poll, are returned without ending the stream.
+    /// Trashed pages are only reported as [Change::Archived] by API versions that
+    /// return them from queries, see [DatabaseQuery::in_trash].
+    pub fn watch_database_with<D: AsIdentifier<DatabaseId>>(
+        &self,
+        database: D,
+        options: WatchOptions,
+    ) -> impl Stream<Item = Result<Change>> + '_ {
+        let watch = DatabaseWatch {
+            api: self,
+            database: database.as_id().clone(),
+            options,
+            cursor: None,
+            unsaved: false,
+            polled: false,
+            changes: VecDeque::new(),
+        };
+        stream::unfold(watch, |mut watch| async move {
+            let change = watch.next().await;
+            Some((change, watch))
+        })
+    }
+
+    /// Reports the changes of a single page, reading it every `interval` forever.
+    ///
+    /// The first read only records the state of the page. After that, the page is
+    /// reported as [Change::Updated] when its `last_edited_time` changed and as
+    /// [Change::Archived] when it was archived or moved to the trash.
+    /// Errors are returned without ending the stream.
+    pub fn watch_page<P: AsIdentifier<PageId>>(
+        &self,
+        page: P,
+        interval: Duration,
+    ) -> impl Stream<Item = Result<Change>> + '_ {
+        let page = page.as_id().clone();
+        let state = (None::<Page>, false);
+        stream::unfold(state, move |(mut last, mut polled)| {
+            let page = page.clone();
+            async move {
+                loop {
+                    if polled {
+                        sleep(interval).await;
+                    }
+                    polled = true;
+                    let current = match self.get_current_page(&page).await {
+                        Ok(current) => current,
+                        Err(error) => return Some((Err(error), (last, polled))),
+                    };
+                    let archived = current.archived || current.in_trash;
+                    let change = match &last {
+                        Some(last) if last.last_edited_time == current.last_edited_time => None,
+                        Some(last) if archived && !(last.archived || last.in_trash) => {
+                            Some(Change::Archived(current.clone()))
+                        }
+                        Some(_) => Some(Change::Updated(current.clone())),
+                        None => None,
+                    };
+                    last = Some(current);
+                    if let Some(change) = change {
+                        return Some((Ok(change), (last, polled)));
+                    }
+                }
+            }
+        })
+    }
+}
+
+#[cfg(all(test, not(target_os = "wasi")))]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use chrono::TimeZone;
+    use futures::StreamExt;
+
+    use super::{Change, CursorStore, FileCursorStore, MemoryCursorStore, WatchOptions};
+    use crate::models::Page;
+    use crate::test_util::{DatabaseFixture, MockClient, PageFixture};
+
+    fn edited(
+        title: &str,
+        created_minute: u32,
+        edited_minute: u32,
+    ) -> Page {
+        let mut page = PageFixture::new().title(title).build();
+        page.created_time = chrono::Utc
+            .with_ymd_and_hms(2024, 3, 1, 9, created_minute, 0)
+            .unwrap();
+        page.last_edited_time = chrono::Utc
+            .with_ymd_and_hms(2024, 3, 1, 9, edited_minute, 0)
+            .unwrap();
+        page
+    }
+
+    #[tokio::test]
+    async fn reports_each_edit_once() {
+        let database = DatabaseFixture::new().build();
+        let first = edited("first", 0, 1);
+        let second = edited("second", 0, 2);
+        let mut updated = first.clone();
+        updated.last_edited_time = second.last_edited_time + chrono::Duration::minutes(1);
+        let mut trashed = second.clone();
+        trashed.in_trash = true;
+        trashed.last_edited_time = updated.last_edited_time;
+        let added = edited("added", 3, 3);
+        let later = vec![updated.clone(), trashed.clone(), added.clone()];
+
+        let client = MockClient::new();
+        client.mount_query(&database.id, vec![first.clone(), second.clone()]);
+        // `second` is returned again, as it was edited in the minute of the cursor.
+        client.mount_query(&database.id, vec![second.clone()]);
+        client.mount_query(&database.id, later.clone());
+        let api = client.api();
+        let store = Arc::new(MemoryCursorStore::default());
+        let options = WatchOptions::new(Duration::from_millis(1)).store(store.clone());
+
+        let changes: Vec<Change> = api
+            .watch_database_with(&database.id, options.clone())
+            .take(5)
+            .map(|change| change.unwrap())
+            .collect()
+            .await;
+        let expected = vec![
+            Change::Updated(updated),
+            Change::Archived(trashed),
+            Change::Created(added),
+        ];
+        assert_eq!(
+            changes[..2],
+            [Change::Created(first), Change::Created(second.clone())]
+        );
+        assert_eq!(changes[2..], expected[..]);
+
+        let requests = api.client().requests();
+        assert!(requests[0].body.as_ref().unwrap().get("filter").is_none());
+        assert_eq!(
+            requests[1].body.as_ref().unwrap()["filter"]["last_edited_time"]["on_or_after"],
+            "2024-03-01T09:02:00Z"
+        );
+
+        // The last changes were never followed by another read, so a restarted
+        // watch reports them again.
+        let cursor = store.load(&database.id.to_string()).unwrap().unwrap();
+        assert_eq!(cursor.last_edited_time, second.last_edited_time);
+        let client = MockClient::new();
+        client.mount_query(&database.id, later);
+        let api = client.api();
+        let changes: Vec<Change> = api
+            .watch_database_with(&database.id, options)
+            .take(3)
+            .map(|change| change.unwrap())
+            .collect()
+            .await;
+        assert_eq!(changes, expected);
+    }
+
+    #[test]
+    fn file_store_keeps_cursors_by_key() {
+        let path = std::env::temp_dir().join(format!("watch-{}.json", std::process::id()));
+        let store = FileCursorStore::new(&path);
+        assert_eq!(store.load("a").unwrap(), None);
+
+        let cursor = super::WatchCursor {
+            last_edited_time: edited("page", 0, 5).last_edited_time,
+            seen: vec![],
+        };
+        store.save("a", &cursor).unwrap();
+        store.save("b", &cursor).unwrap();
+        assert_eq!(FileCursorStore::new(&path).load("a").unwrap(), Some(cursor));
+        std::fs::remove_file(path).unwrap();
+    }
+}
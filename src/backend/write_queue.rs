@@ -0,0 +1,326 @@
+//! Buffering writes while the API can't be reached.
+//!
+//! ```no_run
+//! # async fn example(request: notion_wasi::models::PageCreateRequest) -> Result<(), Box<dyn std::error::Error>> {
+//! use notion_wasi::{NotionApi, WriteQueue};
+//!
+//! let api = NotionApi::new(std::env::var("NOTION_API_TOKEN")?)?;
+//! let mut queue = WriteQueue::load_or_default("writes.json")?;
+//!
+//! // Sent right away if possible and no older writes are queued,
+//! // otherwise kept for later.
+//! api.send_or_queue(&mut queue, WriteQueue::create_page(request)?)
+//!     .await?;
+//!
+//! for outcome in api.flush_writes(&mut queue).await {
+//!     if let Err(error) = outcome.result {
+//!         eprintln!("{} {} failed: {}", outcome.write.method, outcome.write.path, error);
+//!     }
+//! }
+//! queue.save("writes.json")?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::VecDeque;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[cfg(feature = "blocks")]
+use crate::ids::BlockId;
+use crate::ids::{AsIdentifier, PageId};
+#[cfg(feature = "blocks")]
+use crate::models::block::BlockAppendRequest;
+use crate::models::{Object, PageCreateRequest, PageUpdateRequest};
+use crate::{Error, Method, NotionApi, Result, TClient};
+
+/// A create, update or append request waiting to be sent.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct QueuedWrite {
+    pub method: Method,
+    /// The endpoint relative to the API base URL, such as `pages/{id}`.
+    pub path: String,
+    pub body: Value,
+}
+
+/// The result of sending a [QueuedWrite].
+#[derive(Debug)]
+pub struct WriteOutcome {
+    pub write: QueuedWrite,
+    pub result: Result<Object>,
+}
+
+/// Writes in the order they were made, stored as a JSON file
+/// so they survive restarts.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Default)]
+pub struct WriteQueue {
+    writes: VecDeque<QueuedWrite>,
+}
+
+impl WriteQueue {
+    pub fn load<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// Like [load()](Self::load()), but starts empty if the file doesn't exist yet.
+    pub fn load_or_default<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        match Self::load(path) {
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            result => result,
+        }
+    }
+
+    pub fn save<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> std::io::Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, json)
+    }
+
+    pub fn push(
+        &mut self,
+        write: QueuedWrite,
+    ) {
+        self.writes.push_back(write);
+    }
+
+    pub fn len(&self) -> usize {
+        self.writes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.writes.is_empty()
+    }
+
+    /// The writes that have not been sent yet, oldest first.
+    pub fn writes(&self) -> impl Iterator<Item = &QueuedWrite> {
+        self.writes.iter()
+    }
+
+    /// Fails if the page is invalid, see [PageCreateRequest::validate()],
+    /// so it is rejected now rather than when the queue is flushed.
+    #[allow(clippy::result_large_err)]
+    pub fn create_page<T: Into<PageCreateRequest>>(page: T) -> Result<QueuedWrite> {
+        let page = page.into();
+        page.validate()
+            .map_err(|source| Error::InvalidPage { source })?;
+        Ok(QueuedWrite {
+            method: Method::Post,
+            path: "pages".to_string(),
+            body: serde_json::to_value(page).unwrap(),
+        })
+    }
+
+    pub fn update_page<P, T>(
+        page_id: P,
+        update: T,
+    ) -> QueuedWrite
+    where
+        P: AsIdentifier<PageId>,
+        T: Into<PageUpdateRequest>,
+    {
+        QueuedWrite {
+            method: Method::Patch,
+            path: format!("pages/{}", page_id.as_id()),
+            body: serde_json::to_value(update.into()).unwrap(),
+        }
+    }
+
+    #[cfg(feature = "blocks")]
+    pub fn append_block_children<B, T>(
+        block_id: B,
+        request: T,
+    ) -> QueuedWrite
+    where
+        B: AsIdentifier<BlockId>,
+        T: Into<BlockAppendRequest>,
+    {
+        QueuedWrite {
+            method: Method::Patch,
+            path: format!("blocks/{}/children", block_id.as_id()),
+            body: serde_json::to_value(request.into()).unwrap(),
+        }
+    }
+}
+
+impl<C: TClient> NotionApi<C> {
+    pub(crate) async fn send_write(
+        &self,
+        write: &QueuedWrite,
+    ) -> Result<Object> {
+        let uri = format!("{}/{}", self.base_url, write.path);
+        let body = serde_json::to_vec(&write.body).unwrap();
+        match write.method {
+            Method::Get => self.client.get(uri).await,
+            Method::Post => self.client.post_json(uri, body).await,
+            Method::Patch => self.client.patch_json(uri, body).await,
+            Method::Delete => self.client.delete(uri).await,
+        }
+    }
+
+    /// Sends `write` now, or adds it to `queue` if the request could not be sent.
+    ///
+    /// Returns the response, or `None` if the write was queued.
+    /// API errors are returned as usual, since sending again would fail the same way.
+    ///
+    /// While older writes are queued, `write` is queued behind them without being
+    /// sent, so an update can't reach the API before the create it depends on.
+    /// Send them with [flush_writes()](Self::flush_writes()).
+    ///
+    /// A request that failed to send includes one that timed out, which the API may
+    /// have applied anyway, so flushing can repeat it. Queue writes that are safe
+    /// to repeat, such as updates, or check for duplicates after flushing.
+    pub async fn send_or_queue(
+        &self,
+        queue: &mut WriteQueue,
+        write: QueuedWrite,
+    ) -> Result<Option<Object>> {
+        if !queue.is_empty() {
+            debug!(path = %write.path, queued = queue.len(), "Queueing write behind older writes");
+            queue.push(write);
+            return Ok(None);
+        }
+
+        match self.send_write(&write).await {
+            Ok(response) => Ok(Some(response)),
+            Err(Error::RequestFailed { .. }) => {
+                debug!(path = %write.path, "Queueing write");
+                queue.push(write);
+                Ok(None)
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Sends the queued writes in order and returns the outcome of each sent write.
+    ///
+    /// Writes the API answered, successfully or with an error, leave the queue.
+    /// Flushing stops at the first write that could not be sent; it is reported
+    /// with its error but stays at the front of the queue with the ones after it.
+    /// Like with [send_or_queue()](Self::send_or_queue()), that write may have
+    /// been applied if the request timed out, and is sent again by the next flush.
+    pub async fn flush_writes(
+        &self,
+        queue: &mut WriteQueue,
+    ) -> Vec<WriteOutcome> {
+        let mut outcomes = vec![];
+
+        while let Some(write) = queue.writes.pop_front() {
+            let result = self.send_write(&write).await;
+            let offline = matches!(result, Err(Error::RequestFailed { .. }));
+            if offline {
+                queue.writes.push_front(write.clone());
+            }
+            outcomes.push(WriteOutcome { write, result });
+            if offline {
+                break;
+            }
+        }
+
+        outcomes
+    }
+}
+
+#[cfg(all(test, not(target_os = "wasi")))]
+mod tests {
+    use crate::ids::{DatabaseId, PageId};
+    use crate::models::{InvalidPage, Object, PageCreateRequest, Parent, Properties};
+    use crate::test_util::{MockNotion, PageFixture, MOCK_API_TOKEN};
+    use crate::{Error, NotionApi, WriteQueue};
+
+    use std::str::FromStr;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    #[tokio::test]
+    async fn flush_queued_writes() {
+        let mock = MockNotion::start().await;
+        let page = PageFixture::new().title("Queued").build();
+        Mock::given(method("POST"))
+            .and(path("/v1/pages"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(Object::Page { page: page.clone() }),
+            )
+            .expect(1)
+            .mount(mock.server())
+            .await;
+        let create = WriteQueue::create_page(PageCreateRequest::new(
+            Parent::Page {
+                page_id: PageId::from_str("parent").unwrap(),
+            },
+            page.properties.clone(),
+        ))
+        .unwrap();
+        let update = WriteQueue::update_page(
+            PageId::from_str("missing").unwrap(),
+            page.properties.clone(),
+        );
+
+        // Nothing listens on the discard port, so the writes are queued.
+        let offline = NotionApi::new(MOCK_API_TOKEN)
+            .unwrap()
+            .with_base_url("http://127.0.0.1:9/v1");
+        let mut queue = WriteQueue::default();
+        for write in [create.clone(), update.clone()] {
+            assert!(offline
+                .send_or_queue(&mut queue, write)
+                .await
+                .unwrap()
+                .is_none());
+        }
+        let outcomes = offline.flush_writes(&mut queue).await;
+        assert_eq!(outcomes.len(), 1);
+        assert!(matches!(
+            outcomes[0].result,
+            Err(Error::RequestFailed { .. })
+        ));
+        assert_eq!(queue.len(), 2);
+
+        let file = std::env::temp_dir().join(format!("notion-write-queue-{}.json", page.id));
+        queue.save(&file).unwrap();
+        let mut queue = WriteQueue::load(&file).unwrap();
+        std::fs::remove_file(&file).unwrap();
+        assert_eq!(queue.writes().collect::<Vec<_>>(), vec![&create, &update]);
+
+        // Older writes are still queued, so a new one waits behind them.
+        let api = mock.api();
+        assert!(api
+            .send_or_queue(&mut queue, update.clone())
+            .await
+            .unwrap()
+            .is_none());
+        assert_eq!(mock.server().received_requests().await.unwrap().len(), 0);
+        assert_eq!(
+            queue.writes().collect::<Vec<_>>(),
+            vec![&create, &update, &update]
+        );
+
+        let outcomes = api.flush_writes(&mut queue).await;
+        assert!(queue.is_empty());
+        assert_eq!(outcomes.len(), 3);
+        assert!(
+            matches!(&outcomes[0].result, Ok(Object::Page { page: created }) if created == &page)
+        );
+        assert!(matches!(outcomes[1].result, Err(Error::ApiError { .. })));
+    }
+
+    #[test]
+    fn reject_invalid_creates_when_queued() {
+        let untitled = PageCreateRequest::new(
+            Parent::Database {
+                database_id: DatabaseId::from_str("database").unwrap(),
+            },
+            Properties::default(),
+        );
+        assert!(matches!(
+            WriteQueue::create_page(untitled),
+            Err(Error::InvalidPage {
+                source: InvalidPage::MissingTitle
+            })
+        ));
+    }
+}
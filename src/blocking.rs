@@ -0,0 +1,461 @@
+//! A synchronous [NotionApi], enabled with the `blocking` feature,
+//! for scripts and command line tools that don't run an async runtime.
+//!
+//! ```no_run
+//! # fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! use notion_wasi::blocking::NotionApi;
+//! use notion_wasi::ids::PageId;
+//!
+//! let api = NotionApi::new(std::env::var("NOTION_API_TOKEN")?)?;
+//! let page_id: PageId = "b55c9c91-384d-452b-81db-d1ef79372b75".parse()?;
+//! let page = api.get_page(&page_id)?;
+//! println!("{:?}", page.title());
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Each call runs the request of the async [crate::NotionApi] to completion,
+//! on a single threaded tokio runtime owned by the client, or directly on WASI.
+//! Methods without a blocking counterpart can be called through [NotionApi::block_on()].
+//!
+//! Paginated endpoints return one page, such as [NotionApi::search()], every result,
+//! such as [NotionApi::search_all()], or an iterator requesting the next page
+//! once the current one is used up, such as [NotionApi::search_iter()].
+//! Don't use the blocking client from within an async runtime, which panics.
+
+use std::future::Future;
+
+#[cfg(feature = "blocks")]
+use crate::ids::BlockId;
+#[cfg(feature = "databases")]
+use crate::ids::DatabaseId;
+use crate::ids::{AsIdentifier, PageId, UserId};
+#[cfg(feature = "blocks")]
+use crate::models::block::{Block, BlockAppendRequest, BlockTree, BlockUpdateRequest};
+use crate::models::paging::{Pageable, PagingCursor};
+#[cfg(feature = "databases")]
+use crate::models::search::DatabaseQuery;
+use crate::models::search::SearchRequest;
+use crate::models::users::User;
+#[cfg(feature = "databases")]
+use crate::models::Database;
+use crate::models::{ListResponse, Object, Page, PageCreateRequest, PageUpdateRequest};
+#[cfg(feature = "blocks")]
+use crate::{BlockTreeOptions, PaginationOptions};
+use crate::{Client, CollectAll, PageIter, Result, SecretString, TClient};
+
+/// Runs futures to completion on the calling thread.
+#[derive(Debug)]
+struct Runtime {
+    #[cfg(not(target_os = "wasi"))]
+    runtime: tokio::runtime::Runtime,
+}
+
+impl Runtime {
+    #[allow(clippy::result_large_err)]
+    fn new() -> Result<Self> {
+        Ok(Runtime {
+            #[cfg(not(target_os = "wasi"))]
+            runtime: tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()?,
+        })
+    }
+
+    fn block_on<F: Future>(
+        &self,
+        future: F,
+    ) -> F::Output {
+        #[cfg(not(target_os = "wasi"))]
+        return self.runtime.block_on(future);
+        // `http_req` sends its requests synchronously, nothing to drive.
+        #[cfg(target_os = "wasi")]
+        return futures::executor::block_on(future);
+    }
+}
+
+/// The blocking counterpart of [crate::NotionApi], see the [module docs](self).
+#[derive(Debug)]
+pub struct NotionApi<C = Client> {
+    api: crate::NotionApi<C>,
+    runtime: Runtime,
+}
+
+impl NotionApi {
+    #[allow(clippy::result_large_err)]
+    pub fn new<S: Into<SecretString>>(api_token: S) -> Result<Self> {
+        Self::from_async(crate::NotionApi::new(api_token)?)
+    }
+}
+
+// Every method returns the crate's `Result`.
+#[allow(clippy::result_large_err)]
+impl<C: TClient> NotionApi<C> {
+    /// Creates a NotionApi that sends its requests through `client`.
+    pub fn with_client(client: C) -> Result<Self> {
+        Self::from_async(crate::NotionApi::with_client(client))
+    }
+
+    /// Blocks on the requests of `api`, keeping its configuration.
+    pub fn from_async(api: crate::NotionApi<C>) -> Result<Self> {
+        Ok(NotionApi {
+            api,
+            runtime: Runtime::new()?,
+        })
+    }
+
+    /// Send requests to `base_url` instead of `https://api.notion.com/v1`,
+    /// for example to point the client at a mock server.
+    pub fn with_base_url<S: Into<String>>(
+        mut self,
+        base_url: S,
+    ) -> Self {
+        self.api = self.api.with_base_url(base_url);
+        self
+    }
+
+    /// The async client the requests are sent with.
+    pub fn as_async(&self) -> &crate::NotionApi<C> {
+        &self.api
+    }
+
+    pub fn into_async(self) -> crate::NotionApi<C> {
+        self.api
+    }
+
+    /// Runs any request of the async client to completion:
+    ///
+    /// ```no_run
+    /// # fn example(api: notion_wasi::blocking::NotionApi) -> notion_wasi::Result<()> {
+    /// let limits = api.block_on(api.as_async().workspace_limits())?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn block_on<F: Future>(
+        &self,
+        future: F,
+    ) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    /// See [crate::NotionApi::search()].
+    pub fn search<T: Into<SearchRequest>>(
+        &self,
+        query: T,
+    ) -> Result<ListResponse<Object>> {
+        self.block_on(self.api.search(query))
+    }
+
+    /// Every result of a search, see [crate::NotionApi::search_stream()].
+    pub fn search_all<T: Into<SearchRequest>>(
+        &self,
+        query: T,
+    ) -> Result<Vec<Object>> {
+        self.block_on(self.api.search_stream(query).collect_all())
+    }
+
+    /// Every result of a search, requested a page at a time as the iterator
+    /// advances, see [crate::NotionApi::search_stream()].
+    pub fn search_iter<T: Into<SearchRequest>>(
+        &self,
+        query: T,
+    ) -> PageIter<Object, impl FnMut(Option<PagingCursor>) -> Result<ListResponse<Object>> + '_>
+    {
+        let query = query.into();
+        PageIter::new(move |cursor| self.search(query.clone().start_from(cursor)))
+    }
+
+    /// See [crate::NotionApi::search_pages()].
+    pub fn search_pages<T: Into<SearchRequest>>(
+        &self,
+        query: T,
+    ) -> Result<ListResponse<Page>> {
+        self.block_on(self.api.search_pages(query))
+    }
+
+    /// Every page a search finds, see [crate::NotionApi::search_pages_stream()].
+    pub fn search_pages_all<T: Into<SearchRequest>>(
+        &self,
+        query: T,
+    ) -> Result<Vec<Page>> {
+        self.block_on(self.api.search_pages_stream(query).collect_all())
+    }
+
+    pub fn get_page<T: AsIdentifier<PageId>>(
+        &self,
+        page_id: T,
+    ) -> Result<Page> {
+        self.block_on(self.api.get_page(page_id))
+    }
+
+    /// See [crate::NotionApi::create_page()].
+    pub fn create_page<T: Into<PageCreateRequest>>(
+        &self,
+        page: T,
+    ) -> Result<Page> {
+        self.block_on(self.api.create_page(page))
+    }
+
+    /// See [crate::NotionApi::update_page()].
+    pub fn update_page<P, T>(
+        &self,
+        page_id: P,
+        update: T,
+    ) -> Result<Page>
+    where
+        P: AsIdentifier<PageId>,
+        T: Into<PageUpdateRequest>,
+    {
+        self.block_on(self.api.update_page(page_id, update))
+    }
+
+    #[cfg(feature = "databases")]
+    pub fn get_database<T: AsIdentifier<DatabaseId>>(
+        &self,
+        database_id: T,
+    ) -> Result<Database> {
+        self.block_on(self.api.get_database(database_id))
+    }
+
+    /// See [crate::NotionApi::query_database()].
+    #[cfg(feature = "databases")]
+    pub fn query_database<D, T>(
+        &self,
+        database: D,
+        query: T,
+    ) -> Result<ListResponse<Page>>
+    where
+        T: Into<DatabaseQuery>,
+        D: AsIdentifier<DatabaseId>,
+    {
+        self.block_on(self.api.query_database(database, query))
+    }
+
+    /// Every page matching `query`, see [crate::NotionApi::query_database_stream()].
+    #[cfg(feature = "databases")]
+    pub fn query_database_all<D, T>(
+        &self,
+        database: D,
+        query: T,
+    ) -> Result<Vec<Page>>
+    where
+        T: Into<DatabaseQuery>,
+        D: AsIdentifier<DatabaseId>,
+    {
+        self.block_on(
+            self.api
+                .query_database_stream(database, query)
+                .collect_all(),
+        )
+    }
+
+    /// Every page matching `query`, requested a page at a time as the iterator
+    /// advances, see [crate::NotionApi::query_database_stream()].
+    ///
+    /// ```no_run
+    /// # fn example(api: notion_wasi::blocking::NotionApi, database_id: notion_wasi::ids::DatabaseId) -> notion_wasi::Result<()> {
+    /// use notion_wasi::models::search::DatabaseQuery;
+    ///
+    /// for page in api.query_database_iter(&database_id, DatabaseQuery::default()) {
+    ///     println!("{:?}", page?.title());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "databases")]
+    pub fn query_database_iter<D, T>(
+        &self,
+        database: D,
+        query: T,
+    ) -> PageIter<Page, impl FnMut(Option<PagingCursor>) -> Result<ListResponse<Page>> + '_>
+    where
+        T: Into<DatabaseQuery>,
+        D: AsIdentifier<DatabaseId>,
+    {
+        let database = database.as_id().clone();
+        let query = query.into();
+        PageIter::new(move |cursor| {
+            self.query_database(&database, query.clone().start_from(cursor))
+        })
+    }
+
+    #[cfg(feature = "blocks")]
+    pub fn get_block<T: AsIdentifier<BlockId>>(
+        &self,
+        block_id: T,
+    ) -> Result<Block> {
+        self.block_on(self.api.get_block(block_id))
+    }
+
+    /// See [crate::NotionApi::get_all_block_children()].
+    #[cfg(feature = "blocks")]
+    pub fn get_all_block_children<T: AsIdentifier<BlockId>>(
+        &self,
+        block_id: T,
+    ) -> Result<Vec<Block>> {
+        self.block_on(self.api.get_all_block_children(block_id))
+    }
+
+    /// Every child of a block, requested a page at a time as the iterator
+    /// advances, see [crate::NotionApi::block_children_stream()].
+    #[cfg(feature = "blocks")]
+    pub fn block_children_iter<T: AsIdentifier<BlockId>>(
+        &self,
+        block_id: T,
+    ) -> PageIter<Block, impl FnMut(Option<PagingCursor>) -> Result<ListResponse<Block>> + '_> {
+        let block_id = block_id.as_id().clone();
+        let page_size = PaginationOptions::default().page_size;
+        PageIter::new(move |cursor| {
+            self.block_on(self.api.block_children_page(&block_id, cursor, page_size))
+        })
+    }
+
+    /// See [crate::NotionApi::get_block_tree()].
+    #[cfg(feature = "blocks")]
+    pub fn get_block_tree<T: AsIdentifier<BlockId>>(
+        &self,
+        block_id: T,
+        options: BlockTreeOptions,
+    ) -> Result<Vec<BlockTree>> {
+        self.block_on(self.api.get_block_tree(block_id, options))
+    }
+
+    /// See [crate::NotionApi::append_block_children()].
+    #[cfg(feature = "blocks")]
+    pub fn append_block_children<B, T>(
+        &self,
+        block_id: B,
+        request: T,
+    ) -> Result<ListResponse<Block>>
+    where
+        B: AsIdentifier<BlockId>,
+        T: Into<BlockAppendRequest>,
+    {
+        self.block_on(self.api.append_block_children(block_id, request))
+    }
+
+    /// See [crate::NotionApi::update_block()].
+    #[cfg(feature = "blocks")]
+    pub fn update_block<B, T>(
+        &self,
+        block_id: B,
+        update: T,
+    ) -> Result<Block>
+    where
+        B: AsIdentifier<BlockId>,
+        T: Into<BlockUpdateRequest>,
+    {
+        self.block_on(self.api.update_block(block_id, update))
+    }
+
+    /// See [crate::NotionApi::delete_block()].
+    #[cfg(feature = "blocks")]
+    pub fn delete_block<B: AsIdentifier<BlockId>>(
+        &self,
+        block_id: B,
+    ) -> Result<Block> {
+        self.block_on(self.api.delete_block(block_id))
+    }
+
+    pub fn get_user<U: AsIdentifier<UserId>>(
+        &self,
+        user_id: U,
+    ) -> Result<User> {
+        self.block_on(self.api.get_user(user_id))
+    }
+
+    /// See [crate::NotionApi::list_users()].
+    pub fn list_users(
+        &self,
+        cursor: Option<PagingCursor>,
+    ) -> Result<ListResponse<User>> {
+        self.block_on(self.api.list_users(cursor))
+    }
+
+    /// The bot user of the integration, see [crate::NotionApi::get_self()].
+    pub fn get_self(&self) -> Result<User> {
+        self.block_on(self.api.get_self())
+    }
+}
+
+#[cfg(all(
+    test,
+    feature = "blocks",
+    feature = "databases",
+    not(target_os = "wasi")
+))]
+mod tests {
+    use super::NotionApi;
+    use crate::models::search::DatabaseQuery;
+    use crate::models::{ListResponse, Object, Page};
+    use crate::test_util::{DatabaseFixture, MockNotion, PageFixture, MOCK_API_TOKEN};
+    use serde_json::json;
+    use wiremock::matchers::{body_partial_json, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    #[test]
+    fn requests_block_until_answered() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let mock = runtime.block_on(MockNotion::start());
+        let database = DatabaseFixture::new().build();
+        let page = PageFixture::new().title("Synchronous").build();
+        runtime.block_on(mock.mount_page(&page));
+        runtime.block_on(mock.mount_query(&database.id, vec![page.clone()]));
+
+        let api = NotionApi::new(MOCK_API_TOKEN)
+            .unwrap()
+            .with_base_url(mock.base_url());
+        assert_eq!(api.get_page(&page.id).unwrap(), page);
+        assert_eq!(
+            api.query_database_all(&database.id, DatabaseQuery::default())
+                .unwrap(),
+            vec![page]
+        );
+    }
+
+    #[test]
+    fn iterators_fetch_pages_as_they_advance() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let mock = runtime.block_on(MockNotion::start());
+        let database = DatabaseFixture::new().build();
+        let pages = [PageFixture::new().build(), PageFixture::new().build()];
+        let list = |page: &Page, has_more| Object::List {
+            list: ListResponse {
+                results: vec![Object::Page { page: page.clone() }],
+                next_cursor: Some(serde_json::from_str(r#""next""#).unwrap()),
+                has_more,
+            },
+        };
+        let route = format!("/v1/databases/{}/query", database.id);
+        runtime.block_on(
+            Mock::given(path(&route))
+                .respond_with(ResponseTemplate::new(200).set_body_json(list(&pages[0], true)))
+                .mount(mock.server()),
+        );
+        runtime.block_on(
+            Mock::given(path(&route))
+                .and(body_partial_json(json!({"start_cursor": "next"})))
+                .respond_with(ResponseTemplate::new(200).set_body_json(list(&pages[1], false)))
+                .with_priority(1)
+                .mount(mock.server()),
+        );
+        let requests = || {
+            runtime
+                .block_on(mock.server().received_requests())
+                .unwrap()
+                .len()
+        };
+
+        let api = NotionApi::new(MOCK_API_TOKEN)
+            .unwrap()
+            .with_base_url(mock.base_url());
+        let mut iter = api.query_database_iter(&database.id, DatabaseQuery::default());
+        assert_eq!(requests(), 0);
+        assert_eq!(iter.next().unwrap().unwrap(), pages[0]);
+        assert_eq!(requests(), 1);
+        assert_eq!(iter.next().unwrap().unwrap(), pages[1]);
+        assert_eq!(requests(), 2);
+        assert!(iter.next().is_none());
+        assert_eq!(requests(), 2);
+    }
+}
@@ -1,5 +1,6 @@
 use std::fmt::Display;
 use std::fmt::Error;
+use std::sync::Arc;
 
 pub trait Identifier: Display {
     fn value(&self) -> &str;
@@ -30,9 +31,10 @@ where
 
 macro_rules! identifer {
     ($name:ident) => {
+        /// Backed by an `Arc<str>`, so cloning never copies the id.
         #[derive(serde::Serialize, serde::Deserialize, Debug, Eq, PartialEq, Hash, Clone)]
         #[serde(transparent)]
-        pub struct $name(String);
+        pub struct $name(Arc<str>);
 
         impl Identifier for $name {
             fn value(&self) -> &str {
@@ -53,20 +55,66 @@ macro_rules! identifer {
             type Err = Error;
 
             fn from_str(s: &str) -> Result<Self, Self::Err> {
-                Ok($name(s.to_string()))
+                Ok($name(Arc::from(s)))
             }
         }
     };
 }
 
 identifer!(DatabaseId);
+identifer!(DataSourceId);
 identifer!(PageId);
 identifer!(BlockId);
 identifer!(UserId);
 identifer!(PropertyId);
+identifer!(CustomEmojiId);
+identifer!(CommentId);
+identifer!(DiscussionId);
+identifer!(FileUploadId);
+
+impl DatabaseId {
+    /// The Notion URL of the database, opening the view `view_id` if given.
+    ///
+    /// View ids are the `v` query parameter of a database link.
+    pub fn view_url(
+        &self,
+        view_id: Option<&str>,
+    ) -> String {
+        let mut url = format!("{}/{}", crate::NOTION_APP_URL, self.0.replace('-', ""));
+        if let Some(view_id) = view_id {
+            url.push_str("?v=");
+            url.push_str(&view_id.replace('-', ""));
+        }
+        url
+    }
+}
+
+impl PageId {
+    /// The Notion URL of the page.
+    pub fn to_url(&self) -> String {
+        format!("{}/{}", crate::NOTION_APP_URL, self.0.replace('-', ""))
+    }
+}
+
+impl BlockId {
+    /// The Notion URL of the page `page_id`, scrolled to this block.
+    pub fn to_url<P: AsIdentifier<PageId>>(
+        &self,
+        page_id: P,
+    ) -> String {
+        format!("{}#{}", page_id.as_id().to_url(), self.0.replace('-', ""))
+    }
+}
 
 impl From<PageId> for BlockId {
     fn from(page_id: PageId) -> Self {
         BlockId(page_id.0)
     }
 }
+
+/// The id of a child page block is the id of the page.
+impl From<BlockId> for PageId {
+    fn from(block_id: BlockId) -> Self {
+        PageId(block_id.0)
+    }
+}
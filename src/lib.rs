@@ -1,9 +1,38 @@
+// `http_req_wasi` only builds for WASI preview 1.
+#[cfg(all(
+    feature = "client",
+    target_os = "wasi",
+    target_env = "p2",
+    not(feature = "wasi-http")
+))]
+compile_error!("the client needs the `wasi-http` feature on wasm32-wasip2");
+
+#[cfg(feature = "client")]
+#[macro_use]
+mod log;
+
+#[cfg(feature = "client")]
 mod backend;
+#[cfg(feature = "client")]
 pub use backend::*;
 
+#[cfg(all(feature = "blocking", not(target_arch = "wasm32")))]
+pub mod blocking;
 pub mod ids;
 pub mod models;
+#[cfg(feature = "blocks")]
+pub mod render;
+#[cfg(feature = "search-index")]
+pub mod search_index;
+mod secret;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
+mod version;
 
 pub use chrono;
+pub use secret::SecretString;
+pub use version::{ApiVersion, UnsupportedApiVersion};
 
-pub(crate) const NOTION_API_VERSION: &str = "2022-02-22";
+#[cfg(feature = "client")]
+pub(crate) const NOTION_API_BASE_URL: &str = "https://api.notion.com/v1";
+pub(crate) const NOTION_APP_URL: &str = "https://www.notion.so";
@@ -0,0 +1,221 @@
+//! Counts, sums and extremes of a property over a set of pages,
+//! which the Notion API doesn't compute.
+//!
+//! Values are read like the cells of [Columns](crate::models::columns::Columns):
+//! numbers come from number properties and number formulas and rollups,
+//! dates from date, created and last edited time properties and date formulas
+//! and rollups. An [Aggregate] can be fed one page at a time, so results
+//! don't need to be held in memory, see
+//! [NotionApi::aggregate_query()](crate::NotionApi::aggregate_query()).
+//!
+//! ```
+//! use notion_wasi::models::aggregate::Aggregate;
+//! # let pages: Vec<notion_wasi::models::Page> = vec![];
+//!
+//! let prices = Aggregate::of("Price", &pages);
+//! println!("{} priced, {} in total", prices.count, prices.sum);
+//! ```
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use crate::models::columns::Cell;
+use crate::models::properties::{DateOrDateTime, PropertyValue};
+use crate::models::Page;
+
+/// A number or date, the lowest and highest of which an [Aggregate] keeps.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum AggregateValue {
+    Number(f64),
+    Date(DateOrDateTime),
+}
+
+/// Numbers are compared with numbers and dates with dates,
+/// dates without a time as midnight UTC.
+impl PartialOrd for AggregateValue {
+    fn partial_cmp(
+        &self,
+        other: &Self,
+    ) -> Option<Ordering> {
+        match (self, other) {
+            (AggregateValue::Number(a), AggregateValue::Number(b)) => a.partial_cmp(b),
+            (AggregateValue::Date(a), AggregateValue::Date(b)) => {
+                Some(a.to_datetime().cmp(&b.to_datetime()))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The aggregates of one property over the pages [added](Self::add()) to it.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Aggregate {
+    /// The property name.
+    pub property: String,
+    /// Pages with a value, where empty text and empty lists count as no value.
+    pub count: usize,
+    /// Pages without a value, including pages without the property.
+    pub empty: usize,
+    /// Pages with a number.
+    pub numbers: usize,
+    /// The sum of the numbers.
+    pub sum: f64,
+    /// The lowest number or date. Dates are only kept if no page had a number.
+    pub min: Option<AggregateValue>,
+    /// The highest number or date, like [min](Self::min).
+    pub max: Option<AggregateValue>,
+    /// The number of pages by value, for text, select, status, multi-select
+    /// and people properties among others.
+    /// A page with several values, such as a multi-select, counts once for each.
+    pub groups: BTreeMap<String, usize>,
+}
+
+impl Aggregate {
+    pub fn new<S: Into<String>>(property: S) -> Self {
+        Aggregate {
+            property: property.into(),
+            ..Aggregate::default()
+        }
+    }
+
+    /// The aggregates of `property` over `pages`.
+    pub fn of<'a, S, I>(
+        property: S,
+        pages: I,
+    ) -> Self
+    where
+        S: Into<String>,
+        I: IntoIterator<Item = &'a Page>,
+    {
+        let mut aggregate = Aggregate::new(property);
+        pages.into_iter().for_each(|page| aggregate.add(page));
+        aggregate
+    }
+
+    /// Adds the value of the property on `page`.
+    pub fn add(
+        &mut self,
+        page: &Page,
+    ) {
+        let value = page.properties.properties.get(&self.property);
+        self.add_value(value);
+    }
+
+    /// Adds a value of the property, `None` for a page without it.
+    pub fn add_value(
+        &mut self,
+        value: Option<&PropertyValue>,
+    ) {
+        let cell = value.map_or(Cell::Null, Cell::from);
+        let counted = match cell {
+            Cell::Null => false,
+            Cell::Number(number) => {
+                self.numbers += 1;
+                self.sum += number;
+                self.extremes(AggregateValue::Number(number));
+                self.group(number.to_string());
+                true
+            }
+            Cell::Date(date) => {
+                self.extremes(AggregateValue::Date(date));
+                self.group(date.to_string());
+                true
+            }
+            Cell::Boolean(boolean) => {
+                self.group(boolean.to_string());
+                true
+            }
+            Cell::Text(text) => {
+                let counted = !text.is_empty();
+                if counted {
+                    self.group(text);
+                }
+                counted
+            }
+            Cell::List(values) => {
+                let counted = !values.is_empty();
+                values.into_iter().for_each(|value| self.group(value));
+                counted
+            }
+        };
+        if counted {
+            self.count += 1;
+        } else {
+            self.empty += 1;
+        }
+    }
+
+    /// The average of the numbers, `None` without numbers.
+    pub fn mean(&self) -> Option<f64> {
+        match self.numbers {
+            0 => None,
+            numbers => Some(self.sum / numbers as f64),
+        }
+    }
+
+    fn extremes(
+        &mut self,
+        value: AggregateValue,
+    ) {
+        // A number replaces dates, which only come from formulas or rollups
+        // that returned another type on some pages.
+        let replace = |current: &Option<AggregateValue>, ordering| match current {
+            None => true,
+            Some(AggregateValue::Date(_)) if matches!(value, AggregateValue::Number(_)) => true,
+            Some(current) => value.partial_cmp(current) == Some(ordering),
+        };
+        if replace(&self.min, Ordering::Less) {
+            self.min = Some(value);
+        }
+        if replace(&self.max, Ordering::Greater) {
+            self.max = Some(value);
+        }
+    }
+
+    fn group(
+        &mut self,
+        value: String,
+    ) {
+        *self.groups.entry(value).or_default() += 1;
+    }
+}
+
+/// The number of `pages` with a value for `property`, see [Aggregate::count].
+pub fn count<'a, I: IntoIterator<Item = &'a Page>>(
+    pages: I,
+    property: &str,
+) -> usize {
+    Aggregate::of(property, pages).count
+}
+
+/// The sum of the numbers of `property` over `pages`.
+pub fn sum<'a, I: IntoIterator<Item = &'a Page>>(
+    pages: I,
+    property: &str,
+) -> f64 {
+    Aggregate::of(property, pages).sum
+}
+
+/// The lowest number or date of `property` over `pages`.
+pub fn min<'a, I: IntoIterator<Item = &'a Page>>(
+    pages: I,
+    property: &str,
+) -> Option<AggregateValue> {
+    Aggregate::of(property, pages).min
+}
+
+/// The highest number or date of `property` over `pages`.
+pub fn max<'a, I: IntoIterator<Item = &'a Page>>(
+    pages: I,
+    property: &str,
+) -> Option<AggregateValue> {
+    Aggregate::of(property, pages).max
+}
+
+/// The number of `pages` by value of `property`, see [Aggregate::groups].
+pub fn group_count<'a, I: IntoIterator<Item = &'a Page>>(
+    pages: I,
+    property: &str,
+) -> BTreeMap<String, usize> {
+    Aggregate::of(property, pages).groups
+}
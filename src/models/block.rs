@@ -1,10 +1,18 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::ids::{AsIdentifier, BlockId, DatabaseId, PageId};
+use crate::ids::{AsIdentifier, BlockId, DatabaseId, PageId, UserId};
+pub use crate::models::files::{
+    CustomEmoji, Emoji, ExternalFileObject, FileObject, FileOrEmojiObject, InternalFileObject,
+};
+pub use crate::models::text::Equation;
 use crate::models::text::{RichText, TextColor};
 use crate::models::users::UserCommon;
+use crate::models::{Parent, Trashable};
 
+pub mod builder;
+pub mod lists;
+#[cfg(test)]
 mod tests;
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
@@ -15,6 +23,14 @@ pub struct BlockCommon {
     pub has_children: bool,
     pub created_by: UserCommon,
     pub last_edited_by: UserCommon,
+    /// The page or block this block is nested in.
+    /// Only reported by API versions from 2022-06-28 on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<Parent>,
+    #[serde(default)]
+    pub archived: bool,
+    #[serde(default)]
+    pub in_trash: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
@@ -25,38 +41,26 @@ pub struct TextAndChildren {
     pub color: TextColor,
 }
 
+/// The contents of heading blocks.
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
 pub struct Text {
     pub rich_text: Vec<RichText>,
+    #[serde(default)]
+    pub color: TextColor,
 }
 
+/// The contents of image, video, file and pdf blocks.
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
-pub struct InternalFileObject {
-    url: String,
-    expiry_time: DateTime<Utc>,
-}
-
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
-pub struct ExternalFileObject {
-    url: String,
-}
-
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
-#[serde(tag = "type")]
-#[serde(rename_all = "snake_case")]
-pub enum FileOrEmojiObject {
-    Emoji { emoji: String },
-    File { file: InternalFileObject },
-    External { external: ExternalFileObject },
+pub struct FileFields {
+    #[serde(default)]
+    pub caption: Vec<RichText>,
+    #[serde(flatten)]
+    pub file: FileObject,
 }
 
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
-#[serde(tag = "type")]
-#[serde(rename_all = "snake_case")]
-pub enum FileObject {
-    File { file: InternalFileObject },
-    External { external: ExternalFileObject },
-}
+/// The contents of blocks without any fields, serialized as `{}`.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Default)]
+pub struct EmptyFields {}
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
 pub struct Callout {
@@ -187,11 +191,6 @@ pub struct CodeFields {
     pub language: CodeLanguage,
 }
 
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
-pub struct Equation {
-    pub expression: String,
-}
-
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
 pub struct TableOfContents {
     pub color: TextColor,
@@ -199,11 +198,13 @@ pub struct TableOfContents {
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
 pub struct ColumnListFields {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub children: Vec<Block>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
 pub struct ColumnFields {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub children: Vec<Block>,
 }
 
@@ -215,6 +216,7 @@ pub struct LinkPreviewFields {
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
 pub struct TemplateFields {
     pub rich_text: Vec<RichText>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub children: Vec<Block>,
 }
 
@@ -234,6 +236,7 @@ pub struct SyncedFromObject {
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
 pub struct SyncedBlockFields {
     pub synced_from: Option<SyncedFromObject>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub children: Vec<Block>,
 }
 
@@ -242,12 +245,14 @@ pub struct TableFields {
     pub table_width: u64,
     pub has_column_header: bool,
     pub has_row_header: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub children: Vec<Block>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
 pub struct TableRowFields {
-    pub cells: Vec<RichText>,
+    /// One rich text array per column.
+    pub cells: Vec<Vec<RichText>>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
@@ -320,7 +325,7 @@ pub enum Block {
     ChildDatabase {
         #[serde(flatten)]
         common: BlockCommon,
-        child_page: ChildDatabaseFields,
+        child_database: ChildDatabaseFields,
     },
     Embed {
         #[serde(flatten)]
@@ -330,23 +335,22 @@ pub enum Block {
     Image {
         #[serde(flatten)]
         common: BlockCommon,
-        image: FileObject,
+        image: FileFields,
     },
     Video {
         #[serde(flatten)]
         common: BlockCommon,
-        video: FileObject,
+        video: FileFields,
     },
     File {
         #[serde(flatten)]
         common: BlockCommon,
-        file: FileObject,
-        caption: Text,
+        file: FileFields,
     },
     Pdf {
         #[serde(flatten)]
         common: BlockCommon,
-        pdf: FileObject,
+        pdf: FileFields,
     },
     Bookmark {
         #[serde(flatten)]
@@ -361,6 +365,8 @@ pub enum Block {
     Divider {
         #[serde(flatten)]
         common: BlockCommon,
+        #[serde(default)]
+        divider: EmptyFields,
     },
     TableOfContents {
         #[serde(flatten)]
@@ -370,6 +376,8 @@ pub enum Block {
     Breadcrumb {
         #[serde(flatten)]
         common: BlockCommon,
+        #[serde(default)]
+        breadcrumb: EmptyFields,
     },
     ColumnList {
         #[serde(flatten)]
@@ -419,8 +427,9 @@ pub enum Block {
     Unknown,
 }
 
-impl AsIdentifier<BlockId> for Block {
-    fn as_id(&self) -> &BlockId {
+impl Block {
+    /// The fields shared by every block, or `None` for [Block::Unknown].
+    pub fn common(&self) -> Option<&BlockCommon> {
         use Block::*;
         match self {
             Paragraph { common, .. }
@@ -454,17 +463,218 @@ impl AsIdentifier<BlockId> for Block {
             | SyncedBlock { common, .. }
             | Table { common, .. }
             | TableRow { common, .. }
-            | Unsupported { common, .. } => &common.id,
-            Unknown => {
-                panic!("Trying to reference identifier for unknown block!")
+            | Unsupported { common, .. } => Some(common),
+            Unknown => None,
+        }
+    }
+
+    /// The `type` of this block in the API, such as `heading_1` or `to_do`.
+    pub fn type_name(&self) -> &'static str {
+        use Block::*;
+        match self {
+            Paragraph { .. } => "paragraph",
+            Heading1 { .. } => "heading_1",
+            Heading2 { .. } => "heading_2",
+            Heading3 { .. } => "heading_3",
+            Callout { .. } => "callout",
+            Quote { .. } => "quote",
+            BulletedListItem { .. } => "bulleted_list_item",
+            NumberedListItem { .. } => "numbered_list_item",
+            ToDo { .. } => "to_do",
+            Toggle { .. } => "toggle",
+            Code { .. } => "code",
+            ChildPage { .. } => "child_page",
+            ChildDatabase { .. } => "child_database",
+            Embed { .. } => "embed",
+            Image { .. } => "image",
+            Video { .. } => "video",
+            File { .. } => "file",
+            Pdf { .. } => "pdf",
+            Bookmark { .. } => "bookmark",
+            Equation { .. } => "equation",
+            Divider { .. } => "divider",
+            TableOfContents { .. } => "table_of_contents",
+            Breadcrumb { .. } => "breadcrumb",
+            ColumnList { .. } => "column_list",
+            Column { .. } => "column",
+            LinkPreview { .. } => "link_preview",
+            Template { .. } => "template",
+            LinkToPage { .. } => "link_to_page",
+            Table { .. } => "table",
+            SyncedBlock { .. } => "synced_block",
+            TableRow { .. } => "table_row",
+            Unsupported { .. } => "unsupported",
+            Unknown => "unknown",
+        }
+    }
+
+    /// The file of an image, video, file or PDF block.
+    pub fn file(&self) -> Option<&FileFields> {
+        match self {
+            Block::Image { image: file, .. }
+            | Block::Video { video: file, .. }
+            | Block::File { file, .. }
+            | Block::Pdf { pdf: file, .. } => Some(file),
+            _ => None,
+        }
+    }
+
+    /// The id of this block, or `None` for [Block::Unknown].
+    pub fn id(&self) -> Option<&BlockId> {
+        self.common().map(|common| &common.id)
+    }
+
+    /// The page or block this block is nested in, if the API reported it.
+    pub fn parent(&self) -> Option<&Parent> {
+        self.common()?.parent.as_ref()
+    }
+
+    /// The color of paragraph, heading, list, to-do, callout, quote, toggle
+    /// and table of contents blocks.
+    pub fn color(&self) -> Option<TextColor> {
+        use Block::*;
+        match self {
+            Paragraph {
+                paragraph: fields, ..
+            }
+            | Quote { quote: fields, .. }
+            | BulletedListItem {
+                bulleted_list_item: fields,
+                ..
+            }
+            | NumberedListItem {
+                numbered_list_item: fields,
+                ..
+            }
+            | Toggle { toggle: fields, .. } => Some(fields.color),
+            Heading1 {
+                heading_1: fields, ..
+            }
+            | Heading2 {
+                heading_2: fields, ..
             }
+            | Heading3 {
+                heading_3: fields, ..
+            } => Some(fields.color),
+            Callout { callout, .. } => Some(callout.color),
+            ToDo { to_do, .. } => Some(to_do.color),
+            TableOfContents {
+                table_of_contents, ..
+            } => Some(table_of_contents.color),
+            _ => None,
         }
     }
+
+    /// Whether this block has nested blocks that can be fetched
+    /// with [get_block_children](crate::NotionApi::get_block_children()).
+    pub fn has_children(&self) -> bool {
+        self.common().is_some_and(|common| common.has_children)
+    }
 }
 
-impl Into<CreateBlock> for Block {
-    fn into(self) -> CreateBlock {
-        match self {
+impl Trashable for Block {
+    fn is_trashed(&self) -> bool {
+        self.common()
+            .is_some_and(|common| common.archived || common.in_trash)
+    }
+}
+
+impl AsIdentifier<BlockId> for Block {
+    fn as_id(&self) -> &BlockId {
+        match self.common() {
+            Some(common) => &common.id,
+            None => panic!("Trying to reference identifier for unknown block!"),
+        }
+    }
+}
+
+/// A block together with its nested blocks,
+/// as returned by [get_block_tree](crate::NotionApi::get_block_tree()).
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct BlockTree {
+    pub block: Block,
+    /// Nested blocks in the order Notion returns them.
+    /// Empty when the block has no children or they were not fetched.
+    pub children: Vec<BlockTree>,
+}
+
+impl BlockTree {
+    /// Every block of the tree, parents before their children.
+    pub fn blocks(&self) -> Vec<&Block> {
+        let mut blocks = vec![&self.block];
+        for child in &self.children {
+            blocks.extend(child.blocks());
+        }
+        blocks
+    }
+
+    /// The blocks of the tree whose last edit was made by `user_id`.
+    pub fn last_edited_by(
+        &self,
+        user_id: &UserId,
+    ) -> Vec<&Block> {
+        self.blocks()
+            .into_iter()
+            .filter(|block| {
+                block
+                    .common()
+                    .is_some_and(|common| &common.last_edited_by.id == user_id)
+            })
+            .collect()
+    }
+}
+
+/// A block to create together with its nested blocks,
+/// see [append_block_trees](crate::NotionApi::append_block_trees()).
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct CreateBlockTree {
+    pub block: CreateBlock,
+    /// The rows of a table, or the blocks nested in any other block.
+    pub children: Vec<CreateBlockTree>,
+}
+
+impl From<CreateBlock> for CreateBlockTree {
+    fn from(block: CreateBlock) -> Self {
+        CreateBlockTree {
+            block,
+            children: vec![],
+        }
+    }
+}
+
+impl CreateBlock {
+    /// Sets the color of blocks that have one, see [Block::color()].
+    /// Other blocks are returned unchanged.
+    pub fn with_color(
+        mut self,
+        color: TextColor,
+    ) -> Self {
+        use CreateBlock::*;
+        match &mut self {
+            Paragraph { paragraph: fields }
+            | Quote { quote: fields }
+            | BulletedListItem {
+                bulleted_list_item: fields,
+            }
+            | NumberedListItem {
+                numbered_list_item: fields,
+            }
+            | Toggle { toggle: fields } => fields.color = color,
+            Heading1 { heading_1: fields }
+            | Heading2 { heading_2: fields }
+            | Heading3 { heading_3: fields } => fields.color = color,
+            Callout { callout } => callout.color = color,
+            ToDo { to_do } => to_do.color = color,
+            TableOfContents { table_of_contents } => table_of_contents.color = color,
+            _ => {}
+        }
+        self
+    }
+}
+
+impl From<Block> for CreateBlock {
+    fn from(block: Block) -> Self {
+        match block {
             Block::Paragraph { paragraph, .. } => CreateBlock::Paragraph { paragraph },
             Block::Heading1 { heading_1, .. } => CreateBlock::Heading1 { heading_1 },
             Block::Heading2 { heading_2, .. } => CreateBlock::Heading2 { heading_2 },
@@ -481,19 +691,21 @@ impl Into<CreateBlock> for Block {
             Block::Toggle { toggle, .. } => CreateBlock::Toggle { toggle },
             Block::Code { code, .. } => CreateBlock::Code { code },
             Block::ChildPage { child_page, .. } => CreateBlock::ChildPage { child_page },
-            Block::ChildDatabase { child_page, .. } => CreateBlock::ChildDatabase { child_page },
+            Block::ChildDatabase { child_database, .. } => {
+                CreateBlock::ChildDatabase { child_database }
+            }
             Block::Embed { embed, .. } => CreateBlock::Embed { embed },
             Block::Image { image, .. } => CreateBlock::Image { image },
             Block::Video { video, .. } => CreateBlock::Video { video },
-            Block::File { file, caption, .. } => CreateBlock::File { file, caption },
+            Block::File { file, .. } => CreateBlock::File { file },
             Block::Pdf { pdf, .. } => CreateBlock::Pdf { pdf },
             Block::Bookmark { bookmark, .. } => CreateBlock::Bookmark { bookmark },
             Block::Equation { equation, .. } => CreateBlock::Equation { equation },
-            Block::Divider { .. } => CreateBlock::Divider {},
+            Block::Divider { divider, .. } => CreateBlock::Divider { divider },
             Block::TableOfContents {
                 table_of_contents, ..
             } => CreateBlock::TableOfContents { table_of_contents },
-            Block::Breadcrumb { .. } => CreateBlock::Breadcrumb {},
+            Block::Breadcrumb { breadcrumb, .. } => CreateBlock::Breadcrumb { breadcrumb },
             Block::ColumnList { column_list, .. } => CreateBlock::ColumnList { column_list },
             Block::Column { column, .. } => CreateBlock::Column { column },
 
@@ -509,6 +721,102 @@ impl Into<CreateBlock> for Block {
     }
 }
 
+/// Request body for appending blocks to a page or block.
+/// See <https://developers.notion.com/reference/patch-block-children>
+#[derive(Serialize, Debug, Eq, PartialEq, Clone)]
+pub struct BlockAppendRequest {
+    pub children: Vec<CreateBlock>,
+    /// Insert the children after this sibling instead of at the end.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<BlockId>,
+}
+
+impl BlockAppendRequest {
+    /// Inserts the children right after `block_id`.
+    pub fn after<T: AsIdentifier<BlockId>>(
+        mut self,
+        block_id: T,
+    ) -> Self {
+        self.after = Some(block_id.as_id().clone());
+        self
+    }
+}
+
+impl From<Vec<CreateBlock>> for BlockAppendRequest {
+    fn from(children: Vec<CreateBlock>) -> Self {
+        BlockAppendRequest {
+            children,
+            after: None,
+        }
+    }
+}
+
+/// Request body for updating a block.
+/// See <https://developers.notion.com/reference/update-a-block>
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct BlockUpdateRequest {
+    /// The new content, which has to be of the same type as the block.
+    /// Only the text and settings are sent, nested blocks are left as they are.
+    pub block: Option<CreateBlock>,
+    /// Set to `true` to archive the block, or `false` to restore it.
+    pub archived: Option<bool>,
+    /// Set to `true` to move the block to the trash, or `false` to restore it.
+    pub in_trash: Option<bool>,
+}
+
+impl BlockUpdateRequest {
+    /// Archives the block without changing its content.
+    pub fn archive() -> Self {
+        BlockUpdateRequest {
+            archived: Some(true),
+            ..Default::default()
+        }
+    }
+
+    /// Takes the block out of the archive without changing its content.
+    pub fn restore() -> Self {
+        BlockUpdateRequest {
+            archived: Some(false),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<CreateBlock> for BlockUpdateRequest {
+    fn from(block: CreateBlock) -> Self {
+        BlockUpdateRequest {
+            block: Some(block),
+            ..Default::default()
+        }
+    }
+}
+
+/// The content is sent as `{"paragraph": {...}}`, without the `type` of a [CreateBlock].
+impl Serialize for BlockUpdateRequest {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        use serde::ser::Error;
+
+        let mut body = match &self.block {
+            Some(block) => match serde_json::to_value(block).map_err(S::Error::custom)? {
+                serde_json::Value::Object(fields) => fields,
+                _ => return Err(S::Error::custom("blocks serialize as objects")),
+            },
+            None => serde_json::Map::new(),
+        };
+        body.remove("type");
+        if let Some(archived) = self.archived {
+            body.insert("archived".to_string(), archived.into());
+        }
+        if let Some(in_trash) = self.in_trash {
+            body.insert("in_trash".to_string(), in_trash.into());
+        }
+        body.serialize(serializer)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
 #[serde(tag = "type")]
 #[serde(rename_all = "snake_case")]
@@ -553,23 +861,22 @@ pub enum CreateBlock {
         child_page: ChildPageFields,
     },
     ChildDatabase {
-        child_page: ChildDatabaseFields,
+        child_database: ChildDatabaseFields,
     },
     Embed {
         embed: EmbedFields,
     },
     Image {
-        image: FileObject,
+        image: FileFields,
     },
     Video {
-        video: FileObject,
+        video: FileFields,
     },
     File {
-        file: FileObject,
-        caption: Text,
+        file: FileFields,
     },
     Pdf {
-        pdf: FileObject,
+        pdf: FileFields,
     },
     Bookmark {
         bookmark: BookmarkFields,
@@ -577,11 +884,15 @@ pub enum CreateBlock {
     Equation {
         equation: Equation,
     },
-    Divider,
+    Divider {
+        divider: EmptyFields,
+    },
     TableOfContents {
         table_of_contents: TableOfContents,
     },
-    Breadcrumb,
+    Breadcrumb {
+        breadcrumb: EmptyFields,
+    },
     ColumnList {
         column_list: ColumnListFields,
     },
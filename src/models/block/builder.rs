@@ -0,0 +1,169 @@
+//! Shorthands for writing block content, usually through [blocks!](crate::blocks).
+//!
+//! ```
+//! use notion_wasi::blocks;
+//! use notion_wasi::models::block::CreateBlock;
+//!
+//! let children: Vec<CreateBlock> = blocks![
+//!     h1("Report"),
+//!     p("Summary…"),
+//!     todo("Ship it", checked = false),
+//!     code("rust", "fn main() {}"),
+//!     image("https://example.com/chart.png"),
+//! ];
+//! assert_eq!(children.len(), 5);
+//! ```
+
+use crate::models::block::{Callout, CodeFields, CodeLanguage, CreateBlock, EmptyFields};
+use crate::models::block::{Emoji, ExternalFileObject, FileFields, FileObject, FileOrEmojiObject};
+use crate::models::block::{Text, TextAndChildren, ToDoFields};
+use crate::models::text::{RichText, TextColor};
+
+/// Builds a `Vec<CreateBlock>` from the functions in [builder](crate::models::block::builder).
+///
+/// `todo` also accepts its state as `checked = <bool>`.
+#[macro_export]
+macro_rules! blocks {
+    (@block todo($text:expr, checked = $checked:expr $(,)?)) => {
+        $crate::models::block::builder::todo($text, $checked)
+    };
+    (@block todo($text:expr $(,)?)) => {
+        $crate::models::block::builder::todo($text, false)
+    };
+    (@block $name:ident($($arg:expr),* $(,)?)) => {
+        $crate::models::block::builder::$name($($arg),*)
+    };
+    ($($name:ident($($arg:tt)*)),* $(,)?) => {
+        vec![$($crate::blocks!(@block $name($($arg)*))),*]
+    };
+}
+
+fn rich_text<S: Into<String>>(text: S) -> Vec<RichText> {
+    vec![RichText::text(text)]
+}
+
+fn text_and_children<S: Into<String>>(text: S) -> TextAndChildren {
+    TextAndChildren {
+        rich_text: rich_text(text),
+        children: None,
+        color: TextColor::Default,
+    }
+}
+
+fn heading<S: Into<String>>(text: S) -> Text {
+    Text {
+        rich_text: rich_text(text),
+        color: TextColor::Default,
+    }
+}
+
+pub fn h1<S: Into<String>>(text: S) -> CreateBlock {
+    CreateBlock::Heading1 {
+        heading_1: heading(text),
+    }
+}
+
+pub fn h2<S: Into<String>>(text: S) -> CreateBlock {
+    CreateBlock::Heading2 {
+        heading_2: heading(text),
+    }
+}
+
+pub fn h3<S: Into<String>>(text: S) -> CreateBlock {
+    CreateBlock::Heading3 {
+        heading_3: heading(text),
+    }
+}
+
+/// A paragraph.
+pub fn p<S: Into<String>>(text: S) -> CreateBlock {
+    CreateBlock::Paragraph {
+        paragraph: text_and_children(text),
+    }
+}
+
+pub fn quote<S: Into<String>>(text: S) -> CreateBlock {
+    CreateBlock::Quote {
+        quote: text_and_children(text),
+    }
+}
+
+pub fn bullet<S: Into<String>>(text: S) -> CreateBlock {
+    CreateBlock::BulletedListItem {
+        bulleted_list_item: text_and_children(text),
+    }
+}
+
+pub fn numbered<S: Into<String>>(text: S) -> CreateBlock {
+    CreateBlock::NumberedListItem {
+        numbered_list_item: text_and_children(text),
+    }
+}
+
+pub fn toggle<S: Into<String>>(text: S) -> CreateBlock {
+    CreateBlock::Toggle {
+        toggle: text_and_children(text),
+    }
+}
+
+pub fn todo<S: Into<String>>(
+    text: S,
+    checked: bool,
+) -> CreateBlock {
+    CreateBlock::ToDo {
+        to_do: ToDoFields {
+            rich_text: rich_text(text),
+            checked,
+            children: None,
+            color: TextColor::Default,
+        },
+    }
+}
+
+/// A code block. `language` is the API name, such as `rust` or `plain text`;
+/// unknown languages fall back to [CodeLanguage::PlainText].
+pub fn code<S: Into<String>>(
+    language: &str,
+    code: S,
+) -> CreateBlock {
+    let language = serde_json::from_value(language.into()).unwrap_or(CodeLanguage::PlainText);
+    CreateBlock::Code {
+        code: CodeFields {
+            rich_text: rich_text(code),
+            caption: vec![],
+            language,
+        },
+    }
+}
+
+pub fn divider() -> CreateBlock {
+    CreateBlock::Divider {
+        divider: EmptyFields {},
+    }
+}
+
+/// A callout with `emoji` as its icon.
+pub fn callout<S: Into<String>>(
+    emoji: Emoji,
+    text: S,
+) -> CreateBlock {
+    CreateBlock::Callout {
+        callout: Callout {
+            rich_text: rich_text(text),
+            icon: FileOrEmojiObject::Emoji { emoji },
+            color: TextColor::Default,
+        },
+    }
+}
+
+/// An image hosted at `url`, without a caption.
+pub fn image<S: Into<String>>(url: S) -> CreateBlock {
+    CreateBlock::Image {
+        image: FileFields {
+            caption: vec![],
+            file: FileObject::External {
+                external: ExternalFileObject { url: url.into() },
+            },
+        },
+    }
+}
@@ -0,0 +1,92 @@
+//! Grouping of list item blocks for renderers.
+//!
+//! The API returns the items of a list as flat siblings, so Markdown or HTML
+//! renderers have to find where each list starts and ends.
+//! [group_lists()] does that once, recursively for nested blocks.
+
+use crate::models::block::{Block, BlockTree};
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum ListKind {
+    Bulleted,
+    Numbered,
+}
+
+impl ListKind {
+    fn of(block: &Block) -> Option<Self> {
+        match block {
+            Block::BulletedListItem { .. } => Some(ListKind::Bulleted),
+            Block::NumberedListItem { .. } => Some(ListKind::Numbered),
+            _ => None,
+        }
+    }
+}
+
+/// A block, or a run of consecutive list items of the same kind.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum Grouped<'a> {
+    Block(&'a BlockTree),
+    List(List<'a>),
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct List<'a> {
+    pub kind: ListKind,
+    pub items: Vec<ListItem<'a>>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct ListItem<'a> {
+    pub item: &'a BlockTree,
+    /// The position in a numbered list, starting at 1. `None` for bulleted lists.
+    pub number: Option<usize>,
+    /// The nested blocks of the item, grouped the same way.
+    pub children: Vec<Grouped<'a>>,
+}
+
+/// Groups consecutive list items of the same kind into [List]s.
+///
+/// Any other block, or a list item of the other kind, ends the current list,
+/// so numbering restarts the way Notion displays it.
+pub fn group_lists(blocks: &[BlockTree]) -> Vec<Grouped<'_>> {
+    let mut grouped = vec![];
+    let mut current: Option<List<'_>> = None;
+
+    for tree in blocks {
+        let kind = match ListKind::of(&tree.block) {
+            Some(kind) => kind,
+            None => {
+                grouped.extend(current.take().map(Grouped::List));
+                grouped.push(Grouped::Block(tree));
+                continue;
+            }
+        };
+        if current.as_ref().is_some_and(|list| list.kind != kind) {
+            grouped.extend(current.take().map(Grouped::List));
+        }
+
+        let list = current.get_or_insert_with(|| List {
+            kind,
+            items: vec![],
+        });
+        let number = match kind {
+            ListKind::Numbered => Some(list.items.len() + 1),
+            ListKind::Bulleted => None,
+        };
+        list.items.push(ListItem {
+            item: tree,
+            number,
+            children: group_lists(&tree.children),
+        });
+    }
+    grouped.extend(current.map(Grouped::List));
+
+    grouped
+}
+
+impl BlockTree {
+    /// The children of this block with list items grouped, see [group_lists()].
+    pub fn grouped_children(&self) -> Vec<Grouped<'_>> {
+        group_lists(&self.children)
+    }
+}
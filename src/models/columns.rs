@@ -0,0 +1,355 @@
+//! Pages as typed columns, one per property, for dataframes and other
+//! columnar tools.
+//!
+//! Each column holds one value per page, in page order, so the values line up
+//! with [Columns::ids]. The column type follows the property type:
+//!
+//! | Property                                                  | Column                  |
+//! |-----------------------------------------------------------|-------------------------|
+//! | number, number formulas and rollups                       | [ColumnValues::Number]  |
+//! | checkbox, boolean formulas                                | [ColumnValues::Boolean] |
+//! | date, created and last edited time, date formulas/rollups | [ColumnValues::Date]    |
+//! | multi-select, people, relation, files, array rollups      | [ColumnValues::List]    |
+//! | everything else                                           | [ColumnValues::Text]    |
+//!
+//! Pages don't say which type an empty formula or rollup has, so the type
+//! is taken from the first page with a value, and properties that are empty
+//! on every page become text columns. Values that don't match the type of their
+//! column, such as a formula returning another type on some pages, are `None`.
+//! Dates are the start of the date value.
+//!
+//! With the `polars` feature, [Columns::to_dataframe()] loads them into a polars
+//! [DataFrame](polars::prelude::DataFrame).
+
+use std::collections::BTreeSet;
+
+#[cfg(all(feature = "polars", not(target_os = "wasi")))]
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+#[cfg(all(feature = "polars", not(target_os = "wasi")))]
+use polars::prelude::{DataFrame, NamedFrom, PlSmallStr, PolarsResult, Series};
+
+use crate::ids::PageId;
+use crate::models::properties::{
+    DateOrDateTime, FormulaResultValue, PropertyValue, RollupPropertyValue, RollupValue,
+};
+use crate::models::text;
+use crate::models::users::UserReference;
+use crate::models::{Number, Page};
+
+/// The values of one property, see the [module docs](self).
+#[derive(Debug, PartialEq, Clone)]
+pub enum ColumnValues {
+    Number(Vec<Option<f64>>),
+    Boolean(Vec<Option<bool>>),
+    Date(Vec<Option<DateOrDateTime>>),
+    Text(Vec<Option<String>>),
+    List(Vec<Vec<String>>),
+}
+
+impl ColumnValues {
+    pub fn len(&self) -> usize {
+        match self {
+            ColumnValues::Number(values) => values.len(),
+            ColumnValues::Boolean(values) => values.len(),
+            ColumnValues::Date(values) => values.len(),
+            ColumnValues::Text(values) => values.len(),
+            ColumnValues::List(values) => values.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Column {
+    /// The property name.
+    pub name: String,
+    pub values: ColumnValues,
+}
+
+/// Every property of a set of pages as columns, sorted by property name.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Columns {
+    /// The page of each row.
+    pub ids: Vec<PageId>,
+    pub columns: Vec<Column>,
+}
+
+impl Columns {
+    pub fn from_pages(pages: &[Page]) -> Self {
+        let names: BTreeSet<&String> = pages
+            .iter()
+            .flat_map(|page| page.properties.properties.keys())
+            .collect();
+
+        let columns = names
+            .into_iter()
+            .map(|name| {
+                let cells: Vec<Cell> = pages
+                    .iter()
+                    .map(|page| {
+                        page.properties
+                            .properties
+                            .get(name)
+                            .map_or(Cell::Null, Cell::from)
+                    })
+                    .collect();
+                Column {
+                    name: name.clone(),
+                    values: column_values(cells),
+                }
+            })
+            .collect();
+
+        Columns {
+            ids: pages.iter().map(|page| page.id.clone()).collect(),
+            columns,
+        }
+    }
+
+    pub fn column(
+        &self,
+        name: &str,
+    ) -> Option<&ColumnValues> {
+        self.columns
+            .iter()
+            .find(|column| column.name == name)
+            .map(|column| &column.values)
+    }
+
+    /// The number of rows.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// A polars [DataFrame] with an `id` column of page ids, followed by
+    /// one column per property. Number, boolean and text columns keep their type,
+    /// lists become lists of strings. Date columns are dates if no value has a time,
+    /// otherwise datetimes in UTC, with dates at midnight.
+    ///
+    /// Fails if a property is called `id`.
+    #[cfg(all(feature = "polars", not(target_os = "wasi")))]
+    pub fn to_dataframe(&self) -> PolarsResult<DataFrame> {
+        let ids: Vec<String> = self.ids.iter().map(PageId::to_string).collect();
+        let mut columns = vec![Series::new("id".into(), ids).into()];
+        for column in &self.columns {
+            let name = column.name.as_str().into();
+            let series = match &column.values {
+                ColumnValues::Number(values) => Series::new(name, values),
+                ColumnValues::Boolean(values) => Series::new(name, values),
+                ColumnValues::Text(values) => Series::new(name, values),
+                ColumnValues::Date(values) => date_series(name, values),
+                ColumnValues::List(values) => {
+                    let lists: Vec<Series> = values
+                        .iter()
+                        .map(|list| Series::new(PlSmallStr::EMPTY, list))
+                        .collect();
+                    Series::new(name, lists)
+                }
+            };
+            columns.push(series.into());
+        }
+        DataFrame::new(self.len(), columns)
+    }
+}
+
+#[cfg(all(feature = "polars", not(target_os = "wasi")))]
+fn date_series(
+    name: PlSmallStr,
+    values: &[Option<DateOrDateTime>],
+) -> Series {
+    let has_time = values
+        .iter()
+        .any(|value| matches!(value, Some(DateOrDateTime::DateTime(_))));
+    if has_time {
+        let values: Vec<Option<NaiveDateTime>> = values
+            .iter()
+            .map(|value| {
+                value.map(|value| match value {
+                    DateOrDateTime::Date(date) => date.and_time(NaiveTime::MIN),
+                    DateOrDateTime::DateTime(date_time) => date_time.naive_utc(),
+                })
+            })
+            .collect();
+        Series::new(name, values)
+    } else {
+        let values: Vec<Option<NaiveDate>> = values
+            .iter()
+            .map(|value| match value {
+                Some(DateOrDateTime::Date(date)) => Some(*date),
+                _ => None,
+            })
+            .collect();
+        Series::new(name, values)
+    }
+}
+
+/// A property value as it appears in a column.
+pub(crate) enum Cell {
+    Null,
+    Number(f64),
+    Boolean(bool),
+    Date(DateOrDateTime),
+    Text(String),
+    List(Vec<String>),
+}
+
+impl Cell {
+    fn number(number: &Option<Number>) -> Self {
+        number
+            .as_ref()
+            .and_then(Number::as_f64)
+            .map_or(Cell::Null, Cell::Number)
+    }
+
+    fn text(text: Option<String>) -> Self {
+        text.map_or(Cell::Null, Cell::Text)
+    }
+}
+
+impl From<&PropertyValue> for Cell {
+    fn from(value: &PropertyValue) -> Self {
+        use PropertyValue::*;
+        match value {
+            Title { title, .. } => Cell::Text(text::plain_text(title).into_owned()),
+            Text { rich_text, .. } => Cell::Text(text::plain_text(rich_text).into_owned()),
+            Number { number, .. } => Cell::number(number),
+            Select { select, .. } | Status { status: select, .. } => {
+                Cell::text(select.as_ref().and_then(|option| option.name.clone()))
+            }
+            MultiSelect { multi_select, .. } => Cell::List(
+                multi_select
+                    .iter()
+                    .flatten()
+                    .filter_map(|option| option.name.clone())
+                    .collect(),
+            ),
+            Date { date, .. } => date
+                .as_ref()
+                .map_or(Cell::Null, |date| Cell::Date(date.start)),
+            Formula { formula, .. } => match formula {
+                FormulaResultValue::String { string } => Cell::text(string.clone()),
+                FormulaResultValue::Number { number } => Cell::number(number),
+                FormulaResultValue::Boolean { boolean } => {
+                    boolean.map_or(Cell::Null, Cell::Boolean)
+                }
+                FormulaResultValue::Date { date } => date
+                    .as_ref()
+                    .map_or(Cell::Null, |date| Cell::Date(date.start)),
+            },
+            Relation { relation, .. } => Cell::List(
+                relation
+                    .iter()
+                    .flatten()
+                    .map(|related| related.id.to_string())
+                    .collect(),
+            ),
+            Rollup { rollup, .. } => match rollup {
+                None => Cell::Null,
+                Some(RollupValue::Number { number }) => Cell::number(number),
+                Some(RollupValue::Date { date }) => date
+                    .as_ref()
+                    .map_or(Cell::Null, |date| Cell::Date(date.start)),
+                Some(RollupValue::Array { array }) => {
+                    Cell::List(array.iter().filter_map(rollup_item).collect())
+                }
+            },
+            People { people, .. } => Cell::List(people.iter().map(person).collect()),
+            Files { files, .. } => Cell::List(
+                files
+                    .iter()
+                    .flatten()
+                    .map(|file| file.name.clone())
+                    .collect(),
+            ),
+            Checkbox { checkbox, .. } => Cell::Boolean(*checkbox),
+            Url { url, .. } => Cell::text(url.clone()),
+            Email { email, .. } => Cell::text(email.clone()),
+            PhoneNumber { phone_number, .. } => Cell::text(phone_number.clone()),
+            CreatedTime { created_time, .. } => Cell::Date((*created_time).into()),
+            LastEditedTime {
+                last_edited_time, ..
+            } => Cell::Date((*last_edited_time).into()),
+            CreatedBy { created_by, .. } => Cell::Text(person(created_by)),
+            LastEditedBy { last_edited_by, .. } => Cell::Text(person(last_edited_by)),
+        }
+    }
+}
+
+/// The name of a user, or their id if the integration can't read names.
+fn person(user: &UserReference) -> String {
+    user.user()
+        .and_then(|user| user.common().name.clone())
+        .unwrap_or_else(|| user.id().to_string())
+}
+
+fn rollup_item(value: &RollupPropertyValue) -> Option<String> {
+    match value {
+        RollupPropertyValue::Title { title } => Some(text::plain_text(title).into_owned()),
+        RollupPropertyValue::Text { rich_text } => Some(text::plain_text(rich_text).into_owned()),
+        RollupPropertyValue::Number { number } => number.as_ref().map(Number::to_string),
+        RollupPropertyValue::Select { select } | RollupPropertyValue::Status { status: select } => {
+            select.as_ref().and_then(|option| option.name.clone())
+        }
+        RollupPropertyValue::Date { date } => date.as_ref().map(|date| date.start.to_string()),
+        _ => None,
+    }
+}
+
+/// Builds a column of the type of the first value.
+fn column_values(cells: Vec<Cell>) -> ColumnValues {
+    let first = cells.iter().find(|cell| !matches!(cell, Cell::Null));
+    match first {
+        Some(Cell::Number(_)) => ColumnValues::Number(
+            cells
+                .into_iter()
+                .map(|cell| match cell {
+                    Cell::Number(number) => Some(number),
+                    _ => None,
+                })
+                .collect(),
+        ),
+        Some(Cell::Boolean(_)) => ColumnValues::Boolean(
+            cells
+                .into_iter()
+                .map(|cell| match cell {
+                    Cell::Boolean(boolean) => Some(boolean),
+                    _ => None,
+                })
+                .collect(),
+        ),
+        Some(Cell::Date(_)) => ColumnValues::Date(
+            cells
+                .into_iter()
+                .map(|cell| match cell {
+                    Cell::Date(date) => Some(date),
+                    _ => None,
+                })
+                .collect(),
+        ),
+        Some(Cell::List(_)) => ColumnValues::List(
+            cells
+                .into_iter()
+                .map(|cell| match cell {
+                    Cell::List(list) => list,
+                    _ => vec![],
+                })
+                .collect(),
+        ),
+        Some(Cell::Text(_)) | Some(Cell::Null) | None => ColumnValues::Text(
+            cells
+                .into_iter()
+                .map(|cell| match cell {
+                    Cell::Text(text) => Some(text),
+                    _ => None,
+                })
+                .collect(),
+        ),
+    }
+}
@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::ids::{CommentId, DiscussionId, PageId};
+use crate::models::text::RichText;
+use crate::models::users::UserCommon;
+use crate::models::{DateTime, Parent, Utc};
+
+/// A comment on a page or block.
+/// See <https://developers.notion.com/reference/comment-object>
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct Comment {
+    pub id: CommentId,
+    /// The page or block the discussion is attached to.
+    pub parent: Parent,
+    /// The thread the comment belongs to.
+    pub discussion_id: DiscussionId,
+    pub created_time: DateTime<Utc>,
+    pub last_edited_time: DateTime<Utc>,
+    pub created_by: UserCommon,
+    pub rich_text: Vec<RichText>,
+}
+
+impl Comment {
+    /// A reply to the discussion of this comment.
+    pub fn reply(
+        &self,
+        rich_text: Vec<RichText>,
+    ) -> CommentCreateRequest {
+        CommentCreateRequest::reply(self.discussion_id.clone(), rich_text)
+    }
+}
+
+/// Where [NotionApi::create_comment()](crate::NotionApi::create_comment()) adds a comment.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[serde(untagged)]
+pub enum CommentTarget {
+    /// Starts a new discussion on a page.
+    Page { parent: Parent },
+    /// Replies to an existing discussion.
+    Discussion { discussion_id: DiscussionId },
+}
+
+/// Request body for creating a comment.
+/// See <https://developers.notion.com/reference/create-a-comment>
+#[derive(Serialize, Debug, Eq, PartialEq, Clone)]
+pub struct CommentCreateRequest {
+    #[serde(flatten)]
+    pub target: CommentTarget,
+    pub rich_text: Vec<RichText>,
+}
+
+impl CommentCreateRequest {
+    /// A comment starting a new discussion on a page.
+    pub fn on_page(
+        page_id: PageId,
+        rich_text: Vec<RichText>,
+    ) -> Self {
+        CommentCreateRequest {
+            target: CommentTarget::Page {
+                parent: Parent::Page { page_id },
+            },
+            rich_text,
+        }
+    }
+
+    /// A reply to the discussion `discussion_id`.
+    pub fn reply(
+        discussion_id: DiscussionId,
+        rich_text: Vec<RichText>,
+    ) -> Self {
+        CommentCreateRequest {
+            target: CommentTarget::Discussion { discussion_id },
+            rich_text,
+        }
+    }
+}
+
+/// The comments of one discussion, oldest first. See [threads()].
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Thread {
+    pub discussion_id: DiscussionId,
+    pub comments: Vec<Comment>,
+}
+
+impl Thread {
+    /// The comment that started the discussion.
+    pub fn first(&self) -> &Comment {
+        &self.comments[0]
+    }
+
+    /// A reply to this discussion.
+    pub fn reply(
+        &self,
+        rich_text: Vec<RichText>,
+    ) -> CommentCreateRequest {
+        CommentCreateRequest::reply(self.discussion_id.clone(), rich_text)
+    }
+}
+
+/// Groups `comments` by discussion.
+///
+/// The comments of each thread are ordered by creation time,
+/// and the threads by the creation time of their first comment.
+pub fn threads(comments: Vec<Comment>) -> Vec<Thread> {
+    let mut threads: Vec<Thread> = vec![];
+    let mut positions: HashMap<DiscussionId, usize> = HashMap::new();
+    for comment in comments {
+        match positions.get(&comment.discussion_id) {
+            Some(&position) => threads[position].comments.push(comment),
+            None => {
+                positions.insert(comment.discussion_id.clone(), threads.len());
+                threads.push(Thread {
+                    discussion_id: comment.discussion_id.clone(),
+                    comments: vec![comment],
+                });
+            }
+        }
+    }
+
+    for thread in &mut threads {
+        thread.comments.sort_by_key(|comment| comment.created_time);
+    }
+    threads.sort_by_key(|thread| thread.first().created_time);
+    threads
+}
@@ -0,0 +1,300 @@
+//! Pages as CSV, one row per page and one column per property.
+//!
+//! [CsvOptions] controls which columns are written and how property values
+//! are formatted, so exports can match what a spreadsheet or import job expects.
+//! Fields are quoted as described in RFC 4180 and rows end with `\r\n`.
+//!
+//! ```
+//! use notion_wasi::models::csv::{self, CsvOptions};
+//!
+//! let options = CsvOptions {
+//!     columns: Some(vec!["Name".to_string(), "Due".to_string()]),
+//!     date_format: Some("%d/%m/%Y".to_string()),
+//!     ..CsvOptions::default()
+//! };
+//! assert_eq!(csv::to_csv(&[], &options), "Name,Due\r\n");
+//! ```
+
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+use crate::models::properties::{
+    DateOrDateTime, DateValue, FormulaResultValue, PropertyValue, RollupPropertyValue, RollupValue,
+};
+use crate::models::text;
+use crate::models::users::{User, UserReference};
+use crate::models::{Number, Page};
+
+/// How users in people, created by and last edited by properties are written.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum PeopleFormat {
+    /// The name of the user.
+    Name,
+    /// The email of the user, only present with the user information capability
+    /// including email addresses.
+    Email,
+    Id,
+}
+
+/// How formula results are written.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum FormulaFormat {
+    /// Formatted like a property of the result type, so date formulas follow
+    /// [CsvOptions::date_format].
+    Computed,
+    /// As Notion returns the result, ignoring the formatting options:
+    /// dates as `YYYY-MM-DD` or RFC 3339, and ranges as `start/end`.
+    Raw,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct CsvOptions {
+    /// The properties to write, in column order.
+    ///
+    /// Defaults to every property of the pages, the title first and the rest sorted by name.
+    /// Properties a page doesn't have are written as empty fields.
+    pub columns: Option<Vec<String>>,
+    /// A [chrono format string](chrono::format::strftime) for dates and times.
+    ///
+    /// Defaults to `YYYY-MM-DD` for dates and RFC 3339 for datetimes.
+    /// Date-only values are formatted as midnight UTC.
+    pub date_format: Option<String>,
+    /// Written between the start and end of date ranges.
+    pub date_range_separator: String,
+    /// Written between the values of multi-selects, people, relations, files and array rollups.
+    pub list_separator: String,
+    pub people: PeopleFormat,
+    pub formulas: FormulaFormat,
+    /// Separates the fields of a row.
+    pub delimiter: char,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            columns: None,
+            date_format: None,
+            date_range_separator: " → ".to_string(),
+            list_separator: ", ".to_string(),
+            people: PeopleFormat::Name,
+            formulas: FormulaFormat::Computed,
+            delimiter: ',',
+        }
+    }
+}
+
+/// Writes `pages` as CSV with a header row, see the [module docs](self).
+pub fn to_csv(
+    pages: &[Page],
+    options: &CsvOptions,
+) -> String {
+    let columns = options
+        .columns
+        .clone()
+        .unwrap_or_else(|| default_columns(pages));
+
+    let mut csv = String::new();
+    write_row(&mut csv, columns.iter().cloned(), options.delimiter);
+    for page in pages {
+        let fields = columns.iter().map(|name| {
+            page.properties
+                .properties
+                .get(name)
+                .map(|value| options.field(value))
+                .unwrap_or_default()
+        });
+        write_row(&mut csv, fields, options.delimiter);
+    }
+    csv
+}
+
+/// The title property, followed by every other property sorted by name.
+fn default_columns(pages: &[Page]) -> Vec<String> {
+    let mut titles = BTreeSet::new();
+    let mut names = BTreeSet::new();
+    for (name, value) in pages.iter().flat_map(|page| &page.properties.properties) {
+        match value {
+            PropertyValue::Title { .. } => titles.insert(name),
+            _ => names.insert(name),
+        };
+    }
+    titles
+        .iter()
+        .chain(names.difference(&titles))
+        .map(|name| name.to_string())
+        .collect()
+}
+
+fn number_field(number: &Option<Number>) -> String {
+    number.as_ref().map(Number::to_string).unwrap_or_default()
+}
+
+fn write_row<I: Iterator<Item = String>>(
+    csv: &mut String,
+    fields: I,
+    delimiter: char,
+) {
+    for (i, field) in fields.enumerate() {
+        if i > 0 {
+            csv.push(delimiter);
+        }
+        if field.contains([delimiter, '"', '\r', '\n']) {
+            csv.push('"');
+            csv.push_str(&field.replace('"', "\"\""));
+            csv.push('"');
+        } else {
+            csv.push_str(&field);
+        }
+    }
+    csv.push_str("\r\n");
+}
+
+impl CsvOptions {
+    fn field(
+        &self,
+        value: &PropertyValue,
+    ) -> String {
+        use PropertyValue::*;
+        match value {
+            Title { title, .. } => text::plain_text(title).into_owned(),
+            Text { rich_text, .. } => text::plain_text(rich_text).into_owned(),
+            Number { number, .. } => number_field(number),
+            Select { select, .. } | Status { status: select, .. } => select
+                .as_ref()
+                .and_then(|option| option.name.clone())
+                .unwrap_or_default(),
+            MultiSelect { multi_select, .. } => self.list(
+                multi_select
+                    .iter()
+                    .flatten()
+                    .filter_map(|option| option.name.clone()),
+            ),
+            Date { date, .. } => date
+                .as_ref()
+                .map(|date| self.date_value(date))
+                .unwrap_or_default(),
+            Formula { formula, .. } => self.formula(formula),
+            Relation { relation, .. } => self.list(
+                relation
+                    .iter()
+                    .flatten()
+                    .map(|related| related.id.to_string()),
+            ),
+            Rollup { rollup, .. } => match rollup {
+                None => String::new(),
+                Some(RollupValue::Number { number }) => number_field(number),
+                Some(RollupValue::Date { date }) => date
+                    .as_ref()
+                    .map(|date| self.date_value(date))
+                    .unwrap_or_default(),
+                Some(RollupValue::Array { array }) => {
+                    self.list(array.iter().filter_map(|item| self.rollup_item(item)))
+                }
+            },
+            People { people, .. } => self.list(people.iter().map(|user| self.person(user))),
+            Files { files, .. } => self.list(files.iter().flatten().map(|file| file.name.clone())),
+            Checkbox { checkbox, .. } => checkbox.to_string(),
+            Url { url, .. } => url.clone().unwrap_or_default(),
+            Email { email, .. } => email.clone().unwrap_or_default(),
+            PhoneNumber { phone_number, .. } => phone_number.clone().unwrap_or_default(),
+            CreatedTime { created_time, .. } => self.date(&(*created_time).into()),
+            LastEditedTime {
+                last_edited_time, ..
+            } => self.date(&(*last_edited_time).into()),
+            CreatedBy { created_by, .. } => self.person(created_by),
+            LastEditedBy { last_edited_by, .. } => self.person(last_edited_by),
+        }
+    }
+
+    fn formula(
+        &self,
+        formula: &FormulaResultValue,
+    ) -> String {
+        match formula {
+            FormulaResultValue::String { string } => string.clone().unwrap_or_default(),
+            FormulaResultValue::Number { number } => number_field(number),
+            FormulaResultValue::Boolean { boolean } => boolean
+                .map(|boolean| boolean.to_string())
+                .unwrap_or_default(),
+            FormulaResultValue::Date { date: None } => String::new(),
+            FormulaResultValue::Date { date: Some(date) } => match self.formulas {
+                FormulaFormat::Computed => self.date_value(date),
+                FormulaFormat::Raw => match &date.end {
+                    Some(end) => format!("{}/{}", date.start, end),
+                    None => date.start.to_string(),
+                },
+            },
+        }
+    }
+
+    fn rollup_item(
+        &self,
+        value: &RollupPropertyValue,
+    ) -> Option<String> {
+        match value {
+            RollupPropertyValue::Title { title } => Some(text::plain_text(title).into_owned()),
+            RollupPropertyValue::Text { rich_text } => {
+                Some(text::plain_text(rich_text).into_owned())
+            }
+            RollupPropertyValue::Number { number } => number.as_ref().map(Number::to_string),
+            RollupPropertyValue::Select { select }
+            | RollupPropertyValue::Status { status: select } => {
+                select.as_ref().and_then(|option| option.name.clone())
+            }
+            RollupPropertyValue::Date { date } => date.as_ref().map(|date| self.date_value(date)),
+            _ => None,
+        }
+    }
+
+    fn list<I: Iterator<Item = String>>(
+        &self,
+        values: I,
+    ) -> String {
+        values.collect::<Vec<_>>().join(&self.list_separator)
+    }
+
+    fn date_value(
+        &self,
+        date: &DateValue,
+    ) -> String {
+        match &date.end {
+            Some(end) => format!(
+                "{}{}{}",
+                self.date(&date.start),
+                self.date_range_separator,
+                self.date(end)
+            ),
+            None => self.date(&date.start),
+        }
+    }
+
+    /// Falls back to the default format if [CsvOptions::date_format] is invalid.
+    fn date(
+        &self,
+        date: &DateOrDateTime,
+    ) -> String {
+        let format = match &self.date_format {
+            Some(format) => format,
+            None => return date.to_string(),
+        };
+        let mut formatted = String::new();
+        match write!(formatted, "{}", date.to_datetime().format(format)) {
+            Ok(()) => formatted,
+            Err(_) => date.to_string(),
+        }
+    }
+
+    /// Falls back to the id if the requested detail isn't available.
+    fn person(
+        &self,
+        user: &UserReference,
+    ) -> String {
+        let detail = match (self.people, user.user()) {
+            (PeopleFormat::Name, Some(user)) => user.common().name.clone(),
+            (PeopleFormat::Email, Some(User::Person { person, .. })) => person.email.clone(),
+            (PeopleFormat::Email, Some(User::Bot { bot, .. })) => Some(bot.email.clone()),
+            _ => None,
+        };
+        detail.unwrap_or_else(|| user.id().to_string())
+    }
+}
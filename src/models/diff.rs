@@ -0,0 +1,152 @@
+use std::collections::{BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::ids::BlockId;
+use crate::models::block::{Block, BlockTree};
+use crate::models::Page;
+
+/// A page together with its block tree, captured at a point in time.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct PageSnapshot {
+    pub page: Page,
+    pub blocks: Vec<BlockTree>,
+}
+
+/// The differences between two [PageSnapshot]s of the same page.
+/// See [diff()].
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Default)]
+pub struct PageDiff {
+    /// Names of properties that were added, removed or changed, in sorted order.
+    pub changed_properties: Vec<String>,
+    /// Blocks present only in the newer snapshot, in tree order.
+    pub added_blocks: Vec<BlockId>,
+    /// Blocks present only in the older snapshot, in tree order.
+    pub removed_blocks: Vec<BlockId>,
+    /// Blocks present in both snapshots whose content differs, in tree order.
+    pub edited_blocks: Vec<BlockId>,
+}
+
+impl PageDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changed_properties.is_empty()
+            && self.added_blocks.is_empty()
+            && self.removed_blocks.is_empty()
+            && self.edited_blocks.is_empty()
+    }
+}
+
+/// Compare an `old` and a `new` snapshot of a page.
+///
+/// Blocks are matched by id, wherever they sit in the tree.
+/// A block counts as edited when any of its own fields changed,
+/// including `has_children` and `last_edited_time`.
+pub fn diff(
+    old: &PageSnapshot,
+    new: &PageSnapshot,
+) -> PageDiff {
+    let old_properties = &old.page.properties.properties;
+    let new_properties = &new.page.properties.properties;
+    let changed_properties = old_properties
+        .keys()
+        .chain(new_properties.keys())
+        .filter(|name| old_properties.get(*name) != new_properties.get(*name))
+        .cloned()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    let old_blocks = flatten(&old.blocks);
+    let new_blocks = flatten(&new.blocks);
+    let old_by_id: HashMap<&BlockId, &Block> = old_blocks.iter().copied().collect();
+    let new_by_id: HashMap<&BlockId, &Block> = new_blocks.iter().copied().collect();
+
+    let mut diff = PageDiff {
+        changed_properties,
+        ..Default::default()
+    };
+
+    for (id, block) in &new_blocks {
+        match old_by_id.get(id) {
+            None => diff.added_blocks.push((*id).clone()),
+            Some(old_block) if old_block != block => diff.edited_blocks.push((*id).clone()),
+            Some(_) => {}
+        }
+    }
+    diff.removed_blocks = old_blocks
+        .iter()
+        .filter(|(id, _)| !new_by_id.contains_key(id))
+        .map(|(id, _)| (*id).clone())
+        .collect();
+
+    diff
+}
+
+fn flatten(trees: &[BlockTree]) -> Vec<(&BlockId, &Block)> {
+    let mut blocks = vec![];
+    for tree in trees {
+        if let Some(common) = tree.block.common() {
+            blocks.push((&common.id, &tree.block));
+        }
+        blocks.extend(flatten(&tree.children));
+    }
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ids::BlockId;
+    use crate::models::block::{Block, BlockTree};
+    use crate::models::diff::{diff, PageSnapshot};
+    use std::str::FromStr;
+
+    fn heading(id: &str) -> Block {
+        let mut block: Block =
+            serde_json::from_str(include_str!("block/tests/heading_1.json")).unwrap();
+        if let Block::Heading1 { common, .. } = &mut block {
+            common.id = BlockId::from_str(id).unwrap();
+        }
+        block
+    }
+
+    fn snapshot(blocks: Vec<BlockTree>) -> PageSnapshot {
+        PageSnapshot {
+            page: serde_json::from_str(include_str!("tests/page.json")).unwrap(),
+            blocks,
+        }
+    }
+
+    fn leaf(block: Block) -> BlockTree {
+        BlockTree {
+            block,
+            children: vec![],
+        }
+    }
+
+    #[test]
+    fn identical_snapshots_have_no_diff() {
+        let old = snapshot(vec![leaf(heading("a"))]);
+        assert!(diff(&old, &old.clone()).is_empty());
+    }
+
+    #[test]
+    fn reports_block_and_property_changes() {
+        let old = snapshot(vec![BlockTree {
+            block: heading("a"),
+            children: vec![leaf(heading("b"))],
+        }]);
+
+        let mut edited = heading("a");
+        if let Block::Heading1 { heading_1, .. } = &mut edited {
+            heading_1.rich_text.pop();
+        }
+        let mut new = snapshot(vec![leaf(edited), leaf(heading("c"))]);
+        new.page.properties.properties.remove("Description");
+
+        let diff = diff(&old, &new);
+        assert_eq!(diff.changed_properties, vec!["Description".to_string()]);
+        assert_eq!(diff.added_blocks, vec![BlockId::from_str("c").unwrap()]);
+        assert_eq!(diff.removed_blocks, vec![BlockId::from_str("b").unwrap()]);
+        assert_eq!(diff.edited_blocks, vec![BlockId::from_str("a").unwrap()]);
+    }
+}
@@ -1,10 +1,17 @@
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
+use std::time::Duration;
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd, Debug, Clone, Hash)]
 #[serde(transparent)]
 pub struct StatusCode(u16);
 
+impl From<u16> for StatusCode {
+    fn from(code: u16) -> Self {
+        StatusCode(code)
+    }
+}
+
 impl StatusCode {
     pub fn code(&self) -> u16 {
         self.0
@@ -26,6 +33,41 @@ pub struct ErrorResponse {
     pub status: StatusCode,
     pub code: ErrorCode,
     pub message: String,
+    /// Identifies the request to Notion support.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    /// A link to Notion's developer survey, included in some error responses.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub developer_survey: Option<String>,
+    /// Details about the error, such as the limits a request exceeded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub additional_data: Option<serde_json::Value>,
+    /// How long the `Retry-After` header of a `429` response asked to wait,
+    /// which [Retrying](crate::Retrying) honors. Not part of the body.
+    #[serde(skip)]
+    pub retry_after: Option<Duration>,
+}
+
+/// A response with an error status whose body isn't an [ErrorResponse],
+/// such as the HTML page of a proxy or gateway.
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct HttpErrorResponse {
+    pub status: StatusCode,
+    /// The `x-notion-request-id` or `x-request-id` header, if there was one.
+    pub request_id: Option<String>,
+    pub retry_after: Option<Duration>,
+    /// The start of the body, cut to [MAX_ERROR_BODY] bytes.
+    pub body: String,
+}
+
+/// How much of a response body errors keep.
+pub const MAX_ERROR_BODY: usize = 16 * 1024;
+
+impl HttpErrorResponse {
+    /// Timeouts, rate limits and server errors.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self.status.code(), 408 | 429 | 500 | 502 | 503 | 504)
+    }
 }
 
 /// <https://developers.notion.com/reference/errors>
@@ -48,6 +90,30 @@ pub enum ErrorCode {
     Unknown,
 }
 
+impl ErrorCode {
+    /// Whether sending the same request again later may succeed.
+    ///
+    /// Conflicts happen when a page or block is edited concurrently,
+    /// so they are retryable, with fresh data for partial updates.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            ErrorCode::RateLimited
+                | ErrorCode::ConflictError
+                | ErrorCode::InternalServerError
+                | ErrorCode::ServiceUnavailable
+        )
+    }
+}
+
+impl ErrorResponse {
+    /// See [ErrorCode::is_retryable()]. Gateway errors that
+    /// don't carry a known code are retryable as well.
+    pub fn is_retryable(&self) -> bool {
+        self.code.is_retryable() || matches!(self.status.code(), 429 | 502 | 503 | 504)
+    }
+}
+
 impl Display for ErrorCode {
     fn fmt(
         &self,
@@ -59,6 +125,8 @@ impl Display for ErrorCode {
 
 #[cfg(test)]
 mod tests {
+    #[cfg(feature = "client")]
+    use crate::models::error::StatusCode;
     use crate::models::error::{ErrorCode, ErrorResponse};
 
     #[test]
@@ -67,10 +135,73 @@ mod tests {
         assert_eq!(error.code, ErrorCode::ValidationError)
     }
 
+    #[test]
+    fn deserialize_error_details() {
+        let error: ErrorResponse = serde_json::from_value(serde_json::json!({
+            "object": "error",
+            "status": 400,
+            "code": "validation_error",
+            "message": "body failed validation: body.children.length should be ≤ `100`, instead was `101`.",
+            "request_id": "3c8bbab4-b8bb-4beb-9da5-a0d8b1a2ca6e",
+            "developer_survey": "https://notionup.typeform.com/to/bllBsoI4?utm_source=postman",
+            "additional_data": { "error_type": "children_limit" }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            error.request_id.as_deref(),
+            Some("3c8bbab4-b8bb-4beb-9da5-a0d8b1a2ca6e")
+        );
+        assert!(error.developer_survey.is_some());
+        assert_eq!(
+            error.additional_data,
+            Some(serde_json::json!({ "error_type": "children_limit" }))
+        );
+
+        let error: ErrorResponse = serde_json::from_str(include_str!("tests/error.json")).unwrap();
+        assert_eq!(error.request_id, None);
+        assert!(serde_json::to_value(&error)
+            .unwrap()
+            .get("request_id")
+            .is_none());
+    }
+
     #[test]
     fn deserialize_unknown_error() {
         let error: ErrorResponse =
             serde_json::from_str(include_str!("tests/unknown_error.json")).unwrap();
         assert_eq!(error.code, ErrorCode::Unknown)
     }
+
+    #[cfg(feature = "client")]
+    #[test]
+    fn classify_errors() {
+        let api_error = |status: u16, code| crate::Error::ApiError {
+            error: ErrorResponse {
+                status: StatusCode::from(status),
+                code,
+                message: String::new(),
+                request_id: None,
+                developer_survey: None,
+                additional_data: None,
+                retry_after: None,
+            },
+        };
+
+        let rate_limited = api_error(429, ErrorCode::RateLimited);
+        assert!(rate_limited.is_rate_limited());
+        assert!(rate_limited.is_retryable());
+        assert!(!rate_limited.is_conflict());
+
+        let conflict = api_error(409, ErrorCode::ConflictError);
+        assert!(conflict.is_conflict());
+        assert!(conflict.is_retryable());
+
+        assert!(api_error(502, ErrorCode::Unknown).is_retryable());
+        assert!(!api_error(400, ErrorCode::ValidationError).is_retryable());
+        assert_eq!(
+            api_error(404, ErrorCode::ObjectNotFound).api_error_code(),
+            Some(&ErrorCode::ObjectNotFound)
+        );
+    }
 }
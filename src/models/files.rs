@@ -0,0 +1,376 @@
+//! File and emoji objects, shared by blocks, page icons and file properties.
+//! See <https://developers.notion.com/reference/file-object>
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "blocks")]
+use crate::ids::BlockId;
+use crate::ids::{CustomEmojiId, FileUploadId, PageId};
+#[cfg(feature = "blocks")]
+use crate::models::block::Block;
+use crate::models::Page;
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct InternalFileObject {
+    pub url: String,
+    pub expiry_time: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct ExternalFileObject {
+    pub url: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[serde(tag = "type")]
+#[serde(rename_all = "snake_case")]
+pub enum FileOrEmojiObject {
+    Emoji {
+        emoji: Emoji,
+    },
+    /// An emoji uploaded to the workspace.
+    CustomEmoji {
+        custom_emoji: CustomEmoji,
+    },
+    File {
+        file: InternalFileObject,
+    },
+    External {
+        external: ExternalFileObject,
+    },
+}
+
+/// An emoji uploaded to a workspace, used in icons and mentioned in rich text.
+///
+/// Only the id is needed to set it as an icon.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct CustomEmoji {
+    pub id: CustomEmojiId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[serde(tag = "type")]
+#[serde(rename_all = "snake_case")]
+pub enum FileObject {
+    File { file: InternalFileObject },
+    External { external: ExternalFileObject },
+}
+
+/// Where a [HostedFile] is attached, to fetch a fresh URL from.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum FileSource {
+    /// An image, video, file or PDF block.
+    #[cfg(feature = "blocks")]
+    Block { block_id: BlockId },
+    /// The file named `name` in the files property `property` of a page.
+    Property {
+        page_id: PageId,
+        property: String,
+        name: String,
+    },
+}
+
+/// A file hosted by Notion and where it is attached.
+///
+/// The URLs of hosted files expire about an hour after they were fetched,
+/// long-running exports can check [expires_within()](Self::expires_within())
+/// and fetch a new one with `HostedFile::refresh()`.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct HostedFile {
+    pub file: InternalFileObject,
+    pub source: FileSource,
+}
+
+impl HostedFile {
+    /// The hosted file of an image, video, file or PDF block,
+    /// `None` for other blocks and external files.
+    #[cfg(feature = "blocks")]
+    pub fn from_block(block: &Block) -> Option<Self> {
+        match &block.file()?.file {
+            FileObject::File { file } => Some(HostedFile {
+                file: file.clone(),
+                source: FileSource::Block {
+                    block_id: block.id()?.clone(),
+                },
+            }),
+            FileObject::External { .. } => None,
+        }
+    }
+
+    /// The hosted files of the files property `property` of a page,
+    /// leaving out external files.
+    pub fn from_property(
+        page: &Page,
+        property: &str,
+    ) -> Vec<Self> {
+        let files = match page.properties.get_files(property) {
+            Ok(Some(files)) => files,
+            _ => return vec![],
+        };
+        files
+            .iter()
+            .filter_map(|reference| match &reference.file {
+                FileObject::File { file } => Some(HostedFile {
+                    file: file.clone(),
+                    source: FileSource::Property {
+                        page_id: page.id.clone(),
+                        property: property.to_string(),
+                        name: reference.name.clone(),
+                    },
+                }),
+                FileObject::External { .. } => None,
+            })
+            .collect()
+    }
+
+    pub fn url(&self) -> &str {
+        &self.file.url
+    }
+
+    /// Whether the URL expires within `margin` from now, or already expired.
+    pub fn expires_within(
+        &self,
+        margin: Duration,
+    ) -> bool {
+        Utc::now() + margin >= self.file.expiry_time
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_within(Duration::zero())
+    }
+}
+
+/// A file sent to Notion, which can be attached to pages and blocks
+/// until it expires.
+/// See <https://developers.notion.com/reference/file-upload>
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct FileUpload {
+    pub id: FileUploadId,
+    pub status: FileUploadStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_length: Option<u64>,
+    /// When the upload is deleted if it isn't attached to anything.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expiry_time: Option<DateTime<Utc>>,
+    pub created_time: DateTime<Utc>,
+    pub last_edited_time: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Copy, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum FileUploadStatus {
+    /// Created, waiting for the contents.
+    Pending,
+    /// The contents were received, ready to be attached.
+    Uploaded,
+    Expired,
+    Failed,
+}
+
+/// How the contents of a [FileUpload] are sent.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Copy, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum FileUploadMode {
+    /// In one request, for files up to 20 MB.
+    SinglePart,
+    /// In numbered parts, completed once all of them are sent.
+    MultiPart,
+}
+
+/// Request body for creating a [FileUpload].
+/// See <https://developers.notion.com/reference/create-a-file-upload>
+#[derive(Serialize, Debug, Eq, PartialEq, Clone)]
+pub struct FileUploadRequest {
+    pub mode: FileUploadMode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    /// Required for [FileUploadMode::MultiPart].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number_of_parts: Option<u32>,
+}
+
+/// A string that is not a single emoji.
+#[derive(Debug, Eq, PartialEq, Clone, thiserror::Error)]
+#[error("Invalid emoji {0:?}, expected a single emoji")]
+pub struct InvalidEmoji(pub String);
+
+/// A single emoji, such as the icon of a page or callout.
+///
+/// Notion rejects icons that are anything else, so constructing an emoji checks
+/// that the string is one emoji, including skin tone, keycap, flag and
+/// ZWJ sequences. Emoji returned by the API aren't checked, so emoji newer
+/// than this check still deserialize.
+///
+/// ```
+/// use notion_wasi::models::files::Emoji;
+///
+/// assert!("👩🏽‍💻".parse::<Emoji>().is_ok());
+/// assert!("🇯🇵".parse::<Emoji>().is_ok());
+/// assert!("💡 idea".parse::<Emoji>().is_err());
+/// ```
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Hash, Clone)]
+#[serde(transparent)]
+pub struct Emoji(String);
+
+impl Emoji {
+    pub fn new<S: Into<String>>(emoji: S) -> Result<Self, InvalidEmoji> {
+        let emoji = emoji.into();
+        if is_single_emoji(&emoji) {
+            Ok(Emoji(emoji))
+        } else {
+            Err(InvalidEmoji(emoji))
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for Emoji {
+    type Err = InvalidEmoji;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Emoji::new(s)
+    }
+}
+
+impl TryFrom<String> for Emoji {
+    type Error = InvalidEmoji;
+
+    fn try_from(emoji: String) -> Result<Self, Self::Error> {
+        Emoji::new(emoji)
+    }
+}
+
+impl AsRef<str> for Emoji {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Emoji {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+const VARIATION_SELECTOR: char = '\u{FE0F}';
+const ZERO_WIDTH_JOINER: char = '\u{200D}';
+const COMBINING_KEYCAP: char = '\u{20E3}';
+const CANCEL_TAG: char = '\u{E007F}';
+
+/// Whether `s` is one emoji, following the shape of the sequences in
+/// Unicode Technical Standard #51.
+fn is_single_emoji(s: &str) -> bool {
+    let chars: Vec<char> = s.chars().collect();
+    match chars.as_slice() {
+        [] => false,
+        // Flags are a pair of regional indicators.
+        [first, second] if is_regional_indicator(*first) || is_regional_indicator(*second) => {
+            is_regional_indicator(*first) && is_regional_indicator(*second)
+        }
+        [base, rest @ ..] if base.is_ascii_digit() || *base == '#' || *base == '*' => matches!(
+            rest,
+            [COMBINING_KEYCAP] | [VARIATION_SELECTOR, COMBINING_KEYCAP]
+        ),
+        _ => is_zwj_sequence(&chars),
+    }
+}
+
+/// Pictographs with optional modifiers, joined by zero width joiners.
+fn is_zwj_sequence(chars: &[char]) -> bool {
+    let mut chars = chars.iter().copied().peekable();
+    loop {
+        match chars.next() {
+            Some(c) if is_pictographic(c) => {}
+            _ => return false,
+        }
+        while let Some(&c) = chars.peek() {
+            if c == VARIATION_SELECTOR || is_skin_tone(c) {
+                chars.next();
+            } else if is_tag(c) {
+                // Subdivision flags such as England end with a cancel tag.
+                while chars.next_if(|&c| is_tag(c)).is_some() {}
+                if chars.next() != Some(CANCEL_TAG) {
+                    return false;
+                }
+            } else {
+                break;
+            }
+        }
+        match chars.next() {
+            None => return true,
+            Some(ZERO_WIDTH_JOINER) => {}
+            Some(_) => return false,
+        }
+    }
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    ('\u{1F1E6}'..='\u{1F1FF}').contains(&c)
+}
+
+fn is_skin_tone(c: char) -> bool {
+    ('\u{1F3FB}'..='\u{1F3FF}').contains(&c)
+}
+
+fn is_tag(c: char) -> bool {
+    ('\u{E0020}'..='\u{E007E}').contains(&c)
+}
+
+/// Characters that can be displayed as emoji, approximating the
+/// `Extended_Pictographic` property.
+fn is_pictographic(c: char) -> bool {
+    matches!(
+        c,
+        '\u{00A9}'
+            | '\u{00AE}'
+            | '\u{203C}'
+            | '\u{2049}'
+            | '\u{2122}'
+            | '\u{2139}'
+            | '\u{2194}'..='\u{2199}'
+            | '\u{21A9}'..='\u{21AA}'
+            | '\u{231A}'..='\u{231B}'
+            | '\u{2328}'
+            | '\u{23CF}'
+            | '\u{23E9}'..='\u{23F3}'
+            | '\u{23F8}'..='\u{23FA}'
+            | '\u{24C2}'
+            | '\u{25AA}'..='\u{25AB}'
+            | '\u{25B6}'
+            | '\u{25C0}'
+            | '\u{25FB}'..='\u{25FE}'
+            | '\u{2600}'..='\u{27BF}'
+            | '\u{2934}'..='\u{2935}'
+            | '\u{2B05}'..='\u{2B07}'
+            | '\u{2B1B}'..='\u{2B1C}'
+            | '\u{2B50}'
+            | '\u{2B55}'
+            | '\u{3030}'
+            | '\u{303D}'
+            | '\u{3297}'
+            | '\u{3299}'
+            | '\u{1F000}'..='\u{1FAFF}'
+    ) && !is_regional_indicator(c)
+        && !is_skin_tone(c)
+}
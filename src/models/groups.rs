@@ -0,0 +1,152 @@
+//! Pages grouped by the value of a property, like the columns of a board view.
+
+use std::collections::HashMap;
+
+use crate::models::columns::Cell;
+use crate::models::properties::PropertyConfiguration;
+use crate::models::{Database, Page};
+
+/// The pages with one value of the grouped property.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct PageGroup {
+    /// The option name, or the name of the person for people properties.
+    pub name: String,
+    pub pages: Vec<Page>,
+}
+
+/// The result of [group_pages()].
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct PageGroups {
+    /// The groups in the order of the options in the schema, including options
+    /// no page has, followed by values the schema doesn't know in the order
+    /// they first appear.
+    pub groups: Vec<PageGroup>,
+    /// Pages without a value.
+    pub ungrouped: Vec<Page>,
+}
+
+impl PageGroups {
+    /// The pages of the group `name`.
+    pub fn get(
+        &self,
+        name: &str,
+    ) -> Option<&[Page]> {
+        self.groups
+            .iter()
+            .find(|group| group.name == name)
+            .map(|group| group.pages.as_slice())
+    }
+
+    /// The groups by name, without the ungrouped pages.
+    pub fn into_map(self) -> HashMap<String, Vec<Page>> {
+        self.groups
+            .into_iter()
+            .map(|group| (group.name, group.pages))
+            .collect()
+    }
+}
+
+/// Groups `pages` by the value of the select, status, multi-select or people
+/// property `property`.
+///
+/// `schema` is the configuration of the property, whose options decide the
+/// order of the groups. Pages with several values, such as a multi-select,
+/// are in the group of each.
+pub fn group_pages<I: IntoIterator<Item = Page>>(
+    pages: I,
+    property: &str,
+    schema: Option<&PropertyConfiguration>,
+) -> PageGroups {
+    let options = match schema {
+        Some(PropertyConfiguration::Select { select, .. })
+        | Some(PropertyConfiguration::MultiSelect {
+            multi_select: select,
+            ..
+        }) => select.options.as_slice(),
+        Some(PropertyConfiguration::Status { status, .. }) => status.options.as_slice(),
+        _ => &[],
+    };
+    let mut groups = PageGroups {
+        groups: options
+            .iter()
+            .map(|option| PageGroup {
+                name: option.name.clone(),
+                pages: vec![],
+            })
+            .collect(),
+        ungrouped: vec![],
+    };
+    let mut positions: HashMap<String, usize> = groups
+        .groups
+        .iter()
+        .enumerate()
+        .map(|(position, group)| (group.name.clone(), position))
+        .collect();
+
+    for page in pages {
+        let names = match page.properties.properties.get(property).map(Cell::from) {
+            Some(Cell::Text(name)) if !name.is_empty() => vec![name],
+            Some(Cell::List(names)) => names,
+            _ => vec![],
+        };
+        if names.is_empty() {
+            groups.ungrouped.push(page);
+            continue;
+        }
+        for name in names {
+            let position = *positions.entry(name.clone()).or_insert_with(|| {
+                groups.groups.push(PageGroup {
+                    name,
+                    pages: vec![],
+                });
+                groups.groups.len() - 1
+            });
+            groups.groups[position].pages.push(page.clone());
+        }
+    }
+    groups
+}
+
+impl Database {
+    /// Groups `pages` of this database by `property`, see [group_pages()].
+    pub fn group_pages<I: IntoIterator<Item = Page>>(
+        &self,
+        pages: I,
+        property: &str,
+    ) -> PageGroups {
+        group_pages(pages, property, self.properties.get(property))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::group_pages;
+    use crate::test_util::{DatabaseFixture, PageFixture};
+
+    #[test]
+    fn groups_follow_the_schema() {
+        let database = DatabaseFixture::new()
+            .select("Stage", &["Todo", "Doing", "Done"])
+            .build();
+        let done = PageFixture::new().select("Stage", "Done").build();
+        let todo = PageFixture::new().select("Stage", "Todo").build();
+        let other = PageFixture::new().select("Stage", "Blocked").build();
+        let unset = PageFixture::new().build();
+
+        let groups = database.group_pages(vec![done.clone(), todo, other, unset.clone()], "Stage");
+        let names: Vec<_> = groups
+            .groups
+            .iter()
+            .map(|group| group.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Todo", "Doing", "Done", "Blocked"]);
+        assert_eq!(groups.get("Done"), Some(&[done][..]));
+        assert_eq!(groups.get("Doing"), Some(&[][..]));
+        assert_eq!(groups.ungrouped, vec![unset]);
+
+        let tagged = PageFixture::new().multi_select("Tags", &["a", "b"]).build();
+        let by_tag = group_pages(vec![tagged.clone()], "Tags", None).into_map();
+        assert_eq!(by_tag["a"], vec![tagged.clone()]);
+        assert_eq!(by_tag["b"], vec![tagged]);
+    }
+}
@@ -0,0 +1,194 @@
+//! Content hashes for detecting whether a block or page really changed.
+//!
+//! A sync tool can store the [ContentHash] of what it last saw and skip the
+//! work when the hash of the freshly fetched value is the same.
+//! Fields that change without an edit are left out of the hash:
+//! edit times and editors, the expiry time of hosted files and the signature
+//! in their URLs.
+//!
+//! Hashes are the same across processes and platforms, but may change between
+//! versions of this crate when the models change.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[cfg(feature = "blocks")]
+use crate::models::block::{Block, BlockTree};
+use crate::models::Properties;
+
+/// A 64-bit FNV-1a hash of the content of a value, see the [module docs](self).
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Hash, Copy, Clone)]
+#[serde(transparent)]
+pub struct ContentHash(pub u64);
+
+impl fmt::Display for ContentHash {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+/// Fields that change without the content being edited.
+const VOLATILE_FIELDS: &[&str] = &["last_edited_time", "last_edited_by", "expiry_time"];
+
+/// The hash of the JSON form of `value` without its volatile fields.
+pub fn content_hash<T: Serialize>(value: &T) -> ContentHash {
+    let value = serde_json::to_value(value).unwrap_or(Value::Null);
+    let mut hasher = Fnv::default();
+    hasher.value(&value);
+    ContentHash(hasher.0)
+}
+
+#[cfg(feature = "blocks")]
+impl Block {
+    /// The [ContentHash] of the block itself, without its children.
+    pub fn content_hash(&self) -> ContentHash {
+        content_hash(self)
+    }
+}
+
+#[cfg(feature = "blocks")]
+impl BlockTree {
+    /// The [ContentHash] of the block and all of its fetched children.
+    pub fn content_hash(&self) -> ContentHash {
+        content_hash(self)
+    }
+}
+
+impl Properties {
+    /// The [ContentHash] of the property values.
+    pub fn content_hash(&self) -> ContentHash {
+        content_hash(self)
+    }
+}
+
+struct Fnv(u64);
+
+impl Default for Fnv {
+    fn default() -> Self {
+        Fnv(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv {
+    fn bytes(
+        &mut self,
+        bytes: &[u8],
+    ) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    /// Prefixed with the length, so `["ab", "c"]` and `["a", "bc"]` differ.
+    fn str(
+        &mut self,
+        value: &str,
+    ) {
+        self.bytes(&(value.len() as u64).to_le_bytes());
+        self.bytes(value.as_bytes());
+    }
+
+    /// Each value is prefixed with its kind, and objects are written with
+    /// sorted keys whatever the order of the map.
+    fn value(
+        &mut self,
+        value: &Value,
+    ) {
+        match value {
+            Value::Null => self.bytes(b"n"),
+            Value::Bool(value) => self.bytes(if *value { b"t" } else { b"f" }),
+            Value::Number(number) => {
+                self.bytes(b"#");
+                self.str(&number.to_string());
+            }
+            Value::String(value) => {
+                self.bytes(b"s");
+                self.str(value);
+            }
+            Value::Array(values) => {
+                self.bytes(b"[");
+                self.bytes(&(values.len() as u64).to_le_bytes());
+                values.iter().for_each(|value| self.value(value));
+            }
+            Value::Object(fields) => {
+                let hosted = fields.contains_key("expiry_time");
+                let mut fields: Vec<_> = fields
+                    .iter()
+                    .filter(|(name, _)| !VOLATILE_FIELDS.contains(&name.as_str()))
+                    .collect();
+                fields.sort_by_key(|(name, _)| *name);
+                self.bytes(b"{");
+                self.bytes(&(fields.len() as u64).to_le_bytes());
+                for (name, value) in fields {
+                    self.str(name);
+                    match (hosted, name.as_str(), value) {
+                        // The query holds the signature, which changes with every fetch.
+                        (true, "url", Value::String(url)) => {
+                            self.bytes(b"s");
+                            self.str(url.split('?').next().unwrap_or_default());
+                        }
+                        _ => self.value(value),
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{content_hash, ContentHash};
+    use serde_json::json;
+
+    fn hosted_file(
+        url: &str,
+        expiry_time: &str,
+    ) -> serde_json::Value {
+        json!({
+            "type": "file",
+            "file": { "url": url, "expiry_time": expiry_time },
+            "last_edited_time": expiry_time,
+        })
+    }
+
+    #[test]
+    fn volatile_fields_are_ignored() {
+        let old = hosted_file(
+            "https://files.test/workspace/cat.png?X-Amz-Signature=old",
+            "2022-05-14T10:00:00.000Z",
+        );
+        let new = hosted_file(
+            "https://files.test/workspace/cat.png?X-Amz-Signature=new",
+            "2022-05-14T11:00:00.000Z",
+        );
+        assert_eq!(content_hash(&old), content_hash(&new));
+
+        let replaced = hosted_file(
+            "https://files.test/workspace/dog.png?X-Amz-Signature=new",
+            "2022-05-14T11:00:00.000Z",
+        );
+        assert_ne!(content_hash(&old), content_hash(&replaced));
+    }
+
+    #[test]
+    fn hashes_are_stable() {
+        let value = json!({ "b": [1, "two", null], "a": { "c": true } });
+        let reordered = json!({ "a": { "c": true }, "b": [1, "two", null] });
+        assert_eq!(content_hash(&value), content_hash(&reordered));
+        assert_ne!(
+            content_hash(&json!(["ab", "c"])),
+            content_hash(&json!(["a", "bc"]))
+        );
+        assert_eq!(
+            content_hash(&json!(null)),
+            ContentHash(0xaf63_e34c_8601_f871)
+        );
+        assert_eq!(ContentHash(255).to_string(), "00000000000000ff");
+    }
+}
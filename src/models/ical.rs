@@ -0,0 +1,219 @@
+//! Pages as an iCalendar feed, one event per page with a date.
+//!
+//! The event spans the value of [IcalOptions::date_property], which can be a date,
+//! a date formula or rollup, or the created or last edited time. Date-only values
+//! become all-day events, and pages without a date are left out.
+//! Each event links back to its page and uses the page id as its UID, so calendar
+//! apps update events instead of duplicating them when the feed is fetched again.
+//!
+//! ```
+//! use notion_wasi::models::ical::{self, IcalOptions};
+//!
+//! let feed = ical::to_ical(&[], &IcalOptions::new("Publish date").calendar_name("Content"));
+//! assert!(feed.starts_with("BEGIN:VCALENDAR\r\n"));
+//! assert!(feed.contains("X-WR-CALNAME:Content\r\n"));
+//! ```
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+
+use crate::models::properties::{
+    DateOrDateTime, DateValue, FormulaResultValue, PropertyValue, RollupValue,
+};
+use crate::models::{text, Page};
+
+/// Lines longer than this many bytes are folded, as required by RFC 5545.
+const MAX_LINE_LENGTH: usize = 75;
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct IcalOptions {
+    /// The property holding the date of each event.
+    pub date_property: String,
+    /// The property used as the event summary, the page title if `None`.
+    pub title_property: Option<String>,
+    /// Shown by calendar apps as the name of the subscribed calendar.
+    pub calendar_name: Option<String>,
+}
+
+impl IcalOptions {
+    pub fn new<S: Into<String>>(date_property: S) -> Self {
+        IcalOptions {
+            date_property: date_property.into(),
+            title_property: None,
+            calendar_name: None,
+        }
+    }
+
+    pub fn title_property<S: Into<String>>(
+        mut self,
+        title_property: S,
+    ) -> Self {
+        self.title_property = Some(title_property.into());
+        self
+    }
+
+    pub fn calendar_name<S: Into<String>>(
+        mut self,
+        calendar_name: S,
+    ) -> Self {
+        self.calendar_name = Some(calendar_name.into());
+        self
+    }
+}
+
+/// Writes `pages` as an iCalendar feed, see the [module docs](self).
+pub fn to_ical(
+    pages: &[Page],
+    options: &IcalOptions,
+) -> String {
+    let mut feed = String::new();
+    line(&mut feed, "BEGIN:VCALENDAR");
+    line(&mut feed, "VERSION:2.0");
+    line(&mut feed, "PRODID:-//notion-wasi//EN");
+    line(&mut feed, "CALSCALE:GREGORIAN");
+    if let Some(name) = &options.calendar_name {
+        line(&mut feed, &format!("X-WR-CALNAME:{}", escape(name)));
+    }
+
+    for page in pages {
+        let date = match page
+            .properties
+            .properties
+            .get(&options.date_property)
+            .and_then(date_value)
+        {
+            Some(date) => date,
+            None => continue,
+        };
+        let summary = match &options.title_property {
+            Some(name) => page.properties.properties.get(name).and_then(property_text),
+            None => page.title(),
+        };
+
+        line(&mut feed, "BEGIN:VEVENT");
+        line(&mut feed, &format!("UID:{}@notion.so", page.id));
+        line(
+            &mut feed,
+            &format!("DTSTAMP:{}", timestamp(&page.last_edited_time)),
+        );
+        line(
+            &mut feed,
+            &format!("LAST-MODIFIED:{}", timestamp(&page.last_edited_time)),
+        );
+        match (&date.start, &date.end) {
+            (DateOrDateTime::Date(start), end) => {
+                // The end of all-day events is exclusive.
+                let end = end.as_ref().map_or(*start, DateOrDateTime::date_naive);
+                line(&mut feed, &format!("DTSTART;VALUE=DATE:{}", day(start)));
+                line(
+                    &mut feed,
+                    &format!("DTEND;VALUE=DATE:{}", day(&(end + Duration::days(1)))),
+                );
+            }
+            (DateOrDateTime::DateTime(start), end) => {
+                line(&mut feed, &format!("DTSTART:{}", timestamp(start)));
+                if let Some(end) = end {
+                    line(
+                        &mut feed,
+                        &format!("DTEND:{}", timestamp(&end.to_datetime())),
+                    );
+                }
+            }
+        }
+        line(
+            &mut feed,
+            &format!("SUMMARY:{}", escape(&summary.unwrap_or_default())),
+        );
+        if !page.url.is_empty() {
+            line(&mut feed, &format!("URL:{}", page.url));
+        }
+        line(&mut feed, "END:VEVENT");
+    }
+
+    line(&mut feed, "END:VCALENDAR");
+    feed
+}
+
+fn date_value(value: &PropertyValue) -> Option<DateValue> {
+    match value {
+        PropertyValue::Date { date, .. }
+        | PropertyValue::Formula {
+            formula: FormulaResultValue::Date { date },
+            ..
+        }
+        | PropertyValue::Rollup {
+            rollup: Some(RollupValue::Date { date }),
+            ..
+        } => date.clone(),
+        PropertyValue::CreatedTime { created_time, .. } => Some(moment(*created_time)),
+        PropertyValue::LastEditedTime {
+            last_edited_time, ..
+        } => Some(moment(*last_edited_time)),
+        _ => None,
+    }
+}
+
+fn moment(datetime: DateTime<Utc>) -> DateValue {
+    DateValue {
+        start: datetime.into(),
+        end: None,
+        time_zone: None,
+    }
+}
+
+fn property_text(value: &PropertyValue) -> Option<String> {
+    match value {
+        PropertyValue::Title { title, .. } => Some(text::plain_text(title).into_owned()),
+        PropertyValue::Text { rich_text, .. } => Some(text::plain_text(rich_text).into_owned()),
+        PropertyValue::Select { select, .. } | PropertyValue::Status { status: select, .. } => {
+            select.as_ref().and_then(|option| option.name.clone())
+        }
+        PropertyValue::Formula {
+            formula: FormulaResultValue::String { string },
+            ..
+        } => string.clone(),
+        _ => None,
+    }
+}
+
+fn day(date: &NaiveDate) -> String {
+    date.format("%Y%m%d").to_string()
+}
+
+fn timestamp(datetime: &DateTime<Utc>) -> String {
+    datetime.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escapes a TEXT value.
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' | ';' | ',' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Appends a content line, folded so no line is longer than [MAX_LINE_LENGTH] bytes.
+fn line(
+    feed: &mut String,
+    content: &str,
+) {
+    let mut length = 0;
+    for c in content.chars() {
+        if length + c.len_utf8() > MAX_LINE_LENGTH {
+            feed.push_str("\r\n ");
+            // The leading space counts towards the folded line.
+            length = 1;
+        }
+        feed.push(c);
+        length += c.len_utf8();
+    }
+    feed.push_str("\r\n");
+}
@@ -0,0 +1,221 @@
+//! Parsing list responses that contain objects the models can't represent.
+//!
+//! A single malformed or unexpected item makes the strict [Object] parser reject
+//! a whole list. [parse_object()] falls back to parsing the items of a list one
+//! by one, leaving out the ones that fail and reporting them as [SkippedItem]s,
+//! so large queries and searches still return everything that could be read.
+//!
+//! ```
+//! use notion_wasi::models::{lenient, Object};
+//!
+//! let body = br#"{
+//!     "object": "list",
+//!     "results": [{ "object": "page", "id": null }],
+//!     "next_cursor": null,
+//!     "has_more": false
+//! }"#;
+//! let parsed = lenient::parse_object(body).unwrap();
+//! assert!(matches!(parsed.value, Object::List { list } if list.results.is_empty()));
+//! assert_eq!(parsed.skipped[0].index, 0);
+//! ```
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+use crate::models::paging::PagingCursor;
+use crate::models::{ListResponse, Object};
+
+/// A value parsed with [parse_object()] or [parse_list()], and the list items left out.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Lenient<T> {
+    pub value: T,
+    pub skipped: Vec<SkippedItem>,
+}
+
+/// An item of a list response that could not be parsed.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct SkippedItem {
+    /// The position of the item in `results`.
+    pub index: usize,
+    /// The `object` field of the item, such as `page`, if it has one.
+    pub object: Option<String>,
+    /// The `id` field of the item, if it has one.
+    pub id: Option<String>,
+    /// Why the item could not be parsed.
+    pub error: String,
+}
+
+/// Parses a response body, leaving out the items of a list that fail to parse.
+///
+/// Bodies that aren't lists fail as they would with the strict parser.
+pub fn parse_object(body: &[u8]) -> serde_json::Result<Lenient<Object>> {
+    let error = match serde_json::from_slice(body) {
+        Ok(value) => {
+            return Ok(Lenient {
+                value,
+                skipped: vec![],
+            })
+        }
+        Err(error) => error,
+    };
+
+    let value: Value = serde_json::from_slice(body)?;
+    if value.get("object").and_then(Value::as_str) != Some("list") {
+        return Err(error);
+    }
+    let Lenient {
+        value: list,
+        skipped,
+    } = list_from_value(value)?;
+    Ok(Lenient {
+        value: Object::List { list },
+        skipped,
+    })
+}
+
+/// Parses a list response of `T`, leaving out the items that fail to parse.
+pub fn parse_list<T: DeserializeOwned>(
+    body: &[u8]
+) -> serde_json::Result<Lenient<ListResponse<T>>> {
+    list_from_value(serde_json::from_slice(body)?)
+}
+
+fn list_from_value<T: DeserializeOwned>(
+    value: Value
+) -> serde_json::Result<Lenient<ListResponse<T>>> {
+    #[derive(Deserialize)]
+    struct RawList {
+        #[serde(default, deserialize_with = "null_as_default")]
+        results: Vec<Value>,
+        next_cursor: Option<PagingCursor>,
+        #[serde(default, deserialize_with = "null_as_default")]
+        has_more: bool,
+    }
+
+    let raw: RawList = serde_json::from_value(value)?;
+    let mut results = Vec::with_capacity(raw.results.len());
+    let mut skipped = vec![];
+    for (index, item) in raw.results.into_iter().enumerate() {
+        let field = |name| item.get(name).and_then(Value::as_str).map(str::to_string);
+        let (object, id) = (field("object"), field("id"));
+        match serde_json::from_value(item) {
+            Ok(result) => results.push(result),
+            Err(error) => skipped.push(SkippedItem {
+                index,
+                object,
+                id,
+                error: error.to_string(),
+            }),
+        }
+    }
+
+    Ok(Lenient {
+        value: ListResponse {
+            results,
+            next_cursor: raw.next_cursor,
+            has_more: raw.has_more,
+        },
+        skipped,
+    })
+}
+
+/// Reads `null` as the default value, for fields Notion sometimes sends as `null`.
+pub(crate) fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Default,
+{
+    Option::<T>::deserialize(deserializer).map(Option::unwrap_or_default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_list, parse_object};
+    use crate::models::{Object, Page};
+    use serde_json::{json, Value};
+
+    fn leaves(
+        value: &Value,
+        path: String,
+        paths: &mut Vec<String>,
+    ) {
+        match value {
+            Value::Object(fields) => {
+                for (name, field) in fields {
+                    leaves(field, format!("{}/{}", path, name), paths)
+                }
+            }
+            Value::Array(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    leaves(item, format!("{}/{}", path, index), paths)
+                }
+            }
+            _ => paths.push(path),
+        }
+    }
+
+    /// Replaces every value of the fixtures in turn with hostile values,
+    /// making sure the parsers fail with errors instead of panicking.
+    #[test]
+    fn mutated_fixtures_do_not_panic() {
+        let fixtures = [
+            include_str!("tests/page.json"),
+            include_str!("tests/query_result.json"),
+            include_str!("tests/search_results.json"),
+            include_str!("tests/property_item_rollup.json"),
+            include_str!("block/tests/callout.json"),
+            include_str!("block/tests/file_object.json"),
+            include_str!("tests/golden/database.json"),
+        ];
+        let replacements = [Value::Null, json!({}), json!(""), json!(-1), json!(1e300)];
+        for fixture in fixtures.iter() {
+            let original: Value = serde_json::from_str(fixture).unwrap();
+            let mut paths = vec![];
+            leaves(&original, String::new(), &mut paths);
+            for path in paths {
+                for replacement in replacements.iter() {
+                    let mut value = original.clone();
+                    *value.pointer_mut(&path).unwrap() = replacement.clone();
+                    let _ = parse_object(value.to_string().as_bytes());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn tolerates_nulls() {
+        let mut page: Value = serde_json::from_str(include_str!("tests/page.json")).unwrap();
+        page["archived"] = Value::Null;
+        page["url"] = Value::Null;
+        let page: Page = serde_json::from_value(page).unwrap();
+        assert!(!page.archived);
+        assert_eq!(page.url, "");
+    }
+
+    #[test]
+    fn skips_items_that_fail_to_parse() {
+        let mut list: Value =
+            serde_json::from_str(include_str!("tests/query_result.json")).unwrap();
+        let page = list["results"][0].clone();
+        let mut broken = page.clone();
+        broken["created_time"] = json!("yesterday");
+        list["results"] = json!([page, broken]);
+        list["has_more"] = Value::Null;
+        let body = list.to_string();
+
+        assert!(serde_json::from_str::<Object>(&body).is_err());
+        let parsed = parse_list::<Page>(body.as_bytes()).unwrap();
+        assert_eq!(parsed.value.results.len(), 1);
+        assert!(!parsed.value.has_more);
+        assert_eq!(parsed.skipped.len(), 1);
+        assert_eq!(parsed.skipped[0].index, 1);
+        assert_eq!(parsed.skipped[0].object.as_deref(), Some("page"));
+        assert_eq!(
+            parsed.skipped[0].id,
+            page["id"].as_str().map(str::to_string)
+        );
+        assert!(parse_object(body.as_bytes()).unwrap().skipped.len() == 1);
+        assert!(parse_object(br#"{"object": "page"}"#).is_err());
+    }
+}
@@ -1,36 +1,70 @@
+pub mod aggregate;
+#[cfg(feature = "blocks")]
 pub mod block;
+pub mod columns;
+#[cfg(feature = "comments")]
+pub mod comments;
+pub mod csv;
+#[cfg(feature = "blocks")]
+pub mod diff;
 pub mod error;
+pub mod files;
+#[cfg(feature = "databases")]
+pub mod groups;
+pub mod hash;
+pub mod ical;
+pub mod lenient;
+pub mod oauth;
 pub mod paging;
 pub mod properties;
+pub mod rows;
+#[cfg(feature = "databases")]
+pub mod schema;
 pub mod search;
 #[cfg(test)]
 mod tests;
 pub mod text;
 pub mod users;
+mod validation;
+mod values;
+pub mod webhooks;
 
-use crate::models::properties::{PropertyConfiguration, PropertyValue};
+use crate::ids::{PropertyId, UserId};
+#[cfg(feature = "databases")]
+use crate::models::properties::definitions::{DatabaseSchema, PropertyDefinition};
+#[cfg(feature = "databases")]
+use crate::models::properties::PropertyConfiguration;
+use crate::models::properties::{
+    Color, DateValue, PropertyItem, PropertyValue, RelationValue, SelectedValue,
+};
 use crate::models::text::RichText;
+#[cfg(feature = "databases")]
+use crate::ApiVersion;
+#[cfg(feature = "client")]
 use crate::Error;
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "databases")]
+use std::collections::BTreeMap;
 use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
 
-use crate::ids::{AsIdentifier, DatabaseId, PageId};
+use crate::ids::{AsIdentifier, BlockId, DataSourceId, DatabaseId, PageId};
+#[cfg(feature = "blocks")]
 use crate::models::block::{Block, CreateBlock};
+#[cfg(feature = "comments")]
+use crate::models::comments::Comment;
 use crate::models::error::ErrorResponse;
-use crate::models::paging::PagingCursor;
-use crate::models::users::User;
+use crate::models::files::{FileObject, FileOrEmojiObject, FileUpload};
+use crate::models::paging::{Cursor, PagingCursor};
+use crate::models::users::{User, UserCommon};
 pub use chrono::{DateTime, Utc};
 pub use serde_json::value::Number;
-
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Copy, Clone)]
-#[serde(rename_all = "snake_case")]
-enum ObjectType {
-    Database,
-    List,
-}
+pub use validation::InvalidPage;
 
 /// Represents a Notion Database
 /// See <https://developers.notion.com/reference/database>
+#[cfg(feature = "databases")]
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
 pub struct Database {
     /// Unique identifier for the database.
@@ -41,6 +75,16 @@ pub struct Database {
     pub last_edited_time: DateTime<Utc>,
     /// Name of the database as it appears in Notion.
     pub title: Vec<RichText>,
+    /// Description of the database as it appears in Notion.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub description: Vec<RichText>,
+    /// The page or workspace the database is in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<Parent>,
+    /// Whether the database is displayed inline in its parent page.
+    /// Only reported by API versions from 2022-06-28 on.
+    #[serde(default, deserialize_with = "lenient::null_as_default")]
+    pub is_inline: bool,
     /// Schema of properties for the database as they appear in Notion.
     //
     // key string
@@ -48,21 +92,166 @@ pub struct Database {
     //
     // value object
     // A Property object.
+    //
+    // Empty from API version 2025-09-03 on, where the data sources hold the properties.
+    #[serde(default)]
+    pub properties: HashMap<String, PropertyConfiguration>,
+    /// The data sources of the database.
+    /// Only reported by API versions from 2025-09-03 on.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub data_sources: Vec<DataSourceReference>,
+    /// The URL of the database in Notion.
+    #[serde(default, deserialize_with = "lenient::null_as_default")]
+    pub url: String,
+    /// The archived status of the database.
+    #[serde(default, deserialize_with = "lenient::null_as_default")]
+    pub archived: bool,
+    /// Whether the database is in the trash.
+    #[serde(default, deserialize_with = "lenient::null_as_default")]
+    pub in_trash: bool,
+}
+
+/// A data source listed in [Database::data_sources].
+#[cfg(feature = "databases")]
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct DataSourceReference {
+    pub id: DataSourceId,
+    pub name: String,
+}
+
+/// A table of pages in a database, with its own properties.
+/// Databases hold one or more data sources from API version 2025-09-03 on.
+/// See <https://developers.notion.com/reference/data-source>
+#[cfg(feature = "databases")]
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct DataSource {
+    pub id: DataSourceId,
+    pub created_time: DateTime<Utc>,
+    pub last_edited_time: DateTime<Utc>,
+    /// Name of the data source as it appears in Notion.
+    pub title: Vec<RichText>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub description: Vec<RichText>,
+    /// Schema of the pages in the data source, by property name.
     pub properties: HashMap<String, PropertyConfiguration>,
+    /// The database the data source belongs to.
+    pub parent: Parent,
+    #[serde(default, deserialize_with = "lenient::null_as_default")]
+    pub archived: bool,
+    #[serde(default, deserialize_with = "lenient::null_as_default")]
+    pub in_trash: bool,
+}
+
+#[cfg(feature = "databases")]
+impl DataSource {
+    pub fn title_plain_text(&self) -> String {
+        text::plain_text(&self.title).into_owned()
+    }
+
+    /// See [Database::title_property()].
+    pub fn title_property(&self) -> Option<&str> {
+        title_property(&self.properties)
+    }
+
+    /// See [Database::property_id()].
+    pub fn property_id(
+        &self,
+        name: &str,
+    ) -> Option<&PropertyId> {
+        self.properties.get(name).map(PropertyConfiguration::id)
+    }
+}
+
+#[cfg(feature = "databases")]
+impl Trashable for DataSource {
+    fn is_trashed(&self) -> bool {
+        self.archived || self.in_trash
+    }
+}
+
+#[cfg(feature = "databases")]
+impl AsIdentifier<DataSourceId> for DataSource {
+    fn as_id(&self) -> &DataSourceId {
+        &self.id
+    }
+}
+
+#[cfg(feature = "databases")]
+fn title_property(properties: &HashMap<String, PropertyConfiguration>) -> Option<&str> {
+    properties
+        .iter()
+        .find(|(_, property)| matches!(property, PropertyConfiguration::Title { .. }))
+        .map(|(name, _)| name.as_str())
+}
+
+/// Displays the plain text title.
+#[cfg(feature = "databases")]
+impl fmt::Display for Database {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        text::PlainText(&self.title).fmt(f)
+    }
 }
 
+/// Displays the plain text title.
+#[cfg(feature = "databases")]
+impl fmt::Display for DataSource {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        text::PlainText(&self.title).fmt(f)
+    }
+}
+
+#[cfg(feature = "databases")]
+impl Trashable for Database {
+    fn is_trashed(&self) -> bool {
+        self.archived || self.in_trash
+    }
+}
+
+#[cfg(feature = "databases")]
 impl AsIdentifier<DatabaseId> for Database {
     fn as_id(&self) -> &DatabaseId {
         &self.id
     }
 }
 
+#[cfg(feature = "databases")]
 impl Database {
     pub fn title_plain_text(&self) -> String {
-        self.title
+        text::plain_text(&self.title).into_owned()
+    }
+
+    /// The name of the title property in the schema.
+    ///
+    /// `None` from API version 2025-09-03 on, where the data sources hold the properties.
+    pub fn title_property(&self) -> Option<&str> {
+        title_property(&self.properties)
+    }
+
+    /// The id of the property called `name`, for id-addressed operations
+    /// such as [get_property_items()](crate::NotionApi::get_property_items())
+    /// or [DatabaseQuery::filter_properties](crate::models::search::DatabaseQuery::filter_properties).
+    pub fn property_id(
+        &self,
+        name: &str,
+    ) -> Option<&PropertyId> {
+        self.properties.get(name).map(PropertyConfiguration::id)
+    }
+
+    /// The name of the property with the id `id`.
+    pub fn property_name(
+        &self,
+        id: &PropertyId,
+    ) -> Option<&str> {
+        self.properties
             .iter()
-            .flat_map(|rich_text| rich_text.plain_text().chars())
-            .collect()
+            .find(|(_, property)| property.id() == id)
+            .map(|(name, _)| name.as_str())
     }
 }
 
@@ -71,6 +260,7 @@ impl Database {
 pub struct ListResponse<T> {
     pub results: Vec<T>,
     pub next_cursor: Option<PagingCursor>,
+    #[serde(default, deserialize_with = "lenient::null_as_default")]
     pub has_more: bool,
 }
 
@@ -78,9 +268,165 @@ impl<T> ListResponse<T> {
     pub fn results(&self) -> &[T] {
         &self.results
     }
+
+    /// Where the next request should continue.
+    pub fn cursor(&self) -> Cursor {
+        Cursor::after(self.next_cursor.clone(), self.has_more)
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.results.iter()
+    }
+
+    /// The number of results in this response, not across all pages.
+    pub fn len(&self) -> usize {
+        self.results.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.results.is_empty()
+    }
+
+    /// Converts each result, keeping the cursor.
+    pub fn map<U, F: FnMut(T) -> U>(
+        self,
+        f: F,
+    ) -> ListResponse<U> {
+        ListResponse {
+            results: self.results.into_iter().map(f).collect(),
+            next_cursor: self.next_cursor,
+            has_more: self.has_more,
+        }
+    }
+
+    /// Converts the results `f` returns `Some` for and drops the others, keeping the cursor.
+    pub fn filter_map<U, F: FnMut(T) -> Option<U>>(
+        self,
+        f: F,
+    ) -> ListResponse<U> {
+        ListResponse {
+            results: self.results.into_iter().filter_map(f).collect(),
+            next_cursor: self.next_cursor,
+            has_more: self.has_more,
+        }
+    }
+}
+
+impl<T> IntoIterator for ListResponse<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.results.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a ListResponse<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.results.iter()
+    }
+}
+
+/// The results of a [ListResponse<Object>] by kind, see
+/// [partition_by_object()](ListResponse::partition_by_object()).
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct ObjectsByKind {
+    pub pages: Vec<Page>,
+    #[cfg(feature = "databases")]
+    pub databases: Vec<Database>,
+    #[cfg(feature = "databases")]
+    pub data_sources: Vec<DataSource>,
+    #[cfg(feature = "blocks")]
+    pub blocks: Vec<Block>,
+    pub users: Vec<User>,
+    #[cfg(feature = "comments")]
+    pub comments: Vec<Comment>,
+    /// Lists, property items and errors.
+    pub other: Vec<Object>,
+}
+
+/// Objects that can be archived or moved to the trash.
+pub trait Trashable {
+    /// Whether the object is archived or in the trash.
+    fn is_trashed(&self) -> bool;
+}
+
+/// Which objects [ListResponse::filter_trashed()] keeps.
+#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone, Default)]
+pub enum TrashFilter {
+    /// Leave out archived and trashed objects.
+    #[default]
+    Exclude,
+    /// Keep every object.
+    Include,
+    /// Keep only archived and trashed objects.
+    Only,
+}
+
+impl<T: Trashable> ListResponse<T> {
+    /// Filters the results by their trash status.
+    ///
+    /// This only looks at the objects the API returned,
+    /// pagination and `has_more` are unchanged.
+    pub fn filter_trashed(
+        mut self,
+        filter: TrashFilter,
+    ) -> Self {
+        match filter {
+            TrashFilter::Exclude => self.results.retain(|object| !object.is_trashed()),
+            TrashFilter::Include => {}
+            TrashFilter::Only => self.results.retain(Trashable::is_trashed),
+        }
+        self
+    }
 }
 
 impl ListResponse<Object> {
+    /// The pages among the results.
+    pub fn pages(&self) -> impl Iterator<Item = &Page> {
+        self.results.iter().filter_map(|object| match object {
+            Object::Page { page } => Some(page),
+            _ => None,
+        })
+    }
+
+    /// Converts the pages among the results with `f`, dropping other objects
+    /// and keeping the cursor.
+    pub fn map_pages<U, F: FnMut(Page) -> U>(
+        self,
+        mut f: F,
+    ) -> ListResponse<U> {
+        self.filter_map(|object| match object {
+            Object::Page { page } => Some(f(page)),
+            _ => None,
+        })
+    }
+
+    /// Sorts the results by their kind, keeping their order within each kind.
+    pub fn partition_by_object(self) -> ObjectsByKind {
+        let mut objects = ObjectsByKind::default();
+        for object in self.results {
+            match object {
+                Object::Page { page } => objects.pages.push(page),
+                #[cfg(feature = "databases")]
+                Object::Database { database } => objects.databases.push(database),
+                #[cfg(feature = "databases")]
+                Object::DataSource { data_source } => objects.data_sources.push(data_source),
+                #[cfg(feature = "blocks")]
+                Object::Block { block } => objects.blocks.push(block),
+                Object::User { user } => objects.users.push(user),
+                #[cfg(feature = "comments")]
+                Object::Comment { comment } => objects.comments.push(comment),
+                object => objects.other.push(object),
+            }
+        }
+        objects
+    }
+
+    #[cfg(feature = "databases")]
     pub fn only_databases(self) -> ListResponse<Database> {
         let databases = self
             .results
@@ -98,6 +444,8 @@ impl ListResponse<Object> {
         }
     }
 
+    #[cfg(all(feature = "client", feature = "databases"))]
+    #[allow(clippy::result_large_err)]
     pub(crate) fn expect_databases(self) -> Result<ListResponse<Database>, crate::Error> {
         let databases: Result<Vec<_>, _> = self
             .results
@@ -115,6 +463,8 @@ impl ListResponse<Object> {
         })
     }
 
+    #[cfg(feature = "client")]
+    #[allow(clippy::result_large_err)]
     pub(crate) fn expect_pages(self) -> Result<ListResponse<Page>, crate::Error> {
         let items: Result<Vec<_>, _> = self
             .results
@@ -132,6 +482,81 @@ impl ListResponse<Object> {
         })
     }
 
+    #[cfg(feature = "client")]
+    #[allow(clippy::result_large_err)]
+    pub(crate) fn expect_search_results(self) -> Result<ListResponse<SearchResult>, crate::Error> {
+        let results: Result<Vec<_>, _> = self
+            .results
+            .into_iter()
+            .map(std::convert::TryInto::try_into)
+            .collect();
+
+        Ok(ListResponse {
+            results: results?,
+            has_more: self.has_more,
+            next_cursor: self.next_cursor,
+        })
+    }
+
+    #[cfg(feature = "client")]
+    #[allow(clippy::result_large_err)]
+    pub(crate) fn expect_users(self) -> Result<ListResponse<User>, crate::Error> {
+        let items: Result<Vec<_>, _> = self
+            .results
+            .into_iter()
+            .map(|object| match object {
+                Object::User { user } => Ok(user),
+                response => Err(Error::UnexpectedResponse { response }),
+            })
+            .collect();
+
+        Ok(ListResponse {
+            results: items?,
+            has_more: self.has_more,
+            next_cursor: self.next_cursor,
+        })
+    }
+
+    #[cfg(all(feature = "client", feature = "comments"))]
+    #[allow(clippy::result_large_err)]
+    pub(crate) fn expect_comments(self) -> Result<ListResponse<Comment>, crate::Error> {
+        let items: Result<Vec<_>, _> = self
+            .results
+            .into_iter()
+            .map(|object| match object {
+                Object::Comment { comment } => Ok(comment),
+                response => Err(Error::UnexpectedResponse { response }),
+            })
+            .collect();
+
+        Ok(ListResponse {
+            results: items?,
+            has_more: self.has_more,
+            next_cursor: self.next_cursor,
+        })
+    }
+
+    #[cfg(feature = "client")]
+    #[allow(clippy::result_large_err)]
+    pub(crate) fn expect_property_items(self) -> Result<ListResponse<PropertyItem>, crate::Error> {
+        let items: Result<Vec<_>, _> = self
+            .results
+            .into_iter()
+            .map(|object| match object {
+                Object::PropertyItem { property_item } => Ok(property_item),
+                response => Err(Error::UnexpectedResponse { response }),
+            })
+            .collect();
+
+        Ok(ListResponse {
+            results: items?,
+            has_more: self.has_more,
+            next_cursor: self.next_cursor,
+        })
+    }
+
+    #[cfg(all(feature = "client", feature = "blocks"))]
+    #[allow(clippy::result_large_err)]
     pub(crate) fn expect_blocks(self) -> Result<ListResponse<Block>, crate::Error> {
         let items: Result<Vec<_>, _> = self
             .results
@@ -162,34 +587,584 @@ pub enum Parent {
     Page {
         page_id: PageId,
     },
+    /// The parent of pages in a data source, from API version 2025-09-03 on.
+    #[serde(rename = "data_source_id")]
+    DataSource {
+        data_source_id: DataSourceId,
+        /// The database the data source belongs to.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        database_id: Option<DatabaseId>,
+    },
+    /// The parent of blocks nested in other blocks, e.g. list items or columns.
+    #[serde(rename = "block_id")]
+    Block {
+        block_id: BlockId,
+    },
     Workspace,
 }
 
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Default)]
 pub struct Properties {
     #[serde(flatten)]
     pub properties: HashMap<String, PropertyValue>,
 }
 
 impl Properties {
+    /// The plain text of the title property, whatever it is called.
     pub fn title(&self) -> Option<String> {
         self.properties.values().find_map(|p| match p {
-            PropertyValue::Title { title, .. } => {
-                Some(title.iter().map(|t| t.plain_text()).collect())
-            }
+            PropertyValue::Title { title, .. } => Some(text::plain_text(title).into_owned()),
             _ => None,
         })
     }
+
+    /// The name of the title property, `"Name"` unless it was renamed.
+    pub fn title_property(&self) -> Option<&str> {
+        self.properties
+            .iter()
+            .find(|(_, value)| matches!(value, PropertyValue::Title { .. }))
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// The value of the checkbox property `name`,
+    /// or `None` if there is no such checkbox property.
+    pub fn checkbox(
+        &self,
+        name: &str,
+    ) -> Option<bool> {
+        match self.properties.get(name)? {
+            PropertyValue::Checkbox { checkbox, .. } => Some(*checkbox),
+            _ => None,
+        }
+    }
+
+    /// The selected options of the multi-select property `name`,
+    /// or `None` if there is no such multi-select property.
+    pub fn multi_select(
+        &self,
+        name: &str,
+    ) -> Option<&[SelectedValue]> {
+        match self.properties.get(name)? {
+            PropertyValue::MultiSelect { multi_select, .. } => {
+                Some(multi_select.as_deref().unwrap_or_default())
+            }
+            _ => None,
+        }
+    }
 }
 
-#[derive(Serialize, Debug, Eq, PartialEq)]
+#[derive(Serialize, Debug, Eq, PartialEq, Clone)]
 pub struct PageCreateRequest {
     pub parent: Parent,
     pub properties: Properties,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<FileOrEmojiObject>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cover: Option<FileObject>,
+    /// The initial page content, created in the same request.
+    /// The API accepts up to 100 blocks here.
+    #[cfg(feature = "blocks")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub children: Option<Vec<CreateBlock>>,
 }
 
+impl PageCreateRequest {
+    /// A request for an empty page.
+    pub fn new(
+        parent: Parent,
+        properties: Properties,
+    ) -> Self {
+        PageCreateRequest {
+            parent,
+            properties,
+            icon: None,
+            cover: None,
+            #[cfg(feature = "blocks")]
+            children: None,
+        }
+    }
+
+    pub fn with_icon(
+        mut self,
+        icon: FileOrEmojiObject,
+    ) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    /// Sets the cover image, which has to be an external file.
+    pub fn with_cover(
+        mut self,
+        cover: FileObject,
+    ) -> Self {
+        self.cover = Some(cover);
+        self
+    }
+
+    /// Creates the page together with its content, instead of appending it afterwards.
+    #[cfg(feature = "blocks")]
+    pub fn with_children(
+        mut self,
+        children: Vec<CreateBlock>,
+    ) -> Self {
+        self.children = Some(children);
+        self
+    }
+}
+
+impl PageCreateRequest {
+    /// Composes a page one property at a time.
+    ///
+    /// ```
+    /// use notion_wasi::ids::DatabaseId;
+    /// use notion_wasi::models::text::RichText;
+    /// use notion_wasi::models::{PageCreateRequest, Parent};
+    ///
+    /// let database_id: DatabaseId = "b55c9c91-384d-452b-81db-d1ef79372b75".parse().unwrap();
+    /// let request = PageCreateRequest::builder(Parent::Database { database_id })
+    ///     .title_rich_text(vec![RichText::text("Ship it").bold()])
+    ///     .select("Status", "Todo")
+    ///     .multi_select("Tags", ["Backend", "Urgent"])
+    ///     .number("Estimate", 3)
+    ///     .checkbox("Done", false)
+    ///     .build();
+    /// assert_eq!(request.properties.properties.len(), 5);
+    /// ```
+    pub fn builder(parent: Parent) -> PageCreateRequestBuilder {
+        PageCreateRequestBuilder {
+            request: PageCreateRequest::new(parent, Properties::default()),
+        }
+    }
+}
+
+impl From<PageCreateRequestBuilder> for PageCreateRequest {
+    fn from(builder: PageCreateRequestBuilder) -> Self {
+        builder.build()
+    }
+}
+
+/// Builds a [PageCreateRequest], created with [PageCreateRequest::builder()].
+///
+/// Properties are keyed by name. Setting a property again replaces it.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct PageCreateRequestBuilder {
+    request: PageCreateRequest,
+}
+
+impl PageCreateRequestBuilder {
+    /// Sets any property value.
+    pub fn property<S: Into<String>>(
+        mut self,
+        name: S,
+        value: PropertyValue,
+    ) -> Self {
+        self.request
+            .properties
+            .properties
+            .insert(name.into(), value);
+        self
+    }
+
+    /// Sets the title, whatever the title property is called.
+    pub fn title<S: Into<String>>(
+        self,
+        title: S,
+    ) -> Self {
+        self.title_rich_text(vec![RichText::text(title)])
+    }
+
+    pub fn title_rich_text(
+        self,
+        title: Vec<RichText>,
+    ) -> Self {
+        let id = property_id("title");
+        self.property("title", PropertyValue::Title { id, title })
+    }
+
+    pub fn rich_text<S: Into<String>>(
+        self,
+        name: S,
+        rich_text: Vec<RichText>,
+    ) -> Self {
+        let name = name.into();
+        let id = property_id(&name);
+        self.property(name, PropertyValue::Text { id, rich_text })
+    }
+
+    pub fn text<S: Into<String>, T: Into<String>>(
+        self,
+        name: S,
+        text: T,
+    ) -> Self {
+        self.rich_text(name, vec![RichText::text(text)])
+    }
+
+    pub fn number<S: Into<String>, N: Into<Number>>(
+        self,
+        name: S,
+        number: N,
+    ) -> Self {
+        let name = name.into();
+        let id = property_id(&name);
+        let number = Some(number.into());
+        self.property(name, PropertyValue::Number { id, number })
+    }
+
+    pub fn select<S: Into<String>, O: Into<String>>(
+        self,
+        name: S,
+        option: O,
+    ) -> Self {
+        let name = name.into();
+        let id = property_id(&name);
+        let select = Some(selected(option));
+        self.property(name, PropertyValue::Select { id, select })
+    }
+
+    pub fn status<S: Into<String>, O: Into<String>>(
+        self,
+        name: S,
+        option: O,
+    ) -> Self {
+        let name = name.into();
+        let id = property_id(&name);
+        let status = Some(selected(option));
+        self.property(name, PropertyValue::Status { id, status })
+    }
+
+    pub fn multi_select<S, I>(
+        self,
+        name: S,
+        options: I,
+    ) -> Self
+    where
+        S: Into<String>,
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let name = name.into();
+        let id = property_id(&name);
+        let multi_select = Some(options.into_iter().map(selected).collect());
+        self.property(name, PropertyValue::MultiSelect { id, multi_select })
+    }
+
+    pub fn checkbox<S: Into<String>>(
+        self,
+        name: S,
+        checkbox: bool,
+    ) -> Self {
+        let name = name.into();
+        let id = property_id(&name);
+        self.property(name, PropertyValue::Checkbox { id, checkbox })
+    }
+
+    pub fn date<S: Into<String>>(
+        self,
+        name: S,
+        date: DateValue,
+    ) -> Self {
+        let name = name.into();
+        let id = property_id(&name);
+        let date = Some(date);
+        self.property(name, PropertyValue::Date { id, date })
+    }
+
+    pub fn url<S: Into<String>, U: Into<String>>(
+        self,
+        name: S,
+        url: U,
+    ) -> Self {
+        let name = name.into();
+        let id = property_id(&name);
+        let url = Some(url.into());
+        self.property(name, PropertyValue::Url { id, url })
+    }
+
+    pub fn people<S: Into<String>, I: IntoIterator<Item = UserId>>(
+        self,
+        name: S,
+        people: I,
+    ) -> Self {
+        let name = name.into();
+        let id = property_id(&name);
+        let people = people.into_iter().map(Into::into).collect();
+        self.property(name, PropertyValue::People { id, people })
+    }
+
+    pub fn relation<S: Into<String>, I: IntoIterator<Item = PageId>>(
+        self,
+        name: S,
+        pages: I,
+    ) -> Self {
+        let name = name.into();
+        let id = property_id(&name);
+        let relation = Some(pages.into_iter().map(|id| RelationValue { id }).collect());
+        self.property(
+            name,
+            PropertyValue::Relation {
+                id,
+                relation,
+                has_more: false,
+            },
+        )
+    }
+
+    pub fn icon(
+        mut self,
+        icon: FileOrEmojiObject,
+    ) -> Self {
+        self.request.icon = Some(icon);
+        self
+    }
+
+    /// Sets the cover image, which has to be an external file.
+    pub fn cover(
+        mut self,
+        cover: FileObject,
+    ) -> Self {
+        self.request.cover = Some(cover);
+        self
+    }
+
+    /// Creates the page together with its content, at most 100 blocks.
+    #[cfg(feature = "blocks")]
+    pub fn children(
+        mut self,
+        children: Vec<CreateBlock>,
+    ) -> Self {
+        self.request.children = Some(children);
+        self
+    }
+
+    pub fn build(self) -> PageCreateRequest {
+        self.request
+    }
+}
+
+/// Properties being written are keyed by name, which Notion accepts as their id.
+fn property_id(name: &str) -> PropertyId {
+    PropertyId::from_str(name).unwrap()
+}
+
+fn selected<S: Into<String>>(name: S) -> SelectedValue {
+    SelectedValue {
+        id: None,
+        name: Some(name.into()),
+        color: Color::Default,
+    }
+}
+
+/// Request body for updating a page.
+/// Only the properties present in `properties` are changed.
+/// See <https://developers.notion.com/reference/patch-page>
+#[derive(Serialize, Debug, Eq, PartialEq, Clone, Default)]
+pub struct PageUpdateRequest {
+    pub properties: Properties,
+    /// Set to `true` to archive the page, or `false` to restore it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archived: Option<bool>,
+    /// Set to `true` to move the page to the trash, or `false` to restore it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub in_trash: Option<bool>,
+    /// Replaces the icon, which is left unchanged when `None`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<FileOrEmojiObject>,
+    /// Replaces the cover, which has to be an external file. Left unchanged when `None`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cover: Option<FileObject>,
+}
+
+impl PageUpdateRequest {
+    /// Archives the page without changing its properties.
+    pub fn archive() -> Self {
+        PageUpdateRequest {
+            archived: Some(true),
+            ..Default::default()
+        }
+    }
+
+    /// Takes the page out of the archive without changing its properties.
+    pub fn restore() -> Self {
+        PageUpdateRequest {
+            archived: Some(false),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<Properties> for PageUpdateRequest {
+    fn from(properties: Properties) -> Self {
+        PageUpdateRequest {
+            properties,
+            ..Default::default()
+        }
+    }
+}
+
+/// Request body for creating a database in a page.
+/// See <https://developers.notion.com/reference/create-a-database>
+#[cfg(feature = "databases")]
+#[derive(Serialize, Debug, Eq, PartialEq, Clone)]
+pub struct DatabaseCreateRequest {
+    pub parent: Parent,
+    pub title: Vec<RichText>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub description: Vec<RichText>,
+    pub properties: DatabaseSchema,
+    /// Whether the database is shown inline in the parent page rather than as a child page.
+    pub is_inline: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<FileOrEmojiObject>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cover: Option<FileObject>,
+}
+
+#[cfg(feature = "databases")]
+impl DatabaseCreateRequest {
+    /// A request for a database titled `title` in the page `parent`.
+    pub fn new<P: AsIdentifier<PageId>, S: Into<String>>(
+        parent: P,
+        title: S,
+        properties: DatabaseSchema,
+    ) -> Self {
+        DatabaseCreateRequest {
+            parent: Parent::Page {
+                page_id: parent.as_id().clone(),
+            },
+            title: vec![RichText::text(title)],
+            description: vec![],
+            properties,
+            is_inline: false,
+            icon: None,
+            cover: None,
+        }
+    }
+
+    pub fn with_description<S: Into<String>>(
+        mut self,
+        description: S,
+    ) -> Self {
+        self.description = vec![RichText::text(description)];
+        self
+    }
+
+    pub fn inline(mut self) -> Self {
+        self.is_inline = true;
+        self
+    }
+
+    pub fn with_icon(
+        mut self,
+        icon: FileOrEmojiObject,
+    ) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+}
+
+/// A change to one property of a [DatabaseUpdateRequest].
+#[cfg(feature = "databases")]
+#[derive(Serialize, Debug, Eq, PartialEq, Clone)]
+#[serde(untagged)]
+pub enum PropertyChange {
+    /// Adds the property, or changes its type or configuration.
+    Define(PropertyDefinition),
+    /// Renames the property, keeping its values.
+    Rename { name: String },
+    /// Removes the property and its values, sent as `null`.
+    Remove,
+}
+
+/// Request body for changing the title, description or schema of a database.
+/// Only what is set is changed.
+/// See <https://developers.notion.com/reference/update-a-database>
+#[cfg(feature = "databases")]
+#[derive(Serialize, Debug, Eq, PartialEq, Clone, Default)]
+pub struct DatabaseUpdateRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<Vec<RichText>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<Vec<RichText>>,
+    /// Changes by property name.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub properties: BTreeMap<String, PropertyChange>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archived: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub in_trash: Option<bool>,
+}
+
+#[cfg(feature = "databases")]
+impl DatabaseUpdateRequest {
+    pub fn title<S: Into<String>>(
+        mut self,
+        title: S,
+    ) -> Self {
+        self.title = Some(vec![RichText::text(title)]);
+        self
+    }
+
+    pub fn description<S: Into<String>>(
+        mut self,
+        description: S,
+    ) -> Self {
+        self.description = Some(vec![RichText::text(description)]);
+        self
+    }
+
+    /// Adds the properties of `schema`, or changes the ones that exist.
+    pub fn define(
+        mut self,
+        schema: DatabaseSchema,
+    ) -> Self {
+        self.properties.extend(
+            schema
+                .into_iter()
+                .map(|(name, definition)| (name, PropertyChange::Define(definition))),
+        );
+        self
+    }
+
+    pub fn rename<S: Into<String>, T: Into<String>>(
+        mut self,
+        property: S,
+        name: T,
+    ) -> Self {
+        let change = PropertyChange::Rename { name: name.into() };
+        self.properties.insert(property.into(), change);
+        self
+    }
+
+    pub fn remove<S: Into<String>>(
+        mut self,
+        property: S,
+    ) -> Self {
+        self.properties
+            .insert(property.into(), PropertyChange::Remove);
+        self
+    }
+
+    /// Rewrites the relations of defined properties into the shape `version` expects.
+    pub fn for_version(
+        mut self,
+        version: ApiVersion,
+    ) -> Self {
+        for change in self.properties.values_mut() {
+            if let PropertyChange::Define(definition) = change {
+                *definition = definition.clone().for_version(version);
+            }
+        }
+        self
+    }
+}
+
+#[cfg(feature = "databases")]
+impl From<DatabaseSchema> for DatabaseUpdateRequest {
+    fn from(schema: DatabaseSchema) -> Self {
+        DatabaseUpdateRequest::default().define(schema)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
 pub struct Page {
     pub id: PageId,
@@ -197,16 +1172,106 @@ pub struct Page {
     pub created_time: DateTime<Utc>,
     /// Date and time when this page was updated.
     pub last_edited_time: DateTime<Utc>,
+    /// The user who created the page.
+    pub created_by: UserCommon,
+    /// The user who last edited the page.
+    /// Edits of blocks in the page are recorded on the blocks themselves.
+    pub last_edited_by: UserCommon,
     /// The archived status of the page.
+    #[serde(default, deserialize_with = "lenient::null_as_default")]
     pub archived: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<FileOrEmojiObject>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cover: Option<FileObject>,
+    /// Whether the page is in the trash.
+    #[serde(default, deserialize_with = "lenient::null_as_default")]
+    pub in_trash: bool,
     pub properties: Properties,
     pub parent: Parent,
+    /// The URL of the page in Notion.
+    #[serde(default, deserialize_with = "lenient::null_as_default")]
+    pub url: String,
+    /// The URL of the page if it is published to the web.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_url: Option<String>,
 }
 
 impl Page {
+    /// See [Properties::title()].
     pub fn title(&self) -> Option<String> {
         self.properties.title()
     }
+
+    /// See [Properties::title_property()].
+    pub fn title_property(&self) -> Option<&str> {
+        self.properties.title_property()
+    }
+
+    /// See [Properties::checkbox()].
+    pub fn checkbox(
+        &self,
+        name: &str,
+    ) -> Option<bool> {
+        self.properties.checkbox(name)
+    }
+
+    /// See [Properties::multi_select()].
+    pub fn multi_select(
+        &self,
+        name: &str,
+    ) -> Option<&[SelectedValue]> {
+        self.properties.multi_select(name)
+    }
+}
+
+/// Displays the plain text title, or `Untitled` like Notion does for pages without one.
+impl fmt::Display for Page {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        let title = self
+            .properties
+            .properties
+            .values()
+            .find_map(|value| match value {
+                PropertyValue::Title { title, .. }
+                    if title
+                        .iter()
+                        .any(|rich_text| !rich_text.plain_text().is_empty()) =>
+                {
+                    Some(title)
+                }
+                _ => None,
+            });
+        match title {
+            Some(title) => text::PlainText(title).fmt(f),
+            None => f.write_str("Untitled"),
+        }
+    }
+}
+
+impl Trashable for Page {
+    fn is_trashed(&self) -> bool {
+        self.archived || self.in_trash
+    }
+}
+
+impl Trashable for Object {
+    /// Whether the object is a trashed block, database or page.
+    fn is_trashed(&self) -> bool {
+        match self {
+            #[cfg(feature = "blocks")]
+            Object::Block { block } => block.is_trashed(),
+            #[cfg(feature = "databases")]
+            Object::Database { database } => database.is_trashed(),
+            #[cfg(feature = "databases")]
+            Object::DataSource { data_source } => data_source.is_trashed(),
+            Object::Page { page } => page.is_trashed(),
+            _ => false,
+        }
+    }
 }
 
 impl AsIdentifier<PageId> for Page {
@@ -219,14 +1284,21 @@ impl AsIdentifier<PageId> for Page {
 #[serde(tag = "object")]
 #[serde(rename_all = "snake_case")]
 pub enum Object {
+    #[cfg(feature = "blocks")]
     Block {
         #[serde(flatten)]
         block: Block,
     },
+    #[cfg(feature = "databases")]
     Database {
         #[serde(flatten)]
         database: Database,
     },
+    #[cfg(feature = "databases")]
+    DataSource {
+        #[serde(flatten)]
+        data_source: DataSource,
+    },
     Page {
         #[serde(flatten)]
         page: Page,
@@ -239,14 +1311,105 @@ pub enum Object {
         #[serde(flatten)]
         user: User,
     },
+    #[cfg(feature = "comments")]
+    Comment {
+        #[serde(flatten)]
+        comment: Comment,
+    },
+    FileUpload {
+        #[serde(flatten)]
+        file_upload: FileUpload,
+    },
+    PropertyItem {
+        #[serde(flatten)]
+        property_item: PropertyItem,
+    },
     Error {
         #[serde(flatten)]
         error: ErrorResponse,
     },
+    /// An object this build doesn't have a model for, such as a database
+    /// when the `databases` or `comments` feature is turned off.
+    #[serde(other)]
+    Unknown,
 }
 
 impl Object {
+    #[cfg(feature = "databases")]
     pub fn is_database(&self) -> bool {
         matches!(self, Object::Database { .. })
     }
 }
+
+/// An object returned by [NotionApi::search_results()](crate::NotionApi::search_results()).
+///
+/// API versions from 2025-09-03 on return [DataSource]s where older ones return [Database]s.
+#[derive(Eq, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "object")]
+#[serde(rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
+pub enum SearchResult {
+    Page(Page),
+    #[cfg(feature = "databases")]
+    Database(Database),
+    #[cfg(feature = "databases")]
+    DataSource(DataSource),
+    /// A database or data source when the `databases` feature is turned off.
+    #[serde(other)]
+    Unknown,
+}
+
+impl SearchResult {
+    pub fn as_page(&self) -> Option<&Page> {
+        match self {
+            SearchResult::Page(page) => Some(page),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "databases")]
+    pub fn as_database(&self) -> Option<&Database> {
+        match self {
+            SearchResult::Database(database) => Some(database),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "databases")]
+    pub fn as_data_source(&self) -> Option<&DataSource> {
+        match self {
+            SearchResult::DataSource(data_source) => Some(data_source),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "client")]
+impl std::convert::TryFrom<Object> for SearchResult {
+    type Error = Error;
+
+    fn try_from(object: Object) -> Result<Self, Self::Error> {
+        match object {
+            Object::Page { page } => Ok(SearchResult::Page(page)),
+            #[cfg(feature = "databases")]
+            Object::Database { database } => Ok(SearchResult::Database(database)),
+            #[cfg(feature = "databases")]
+            Object::DataSource { data_source } => Ok(SearchResult::DataSource(data_source)),
+            Object::Unknown => Ok(SearchResult::Unknown),
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
+}
+
+impl From<SearchResult> for Object {
+    fn from(result: SearchResult) -> Self {
+        match result {
+            SearchResult::Page(page) => Object::Page { page },
+            #[cfg(feature = "databases")]
+            SearchResult::Database(database) => Object::Database { database },
+            #[cfg(feature = "databases")]
+            SearchResult::DataSource(data_source) => Object::DataSource { data_source },
+            SearchResult::Unknown => Object::Unknown,
+        }
+    }
+}
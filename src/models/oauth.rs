@@ -0,0 +1,54 @@
+//! Tokens of public integrations, see [OAuthClient](crate::OAuthClient)
+//! and <https://developers.notion.com/docs/authorization>.
+
+use serde::{Deserialize, Serialize};
+
+use crate::ids::{PageId, UserId};
+use crate::models::users::BotOwner;
+use crate::SecretString;
+
+/// The body of `POST /v1/oauth/token`.
+#[derive(Serialize, Debug, Eq, PartialEq, Clone)]
+#[serde(tag = "grant_type", rename_all = "snake_case")]
+pub enum OAuthTokenRequest {
+    /// Exchanges the `code` a user was redirected with for a token.
+    AuthorizationCode {
+        code: String,
+        /// Has to be the URI the authorization started with, if it had one.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        redirect_uri: Option<String>,
+    },
+    /// Issues a new token for a refresh token.
+    RefreshToken { refresh_token: String },
+}
+
+/// The token of an integration installed in a workspace,
+/// to be passed to [NotionApi::new()](crate::NotionApi::new()).
+#[derive(Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct OAuthToken {
+    pub access_token: SecretString,
+    /// Only issued to integrations that use refresh tokens.
+    #[serde(default)]
+    pub refresh_token: Option<SecretString>,
+    /// The bot user of the integration in the workspace.
+    pub bot_id: UserId,
+    pub workspace_id: String,
+    #[serde(default)]
+    pub workspace_name: Option<String>,
+    /// An emoji, or the URL of an image.
+    #[serde(default)]
+    pub workspace_icon: Option<String>,
+    pub owner: BotOwner,
+    /// The page duplicated from the integration's template during authorization.
+    #[serde(default)]
+    pub duplicated_template_id: Option<PageId>,
+}
+
+/// The error body of the token endpoint, as defined by OAuth 2.0.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct OAuthErrorResponse {
+    /// Such as `invalid_grant` for a used or expired code.
+    pub error: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_description: Option<String>,
+}
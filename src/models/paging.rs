@@ -4,6 +4,12 @@ use serde::{Deserialize, Serialize};
 #[serde(transparent)]
 pub struct PagingCursor(String);
 
+impl PagingCursor {
+    pub fn value(&self) -> &str {
+        &self.0
+    }
+}
+
 #[derive(Serialize, Debug, Eq, PartialEq, Default, Clone)]
 pub struct Paging {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -12,9 +18,77 @@ pub struct Paging {
     pub page_size: Option<u8>,
 }
 
+/// A position in a paginated list, see [ListResponse::cursor()](crate::models::ListResponse::cursor()).
+///
+/// Cursors can be stored, for example as JSON, to continue a long export
+/// after a restart:
+///
+/// ```
+/// use notion_wasi::models::paging::{Cursor, Pageable};
+/// use notion_wasi::models::search::DatabaseQuery;
+///
+/// let saved = serde_json::to_string(&Cursor::Start).unwrap();
+///
+/// let cursor: Cursor = serde_json::from_str(&saved).unwrap();
+/// if !cursor.is_end() {
+///     let query = DatabaseQuery::default().resume(&cursor);
+///     // query_database(database_id, query)
+/// }
+/// ```
+///
+/// Notion cursors expire after a while, so resuming much later may fail.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Hash, Clone, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Cursor {
+    /// Nothing was fetched yet.
+    #[default]
+    Start,
+    /// More results follow, starting at this cursor.
+    Next(PagingCursor),
+    /// Every result was fetched.
+    End,
+}
+
+impl Cursor {
+    /// The position after a response with these `next_cursor` and `has_more` fields.
+    pub fn after(
+        next_cursor: Option<PagingCursor>,
+        has_more: bool,
+    ) -> Self {
+        match next_cursor {
+            Some(cursor) if has_more => Cursor::Next(cursor),
+            _ => Cursor::End,
+        }
+    }
+
+    pub fn is_end(&self) -> bool {
+        matches!(self, Cursor::End)
+    }
+
+    /// The cursor to send as `start_cursor`, `None` at the start and end.
+    pub fn start_cursor(&self) -> Option<&PagingCursor> {
+        match self {
+            Cursor::Next(cursor) => Some(cursor),
+            Cursor::Start | Cursor::End => None,
+        }
+    }
+}
+
 pub trait Pageable {
     fn start_from(
         self,
         starting_point: Option<PagingCursor>,
     ) -> Self;
+
+    /// Continues from `cursor`.
+    /// At [Cursor::End] this starts over, check [Cursor::is_end()] first.
+    fn resume(
+        self,
+        cursor: &Cursor,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        self.start_from(cursor.start_cursor().cloned())
+    }
 }
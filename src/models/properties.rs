@@ -1,12 +1,19 @@
+use crate::models::files::FileObject;
 use crate::models::text::RichText;
-use crate::models::users::User;
+use crate::models::users::UserReference;
 
-use crate::ids::{DatabaseId, PageId, PropertyId};
+use crate::ids::{DataSourceId, DatabaseId, PageId, PropertyId};
 use crate::models::{DateTime, Number, Utc};
+use crate::ApiVersion;
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
+pub mod dates;
+#[cfg(feature = "databases")]
+pub mod definitions;
 pub mod formulas;
+pub mod getters;
+pub mod validated;
 
 #[cfg(test)]
 mod tests;
@@ -37,6 +44,12 @@ pub struct NumberDetails {
 #[serde(transparent)]
 pub struct SelectOptionId(String);
 
+impl From<String> for SelectOptionId {
+    fn from(id: String) -> Self {
+        SelectOptionId(id)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Copy, Clone)]
 #[serde(rename_all = "lowercase")]
 pub enum Color {
@@ -92,14 +105,117 @@ pub struct Relation {
     /// The database this relation refers to.
     /// New linked pages must belong to this database in order to be valid.
     pub database_id: DatabaseId,
+    /// The data source of the database this relation refers to.
+    /// Only reported by API versions from 2025-09-03 on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_source_id: Option<DataSourceId>,
     /// By default, relations are formed as two synced properties across databases:
     ///     if you make a change to one property, it updates the synced property at the same time.
     /// `synced_property_name` refers to the name of the property in the related database.
+    ///
+    /// API versions from 2022-06-28 on report it in [RelationType::DualProperty] instead.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub synced_property_name: Option<String>,
     /// By default, relations are formed as two synced properties across databases:
     ///     if you make a change to one property, it updates the synced property at the same time.
     /// `synced_property_id` refers to the id of the property in the related database.
     /// This is usually a short string of random letters and symbols.
+    ///
+    /// API versions from 2022-06-28 on report it in [RelationType::DualProperty] instead.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub synced_property_id: Option<PropertyId>,
+    /// Whether the relation is synced with a property in the related database.
+    /// Only reported by API versions from 2022-06-28 on.
+    #[serde(flatten, default, skip_serializing_if = "Option::is_none")]
+    pub relation_type: Option<RelationType>,
+}
+
+impl Relation {
+    /// The name of the synced property in the related database, in either version's shape.
+    pub fn synced_property_name(&self) -> Option<&str> {
+        match &self.relation_type {
+            Some(RelationType::DualProperty { dual_property }) => {
+                dual_property.synced_property_name.as_deref()
+            }
+            Some(RelationType::SingleProperty { .. }) => None,
+            None => self.synced_property_name.as_deref(),
+        }
+    }
+
+    /// The id of the synced property in the related database, in either version's shape.
+    pub fn synced_property_id(&self) -> Option<&PropertyId> {
+        match &self.relation_type {
+            Some(RelationType::DualProperty { dual_property }) => {
+                dual_property.synced_property_id.as_ref()
+            }
+            Some(RelationType::SingleProperty { .. }) => None,
+            None => self.synced_property_id.as_ref(),
+        }
+    }
+
+    /// Rewrites the relation into the shape `version` expects.
+    ///
+    /// 2022-02-22 has no one-way relations, so those become synced relations
+    /// for which Notion creates the synced property.
+    pub fn for_version(
+        self,
+        version: ApiVersion,
+    ) -> Self {
+        let synced_property_name = self.synced_property_name().map(str::to_string);
+        let synced_property_id = self.synced_property_id().cloned();
+        let single = matches!(
+            self.relation_type,
+            Some(RelationType::SingleProperty { .. })
+        );
+
+        match version {
+            ApiVersion::V2022_02_22 => Relation {
+                database_id: self.database_id,
+                data_source_id: None,
+                synced_property_name,
+                synced_property_id,
+                relation_type: None,
+            },
+            ApiVersion::V2022_06_28 | ApiVersion::V2025_09_03 => Relation {
+                data_source_id: self
+                    .data_source_id
+                    .filter(|_| version >= ApiVersion::V2025_09_03),
+                database_id: self.database_id,
+                synced_property_name: None,
+                synced_property_id: None,
+                relation_type: Some(if single {
+                    RelationType::SingleProperty {
+                        single_property: Empty {},
+                    }
+                } else {
+                    RelationType::DualProperty {
+                        dual_property: DualProperty {
+                            synced_property_name,
+                            synced_property_id,
+                        },
+                    }
+                }),
+            },
+        }
+    }
+}
+
+/// <https://developers.notion.com/reference/property-object#relation>
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RelationType {
+    /// The relation has no synced property in the related database.
+    SingleProperty { single_property: Empty },
+    /// The relation is synced with a property in the related database.
+    DualProperty { dual_property: DualProperty },
+}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Default)]
+pub struct Empty {}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct DualProperty {
+    pub synced_property_name: Option<String>,
     pub synced_property_id: Option<PropertyId>,
 }
 
@@ -150,6 +266,7 @@ pub struct Rollup {
     pub function: RollupFunction,
 }
 
+#[cfg(feature = "databases")]
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
 #[serde(tag = "type")]
 #[serde(rename_all = "snake_case")]
@@ -215,7 +332,36 @@ pub enum PropertyConfiguration {
     /// See <https://developers.notion.com/reference/database#last-edited-time-configuration>
     LastEditedTime { id: PropertyId },
     /// See <https://developers.notion.com/reference/database#last-edited-by-configuration>
-    LastEditBy { id: PropertyId },
+    LastEditedBy { id: PropertyId },
+}
+
+#[cfg(feature = "databases")]
+impl PropertyConfiguration {
+    pub fn id(&self) -> &PropertyId {
+        use PropertyConfiguration::*;
+        match self {
+            Title { id, .. }
+            | Text { id, .. }
+            | Number { id, .. }
+            | Select { id, .. }
+            | Status { id, .. }
+            | MultiSelect { id, .. }
+            | Date { id, .. }
+            | People { id, .. }
+            | Files { id, .. }
+            | Checkbox { id, .. }
+            | Url { id, .. }
+            | Email { id, .. }
+            | PhoneNumber { id, .. }
+            | Formula { id, .. }
+            | Relation { id, .. }
+            | Rollup { id, .. }
+            | CreatedTime { id, .. }
+            | CreatedBy { id, .. }
+            | LastEditedTime { id, .. }
+            | LastEditedBy { id, .. } => id,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
@@ -227,8 +373,9 @@ pub struct SelectedValue {
     pub color: Color,
 }
 
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
-#[serde(untagged)]
+/// A date-only value like `2022-05-14`, or a full RFC 3339 datetime.
+/// See [dates] for parsing and formatting.
+#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone)]
 pub enum DateOrDateTime {
     Date(NaiveDate),
     DateTime(DateTime<Utc>),
@@ -265,15 +412,15 @@ pub struct RelationValue {
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum RollupValue {
     Number { number: Option<Number> },
-    Date { date: Option<DateTime<Utc>> },
+    Date { date: Option<DateValue> },
     Array { array: Vec<RollupPropertyValue> },
 }
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
 pub struct FileReference {
     pub name: String,
-    pub url: String,
-    pub mime_type: String,
+    #[serde(flatten)]
+    pub file: FileObject,
 }
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
@@ -326,6 +473,11 @@ pub enum PropertyValue {
     Relation {
         id: PropertyId,
         relation: Option<Vec<RelationValue>>,
+        /// Whether the page has more related pages than Notion included,
+        /// which [NotionApi::get_property_items()](crate::NotionApi::get_property_items()) fetches.
+        /// Only reported by API versions from 2022-06-28 on.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        has_more: bool,
     },
     /// <https://developers.notion.com/reference/property-object#rollup-configuration>
     Rollup {
@@ -333,7 +485,10 @@ pub enum PropertyValue {
         rollup: Option<RollupValue>,
     },
     /// <https://developers.notion.com/reference/property-object#people-configuration>
-    People { id: PropertyId, people: Vec<User> },
+    People {
+        id: PropertyId,
+        people: Vec<UserReference>,
+    },
     /// <https://developers.notion.com/reference/property-object#files-configuration>
     Files {
         id: PropertyId,
@@ -351,7 +506,7 @@ pub enum PropertyValue {
     /// <https://developers.notion.com/reference/property-object#phone-number-configuration>
     PhoneNumber {
         id: PropertyId,
-        phone_number: String,
+        phone_number: Option<String>,
     },
     /// <https://developers.notion.com/reference/property-object#created-time-configuration>
     CreatedTime {
@@ -359,7 +514,10 @@ pub enum PropertyValue {
         created_time: DateTime<Utc>,
     },
     /// <https://developers.notion.com/reference/property-object#created-by-configuration>
-    CreatedBy { id: PropertyId, created_by: User },
+    CreatedBy {
+        id: PropertyId,
+        created_by: UserReference,
+    },
     /// <https://developers.notion.com/reference/property-object#last-edited-time-configuration>
     LastEditedTime {
         id: PropertyId,
@@ -368,7 +526,7 @@ pub enum PropertyValue {
     /// <https://developers.notion.com/reference/property-object#last-edited-by-configuration>
     LastEditedBy {
         id: PropertyId,
-        last_edited_by: User,
+        last_edited_by: UserReference,
     },
 }
 
@@ -377,6 +535,10 @@ pub enum PropertyValue {
 #[serde(tag = "type")]
 #[serde(rename_all = "snake_case")]
 pub enum RollupPropertyValue {
+    /// <https://developers.notion.com/reference/page#title-property-values>
+    Title {
+        title: Vec<RichText>,
+    },
     /// <https://developers.notion.com/reference/page#rich-text-property-values>
     #[serde(rename = "rich_text")]
     Text {
@@ -413,33 +575,241 @@ pub enum RollupPropertyValue {
         rollup: Option<RollupValue>,
     },
     People {
-        people: Vec<User>,
+        people: Vec<UserReference>,
+    },
+    Files {
+        files: Option<Vec<FileReference>>,
+    },
+    Checkbox {
+        checkbox: bool,
+    },
+    Url {
+        url: Option<String>,
+    },
+    Email {
+        email: Option<String>,
+    },
+    PhoneNumber {
+        phone_number: Option<String>,
+    },
+    CreatedTime {
+        created_time: DateTime<Utc>,
+    },
+    CreatedBy {
+        created_by: UserReference,
+    },
+    LastEditedTime {
+        last_edited_time: DateTime<Utc>,
+    },
+    LastEditedBy {
+        last_edited_by: UserReference,
+    },
+}
+
+/// A single property value, as returned by the property item endpoint.
+///
+/// Unlike [PropertyValue], title, rich text, relation and people properties
+/// are paginated and return one element per item.
+/// <https://developers.notion.com/reference/property-item-object>
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[serde(tag = "type")]
+#[serde(rename_all = "snake_case")]
+pub enum PropertyItem {
+    Title {
+        id: PropertyId,
+        title: RichText,
+    },
+    #[serde(rename = "rich_text")]
+    Text {
+        id: PropertyId,
+        rich_text: RichText,
+    },
+    Relation {
+        id: PropertyId,
+        relation: RelationValue,
+    },
+    People {
+        id: PropertyId,
+        people: UserReference,
+    },
+    Number {
+        id: PropertyId,
+        number: Option<Number>,
+    },
+    Select {
+        id: PropertyId,
+        select: Option<SelectedValue>,
+    },
+    Status {
+        id: PropertyId,
+        status: Option<SelectedValue>,
+    },
+    MultiSelect {
+        id: PropertyId,
+        multi_select: Option<Vec<SelectedValue>>,
+    },
+    Date {
+        id: PropertyId,
+        date: Option<DateValue>,
+    },
+    Formula {
+        id: PropertyId,
+        formula: FormulaResultValue,
     },
     Files {
+        id: PropertyId,
         files: Option<Vec<FileReference>>,
     },
     Checkbox {
+        id: PropertyId,
         checkbox: bool,
     },
     Url {
+        id: PropertyId,
         url: Option<String>,
     },
     Email {
+        id: PropertyId,
         email: Option<String>,
     },
     PhoneNumber {
-        phone_number: String,
+        id: PropertyId,
+        phone_number: Option<String>,
     },
     CreatedTime {
+        id: PropertyId,
         created_time: DateTime<Utc>,
     },
     CreatedBy {
-        created_by: User,
+        id: PropertyId,
+        created_by: UserReference,
     },
     LastEditedTime {
+        id: PropertyId,
         last_edited_time: DateTime<Utc>,
     },
     LastEditedBy {
-        last_edited_by: User,
+        id: PropertyId,
+        last_edited_by: UserReference,
     },
 }
+
+impl PropertyValue {
+    /// Whether [NotionApi::get_page()](crate::NotionApi::get_page()) may leave items out of
+    /// this property, which are complete after [with_items()](Self::with_items()).
+    pub fn is_paginated(&self) -> bool {
+        matches!(
+            self,
+            PropertyValue::Title { .. }
+                | PropertyValue::Text { .. }
+                | PropertyValue::Relation { .. }
+                | PropertyValue::People { .. }
+                | PropertyValue::Rollup {
+                    rollup: Some(RollupValue::Array { .. }),
+                    ..
+                }
+        )
+    }
+
+    /// Replaces the elements of a paginated property with the `items`
+    /// of the property item endpoint, which are all of them.
+    ///
+    /// Items of another property type are skipped,
+    /// properties that aren't paginated are returned unchanged.
+    pub fn with_items(
+        self,
+        items: Vec<PropertyItem>,
+    ) -> Self {
+        use PropertyValue::*;
+        let items = items.into_iter();
+        match self {
+            Title { id, .. } => Title {
+                id,
+                title: items
+                    .filter_map(|item| match item {
+                        PropertyItem::Title { title, .. } => Some(title),
+                        _ => None,
+                    })
+                    .collect(),
+            },
+            Text { id, .. } => Text {
+                id,
+                rich_text: items
+                    .filter_map(|item| match item {
+                        PropertyItem::Text { rich_text, .. } => Some(rich_text),
+                        _ => None,
+                    })
+                    .collect(),
+            },
+            Relation { id, .. } => Relation {
+                id,
+                relation: Some(
+                    items
+                        .filter_map(|item| match item {
+                            PropertyItem::Relation { relation, .. } => Some(relation),
+                            _ => None,
+                        })
+                        .collect(),
+                ),
+                has_more: false,
+            },
+            People { id, .. } => People {
+                id,
+                people: items
+                    .filter_map(|item| match item {
+                        PropertyItem::People { people, .. } => Some(people),
+                        _ => None,
+                    })
+                    .collect(),
+            },
+            Rollup {
+                id,
+                rollup: Some(RollupValue::Array { .. }),
+            } => Rollup {
+                id,
+                rollup: Some(RollupValue::Array {
+                    array: items.map(RollupPropertyValue::from).collect(),
+                }),
+            },
+            value => value,
+        }
+    }
+}
+
+/// Each item of a rollup array is the value of the rolled up property on one related page.
+impl From<PropertyItem> for RollupPropertyValue {
+    fn from(item: PropertyItem) -> Self {
+        use PropertyItem::*;
+        match item {
+            Title { title, .. } => RollupPropertyValue::Title { title: vec![title] },
+            Text { rich_text, .. } => RollupPropertyValue::Text {
+                rich_text: vec![rich_text],
+            },
+            Relation { relation, .. } => RollupPropertyValue::Relation {
+                relation: Some(vec![relation]),
+            },
+            People { people, .. } => RollupPropertyValue::People {
+                people: vec![people],
+            },
+            Number { number, .. } => RollupPropertyValue::Number { number },
+            Select { select, .. } => RollupPropertyValue::Select { select },
+            Status { status, .. } => RollupPropertyValue::Status { status },
+            MultiSelect { multi_select, .. } => RollupPropertyValue::MultiSelect { multi_select },
+            Date { date, .. } => RollupPropertyValue::Date { date },
+            Formula { formula, .. } => RollupPropertyValue::Formula { formula },
+            Files { files, .. } => RollupPropertyValue::Files { files },
+            Checkbox { checkbox, .. } => RollupPropertyValue::Checkbox { checkbox },
+            Url { url, .. } => RollupPropertyValue::Url { url },
+            Email { email, .. } => RollupPropertyValue::Email { email },
+            PhoneNumber { phone_number, .. } => RollupPropertyValue::PhoneNumber { phone_number },
+            CreatedTime { created_time, .. } => RollupPropertyValue::CreatedTime { created_time },
+            CreatedBy { created_by, .. } => RollupPropertyValue::CreatedBy { created_by },
+            LastEditedTime {
+                last_edited_time, ..
+            } => RollupPropertyValue::LastEditedTime { last_edited_time },
+            LastEditedBy { last_edited_by, .. } => {
+                RollupPropertyValue::LastEditedBy { last_edited_by }
+            }
+        }
+    }
+}
@@ -0,0 +1,144 @@
+//! Parsing and formatting of Notion date values.
+//!
+//! Notion sends date-only values as `YYYY-MM-DD` and everything else as RFC 3339.
+//! [DateOrDateTime] keeps the two apart, so a date-only property is written back
+//! without a time, and datetimes are written in the format Notion returns.
+//!
+//! ```
+//! use notion_wasi::models::properties::DateOrDateTime;
+//!
+//! let date: DateOrDateTime = "2022-05-14".parse().unwrap();
+//! assert_eq!(date.to_string(), "2022-05-14");
+//!
+//! let datetime: DateOrDateTime = "2022-05-14T09:00:00-04:00".parse().unwrap();
+//! assert_eq!(datetime.to_string(), "2022-05-14T13:00:00.000Z");
+//! assert_eq!(datetime.to_date_only().to_string(), "2022-05-14");
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::models::properties::{DateOrDateTime, DateValue};
+
+/// A string that is neither a `YYYY-MM-DD` date nor an RFC 3339 datetime.
+#[derive(Debug, Eq, PartialEq, Clone, thiserror::Error)]
+#[error("Invalid date {0:?}, expected `YYYY-MM-DD` or an RFC 3339 datetime")]
+pub struct InvalidDate(pub String);
+
+impl DateOrDateTime {
+    pub fn is_date_only(&self) -> bool {
+        matches!(self, DateOrDateTime::Date(_))
+    }
+
+    /// The calendar date, in UTC for datetimes.
+    pub fn date_naive(&self) -> NaiveDate {
+        match self {
+            DateOrDateTime::Date(date) => *date,
+            DateOrDateTime::DateTime(datetime) => datetime.date_naive(),
+        }
+    }
+
+    /// The datetime, at midnight UTC for date-only values.
+    pub fn to_datetime(&self) -> DateTime<Utc> {
+        match self {
+            DateOrDateTime::Date(date) => date.and_hms_opt(0, 0, 0).unwrap().and_utc(),
+            DateOrDateTime::DateTime(datetime) => *datetime,
+        }
+    }
+
+    /// Drops the time, for writing to properties that only hold dates.
+    pub fn to_date_only(&self) -> Self {
+        DateOrDateTime::Date(self.date_naive())
+    }
+}
+
+impl From<NaiveDate> for DateOrDateTime {
+    fn from(date: NaiveDate) -> Self {
+        DateOrDateTime::Date(date)
+    }
+}
+
+impl From<DateTime<Utc>> for DateOrDateTime {
+    fn from(datetime: DateTime<Utc>) -> Self {
+        DateOrDateTime::DateTime(datetime)
+    }
+}
+
+impl FromStr for DateOrDateTime {
+    type Err = InvalidDate;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+            return Ok(DateOrDateTime::Date(date));
+        }
+        DateTime::parse_from_rfc3339(s)
+            .map(|datetime| DateOrDateTime::DateTime(datetime.with_timezone(&Utc)))
+            .map_err(|_| InvalidDate(s.to_string()))
+    }
+}
+
+/// `YYYY-MM-DD` for dates, RFC 3339 in UTC with milliseconds for datetimes.
+impl fmt::Display for DateOrDateTime {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            DateOrDateTime::Date(date) => date.format("%Y-%m-%d").fmt(f),
+            DateOrDateTime::DateTime(datetime) => {
+                datetime.to_rfc3339_opts(SecondsFormat::Millis, true).fmt(f)
+            }
+        }
+    }
+}
+
+impl Serialize for DateOrDateTime {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for DateOrDateTime {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        value.parse().map_err(D::Error::custom)
+    }
+}
+
+impl DateValue {
+    /// A single date or datetime without a time zone.
+    pub fn new<D: Into<DateOrDateTime>>(start: D) -> Self {
+        DateValue {
+            start: start.into(),
+            end: None,
+            time_zone: None,
+        }
+    }
+
+    /// A range from `start` to `end`.
+    pub fn range<D: Into<DateOrDateTime>>(
+        start: D,
+        end: D,
+    ) -> Self {
+        DateValue {
+            end: Some(end.into()),
+            ..DateValue::new(start)
+        }
+    }
+
+    /// The same value with the time dropped from `start` and `end`.
+    pub fn to_date_only(&self) -> Self {
+        DateValue {
+            start: self.start.to_date_only(),
+            end: self.end.as_ref().map(DateOrDateTime::to_date_only),
+            time_zone: None,
+        }
+    }
+}
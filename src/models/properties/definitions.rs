@@ -0,0 +1,436 @@
+//! Property definitions for creating databases and changing their schema,
+//! see [NotionApi::create_database()](crate::NotionApi::create_database()).
+//!
+//! ```
+//! use notion_wasi::ids::DatabaseId;
+//! use notion_wasi::models::properties::definitions::DatabaseSchema;
+//! use notion_wasi::models::properties::{Color, NumberFormat, RollupFunction};
+//!
+//! # let projects: DatabaseId = "b55c9c91-384d-452b-81db-d1ef79372b75".parse().unwrap();
+//! let schema = DatabaseSchema::new()
+//!     .title("Name")
+//!     .select("Status", ["Todo", "Doing"])
+//!     .select("Priority", [("High", Color::Red), ("Low", Color::Gray)])
+//!     .number("Price", NumberFormat::Dollar)
+//!     .relation("Project", projects)
+//!     .rollup("Budget", "Project", "Budget", RollupFunction::Sum)
+//!     .formula("Total", r#"prop("Price") * 2"#);
+//! assert_eq!(schema.len(), 7);
+//! ```
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::ids::{DataSourceId, DatabaseId};
+use crate::models::properties::{
+    Color, Empty, Formula, NumberDetails, NumberFormat, RollupFunction,
+};
+use crate::ApiVersion;
+
+/// The type and configuration of a property to create, without the ids
+/// the API assigns, serialized as `{"select": {"options": [...]}}`.
+#[derive(Serialize, Debug, Eq, PartialEq, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum PropertyDefinition {
+    Title {},
+    RichText {},
+    Number(NumberDetails),
+    Select(SelectDefinition),
+    MultiSelect(SelectDefinition),
+    Date {},
+    People {},
+    Files {},
+    Checkbox {},
+    Url {},
+    Email {},
+    PhoneNumber {},
+    Formula(Formula),
+    Relation(RelationDefinition),
+    Rollup(RollupDefinition),
+    CreatedTime {},
+    CreatedBy {},
+    LastEditedTime {},
+    LastEditedBy {},
+}
+
+impl PropertyDefinition {
+    /// Rewrites relations into the shape `version` expects, see [RelationDefinition::for_version()].
+    pub fn for_version(
+        self,
+        version: ApiVersion,
+    ) -> Self {
+        match self {
+            PropertyDefinition::Relation(relation) => {
+                PropertyDefinition::Relation(relation.for_version(version))
+            }
+            definition => definition,
+        }
+    }
+}
+
+/// The options of a select or multi-select property to create.
+#[derive(Serialize, Debug, Eq, PartialEq, Clone, Default)]
+pub struct SelectDefinition {
+    pub options: Vec<OptionDefinition>,
+}
+
+/// A select option to create. Notion picks a color when there is none.
+#[derive(Serialize, Debug, Eq, PartialEq, Clone)]
+pub struct OptionDefinition {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<Color>,
+}
+
+impl From<&str> for OptionDefinition {
+    fn from(name: &str) -> Self {
+        name.to_string().into()
+    }
+}
+
+impl From<String> for OptionDefinition {
+    fn from(name: String) -> Self {
+        OptionDefinition { name, color: None }
+    }
+}
+
+impl<S: Into<String>> From<(S, Color)> for OptionDefinition {
+    fn from((name, color): (S, Color)) -> Self {
+        OptionDefinition {
+            name: name.into(),
+            color: Some(color),
+        }
+    }
+}
+
+/// A relation to pages of another database.
+#[derive(Serialize, Debug, Eq, PartialEq, Clone)]
+pub struct RelationDefinition {
+    pub database_id: DatabaseId,
+    /// The data source of the related database, from API version 2025-09-03 on.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_source_id: Option<DataSourceId>,
+    /// Whether Notion adds a synced property to the related database.
+    /// Sent from API version 2022-06-28 on, 2022-02-22 always syncs.
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub relation_type: Option<RelationDefinitionType>,
+}
+
+impl RelationDefinition {
+    /// A relation with a synced property in the related database.
+    pub fn synced(database_id: DatabaseId) -> Self {
+        RelationDefinition {
+            database_id,
+            data_source_id: None,
+            relation_type: Some(RelationDefinitionType::DualProperty {
+                dual_property: Empty {},
+            }),
+        }
+    }
+
+    /// A relation without a synced property in the related database.
+    pub fn one_way(database_id: DatabaseId) -> Self {
+        RelationDefinition {
+            relation_type: Some(RelationDefinitionType::SingleProperty {
+                single_property: Empty {},
+            }),
+            ..Self::synced(database_id)
+        }
+    }
+
+    /// Rewrites the relation into the shape `version` expects.
+    ///
+    /// 2022-02-22 has no one-way relations, so those become synced relations.
+    pub fn for_version(
+        self,
+        version: ApiVersion,
+    ) -> Self {
+        match version {
+            ApiVersion::V2022_02_22 => RelationDefinition {
+                data_source_id: None,
+                relation_type: None,
+                ..self
+            },
+            ApiVersion::V2022_06_28 | ApiVersion::V2025_09_03 => RelationDefinition {
+                data_source_id: self
+                    .data_source_id
+                    .filter(|_| version >= ApiVersion::V2025_09_03),
+                relation_type: self
+                    .relation_type
+                    .or(Some(RelationDefinitionType::DualProperty {
+                        dual_property: Empty {},
+                    })),
+                ..self
+            },
+        }
+    }
+}
+
+/// See [RelationType](crate::models::properties::RelationType).
+#[derive(Serialize, Debug, Eq, PartialEq, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RelationDefinitionType {
+    SingleProperty { single_property: Empty },
+    DualProperty { dual_property: Empty },
+}
+
+/// A rollup of the relation property `relation_property_name`.
+#[derive(Serialize, Debug, Eq, PartialEq, Clone)]
+pub struct RollupDefinition {
+    pub relation_property_name: String,
+    /// The property of the related pages that `function` is applied to.
+    pub rollup_property_name: String,
+    pub function: RollupFunction,
+}
+
+/// Property definitions by name, built one property at a time.
+#[derive(Serialize, Debug, Eq, PartialEq, Clone, Default)]
+#[serde(transparent)]
+pub struct DatabaseSchema {
+    properties: BTreeMap<String, PropertyDefinition>,
+}
+
+impl DatabaseSchema {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `definition` as `name`, replacing an earlier property of that name.
+    pub fn property<S: Into<String>>(
+        mut self,
+        name: S,
+        definition: PropertyDefinition,
+    ) -> Self {
+        self.properties.insert(name.into(), definition);
+        self
+    }
+
+    /// The title property, which every database has exactly one of.
+    pub fn title<S: Into<String>>(
+        self,
+        name: S,
+    ) -> Self {
+        self.property(name, PropertyDefinition::Title {})
+    }
+
+    pub fn rich_text<S: Into<String>>(
+        self,
+        name: S,
+    ) -> Self {
+        self.property(name, PropertyDefinition::RichText {})
+    }
+
+    pub fn number<S: Into<String>>(
+        self,
+        name: S,
+        format: NumberFormat,
+    ) -> Self {
+        self.property(name, PropertyDefinition::Number(NumberDetails { format }))
+    }
+
+    /// A select property with `options`, given as names or `(name, color)` pairs.
+    pub fn select<S, I>(
+        self,
+        name: S,
+        options: I,
+    ) -> Self
+    where
+        S: Into<String>,
+        I: IntoIterator,
+        I::Item: Into<OptionDefinition>,
+    {
+        self.property(name, PropertyDefinition::Select(select(options)))
+    }
+
+    /// A multi-select property with `options`, given as names or `(name, color)` pairs.
+    pub fn multi_select<S, I>(
+        self,
+        name: S,
+        options: I,
+    ) -> Self
+    where
+        S: Into<String>,
+        I: IntoIterator,
+        I::Item: Into<OptionDefinition>,
+    {
+        self.property(name, PropertyDefinition::MultiSelect(select(options)))
+    }
+
+    pub fn date<S: Into<String>>(
+        self,
+        name: S,
+    ) -> Self {
+        self.property(name, PropertyDefinition::Date {})
+    }
+
+    pub fn people<S: Into<String>>(
+        self,
+        name: S,
+    ) -> Self {
+        self.property(name, PropertyDefinition::People {})
+    }
+
+    pub fn files<S: Into<String>>(
+        self,
+        name: S,
+    ) -> Self {
+        self.property(name, PropertyDefinition::Files {})
+    }
+
+    pub fn checkbox<S: Into<String>>(
+        self,
+        name: S,
+    ) -> Self {
+        self.property(name, PropertyDefinition::Checkbox {})
+    }
+
+    pub fn url<S: Into<String>>(
+        self,
+        name: S,
+    ) -> Self {
+        self.property(name, PropertyDefinition::Url {})
+    }
+
+    pub fn email<S: Into<String>>(
+        self,
+        name: S,
+    ) -> Self {
+        self.property(name, PropertyDefinition::Email {})
+    }
+
+    pub fn phone_number<S: Into<String>>(
+        self,
+        name: S,
+    ) -> Self {
+        self.property(name, PropertyDefinition::PhoneNumber {})
+    }
+
+    pub fn formula<S: Into<String>, E: Into<String>>(
+        self,
+        name: S,
+        expression: E,
+    ) -> Self {
+        let formula = Formula {
+            expression: expression.into(),
+        };
+        self.property(name, PropertyDefinition::Formula(formula))
+    }
+
+    /// A relation synced with a new property in `database_id`.
+    pub fn relation<S: Into<String>>(
+        self,
+        name: S,
+        database_id: DatabaseId,
+    ) -> Self {
+        let relation = RelationDefinition::synced(database_id);
+        self.property(name, PropertyDefinition::Relation(relation))
+    }
+
+    /// A relation that leaves `database_id` unchanged.
+    pub fn one_way_relation<S: Into<String>>(
+        self,
+        name: S,
+        database_id: DatabaseId,
+    ) -> Self {
+        let relation = RelationDefinition::one_way(database_id);
+        self.property(name, PropertyDefinition::Relation(relation))
+    }
+
+    /// A rollup of `rollup_property` of the pages related through `relation_property`.
+    pub fn rollup<S, R, P>(
+        self,
+        name: S,
+        relation_property: R,
+        rollup_property: P,
+        function: RollupFunction,
+    ) -> Self
+    where
+        S: Into<String>,
+        R: Into<String>,
+        P: Into<String>,
+    {
+        let rollup = RollupDefinition {
+            relation_property_name: relation_property.into(),
+            rollup_property_name: rollup_property.into(),
+            function,
+        };
+        self.property(name, PropertyDefinition::Rollup(rollup))
+    }
+
+    pub fn created_time<S: Into<String>>(
+        self,
+        name: S,
+    ) -> Self {
+        self.property(name, PropertyDefinition::CreatedTime {})
+    }
+
+    pub fn created_by<S: Into<String>>(
+        self,
+        name: S,
+    ) -> Self {
+        self.property(name, PropertyDefinition::CreatedBy {})
+    }
+
+    pub fn last_edited_time<S: Into<String>>(
+        self,
+        name: S,
+    ) -> Self {
+        self.property(name, PropertyDefinition::LastEditedTime {})
+    }
+
+    pub fn last_edited_by<S: Into<String>>(
+        self,
+        name: S,
+    ) -> Self {
+        self.property(name, PropertyDefinition::LastEditedBy {})
+    }
+
+    pub fn len(&self) -> usize {
+        self.properties.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.properties.is_empty()
+    }
+
+    pub fn get(
+        &self,
+        name: &str,
+    ) -> Option<&PropertyDefinition> {
+        self.properties.get(name)
+    }
+
+    /// Rewrites the relations into the shape `version` expects.
+    pub fn for_version(
+        self,
+        version: ApiVersion,
+    ) -> Self {
+        DatabaseSchema {
+            properties: self
+                .properties
+                .into_iter()
+                .map(|(name, definition)| (name, definition.for_version(version)))
+                .collect(),
+        }
+    }
+}
+
+impl IntoIterator for DatabaseSchema {
+    type Item = (String, PropertyDefinition);
+    type IntoIter = std::collections::btree_map::IntoIter<String, PropertyDefinition>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.properties.into_iter()
+    }
+}
+
+fn select<I>(options: I) -> SelectDefinition
+where
+    I: IntoIterator,
+    I::Item: Into<OptionDefinition>,
+{
+    SelectDefinition {
+        options: options.into_iter().map(Into::into).collect(),
+    }
+}
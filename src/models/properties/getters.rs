@@ -0,0 +1,371 @@
+//! Typed access to the values of [Properties].
+//!
+//! Properties are looked up by a [PropertyKey]: a name, a property id,
+//! or a name matched ignoring case and surrounding whitespace.
+//! Each getter returns `Ok(None)` if there is no such property
+//! or the property is empty, and [WrongPropertyType] if the property has another type.
+
+use std::borrow::Cow;
+
+use crate::ids::{PageId, PropertyId};
+use crate::models::properties::{DateValue, FileReference, PropertyValue, SelectedValue};
+use crate::models::text::{self, RichText};
+use crate::models::users::UserReference;
+use crate::models::{Number, Page, Properties};
+
+/// A property read with a getter for another property type.
+#[derive(Debug, Eq, PartialEq, Clone, thiserror::Error)]
+#[error("Property {name:?} is a {found} property, not a {expected} property")]
+pub struct WrongPropertyType {
+    pub name: String,
+    pub expected: &'static str,
+    pub found: &'static str,
+}
+
+/// How to find a property in [Properties].
+///
+/// `&str` converts to [PropertyKey::Name] and `&PropertyId` to [PropertyKey::Id].
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum PropertyKey<'a> {
+    /// The exact property name.
+    Name(&'a str),
+    /// The property id, which stays the same when the property is renamed.
+    Id(&'a PropertyId),
+    /// The property name, ignoring case and surrounding whitespace.
+    /// An exact match is preferred.
+    LooseName(&'a str),
+}
+
+impl<'a> PropertyKey<'a> {
+    pub fn loose(name: &'a str) -> Self {
+        PropertyKey::LooseName(name)
+    }
+}
+
+impl<'a> From<&'a str> for PropertyKey<'a> {
+    fn from(name: &'a str) -> Self {
+        PropertyKey::Name(name)
+    }
+}
+
+impl<'a> From<&'a String> for PropertyKey<'a> {
+    fn from(name: &'a String) -> Self {
+        PropertyKey::Name(name)
+    }
+}
+
+impl<'a> From<&'a PropertyId> for PropertyKey<'a> {
+    fn from(id: &'a PropertyId) -> Self {
+        PropertyKey::Id(id)
+    }
+}
+
+impl PropertyValue {
+    pub fn id(&self) -> &PropertyId {
+        use PropertyValue::*;
+        match self {
+            Title { id, .. }
+            | Text { id, .. }
+            | Number { id, .. }
+            | Select { id, .. }
+            | Status { id, .. }
+            | MultiSelect { id, .. }
+            | Date { id, .. }
+            | Formula { id, .. }
+            | Relation { id, .. }
+            | Rollup { id, .. }
+            | People { id, .. }
+            | Files { id, .. }
+            | Checkbox { id, .. }
+            | Url { id, .. }
+            | Email { id, .. }
+            | PhoneNumber { id, .. }
+            | CreatedTime { id, .. }
+            | CreatedBy { id, .. }
+            | LastEditedTime { id, .. }
+            | LastEditedBy { id, .. } => id,
+        }
+    }
+
+    /// The `type` of the property as the API names it, e.g. `rich_text`.
+    pub fn type_name(&self) -> &'static str {
+        use PropertyValue::*;
+        match self {
+            Title { .. } => "title",
+            Text { .. } => "rich_text",
+            Number { .. } => "number",
+            Select { .. } => "select",
+            Status { .. } => "status",
+            MultiSelect { .. } => "multi_select",
+            Date { .. } => "date",
+            Formula { .. } => "formula",
+            Relation { .. } => "relation",
+            Rollup { .. } => "rollup",
+            People { .. } => "people",
+            Files { .. } => "files",
+            Checkbox { .. } => "checkbox",
+            Url { .. } => "url",
+            Email { .. } => "email",
+            PhoneNumber { .. } => "phone_number",
+            CreatedTime { .. } => "created_time",
+            CreatedBy { .. } => "created_by",
+            LastEditedTime { .. } => "last_edited_time",
+            LastEditedBy { .. } => "last_edited_by",
+        }
+    }
+}
+
+impl Properties {
+    /// The name and value of the property `key` refers to.
+    pub fn lookup<'k, K: Into<PropertyKey<'k>>>(
+        &self,
+        key: K,
+    ) -> Option<(&str, &PropertyValue)> {
+        fn entry<'a>(
+            (name, value): (&'a String, &'a PropertyValue)
+        ) -> (&'a str, &'a PropertyValue) {
+            (name, value)
+        }
+
+        match key.into() {
+            PropertyKey::Name(name) => self.properties.get_key_value(name).map(entry),
+            PropertyKey::Id(id) => self
+                .properties
+                .iter()
+                .find(|(_, value)| value.id() == id)
+                .map(entry),
+            PropertyKey::LooseName(name) => {
+                self.properties.get_key_value(name).map(entry).or_else(|| {
+                    let name = name.trim().to_lowercase();
+                    self.properties
+                        .iter()
+                        .find(|(key, _)| key.trim().to_lowercase() == name)
+                        .map(entry)
+                })
+            }
+        }
+    }
+
+    /// The plain text of the title property.
+    pub fn get_title(&self) -> Option<Cow<'_, str>> {
+        self.properties.values().find_map(|value| match value {
+            PropertyValue::Title { title, .. } => Some(text::plain_text(title)),
+            _ => None,
+        })
+    }
+
+    /// The plain text of the text property `name`.
+    pub fn get_text<'k, K: Into<PropertyKey<'k>>>(
+        &self,
+        key: K,
+    ) -> Result<Option<Cow<'_, str>>, WrongPropertyType> {
+        self.get(key.into(), "rich_text", |value| match value {
+            PropertyValue::Text { rich_text, .. } => Some(non_empty(rich_text)),
+            _ => None,
+        })
+    }
+
+    pub fn get_number<'k, K: Into<PropertyKey<'k>>>(
+        &self,
+        key: K,
+    ) -> Result<Option<&Number>, WrongPropertyType> {
+        self.get(key.into(), "number", |value| match value {
+            PropertyValue::Number { number, .. } => Some(number.as_ref()),
+            _ => None,
+        })
+    }
+
+    pub fn get_date<'k, K: Into<PropertyKey<'k>>>(
+        &self,
+        key: K,
+    ) -> Result<Option<&DateValue>, WrongPropertyType> {
+        self.get(key.into(), "date", |value| match value {
+            PropertyValue::Date { date, .. } => Some(date.as_ref()),
+            _ => None,
+        })
+    }
+
+    pub fn get_checkbox<'k, K: Into<PropertyKey<'k>>>(
+        &self,
+        key: K,
+    ) -> Result<Option<bool>, WrongPropertyType> {
+        self.get(key.into(), "checkbox", |value| match value {
+            PropertyValue::Checkbox { checkbox, .. } => Some(Some(*checkbox)),
+            _ => None,
+        })
+    }
+
+    pub fn get_select<'k, K: Into<PropertyKey<'k>>>(
+        &self,
+        key: K,
+    ) -> Result<Option<&SelectedValue>, WrongPropertyType> {
+        self.get(key.into(), "select", |value| match value {
+            PropertyValue::Select { select, .. } => Some(select.as_ref()),
+            _ => None,
+        })
+    }
+
+    pub fn get_status<'k, K: Into<PropertyKey<'k>>>(
+        &self,
+        key: K,
+    ) -> Result<Option<&SelectedValue>, WrongPropertyType> {
+        self.get(key.into(), "status", |value| match value {
+            PropertyValue::Status { status, .. } => Some(status.as_ref()),
+            _ => None,
+        })
+    }
+
+    pub fn get_multi_select<'k, K: Into<PropertyKey<'k>>>(
+        &self,
+        key: K,
+    ) -> Result<Option<&[SelectedValue]>, WrongPropertyType> {
+        self.get(key.into(), "multi_select", |value| match value {
+            PropertyValue::MultiSelect { multi_select, .. } => Some(multi_select.as_deref()),
+            _ => None,
+        })
+    }
+
+    /// The pages of the relation property `name`.
+    /// Notion includes at most 25 of them, see [PropertyValue::Relation].
+    pub fn get_relation<'k, K: Into<PropertyKey<'k>>>(
+        &self,
+        key: K,
+    ) -> Result<Option<Vec<&PageId>>, WrongPropertyType> {
+        self.get(key.into(), "relation", |value| match value {
+            PropertyValue::Relation { relation, .. } => Some(
+                relation
+                    .as_ref()
+                    .map(|relation| relation.iter().map(|page| &page.id).collect()),
+            ),
+            _ => None,
+        })
+    }
+
+    /// The ids of the pages of the relation property `name`, empty if there is no such property.
+    pub fn get_relation_ids<'k, K: Into<PropertyKey<'k>>>(
+        &self,
+        key: K,
+    ) -> Result<Vec<PageId>, WrongPropertyType> {
+        let ids = self.get_relation(key)?.unwrap_or_default();
+        Ok(ids.into_iter().cloned().collect())
+    }
+
+    pub fn get_people<'k, K: Into<PropertyKey<'k>>>(
+        &self,
+        key: K,
+    ) -> Result<Option<&[UserReference]>, WrongPropertyType> {
+        self.get(key.into(), "people", |value| match value {
+            PropertyValue::People { people, .. } => Some(Some(people.as_slice())),
+            _ => None,
+        })
+    }
+
+    pub fn get_files<'k, K: Into<PropertyKey<'k>>>(
+        &self,
+        key: K,
+    ) -> Result<Option<&[FileReference]>, WrongPropertyType> {
+        self.get(key.into(), "files", |value| match value {
+            PropertyValue::Files { files, .. } => Some(files.as_deref()),
+            _ => None,
+        })
+    }
+
+    pub fn get_url<'k, K: Into<PropertyKey<'k>>>(
+        &self,
+        key: K,
+    ) -> Result<Option<&str>, WrongPropertyType> {
+        self.get(key.into(), "url", |value| match value {
+            PropertyValue::Url { url, .. } => Some(url.as_deref()),
+            _ => None,
+        })
+    }
+
+    pub fn get_email<'k, K: Into<PropertyKey<'k>>>(
+        &self,
+        key: K,
+    ) -> Result<Option<&str>, WrongPropertyType> {
+        self.get(key.into(), "email", |value| match value {
+            PropertyValue::Email { email, .. } => Some(email.as_deref()),
+            _ => None,
+        })
+    }
+
+    pub fn get_phone_number<'k, K: Into<PropertyKey<'k>>>(
+        &self,
+        key: K,
+    ) -> Result<Option<&str>, WrongPropertyType> {
+        self.get(key.into(), "phone_number", |value| match value {
+            PropertyValue::PhoneNumber { phone_number, .. } => Some(phone_number.as_deref()),
+            _ => None,
+        })
+    }
+
+    /// Looks up `key` and converts its value with `convert`,
+    /// which returns `None` for values of another type.
+    fn get<'a, T>(
+        &'a self,
+        key: PropertyKey<'_>,
+        expected: &'static str,
+        convert: impl FnOnce(&'a PropertyValue) -> Option<Option<T>>,
+    ) -> Result<Option<T>, WrongPropertyType> {
+        let (name, value) = match self.lookup(key) {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+
+        convert(value).ok_or_else(|| WrongPropertyType {
+            name: name.to_string(),
+            expected,
+            found: value.type_name(),
+        })
+    }
+}
+
+/// Forwards the getters of [Properties] to [Page::properties].
+macro_rules! page_getters {
+    ($($name:ident -> $value:ty;)*) => {
+        impl Page {
+            $(
+                #[doc = concat!("See [Properties::", stringify!($name), "()].")]
+                pub fn $name<'k, K: Into<PropertyKey<'k>>>(
+                    &self,
+                    key: K,
+                ) -> Result<$value, WrongPropertyType> {
+                    self.properties.$name(key)
+                }
+            )*
+        }
+    };
+}
+
+page_getters! {
+    get_text -> Option<Cow<'_, str>>;
+    get_number -> Option<&Number>;
+    get_date -> Option<&DateValue>;
+    get_checkbox -> Option<bool>;
+    get_select -> Option<&SelectedValue>;
+    get_status -> Option<&SelectedValue>;
+    get_multi_select -> Option<&[SelectedValue]>;
+    get_relation -> Option<Vec<&PageId>>;
+    get_relation_ids -> Vec<PageId>;
+    get_people -> Option<&[UserReference]>;
+    get_files -> Option<&[FileReference]>;
+    get_url -> Option<&str>;
+    get_email -> Option<&str>;
+    get_phone_number -> Option<&str>;
+}
+
+impl Page {
+    /// The plain text of the title, empty if the page has none.
+    pub fn title_text(&self) -> Cow<'_, str> {
+        self.properties.get_title().unwrap_or_default()
+    }
+}
+
+fn non_empty(rich_text: &[RichText]) -> Option<Cow<'_, str>> {
+    if rich_text.is_empty() {
+        None
+    } else {
+        Some(text::plain_text(rich_text))
+    }
+}
@@ -1,12 +1,21 @@
+use crate::ids::{DatabaseId, PropertyId};
 use crate::models::{
-    properties::{DateOrDateTime, RollupPropertyValue, RollupValue},
-    PropertyValue,
+    properties::validated::{Email, PhoneNumber, Url},
+    properties::{
+        dates::InvalidDate,
+        getters::{PropertyKey, WrongPropertyType},
+        DateOrDateTime, DateValue, DualProperty, Relation, RelationType, RollupPropertyValue,
+        RollupValue,
+    },
+    Page, PropertyValue,
 };
+use crate::ApiVersion;
 use chrono::NaiveDate;
+use std::str::FromStr;
 
 #[test]
 fn verify_date_parsing() {
-    let date = NaiveDate::from_ymd_opt(2021, 01, 02).unwrap();
+    let date = NaiveDate::from_ymd_opt(2021, 1, 2).unwrap();
     let result = serde_json::to_string(&DateOrDateTime::Date(date)).unwrap();
     let parsed: DateOrDateTime = serde_json::from_str(&result).unwrap();
     println!("{:?}", parsed);
@@ -57,3 +66,339 @@ fn parse_rollup_property() {
         assert!(matches!(array[0], RollupPropertyValue::Text { .. }))
     }
 }
+
+#[test]
+fn parse_partial_created_by_property() {
+    let property: PropertyValue =
+        serde_json::from_str(include_str!("tests/created_by_property.json")).unwrap();
+
+    match property {
+        PropertyValue::CreatedBy { created_by, .. } => {
+            assert_eq!(
+                created_by.id().to_string(),
+                "e2507360-468c-4e0f-a928-7bbcbbb45353"
+            );
+            assert_eq!(created_by.user(), None);
+        }
+        property => panic!("Expected a created_by property, got {:?}", property),
+    }
+}
+
+#[test]
+fn validated_emails() {
+    for valid in ["ada@example.com", "first.last+tag@mail.example.co.uk"] {
+        assert!(valid.parse::<Email>().is_ok(), "{}", valid);
+    }
+    for invalid in [
+        "",
+        "ada",
+        "@example.com",
+        "ada@",
+        "ada@example",
+        "a@b@c.com",
+        "a b@c.com",
+    ] {
+        assert!(invalid.parse::<Email>().is_err(), "{}", invalid);
+    }
+}
+
+#[test]
+fn validated_phone_numbers() {
+    for valid in ["+1 (555) 010-0100", "555.0100", "0123456789"] {
+        assert!(valid.parse::<PhoneNumber>().is_ok(), "{}", valid);
+    }
+    for invalid in ["", "+", "call me", "555-0100 ext. 2", "1+2"] {
+        assert!(invalid.parse::<PhoneNumber>().is_err(), "{}", invalid);
+    }
+}
+
+#[test]
+fn validated_urls() {
+    for valid in [
+        "https://example.com",
+        "mailto:ada@example.com",
+        "git+ssh://host/repo",
+    ] {
+        assert!(valid.parse::<Url>().is_ok(), "{}", valid);
+    }
+    let too_long = format!("https://example.com/{}", "a".repeat(2000));
+    for invalid in [
+        "example.com",
+        "https://",
+        "https://exa mple.com",
+        "1http://x",
+        &too_long,
+    ] {
+        assert!(invalid.parse::<Url>().is_err(), "{}", invalid);
+    }
+}
+
+#[test]
+fn serialize_validated_values() {
+    let id = PropertyId::from_str("mail").unwrap();
+    let value = PropertyValue::email(id, "ada@example.com".parse().unwrap());
+    assert_eq!(
+        serde_json::to_value(value).unwrap(),
+        serde_json::json!({"id": "mail", "type": "email", "email": "ada@example.com"})
+    );
+}
+
+#[test]
+fn parse_relation_configurations() {
+    let legacy: Relation = serde_json::from_value(serde_json::json!({
+        "database_id": "4c1f3a2e-2b2d-4dd1-b2c8-0d5c0a1e2f3b",
+        "synced_property_name": "Back",
+        "synced_property_id": "back"
+    }))
+    .unwrap();
+    assert_eq!(legacy.relation_type, None);
+    assert_eq!(legacy.synced_property_name.as_deref(), Some("Back"));
+
+    let dual: Relation = serde_json::from_value(serde_json::json!({
+        "database_id": "4c1f3a2e-2b2d-4dd1-b2c8-0d5c0a1e2f3b",
+        "type": "dual_property",
+        "dual_property": {"synced_property_name": "Back", "synced_property_id": "back"}
+    }))
+    .unwrap();
+    assert_eq!(
+        dual.relation_type,
+        Some(RelationType::DualProperty {
+            dual_property: DualProperty {
+                synced_property_name: Some("Back".to_string()),
+                synced_property_id: Some(PropertyId::from_str("back").unwrap()),
+            }
+        })
+    );
+
+    let single: Relation = serde_json::from_value(serde_json::json!({
+        "database_id": "4c1f3a2e-2b2d-4dd1-b2c8-0d5c0a1e2f3b",
+        "type": "single_property",
+        "single_property": {}
+    }))
+    .unwrap();
+    assert!(matches!(
+        single.relation_type,
+        Some(RelationType::SingleProperty { .. })
+    ));
+}
+
+#[test]
+fn relation_for_version() {
+    let legacy = Relation {
+        database_id: DatabaseId::from_str("db").unwrap(),
+        data_source_id: None,
+        synced_property_name: Some("Back".to_string()),
+        synced_property_id: None,
+        relation_type: None,
+    };
+
+    let current = legacy.clone().for_version(ApiVersion::V2022_06_28);
+    assert_eq!(current.synced_property_name, None);
+    assert_eq!(current.synced_property_name(), Some("Back"));
+    assert_eq!(
+        serde_json::to_value(&current).unwrap(),
+        serde_json::json!({
+            "database_id": "db",
+            "type": "dual_property",
+            "dual_property": {"synced_property_name": "Back", "synced_property_id": null}
+        })
+    );
+    assert_eq!(current.for_version(ApiVersion::V2022_02_22), legacy);
+}
+
+#[test]
+fn typed_getters() {
+    let page: Page = serde_json::from_str(include_str!("../tests/golden/page.json")).unwrap();
+    let properties = &page.properties;
+
+    assert_eq!(properties.get_title().as_deref(), Some("Every property"));
+    assert_eq!(
+        properties.get_number("Budget").unwrap().unwrap().as_f64(),
+        Some(12.5)
+    );
+    assert_eq!(properties.get_number("Empty number"), Ok(None));
+    assert_eq!(properties.get_number("Missing"), Ok(None));
+    assert_eq!(
+        properties.get_date("Due").unwrap().unwrap().start,
+        DateOrDateTime::Date(NaiveDate::from_ymd_opt(2022, 5, 14).unwrap())
+    );
+    assert_eq!(
+        properties
+            .get_select("Priority")
+            .unwrap()
+            .unwrap()
+            .name
+            .as_deref(),
+        Some("High")
+    );
+    assert_eq!(
+        properties.get_multi_select("Tags").unwrap().unwrap().len(),
+        2
+    );
+    assert_eq!(
+        properties.get_relation("Related").unwrap().unwrap().len(),
+        1
+    );
+    assert_eq!(properties.get_checkbox("Done"), Ok(Some(true)));
+    assert_eq!(
+        properties.get_url("Website"),
+        Ok(Some("https://example.com"))
+    );
+    assert_eq!(properties.get_phone_number("Fax"), Ok(None));
+    assert!(properties
+        .get_text("Notes")
+        .unwrap()
+        .unwrap()
+        .starts_with("Some "));
+
+    assert_eq!(
+        properties.get_number("Due"),
+        Err(WrongPropertyType {
+            name: "Due".to_string(),
+            expected: "number",
+            found: "date",
+        })
+    );
+    assert_eq!(
+        properties.get_text("Name").unwrap_err().to_string(),
+        "Property \"Name\" is a title property, not a rich_text property"
+    );
+}
+
+#[test]
+fn property_lookup() {
+    let page: Page = serde_json::from_str(include_str!("../tests/golden/page.json")).unwrap();
+    let properties = &page.properties;
+    let budget_id = PropertyId::from_str("num1").unwrap();
+
+    assert_eq!(properties.lookup(&budget_id).unwrap().0, "Budget");
+    assert_eq!(
+        properties.get_number(&budget_id),
+        properties.get_number("Budget")
+    );
+    assert_eq!(properties.lookup(" budget "), None);
+    assert_eq!(
+        properties.lookup(PropertyKey::loose(" budget ")).unwrap().0,
+        "Budget"
+    );
+    assert_eq!(
+        properties.get_date(PropertyKey::loose("DUE")).unwrap(),
+        properties.get_date("Due").unwrap()
+    );
+    assert_eq!(
+        properties
+            .get_number(PropertyKey::loose("due"))
+            .unwrap_err()
+            .name,
+        "Due"
+    );
+    assert_eq!(
+        properties.lookup(&PropertyId::from_str("missing").unwrap()),
+        None
+    );
+}
+
+#[test]
+fn date_strings() {
+    let date: DateOrDateTime = "2022-05-14".parse().unwrap();
+    assert!(date.is_date_only());
+    assert_eq!(serde_json::to_value(date).unwrap(), "2022-05-14");
+
+    for datetime in [
+        "2022-05-14T13:00:00.000Z",
+        "2022-05-14T13:00:00Z",
+        "2022-05-14T09:00:00.000-04:00",
+    ]
+    .iter()
+    {
+        let parsed: DateOrDateTime = datetime.parse().unwrap();
+        assert!(!parsed.is_date_only());
+        assert_eq!(parsed.to_string(), "2022-05-14T13:00:00.000Z");
+        assert_eq!(parsed.to_date_only(), date);
+        assert_eq!(
+            serde_json::from_value::<DateOrDateTime>(serde_json::json!(datetime)).unwrap(),
+            parsed
+        );
+    }
+
+    assert_eq!(
+        "14/05/2022".parse::<DateOrDateTime>(),
+        Err(InvalidDate("14/05/2022".to_string()))
+    );
+    assert!(serde_json::from_str::<DateOrDateTime>("\"2022-05-14 13:00\"").is_err());
+}
+
+#[test]
+fn date_values_for_date_only_properties() {
+    let meeting = DateValue::range(
+        "2022-05-14T09:00:00-04:00"
+            .parse::<DateOrDateTime>()
+            .unwrap(),
+        "2022-05-15T10:00:00-04:00".parse().unwrap(),
+    );
+
+    assert_eq!(
+        serde_json::to_value(meeting.to_date_only()).unwrap(),
+        serde_json::json!({"start": "2022-05-14", "end": "2022-05-15", "time_zone": null})
+    );
+    assert_eq!(
+        DateValue::new(NaiveDate::from_ymd_opt(2022, 5, 14).unwrap()),
+        DateValue {
+            start: DateOrDateTime::Date(NaiveDate::from_ymd_opt(2022, 5, 14).unwrap()),
+            end: None,
+            time_zone: None,
+        }
+    );
+}
+
+#[cfg(feature = "databases")]
+#[test]
+fn serialize_database_schema() {
+    use crate::models::properties::definitions::DatabaseSchema;
+    use crate::models::properties::{Color, NumberFormat};
+    use crate::models::DatabaseUpdateRequest;
+
+    let projects = DatabaseId::from_str("b55c9c91-384d-452b-81db-d1ef79372b75").unwrap();
+    let schema = DatabaseSchema::new()
+        .title("Name")
+        .select("Status", ["Todo", "Doing"])
+        .multi_select("Tags", [("Urgent", Color::Red)])
+        .number("Price", NumberFormat::Dollar)
+        .one_way_relation("Project", projects.clone());
+
+    assert_eq!(
+        serde_json::to_value(&schema).unwrap(),
+        serde_json::json!({
+            "Name": {"title": {}},
+            "Status": {"select": {"options": [{"name": "Todo"}, {"name": "Doing"}]}},
+            "Tags": {"multi_select": {"options": [{"name": "Urgent", "color": "red"}]}},
+            "Price": {"number": {"format": "dollar"}},
+            "Project": {"relation": {
+                "database_id": "b55c9c91-384d-452b-81db-d1ef79372b75",
+                "type": "single_property",
+                "single_property": {}
+            }},
+        })
+    );
+    assert_eq!(
+        serde_json::to_value(schema.for_version(ApiVersion::V2022_02_22)).unwrap()["Project"],
+        serde_json::json!({"relation": {"database_id": "b55c9c91-384d-452b-81db-d1ef79372b75"}})
+    );
+
+    let update = DatabaseUpdateRequest::default()
+        .title("Tasks")
+        .define(DatabaseSchema::new().checkbox("Done"))
+        .rename("Status", "State")
+        .remove("Tags");
+    assert_eq!(
+        serde_json::to_value(update).unwrap(),
+        serde_json::json!({
+            "title": [{"type": "text", "text": {"content": "Tasks", "link": null}, "plain_text": "Tasks"}],
+            "properties": {
+                "Done": {"checkbox": {}},
+                "Status": {"name": "State"},
+                "Tags": null,
+            },
+        })
+    );
+}
@@ -0,0 +1,198 @@
+//! Email, phone number and URL values that are checked before they are written.
+//!
+//! The API answers malformed values with a generic `validation_error`,
+//! these types reject them locally with a clearer message.
+//!
+//! ```
+//! use notion_wasi::ids::PropertyId;
+//! use notion_wasi::models::properties::validated::Email;
+//! use notion_wasi::models::properties::PropertyValue;
+//! use std::str::FromStr;
+//!
+//! let email: Email = "ada@example.com".parse().unwrap();
+//! let value = PropertyValue::email(PropertyId::from_str("mail").unwrap(), email);
+//!
+//! assert!("not an email".parse::<Email>().is_err());
+//! ```
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use crate::ids::PropertyId;
+use crate::models::properties::PropertyValue;
+
+/// Notion rejects URLs longer than this.
+const MAX_URL_LENGTH: usize = 2000;
+
+/// A value rejected by [Email], [PhoneNumber] or [Url].
+#[derive(Debug, Eq, PartialEq, Clone, thiserror::Error)]
+#[error("Invalid {kind} {value:?}: {reason}")]
+pub struct InvalidValue {
+    pub kind: &'static str,
+    pub value: String,
+    pub reason: &'static str,
+}
+
+impl InvalidValue {
+    fn new(
+        kind: &'static str,
+        value: &str,
+        reason: &'static str,
+    ) -> Self {
+        InvalidValue {
+            kind,
+            value: value.to_string(),
+            reason,
+        }
+    }
+}
+
+macro_rules! validated {
+    ($name:ident, $validate:ident) => {
+        #[derive(Serialize, Debug, Eq, PartialEq, Hash, Clone)]
+        #[serde(transparent)]
+        pub struct $name(String);
+
+        impl $name {
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = InvalidValue;
+
+            fn from_str(value: &str) -> Result<Self, Self::Err> {
+                $validate(value)?;
+                Ok($name(value.to_string()))
+            }
+        }
+
+        impl TryFrom<String> for $name {
+            type Error = InvalidValue;
+
+            fn try_from(value: String) -> Result<Self, Self::Error> {
+                $validate(&value)?;
+                Ok($name(value))
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(
+                &self,
+                f: &mut fmt::Formatter<'_>,
+            ) -> fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+    };
+}
+
+validated!(Email, validate_email);
+validated!(PhoneNumber, validate_phone_number);
+validated!(Url, validate_url);
+
+/// `local@domain.tld`, without whitespace.
+fn validate_email(value: &str) -> Result<(), InvalidValue> {
+    let invalid = |reason| Err(InvalidValue::new("email", value, reason));
+
+    if value.chars().any(char::is_whitespace) {
+        return invalid("contains whitespace");
+    }
+    match value.split_once('@') {
+        Some((local, domain)) if !local.is_empty() && !domain.contains('@') => {
+            let labels: Vec<&str> = domain.split('.').collect();
+            if labels.len() < 2 || labels.iter().any(|label| label.is_empty()) {
+                invalid("domain must look like `example.com`")
+            } else {
+                Ok(())
+            }
+        }
+        _ => invalid("expected exactly one `@` after a non-empty name"),
+    }
+}
+
+/// Digits with optional separators and a leading `+`.
+fn validate_phone_number(value: &str) -> Result<(), InvalidValue> {
+    let invalid = |reason| Err(InvalidValue::new("phone number", value, reason));
+
+    let number = value.strip_prefix('+').unwrap_or(value);
+    if !number.chars().any(|c| c.is_ascii_digit()) {
+        return invalid("contains no digits");
+    }
+    if !number
+        .chars()
+        .all(|c| c.is_ascii_digit() || matches!(c, ' ' | '-' | '.' | '(' | ')'))
+    {
+        return invalid("only digits, spaces, `-`, `.`, parentheses and a leading `+` are allowed");
+    }
+    Ok(())
+}
+
+/// An absolute URL such as `https://example.com`, up to 2000 characters.
+fn validate_url(value: &str) -> Result<(), InvalidValue> {
+    let invalid = |reason| Err(InvalidValue::new("url", value, reason));
+
+    if value.len() > MAX_URL_LENGTH {
+        return invalid("longer than 2000 characters");
+    }
+    if value.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return invalid("contains whitespace");
+    }
+    let (scheme, rest) = match value.split_once(':') {
+        Some(parts) => parts,
+        None => return invalid("missing a scheme like `https:`"),
+    };
+    let valid_scheme = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    if !valid_scheme {
+        return invalid("missing a scheme like `https:`");
+    }
+    if rest.trim_start_matches('/').is_empty() {
+        return invalid("nothing after the scheme");
+    }
+    Ok(())
+}
+
+impl PropertyValue {
+    pub fn email(
+        id: PropertyId,
+        email: Email,
+    ) -> Self {
+        PropertyValue::Email {
+            id,
+            email: Some(email.into()),
+        }
+    }
+
+    pub fn phone_number(
+        id: PropertyId,
+        phone_number: PhoneNumber,
+    ) -> Self {
+        PropertyValue::PhoneNumber {
+            id,
+            phone_number: Some(phone_number.into()),
+        }
+    }
+
+    pub fn url(
+        id: PropertyId,
+        url: Url,
+    ) -> Self {
+        PropertyValue::Url {
+            id,
+            url: Some(url.into()),
+        }
+    }
+}
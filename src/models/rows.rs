@@ -0,0 +1,348 @@
+//! Mapping the pages of a database to the program's own types.
+//!
+//! Implement [FromPage] to read a row and [IntoProperties] to write one,
+//! then use them through [NotionApi::typed_database()](crate::NotionApi::typed_database())
+//! or [NotionApi::query_database_as()](crate::NotionApi::query_database_as()).
+//! Properties are read as plain Rust types with [Page::property_as()], see [FromProperty].
+//!
+//! There are no derive macros, which would need a companion proc-macro crate.
+//! [from_page!](crate::from_page) implements [FromPage] for structs whose fields
+//! are each read from one property, and [IntoProperties] is implemented by hand.
+//!
+//! ```
+//! use chrono::NaiveDate;
+//! use notion_wasi::from_page;
+//!
+//! struct Task {
+//!     name: String,
+//!     done: bool,
+//!     estimate: Option<f64>,
+//!     tags: Vec<String>,
+//!     due: Option<NaiveDate>,
+//! }
+//!
+//! from_page!(Task {
+//!     name: "Name",
+//!     done: "Done",
+//!     estimate: "Estimate",
+//!     tags: "Tags",
+//!     due: "Due",
+//! });
+//! ```
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::ids::PageId;
+use crate::models::properties::{DateValue, FormulaResultValue, PropertyValue};
+use crate::models::text::plain_text;
+use crate::models::{Page, Properties};
+
+/// Why [FromPage::from_page()] could not read a page.
+#[derive(Debug, Eq, PartialEq, Clone, thiserror::Error)]
+pub enum RowError {
+    #[error("The page has no property {property:?}")]
+    MissingProperty { property: String },
+    #[error("Property {property:?} is not a {expected} property")]
+    WrongType {
+        property: String,
+        expected: &'static str,
+    },
+    #[error("Property {property:?} has an invalid value: {message}")]
+    InvalidValue { property: String, message: String },
+    #[error("Property {property:?} is empty")]
+    Empty { property: String },
+}
+
+impl RowError {
+    pub fn missing<S: Into<String>>(property: S) -> Self {
+        RowError::MissingProperty {
+            property: property.into(),
+        }
+    }
+
+    pub fn empty<S: Into<String>>(property: S) -> Self {
+        RowError::Empty {
+            property: property.into(),
+        }
+    }
+
+    pub fn wrong_type<S: Into<String>>(
+        property: S,
+        expected: &'static str,
+    ) -> Self {
+        RowError::WrongType {
+            property: property.into(),
+            expected,
+        }
+    }
+}
+
+/// A type that can be read from the properties of a database page,
+/// usually implemented with [from_page!](crate::from_page).
+pub trait FromPage: Sized {
+    fn from_page(page: &Page) -> Result<Self, RowError>;
+}
+
+/// Implements [FromPage] for a struct, reading each field from the property
+/// named after it with [Page::property_as()].
+///
+/// ```
+/// use notion_wasi::from_page;
+/// use notion_wasi::models::rows::FromPage;
+/// use notion_wasi::models::Page;
+///
+/// struct Task {
+///     name: String,
+///     estimate: Option<f64>,
+/// }
+///
+/// from_page!(Task {
+///     name: "Name",
+///     estimate: "Estimate",
+/// });
+///
+/// # fn read(page: &Page) -> Result<(), notion_wasi::models::rows::RowError> {
+/// let task = Task::from_page(page)?;
+/// # Ok(())
+/// # }
+/// ```
+#[macro_export]
+macro_rules! from_page {
+    ($row:ident { $($field:ident: $property:expr),* $(,)? }) => {
+        impl $crate::models::rows::FromPage for $row {
+            fn from_page(
+                page: &$crate::models::Page,
+            ) -> ::std::result::Result<Self, $crate::models::rows::RowError> {
+                ::std::result::Result::Ok($row {
+                    $($field: page.property_as($property)?),*
+                })
+            }
+        }
+    };
+}
+
+/// A type that can be written as the properties of a database page.
+///
+/// Only the returned properties are set, so leaving one out keeps its value
+/// when updating a page.
+pub trait IntoProperties {
+    fn into_properties(self) -> Properties;
+}
+
+/// A type that can be read from the value of a single property,
+/// see [Page::property_as()].
+///
+/// Empty values fail with [RowError::Empty], read an `Option` to allow them.
+pub trait FromProperty: Sized {
+    fn from_property(
+        name: &str,
+        value: &PropertyValue,
+    ) -> Result<Self, RowError>;
+
+    /// The value when the page has no property `name`.
+    fn from_missing(name: &str) -> Result<Self, RowError> {
+        Err(RowError::missing(name))
+    }
+}
+
+impl Properties {
+    /// The property `name` read as `T`.
+    pub fn get_as<T: FromProperty>(
+        &self,
+        name: &str,
+    ) -> Result<T, RowError> {
+        match self.properties.get(name) {
+            Some(value) => T::from_property(name, value),
+            None => T::from_missing(name),
+        }
+    }
+}
+
+impl Page {
+    /// The property `name` read as `T`, see [FromProperty].
+    pub fn property_as<T: FromProperty>(
+        &self,
+        name: &str,
+    ) -> Result<T, RowError> {
+        self.properties.get_as(name)
+    }
+}
+
+/// `None` for missing properties and empty values.
+impl<T: FromProperty> FromProperty for Option<T> {
+    fn from_property(
+        name: &str,
+        value: &PropertyValue,
+    ) -> Result<Self, RowError> {
+        match T::from_property(name, value) {
+            Ok(value) => Ok(Some(value)),
+            Err(RowError::Empty { .. }) => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    fn from_missing(_name: &str) -> Result<Self, RowError> {
+        Ok(None)
+    }
+}
+
+/// The plain text of title and text properties, the name of selects and statuses,
+/// urls, emails, phone numbers and string formulas.
+impl FromProperty for String {
+    fn from_property(
+        name: &str,
+        value: &PropertyValue,
+    ) -> Result<Self, RowError> {
+        use PropertyValue::*;
+        let text = match value {
+            Title { title: text, .. }
+            | Text {
+                rich_text: text, ..
+            } if !text.is_empty() => Some(plain_text(text).into_owned()),
+            Title { .. } | Text { .. } => None,
+            Select { select, .. } | Status { status: select, .. } => {
+                select.as_ref().and_then(|select| select.name.clone())
+            }
+            Url { url: text, .. }
+            | Email { email: text, .. }
+            | PhoneNumber {
+                phone_number: text, ..
+            }
+            | Formula {
+                formula: FormulaResultValue::String { string: text },
+                ..
+            } => text.clone(),
+            _ => return Err(RowError::wrong_type(name, "text")),
+        };
+        text.ok_or_else(|| RowError::empty(name))
+    }
+}
+
+/// Number properties and number formulas.
+impl FromProperty for f64 {
+    fn from_property(
+        name: &str,
+        value: &PropertyValue,
+    ) -> Result<Self, RowError> {
+        let number = match value {
+            PropertyValue::Number { number, .. }
+            | PropertyValue::Formula {
+                formula: FormulaResultValue::Number { number },
+                ..
+            } => number.as_ref().ok_or_else(|| RowError::empty(name))?,
+            _ => return Err(RowError::wrong_type(name, "number")),
+        };
+        number.as_f64().ok_or_else(|| RowError::InvalidValue {
+            property: name.to_string(),
+            message: format!("{} is out of range", number),
+        })
+    }
+}
+
+/// Checkboxes and boolean formulas.
+impl FromProperty for bool {
+    fn from_property(
+        name: &str,
+        value: &PropertyValue,
+    ) -> Result<Self, RowError> {
+        match value {
+            PropertyValue::Checkbox { checkbox, .. } => Ok(*checkbox),
+            PropertyValue::Formula {
+                formula: FormulaResultValue::Boolean { boolean },
+                ..
+            } => boolean.ok_or_else(|| RowError::empty(name)),
+            _ => Err(RowError::wrong_type(name, "checkbox")),
+        }
+    }
+}
+
+/// The names of the options of a multi-select property.
+impl FromProperty for Vec<String> {
+    fn from_property(
+        name: &str,
+        value: &PropertyValue,
+    ) -> Result<Self, RowError> {
+        match value {
+            PropertyValue::MultiSelect { multi_select, .. } => Ok(multi_select
+                .iter()
+                .flatten()
+                .filter_map(|option| option.name.clone())
+                .collect()),
+            _ => Err(RowError::wrong_type(name, "multi_select")),
+        }
+    }
+}
+
+/// The related pages, at most 25 unless fetched with
+/// [NotionApi::get_full_property()](crate::NotionApi::get_full_property()).
+impl FromProperty for Vec<PageId> {
+    fn from_property(
+        name: &str,
+        value: &PropertyValue,
+    ) -> Result<Self, RowError> {
+        match value {
+            PropertyValue::Relation { relation, .. } => Ok(relation
+                .iter()
+                .flatten()
+                .map(|page| page.id.clone())
+                .collect()),
+            _ => Err(RowError::wrong_type(name, "relation")),
+        }
+    }
+}
+
+/// Date properties and date formulas.
+impl FromProperty for DateValue {
+    fn from_property(
+        name: &str,
+        value: &PropertyValue,
+    ) -> Result<Self, RowError> {
+        match value {
+            PropertyValue::Date { date, .. }
+            | PropertyValue::Formula {
+                formula: FormulaResultValue::Date { date },
+                ..
+            } => date.clone().ok_or_else(|| RowError::empty(name)),
+            _ => Err(RowError::wrong_type(name, "date")),
+        }
+    }
+}
+
+/// The day a date starts on.
+impl FromProperty for NaiveDate {
+    fn from_property(
+        name: &str,
+        value: &PropertyValue,
+    ) -> Result<Self, RowError> {
+        DateValue::from_property(name, value).map(|date| date.start.date_naive())
+    }
+}
+
+/// The start of a date, midnight UTC for dates without a time,
+/// and the created and last edited times.
+impl FromProperty for DateTime<Utc> {
+    fn from_property(
+        name: &str,
+        value: &PropertyValue,
+    ) -> Result<Self, RowError> {
+        match value {
+            PropertyValue::CreatedTime { created_time, .. } => Ok(*created_time),
+            PropertyValue::LastEditedTime {
+                last_edited_time, ..
+            } => Ok(*last_edited_time),
+            value => DateValue::from_property(name, value).map(|date| date.start.to_datetime()),
+        }
+    }
+}
+
+impl FromPage for Page {
+    fn from_page(page: &Page) -> Result<Self, RowError> {
+        Ok(page.clone())
+    }
+}
+
+impl IntoProperties for Properties {
+    fn into_properties(self) -> Properties {
+        self
+    }
+}
@@ -0,0 +1,377 @@
+//! Comparing the schema a program expects with the live schema of a database.
+//!
+//! [diff_schema()] finds the properties and select options that are missing
+//! and can be added safely, and the [SchemaConflict]s that would need a
+//! destructive or unsupported change.
+//! [NotionApi::ensure_database_schema()](crate::NotionApi::ensure_database_schema())
+//! applies the additions and reports the conflicts.
+
+use std::collections::{BTreeMap, HashMap};
+
+#[cfg(feature = "client")]
+use serde_json::Map;
+use serde_json::{json, Value};
+
+use crate::models::properties::{PropertyConfiguration, SelectOption};
+
+/// A difference between two schemas that isn't applied automatically.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum SchemaConflict {
+    /// A live property the desired schema doesn't have. Removing it would delete its values.
+    ExtraProperty { property: String },
+    /// A property with a different type in the live schema.
+    /// Changing the type can lose values.
+    ChangedType {
+        property: String,
+        live: String,
+        desired: String,
+    },
+    /// The title property has a different name in the live schema.
+    /// A database has a single title property, so it can't be added.
+    RenamedTitle { live: String, desired: String },
+    /// A live option of a select or multi-select property the desired schema doesn't have.
+    /// Removing it would clear it from the pages that use it.
+    ExtraOption { property: String, option: String },
+    /// A property whose configuration differs in other ways, such as the number format,
+    /// a formula or the options of a status property, which the API can't change.
+    ChangedConfiguration { property: String },
+}
+
+/// The result of [diff_schema()].
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct SchemaDiff {
+    /// Properties of the desired schema the live schema lacks, by name.
+    pub missing_properties: BTreeMap<String, PropertyConfiguration>,
+    /// Options of select and multi-select properties the live schema lacks, by property name.
+    pub missing_options: BTreeMap<String, Vec<SelectOption>>,
+    /// Differences that would need a destructive change, in property order.
+    pub conflicts: Vec<SchemaConflict>,
+}
+
+impl SchemaDiff {
+    /// Whether nothing needs to be added. There may still be [conflicts](Self::conflicts).
+    pub fn is_empty(&self) -> bool {
+        self.missing_properties.is_empty() && self.missing_options.is_empty()
+    }
+
+    /// The `properties` of the request that adds the missing properties and options.
+    #[cfg(feature = "client")]
+    pub(crate) fn update(
+        &self,
+        live: &HashMap<String, PropertyConfiguration>,
+    ) -> Value {
+        let mut properties = Map::new();
+        for (name, configuration) in &self.missing_properties {
+            let (kind, mut details) = schema_value(configuration);
+            if let Some(options) = details.get_mut("options").and_then(Value::as_array_mut) {
+                options.iter_mut().for_each(remove_id);
+            }
+            properties.insert(name.clone(), json!({ kind: details }));
+        }
+        for (name, missing) in &self.missing_options {
+            let (kind, details) = match live.get(name) {
+                Some(configuration) => schema_value(configuration),
+                None => continue,
+            };
+            let mut options = details["options"].as_array().cloned().unwrap_or_default();
+            options.extend(missing.iter().map(|option| {
+                let mut option = serde_json::to_value(option).unwrap();
+                remove_id(&mut option);
+                option
+            }));
+            properties.insert(name.clone(), json!({ kind: { "options": options } }));
+        }
+        Value::Object(properties)
+    }
+}
+
+/// Compares the `live` schema of a database with the `desired` one, matching properties by name.
+///
+/// The ids of the desired schema are ignored, as are the colors of options that exist in both.
+/// Configuration details present only in the live schema, such as the synced property of a
+/// relation, don't count as differences.
+pub fn diff_schema(
+    live: &HashMap<String, PropertyConfiguration>,
+    desired: &HashMap<String, PropertyConfiguration>,
+) -> SchemaDiff {
+    let mut diff = SchemaDiff::default();
+    let live_title = live
+        .iter()
+        .find(|(_, configuration)| matches!(configuration, PropertyConfiguration::Title { .. }))
+        .map(|(name, _)| name);
+
+    let mut names: Vec<_> = desired.keys().collect();
+    names.sort();
+    for name in names {
+        let wanted = &desired[name];
+        let existing = match live.get(name) {
+            Some(existing) => existing,
+            None => {
+                match (wanted, live_title) {
+                    (PropertyConfiguration::Title { .. }, Some(live_title)) => {
+                        diff.conflicts.push(SchemaConflict::RenamedTitle {
+                            live: live_title.clone(),
+                            desired: name.clone(),
+                        })
+                    }
+                    _ => {
+                        diff.missing_properties.insert(name.clone(), wanted.clone());
+                    }
+                }
+                continue;
+            }
+        };
+
+        let (live_kind, live_details) = schema_value(existing);
+        let (kind, details) = schema_value(wanted);
+        if live_kind != kind {
+            diff.conflicts.push(SchemaConflict::ChangedType {
+                property: name.clone(),
+                live: live_kind,
+                desired: kind,
+            });
+            continue;
+        }
+
+        match (existing, wanted) {
+            (
+                PropertyConfiguration::Select {
+                    select: live_select,
+                    ..
+                },
+                PropertyConfiguration::Select { select, .. },
+            )
+            | (
+                PropertyConfiguration::MultiSelect {
+                    multi_select: live_select,
+                    ..
+                },
+                PropertyConfiguration::MultiSelect {
+                    multi_select: select,
+                    ..
+                },
+            ) => {
+                let missing: Vec<_> = select
+                    .options
+                    .iter()
+                    .filter(|option| !has_option(&live_select.options, &option.name))
+                    .cloned()
+                    .collect();
+                if !missing.is_empty() {
+                    diff.missing_options.insert(name.clone(), missing);
+                }
+                for option in &live_select.options {
+                    if !has_option(&select.options, &option.name) {
+                        diff.conflicts.push(SchemaConflict::ExtraOption {
+                            property: name.clone(),
+                            option: option.name.clone(),
+                        });
+                    }
+                }
+            }
+            _ => {
+                if !contains(&live_details, &details) {
+                    diff.conflicts.push(SchemaConflict::ChangedConfiguration {
+                        property: name.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    let mut extra: Vec<_> = live
+        .keys()
+        .filter(|name| !desired.contains_key(*name))
+        .filter(|name| Some(*name) != live_title || !conflicts_on_title(&diff))
+        .collect();
+    extra.sort();
+    diff.conflicts
+        .extend(extra.into_iter().map(|name| SchemaConflict::ExtraProperty {
+            property: name.clone(),
+        }));
+    diff
+}
+
+fn conflicts_on_title(diff: &SchemaDiff) -> bool {
+    diff.conflicts
+        .iter()
+        .any(|conflict| matches!(conflict, SchemaConflict::RenamedTitle { .. }))
+}
+
+fn has_option(
+    options: &[SelectOption],
+    name: &str,
+) -> bool {
+    options.iter().any(|option| option.name == name)
+}
+
+/// The type of a property configuration and its details, without the id,
+/// as in `{"number": {"format": "dollar"}}`.
+fn schema_value(configuration: &PropertyConfiguration) -> (String, Value) {
+    let mut value = serde_json::to_value(configuration).unwrap();
+    let kind = value["type"].as_str().unwrap_or_default().to_string();
+    let details = value
+        .as_object_mut()
+        .and_then(|fields| fields.remove(&kind))
+        .unwrap_or_else(|| json!({}));
+    (kind, details)
+}
+
+#[cfg(feature = "client")]
+fn remove_id(value: &mut Value) {
+    if let Some(fields) = value.as_object_mut() {
+        fields.remove("id");
+    }
+}
+
+/// Whether every field of `desired` but the ids has the same value in `live`.
+fn contains(
+    live: &Value,
+    desired: &Value,
+) -> bool {
+    match (live, desired) {
+        (Value::Object(live), Value::Object(desired)) => desired
+            .iter()
+            .filter(|(name, _)| name.as_str() != "id")
+            .all(|(name, value)| live.get(name).is_some_and(|live| contains(live, value))),
+        (Value::Array(live), Value::Array(desired)) => {
+            live.len() == desired.len()
+                && live
+                    .iter()
+                    .zip(desired)
+                    .all(|(live, desired)| contains(live, desired))
+        }
+        _ => live == desired,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff_schema, SchemaConflict};
+    use crate::ids::PropertyId;
+    use crate::models::properties::{
+        Color, NumberDetails, NumberFormat, PropertyConfiguration, Select, SelectOption,
+        SelectOptionId,
+    };
+    #[cfg(feature = "client")]
+    use serde_json::json;
+    use std::collections::HashMap;
+    use std::str::FromStr;
+
+    fn id() -> PropertyId {
+        PropertyId::from_str("id").unwrap()
+    }
+
+    fn tags(options: &[&str]) -> PropertyConfiguration {
+        PropertyConfiguration::MultiSelect {
+            id: id(),
+            multi_select: Select {
+                options: options
+                    .iter()
+                    .map(|name| SelectOption {
+                        name: name.to_string(),
+                        id: SelectOptionId::from(name.to_string()),
+                        color: Color::Default,
+                    })
+                    .collect(),
+            },
+        }
+    }
+
+    fn schema(
+        properties: Vec<(&str, PropertyConfiguration)>
+    ) -> HashMap<String, PropertyConfiguration> {
+        properties
+            .into_iter()
+            .map(|(name, configuration)| (name.to_string(), configuration))
+            .collect()
+    }
+
+    #[test]
+    fn adds_missing_properties_and_options() {
+        let live = schema(vec![
+            ("Name", PropertyConfiguration::Title { id: id() }),
+            ("Tags", tags(&["a"])),
+        ]);
+        let desired = schema(vec![
+            ("Name", PropertyConfiguration::Title { id: id() }),
+            ("Tags", tags(&["a", "b"])),
+            ("Notes", PropertyConfiguration::Text { id: id() }),
+        ]);
+
+        let diff = diff_schema(&live, &desired);
+        assert!(diff.conflicts.is_empty());
+        assert_eq!(
+            diff.missing_properties.keys().collect::<Vec<_>>(),
+            vec!["Notes"]
+        );
+        #[cfg(feature = "client")]
+        assert_eq!(
+            diff.update(&live),
+            json!({
+                "Notes": { "rich_text": {} },
+                "Tags": { "multi_select": { "options": [
+                    { "id": "a", "name": "a", "color": "default" },
+                    { "name": "b", "color": "default" }
+                ]}}
+            })
+        );
+        assert!(diff_schema(&live, &live).is_empty());
+    }
+
+    #[test]
+    fn reports_destructive_changes() {
+        let live = schema(vec![
+            ("Title", PropertyConfiguration::Title { id: id() }),
+            ("Tags", tags(&["a", "b"])),
+            ("Old", PropertyConfiguration::Checkbox { id: id() }),
+            (
+                "Price",
+                PropertyConfiguration::Number {
+                    id: id(),
+                    number: NumberDetails {
+                        format: NumberFormat::Dollar,
+                    },
+                },
+            ),
+        ]);
+        let desired = schema(vec![
+            ("Name", PropertyConfiguration::Title { id: id() }),
+            ("Tags", tags(&["a"])),
+            ("Old", PropertyConfiguration::Text { id: id() }),
+            (
+                "Price",
+                PropertyConfiguration::Number {
+                    id: id(),
+                    number: NumberDetails {
+                        format: NumberFormat::Euro,
+                    },
+                },
+            ),
+        ]);
+
+        let diff = diff_schema(&live, &desired);
+        assert!(diff.is_empty());
+        assert_eq!(
+            diff.conflicts,
+            vec![
+                SchemaConflict::RenamedTitle {
+                    live: "Title".to_string(),
+                    desired: "Name".to_string()
+                },
+                SchemaConflict::ChangedType {
+                    property: "Old".to_string(),
+                    live: "checkbox".to_string(),
+                    desired: "rich_text".to_string()
+                },
+                SchemaConflict::ChangedConfiguration {
+                    property: "Price".to_string()
+                },
+                SchemaConflict::ExtraOption {
+                    property: "Tags".to_string(),
+                    option: "b".to_string()
+                },
+            ]
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::ids::{PageId, UserId};
+use crate::ids::{PageId, PropertyId, UserId};
 use crate::models::paging::{Pageable, Paging, PagingCursor};
 use crate::models::Number;
 use chrono::{DateTime, Utc};
@@ -44,7 +44,7 @@ pub struct Filter {
     value: FilterValue,
 }
 
-#[derive(Serialize, Debug, Eq, PartialEq, Default)]
+#[derive(Serialize, Debug, Eq, PartialEq, Default, Clone)]
 pub struct SearchRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     query: Option<String>,
@@ -56,6 +56,54 @@ pub struct SearchRequest {
     paging: Option<Paging>,
 }
 
+impl SearchRequest {
+    /// Only returns results whose title contains `query`.
+    pub fn query<S: Into<String>>(
+        mut self,
+        query: S,
+    ) -> Self {
+        self.query = Some(query.into());
+        self
+    }
+
+    /// Sorts the results by `last_edited_time`, the only sort search supports.
+    /// Without a sort, results are ordered by relevance.
+    pub fn sort_by_last_edited(
+        mut self,
+        direction: SortDirection,
+    ) -> Self {
+        self.sort = Some(Sort {
+            timestamp: SortTimestamp::LastEditedTime,
+            direction,
+        });
+        self
+    }
+
+    /// Results per request, at most 100.
+    pub fn page_size(
+        mut self,
+        page_size: u8,
+    ) -> Self {
+        self.paging.get_or_insert_with(Paging::default).page_size = Some(page_size);
+        self
+    }
+
+    /// Limits the results to pages or databases, replacing any other filter.
+    #[cfg(feature = "client")]
+    pub(crate) fn only(
+        self,
+        value: FilterValue,
+    ) -> Self {
+        SearchRequest {
+            filter: Some(Filter {
+                property: FilterProperty::Object,
+                value,
+            }),
+            ..self
+        }
+    }
+}
+
 #[derive(Serialize, Debug, Eq, PartialEq, Clone)]
 #[serde(rename_all = "snake_case")]
 pub enum TextCondition {
@@ -185,6 +233,9 @@ pub enum DateCondition {
     /// Only return pages where the page property value is within the next year.
     #[serde(serialize_with = "serialize_to_empty_object")]
     NextYear,
+    /// Only return pages where the page property value is within the current week.
+    #[serde(serialize_with = "serialize_to_empty_object")]
+    ThisWeek,
 }
 
 #[derive(Serialize, Debug, Eq, PartialEq, Clone)]
@@ -247,6 +298,7 @@ pub enum FormulaCondition {
 #[derive(Serialize, Debug, Eq, PartialEq, Clone)]
 #[serde(rename_all = "snake_case")]
 pub enum PropertyCondition {
+    Title(TextCondition),
     RichText(TextCondition),
     Number(NumberCondition),
     Checkbox(CheckboxCondition),
@@ -285,6 +337,364 @@ pub enum FilterCondition {
     Or { or: Vec<FilterCondition> },
 }
 
+/// Relative date filters.
+///
+/// Relative conditions like [DateCondition::NextWeek] are evaluated by Notion
+/// in the time zone of the integration's workspace.
+impl FilterCondition {
+    pub fn date<S: Into<String>>(
+        property: S,
+        condition: DateCondition,
+    ) -> Self {
+        FilterCondition::Property {
+            property: property.into(),
+            condition: PropertyCondition::Date(condition),
+        }
+    }
+
+    /// Pages whose date `property` falls within the next 7 days.
+    pub fn next_week<S: Into<String>>(property: S) -> Self {
+        Self::date(property, DateCondition::NextWeek)
+    }
+
+    /// The same as [next_week()](Self::next_week()), for due dates.
+    pub fn due_within_next_week<S: Into<String>>(property: S) -> Self {
+        Self::next_week(property)
+    }
+
+    pub fn next_month<S: Into<String>>(property: S) -> Self {
+        Self::date(property, DateCondition::NextMonth)
+    }
+
+    pub fn next_year<S: Into<String>>(property: S) -> Self {
+        Self::date(property, DateCondition::NextYear)
+    }
+
+    pub fn past_week<S: Into<String>>(property: S) -> Self {
+        Self::date(property, DateCondition::PastWeek)
+    }
+
+    pub fn past_month<S: Into<String>>(property: S) -> Self {
+        Self::date(property, DateCondition::PastMonth)
+    }
+
+    pub fn past_year<S: Into<String>>(property: S) -> Self {
+        Self::date(property, DateCondition::PastYear)
+    }
+
+    pub fn this_week<S: Into<String>>(property: S) -> Self {
+        Self::date(property, DateCondition::ThisWeek)
+    }
+
+    /// Pages whose date `property` is from `start` up to `days` days later, both inclusive.
+    ///
+    /// Use this when "next week" should mean something other than the next 7 days,
+    /// e.g. `within_days("Due", Utc::now(), 3)`.
+    pub fn within_days<S: Into<String>>(
+        property: S,
+        start: DateTime<Utc>,
+        days: i64,
+    ) -> Self {
+        let property = property.into();
+        FilterCondition::And {
+            and: vec![
+                Self::date(property.clone(), DateCondition::OnOrAfter(start)),
+                Self::date(
+                    property,
+                    DateCondition::OnOrBefore(start + chrono::Duration::days(days)),
+                ),
+            ],
+        }
+    }
+
+    /// Pages whose date `property` is before `now`.
+    /// Pages without a date don't match.
+    pub fn overdue<S: Into<String>>(
+        property: S,
+        now: DateTime<Utc>,
+    ) -> Self {
+        Self::date(property, DateCondition::Before(now))
+    }
+}
+
+/// Compound filters.
+impl FilterCondition {
+    /// Starts a filter on the property `name`, see [PropertyFilter].
+    pub fn property<S: Into<String>>(name: S) -> PropertyFilter {
+        PropertyFilter {
+            property: name.into(),
+        }
+    }
+
+    pub fn created_time(condition: DateCondition) -> Self {
+        FilterCondition::Timestamp {
+            timestamp: "created_time".to_string(),
+            condition: TimestampCondition::CreatedTime(condition),
+        }
+    }
+
+    pub fn last_edited_time(condition: DateCondition) -> Self {
+        FilterCondition::Timestamp {
+            timestamp: "last_edited_time".to_string(),
+            condition: TimestampCondition::LastEditedTime(condition),
+        }
+    }
+
+    /// Pages matching this filter and `other`.
+    /// `and` filters are extended instead of nested.
+    pub fn and(
+        self,
+        other: FilterCondition,
+    ) -> Self {
+        match self {
+            FilterCondition::And { mut and } => {
+                and.push(other);
+                FilterCondition::And { and }
+            }
+            filter => FilterCondition::And {
+                and: vec![filter, other],
+            },
+        }
+    }
+
+    /// Pages matching this filter or `other`.
+    /// `or` filters are extended instead of nested.
+    pub fn or(
+        self,
+        other: FilterCondition,
+    ) -> Self {
+        match self {
+            FilterCondition::Or { mut or } => {
+                or.push(other);
+                FilterCondition::Or { or }
+            }
+            filter => FilterCondition::Or {
+                or: vec![filter, other],
+            },
+        }
+    }
+}
+
+/// A filter on one property, created with [FilterCondition::property()].
+/// Each method picks the condition, and with it the type of the property.
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use notion_wasi::models::search::{DatabaseQuery, FilterCondition, SortDirection};
+///
+/// let due = Utc.with_ymd_and_hms(2022, 5, 14, 0, 0, 0).unwrap();
+/// let query = DatabaseQuery::builder()
+///     .filter(
+///         FilterCondition::property("Status")
+///             .select_equals("Done")
+///             .or(FilterCondition::property("Due").date_before(due)),
+///     )
+///     .filter(FilterCondition::property("Archived").checkbox(false))
+///     .sort_by("Due", SortDirection::Ascending)
+///     .try_build()
+///     .unwrap();
+/// assert!(query.filter.is_some());
+/// ```
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct PropertyFilter {
+    property: String,
+}
+
+impl PropertyFilter {
+    /// The filter with any `condition`.
+    pub fn condition(
+        self,
+        condition: PropertyCondition,
+    ) -> FilterCondition {
+        FilterCondition::Property {
+            property: self.property,
+            condition,
+        }
+    }
+
+    pub fn title(
+        self,
+        condition: TextCondition,
+    ) -> FilterCondition {
+        self.condition(PropertyCondition::Title(condition))
+    }
+
+    pub fn title_equals<S: Into<String>>(
+        self,
+        title: S,
+    ) -> FilterCondition {
+        self.title(TextCondition::Equals(title.into()))
+    }
+
+    pub fn title_contains<S: Into<String>>(
+        self,
+        text: S,
+    ) -> FilterCondition {
+        self.title(TextCondition::Contains(text.into()))
+    }
+
+    pub fn rich_text(
+        self,
+        condition: TextCondition,
+    ) -> FilterCondition {
+        self.condition(PropertyCondition::RichText(condition))
+    }
+
+    pub fn text_equals<S: Into<String>>(
+        self,
+        text: S,
+    ) -> FilterCondition {
+        self.rich_text(TextCondition::Equals(text.into()))
+    }
+
+    pub fn text_contains<S: Into<String>>(
+        self,
+        text: S,
+    ) -> FilterCondition {
+        self.rich_text(TextCondition::Contains(text.into()))
+    }
+
+    pub fn number(
+        self,
+        condition: NumberCondition,
+    ) -> FilterCondition {
+        self.condition(PropertyCondition::Number(condition))
+    }
+
+    pub fn number_equals<N: Into<Number>>(
+        self,
+        number: N,
+    ) -> FilterCondition {
+        self.number(NumberCondition::Equals(number.into()))
+    }
+
+    pub fn greater_than<N: Into<Number>>(
+        self,
+        number: N,
+    ) -> FilterCondition {
+        self.number(NumberCondition::GreaterThan(number.into()))
+    }
+
+    pub fn less_than<N: Into<Number>>(
+        self,
+        number: N,
+    ) -> FilterCondition {
+        self.number(NumberCondition::LessThan(number.into()))
+    }
+
+    /// Pages whose checkbox is `checked`.
+    pub fn checkbox(
+        self,
+        checked: bool,
+    ) -> FilterCondition {
+        self.condition(PropertyCondition::Checkbox(CheckboxCondition::Equals(
+            checked,
+        )))
+    }
+
+    pub fn select(
+        self,
+        condition: SelectCondition,
+    ) -> FilterCondition {
+        self.condition(PropertyCondition::Select(condition))
+    }
+
+    pub fn select_equals<S: Into<String>>(
+        self,
+        option: S,
+    ) -> FilterCondition {
+        self.select(SelectCondition::Equals(option.into()))
+    }
+
+    pub fn select_does_not_equal<S: Into<String>>(
+        self,
+        option: S,
+    ) -> FilterCondition {
+        self.select(SelectCondition::DoesNotEqual(option.into()))
+    }
+
+    pub fn multi_select(
+        self,
+        condition: MultiSelectCondition,
+    ) -> FilterCondition {
+        self.condition(PropertyCondition::MultiSelect(condition))
+    }
+
+    pub fn multi_select_contains<S: Into<String>>(
+        self,
+        option: S,
+    ) -> FilterCondition {
+        self.multi_select(MultiSelectCondition::Contains(option.into()))
+    }
+
+    pub fn date(
+        self,
+        condition: DateCondition,
+    ) -> FilterCondition {
+        self.condition(PropertyCondition::Date(condition))
+    }
+
+    pub fn date_before(
+        self,
+        date: DateTime<Utc>,
+    ) -> FilterCondition {
+        self.date(DateCondition::Before(date))
+    }
+
+    pub fn date_after(
+        self,
+        date: DateTime<Utc>,
+    ) -> FilterCondition {
+        self.date(DateCondition::After(date))
+    }
+
+    pub fn date_on_or_before(
+        self,
+        date: DateTime<Utc>,
+    ) -> FilterCondition {
+        self.date(DateCondition::OnOrBefore(date))
+    }
+
+    pub fn date_on_or_after(
+        self,
+        date: DateTime<Utc>,
+    ) -> FilterCondition {
+        self.date(DateCondition::OnOrAfter(date))
+    }
+
+    pub fn people_contains(
+        self,
+        user_id: UserId,
+    ) -> FilterCondition {
+        self.condition(PropertyCondition::People(PeopleCondition::Contains(
+            user_id,
+        )))
+    }
+
+    pub fn files(
+        self,
+        condition: FilesCondition,
+    ) -> FilterCondition {
+        self.condition(PropertyCondition::Files(condition))
+    }
+
+    pub fn relation_contains(
+        self,
+        page_id: PageId,
+    ) -> FilterCondition {
+        self.condition(PropertyCondition::Relation(RelationCondition::Contains(
+            page_id,
+        )))
+    }
+
+    pub fn formula(
+        self,
+        condition: FormulaCondition,
+    ) -> FilterCondition {
+        self.condition(PropertyCondition::Formula(condition))
+    }
+}
+
 #[derive(Serialize, Debug, Eq, PartialEq, Hash, Copy, Clone)]
 #[serde(rename_all = "snake_case")]
 pub enum DatabaseSortTimestamp {
@@ -305,6 +715,23 @@ pub struct DatabaseSort {
     pub direction: SortDirection,
 }
 
+impl DatabaseSort {
+    pub fn ascending<S: Into<String>>(property: S) -> Self {
+        DatabaseSort {
+            property: Some(property.into()),
+            timestamp: None,
+            direction: SortDirection::Ascending,
+        }
+    }
+
+    pub fn descending<S: Into<String>>(property: S) -> Self {
+        DatabaseSort {
+            direction: SortDirection::Descending,
+            ..Self::ascending(property)
+        }
+    }
+}
+
 #[derive(Serialize, Debug, Eq, PartialEq, Default, Clone)]
 pub struct DatabaseQuery {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -313,6 +740,29 @@ pub struct DatabaseQuery {
     pub filter: Option<FilterCondition>,
     #[serde(flatten)]
     pub paging: Option<Paging>,
+    /// Only return these properties of the matching pages, see [Database::property_id()](crate::models::Database::property_id()).
+    /// Sent in the URL, all properties are returned when empty.
+    #[serde(skip)]
+    pub filter_properties: Vec<PropertyId>,
+    /// `true` to also return pages in the trash, which queries leave out by default.
+    /// API versions that don't support it ignore it and return no trashed pages.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub in_trash: Option<bool>,
+}
+
+impl Pageable for SearchRequest {
+    fn start_from(
+        self,
+        starting_point: Option<PagingCursor>,
+    ) -> Self {
+        SearchRequest {
+            paging: Some(Paging {
+                start_cursor: starting_point,
+                page_size: self.paging.and_then(|p| p.page_size),
+            }),
+            ..self
+        }
+    }
 }
 
 impl Pageable for DatabaseQuery {
@@ -330,6 +780,305 @@ impl Pageable for DatabaseQuery {
     }
 }
 
+impl DatabaseQuery {
+    /// Composes a query one filter or sort at a time.
+    ///
+    /// ```
+    /// use notion_wasi::models::search::{
+    ///     CheckboxCondition, DatabaseQuery, DatabaseSortTimestamp, FilterCondition,
+    ///     PropertyCondition, SortDirection,
+    /// };
+    ///
+    /// let query = DatabaseQuery::builder()
+    ///     .filter(FilterCondition::Property {
+    ///         property: "Done".to_string(),
+    ///         condition: PropertyCondition::Checkbox(CheckboxCondition::Equals(false)),
+    ///     })
+    ///     .filter(FilterCondition::due_within_next_week("Due"))
+    ///     .sort_by("Priority", SortDirection::Descending)
+    ///     .sort_by_timestamp(DatabaseSortTimestamp::CreatedTime, SortDirection::Ascending)
+    ///     .page_size(50)
+    ///     .build();
+    /// assert!(matches!(query.filter, Some(FilterCondition::And { ref and }) if and.len() == 2));
+    /// ```
+    pub fn builder() -> DatabaseQueryBuilder {
+        DatabaseQueryBuilder::default()
+    }
+}
+
+/// The most results Notion returns per request.
+const MAX_PAGE_SIZE: u8 = 100;
+
+/// Notion accepts compound filters nested this many levels deep.
+const MAX_FILTER_DEPTH: usize = 2;
+
+/// A [DatabaseQuery] that Notion would reject, found by [DatabaseQuery::validate()].
+#[derive(Debug, Eq, PartialEq, Clone, thiserror::Error)]
+pub enum InvalidQuery {
+    #[error("The page size must be between 1 and 100, got {page_size}")]
+    PageSize { page_size: u8 },
+    #[error(
+        "Compound filters are nested {depth} levels deep, but Notion allows at most 2; \
+         move the innermost `and`/`or` filters up a level"
+    )]
+    FilterTooDeep { depth: usize },
+    #[error("An `and` or `or` filter has no filters inside it")]
+    EmptyCompoundFilter,
+    #[error("A filter has an empty property name")]
+    EmptyFilterProperty,
+    #[error("Sort number {index} has neither a property nor a timestamp")]
+    SortWithoutKey { index: usize },
+    #[error("Sort number {index} has both a property and a timestamp, set only one of them")]
+    SortWithPropertyAndTimestamp { index: usize },
+    #[error("Sort number {index} has an empty property name")]
+    EmptySortProperty { index: usize },
+    #[error("The database has no property named {property:?}, used in a {used_in}")]
+    UnknownProperty {
+        property: String,
+        used_in: &'static str,
+    },
+}
+
+impl DatabaseQuery {
+    /// Checks the rules Notion applies to queries, so mistakes are reported
+    /// with a clear message instead of a generic `validation_error`.
+    ///
+    /// [NotionApi::query_database()](crate::NotionApi::query_database()) validates
+    /// each query before sending it.
+    pub fn validate(&self) -> Result<(), InvalidQuery> {
+        if let Some(page_size) = self.paging.as_ref().and_then(|paging| paging.page_size) {
+            if page_size == 0 || page_size > MAX_PAGE_SIZE {
+                return Err(InvalidQuery::PageSize { page_size });
+            }
+        }
+        if let Some(filter) = &self.filter {
+            let depth = filter.validate()?;
+            if depth > MAX_FILTER_DEPTH {
+                return Err(InvalidQuery::FilterTooDeep { depth });
+            }
+        }
+        for (index, sort) in self.sorts.iter().flatten().enumerate() {
+            match (&sort.property, &sort.timestamp) {
+                (None, None) => return Err(InvalidQuery::SortWithoutKey { index }),
+                (Some(_), Some(_)) => {
+                    return Err(InvalidQuery::SortWithPropertyAndTimestamp { index })
+                }
+                (Some(property), None) if property.is_empty() => {
+                    return Err(InvalidQuery::EmptySortProperty { index })
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Like [validate()](Self::validate()), and checks that the filters and sorts
+    /// only name properties in `property_names`, such as the keys of
+    /// [Database::properties](crate::models::Database::properties).
+    pub fn validate_properties<'a, I>(
+        &self,
+        property_names: I,
+    ) -> Result<(), InvalidQuery>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        self.validate()?;
+        let names: std::collections::HashSet<&str> = property_names.into_iter().collect();
+        let unknown = |property: &str, used_in| {
+            if names.contains(property) {
+                Ok(())
+            } else {
+                Err(InvalidQuery::UnknownProperty {
+                    property: property.to_string(),
+                    used_in,
+                })
+            }
+        };
+        if let Some(filter) = &self.filter {
+            filter.visit_properties(&mut |property| unknown(property, "filter"))?;
+        }
+        for property in self
+            .sorts
+            .iter()
+            .flatten()
+            .filter_map(|sort| sort.property.as_deref())
+        {
+            unknown(property, "sort")?;
+        }
+        Ok(())
+    }
+}
+
+impl FilterCondition {
+    /// The levels of compound filters, checking that none of them is empty.
+    fn validate(&self) -> Result<usize, InvalidQuery> {
+        match self {
+            FilterCondition::Property { property, .. } if property.is_empty() => {
+                Err(InvalidQuery::EmptyFilterProperty)
+            }
+            FilterCondition::Property { .. } | FilterCondition::Timestamp { .. } => Ok(0),
+            FilterCondition::And { and: filters } | FilterCondition::Or { or: filters } => {
+                if filters.is_empty() {
+                    return Err(InvalidQuery::EmptyCompoundFilter);
+                }
+                let mut depth = 0;
+                for filter in filters {
+                    depth = depth.max(filter.validate()?);
+                }
+                Ok(depth + 1)
+            }
+        }
+    }
+
+    fn visit_properties<F>(
+        &self,
+        visit: &mut F,
+    ) -> Result<(), InvalidQuery>
+    where
+        F: FnMut(&str) -> Result<(), InvalidQuery>,
+    {
+        match self {
+            FilterCondition::Property { property, .. } => visit(property),
+            FilterCondition::Timestamp { .. } => Ok(()),
+            FilterCondition::And { and: filters } | FilterCondition::Or { or: filters } => filters
+                .iter()
+                .try_for_each(|filter| filter.visit_properties(visit)),
+        }
+    }
+}
+
+/// Queries the pages matching `filter`, in the default order.
+impl From<FilterCondition> for DatabaseQuery {
+    fn from(filter: FilterCondition) -> Self {
+        DatabaseQuery {
+            filter: Some(filter),
+            ..Default::default()
+        }
+    }
+}
+
+/// Queries all pages, sorted by `sort`.
+impl From<DatabaseSort> for DatabaseQuery {
+    fn from(sort: DatabaseSort) -> Self {
+        DatabaseQuery {
+            sorts: Some(vec![sort]),
+            ..Default::default()
+        }
+    }
+}
+
+/// Queries all pages, sorted by `sorts` in order of precedence.
+impl From<Vec<DatabaseSort>> for DatabaseQuery {
+    fn from(sorts: Vec<DatabaseSort>) -> Self {
+        DatabaseQuery {
+            sorts: Some(sorts),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<DatabaseQueryBuilder> for DatabaseQuery {
+    fn from(builder: DatabaseQueryBuilder) -> Self {
+        builder.build()
+    }
+}
+
+/// Builds a [DatabaseQuery], created with [DatabaseQuery::builder()].
+#[derive(Debug, Eq, PartialEq, Default, Clone)]
+pub struct DatabaseQueryBuilder {
+    query: DatabaseQuery,
+}
+
+impl DatabaseQueryBuilder {
+    /// Adds a filter that pages must match, in addition to the filters added before.
+    pub fn filter(
+        mut self,
+        filter: FilterCondition,
+    ) -> Self {
+        self.query.filter = Some(match self.query.filter.take() {
+            None => filter,
+            Some(FilterCondition::And { mut and }) => {
+                and.push(filter);
+                FilterCondition::And { and }
+            }
+            Some(previous) => FilterCondition::And {
+                and: vec![previous, filter],
+            },
+        });
+        self
+    }
+
+    /// Adds a sort, applied after the sorts added before.
+    pub fn sort(
+        mut self,
+        sort: DatabaseSort,
+    ) -> Self {
+        self.query.sorts.get_or_insert_with(Vec::new).push(sort);
+        self
+    }
+
+    pub fn sort_by<S: Into<String>>(
+        self,
+        property: S,
+        direction: SortDirection,
+    ) -> Self {
+        self.sort(DatabaseSort {
+            property: Some(property.into()),
+            timestamp: None,
+            direction,
+        })
+    }
+
+    pub fn sort_by_timestamp(
+        self,
+        timestamp: DatabaseSortTimestamp,
+        direction: SortDirection,
+    ) -> Self {
+        self.sort(DatabaseSort {
+            property: None,
+            timestamp: Some(timestamp),
+            direction,
+        })
+    }
+
+    /// Results per request, at most 100.
+    pub fn page_size(
+        mut self,
+        page_size: u8,
+    ) -> Self {
+        self.query
+            .paging
+            .get_or_insert_with(Paging::default)
+            .page_size = Some(page_size);
+        self
+    }
+
+    /// See [DatabaseQuery::filter_properties].
+    pub fn filter_properties<I: IntoIterator<Item = PropertyId>>(
+        mut self,
+        property_ids: I,
+    ) -> Self {
+        self.query.filter_properties.extend(property_ids);
+        self
+    }
+
+    /// See [DatabaseQuery::in_trash].
+    pub fn include_trashed(mut self) -> Self {
+        self.query.in_trash = Some(true);
+        self
+    }
+
+    pub fn build(self) -> DatabaseQuery {
+        self.query
+    }
+
+    /// Builds the query, failing if Notion would reject it, see [DatabaseQuery::validate()].
+    pub fn try_build(self) -> Result<DatabaseQuery, InvalidQuery> {
+        self.query.validate()?;
+        Ok(self.query)
+    }
+}
+
 #[derive(Debug, Eq, PartialEq)]
 pub enum NotionSearch {
     /// When supplied, limits which pages are returned by comparing the query to the page title.
@@ -355,6 +1104,26 @@ pub enum NotionSearch {
 }
 
 impl NotionSearch {
+    pub fn query<S: Into<String>>(query: S) -> Self {
+        Self::Query(query.into())
+    }
+
+    /// Orders the results by when they were last edited.
+    /// Combine it with a query through [SearchRequest::sort_by_last_edited()].
+    pub fn sort_by_last_edited(direction: SortDirection) -> Self {
+        Self::Sort {
+            timestamp: SortTimestamp::LastEditedTime,
+            direction,
+        }
+    }
+
+    pub fn filter_by_pages() -> Self {
+        Self::Filter {
+            property: FilterProperty::Object,
+            value: FilterValue::Page,
+        }
+    }
+
     pub fn filter_by_databases() -> Self {
         Self::Filter {
             property: FilterProperty::Object,
@@ -390,6 +1159,56 @@ impl From<NotionSearch> for SearchRequest {
 
 #[cfg(test)]
 mod tests {
+    mod date_filters {
+        use crate::models::search::FilterCondition;
+        use chrono::{DateTime, Utc};
+        use serde_json::json;
+        use std::str::FromStr;
+
+        #[test]
+        fn relative_date_filters() {
+            let filter = |filter: FilterCondition| serde_json::to_value(filter).unwrap();
+
+            assert_eq!(
+                filter(FilterCondition::due_within_next_week("Due")),
+                json!({"property": "Due", "date": {"next_week": {}}})
+            );
+            assert_eq!(
+                filter(FilterCondition::next_week("Due")),
+                filter(FilterCondition::due_within_next_week("Due"))
+            );
+            assert_eq!(
+                filter(FilterCondition::next_year("Due")),
+                json!({"property": "Due", "date": {"next_year": {}}})
+            );
+            assert_eq!(
+                filter(FilterCondition::past_month("Done")),
+                json!({"property": "Done", "date": {"past_month": {}}})
+            );
+            assert_eq!(
+                filter(FilterCondition::this_week("Due")),
+                json!({"property": "Due", "date": {"this_week": {}}})
+            );
+        }
+
+        #[test]
+        fn absolute_date_ranges() {
+            let now = DateTime::<Utc>::from_str("2022-03-01T12:00:00Z").unwrap();
+
+            assert_eq!(
+                serde_json::to_value(FilterCondition::within_days("Due", now, 3)).unwrap(),
+                json!({"and": [
+                    {"property": "Due", "date": {"on_or_after": "2022-03-01T12:00:00Z"}},
+                    {"property": "Due", "date": {"on_or_before": "2022-03-04T12:00:00Z"}},
+                ]})
+            );
+            assert_eq!(
+                serde_json::to_value(FilterCondition::overdue("Due", now)).unwrap(),
+                json!({"property": "Due", "date": {"before": "2022-03-01T12:00:00Z"}})
+            );
+        }
+    }
+
     mod text_filters {
         use crate::models::search::PropertyCondition::{Checkbox, Number, RichText, Select};
         use crate::models::search::{
@@ -518,4 +1337,195 @@ mod tests {
             Ok(())
         }
     }
+
+    mod query_builder {
+        use crate::models::search::{
+            CheckboxCondition, DatabaseQuery, DatabaseSort, DatabaseSortTimestamp, DateCondition,
+            FilterCondition, InvalidQuery, PropertyCondition, SortDirection,
+        };
+        use chrono::{TimeZone, Utc};
+        use serde_json::json;
+
+        fn done(done: bool) -> FilterCondition {
+            FilterCondition::Property {
+                property: "Done".to_string(),
+                condition: PropertyCondition::Checkbox(CheckboxCondition::Equals(done)),
+            }
+        }
+
+        #[test]
+        fn property_filters() {
+            let due = Utc.with_ymd_and_hms(2022, 5, 14, 0, 0, 0).unwrap();
+            let filter = FilterCondition::property("Status")
+                .select_equals("Done")
+                .and(FilterCondition::property("Done").checkbox(true))
+                .and(
+                    FilterCondition::property("Due")
+                        .date_before(due)
+                        .or(FilterCondition::property("Estimate").greater_than(3))
+                        .or(FilterCondition::created_time(DateCondition::PastWeek)),
+                );
+            assert_eq!(
+                serde_json::to_value(&filter).unwrap(),
+                json!({"and": [
+                    {"property": "Status", "select": {"equals": "Done"}},
+                    {"property": "Done", "checkbox": {"equals": true}},
+                    {"or": [
+                        {"property": "Due", "date": {"before": "2022-05-14T00:00:00Z"}},
+                        {"property": "Estimate", "number": {"greater_than": 3}},
+                        {"timestamp": "created_time", "created_time": {"past_week": {}}},
+                    ]},
+                ]})
+            );
+            assert_eq!(FilterCondition::property("Done").checkbox(true), done(true));
+
+            let query = DatabaseQuery::builder()
+                .filter(filter)
+                .sort(DatabaseSort::descending("Priority"))
+                .try_build()
+                .unwrap();
+            assert_eq!(
+                serde_json::to_value(query).unwrap()["sorts"],
+                json!([{"property": "Priority", "direction": "descending"}])
+            );
+            assert_eq!(
+                DatabaseQuery::builder()
+                    .filter(FilterCondition::property("").title_contains("plan"))
+                    .try_build(),
+                Err(InvalidQuery::EmptyFilterProperty)
+            );
+        }
+
+        #[test]
+        fn from_filter_and_sorts() {
+            assert_eq!(
+                serde_json::to_value(DatabaseQuery::from(done(true))).unwrap(),
+                json!({"filter": {"property": "Done", "checkbox": {"equals": true}}})
+            );
+            let sort = DatabaseSort {
+                property: Some("Priority".to_string()),
+                timestamp: None,
+                direction: SortDirection::Descending,
+            };
+            assert_eq!(
+                serde_json::to_value(DatabaseQuery::from(sort)).unwrap(),
+                json!({"sorts": [{"property": "Priority", "direction": "descending"}]})
+            );
+        }
+
+        #[test]
+        fn builder_composes_filters_and_sorts() {
+            let query = DatabaseQuery::builder()
+                .filter(done(false))
+                .filter(FilterCondition::due_within_next_week("Due"))
+                .filter(done(true))
+                .sort_by("Priority", SortDirection::Descending)
+                .sort_by_timestamp(DatabaseSortTimestamp::CreatedTime, SortDirection::Ascending)
+                .page_size(10)
+                .build();
+
+            assert_eq!(
+                serde_json::to_value(query).unwrap(),
+                json!({
+                    "filter": {"and": [
+                        {"property": "Done", "checkbox": {"equals": false}},
+                        {"property": "Due", "date": {"next_week": {}}},
+                        {"property": "Done", "checkbox": {"equals": true}},
+                    ]},
+                    "sorts": [
+                        {"property": "Priority", "direction": "descending"},
+                        {"timestamp": "created_time", "direction": "ascending"},
+                    ],
+                    "page_size": 10,
+                })
+            );
+            assert_eq!(DatabaseQuery::builder().build(), DatabaseQuery::default());
+        }
+    }
+
+    mod query_validation {
+        use crate::models::paging::Paging;
+        use crate::models::search::{
+            DatabaseQuery, DatabaseSort, DatabaseSortTimestamp, FilterCondition, InvalidQuery,
+            SortDirection,
+        };
+
+        fn nested(depth: usize) -> FilterCondition {
+            (0..depth).fold(FilterCondition::due_within_next_week("Due"), |filter, _| {
+                FilterCondition::Or { or: vec![filter] }
+            })
+        }
+
+        #[test]
+        fn rejects_invalid_queries() {
+            let invalid = |query: DatabaseQuery| query.validate().unwrap_err();
+
+            assert_eq!(
+                invalid(DatabaseQuery {
+                    paging: Some(Paging {
+                        start_cursor: None,
+                        page_size: Some(101),
+                    }),
+                    ..Default::default()
+                }),
+                InvalidQuery::PageSize { page_size: 101 }
+            );
+            assert!(DatabaseQuery::from(nested(2)).validate().is_ok());
+            assert_eq!(
+                invalid(nested(3).into()),
+                InvalidQuery::FilterTooDeep { depth: 3 }
+            );
+            assert_eq!(
+                invalid(FilterCondition::And { and: vec![] }.into()),
+                InvalidQuery::EmptyCompoundFilter
+            );
+            assert_eq!(
+                invalid(
+                    DatabaseSort {
+                        property: None,
+                        timestamp: None,
+                        direction: SortDirection::Ascending,
+                    }
+                    .into()
+                ),
+                InvalidQuery::SortWithoutKey { index: 0 }
+            );
+            assert_eq!(
+                invalid(
+                    DatabaseQuery::builder()
+                        .sort_by_timestamp(
+                            DatabaseSortTimestamp::CreatedTime,
+                            SortDirection::Ascending
+                        )
+                        .sort_by("", SortDirection::Ascending)
+                        .build()
+                ),
+                InvalidQuery::EmptySortProperty { index: 1 }
+            );
+        }
+
+        #[test]
+        fn rejects_unknown_properties() {
+            let query = DatabaseQuery::builder()
+                .filter(nested(1))
+                .sort_by("Priority", SortDirection::Descending)
+                .build();
+
+            assert!(query.validate_properties(vec!["Due", "Priority"]).is_ok());
+            assert_eq!(
+                query.validate_properties(vec!["Due"]).unwrap_err(),
+                InvalidQuery::UnknownProperty {
+                    property: "Priority".to_string(),
+                    used_in: "sort"
+                }
+            );
+            assert_eq!(
+                query
+                    .validate_properties(vec!["Priority"])
+                    .unwrap_err()
+                    .to_string(),
+                "The database has no property named \"Due\", used in a filter"
+            );
+        }
+    }
 }
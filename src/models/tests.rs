@@ -1,16 +1,52 @@
-use crate::ids::UserId;
-use crate::models::properties::{DateOrDateTime, DateValue};
+#[cfg(all(feature = "blocks", feature = "databases"))]
+mod golden;
+
+use crate::ids::{BlockId, DataSourceId, DatabaseId, PageId, PropertyId, UserId};
+use crate::models::aggregate::{self, Aggregate, AggregateValue};
+use crate::models::columns::{ColumnValues, Columns};
+#[cfg(feature = "comments")]
+use crate::models::comments::{self, Comment, CommentCreateRequest};
+use crate::models::csv::{self, CsvOptions, FormulaFormat, PeopleFormat};
+use crate::models::files::{
+    Emoji, ExternalFileObject, FileObject, FileOrEmojiObject, InvalidEmoji,
+};
+use crate::models::ical::{self, IcalOptions};
+use crate::models::paging::{Cursor, Pageable, PagingCursor};
+#[cfg(feature = "databases")]
+use crate::models::properties::PropertyConfiguration;
+use crate::models::properties::{DateOrDateTime, DateValue, FormulaResultValue, PropertyValue};
+#[cfg(feature = "client")]
+use crate::models::properties::{PropertyItem, RollupPropertyValue};
+use crate::models::search::{NotionSearch, SearchRequest, SortDirection};
 use crate::models::text::{
-    Annotations, Link, MentionObject, RichText, RichTextCommon, Text, TextColor,
+    plain_text, Annotations, Link, MentionObject, PlainText, RichText, RichTextCommon,
+    TemplateMention, Text, TextColor,
 };
-use crate::models::users::{Person, User, UserCommon};
-use crate::models::{ListResponse, Object, Page};
+use crate::models::users::{Person, User, UserCommon, UserReference};
+use crate::models::webhooks::{parse_webhook, WebhookEntityType, WebhookEventType, WebhookPayload};
+use crate::models::{
+    ListResponse, Object, Page, PageCreateRequest, PageUpdateRequest, Parent, Properties,
+    TrashFilter,
+};
+use crate::test_util::{person, PageFixture};
 use chrono::{DateTime, NaiveDate};
+use std::borrow::Cow;
+use std::convert::TryFrom;
 use std::str::FromStr;
 
 #[test]
 fn deserialize_page() {
-    let _page: Page = serde_json::from_str(include_str!("tests/page.json")).unwrap();
+    let page: Page = serde_json::from_str(include_str!("tests/page.json")).unwrap();
+    assert_eq!(
+        page.url,
+        "https://www.notion.so/b55c9c91384d452b81dbd1ef79372b75"
+    );
+    assert_eq!(page.public_url, None);
+    assert_eq!(page.title_property(), Some("Name"));
+    assert_eq!(
+        page.last_edited_by.id,
+        UserId::from_str("6419f912-5293-4ea8-b2c8-9c3ce44f90e3").unwrap()
+    );
 }
 
 #[test]
@@ -20,9 +56,147 @@ fn deserialize_query_result() {
 }
 
 #[test]
+#[cfg(feature = "databases")]
 fn deserialize_number_format() {
-    let _search_results: ListResponse<Object> =
+    let search_results: ListResponse<Object> =
         serde_json::from_str(include_str!("tests/issue_15.json")).unwrap();
+
+    let databases = search_results.only_databases();
+    let database = &databases.results[0];
+    assert_eq!(database.url, database.id.view_url(None));
+}
+
+#[test]
+#[cfg(feature = "comments")]
+fn comment_threads() {
+    let list: ListResponse<Object> =
+        serde_json::from_str(include_str!("tests/comments.json")).unwrap();
+    let comments: Vec<Comment> = list
+        .results
+        .into_iter()
+        .map(|object| match object {
+            Object::Comment { comment } => comment,
+            object => panic!("not a comment: {:?}", object),
+        })
+        .collect();
+
+    let threads = comments::threads(comments);
+    assert_eq!(threads.len(), 2);
+    assert_eq!(threads[0].comments.len(), 1);
+    assert_eq!(
+        threads[1]
+            .comments
+            .iter()
+            .map(|comment| plain_text(&comment.rich_text).into_owned())
+            .collect::<Vec<_>>(),
+        vec!["Question", "Reply"]
+    );
+
+    let reply = threads[1].reply(vec![RichText::text("Answer")]);
+    assert_eq!(
+        serde_json::to_value(&reply).unwrap()["discussion_id"],
+        "a0b2a6f4-7c11-4a52-9d1f-0e0c8d6b7f21"
+    );
+    let comment = CommentCreateRequest::on_page(
+        PageId::from_str("5c6a2821-6bb1-4a7e-b6e1-c50111515c3d").unwrap(),
+        vec![RichText::text("New")],
+    );
+    assert_eq!(
+        serde_json::to_value(&comment).unwrap()["parent"],
+        serde_json::json!({"type": "page_id", "page_id": "5c6a2821-6bb1-4a7e-b6e1-c50111515c3d"})
+    );
+}
+
+#[test]
+fn database_view_url() {
+    let database_id = DatabaseId::from_str("58be2827-5ca0-4cc4-85a8-ff656911df67").unwrap();
+
+    assert_eq!(
+        database_id.view_url(Some("0a1b2c3d-0a1b-0a1b-0a1b-0a1b2c3d4e5f")),
+        "https://www.notion.so/58be28275ca04cc485a8ff656911df67?v=0a1b2c3d0a1b0a1b0a1b0a1b2c3d4e5f"
+    );
+}
+
+#[test]
+fn page_and_block_urls() {
+    let page_id = PageId::from_str("58be2827-5ca0-4cc4-85a8-ff656911df67").unwrap();
+    let block_id = BlockId::from_str("0a1b2c3d-0a1b-0a1b-0a1b-0a1b2c3d4e5f").unwrap();
+
+    assert_eq!(
+        page_id.to_url(),
+        "https://www.notion.so/58be28275ca04cc485a8ff656911df67"
+    );
+    assert_eq!(
+        block_id.to_url(&page_id),
+        "https://www.notion.so/58be28275ca04cc485a8ff656911df67#0a1b2c3d0a1b0a1b0a1b0a1b2c3d4e5f"
+    );
+}
+
+#[test]
+fn serialize_page_update_request() {
+    let update = PageUpdateRequest {
+        properties: Properties::default(),
+        archived: Some(true),
+        ..Default::default()
+    };
+    assert_eq!(
+        serde_json::to_value(&update).unwrap(),
+        serde_json::json!({"properties": {}, "archived": true})
+    );
+
+    let update: PageUpdateRequest = Properties::default().into();
+    assert_eq!(
+        serde_json::to_value(&update).unwrap(),
+        serde_json::json!({"properties": {}})
+    );
+
+    let update = PageUpdateRequest {
+        icon: Some(FileOrEmojiObject::Emoji {
+            emoji: Emoji::new("🎢").unwrap(),
+        }),
+        cover: Some(FileObject::External {
+            external: ExternalFileObject {
+                url: "https://example.com/cover.png".to_string(),
+            },
+        }),
+        ..PageUpdateRequest::restore()
+    };
+    assert_eq!(
+        serde_json::to_value(&update).unwrap(),
+        serde_json::json!({
+            "properties": {},
+            "archived": false,
+            "icon": {"type": "emoji", "emoji": "🎢"},
+            "cover": {"type": "external", "external": {"url": "https://example.com/cover.png"}},
+        })
+    );
+    assert_eq!(PageUpdateRequest::archive().archived, Some(true));
+
+    // Only the page of the search results, which also has databases.
+    let mut results: serde_json::Value =
+        serde_json::from_str(include_str!("tests/search_results.json")).unwrap();
+    let page: Page = serde_json::from_value(results["results"][1].take()).unwrap();
+    assert_eq!(
+        page.icon,
+        Some(FileOrEmojiObject::Emoji {
+            emoji: Emoji::new("🎢").unwrap()
+        })
+    );
+}
+
+#[test]
+fn deserialize_unknown_objects() {
+    let objects: ListResponse<Object> =
+        serde_json::from_str(include_str!("tests/search_results.json")).unwrap();
+    #[cfg(feature = "databases")]
+    assert!(objects.results[0].is_database());
+    #[cfg(not(feature = "databases"))]
+    assert_eq!(objects.results[0], Object::Unknown);
+    assert_eq!(objects.pages().count(), 1);
+
+    let object: Object =
+        serde_json::from_value(serde_json::json!({"object": "workspace", "id": "w"})).unwrap();
+    assert_eq!(object, Object::Unknown);
 }
 
 #[test]
@@ -74,7 +248,7 @@ fn rich_text_mention_user_person() {
         }),
       },
       mention: MentionObject::User {
-        user: User::Person {
+        user: UserReference::User(User::Person {
           common: UserCommon {
             id: UserId::from_str("1118608e-35e8-4fa3-aef7-a4ced85ce8e0").unwrap(),
             name: Some("John Doe".to_string()),
@@ -84,14 +258,64 @@ fn rich_text_mention_user_person() {
             ),
           },
           person: Person {
-            email: "john.doe@gmail.com".to_string()
+            email: Some("john.doe@gmail.com".to_string())
           },
-        }
+        })
       },
     }
   )
 }
 
+#[test]
+fn bot_users_of_public_integrations() {
+    let user: User = serde_json::from_value(serde_json::json!({
+        "object": "user",
+        "id": "16d84021-db59-4e4f-9e66-f2225ac12ec3",
+        "name": "Importer",
+        "avatar_url": null,
+        "type": "bot",
+        "bot": {
+            "owner": {
+                "type": "user",
+                "user": {"object": "user", "id": "e79a0b74-3aba-4149-9f74-0bb5791a6ee6"}
+            },
+            "workspace_name": "Acme"
+        }
+    }))
+    .unwrap();
+
+    assert_eq!(user.name(), Some("Importer"));
+    assert_eq!(user.workspace_name(), Some("Acme"));
+    let owner = user.bot().and_then(|bot| bot.owner.as_ref()).unwrap();
+    assert!(!owner.is_workspace());
+    assert_eq!(
+        owner.user().map(|user| user.id().to_string()),
+        Some("e79a0b74-3aba-4149-9f74-0bb5791a6ee6".to_string())
+    );
+    assert_eq!(person("Ann", "ann@example.com").workspace_name(), None);
+}
+
+#[test]
+fn rich_text_user_mention_by_id() {
+    let id = UserId::from_str("1118608e-35e8-4fa3-aef7-a4ced85ce8e0").unwrap();
+    let mention = RichText::user_mention(id.clone());
+    assert_eq!(mention.plain_text(), "@");
+    let json = serde_json::to_value(&mention).unwrap();
+    assert_eq!(
+        json["mention"],
+        serde_json::json!({"type": "user", "user": {"id": "1118608e-35e8-4fa3-aef7-a4ced85ce8e0"}})
+    );
+
+    let parsed: RichText = serde_json::from_value(json).unwrap();
+    assert!(matches!(
+        parsed,
+        RichText::Mention {
+            mention: MentionObject::User { user: UserReference::Partial { id: parsed } },
+            ..
+        } if parsed == id
+    ));
+}
+
 #[test]
 fn rich_text_mention_date() {
     let rich_text_mention_date: RichText =
@@ -222,3 +446,823 @@ fn rich_text_mention_date_with_end_and_time() {
         }
     )
 }
+
+#[test]
+fn plain_text_borrows_single_element() {
+    let rich_text_text: RichText =
+        serde_json::from_str(include_str!("tests/rich_text_text.json")).unwrap();
+
+    let single = vec![rich_text_text.clone()];
+    assert!(matches!(plain_text(&single), Cow::Borrowed("Rich")));
+
+    let many = vec![rich_text_text.clone(), rich_text_text];
+    assert!(matches!(plain_text(&many), Cow::Owned(text) if text == "RichRich"));
+}
+
+#[test]
+fn display_plain_text_titles() {
+    let rich_text_text: RichText =
+        serde_json::from_str(include_str!("tests/rich_text_text.json")).unwrap();
+    let many = vec![rich_text_text.clone(), rich_text_text.clone()];
+    assert_eq!(rich_text_text.to_string(), "Rich");
+    assert_eq!(PlainText(&many).to_string(), "RichRich");
+    assert_eq!(PlainText(&[]).to_string(), "");
+
+    let page = PageFixture::new().title("Roadmap").build();
+    assert_eq!(page.to_string(), "Roadmap");
+    #[cfg(feature = "databases")]
+    assert_eq!(
+        crate::test_util::DatabaseFixture::new()
+            .title("Tasks")
+            .build()
+            .to_string(),
+        "Tasks"
+    );
+    #[cfg(feature = "databases")]
+    assert_eq!(
+        crate::test_util::DatabaseFixture::new()
+            .build()
+            .title_property(),
+        Some("Name")
+    );
+    let untitled = PageFixture::new().title("").build();
+    assert_eq!(untitled.to_string(), "Untitled");
+}
+
+#[cfg(feature = "client")]
+#[test]
+fn deserialize_property_item_list() {
+    let list: ListResponse<Object> =
+        serde_json::from_str(include_str!("tests/property_item_rollup.json")).unwrap();
+    let items = list.expect_property_items().unwrap();
+
+    assert!(items.has_more);
+    assert!(matches!(items.results[0], PropertyItem::Relation { .. }));
+    assert!(matches!(
+        RollupPropertyValue::from(items.results[1].clone()),
+        RollupPropertyValue::Title { title } if title.len() == 1
+    ));
+}
+
+#[test]
+fn resumable_cursor() {
+    let list: ListResponse<Object> =
+        serde_json::from_str(include_str!("tests/property_item_rollup.json")).unwrap();
+    let cursor = list.cursor();
+    assert_eq!(cursor.start_cursor(), list.next_cursor.as_ref());
+
+    let saved = serde_json::to_string(&cursor).unwrap();
+    let resumed: Cursor = serde_json::from_str(&saved).unwrap();
+    assert_eq!(resumed, cursor);
+    let query = serde_json::to_value(SearchRequest::default().resume(&resumed)).unwrap();
+    assert_eq!(
+        query["start_cursor"].as_str(),
+        list.next_cursor.as_ref().map(PagingCursor::value)
+    );
+
+    assert_eq!(Cursor::after(list.next_cursor.clone(), false), Cursor::End);
+    assert_eq!(serde_json::to_string(&Cursor::End).unwrap(), r#""end""#);
+}
+
+#[test]
+fn search_helpers() {
+    let query = |search: NotionSearch| serde_json::to_value(SearchRequest::from(search)).unwrap();
+    assert_eq!(query(NotionSearch::query("Roadmap"))["query"], "Roadmap");
+    assert_eq!(
+        query(NotionSearch::sort_by_last_edited(SortDirection::Ascending))["sort"],
+        serde_json::json!({"timestamp": "last_edited_time", "direction": "ascending"})
+    );
+    assert_eq!(
+        query(NotionSearch::filter_by_pages())["filter"],
+        serde_json::json!({"property": "object", "value": "page"})
+    );
+}
+
+#[test]
+fn pages_to_columns() {
+    let first = PageFixture::new()
+        .title("Write docs")
+        .number("Points", 3)
+        .checkbox("Done", true)
+        .multi_select("Tags", &["docs", "api"])
+        .build();
+    let second = PageFixture::new()
+        .title("Release")
+        .checkbox("Done", false)
+        .build();
+
+    let columns = Columns::from_pages(&[first.clone(), second.clone()]);
+
+    assert_eq!(columns.ids, vec![first.id, second.id]);
+    let names: Vec<&str> = columns.columns.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["Done", "Name", "Points", "Tags"]);
+    assert_eq!(
+        columns.column("Name"),
+        Some(&ColumnValues::Text(vec![
+            Some("Write docs".to_string()),
+            Some("Release".to_string())
+        ]))
+    );
+    assert_eq!(
+        columns.column("Points"),
+        Some(&ColumnValues::Number(vec![Some(3.0), None]))
+    );
+    assert_eq!(
+        columns.column("Done"),
+        Some(&ColumnValues::Boolean(vec![Some(true), Some(false)]))
+    );
+    assert_eq!(
+        columns.column("Tags"),
+        Some(&ColumnValues::List(vec![
+            vec!["docs".to_string(), "api".to_string()],
+            vec![]
+        ]))
+    );
+}
+
+#[cfg(feature = "polars")]
+#[test]
+fn pages_to_dataframe() {
+    use polars::prelude::{DataType, TimeUnit};
+
+    let due = |start: &str| PropertyValue::Date {
+        id: FromStr::from_str("due").unwrap(),
+        date: Some(DateValue {
+            start: start.parse().unwrap(),
+            end: None,
+            time_zone: None,
+        }),
+    };
+    let first = PageFixture::new()
+        .title("Write docs")
+        .number("Points", 3)
+        .multi_select("Tags", &["docs", "api"])
+        .property("Due", due("2022-05-14"))
+        .property("Reminder", due("2022-05-14T09:30:00Z"))
+        .build();
+    let second = PageFixture::new()
+        .title("Release")
+        .checkbox("Done", true)
+        .property("Reminder", due("2022-05-16"))
+        .build();
+
+    let frame = Columns::from_pages(&[first.clone(), second])
+        .to_dataframe()
+        .unwrap();
+
+    assert_eq!(frame.shape(), (2, 7));
+    let dtype = |name: &str| frame.column(name).unwrap().dtype().clone();
+    assert_eq!(dtype("id"), DataType::String);
+    assert_eq!(dtype("Points"), DataType::Float64);
+    assert_eq!(dtype("Done"), DataType::Boolean);
+    assert_eq!(dtype("Name"), DataType::String);
+    assert_eq!(dtype("Due"), DataType::Date);
+    assert_eq!(
+        dtype("Reminder"),
+        DataType::Datetime(TimeUnit::Milliseconds, None)
+    );
+    assert_eq!(dtype("Tags"), DataType::List(Box::new(DataType::String)));
+    let id = frame.column("id").unwrap().str().unwrap().get(0);
+    assert_eq!(id, Some(first.id.to_string().as_str()));
+    assert_eq!(frame.column("Points").unwrap().null_count(), 1);
+
+    let clashing = PageFixture::new().number("id", 1).build();
+    assert!(Columns::from_pages(&[clashing]).to_dataframe().is_err());
+}
+
+#[test]
+fn aggregate_pages() {
+    let pages = vec![
+        PageFixture::new()
+            .number("Points", 3)
+            .multi_select("Tags", &["docs", "api"])
+            .build(),
+        PageFixture::new()
+            .number("Points", 5)
+            .multi_select("Tags", &["api"])
+            .build(),
+        PageFixture::new().build(),
+    ];
+
+    let points = Aggregate::of("Points", &pages);
+    assert_eq!((points.count, points.empty, points.sum), (2, 1, 8.0));
+    assert_eq!(points.min, Some(AggregateValue::Number(3.0)));
+    assert_eq!(points.max, Some(AggregateValue::Number(5.0)));
+    assert_eq!(points.mean(), Some(4.0));
+
+    let tags = aggregate::group_count(&pages, "Tags");
+    assert_eq!(tags["api"], 2);
+    assert_eq!(tags["docs"], 1);
+    assert_eq!(aggregate::count(&pages, "Tags"), 2);
+    assert_eq!(aggregate::max(&pages, "Tags"), None);
+
+    let first = DateOrDateTime::from(NaiveDate::from_ymd_opt(2022, 5, 14).unwrap());
+    let later = DateOrDateTime::from_str("2022-05-14T09:30:00Z").unwrap();
+    assert!(AggregateValue::Date(first) < AggregateValue::Date(later));
+    assert_eq!(
+        AggregateValue::Date(first).partial_cmp(&AggregateValue::Number(1.0)),
+        None
+    );
+}
+
+#[test]
+fn pages_to_csv() {
+    let ada = UserReference::User(User::Person {
+        common: UserCommon {
+            id: UserId::from_str("6c0a1ea6-4a0b-4b8e-9c33-7a5b4a1f1a01").unwrap(),
+            name: Some("Ada".to_string()),
+            avatar_url: None,
+        },
+        person: Person {
+            email: Some("ada@example.com".to_string()),
+        },
+    });
+    let due = DateValue {
+        start: "2022-05-14".parse().unwrap(),
+        end: Some("2022-05-16".parse().unwrap()),
+        time_zone: None,
+    };
+    let first = PageFixture::new()
+        .title("Write \"docs\", then ship")
+        .number("Points", 3)
+        .multi_select("Tags", &["docs", "api"])
+        .people("Owner", vec![ada])
+        .property(
+            "Due",
+            PropertyValue::Date {
+                id: FromStr::from_str("due").unwrap(),
+                date: Some(due.clone()),
+            },
+        )
+        .property(
+            "Next",
+            PropertyValue::Formula {
+                id: FromStr::from_str("next").unwrap(),
+                formula: FormulaResultValue::Date { date: Some(due) },
+            },
+        )
+        .build();
+    let second = PageFixture::new().title("Release").build();
+    let pages = [first, second];
+
+    assert_eq!(
+        csv::to_csv(&pages, &CsvOptions::default()),
+        "Name,Due,Next,Owner,Points,Tags\r\n\
+         \"Write \"\"docs\"\", then ship\",2022-05-14 → 2022-05-16,2022-05-14 → 2022-05-16,Ada,3,\"docs, api\"\r\n\
+         Release,,,,,\r\n"
+    );
+
+    let options = CsvOptions {
+        columns: Some(vec![
+            "Tags".to_string(),
+            "Owner".to_string(),
+            "Due".to_string(),
+            "Next".to_string(),
+            "Missing".to_string(),
+        ]),
+        date_format: Some("%d/%m/%Y".to_string()),
+        date_range_separator: " - ".to_string(),
+        list_separator: ";".to_string(),
+        people: PeopleFormat::Email,
+        formulas: FormulaFormat::Raw,
+        delimiter: '\t',
+    };
+    assert_eq!(
+        csv::to_csv(&pages[..1], &options),
+        "Tags\tOwner\tDue\tNext\tMissing\r\n\
+         docs;api\tada@example.com\t14/05/2022 - 16/05/2022\t2022-05-14/2022-05-16\t\r\n"
+    );
+}
+
+#[test]
+fn pages_to_ical() {
+    let date = |start: &str, end: Option<&str>| PropertyValue::Date {
+        id: FromStr::from_str("date").unwrap(),
+        date: Some(DateValue {
+            start: start.parse().unwrap(),
+            end: end.map(|end| end.parse().unwrap()),
+            time_zone: None,
+        }),
+    };
+    let edited = DateTime::parse_from_rfc3339("2022-05-01T08:30:00Z")
+        .unwrap()
+        .into();
+    let launch = PageFixture::new()
+        .id("b55c9c91-384d-452b-81db-d1ef79372b75")
+        .title("Launch; part 1, and more")
+        .last_edited_time(edited)
+        .property("Publish", date("2022-05-14", Some("2022-05-15")))
+        .build();
+    let call = PageFixture::new()
+        .id("c55c9c91-384d-452b-81db-d1ef79372b75")
+        .title("Call")
+        .last_edited_time(edited)
+        .property("Publish", date("2022-05-16T09:00:00Z", None))
+        .build();
+    let undated = PageFixture::new().title("Someday").build();
+
+    let feed = ical::to_ical(
+        &[launch, call, undated],
+        &IcalOptions::new("Publish").calendar_name("Content"),
+    );
+
+    assert_eq!(
+        feed,
+        "BEGIN:VCALENDAR\r\n\
+         VERSION:2.0\r\n\
+         PRODID:-//notion-wasi//EN\r\n\
+         CALSCALE:GREGORIAN\r\n\
+         X-WR-CALNAME:Content\r\n\
+         BEGIN:VEVENT\r\n\
+         UID:b55c9c91-384d-452b-81db-d1ef79372b75@notion.so\r\n\
+         DTSTAMP:20220501T083000Z\r\n\
+         LAST-MODIFIED:20220501T083000Z\r\n\
+         DTSTART;VALUE=DATE:20220514\r\n\
+         DTEND;VALUE=DATE:20220516\r\n\
+         SUMMARY:Launch\\; part 1\\, and more\r\n\
+         URL:https://www.notion.so/b55c9c91384d452b81dbd1ef79372b75\r\n\
+         END:VEVENT\r\n\
+         BEGIN:VEVENT\r\n\
+         UID:c55c9c91-384d-452b-81db-d1ef79372b75@notion.so\r\n\
+         DTSTAMP:20220501T083000Z\r\n\
+         LAST-MODIFIED:20220501T083000Z\r\n\
+         DTSTART:20220516T090000Z\r\n\
+         SUMMARY:Call\r\n\
+         URL:https://www.notion.so/c55c9c91384d452b81dbd1ef79372b75\r\n\
+         END:VEVENT\r\n\
+         END:VCALENDAR\r\n"
+    );
+
+    let long = PageFixture::new()
+        .title(&"a".repeat(100))
+        .property("Publish", date("2022-05-14", None))
+        .build();
+    let feed = ical::to_ical(&[long], &IcalOptions::new("Publish"));
+    assert!(feed.contains(&format!(
+        "SUMMARY:{}\r\n {}\r\n",
+        "a".repeat(67),
+        "a".repeat(33)
+    )));
+    assert!(feed.contains("DTEND;VALUE=DATE:20220515\r\n"));
+}
+
+#[test]
+fn validate_emoji() {
+    for emoji in [
+        "💡",
+        "⚠️",
+        "☀",
+        "👍🏿",
+        "👩🏽‍💻",
+        "👨‍👩‍👧‍👦",
+        "🏳️‍🌈",
+        "🇯🇵",
+        "🏴\u{E0067}\u{E0062}\u{E0065}\u{E006E}\u{E0067}\u{E007F}",
+        "#️⃣",
+        "1⃣",
+    ] {
+        assert_eq!(
+            Emoji::new(emoji).map(|e| e.to_string()),
+            Ok(emoji.to_string())
+        );
+    }
+    for invalid in [
+        "",
+        "a",
+        "1",
+        "💡💡",
+        "💡 ",
+        "🇯",
+        "🇯🇵🇯",
+        "👩‍",
+        "🏴\u{E0067}",
+        "\u{FE0F}",
+    ] {
+        assert_eq!(
+            invalid.parse::<Emoji>(),
+            Err(InvalidEmoji(invalid.to_string()))
+        );
+    }
+
+    // Emoji from the API are taken as is.
+    let emoji: Emoji = serde_json::from_str(r#""not an emoji""#).unwrap();
+    assert_eq!(emoji.as_str(), "not an emoji");
+}
+
+#[test]
+fn deserialize_custom_emoji_mention() {
+    let rich_text: RichText = serde_json::from_value(serde_json::json!({
+        "type": "mention",
+        "mention": {
+            "type": "custom_emoji",
+            "custom_emoji": {
+                "id": "45ce454c-d427-4f53-9489-e5d0f3d1db6b",
+                "name": "bufo",
+                "url": "https://example.com/bufo.png"
+            }
+        },
+        "annotations": {
+            "bold": false,
+            "italic": false,
+            "strikethrough": false,
+            "underline": false,
+            "code": false,
+            "color": "default"
+        },
+        "plain_text": ":bufo:",
+        "href": null
+    }))
+    .unwrap();
+
+    match &rich_text {
+        RichText::Mention {
+            mention: MentionObject::CustomEmoji { custom_emoji },
+            ..
+        } => assert_eq!(custom_emoji.name.as_deref(), Some("bufo")),
+        rich_text => panic!("Expected a custom emoji mention, got {:?}", rich_text),
+    }
+    assert_eq!(rich_text.plain_text(), ":bufo:");
+}
+
+#[test]
+fn convert_from_and_into_values() {
+    let mut value: serde_json::Value =
+        serde_json::from_str(include_str!("tests/page.json")).unwrap();
+    value["archived"] = true.into();
+
+    let page = Page::try_from(value).unwrap();
+    assert!(page.archived);
+    assert_eq!(page.into_value()["archived"], true);
+
+    let not_a_page = serde_json::json!({"object": "page"});
+    assert!(Page::try_from(not_a_page).is_err());
+}
+
+#[test]
+fn filter_trashed() {
+    let kept = PageFixture::new().build();
+    let archived = PageFixture::new().archived(true).build();
+    let mut trashed = PageFixture::new().build();
+    trashed.in_trash = true;
+    let pages = ListResponse {
+        results: vec![kept.clone(), archived.clone(), trashed.clone()],
+        next_cursor: None,
+        has_more: false,
+    };
+
+    assert_eq!(
+        pages.clone().filter_trashed(TrashFilter::Exclude).results,
+        vec![kept.clone()]
+    );
+    assert_eq!(
+        pages.clone().filter_trashed(TrashFilter::Only).results,
+        vec![archived.clone(), trashed.clone()]
+    );
+    assert_eq!(
+        pages.filter_trashed(TrashFilter::Include).results,
+        vec![kept, archived, trashed]
+    );
+}
+
+#[test]
+fn list_response_iterators() {
+    let first = PageFixture::new().title("First").build();
+    let second = PageFixture::new().title("Second").build();
+    let user = person("Ada", "ada@example.com");
+    let list = ListResponse {
+        results: vec![
+            Object::Page {
+                page: first.clone(),
+            },
+            Object::User { user: user.clone() },
+            Object::Page {
+                page: second.clone(),
+            },
+        ],
+        next_cursor: serde_json::from_value(serde_json::json!("next")).unwrap(),
+        has_more: true,
+    };
+
+    assert_eq!(list.len(), 3);
+    assert_eq!((&list).into_iter().count(), 3);
+    assert_eq!(list.pages().count(), 2);
+
+    let titles = list.clone().map_pages(|page| page.to_string());
+    assert_eq!(titles.results, vec!["First", "Second"]);
+    assert_eq!(titles.cursor(), list.cursor());
+
+    let objects = list.clone().partition_by_object();
+    assert_eq!(objects.pages, vec![first, second]);
+    assert_eq!(objects.users, vec![user]);
+    assert!(objects.other.is_empty());
+
+    let kinds: Vec<bool> = list
+        .into_iter()
+        .map(|object| matches!(object, Object::Page { .. }))
+        .collect();
+    assert_eq!(kinds, vec![true, false, true]);
+}
+
+#[test]
+#[cfg(feature = "databases")]
+fn deserialize_data_source() {
+    let object: Object = serde_json::from_str(include_str!("tests/data_source.json")).unwrap();
+    let data_source = match object {
+        Object::DataSource { data_source } => data_source,
+        object => panic!("Expected a data source, got {:?}", object),
+    };
+
+    assert_eq!(data_source.title_plain_text(), "Tasks");
+    assert_eq!(data_source.title_property(), Some("Name"));
+    assert_eq!(
+        data_source.parent,
+        Parent::Database {
+            database_id: DatabaseId::from_str("248104cd-477e-80fd-b757-e945d38000bd").unwrap()
+        }
+    );
+    match &data_source.properties["Project"] {
+        PropertyConfiguration::Relation { relation, .. } => assert_eq!(
+            relation.data_source_id,
+            Some(DataSourceId::from_str("a42a62ed-9b51-4b98-9dea-ea6d091bc508").unwrap())
+        ),
+        property => panic!("Expected a relation, got {:?}", property),
+    }
+}
+
+#[test]
+fn deserialize_data_source_parent() {
+    let parent: Parent = serde_json::from_value(serde_json::json!({
+        "type": "data_source_id",
+        "data_source_id": "bc1211ca-e3f1-4939-ae34-5260b16f627c",
+        "database_id": "248104cd-477e-80fd-b757-e945d38000bd"
+    }))
+    .unwrap();
+
+    assert_eq!(
+        parent,
+        Parent::DataSource {
+            data_source_id: DataSourceId::from_str("bc1211ca-e3f1-4939-ae34-5260b16f627c").unwrap(),
+            database_id: Some(
+                DatabaseId::from_str("248104cd-477e-80fd-b757-e945d38000bd").unwrap()
+            ),
+        }
+    );
+}
+
+#[test]
+fn rich_text_mention_template() {
+    let rich_text: Vec<RichText> =
+        serde_json::from_str(include_str!("tests/rich_text_mention_template.json")).unwrap();
+    let mentions: Vec<_> = rich_text
+        .iter()
+        .map(|rich_text| match rich_text {
+            RichText::Mention {
+                mention: MentionObject::TemplateMention { template_mention },
+                ..
+            } => *template_mention,
+            rich_text => panic!("Expected a template mention, got {:?}", rich_text),
+        })
+        .collect();
+    assert_eq!(
+        mentions,
+        vec![TemplateMention::today(), TemplateMention::me()]
+    );
+
+    let now = RichText::template_mention(TemplateMention::now());
+    assert_eq!(now.plain_text(), "@Now");
+    assert_eq!(
+        serde_json::to_value(&now).unwrap(),
+        serde_json::json!({
+            "type": "mention",
+            "plain_text": "@Now",
+            "mention": {
+                "type": "template_mention",
+                "template_mention": {"type": "template_mention_date", "template_mention_date": "now"}
+            }
+        })
+    );
+}
+
+#[test]
+fn rich_text_equation() {
+    let equation: RichText =
+        serde_json::from_str(include_str!("tests/rich_text_equation.json")).unwrap();
+    match &equation {
+        RichText::Equation { equation, .. } => assert_eq!(equation.expression, "E = mc^2"),
+        rich_text => panic!("Expected an equation, got {:?}", rich_text),
+    }
+    assert_eq!(equation.plain_text(), "E = mc^2");
+
+    assert_eq!(
+        serde_json::to_value(RichText::equation("x^2")).unwrap(),
+        serde_json::json!({
+            "type": "equation",
+            "plain_text": "x^2",
+            "equation": {"expression": "x^2"}
+        })
+    );
+}
+
+#[test]
+fn parse_webhook_events() {
+    let event = match parse_webhook(include_bytes!("tests/webhook_page_properties_updated.json"))
+        .unwrap()
+    {
+        WebhookPayload::Event(event) => event,
+        payload => panic!("Expected an event, got {:?}", payload),
+    };
+    assert_eq!(event.kind, WebhookEventType::PagePropertiesUpdated);
+    assert_eq!(event.attempt_number, 1);
+    assert_eq!(
+        event.entity.page_id(),
+        Some(PageId::from_str("153104cd-477e-809d-8dc4-ff2d96ae3090").unwrap())
+    );
+    assert_eq!(event.entity.database_id(), None);
+    assert_eq!(event.authors[0].kind, WebhookEntityType::Person);
+    assert_eq!(
+        event.data.parent.map(|parent| parent.kind),
+        Some(WebhookEntityType::Space)
+    );
+    assert_eq!(
+        event.data.updated_properties[2],
+        PropertyId::from_str("DbAu").unwrap()
+    );
+
+    assert_eq!(
+        parse_webhook(
+            br#"{"verification_token": "secret_tMrlL1qK5vuQAh1b6cZGhFChZTSYJlce98V0pYn7yBl"}"#
+        )
+        .unwrap(),
+        WebhookPayload::Verification {
+            verification_token: "secret_tMrlL1qK5vuQAh1b6cZGhFChZTSYJlce98V0pYn7yBl".to_string()
+        }
+    );
+}
+
+#[test]
+fn read_properties_as_rust_types() {
+    use crate::models::rows::RowError;
+
+    let due = NaiveDate::from_ymd_opt(2022, 5, 14).unwrap();
+    let page = PageFixture::new()
+        .title("Ship it")
+        .select("Status", "Doing")
+        .number("Estimate", serde_json::Number::from_f64(2.5).unwrap())
+        .multi_select("Tags", &["Urgent", "Backend"])
+        .checkbox("Done", false)
+        .property(
+            "Due",
+            PropertyValue::Date {
+                id: PropertyId::from_str("due").unwrap(),
+                date: Some(DateValue::new(due)),
+            },
+        )
+        .property(
+            "Owner",
+            PropertyValue::Email {
+                id: PropertyId::from_str("owner").unwrap(),
+                email: None,
+            },
+        )
+        .build();
+
+    assert_eq!(
+        page.property_as::<String>("Name"),
+        Ok("Ship it".to_string())
+    );
+    assert_eq!(
+        page.property_as::<String>("Status"),
+        Ok("Doing".to_string())
+    );
+    assert_eq!(page.property_as::<f64>("Estimate"), Ok(2.5));
+    assert_eq!(
+        page.property_as::<Vec<String>>("Tags"),
+        Ok(vec!["Urgent".to_string(), "Backend".to_string()])
+    );
+    assert_eq!(page.property_as::<bool>("Done"), Ok(false));
+    assert_eq!(page.property_as::<NaiveDate>("Due"), Ok(due));
+    assert_eq!(
+        page.property_as::<DateTime<chrono::Utc>>("Due")
+            .map(|due| due.to_rfc3339()),
+        Ok("2022-05-14T00:00:00+00:00".to_string())
+    );
+
+    assert_eq!(page.property_as::<Option<String>>("Owner"), Ok(None));
+    assert_eq!(
+        page.property_as::<String>("Owner"),
+        Err(RowError::empty("Owner"))
+    );
+    assert_eq!(page.property_as::<Option<f64>>("Missing"), Ok(None));
+    assert_eq!(
+        page.property_as::<f64>("Missing"),
+        Err(RowError::missing("Missing"))
+    );
+    assert_eq!(
+        page.property_as::<f64>("Done"),
+        Err(RowError::wrong_type("Done", "number"))
+    );
+}
+
+#[test]
+fn typed_property_accessors_on_pages() {
+    let parent = PageFixture::new().title("Epic").build();
+    let due = NaiveDate::from_ymd_opt(2022, 5, 14).unwrap();
+    let page = PageFixture::new()
+        .title("Ship it")
+        .select("Status", "Doing")
+        .number("Score", 7)
+        .checkbox("Done", true)
+        .relation("Parent task", &[&parent])
+        .property(
+            "Due",
+            PropertyValue::Date {
+                id: PropertyId::from_str("due").unwrap(),
+                date: Some(DateValue::new(due)),
+            },
+        )
+        .build();
+
+    assert_eq!(page.title_text(), "Ship it");
+    assert_eq!(
+        page.get_number("Score").unwrap().and_then(|n| n.as_i64()),
+        Some(7)
+    );
+    assert_eq!(page.get_date("Due"), Ok(Some(&DateValue::new(due))));
+    assert_eq!(
+        page.get_select("Status")
+            .unwrap()
+            .and_then(|option| option.name.as_deref()),
+        Some("Doing")
+    );
+    assert_eq!(page.get_relation_ids("Parent task"), Ok(vec![parent.id]));
+    assert_eq!(page.get_relation_ids("Missing"), Ok(vec![]));
+    assert_eq!(
+        page.get_number("Done").unwrap_err().to_string(),
+        "Property \"Done\" is a checkbox property, not a number property"
+    );
+    assert_eq!(PageFixture::new().build().title_text(), "");
+}
+
+#[test]
+fn annotate_and_link_rich_text() {
+    let text = RichText::text("docs")
+        .bold()
+        .italic()
+        .color(TextColor::Blue)
+        .link("https://developers.notion.com");
+    assert_eq!(
+        serde_json::to_value(&text).unwrap(),
+        serde_json::json!({
+            "type": "text",
+            "plain_text": "docs",
+            "href": "https://developers.notion.com",
+            "annotations": {"bold": true, "italic": true, "color": "blue"},
+            "text": {"content": "docs", "link": {"url": "https://developers.notion.com"}}
+        })
+    );
+
+    let date = DateValue {
+        start: DateOrDateTime::Date(NaiveDate::from_ymd_opt(2022, 5, 14).unwrap()),
+        end: None,
+        time_zone: None,
+    };
+    let mention = RichText::date_mention(date).code();
+    assert_eq!(mention.plain_text(), "2022-05-14");
+    assert_eq!(
+        serde_json::to_value(&mention).unwrap()["annotations"],
+        serde_json::json!({"code": true})
+    );
+}
+
+#[test]
+fn build_page_create_request() {
+    let database_id = DatabaseId::from_str("b55c9c91-384d-452b-81db-d1ef79372b75").unwrap();
+    let owner = UserId::from_str("6794760a-1f15-45cd-9c65-0dfe42f5135a").unwrap();
+    let request = PageCreateRequest::builder(Parent::Database { database_id })
+        .title("Ship it")
+        .select("Status", "Todo")
+        .multi_select("Tags", ["Backend"])
+        .number("Estimate", 3)
+        .checkbox("Done", false)
+        .url("Spec", "https://example.com")
+        .people("Owner", vec![owner])
+        .build();
+
+    let value = serde_json::to_value(&request).unwrap();
+    let properties = &value["properties"];
+    assert_eq!(properties.as_object().unwrap().len(), 7);
+    assert_eq!(properties["title"]["title"][0]["plain_text"], "Ship it");
+    assert_eq!(properties["Status"]["select"]["name"], "Todo");
+    assert_eq!(properties["Tags"]["multi_select"][0]["name"], "Backend");
+    assert_eq!(properties["Estimate"]["number"], 3);
+    assert_eq!(properties["Done"]["checkbox"], false);
+    assert_eq!(properties["Spec"]["url"], "https://example.com");
+    assert_eq!(
+        properties["Owner"]["people"][0]["id"],
+        "6794760a-1f15-45cd-9c65-0dfe42f5135a"
+    );
+    assert_eq!(
+        value["parent"]["database_id"],
+        "b55c9c91-384d-452b-81db-d1ef79372b75"
+    );
+}
@@ -0,0 +1,244 @@
+//! Round-trip tests over captured API payloads.
+//!
+//! Every model must re-parse to the same value after serialization,
+//! and the serialized JSON must only contain keys and values present in
+//! the original payload, so it never sends the API something it didn't return.
+//! Fields the models don't capture yet are allowed to be dropped.
+
+use crate::models::block::Block;
+use crate::models::properties::{PropertyConfiguration, PropertyValue};
+use crate::models::{Database, Object, Page};
+use chrono::DateTime;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt::Debug;
+
+fn golden(json: &str) -> Value {
+    serde_json::from_str(json).unwrap()
+}
+
+fn assert_round_trip<T>(original: &Value) -> T
+where
+    T: Serialize + DeserializeOwned + Debug + PartialEq,
+{
+    let parsed: T = serde_json::from_value(original.clone())
+        .unwrap_or_else(|error| panic!("{} parsing {:#}", error, original));
+    let serialized = serde_json::to_value(&parsed).unwrap();
+
+    if let Err(path) = contained_in(&serialized, original, String::new()) {
+        panic!(
+            "Serialized {} differs from the payload at `{}`:\n{:#}",
+            std::any::type_name::<T>(),
+            path,
+            serialized
+        );
+    }
+    let reparsed: T = serde_json::from_value(serialized).unwrap();
+    assert_eq!(reparsed, parsed);
+
+    parsed
+}
+
+/// Checks that every value in `serialized` is also in `original`,
+/// returning the path of the first difference.
+fn contained_in(
+    serialized: &Value,
+    original: &Value,
+    path: String,
+) -> Result<(), String> {
+    match (serialized, original) {
+        (Value::Object(serialized), Value::Object(original)) => {
+            for (key, value) in serialized {
+                let path = format!("{}.{}", path, key);
+                match original.get(key) {
+                    Some(original) => contained_in(value, original, path)?,
+                    None => return Err(path),
+                }
+            }
+            Ok(())
+        }
+        (Value::Array(serialized), Value::Array(original))
+            if serialized.len() == original.len() =>
+        {
+            for (index, (value, original)) in serialized.iter().zip(original).enumerate() {
+                contained_in(value, original, format!("{}[{}]", path, index))?;
+            }
+            Ok(())
+        }
+        (Value::String(serialized), Value::String(original))
+            if same_instant(serialized, original) =>
+        {
+            Ok(())
+        }
+        (Value::Number(serialized), Value::Number(original))
+            if serialized.as_f64() == original.as_f64() =>
+        {
+            Ok(())
+        }
+        (serialized, original) if serialized == original => Ok(()),
+        _ => Err(path),
+    }
+}
+
+/// Timestamps are normalized to UTC, so compare them by instant.
+fn same_instant(
+    a: &str,
+    b: &str,
+) -> bool {
+    match (
+        DateTime::parse_from_rfc3339(a),
+        DateTime::parse_from_rfc3339(b),
+    ) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+#[test]
+fn blocks() {
+    let blocks = golden(include_str!("golden/blocks.json"));
+
+    for original in blocks.as_array().unwrap() {
+        let block: Block = assert_round_trip(original);
+        assert_ne!(
+            block,
+            Block::Unknown,
+            "{} parsed as unknown",
+            original["type"]
+        );
+        assert_eq!(block.id().unwrap().to_string(), original["id"]);
+        assert!(block.parent().is_some());
+        assert_round_trip::<Object>(original);
+    }
+}
+
+#[test]
+fn page_property_values() {
+    let page = golden(include_str!("golden/page.json"));
+
+    for (name, original) in page["properties"].as_object().unwrap() {
+        let value: PropertyValue = assert_round_trip(original);
+        assert_eq!(
+            serde_json::to_value(&value).unwrap()["type"],
+            original["type"],
+            "{}",
+            name
+        );
+    }
+    assert_round_trip::<Page>(&page);
+    assert_round_trip::<Object>(&page);
+}
+
+#[test]
+fn database_property_configurations() {
+    let database = golden(include_str!("golden/database.json"));
+
+    for original in database["properties"].as_object().unwrap().values() {
+        assert_round_trip::<PropertyConfiguration>(original);
+    }
+    assert_round_trip::<Database>(&database);
+    assert_round_trip::<Object>(&database);
+}
+
+/// Timestamp formats seen in API responses, all for the same instant.
+const NOTION_TIMESTAMPS: [&str; 4] = [
+    "2022-03-01T19:05:00.000Z",
+    "2022-03-01T19:05:00Z",
+    "2022-03-01T19:05:00.000+00:00",
+    "2022-03-01T21:05:00.000+02:00",
+];
+
+/// How chrono serializes [NOTION_TIMESTAMPS].
+const CANONICAL_TIMESTAMP: &str = "2022-03-01T19:05:00Z";
+
+/// Replaces every `created_time`, `last_edited_time` and `expiry_time` string.
+fn set_timestamps(
+    value: &mut Value,
+    timestamp: &str,
+) -> usize {
+    match value {
+        Value::Object(map) => map
+            .iter_mut()
+            .map(|(key, value)| match value {
+                Value::String(string) if is_timestamp_key(key) => {
+                    *string = timestamp.to_string();
+                    1
+                }
+                value => set_timestamps(value, timestamp),
+            })
+            .sum(),
+        Value::Array(values) => values
+            .iter_mut()
+            .map(|value| set_timestamps(value, timestamp))
+            .sum(),
+        _ => 0,
+    }
+}
+
+fn is_timestamp_key(key: &str) -> bool {
+    matches!(key, "created_time" | "last_edited_time" | "expiry_time")
+}
+
+fn assert_timestamps_parse<T>(original: &Value)
+where
+    T: Serialize + DeserializeOwned + Debug + PartialEq,
+{
+    for timestamp in NOTION_TIMESTAMPS {
+        let mut payload = original.clone();
+        let replaced = set_timestamps(&mut payload, timestamp);
+        assert!(replaced > 0);
+
+        // Parsing must land on the same instant as the canonical form.
+        let parsed: T = serde_json::from_value(payload).unwrap();
+        let mut serialized = serde_json::to_value(&parsed).unwrap();
+        assert_eq!(
+            set_timestamps(&mut serialized, CANONICAL_TIMESTAMP),
+            replaced,
+            "{} dropped a timestamp",
+            std::any::type_name::<T>()
+        );
+        let reparsed: T = serde_json::from_value(serialized).unwrap();
+        assert_eq!(
+            reparsed,
+            parsed,
+            "{} from {}",
+            std::any::type_name::<T>(),
+            timestamp
+        );
+    }
+}
+
+#[test]
+fn timestamps_parse_notion_formats() {
+    for block in golden(include_str!("golden/blocks.json"))
+        .as_array()
+        .unwrap()
+    {
+        assert_timestamps_parse::<Block>(block);
+    }
+    assert_timestamps_parse::<Page>(&golden(include_str!("golden/page.json")));
+    assert_timestamps_parse::<Database>(&golden(include_str!("golden/database.json")));
+}
+
+#[test]
+fn contained_in_reports_invented_values() {
+    let original = serde_json::json!({"a": {"b": null}, "c": [1, 2]});
+
+    assert_eq!(
+        contained_in(&serde_json::json!({"a": {}}), &original, String::new()),
+        Ok(())
+    );
+    assert_eq!(
+        contained_in(
+            &serde_json::json!({"a": {"d": 1}}),
+            &original,
+            String::new()
+        ),
+        Err(".a.d".to_string())
+    );
+    assert_eq!(
+        contained_in(&serde_json::json!({"c": [1]}), &original, String::new()),
+        Err(".c".to_string())
+    );
+}
@@ -1,9 +1,16 @@
-use crate::models::{properties::DateValue, users::User, Database, Page};
+use crate::models::files::CustomEmoji;
+#[cfg(feature = "databases")]
+use crate::models::Database;
+use crate::models::{properties::DateValue, users::UserReference, Page};
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::fmt;
 
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Copy, Clone)]
+/// The text or background color of blocks and rich text.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Copy, Clone, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum TextColor {
+    #[default]
     Default,
     Gray,
     Brown,
@@ -25,15 +32,40 @@ pub enum TextColor {
     RedBackground,
 }
 
+impl TextColor {
+    /// Whether this colors the background rather than the text.
+    pub fn is_background(self) -> bool {
+        use TextColor::*;
+        matches!(
+            self,
+            GrayBackground
+                | BrownBackground
+                | OrangeBackground
+                | YellowBackground
+                | GreenBackground
+                | BlueBackground
+                | PurpleBackground
+                | PinkBackground
+                | RedBackground
+        )
+    }
+}
+
 /// Rich text annotations
 /// See <https://developers.notion.com/reference/rich-text#annotations>
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Default)]
 pub struct Annotations {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub bold: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub code: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub color: Option<TextColor>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub italic: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub strikethrough: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub underline: Option<bool>,
 }
 
@@ -59,25 +91,41 @@ pub struct Text {
     pub link: Option<Link>,
 }
 
+/// A KaTeX compatible expression, used by inline equations and equation blocks.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct Equation {
+    pub expression: String,
+}
+
 /// See https://developers.notion.com/reference/rich-text#mention-objects
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
 #[serde(tag = "type")]
 #[serde(rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
 pub enum MentionObject {
+    /// Notion only guarantees the id of the user, see [UserReference].
     User {
-        user: User,
+        user: UserReference,
     },
     // TODO: need to add tests
     Page {
         page: Page,
     },
     // TODO: need to add tests
+    #[cfg(feature = "databases")]
     Database {
         database: Database,
     },
     Date {
         date: DateValue,
     },
+    /// A placeholder in a database template, filled in when a page is created from it.
+    TemplateMention {
+        template_mention: TemplateMention,
+    },
+    CustomEmoji {
+        custom_emoji: CustomEmoji,
+    },
     // TODO: need to add LinkPreview
     // LinkPreview {
     //
@@ -86,6 +134,68 @@ pub enum MentionObject {
     Unknown,
 }
 
+/// See <https://developers.notion.com/reference/rich-text#template-mention-type-object>
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Copy, Clone)]
+#[serde(tag = "type")]
+#[serde(rename_all = "snake_case")]
+pub enum TemplateMention {
+    TemplateMentionDate {
+        template_mention_date: TemplateMentionDate,
+    },
+    TemplateMentionUser {
+        template_mention_user: TemplateMentionUser,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Copy, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum TemplateMentionDate {
+    /// The date the page is created.
+    Today,
+    /// The date and time the page is created.
+    Now,
+}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Copy, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum TemplateMentionUser {
+    /// The user who creates the page.
+    Me,
+}
+
+impl TemplateMention {
+    pub fn today() -> Self {
+        TemplateMention::TemplateMentionDate {
+            template_mention_date: TemplateMentionDate::Today,
+        }
+    }
+
+    pub fn now() -> Self {
+        TemplateMention::TemplateMentionDate {
+            template_mention_date: TemplateMentionDate::Now,
+        }
+    }
+
+    pub fn me() -> Self {
+        TemplateMention::TemplateMentionUser {
+            template_mention_user: TemplateMentionUser::Me,
+        }
+    }
+
+    /// How Notion displays the placeholder in the template.
+    pub fn display_text(&self) -> &'static str {
+        match self {
+            TemplateMention::TemplateMentionDate {
+                template_mention_date: TemplateMentionDate::Today,
+            } => "@Today",
+            TemplateMention::TemplateMentionDate {
+                template_mention_date: TemplateMentionDate::Now,
+            } => "@Now",
+            TemplateMention::TemplateMentionUser { .. } => "@Me",
+        }
+    }
+}
+
 /// Rich text objects contain data for displaying formatted text, mentions, and equations.
 /// A rich text object also contains annotations for style information.
 /// Arrays of rich text objects are used within property objects and property
@@ -93,6 +203,7 @@ pub enum MentionObject {
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
 #[serde(tag = "type")]
 #[serde(rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
 pub enum RichText {
     /// See <https://developers.notion.com/reference/rich-text#text-objects>
     Text {
@@ -110,10 +221,154 @@ pub enum RichText {
     Equation {
         #[serde(flatten)]
         rich_text: RichTextCommon,
+        equation: Equation,
     },
 }
 
 impl RichText {
+    /// Unannotated text without a link.
+    pub fn text<S: Into<String>>(content: S) -> Self {
+        let content = content.into();
+        RichText::Text {
+            rich_text: RichTextCommon {
+                plain_text: content.clone(),
+                href: None,
+                annotations: None,
+            },
+            text: Text {
+                content,
+                link: None,
+            },
+        }
+    }
+
+    /// An unannotated template mention, for writing database templates.
+    pub fn template_mention(template_mention: TemplateMention) -> Self {
+        RichText::Mention {
+            rich_text: RichTextCommon {
+                plain_text: template_mention.display_text().to_string(),
+                href: None,
+                annotations: None,
+            },
+            mention: MentionObject::TemplateMention { template_mention },
+        }
+    }
+
+    /// An unannotated mention of a user, which notifies them.
+    ///
+    /// Pass a [UserId](crate::ids::UserId) when only the id is known,
+    /// the plain text is then only `@` until Notion fills in the name.
+    pub fn user_mention<U: Into<UserReference>>(user: U) -> Self {
+        let user = user.into();
+        let name = user.user().and_then(|user| user.common().name.as_deref());
+        RichText::Mention {
+            rich_text: RichTextCommon {
+                plain_text: format!("@{}", name.unwrap_or_default()),
+                href: None,
+                annotations: None,
+            },
+            mention: MentionObject::User { user },
+        }
+    }
+
+    /// An unannotated inline equation.
+    pub fn equation<S: Into<String>>(expression: S) -> Self {
+        let expression = expression.into();
+        RichText::Equation {
+            rich_text: RichTextCommon {
+                plain_text: expression.clone(),
+                href: None,
+                annotations: None,
+            },
+            equation: Equation { expression },
+        }
+    }
+
+    /// An unannotated mention of a date or date range.
+    pub fn date_mention(date: DateValue) -> Self {
+        RichText::Mention {
+            rich_text: RichTextCommon {
+                plain_text: date.start.to_string(),
+                href: None,
+                annotations: None,
+            },
+            mention: MentionObject::Date { date },
+        }
+    }
+
+    pub fn bold(self) -> Self {
+        self.annotate(|annotations| annotations.bold = Some(true))
+    }
+
+    pub fn italic(self) -> Self {
+        self.annotate(|annotations| annotations.italic = Some(true))
+    }
+
+    pub fn strikethrough(self) -> Self {
+        self.annotate(|annotations| annotations.strikethrough = Some(true))
+    }
+
+    pub fn underline(self) -> Self {
+        self.annotate(|annotations| annotations.underline = Some(true))
+    }
+
+    /// Formats the text as inline code.
+    pub fn code(self) -> Self {
+        self.annotate(|annotations| annotations.code = Some(true))
+    }
+
+    pub fn color(
+        self,
+        color: TextColor,
+    ) -> Self {
+        self.annotate(|annotations| annotations.color = Some(color))
+    }
+
+    /// Links the text to `url`. Mentions and equations can't be links
+    /// and are returned unchanged.
+    ///
+    /// ```
+    /// use notion_wasi::models::text::{RichText, TextColor};
+    ///
+    /// let docs = RichText::text("the docs")
+    ///     .link("https://developers.notion.com")
+    ///     .bold()
+    ///     .color(TextColor::Blue);
+    /// assert_eq!(docs.plain_text(), "the docs");
+    /// ```
+    pub fn link<S: Into<String>>(
+        self,
+        url: S,
+    ) -> Self {
+        match self {
+            RichText::Text {
+                mut rich_text,
+                mut text,
+            } => {
+                let url = url.into();
+                rich_text.href = Some(url.clone());
+                text.link = Some(Link { url });
+                RichText::Text { rich_text, text }
+            }
+            rich_text => rich_text,
+        }
+    }
+
+    fn annotate(
+        mut self,
+        annotate: impl FnOnce(&mut Annotations),
+    ) -> Self {
+        use RichText::*;
+        let (Text { rich_text, .. } | Mention { rich_text, .. } | Equation { rich_text, .. }) =
+            &mut self;
+        annotate(
+            rich_text
+                .annotations
+                .get_or_insert_with(Annotations::default),
+        );
+        self
+    }
+
     pub fn plain_text(&self) -> &str {
         use RichText::*;
         match self {
@@ -123,3 +378,42 @@ impl RichText {
         }
     }
 }
+
+impl fmt::Display for RichText {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        f.write_str(self.plain_text())
+    }
+}
+
+/// Displays rich text as its concatenated plain text, without allocating.
+///
+/// ```
+/// use notion_wasi::models::text::{PlainText, RichText};
+///
+/// let rich_text = vec![RichText::equation("x^2"), RichText::equation(" + 1")];
+/// assert_eq!(format!("{}", PlainText(&rich_text)), "x^2 + 1");
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct PlainText<'a>(pub &'a [RichText]);
+
+impl fmt::Display for PlainText<'_> {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        self.0.iter().try_for_each(|rich_text| rich_text.fmt(f))
+    }
+}
+
+/// The concatenated plain text of `rich_text`.
+/// Borrows from the single element in the common case of unformatted text.
+pub fn plain_text(rich_text: &[RichText]) -> Cow<'_, str> {
+    match rich_text {
+        [] => Cow::Borrowed(""),
+        [single] => Cow::Borrowed(single.plain_text()),
+        many => Cow::Owned(many.iter().map(RichText::plain_text).collect()),
+    }
+}
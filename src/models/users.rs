@@ -4,18 +4,65 @@ use serde::{Deserialize, Serialize};
 #[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
 pub struct UserCommon {
     pub id: UserId,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub avatar_url: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
 pub struct Person {
-    pub email: String,
+    /// Only present when the integration has access to user emails.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
 pub struct Bot {
+    /// Empty for integrations, which have no email.
+    #[serde(default)]
     pub email: String,
+    /// Who installed the integration, only reported for the integration's own bot user.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<BotOwner>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_name: Option<String>,
+    /// Only reported for the integration's own bot user,
+    /// see [NotionApi::get_self()](crate::NotionApi::get_self()).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_limits: Option<WorkspaceLimits>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BotOwner {
+    /// Installed for the whole workspace.
+    Workspace { workspace: bool },
+    /// Installed by a user, for public integrations.
+    User { user: Box<UserReference> },
+}
+
+impl BotOwner {
+    /// Whether the integration is installed for the whole workspace.
+    pub fn is_workspace(&self) -> bool {
+        matches!(self, BotOwner::Workspace { .. })
+    }
+
+    /// The user that installed the integration.
+    pub fn user(&self) -> Option<&UserReference> {
+        match self {
+            BotOwner::User { user } => Some(user),
+            BotOwner::Workspace { .. } => None,
+        }
+    }
+}
+
+/// The limits of the workspace an integration is installed in.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Default)]
+pub struct WorkspaceLimits {
+    /// The largest file that can be uploaded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_file_upload_size_in_bytes: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
@@ -32,3 +79,73 @@ pub enum User {
         bot: Bot,
     },
 }
+
+impl User {
+    pub fn common(&self) -> &UserCommon {
+        match self {
+            User::Person { common, .. } | User::Bot { common, .. } => common,
+        }
+    }
+
+    pub fn id(&self) -> &UserId {
+        &self.common().id
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.common().name.as_deref()
+    }
+
+    /// The bot details, for integrations and the users they act as.
+    pub fn bot(&self) -> Option<&Bot> {
+        match self {
+            User::Bot { bot, .. } => Some(bot),
+            User::Person { .. } => None,
+        }
+    }
+
+    /// The workspace a bot is connected to, only reported for the integration's own bot user.
+    pub fn workspace_name(&self) -> Option<&str> {
+        self.bot()?.workspace_name.as_deref()
+    }
+}
+
+/// A user referenced from a property value.
+///
+/// Notion only guarantees the id, the remaining fields are included
+/// when the integration has the user information capability.
+/// [NotionApi::resolve_people()](crate::NotionApi::resolve_people()) fetches the full users.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[serde(untagged)]
+pub enum UserReference {
+    User(User),
+    Partial { id: UserId },
+}
+
+impl UserReference {
+    pub fn id(&self) -> &UserId {
+        match self {
+            UserReference::User(user) => user.id(),
+            UserReference::Partial { id } => id,
+        }
+    }
+
+    /// The full user, if Notion included it.
+    pub fn user(&self) -> Option<&User> {
+        match self {
+            UserReference::User(user) => Some(user),
+            UserReference::Partial { .. } => None,
+        }
+    }
+}
+
+impl From<UserId> for UserReference {
+    fn from(id: UserId) -> Self {
+        UserReference::Partial { id }
+    }
+}
+
+impl From<User> for UserReference {
+    fn from(user: User) -> Self {
+        UserReference::User(user)
+    }
+}
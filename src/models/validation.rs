@@ -0,0 +1,394 @@
+//! Request limits of the API, checked before a request is sent.
+//!
+//! See <https://developers.notion.com/reference/request-limits#limits-for-property-values>.
+
+use crate::models::properties::{PropertyValue, SelectedValue};
+use crate::models::text::{RichText, Text};
+use crate::models::{PageCreateRequest, Parent};
+
+/// The most characters of a text object, link or URL.
+const MAX_TEXT_LENGTH: usize = 2000;
+/// The most characters of an equation expression.
+const MAX_EQUATION_LENGTH: usize = 1000;
+/// The most elements of an array, such as rich text, relations or people.
+const MAX_ARRAY_LENGTH: usize = 100;
+/// The most characters of a select option name.
+const MAX_OPTION_NAME_LENGTH: usize = 100;
+/// The most characters of an email or phone number.
+const MAX_CONTACT_LENGTH: usize = 200;
+
+/// A [PageCreateRequest] that Notion would reject, found by [PageCreateRequest::validate()].
+#[derive(Debug, Eq, PartialEq, Clone, thiserror::Error)]
+pub enum InvalidPage {
+    #[error("Pages in a database need a title property")]
+    MissingTitle,
+    #[error("Pages have a single title property, but {} were given: {}", .properties.len(), .properties.join(", "))]
+    MultipleTitles { properties: Vec<String> },
+    #[error(
+        "Property {property:?} can't be set on a page outside a database, \
+         where the title is the only property"
+    )]
+    PropertyOutsideDatabase { property: String },
+    #[error("Property {property:?} has {length} {what}, but Notion accepts at most 100")]
+    TooManyElements {
+        property: String,
+        what: &'static str,
+        length: usize,
+    },
+    #[error(
+        "The page has {count} child blocks, but Notion accepts at most 100 per request; \
+         append the rest with append_block_children()"
+    )]
+    TooManyChildren { count: usize },
+    #[error(
+        "A text in property {property:?} has {length} characters, but Notion accepts at most 2000; \
+         split it into several rich text objects"
+    )]
+    TextTooLong { property: String, length: usize },
+    #[error("Property {property:?} has a {what} of {length} characters, but Notion accepts at most {limit}")]
+    TooLong {
+        property: String,
+        what: &'static str,
+        length: usize,
+        limit: usize,
+    },
+    #[error("Option {name:?} of property {property:?} is {length} characters long, but Notion accepts at most 100")]
+    OptionNameTooLong {
+        property: String,
+        name: String,
+        length: usize,
+    },
+    #[error("Option {name:?} of multi-select property {property:?} contains a comma, which Notion doesn't allow")]
+    OptionNameWithComma { property: String, name: String },
+}
+
+impl PageCreateRequest {
+    /// Checks the rules and [request limits](self) Notion applies to new pages,
+    /// so mistakes are reported with a clear message instead of a generic `validation_error`.
+    ///
+    /// [NotionApi::create_page()](crate::NotionApi::create_page()) validates
+    /// each request before sending it, and
+    /// [WriteQueue::create_page()](crate::WriteQueue::create_page()) before queueing it.
+    pub fn validate(&self) -> Result<(), InvalidPage> {
+        let mut titles: Vec<String> = self
+            .properties
+            .properties
+            .iter()
+            .filter(|(_, value)| matches!(value, PropertyValue::Title { .. }))
+            .map(|(name, _)| name.clone())
+            .collect();
+        titles.sort();
+        if titles.len() > 1 {
+            return Err(InvalidPage::MultipleTitles { properties: titles });
+        }
+        match &self.parent {
+            Parent::Database { .. } | Parent::DataSource { .. } if titles.is_empty() => {
+                return Err(InvalidPage::MissingTitle);
+            }
+            Parent::Page { .. } | Parent::Workspace | Parent::Block { .. } => {
+                if let Some((name, _)) = self
+                    .properties
+                    .properties
+                    .iter()
+                    .find(|(_, value)| !matches!(value, PropertyValue::Title { .. }))
+                {
+                    return Err(InvalidPage::PropertyOutsideDatabase {
+                        property: name.clone(),
+                    });
+                }
+            }
+            _ => {}
+        }
+
+        let mut names: Vec<&String> = self.properties.properties.keys().collect();
+        names.sort();
+        for name in names {
+            check_property(name, &self.properties.properties[name])?;
+        }
+
+        #[cfg(feature = "blocks")]
+        if let Some(children) = &self.children {
+            if children.len() > MAX_ARRAY_LENGTH {
+                return Err(InvalidPage::TooManyChildren {
+                    count: children.len(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+fn check_property(
+    property: &str,
+    value: &PropertyValue,
+) -> Result<(), InvalidPage> {
+    match value {
+        PropertyValue::Title {
+            title: rich_text, ..
+        }
+        | PropertyValue::Text { rich_text, .. } => check_rich_text(property, rich_text),
+        PropertyValue::Select {
+            select: Some(option),
+            ..
+        }
+        | PropertyValue::Status {
+            status: Some(option),
+            ..
+        } => check_option(property, option, false),
+        PropertyValue::MultiSelect {
+            multi_select: Some(options),
+            ..
+        } => {
+            check_length(property, "options", options.len())?;
+            options
+                .iter()
+                .try_for_each(|option| check_option(property, option, true))
+        }
+        PropertyValue::Relation {
+            relation: Some(relation),
+            ..
+        } => check_length(property, "relations", relation.len()),
+        PropertyValue::People { people, .. } => check_length(property, "people", people.len()),
+        PropertyValue::Files {
+            files: Some(files), ..
+        } => check_length(property, "files", files.len()),
+        PropertyValue::Url { url: Some(url), .. } => {
+            check_chars(property, "URL", url, MAX_TEXT_LENGTH)
+        }
+        PropertyValue::Email {
+            email: Some(email), ..
+        } => check_chars(property, "email", email, MAX_CONTACT_LENGTH),
+        PropertyValue::PhoneNumber {
+            phone_number: Some(phone_number),
+            ..
+        } => check_chars(property, "phone number", phone_number, MAX_CONTACT_LENGTH),
+        _ => Ok(()),
+    }
+}
+
+fn check_rich_text(
+    property: &str,
+    rich_text: &[RichText],
+) -> Result<(), InvalidPage> {
+    check_length(property, "rich text objects", rich_text.len())?;
+    for text in rich_text {
+        match text {
+            RichText::Text {
+                text: Text { content, link },
+                ..
+            } => {
+                let length = content.chars().count();
+                if length > MAX_TEXT_LENGTH {
+                    return Err(InvalidPage::TextTooLong {
+                        property: property.to_string(),
+                        length,
+                    });
+                }
+                if let Some(link) = link {
+                    check_chars(property, "link", &link.url, MAX_TEXT_LENGTH)?;
+                }
+            }
+            RichText::Equation { equation, .. } => check_chars(
+                property,
+                "equation",
+                &equation.expression,
+                MAX_EQUATION_LENGTH,
+            )?,
+            RichText::Mention { .. } => {}
+        }
+    }
+    Ok(())
+}
+
+fn check_option(
+    property: &str,
+    option: &SelectedValue,
+    multi_select: bool,
+) -> Result<(), InvalidPage> {
+    let name = match &option.name {
+        Some(name) => name,
+        None => return Ok(()),
+    };
+    let length = name.chars().count();
+    if length > MAX_OPTION_NAME_LENGTH {
+        return Err(InvalidPage::OptionNameTooLong {
+            property: property.to_string(),
+            name: name.clone(),
+            length,
+        });
+    }
+    if multi_select && name.contains(',') {
+        return Err(InvalidPage::OptionNameWithComma {
+            property: property.to_string(),
+            name: name.clone(),
+        });
+    }
+    Ok(())
+}
+
+fn check_length(
+    property: &str,
+    what: &'static str,
+    length: usize,
+) -> Result<(), InvalidPage> {
+    if length > MAX_ARRAY_LENGTH {
+        return Err(InvalidPage::TooManyElements {
+            property: property.to_string(),
+            what,
+            length,
+        });
+    }
+    Ok(())
+}
+
+fn check_chars(
+    property: &str,
+    what: &'static str,
+    value: &str,
+    limit: usize,
+) -> Result<(), InvalidPage> {
+    let length = value.chars().count();
+    if length > limit {
+        return Err(InvalidPage::TooLong {
+            property: property.to_string(),
+            what,
+            length,
+            limit,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InvalidPage;
+    use crate::ids::{DatabaseId, PageId, PropertyId};
+    use crate::models::properties::{Color, PropertyValue, SelectedValue};
+    use crate::models::text::RichText;
+    use crate::models::{PageCreateRequest, Parent, Properties};
+    use std::str::FromStr;
+
+    fn database() -> Parent {
+        Parent::Database {
+            database_id: DatabaseId::from_str("database").unwrap(),
+        }
+    }
+
+    fn title(text: &str) -> PropertyValue {
+        PropertyValue::Title {
+            id: PropertyId::from_str("title").unwrap(),
+            title: vec![RichText::text(text)],
+        }
+    }
+
+    fn tags(names: &[&str]) -> PropertyValue {
+        PropertyValue::MultiSelect {
+            id: PropertyId::from_str("tags").unwrap(),
+            multi_select: Some(
+                names
+                    .iter()
+                    .map(|name| SelectedValue {
+                        id: None,
+                        name: Some(name.to_string()),
+                        color: Color::Default,
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    fn request(
+        parent: Parent,
+        properties: Vec<(&str, PropertyValue)>,
+    ) -> PageCreateRequest {
+        let properties = properties
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect();
+        PageCreateRequest::new(parent, Properties { properties })
+    }
+
+    #[test]
+    fn valid_pages() {
+        let page = request(
+            database(),
+            vec![("Name", title("Report")), ("Tags", tags(&["urgent"]))],
+        );
+        assert_eq!(page.validate(), Ok(()));
+
+        let page = request(
+            Parent::Page {
+                page_id: PageId::from_str("parent").unwrap(),
+            },
+            vec![("title", title("Notes"))],
+        );
+        assert_eq!(page.validate(), Ok(()));
+    }
+
+    #[test]
+    fn titles() {
+        let page = request(database(), vec![("Tags", tags(&["urgent"]))]);
+        assert_eq!(page.validate(), Err(InvalidPage::MissingTitle));
+
+        let page = request(database(), vec![("B", title("b")), ("A", title("a"))]);
+        assert_eq!(
+            page.validate(),
+            Err(InvalidPage::MultipleTitles {
+                properties: vec!["A".to_string(), "B".to_string()]
+            })
+        );
+
+        let page = request(
+            Parent::Workspace,
+            vec![("title", title("Notes")), ("Tags", tags(&[]))],
+        );
+        assert_eq!(
+            page.validate(),
+            Err(InvalidPage::PropertyOutsideDatabase {
+                property: "Tags".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn limits() {
+        let page = request(database(), vec![("Name", title(&"a".repeat(2001)))]);
+        let error = page.validate().unwrap_err();
+        assert_eq!(
+            error,
+            InvalidPage::TextTooLong {
+                property: "Name".to_string(),
+                length: 2001
+            }
+        );
+        assert!(error.to_string().contains("split it"));
+
+        let long = "a".repeat(101);
+        let page = request(
+            database(),
+            vec![("Name", title("Report")), ("Tags", tags(&[&long]))],
+        );
+        assert!(matches!(
+            page.validate(),
+            Err(InvalidPage::OptionNameTooLong { length: 101, .. })
+        ));
+
+        let page = request(
+            database(),
+            vec![("Name", title("Report")), ("Tags", tags(&["a, b"]))],
+        );
+        assert!(matches!(
+            page.validate(),
+            Err(InvalidPage::OptionNameWithComma { .. })
+        ));
+
+        let page = request(
+            database(),
+            vec![("Name", title("Report")), ("Tags", tags(&["a"; 101]))],
+        );
+        assert!(matches!(
+            page.validate(),
+            Err(InvalidPage::TooManyElements { length: 101, .. })
+        ));
+    }
+}
@@ -0,0 +1,88 @@
+//! Conversions between the models and untyped [serde_json::Value]s,
+//! for fields the models don't cover yet and payloads that arrive as JSON,
+//! such as webhook bodies.
+
+use std::convert::TryFrom;
+
+use serde_json::Value;
+
+#[cfg(feature = "blocks")]
+use crate::models::block::{Block, CreateBlock};
+use crate::models::properties::{PropertyItem, PropertyValue};
+use crate::models::text::RichText;
+use crate::models::users::User;
+use crate::models::webhooks::{WebhookEvent, WebhookPayload};
+#[cfg(feature = "databases")]
+use crate::models::{DataSource, Database};
+use crate::models::{Object, Page, PageCreateRequest, PageUpdateRequest, Properties};
+
+macro_rules! into_value {
+    ($($(#[$attr:meta])* $model:ty),* $(,)?) => {
+        $(
+            $(#[$attr])*
+            impl $model {
+                /// The JSON the API would send or receive for this value.
+                pub fn into_value(self) -> Value {
+                    serde_json::to_value(self).expect("models serialize to JSON")
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! from_value {
+    ($($(#[$attr:meta])* $model:ty),* $(,)?) => {
+        $(
+            $(#[$attr])*
+            impl TryFrom<Value> for $model {
+                type Error = serde_json::Error;
+
+                fn try_from(value: Value) -> Result<Self, serde_json::Error> {
+                    serde_json::from_value(value)
+                }
+            }
+        )*
+    };
+}
+
+into_value!(
+    Object,
+    Page,
+    PageCreateRequest,
+    PageUpdateRequest,
+    Properties,
+    PropertyValue,
+    PropertyItem,
+    RichText,
+    User,
+    #[cfg(feature = "blocks")]
+    Block,
+    #[cfg(feature = "blocks")]
+    CreateBlock,
+    #[cfg(feature = "databases")]
+    Database,
+    #[cfg(feature = "databases")]
+    DataSource,
+    WebhookPayload,
+    WebhookEvent,
+);
+
+from_value!(
+    Object,
+    Page,
+    Properties,
+    PropertyValue,
+    PropertyItem,
+    RichText,
+    User,
+    #[cfg(feature = "blocks")]
+    Block,
+    #[cfg(feature = "blocks")]
+    CreateBlock,
+    #[cfg(feature = "databases")]
+    Database,
+    #[cfg(feature = "databases")]
+    DataSource,
+    WebhookPayload,
+    WebhookEvent,
+);
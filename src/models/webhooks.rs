@@ -0,0 +1,382 @@
+//! Events Notion sends to webhook subscriptions, and a router that hands
+//! each event to the handlers registered for its type.
+//!
+//! ```no_run
+//! # async fn example(body: &[u8]) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+//! use notion_wasi::models::webhooks::{parse_webhook, WebhookEventType, WebhookPayload, WebhookRouter};
+//!
+//! let router: WebhookRouter = WebhookRouter::new()
+//!     .on(WebhookEventType::PageCreated, |event| async move {
+//!         println!("page {} created", event.entity.id);
+//!         Ok(())
+//!     })
+//!     .on(WebhookEventType::CommentCreated, |event| async move {
+//!         println!("comment on {:?}", event.data.page_id);
+//!         Ok(())
+//!     });
+//!
+//! match parse_webhook(body)? {
+//!     WebhookPayload::Verification { verification_token } => {
+//!         println!("paste {} into the integration settings", verification_token);
+//!     }
+//!     WebhookPayload::Event(event) => {
+//!         router.dispatch(event).await?;
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Events only say what changed, not how: fetch the entity to see its new state.
+//! See <https://developers.notion.com/reference/webhooks-events-delivery>
+
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+
+use crate::ids::{BlockId, CommentId, DataSourceId, DatabaseId, PageId, PropertyId, UserId};
+use crate::models::{DateTime, Utc};
+
+macro_rules! event_types {
+    ($($variant:ident => $name:literal),* $(,)?) => {
+        /// The `type` of a [WebhookEvent].
+        #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Hash, Clone)]
+        #[serde(from = "String", into = "String")]
+        pub enum WebhookEventType {
+            $(
+                #[doc = concat!("`", $name, "`")]
+                $variant,
+            )*
+            /// A type this crate doesn't know yet.
+            Other(String),
+        }
+
+        impl WebhookEventType {
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(WebhookEventType::$variant => $name,)*
+                    WebhookEventType::Other(name) => name,
+                }
+            }
+        }
+
+        impl From<String> for WebhookEventType {
+            fn from(name: String) -> Self {
+                match name.as_str() {
+                    $($name => WebhookEventType::$variant,)*
+                    _ => WebhookEventType::Other(name),
+                }
+            }
+        }
+    };
+}
+
+event_types! {
+    PageCreated => "page.created",
+    PageContentUpdated => "page.content_updated",
+    PagePropertiesUpdated => "page.properties_updated",
+    PageMoved => "page.moved",
+    PageDeleted => "page.deleted",
+    PageUndeleted => "page.undeleted",
+    PageLocked => "page.locked",
+    PageUnlocked => "page.unlocked",
+    DatabaseCreated => "database.created",
+    DatabaseContentUpdated => "database.content_updated",
+    DatabaseSchemaUpdated => "database.schema_updated",
+    DatabaseMoved => "database.moved",
+    DatabaseDeleted => "database.deleted",
+    DatabaseUndeleted => "database.undeleted",
+    DataSourceCreated => "data_source.created",
+    DataSourceContentUpdated => "data_source.content_updated",
+    DataSourceSchemaUpdated => "data_source.schema_updated",
+    DataSourceMoved => "data_source.moved",
+    DataSourceDeleted => "data_source.deleted",
+    DataSourceUndeleted => "data_source.undeleted",
+    CommentCreated => "comment.created",
+    CommentUpdated => "comment.updated",
+    CommentDeleted => "comment.deleted",
+}
+
+impl From<WebhookEventType> for String {
+    fn from(event_type: WebhookEventType) -> Self {
+        match event_type {
+            WebhookEventType::Other(name) => name,
+            event_type => event_type.as_str().to_string(),
+        }
+    }
+}
+
+impl fmt::Display for WebhookEventType {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The kind of object a webhook event is about.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Copy, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEntityType {
+    Page,
+    Database,
+    DataSource,
+    Block,
+    Comment,
+    /// The workspace, as the parent of top-level pages.
+    Space,
+    Person,
+    Bot,
+    #[serde(other)]
+    Other,
+}
+
+/// An object referenced by a webhook event.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct WebhookEntity {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub kind: WebhookEntityType,
+}
+
+impl WebhookEntity {
+    pub fn page_id(&self) -> Option<PageId> {
+        self.id_of(WebhookEntityType::Page)
+    }
+
+    pub fn database_id(&self) -> Option<DatabaseId> {
+        self.id_of(WebhookEntityType::Database)
+    }
+
+    pub fn data_source_id(&self) -> Option<DataSourceId> {
+        self.id_of(WebhookEntityType::DataSource)
+    }
+
+    pub fn block_id(&self) -> Option<BlockId> {
+        self.id_of(WebhookEntityType::Block)
+    }
+
+    pub fn comment_id(&self) -> Option<CommentId> {
+        self.id_of(WebhookEntityType::Comment)
+    }
+
+    /// The id of a person or bot.
+    pub fn user_id(&self) -> Option<UserId> {
+        match self.kind {
+            WebhookEntityType::Person | WebhookEntityType::Bot => self.id.parse().ok(),
+            _ => None,
+        }
+    }
+
+    fn id_of<T: std::str::FromStr>(
+        &self,
+        kind: WebhookEntityType,
+    ) -> Option<T> {
+        if self.kind == kind {
+            self.id.parse().ok()
+        } else {
+            None
+        }
+    }
+}
+
+/// The details of a [WebhookEvent], which depend on its type.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Default)]
+pub struct WebhookEventData {
+    /// The parent of the entity, the new one for `*.moved` events.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<WebhookEntity>,
+    /// The ids of the changed properties, for `page.properties_updated`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub updated_properties: Vec<PropertyId>,
+    /// The changed blocks, for `page.content_updated`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub updated_blocks: Vec<WebhookEntity>,
+    /// The page the comment is on, for `comment.*` events.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_id: Option<PageId>,
+}
+
+/// A change in a workspace, delivered to a webhook subscription.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct WebhookEvent {
+    /// Unique per event, and the same for repeated deliveries of the event.
+    pub id: String,
+    pub timestamp: DateTime<Utc>,
+    #[serde(rename = "type")]
+    pub kind: WebhookEventType,
+    pub workspace_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_name: Option<String>,
+    pub subscription_id: String,
+    pub integration_id: String,
+    /// The people and bots who made the change.
+    #[serde(default)]
+    pub authors: Vec<WebhookEntity>,
+    /// Counts from 1, higher for retried deliveries.
+    #[serde(default)]
+    pub attempt_number: u32,
+    /// The page, database, data source or comment the event is about.
+    pub entity: WebhookEntity,
+    #[serde(default)]
+    pub data: WebhookEventData,
+}
+
+/// The body of a request to a webhook endpoint.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
+pub enum WebhookPayload {
+    /// Sent once when the subscription is created, to confirm the endpoint.
+    Verification {
+        verification_token: String,
+    },
+    Event(WebhookEvent),
+}
+
+/// Parses the body of a request to a webhook endpoint.
+pub fn parse_webhook(body: &[u8]) -> Result<WebhookPayload, serde_json::Error> {
+    serde_json::from_slice(body)
+}
+
+type Handler<E> = Box<dyn Fn(WebhookEvent) -> BoxFuture<'static, Result<(), E>> + Send + Sync>;
+
+/// Runs the handlers registered for the type of each event, see the [module docs](self).
+pub struct WebhookRouter<E = Box<dyn std::error::Error + Send + Sync>> {
+    handlers: HashMap<WebhookEventType, Vec<Handler<E>>>,
+    fallback: Option<Handler<E>>,
+}
+
+impl<E> Default for WebhookRouter<E> {
+    fn default() -> Self {
+        WebhookRouter {
+            handlers: HashMap::new(),
+            fallback: None,
+        }
+    }
+}
+
+impl<E> WebhookRouter<E> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs `handler` for events of type `kind`, after the handlers added before.
+    pub fn on<F, Fut>(
+        mut self,
+        kind: WebhookEventType,
+        handler: F,
+    ) -> Self
+    where
+        F: Fn(WebhookEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), E>> + Send + 'static,
+    {
+        self.handlers
+            .entry(kind)
+            .or_default()
+            .push(Box::new(move |event| Box::pin(handler(event))));
+        self
+    }
+
+    /// Runs `handler` for events without a handler of their own.
+    pub fn fallback<F, Fut>(
+        mut self,
+        handler: F,
+    ) -> Self
+    where
+        F: Fn(WebhookEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), E>> + Send + 'static,
+    {
+        self.fallback = Some(Box::new(move |event| Box::pin(handler(event))));
+        self
+    }
+
+    /// Runs the handlers of the type of `event` one after the other,
+    /// stopping at the first error.
+    ///
+    /// Returns the number of handlers run, 0 if the event was ignored.
+    pub async fn dispatch(
+        &self,
+        event: WebhookEvent,
+    ) -> Result<usize, E> {
+        let handlers = match self.handlers.get(&event.kind) {
+            Some(handlers) => handlers.iter().collect(),
+            None => self.fallback.iter().collect::<Vec<_>>(),
+        };
+        for handler in &handlers {
+            handler(event.clone()).await?;
+        }
+        Ok(handlers.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::{WebhookEvent, WebhookEventType, WebhookRouter};
+
+    fn event(kind: &str) -> WebhookEvent {
+        serde_json::from_value(serde_json::json!({
+            "id": "event",
+            "timestamp": "2024-12-05T23:57:05.379Z",
+            "type": kind,
+            "workspace_id": "workspace",
+            "subscription_id": "subscription",
+            "integration_id": "integration",
+            "entity": { "id": "153104cd-477e-809d-8dc4-ff2d96ae3090", "type": "page" },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn events_go_to_their_handlers() {
+        let seen = Arc::new(Mutex::new(vec![]));
+        let record = |label: &'static str| {
+            let seen = seen.clone();
+            move |event: WebhookEvent| {
+                let seen = seen.clone();
+                async move {
+                    seen.lock()
+                        .unwrap()
+                        .push(format!("{} {}", label, event.kind));
+                    Ok(())
+                }
+            }
+        };
+        let router: WebhookRouter<String> = WebhookRouter::new()
+            .on(WebhookEventType::PageCreated, record("first"))
+            .on(WebhookEventType::PageCreated, record("second"))
+            .on(WebhookEventType::PageDeleted, |_| async {
+                Err("deleted".to_string())
+            })
+            .fallback(record("fallback"));
+
+        futures::executor::block_on(async {
+            assert_eq!(router.dispatch(event("page.created")).await, Ok(2));
+            assert_eq!(router.dispatch(event("page.moved")).await, Ok(1));
+            assert_eq!(router.dispatch(event("view.created")).await, Ok(1));
+            assert_eq!(
+                router.dispatch(event("page.deleted")).await,
+                Err("deleted".to_string())
+            );
+        });
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![
+                "first page.created",
+                "second page.created",
+                "fallback page.moved",
+                "fallback view.created",
+            ]
+        );
+        assert_eq!(
+            event("view.created").kind,
+            WebhookEventType::Other("view.created".to_string())
+        );
+    }
+}
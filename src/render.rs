@@ -0,0 +1,9 @@
+//! Rendering blocks and rich text to other formats.
+
+mod build;
+#[cfg(feature = "html")]
+pub mod html;
+pub mod markdown;
+pub mod outline;
+pub mod print;
+pub mod reading;
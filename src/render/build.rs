@@ -0,0 +1,192 @@
+//! Helpers for building blocks to create, shared by the parsers.
+
+use crate::models::block::{
+    CodeFields, CodeLanguage, CreateBlock, CreateBlockTree, ExternalFileObject, FileFields,
+    FileObject, TableFields, TableRowFields, Text, TextAndChildren,
+};
+use crate::models::text::{self, Annotations, Link, RichText, RichTextCommon, TextColor};
+
+/// The most characters the API accepts in one rich text object.
+const MAX_TEXT_LENGTH: usize = 2000;
+
+/// The text of a leading paragraph, to be used as the text of its container,
+/// and the remaining blocks.
+pub(crate) fn leading_text(
+    mut children: Vec<CreateBlockTree>
+) -> (Vec<RichText>, Vec<CreateBlockTree>) {
+    match children.first() {
+        Some(CreateBlockTree {
+            block: CreateBlock::Paragraph { .. },
+            ..
+        }) => match children.remove(0).block {
+            CreateBlock::Paragraph { paragraph } => (paragraph.rich_text, children),
+            _ => unreachable!(),
+        },
+        _ => (vec![], children),
+    }
+}
+
+/// A table with one row per element of `rows`, padded to the widest row.
+pub(crate) fn table(
+    rows: Vec<Vec<Vec<RichText>>>,
+    has_column_header: bool,
+) -> CreateBlockTree {
+    let width = rows.iter().map(Vec::len).max().unwrap_or_default();
+    CreateBlockTree {
+        block: CreateBlock::Table {
+            table: TableFields {
+                table_width: width as u64,
+                has_column_header,
+                has_row_header: false,
+                children: vec![],
+            },
+        },
+        children: rows
+            .into_iter()
+            .map(|mut cells| {
+                cells.resize(width, vec![]);
+                CreateBlock::TableRow {
+                    table_row: TableRowFields { cells },
+                }
+                .into()
+            })
+            .collect(),
+    }
+}
+
+/// A code block, with the language named like a Markdown info string or class name.
+pub(crate) fn code_block(
+    info: &str,
+    code: &str,
+) -> CreateBlock {
+    let name = info
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    let name = match name.as_str() {
+        "rs" => "rust",
+        "js" | "jsx" => "javascript",
+        "ts" | "tsx" => "typescript",
+        "py" => "python",
+        "rb" => "ruby",
+        "sh" | "zsh" | "console" => "shell",
+        "yml" => "yaml",
+        "md" => "markdown",
+        "cpp" | "cc" => "c++",
+        "cs" => "c#",
+        "" | "text" | "txt" => "plain text",
+        name => name,
+    };
+    let language = serde_json::from_value(serde_json::Value::String(name.to_string()))
+        .unwrap_or(CodeLanguage::PlainText);
+
+    CreateBlock::Code {
+        code: CodeFields {
+            rich_text: chunks(code).map(RichText::text).collect(),
+            caption: vec![],
+            language,
+        },
+    }
+}
+
+pub(crate) fn heading_block(
+    level: usize,
+    rich_text: Vec<RichText>,
+) -> CreateBlock {
+    let fields = Text {
+        rich_text,
+        color: TextColor::Default,
+    };
+    match level {
+        1 => CreateBlock::Heading1 { heading_1: fields },
+        2 => CreateBlock::Heading2 { heading_2: fields },
+        _ => CreateBlock::Heading3 { heading_3: fields },
+    }
+}
+
+pub(crate) fn image_block(
+    caption: Vec<RichText>,
+    url: &str,
+) -> CreateBlock {
+    CreateBlock::Image {
+        image: FileFields {
+            caption,
+            file: FileObject::External {
+                external: ExternalFileObject {
+                    url: url.to_string(),
+                },
+            },
+        },
+    }
+}
+
+pub(crate) fn text_and_children(rich_text: Vec<RichText>) -> TextAndChildren {
+    TextAndChildren {
+        rich_text,
+        children: None,
+        color: TextColor::Default,
+    }
+}
+
+/// Splits `text` into pieces the API accepts in one rich text object.
+pub(crate) fn chunks(text: &str) -> impl Iterator<Item = String> + '_ {
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let end = rest
+            .char_indices()
+            .nth(MAX_TEXT_LENGTH)
+            .map_or(rest.len(), |(index, _)| index);
+        let (chunk, tail) = rest.split_at(end);
+        rest = tail;
+        Some(chunk.to_string())
+    })
+}
+
+/// The annotations and link of a span of text being parsed.
+#[derive(Debug, Default, Eq, PartialEq, Clone)]
+pub(crate) struct Style {
+    pub(crate) bold: bool,
+    pub(crate) italic: bool,
+    pub(crate) strikethrough: bool,
+    pub(crate) underline: bool,
+    pub(crate) code: bool,
+    pub(crate) link: Option<String>,
+}
+
+impl Style {
+    pub(crate) fn annotations(&self) -> Option<Annotations> {
+        if !(self.bold || self.italic || self.strikethrough || self.underline || self.code) {
+            return None;
+        }
+        let flag = |enabled: bool| if enabled { Some(true) } else { None };
+        Some(Annotations {
+            bold: flag(self.bold),
+            code: flag(self.code),
+            color: None,
+            italic: flag(self.italic),
+            strikethrough: flag(self.strikethrough),
+            underline: flag(self.underline),
+        })
+    }
+
+    pub(crate) fn rich_text(
+        &self,
+        content: String,
+    ) -> RichText {
+        RichText::Text {
+            rich_text: RichTextCommon {
+                plain_text: content.clone(),
+                href: self.link.clone(),
+                annotations: self.annotations(),
+            },
+            text: text::Text {
+                content,
+                link: self.link.clone().map(|url| Link { url }),
+            },
+        }
+    }
+}
@@ -0,0 +1,850 @@
+//! Best-effort conversion of HTML into blocks to create, for migrating pages
+//! from a CMS or a Confluence export. Enabled with the `html` feature.
+//!
+//! Headings, paragraphs, lists with nesting and checkboxes, tables, images,
+//! figures, code blocks, quotes, horizontal rules and `<details>` become their
+//! blocks, and `<strong>`, `<em>`, `<u>`, `<s>`, `<code>` and links become
+//! annotated rich text. Confluence code macros become code blocks and info,
+//! note, tip and warning panels become callouts.
+//!
+//! Markup is parsed leniently: unclosed elements are closed where HTML would
+//! close them, and unknown elements are replaced by their contents. Scripts,
+//! styles and the document head are dropped, and so are images with a relative
+//! `src`, because Notion can only embed absolute URLs.
+
+use crate::models::block::{
+    Callout, CreateBlock, CreateBlockTree, EmptyFields, FileOrEmojiObject, ToDoFields,
+};
+use crate::models::text::{RichText, TextColor};
+use crate::render::build::{
+    chunks, code_block, heading_block, image_block, leading_text, table, text_and_children, Style,
+};
+
+/// Elements without content or a closing tag.
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Elements whose content is dropped.
+const SKIPPED_ELEMENTS: &[&str] = &["head", "script", "style", "template", "noscript"];
+
+/// Elements that start a new block, and close an open `<p>`.
+const BLOCK_ELEMENTS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "details",
+    "div",
+    "dl",
+    "fieldset",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "img",
+    "li",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "ul",
+    "ac:structured-macro",
+];
+
+/// Parses an HTML document or fragment into block trees.
+pub fn parse(html: &str) -> Vec<CreateBlockTree> {
+    blocks(&document(html).children, &Style::default())
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Element(Element),
+    Text(String),
+}
+
+#[derive(Debug, Clone)]
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Node>,
+}
+
+impl Element {
+    fn new(name: &str) -> Self {
+        Element {
+            name: name.to_string(),
+            attributes: vec![],
+            children: vec![],
+        }
+    }
+
+    fn attribute(
+        &self,
+        name: &str,
+    ) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(attribute, _)| attribute == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|child| match child {
+            Node::Element(element) => Some(element),
+            Node::Text(_) => None,
+        })
+    }
+
+    fn find(
+        &self,
+        name: &str,
+    ) -> Option<&Element> {
+        self.elements().find_map(|element| {
+            if element.name == name {
+                Some(element)
+            } else {
+                element.find(name)
+            }
+        })
+    }
+
+    /// The text of the element and its descendants, as is.
+    fn text(&self) -> String {
+        self.children
+            .iter()
+            .map(|child| match child {
+                Node::Element(element) if element.name == "br" => "\n".to_string(),
+                Node::Element(element) => element.text(),
+                Node::Text(text) => text.clone(),
+            })
+            .collect()
+    }
+
+    fn is_block(&self) -> bool {
+        BLOCK_ELEMENTS.contains(&self.name.as_str()) || self.elements().any(Element::is_block)
+    }
+}
+
+/// Builds the element tree of `html`, under a root element.
+fn document(html: &str) -> Element {
+    let mut stack = vec![Element::new("#document")];
+    let mut i = 0;
+
+    while i < html.len() {
+        let rest = &html[i..];
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            i += 4 + comment.find("-->").map_or(comment.len(), |end| end + 3);
+        } else if let Some(data) = rest.strip_prefix("<![CDATA[") {
+            let end = data.find("]]>").unwrap_or(data.len());
+            push_text(&mut stack, data[..end].to_string());
+            i += 9 + (end + 3).min(data.len());
+        } else if rest.starts_with("<!") || rest.starts_with("<?") {
+            i += rest.find('>').map_or(rest.len(), |end| end + 1);
+        } else if let Some(tag) = rest.strip_prefix("</") {
+            match tag.find('>') {
+                Some(end) => {
+                    close(&mut stack, &tag[..end].trim().to_lowercase());
+                    i += end + 3;
+                }
+                None => i = html.len(),
+            }
+        } else if let Some((element, self_closing, length)) = start_tag(rest) {
+            i += length;
+            let name = element.name.clone();
+            if SKIPPED_ELEMENTS.contains(&name.as_str()) && !self_closing {
+                let closing = format!("</{}", name);
+                i += html[i..]
+                    .to_ascii_lowercase()
+                    .find(&closing)
+                    .map_or(html.len() - i, |end| {
+                        end + html[i + end..].find('>').map_or(0, |close| close + 1)
+                    });
+                continue;
+            }
+
+            open(&mut stack, element);
+            if self_closing || VOID_ELEMENTS.contains(&name.as_str()) {
+                close(&mut stack, &name);
+            }
+        } else {
+            // `rest` starts with text, or with a `<` that doesn't open a tag.
+            let first = rest.chars().next().map_or(1, char::len_utf8);
+            let end = rest[first..]
+                .find('<')
+                .map_or(rest.len(), |end| end + first);
+            push_text(&mut stack, decode(&rest[..end]));
+            i += end;
+        }
+    }
+
+    while stack.len() > 1 {
+        pop(&mut stack);
+    }
+    stack.pop().unwrap_or_else(|| Element::new("#document"))
+}
+
+fn push_text(
+    stack: &mut [Element],
+    text: String,
+) {
+    if let Some(parent) = stack.last_mut() {
+        parent.children.push(Node::Text(text));
+    }
+}
+
+fn pop(stack: &mut Vec<Element>) {
+    if let Some(element) = stack.pop() {
+        if let Some(parent) = stack.last_mut() {
+            parent.children.push(Node::Element(element));
+        }
+    }
+}
+
+/// Closes the innermost open `name`, and every element opened after it.
+fn close(
+    stack: &mut Vec<Element>,
+    name: &str,
+) {
+    if let Some(position) = stack
+        .iter()
+        .skip(1)
+        .rposition(|element| element.name == name)
+    {
+        while stack.len() > position + 1 {
+            pop(stack);
+        }
+    }
+}
+
+/// Closes the innermost open element named in `names`, unless one of
+/// `boundaries` is open inside it, like HTML does for `<li>` or `<td>`.
+fn close_implied(
+    stack: &mut Vec<Element>,
+    names: &[&str],
+    boundaries: &[&str],
+) {
+    for (position, element) in stack.iter().enumerate().skip(1).rev() {
+        if boundaries.contains(&element.name.as_str()) {
+            return;
+        }
+        if names.contains(&element.name.as_str()) {
+            while stack.len() > position {
+                pop(stack);
+            }
+            return;
+        }
+    }
+}
+
+fn open(
+    stack: &mut Vec<Element>,
+    element: Element,
+) {
+    match element.name.as_str() {
+        "li" => close_implied(stack, &["li"], &["ul", "ol"]),
+        "dt" | "dd" => close_implied(stack, &["dt", "dd"], &["dl"]),
+        "tr" => close_implied(stack, &["tr", "td", "th"], &["table"]),
+        "td" | "th" => close_implied(stack, &["td", "th"], &["tr", "table"]),
+        "thead" | "tbody" | "tfoot" => {
+            close_implied(stack, &["thead", "tbody", "tfoot"], &["table"])
+        }
+        _ => {}
+    }
+    if BLOCK_ELEMENTS.contains(&element.name.as_str()) {
+        close_implied(stack, &["p"], &["div", "li", "td", "th", "blockquote"]);
+    }
+    stack.push(element);
+}
+
+/// Parses a start tag, returning the element, whether it is self-closing
+/// and the length of the tag.
+fn start_tag(html: &str) -> Option<(Element, bool, usize)> {
+    let tag = html.strip_prefix('<')?;
+    let name_length = tag
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == ':'))
+        .unwrap_or(tag.len());
+    if name_length == 0 || !tag.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let mut element = Element::new(&tag[..name_length].to_lowercase());
+
+    let mut rest = &tag[name_length..];
+    loop {
+        rest = rest.trim_start();
+        if let Some(after) = rest.strip_prefix("/>") {
+            return Some((element, true, html.len() - after.len()));
+        }
+        if let Some(after) = rest.strip_prefix('>') {
+            return Some((element, false, html.len() - after.len()));
+        }
+        if rest.is_empty() {
+            return None;
+        }
+
+        let name_end = rest
+            .find(|c: char| c.is_whitespace() || c == '=' || c == '>' || c == '/')
+            .unwrap_or(rest.len())
+            .max(1);
+        let name = rest[..name_end].to_lowercase();
+        rest = rest[name_end..].trim_start();
+
+        let mut value = String::new();
+        if let Some(after) = rest.strip_prefix('=') {
+            let after = after.trim_start();
+            match after.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let end = after[1..].find(quote).map_or(after.len(), |end| end + 1);
+                    value = decode(&after[1..end]);
+                    rest = after.get(end + 1..).unwrap_or_default();
+                }
+                _ => {
+                    let end = after
+                        .find(|c: char| c.is_whitespace() || c == '>')
+                        .unwrap_or(after.len());
+                    value = decode(&after[..end]);
+                    rest = &after[end..];
+                }
+            }
+        }
+        if name != "/" {
+            element.attributes.push((name, value));
+        }
+    }
+}
+
+/// Decodes character references.
+fn decode(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let reference = rest[1..]
+            .find(';')
+            .filter(|&end| end <= 10)
+            .map(|end| &rest[1..end + 1]);
+        let character = reference.and_then(|reference| match reference {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some('\u{a0}'),
+            "ndash" => Some('–'),
+            "mdash" => Some('—'),
+            "hellip" => Some('…'),
+            "rsquo" => Some('’'),
+            "lsquo" => Some('‘'),
+            "rdquo" => Some('”'),
+            "ldquo" => Some('“'),
+            _ => {
+                let number = reference.strip_prefix('#')?;
+                let code = match number.strip_prefix(['x', 'X']) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                    None => number.parse().ok()?,
+                };
+                char::from_u32(code)
+            }
+        });
+        match (reference, character) {
+            (Some(reference), Some(character)) => {
+                decoded.push(character);
+                rest = &rest[reference.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// Converts nodes to blocks, wrapping loose inline content in paragraphs.
+fn blocks(
+    nodes: &[Node],
+    style: &Style,
+) -> Vec<CreateBlockTree> {
+    let mut trees = vec![];
+    let mut text = Inline::default();
+
+    for node in nodes {
+        let element = match node {
+            Node::Element(element) if element.is_block() => element,
+            node => {
+                text.node(node, style);
+                continue;
+            }
+        };
+
+        let paragraph = std::mem::take(&mut text).finish();
+        if !paragraph.is_empty() {
+            trees.push(paragraph_block(paragraph));
+        }
+        trees.extend(block(element, style));
+    }
+
+    let paragraph = text.finish();
+    if !paragraph.is_empty() {
+        trees.push(paragraph_block(paragraph));
+    }
+    trees
+}
+
+fn block(
+    element: &Element,
+    style: &Style,
+) -> Vec<CreateBlockTree> {
+    let name = element.name.as_str();
+    match name {
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+            let level = name[1..].parse().unwrap_or(3);
+            vec![heading_block(level, inline(&element.children, style)).into()]
+        }
+        "p" => blocks(&element.children, style),
+        "ul" | "ol" => element
+            .elements()
+            .flat_map(|child| match child.name.as_str() {
+                "li" => vec![list_item(child, name == "ol", style)],
+                _ => block(child, style),
+            })
+            .collect(),
+        "li" => vec![list_item(element, false, style)],
+        "pre" => {
+            let code = element.find("code");
+            let language = [Some(element), code]
+                .iter()
+                .flatten()
+                .find_map(|element| language(element))
+                .unwrap_or_default();
+            let text = element.text();
+            let text = text.strip_prefix('\n').unwrap_or(&text);
+            vec![code_block(&language, text.trim_end_matches('\n')).into()]
+        }
+        "blockquote" => {
+            let (rich_text, children) = leading_text(blocks(&element.children, style));
+            vec![CreateBlockTree {
+                block: CreateBlock::Quote {
+                    quote: text_and_children(rich_text),
+                },
+                children,
+            }]
+        }
+        "table" => vec![html_table(element, style)],
+        "img" => image(element, vec![]).into_iter().collect(),
+        "figure" => {
+            let caption = element
+                .find("figcaption")
+                .map(|caption| inline(&caption.children, style))
+                .unwrap_or_default();
+            match element.find("img").and_then(|img| image(img, caption)) {
+                Some(image) => vec![image],
+                None => blocks(&element.children, style),
+            }
+        }
+        "hr" => vec![CreateBlock::Divider {
+            divider: EmptyFields {},
+        }
+        .into()],
+        "details" => {
+            let summary = element
+                .elements()
+                .find(|child| child.name == "summary")
+                .map(|summary| inline(&summary.children, style))
+                .unwrap_or_default();
+            let content: Vec<Node> = element
+                .children
+                .iter()
+                .filter(
+                    |child| !matches!(child, Node::Element(element) if element.name == "summary"),
+                )
+                .cloned()
+                .collect();
+            vec![CreateBlockTree {
+                block: CreateBlock::Toggle {
+                    toggle: text_and_children(summary),
+                },
+                children: blocks(&content, style),
+            }]
+        }
+        "ac:structured-macro" => confluence_macro(element, style),
+        _ => blocks(&element.children, &inline_style(element, style)),
+    }
+}
+
+fn paragraph_block(rich_text: Vec<RichText>) -> CreateBlockTree {
+    CreateBlock::Paragraph {
+        paragraph: text_and_children(rich_text),
+    }
+    .into()
+}
+
+fn list_item(
+    item: &Element,
+    numbered: bool,
+    style: &Style,
+) -> CreateBlockTree {
+    let checkbox = item
+        .children
+        .iter()
+        .take_while(|child| !matches!(child, Node::Element(element) if element.is_block()))
+        .find_map(|child| match child {
+            Node::Element(element) if element.name == "input" => Some(element),
+            Node::Element(element) => element.find("input"),
+            Node::Text(_) => None,
+        })
+        .filter(|input| input.attribute("type") == Some("checkbox"));
+
+    let (rich_text, children) = leading_text(blocks(&item.children, style));
+    let block = match checkbox {
+        Some(checkbox) => CreateBlock::ToDo {
+            to_do: ToDoFields {
+                rich_text,
+                checked: checkbox.attribute("checked").is_some(),
+                children: None,
+                color: TextColor::Default,
+            },
+        },
+        None if numbered => CreateBlock::NumberedListItem {
+            numbered_list_item: text_and_children(rich_text),
+        },
+        None => CreateBlock::BulletedListItem {
+            bulleted_list_item: text_and_children(rich_text),
+        },
+    };
+    CreateBlockTree { block, children }
+}
+
+/// The language of a code element, from classes like `language-rust`,
+/// a `data-language` attribute or a Confluence `brush: java` class.
+fn language(element: &Element) -> Option<String> {
+    if let Some(language) = element.attribute("data-language") {
+        return Some(language.to_string());
+    }
+    let class = element.attribute("class")?;
+    class
+        .split(|c: char| c.is_whitespace() || c == ';')
+        .find_map(|class| {
+            class
+                .strip_prefix("language-")
+                .or_else(|| class.strip_prefix("lang-"))
+        })
+        .or_else(|| {
+            class
+                .split(';')
+                .find_map(|part| part.trim().strip_prefix("brush:"))
+                .map(str::trim)
+        })
+        .map(str::to_string)
+}
+
+fn html_table(
+    element: &Element,
+    style: &Style,
+) -> CreateBlockTree {
+    let mut rows = vec![];
+    let mut header = false;
+    collect_rows(element, &mut |row, in_head| {
+        let cells: Vec<&Element> = row
+            .elements()
+            .filter(|cell| cell.name == "td" || cell.name == "th")
+            .collect();
+        if rows.is_empty() {
+            header = in_head || (!cells.is_empty() && cells.iter().all(|cell| cell.name == "th"));
+        }
+        rows.push(
+            cells
+                .iter()
+                .map(|cell| inline(&cell.children, style))
+                .collect(),
+        );
+    });
+    table(rows, header)
+}
+
+/// Calls `f` with each row of a table and whether it is in the `<thead>`,
+/// leaving out nested tables.
+fn collect_rows<F: FnMut(&Element, bool)>(
+    element: &Element,
+    f: &mut F,
+) {
+    for child in element.elements() {
+        match child.name.as_str() {
+            "tr" => f(child, element.name == "thead"),
+            "table" => {}
+            _ => collect_rows(child, f),
+        }
+    }
+}
+
+fn image(
+    img: &Element,
+    caption: Vec<RichText>,
+) -> Option<CreateBlockTree> {
+    let src = img.attribute("src")?;
+    if !(src.starts_with("http://") || src.starts_with("https://")) {
+        return None;
+    }
+    let caption = if caption.is_empty() {
+        img.attribute("alt")
+            .filter(|alt| !alt.trim().is_empty())
+            .map(|alt| vec![RichText::text(alt.trim())])
+            .unwrap_or_default()
+    } else {
+        caption
+    };
+    Some(image_block(caption, src).into())
+}
+
+/// Code macros and info, note, tip and warning panels of Confluence storage format.
+fn confluence_macro(
+    element: &Element,
+    style: &Style,
+) -> Vec<CreateBlockTree> {
+    let parameter = |name: &str| {
+        element
+            .elements()
+            .find(|child| child.name == "ac:parameter" && child.attribute("ac:name") == Some(name))
+            .map(Element::text)
+    };
+    let body = element
+        .elements()
+        .find(|child| child.name == "ac:rich-text-body" || child.name == "ac:plain-text-body");
+
+    let icon = match element.attribute("ac:name") {
+        Some("code") | Some("noformat") => {
+            let language = parameter("language").unwrap_or_default();
+            let code = body.map(Element::text).unwrap_or_default();
+            return vec![code_block(&language, code.trim_matches('\n')).into()];
+        }
+        Some("info") => "ℹ️",
+        Some("note") => "📝",
+        Some("tip") => "💡",
+        Some("warning") => "⚠️",
+        _ => return body.map_or_else(Vec::new, |body| blocks(&body.children, style)),
+    };
+
+    let content = body.map_or_else(Vec::new, |body| blocks(&body.children, style));
+    let (rich_text, children) = leading_text(content);
+    vec![CreateBlockTree {
+        block: CreateBlock::Callout {
+            callout: Callout {
+                rich_text,
+                icon: FileOrEmojiObject::Emoji {
+                    emoji: icon.parse().unwrap(),
+                },
+                color: TextColor::Default,
+            },
+        },
+        children,
+    }]
+}
+
+/// The style of the content of an inline element.
+fn inline_style(
+    element: &Element,
+    style: &Style,
+) -> Style {
+    let mut style = style.clone();
+    match element.name.as_str() {
+        "strong" | "b" => style.bold = true,
+        "em" | "i" | "cite" => style.italic = true,
+        "s" | "strike" | "del" => style.strikethrough = true,
+        "u" | "ins" => style.underline = true,
+        "code" | "kbd" | "samp" | "tt" => style.code = true,
+        "a" => {
+            if let Some(href) = element
+                .attribute("href")
+                .filter(|href| href.starts_with("http://") || href.starts_with("https://"))
+            {
+                style.link = Some(href.to_string());
+            }
+        }
+        _ => {}
+    }
+    style
+}
+
+fn inline(
+    nodes: &[Node],
+    style: &Style,
+) -> Vec<RichText> {
+    let mut text = Inline::default();
+    for node in nodes {
+        text.node(node, style);
+    }
+    text.finish()
+}
+
+/// Inline content, with whitespace collapsed like a browser would.
+#[derive(Default)]
+struct Inline {
+    spans: Vec<(Style, String)>,
+}
+
+impl Inline {
+    fn node(
+        &mut self,
+        node: &Node,
+        style: &Style,
+    ) {
+        match node {
+            Node::Text(text) => self.push(style, text),
+            Node::Element(element) if element.name == "br" => self.push_raw(style, "\n"),
+            Node::Element(element) if element.name == "input" => {}
+            Node::Element(element) => {
+                let style = inline_style(element, style);
+                for child in &element.children {
+                    self.node(child, &style);
+                }
+            }
+        }
+    }
+
+    fn at_space(&self) -> bool {
+        self.spans
+            .last()
+            .is_none_or(|(_, text)| text.ends_with([' ', '\n']))
+    }
+
+    fn push(
+        &mut self,
+        style: &Style,
+        text: &str,
+    ) {
+        let mut collapsed = String::with_capacity(text.len());
+        let mut at_space = self.at_space();
+        for c in text.chars() {
+            if c.is_ascii_whitespace() {
+                if !at_space {
+                    collapsed.push(' ');
+                }
+                at_space = true;
+            } else {
+                collapsed.push(c);
+                at_space = false;
+            }
+        }
+        self.push_raw(style, &collapsed);
+    }
+
+    fn push_raw(
+        &mut self,
+        style: &Style,
+        text: &str,
+    ) {
+        if text.is_empty() {
+            return;
+        }
+        match self.spans.last_mut() {
+            Some((last, content)) if last == style => content.push_str(text),
+            _ => self.spans.push((style.clone(), text.to_string())),
+        }
+    }
+
+    fn finish(mut self) -> Vec<RichText> {
+        while let Some((_, text)) = self.spans.last_mut() {
+            let trimmed = text.trim_end_matches([' ', '\n']).len();
+            text.truncate(trimmed);
+            if !text.is_empty() {
+                break;
+            }
+            self.spans.pop();
+        }
+
+        self.spans
+            .iter()
+            .flat_map(|(style, text)| chunks(text).map(move |chunk| style.rich_text(chunk)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+    use crate::models::block::builder::{bullet, code, h1, p, todo};
+    use crate::models::block::{CreateBlock, CreateBlockTree, FileOrEmojiObject};
+    use crate::models::text::{self, RichText};
+
+    #[test]
+    fn parse_html() {
+        let html = r#"<!DOCTYPE html>
+<html><head><title>Ignored</title><style>p { color: red }</style></head><body>
+<h1>Release &amp; notes</h1>
+<p>Some <strong>bold</strong> and <a href="https://example.com">a   link</a>.<br>
+   Next line
+<ul><li>one<ul><li>nested</li></ul><li><input type="checkbox" checked> done</ul>
+<pre><code class="language-rust">fn main() {}
+</code></pre>
+<table><thead><tr><th>a<th>b</thead><tbody><tr><td>1<td>2</tbody></table>
+<figure><img src="https://example.com/d.png"><figcaption>Diagram</figcaption></figure>
+<img src="relative.png">
+<ac:structured-macro ac:name="info"><ac:rich-text-body><p>Heads up</p></ac:rich-text-body></ac:structured-macro>
+</body></html>"#;
+        let trees = parse(html);
+
+        assert_eq!(trees[0], h1("Release & notes").into());
+        let paragraph = match &trees[1].block {
+            CreateBlock::Paragraph { paragraph } => &paragraph.rich_text,
+            other => panic!("expected a paragraph, got {:?}", other),
+        };
+        assert_eq!(
+            text::plain_text(paragraph),
+            "Some bold and a link.\nNext line"
+        );
+        let link = serde_json::to_value(&paragraph[3]).unwrap();
+        assert_eq!(link["href"], "https://example.com");
+        assert_eq!(
+            trees[2],
+            CreateBlockTree {
+                block: bullet("one"),
+                children: vec![bullet("nested").into()],
+            }
+        );
+        assert_eq!(trees[3], todo("done", true).into());
+        assert_eq!(trees[4], code("rust", "fn main() {}").into());
+        assert!(matches!(
+            &trees[5],
+            CreateBlockTree { block: CreateBlock::Table { table }, children }
+                if table.has_column_header && table.table_width == 2 && children.len() == 2
+        ));
+        assert!(matches!(
+            &trees[6].block,
+            CreateBlock::Image { image } if image.caption == vec![RichText::text("Diagram")]
+        ));
+        assert!(matches!(
+            &trees[7].block,
+            CreateBlock::Callout { callout }
+                if text::plain_text(&callout.rich_text) == "Heads up"
+                    && matches!(&callout.icon, FileOrEmojiObject::Emoji { .. })
+        ));
+        assert_eq!(trees.len(), 8);
+    }
+
+    #[test]
+    fn parse_non_ascii_text() {
+        assert_eq!(parse("<p>é</p>"), vec![p("é").into()]);
+        assert_eq!(parse("<h1>日本</h1>"), vec![h1("日本").into()]);
+        let trees = parse("a<b>é");
+        assert_eq!(trees.len(), 1);
+        assert!(matches!(
+            &trees[0].block,
+            CreateBlock::Paragraph { paragraph } if text::plain_text(&paragraph.rich_text) == "aé"
+        ));
+        assert_eq!(parse("<é"), vec![p("<é").into()]);
+    }
+}
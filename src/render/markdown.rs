@@ -0,0 +1,582 @@
+//! Markdown rendering of block trees, such as the ones returned by
+//! [get_block_tree()](crate::NotionApi::get_block_tree()).
+//!
+//! The output is CommonMark with the GitHub extensions for tables, task lists
+//! and strikethrough. Blocks without a Markdown equivalent are approximated:
+//! callouts become quotes, toggles `<details>` elements, and columns and synced
+//! blocks are flattened into their contents. Tables of contents, breadcrumbs and
+//! unsupported blocks are left out.
+
+use std::collections::HashMap;
+
+mod parse;
+pub use parse::parse;
+
+use crate::ids::BlockId;
+use crate::models::block::lists::{group_lists, Grouped, List};
+use crate::models::block::{
+    Block, BlockTree, CustomEmoji, FileFields, FileObject, FileOrEmojiObject,
+};
+use crate::models::text::{self, Annotations, RichText};
+use crate::models::Page;
+
+/// Renders `blocks` with the default [MarkdownRenderer].
+pub fn blocks_to_markdown(blocks: &[BlockTree]) -> String {
+    MarkdownRenderer::new().render(blocks)
+}
+
+/// `page` as a Markdown document, its title as a heading followed by `blocks`,
+/// rendered with the default [MarkdownRenderer].
+///
+/// [NotionApi::export_markdown()](crate::NotionApi::export_markdown()) fetches both.
+pub fn page_to_markdown(
+    page: &Page,
+    blocks: &[BlockTree],
+) -> String {
+    MarkdownRenderer::new().render_page(page, blocks)
+}
+
+/// Renders rich text as inline Markdown, keeping bold, italic,
+/// strikethrough and code annotations, links and inline equations.
+pub fn rich_text_to_markdown(rich_text: &[RichText]) -> String {
+    rich_text.iter().map(span).collect()
+}
+
+/// Renders block trees to Markdown.
+///
+/// Images, files and child pages link to their Notion URLs unless
+/// [link()](Self::link()) points them somewhere else, such as a downloaded copy.
+#[derive(Debug, Default, Clone)]
+pub struct MarkdownRenderer {
+    links: HashMap<BlockId, String>,
+}
+
+impl MarkdownRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Links the image, file, child page or child database block `block_id` to `target`.
+    pub fn link<S: Into<String>>(
+        mut self,
+        block_id: BlockId,
+        target: S,
+    ) -> Self {
+        self.links.insert(block_id, target.into());
+        self
+    }
+
+    pub fn render(
+        &self,
+        blocks: &[BlockTree],
+    ) -> String {
+        let mut markdown = self.chunks(&group_lists(blocks)).join("\n\n");
+        if !markdown.is_empty() {
+            markdown.push('\n');
+        }
+        markdown
+    }
+
+    /// Renders `blocks` below a heading with the title of `page`.
+    pub fn render_page(
+        &self,
+        page: &Page,
+        blocks: &[BlockTree],
+    ) -> String {
+        let title = page.title().unwrap_or_default();
+        let body = self.render(blocks);
+        if body.is_empty() {
+            format!("# {}\n", title)
+        } else {
+            format!("# {}\n\n{}", title, body)
+        }
+    }
+
+    /// The rendered blocks, to be separated by blank lines.
+    fn chunks(
+        &self,
+        grouped: &[Grouped<'_>],
+    ) -> Vec<String> {
+        let mut chunks: Vec<String> = vec![];
+        let mut after_to_do = false;
+
+        for group in grouped {
+            let rendered = match group {
+                Grouped::Block(tree) => self.block(tree),
+                Grouped::List(list) => vec![self.list(list)],
+            };
+            let is_to_do =
+                matches!(group, Grouped::Block(tree) if matches!(tree.block, Block::ToDo { .. }));
+
+            for chunk in rendered.into_iter().filter(|chunk| !chunk.is_empty()) {
+                // Consecutive to-dos form one task list.
+                match chunks.last_mut() {
+                    Some(last) if is_to_do && after_to_do => {
+                        last.push('\n');
+                        last.push_str(&chunk);
+                    }
+                    _ => chunks.push(chunk),
+                }
+            }
+            after_to_do = is_to_do;
+        }
+
+        chunks
+    }
+
+    fn children(
+        &self,
+        tree: &BlockTree,
+    ) -> Vec<String> {
+        self.chunks(&tree.grouped_children())
+    }
+
+    fn block(
+        &self,
+        tree: &BlockTree,
+    ) -> Vec<String> {
+        use Block::*;
+        let mut chunks = match &tree.block {
+            Paragraph { paragraph, .. } => vec![rich_text_to_markdown(&paragraph.rich_text)],
+            Heading1 { heading_1, .. } => vec![heading(1, &heading_1.rich_text)],
+            Heading2 { heading_2, .. } => vec![heading(2, &heading_2.rich_text)],
+            Heading3 { heading_3, .. } => vec![heading(3, &heading_3.rich_text)],
+            Quote { quote, .. } => {
+                let mut content = vec![rich_text_to_markdown(&quote.rich_text)];
+                content.extend(self.children(tree));
+                return vec![quoted(&content.join("\n\n"))];
+            }
+            Callout { callout, .. } => {
+                let text = rich_text_to_markdown(&callout.rich_text);
+                let mut content = vec![match &callout.icon {
+                    FileOrEmojiObject::Emoji { emoji } => format!("{} {}", emoji, text),
+                    FileOrEmojiObject::CustomEmoji {
+                        custom_emoji:
+                            CustomEmoji {
+                                name: Some(name), ..
+                            },
+                    } => format!(":{}: {}", name, text),
+                    _ => text,
+                }];
+                content.extend(self.children(tree));
+                return vec![quoted(&content.join("\n\n"))];
+            }
+            ToDo { to_do, .. } => {
+                let marker = if to_do.checked { "- [x]" } else { "- [ ]" };
+                let text = rich_text_to_markdown(&to_do.rich_text);
+                return vec![item(marker, &text, &self.children(tree))];
+            }
+            Toggle { toggle, .. } => {
+                let mut content = vec![format!(
+                    "<details>\n<summary>{}</summary>",
+                    rich_text_to_markdown(&toggle.rich_text)
+                )];
+                content.extend(self.children(tree));
+                content.push("</details>".to_string());
+                return vec![content.join("\n\n")];
+            }
+            Code { code, .. } => {
+                let language = serde_json::to_value(&code.language)
+                    .ok()
+                    .and_then(|language| language.as_str().map(str::to_string))
+                    .filter(|language| language != "plain text")
+                    .unwrap_or_default();
+                vec![fenced(&language, &text::plain_text(&code.rich_text))]
+            }
+            Image { image, .. } => vec![format!(
+                "![{}]({})",
+                escape(&text::plain_text(&image.caption)),
+                self.target(tree, file_url(&image.file))
+            )],
+            Video { video: file, .. } | File { file, .. } | Pdf { pdf: file, .. } => {
+                vec![format!(
+                    "[{}]({})",
+                    file_label(file),
+                    self.target(tree, file_url(&file.file))
+                )]
+            }
+            Bookmark { bookmark, .. } => {
+                let caption = rich_text_to_markdown(&bookmark.caption);
+                if caption.is_empty() {
+                    vec![format!("<{}>", bookmark.url)]
+                } else {
+                    vec![format!("[{}]({})", caption, bookmark.url)]
+                }
+            }
+            Embed { embed, .. } => vec![format!("<{}>", embed.url)],
+            LinkPreview { link_preview, .. } => vec![format!("<{}>", link_preview.url)],
+            Equation { equation, .. } => vec![format!("$$\n{}\n$$", equation.expression)],
+            Divider { .. } => vec!["---".to_string()],
+            ChildPage { child_page, .. } => vec![format!(
+                "[{}]({})",
+                escape(&child_page.title),
+                self.target(tree, &notion_url(tree))
+            )],
+            ChildDatabase { child_database, .. } => vec![format!(
+                "[{}]({})",
+                escape(&child_database.title),
+                self.target(tree, &notion_url(tree))
+            )],
+            Table { .. } => vec![table(tree)],
+            ColumnList { .. } | Column { .. } | SyncedBlock { .. } | Template { .. } => vec![],
+            LinkToPage { .. }
+            | TableRow { .. }
+            | TableOfContents { .. }
+            | Breadcrumb { .. }
+            | BulletedListItem { .. }
+            | NumberedListItem { .. }
+            | Unsupported { .. }
+            | Unknown => vec![],
+        };
+
+        if !matches!(tree.block, Block::Table { .. }) {
+            chunks.extend(self.children(tree));
+        }
+        chunks
+    }
+
+    fn list(
+        &self,
+        list: &List<'_>,
+    ) -> String {
+        list.items
+            .iter()
+            .map(|list_item| {
+                let marker = match list_item.number {
+                    Some(number) => format!("{}.", number),
+                    None => "-".to_string(),
+                };
+                let text = match &list_item.item.block {
+                    Block::BulletedListItem {
+                        bulleted_list_item: fields,
+                        ..
+                    }
+                    | Block::NumberedListItem {
+                        numbered_list_item: fields,
+                        ..
+                    } => rich_text_to_markdown(&fields.rich_text),
+                    _ => String::new(),
+                };
+                item(&marker, &text, &self.chunks(&list_item.children))
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn target<'a>(
+        &'a self,
+        tree: &BlockTree,
+        default: &'a str,
+    ) -> &'a str {
+        tree.block
+            .id()
+            .and_then(|id| self.links.get(id))
+            .map_or(default, String::as_str)
+    }
+}
+
+fn heading(
+    level: usize,
+    rich_text: &[RichText],
+) -> String {
+    format!("{} {}", "#".repeat(level), rich_text_to_markdown(rich_text))
+}
+
+/// A list item with its nested blocks indented below it.
+fn item(
+    marker: &str,
+    text: &str,
+    children: &[String],
+) -> String {
+    let width = marker.len() + 1;
+    let mut item = indent_continuation(&format!("{} {}", marker, text), width);
+    if !children.is_empty() {
+        item.push('\n');
+        item.push_str(&indent(&children.join("\n\n"), width));
+    }
+    item
+}
+
+/// Indents every line but the first by `width` spaces.
+fn indent_continuation(
+    text: &str,
+    width: usize,
+) -> String {
+    let mut lines = text.lines();
+    let mut result = lines.next().unwrap_or_default().to_string();
+    for line in lines {
+        result.push('\n');
+        if !line.is_empty() {
+            result.push_str(&" ".repeat(width));
+            result.push_str(line);
+        }
+    }
+    result
+}
+
+fn indent(
+    text: &str,
+    width: usize,
+) -> String {
+    text.lines()
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("{}{}", " ".repeat(width), line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn quoted(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            if line.is_empty() {
+                ">".to_string()
+            } else {
+                format!("> {}", line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A fenced code block, with a fence longer than any backtick run in `code`.
+fn fenced(
+    language: &str,
+    code: &str,
+) -> String {
+    let longest_run = code
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or_default();
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    format!("{fence}{}\n{}\n{fence}", language, code, fence = fence)
+}
+
+fn table(tree: &BlockTree) -> String {
+    let rows: Vec<Vec<String>> = tree
+        .children
+        .iter()
+        .filter_map(|row| match &row.block {
+            Block::TableRow { table_row, .. } => Some(
+                table_row
+                    .cells
+                    .iter()
+                    .map(|cell| {
+                        rich_text_to_markdown(cell)
+                            .replace('|', "\\|")
+                            .replace('\n', "<br>")
+                    })
+                    .collect(),
+            ),
+            _ => None,
+        })
+        .collect();
+    let width = rows.iter().map(Vec::len).max().unwrap_or_default();
+    if width == 0 {
+        return String::new();
+    }
+
+    let line = |cells: &[String]| {
+        let mut cells = cells.to_vec();
+        cells.resize(width, String::new());
+        format!("| {} |", cells.join(" | "))
+    };
+    // Markdown tables always have a header, so the first row is used as one.
+    let mut lines = vec![line(&rows[0]), line(&vec!["---".to_string(); width])];
+    lines.extend(rows[1..].iter().map(|row| line(row)));
+    lines.join("\n")
+}
+
+fn file_url(file: &FileObject) -> &str {
+    match file {
+        FileObject::File { file } => &file.url,
+        FileObject::External { external } => &external.url,
+    }
+}
+
+/// The caption of a file block, or the last segment of its URL.
+fn file_label(file: &FileFields) -> String {
+    let caption = rich_text_to_markdown(&file.caption);
+    if !caption.is_empty() {
+        return caption;
+    }
+    let url = file_url(&file.file);
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    escape(
+        path.rsplit('/')
+            .next()
+            .filter(|name| !name.is_empty())
+            .unwrap_or(url),
+    )
+}
+
+fn notion_url(tree: &BlockTree) -> String {
+    let id = tree.block.id().map(ToString::to_string).unwrap_or_default();
+    format!("{}/{}", crate::NOTION_APP_URL, id.replace('-', ""))
+}
+
+fn span(rich_text: &RichText) -> String {
+    match rich_text {
+        RichText::Text { rich_text, text } => decorate(
+            &text.content,
+            rich_text.annotations.as_ref(),
+            text.link
+                .as_ref()
+                .map(|link| link.url.as_str())
+                .or(rich_text.href.as_deref()),
+        ),
+        RichText::Mention { rich_text, .. } => decorate(
+            &rich_text.plain_text,
+            rich_text.annotations.as_ref(),
+            rich_text.href.as_deref(),
+        ),
+        RichText::Equation { equation, .. } => format!("${}$", equation.expression),
+    }
+}
+
+/// Applies annotations and a link to `content`, keeping surrounding
+/// whitespace outside of the emphasis markers.
+fn decorate(
+    content: &str,
+    annotations: Option<&Annotations>,
+    link: Option<&str>,
+) -> String {
+    let trimmed = content.trim();
+    if trimmed.is_empty() {
+        return content.to_string();
+    }
+    let start = content.len() - content.trim_start().len();
+    let (leading, rest) = content.split_at(start);
+    let trailing = &rest[trimmed.len()..];
+
+    let enabled =
+        |flag: fn(&Annotations) -> Option<bool>| annotations.and_then(flag).unwrap_or(false);
+
+    let mut markdown = if enabled(|annotations| annotations.code) {
+        let ticks = if trimmed.contains('`') { "``" } else { "`" };
+        let padding = if trimmed.starts_with('`') || trimmed.ends_with('`') {
+            " "
+        } else {
+            ""
+        };
+        format!("{0}{1}{2}{1}{0}", ticks, padding, trimmed)
+    } else {
+        escape(trimmed)
+    };
+    if enabled(|annotations| annotations.italic) {
+        markdown = format!("*{}*", markdown);
+    }
+    if enabled(|annotations| annotations.bold) {
+        markdown = format!("**{}**", markdown);
+    }
+    if enabled(|annotations| annotations.strikethrough) {
+        markdown = format!("~~{}~~", markdown);
+    }
+    if let Some(link) = link {
+        markdown = format!("[{}]({})", markdown, link);
+    }
+
+    format!("{}{}{}", escape(leading), markdown, escape(trailing))
+}
+
+/// Escapes characters that would otherwise start Markdown syntax,
+/// and turns line breaks into hard breaks.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '~' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\\n"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{blocks_to_markdown, page_to_markdown, rich_text_to_markdown};
+    use crate::models::block::{Block, BlockTree};
+    use crate::models::text::{Annotations, RichText, RichTextCommon, Text};
+    use crate::test_util::{BlockFixture, PageFixture};
+
+    fn leaf(block: Block) -> BlockTree {
+        BlockTree {
+            block,
+            children: vec![],
+        }
+    }
+
+    #[test]
+    fn render_blocks() {
+        let blocks = vec![
+            leaf(BlockFixture::new().heading_1("Plan")),
+            leaf(BlockFixture::new().paragraph("Use *stars*")),
+            BlockTree {
+                block: BlockFixture::new()
+                    .has_children(true)
+                    .numbered_list_item("one"),
+                children: vec![leaf(BlockFixture::new().bulleted_list_item("nested"))],
+            },
+            leaf(BlockFixture::new().numbered_list_item("two")),
+            leaf(BlockFixture::new().to_do("done", true)),
+            leaf(BlockFixture::new().to_do("open", false)),
+        ];
+
+        assert_eq!(
+            blocks_to_markdown(&blocks),
+            "# Plan\n\nUse \\*stars\\*\n\n1. one\n   - nested\n2. two\n\n- [x] done\n- [ ] open\n"
+        );
+    }
+
+    #[test]
+    fn render_page() {
+        let page = PageFixture::new().title("Release notes").build();
+        let blocks = vec![leaf(BlockFixture::new().paragraph("Shipped"))];
+
+        assert_eq!(
+            page_to_markdown(&page, &blocks),
+            "# Release notes\n\nShipped\n"
+        );
+        assert_eq!(page_to_markdown(&page, &[]), "# Release notes\n");
+    }
+
+    #[test]
+    fn render_annotations() {
+        let span = |content: &str, bold, code| RichText::Text {
+            rich_text: RichTextCommon {
+                plain_text: content.to_string(),
+                href: None,
+                annotations: Some(Annotations {
+                    bold: Some(bold),
+                    code: Some(code),
+                    color: None,
+                    italic: None,
+                    strikethrough: None,
+                    underline: None,
+                }),
+            },
+            text: Text {
+                content: content.to_string(),
+                link: None,
+            },
+        };
+
+        assert_eq!(
+            rich_text_to_markdown(&[
+                span("Run ", false, false),
+                span("a_b ", true, true),
+                span("now", false, false)
+            ]),
+            "Run **`a_b`** now"
+        );
+    }
+}
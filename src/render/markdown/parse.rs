@@ -0,0 +1,695 @@
+//! Parsing Markdown into blocks to create.
+//!
+//! This is the inverse of the renderer: headings, paragraphs, quotes, fenced
+//! code, bulleted, numbered and task lists with nesting, dividers, images on
+//! their own line and tables become their blocks, and bold, italic,
+//! strikethrough, inline code, links and `$inline$` equations become annotated
+//! rich text. Other syntax, such as HTML or reference links, is kept as text.
+
+use crate::models::block::{CreateBlock, CreateBlockTree, EmptyFields, ToDoFields};
+use crate::models::text::{self, RichText, RichTextCommon, TextColor};
+use crate::render::build::{
+    self, chunks, code_block, heading_block, image_block, leading_text, text_and_children, Style,
+};
+
+/// Parses a Markdown document into block trees, keeping nested list items
+/// and quote contents as children.
+pub fn parse(markdown: &str) -> Vec<CreateBlockTree> {
+    let lines: Vec<String> = markdown
+        .lines()
+        .map(|line| line.replace('\t', "    "))
+        .collect();
+    blocks(&lines)
+}
+
+fn blocks(lines: &[String]) -> Vec<CreateBlockTree> {
+    let mut trees = vec![];
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = lines[i].trim_start();
+        let indent = lines[i].len() - line.len();
+        if line.is_empty() {
+            i += 1;
+            continue;
+        }
+
+        if let Some((fence, info)) = fence_open(line) {
+            let mut code = vec![];
+            i += 1;
+            while i < lines.len() && !is_fence_close(lines[i].trim_start(), &fence) {
+                code.push(strip_indent(&lines[i], indent));
+                i += 1;
+            }
+            i += 1;
+            trees.push(code_block(info, &code.join("\n")).into());
+        } else if let Some((level, text)) = heading(line) {
+            trees.push(heading_block(level, inline(text)).into());
+            i += 1;
+        } else if is_divider(line) {
+            trees.push(
+                CreateBlock::Divider {
+                    divider: EmptyFields {},
+                }
+                .into(),
+            );
+            i += 1;
+        } else if line.starts_with('>') {
+            let mut quoted = vec![];
+            while i < lines.len() && lines[i].trim_start().starts_with('>') {
+                let content = &lines[i].trim_start()[1..];
+                quoted.push(content.strip_prefix(' ').unwrap_or(content).to_string());
+                i += 1;
+            }
+            let (rich_text, children) = leading_text(blocks(&quoted));
+            trees.push(CreateBlockTree {
+                block: CreateBlock::Quote {
+                    quote: text_and_children(rich_text),
+                },
+                children,
+            });
+        } else if let Some(marker) = list_marker(line) {
+            let content_indent = indent + marker.width;
+            let mut item = vec![line[marker.width..].to_string()];
+            i += 1;
+            while i < lines.len() {
+                let next = lines[i].trim_start();
+                let next_indent = lines[i].len() - next.len();
+                if next.is_empty() {
+                    let continues = lines[i..]
+                        .iter()
+                        .find(|line| !line.trim().is_empty())
+                        .is_some_and(|line| line.len() - line.trim_start().len() >= content_indent);
+                    if !continues {
+                        break;
+                    }
+                    item.push(String::new());
+                } else if next_indent >= content_indent {
+                    item.push(strip_indent(&lines[i], content_indent));
+                } else if starts_block(next)
+                    || list_marker(next).is_some()
+                    || item.last().is_some_and(String::is_empty)
+                {
+                    break;
+                } else {
+                    // A lazy continuation of the item's paragraph.
+                    item.push(next.to_string());
+                }
+                i += 1;
+            }
+
+            let (rich_text, children) = leading_text(blocks(&item));
+            trees.push(CreateBlockTree {
+                block: marker.kind.block(rich_text),
+                children,
+            });
+        } else if i + 1 < lines.len() && line.contains('|') && is_table_separator(&lines[i + 1]) {
+            let mut rows = vec![table_cells(line)];
+            i += 2;
+            while i < lines.len() && lines[i].contains('|') && !lines[i].trim().is_empty() {
+                rows.push(table_cells(lines[i].trim()));
+                i += 1;
+            }
+            trees.push(table(rows));
+        } else if let Some((alt, url)) = image(line.trim_end()) {
+            trees.push(image_block(inline(alt), url).into());
+            i += 1;
+        } else {
+            let mut paragraph = String::new();
+            while i < lines.len() {
+                let line = lines[i].trim();
+                if line.is_empty() || (!paragraph.is_empty() && starts_block(line)) {
+                    break;
+                }
+                if !paragraph.is_empty() {
+                    paragraph.push(' ');
+                }
+                paragraph.push_str(line);
+                // Hard line breaks, ending in a backslash or two spaces.
+                if line.ends_with('\\') && !line.ends_with("\\\\") {
+                    paragraph.pop();
+                    paragraph.push('\n');
+                } else if lines[i].ends_with("  ") {
+                    paragraph.push('\n');
+                }
+                i += 1;
+            }
+            let paragraph = paragraph.replace("\n ", "\n");
+            trees.push(
+                CreateBlock::Paragraph {
+                    paragraph: text_and_children(inline(paragraph.trim_end())),
+                }
+                .into(),
+            );
+        }
+    }
+
+    trees
+}
+
+/// Whether `line` starts a block that interrupts a paragraph.
+fn starts_block(line: &str) -> bool {
+    fence_open(line).is_some()
+        || heading(line).is_some()
+        || is_divider(line)
+        || line.starts_with('>')
+        || list_marker(line).is_some_and(|marker| match marker.kind {
+            ItemKind::Numbered(number) => number == 1,
+            _ => true,
+        })
+}
+
+fn strip_indent(
+    line: &str,
+    width: usize,
+) -> String {
+    let spaces = line.len() - line.trim_start_matches(' ').len();
+    line[spaces.min(width)..].to_string()
+}
+
+fn fence_open(line: &str) -> Option<(String, &str)> {
+    let fence_char = line.chars().next().filter(|&c| c == '`' || c == '~')?;
+    let length = line.len() - line.trim_start_matches(fence_char).len();
+    if length < 3 {
+        return None;
+    }
+    let info = line[length..].trim();
+    if fence_char == '`' && info.contains('`') {
+        return None;
+    }
+    Some((line[..length].to_string(), info))
+}
+
+fn is_fence_close(
+    line: &str,
+    fence: &str,
+) -> bool {
+    let fence_char = fence.chars().next().unwrap_or('`');
+    let rest = line.trim_start_matches(fence_char);
+    line.len() - rest.len() >= fence.len() && rest.trim().is_empty()
+}
+
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.len() - line.trim_start_matches('#').len();
+    let rest = &line[level..];
+    if !(1..=6).contains(&level) || !(rest.is_empty() || rest.starts_with(' ')) {
+        return None;
+    }
+    // Closing sequences like `## Title ##` aren't part of the title.
+    let text = rest.trim();
+    let text = match text.trim_end_matches('#') {
+        stripped if stripped.is_empty() || stripped.ends_with(' ') => stripped.trim_end(),
+        _ => text,
+    };
+    Some((level, text))
+}
+
+fn is_divider(line: &str) -> bool {
+    let marks: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+    marks.len() >= 3
+        && ['-', '*', '_']
+            .iter()
+            .any(|&mark| marks.chars().all(|c| c == mark))
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ItemKind {
+    Bulleted,
+    Numbered(u64),
+    ToDo(bool),
+}
+
+impl ItemKind {
+    fn block(
+        self,
+        rich_text: Vec<RichText>,
+    ) -> CreateBlock {
+        match self {
+            ItemKind::Bulleted => CreateBlock::BulletedListItem {
+                bulleted_list_item: text_and_children(rich_text),
+            },
+            ItemKind::Numbered(_) => CreateBlock::NumberedListItem {
+                numbered_list_item: text_and_children(rich_text),
+            },
+            ItemKind::ToDo(checked) => CreateBlock::ToDo {
+                to_do: ToDoFields {
+                    rich_text,
+                    checked,
+                    children: None,
+                    color: TextColor::Default,
+                },
+            },
+        }
+    }
+}
+
+struct ListMarker {
+    kind: ItemKind,
+    /// The width of the marker and the following space, which
+    /// is missing when the marker ends the line.
+    width: usize,
+}
+
+fn list_marker(line: &str) -> Option<ListMarker> {
+    let followed_by_space =
+        |width: usize| line[width..].is_empty() || line[width..].starts_with(' ');
+
+    if line.starts_with(['-', '*', '+']) && followed_by_space(1) {
+        let rest = line[1..].trim_start_matches(' ');
+        let spaces = (line.len() - 1 - rest.len()).min(4);
+        for (task, checked) in [("[ ]", false), ("[x]", true), ("[X]", true)] {
+            if let Some(after) = rest.strip_prefix(task) {
+                if after.is_empty() || after.starts_with(' ') {
+                    return Some(ListMarker {
+                        kind: ItemKind::ToDo(checked),
+                        width: line.len() - after.trim_start_matches(' ').len(),
+                    });
+                }
+            }
+        }
+        return Some(ListMarker {
+            kind: ItemKind::Bulleted,
+            width: 1 + spaces,
+        });
+    }
+
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if (1..=9).contains(&digits)
+        && line[digits..].starts_with(['.', ')'])
+        && followed_by_space(digits + 1)
+    {
+        let rest = line[digits + 1..].trim_start_matches(' ');
+        let spaces = (line.len() - digits - 1 - rest.len()).min(4);
+        return Some(ListMarker {
+            kind: ItemKind::Numbered(line[..digits].parse().unwrap_or(1)),
+            width: digits + 1 + spaces,
+        });
+    }
+
+    None
+}
+
+fn is_table_separator(line: &str) -> bool {
+    let cells = table_cells(line.trim());
+    !cells.is_empty()
+        && cells.iter().all(|cell| {
+            let cell = cell.trim_matches(':');
+            cell.len() >= 3 && cell.chars().all(|c| c == '-')
+        })
+}
+
+/// The cells of a table row, split at unescaped pipes.
+fn table_cells(line: &str) -> Vec<String> {
+    let line = line.strip_prefix('|').unwrap_or(line);
+    let mut cells = vec![];
+    let mut cell = String::new();
+    let mut escaped = false;
+    for c in line.chars() {
+        match c {
+            '|' if !escaped => cells.push(std::mem::take(&mut cell)),
+            _ => cell.push(c),
+        }
+        escaped = c == '\\' && !escaped;
+    }
+    if !cell.trim().is_empty() {
+        cells.push(cell);
+    }
+    cells
+        .into_iter()
+        .map(|cell| cell.trim().to_string())
+        .collect()
+}
+
+fn table(rows: Vec<Vec<String>>) -> CreateBlockTree {
+    let rows = rows
+        .iter()
+        .map(|row| row.iter().map(|cell| inline(cell)).collect())
+        .collect();
+    build::table(rows, true)
+}
+
+/// An image on its own line, `![alt](url)`.
+fn image(line: &str) -> Option<(&str, &str)> {
+    let (alt, rest) = line.strip_prefix("![")?.split_once("](")?;
+    let url = rest.strip_suffix(')')?;
+    let url = url.split_once(' ').map_or(url, |(url, _title)| url);
+    if url.contains(['(', ')']) || !(url.starts_with("http://") || url.starts_with("https://")) {
+        return None;
+    }
+    Some((alt, url))
+}
+
+/// Parses inline Markdown into rich text.
+fn inline(markdown: &str) -> Vec<RichText> {
+    let mut spans = vec![];
+    inline_into(markdown, &Style::default(), &mut spans);
+    spans
+}
+
+fn inline_into(
+    markdown: &str,
+    style: &Style,
+    spans: &mut Vec<RichText>,
+) {
+    let mut plain = String::new();
+    let flush = |plain: &mut String, spans: &mut Vec<RichText>| {
+        if !plain.is_empty() {
+            spans.extend(chunks(plain).map(|chunk| style.rich_text(chunk)));
+            plain.clear();
+        }
+    };
+
+    let mut i = 0;
+    while i < markdown.len() {
+        let rest = &markdown[i..];
+        let c = rest.chars().next().unwrap_or_default();
+
+        if c == '\\' {
+            match rest[1..].chars().next() {
+                Some(next) if next.is_ascii_punctuation() => {
+                    plain.push(next);
+                    i += 1 + next.len_utf8();
+                }
+                _ => {
+                    plain.push(c);
+                    i += 1;
+                }
+            }
+            continue;
+        }
+
+        if c == '`' {
+            let ticks = rest.len() - rest.trim_start_matches('`').len();
+            let fence = &rest[..ticks];
+            if let Some(end) = rest[ticks..].find(fence) {
+                let code = &rest[ticks..ticks + end];
+                let code = match code
+                    .strip_prefix(' ')
+                    .and_then(|code| code.strip_suffix(' '))
+                {
+                    Some(padded) if !padded.trim().is_empty() => padded,
+                    _ => code,
+                };
+                flush(&mut plain, spans);
+                let code_style = Style {
+                    code: true,
+                    ..style.clone()
+                };
+                spans.extend(chunks(code).map(|chunk| code_style.rich_text(chunk)));
+                i += 2 * ticks + end;
+                continue;
+            }
+        }
+
+        if let Some((delimiter, apply)) = emphasis(rest) {
+            let after = markdown[..i].chars().next_back();
+            let intraword = delimiter.starts_with('_') && after.is_some_and(char::is_alphanumeric);
+            if let Some(end) = closing(&rest[delimiter.len()..], delimiter) {
+                if !intraword {
+                    flush(&mut plain, spans);
+                    let mut inner = style.clone();
+                    apply(&mut inner);
+                    inline_into(&rest[delimiter.len()..delimiter.len() + end], &inner, spans);
+                    i += 2 * delimiter.len() + end;
+                    continue;
+                }
+            }
+        }
+
+        if c == '!' || c == '[' {
+            let start = if c == '!' { 1 } else { 0 };
+            if rest[start..].starts_with('[') {
+                if let Some((label, url, length)) = link(&rest[start..]) {
+                    flush(&mut plain, spans);
+                    let linked = Style {
+                        link: Some(url.to_string()),
+                        ..style.clone()
+                    };
+                    inline_into(label, &linked, spans);
+                    i += start + length;
+                    continue;
+                }
+            }
+        }
+
+        if c == '<' {
+            if let Some(end) = rest.find('>') {
+                let url = &rest[1..end];
+                if (url.starts_with("http://") || url.starts_with("https://"))
+                    && !url.contains(char::is_whitespace)
+                {
+                    flush(&mut plain, spans);
+                    let linked = Style {
+                        link: Some(url.to_string()),
+                        ..style.clone()
+                    };
+                    spans.push(linked.rich_text(url.to_string()));
+                    i += end + 1;
+                    continue;
+                }
+            }
+        }
+
+        if c == '$' && !style.code {
+            if let Some(expression) = equation(rest) {
+                flush(&mut plain, spans);
+                spans.push(RichText::Equation {
+                    rich_text: RichTextCommon {
+                        plain_text: expression.to_string(),
+                        href: None,
+                        annotations: style.annotations(),
+                    },
+                    equation: text::Equation {
+                        expression: expression.to_string(),
+                    },
+                });
+                i += expression.len() + 2;
+                continue;
+            }
+        }
+
+        plain.push(c);
+        i += c.len_utf8();
+    }
+
+    flush(&mut plain, spans);
+}
+
+type ApplyStyle = fn(&mut Style);
+
+/// The emphasis delimiter `rest` starts with, and the style it applies.
+fn emphasis(rest: &str) -> Option<(&'static str, ApplyStyle)> {
+    let delimiters: [(&str, ApplyStyle); 5] = [
+        ("**", |style| style.bold = true),
+        ("__", |style| style.bold = true),
+        ("~~", |style| style.strikethrough = true),
+        ("*", |style| style.italic = true),
+        ("_", |style| style.italic = true),
+    ];
+    delimiters
+        .iter()
+        .copied()
+        .find(|(delimiter, _)| rest.starts_with(delimiter))
+        .filter(|(delimiter, _)| {
+            // Opening delimiters are followed by text, not whitespace.
+            rest[delimiter.len()..]
+                .chars()
+                .next()
+                .is_some_and(|c| !c.is_whitespace())
+        })
+}
+
+/// The offset of the delimiter closing an emphasis, skipping escaped
+/// characters, code spans, and doubled delimiters when looking for single ones.
+fn closing(
+    text: &str,
+    delimiter: &str,
+) -> Option<usize> {
+    let mut i = 0;
+    while i < text.len() {
+        let rest = &text[i..];
+        let c = rest.chars().next()?;
+        if c == '\\' {
+            i += 1 + rest[1..].chars().next().map_or(0, char::len_utf8);
+            continue;
+        }
+        if c == '`' {
+            let ticks = rest.len() - rest.trim_start_matches('`').len();
+            match rest[ticks..].find(&rest[..ticks]) {
+                Some(end) => i += 2 * ticks + end,
+                None => i += ticks,
+            }
+            continue;
+        }
+        if let Some(after) = rest.strip_prefix(delimiter) {
+            let doubled = delimiter.len() == 1 && after.starts_with(delimiter);
+            let after_space = text[..i]
+                .chars()
+                .next_back()
+                .is_none_or(char::is_whitespace);
+            if doubled {
+                i += 2;
+                continue;
+            }
+            let before_word = delimiter.starts_with('_')
+                && after.chars().next().is_some_and(char::is_alphanumeric);
+            if i > 0 && !after_space && !before_word {
+                return Some(i);
+            }
+        }
+        i += c.len_utf8();
+    }
+    None
+}
+
+/// A link, `[label](url)`, with the length of its Markdown.
+fn link(text: &str) -> Option<(&str, &str, usize)> {
+    let mut depth = 0;
+    let mut label_end = None;
+    let mut escaped = false;
+    for (index, c) in text.char_indices() {
+        match c {
+            _ if escaped => {}
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    label_end = Some(index);
+                    break;
+                }
+            }
+            _ => {}
+        }
+        escaped = c == '\\' && !escaped;
+    }
+    let label_end = label_end?;
+    let destination = text[label_end + 1..].strip_prefix('(')?;
+    let end = destination.find(')')?;
+    let url = destination[..end].trim();
+    let url = url.split_once(' ').map_or(url, |(url, _title)| url);
+    let url = url
+        .strip_prefix('<')
+        .and_then(|url| url.strip_suffix('>'))
+        .unwrap_or(url);
+    if url.is_empty() {
+        return None;
+    }
+    Some((&text[1..label_end], url, label_end + 2 + end + 1))
+}
+
+/// An inline equation, `$expression$`. Dollar amounts like `$5 and $6` aren't equations.
+fn equation(text: &str) -> Option<&str> {
+    let inner = &text[1..];
+    if inner.starts_with(char::is_whitespace) || inner.starts_with('$') {
+        return None;
+    }
+    let end = inner.find('$')?;
+    let expression = &inner[..end];
+    let followed_by_digit = inner[end + 1..].starts_with(|c: char| c.is_ascii_digit());
+    if expression.is_empty() || expression.ends_with(char::is_whitespace) || followed_by_digit {
+        return None;
+    }
+    Some(expression)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+    use crate::models::block::builder::{bullet, code, h1, numbered, p, quote, todo};
+    use crate::models::block::{CreateBlock, CreateBlockTree};
+    use crate::models::text::RichText;
+
+    fn leaf(block: CreateBlock) -> CreateBlockTree {
+        block.into()
+    }
+
+    #[test]
+    fn parse_blocks() {
+        let document = "# Title\n\nFirst line\ncontinued\n\n- one\n  - nested\n- [x] done\n\n1. first\n2. second\n\n```rs\nfn main() {}\n```\n\n> quoted\n\n| a | b |\n| --- | --- |\n| 1 | 2 |\n\n---\n\n![Diagram](https://example.com/d.png)\n";
+        let trees = parse(document);
+
+        assert_eq!(
+            trees[..7],
+            [
+                leaf(h1("Title")),
+                leaf(p("First line continued")),
+                CreateBlockTree {
+                    block: bullet("one"),
+                    children: vec![leaf(bullet("nested"))],
+                },
+                leaf(todo("done", true)),
+                leaf(numbered("first")),
+                leaf(numbered("second")),
+                leaf(code("rust", "fn main() {}")),
+            ]
+        );
+        assert_eq!(trees[7], leaf(quote("quoted")));
+        assert!(
+            matches!(&trees[8], CreateBlockTree { block: CreateBlock::Table { table }, children } if table.table_width == 2 && children.len() == 2)
+        );
+        assert!(matches!(trees[9].block, CreateBlock::Divider { .. }));
+        assert!(
+            matches!(&trees[10].block, CreateBlock::Image { image } if image.caption == vec![RichText::text("Diagram")])
+        );
+        assert_eq!(trees.len(), 11);
+    }
+
+    #[test]
+    fn parse_inline_markdown() {
+        let trees = parse(
+            "Use **bold *and* italic**, `a_b`, snake_case and [docs](https://example.com) at $5.",
+        );
+        let rich_text = match &trees[0].block {
+            CreateBlock::Paragraph { paragraph } => &paragraph.rich_text,
+            other => panic!("expected a paragraph, got {:?}", other),
+        };
+        let json = serde_json::to_value(rich_text).unwrap();
+
+        let spans: Vec<(&str, &serde_json::Value)> = json
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|span| (span["plain_text"].as_str().unwrap(), &span["annotations"]))
+            .collect();
+        assert_eq!(
+            spans.iter().map(|(text, _)| *text).collect::<String>(),
+            "Use bold and italic, a_b, snake_case and docs at $5."
+        );
+        assert_eq!(spans[1], ("bold ", &serde_json::json!({"bold": true})));
+        assert_eq!(
+            spans[2],
+            ("and", &serde_json::json!({"bold": true, "italic": true}))
+        );
+        assert_eq!(spans[5], ("a_b", &serde_json::json!({"code": true})));
+        assert_eq!(json[7]["href"], "https://example.com");
+        assert_eq!(json[7]["plain_text"], "docs");
+    }
+
+    /// Whether `tree` is a list item without text.
+    fn empty_item(tree: &CreateBlockTree) -> bool {
+        let json = serde_json::to_value(&tree.block).unwrap();
+        let item = json.as_object().unwrap().values().next().unwrap();
+        item["rich_text"] == serde_json::json!([])
+    }
+
+    #[test]
+    fn parse_empty_list_items() {
+        for document in ["-", "*", "+", "- ", "-   ", "1.", "2)\n", "10. "] {
+            let trees = parse(document);
+            assert_eq!(trees.len(), 1, "{:?}", document);
+            assert!(empty_item(&trees[0]), "{:?}", document);
+        }
+
+        let trees = parse("1. item\n2.\n- one\n-\n- three");
+        assert_eq!(trees.len(), 5);
+        assert_eq!(trees[0], leaf(numbered("item")));
+        assert!(
+            matches!(trees[1].block, CreateBlock::NumberedListItem { .. }) && empty_item(&trees[1])
+        );
+        assert_eq!(trees[2], leaf(bullet("one")));
+        assert!(
+            matches!(trees[3].block, CreateBlock::BulletedListItem { .. }) && empty_item(&trees[3])
+        );
+        assert_eq!(trees[4], leaf(bullet("three")));
+    }
+}
@@ -0,0 +1,250 @@
+//! Indented outlines of block trees, for printing in CLI tools and while debugging.
+//!
+//! Each block is one line with its type and a short summary of its content,
+//! indented two spaces per level of nesting:
+//!
+//! ```text
+//! heading_1 "Plan"
+//! numbered_list_item "Write the docs and then ask someone to r…"
+//!   to_do [x] "Outline"
+//! code rust "fn main() {\n    println!(\"hello\");\n}"
+//! image https://example.com/diagram.png
+//! ```
+
+use std::fmt;
+
+use crate::models::block::{Block, BlockTree, FileFields, FileObject, LinkToPageFields};
+use crate::models::text::{self, RichText};
+
+/// Text is cut after this many characters by default.
+const DEFAULT_MAX_TEXT_LENGTH: usize = 40;
+
+/// Formats `blocks` as an outline, see the [module docs](self).
+pub fn outline(blocks: &[BlockTree]) -> String {
+    Outline::new(blocks).to_string()
+}
+
+/// Displays block trees as an outline, see the [module docs](self).
+#[derive(Debug, Clone, Copy)]
+pub struct Outline<'a> {
+    blocks: &'a [BlockTree],
+    max_text_length: usize,
+    ids: bool,
+}
+
+impl<'a> Outline<'a> {
+    pub fn new(blocks: &'a [BlockTree]) -> Self {
+        Outline {
+            blocks,
+            max_text_length: DEFAULT_MAX_TEXT_LENGTH,
+            ids: false,
+        }
+    }
+
+    /// Cuts the text of each block after `max_text_length` characters, 40 by default.
+    pub fn max_text_length(
+        mut self,
+        max_text_length: usize,
+    ) -> Self {
+        self.max_text_length = max_text_length;
+        self
+    }
+
+    /// Appends the id of each block to its line.
+    pub fn ids(
+        mut self,
+        ids: bool,
+    ) -> Self {
+        self.ids = ids;
+        self
+    }
+
+    fn write_trees(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        trees: &[BlockTree],
+        depth: usize,
+    ) -> fmt::Result {
+        for tree in trees {
+            write!(
+                f,
+                "{:indent$}{}",
+                "",
+                tree.block.type_name(),
+                indent = depth * 2
+            )?;
+            let (detail, text) = summary(&tree.block);
+            if let Some(detail) = detail {
+                write!(f, " {}", detail)?;
+            }
+            if let Some(text) = text {
+                write!(f, " {:?}", self.truncate(&text))?;
+            }
+            if let (true, Some(id)) = (self.ids, tree.block.id()) {
+                write!(f, " ({})", id)?;
+            }
+            writeln!(f)?;
+            self.write_trees(f, &tree.children, depth + 1)?;
+        }
+        Ok(())
+    }
+
+    fn truncate(
+        &self,
+        text: &str,
+    ) -> String {
+        match text.char_indices().nth(self.max_text_length) {
+            Some((end, _)) => format!("{}…", &text[..end]),
+            None => text.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Outline<'_> {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        self.write_trees(f, self.blocks, 0)
+    }
+}
+
+/// Details such as the code language or a file URL, and the text of the block.
+pub(crate) fn summary(block: &Block) -> (Option<String>, Option<String>) {
+    use Block::*;
+    let rich_text = |rich_text: &[RichText]| Some(text::plain_text(rich_text).into_owned());
+    match block {
+        Paragraph {
+            paragraph: fields, ..
+        }
+        | Quote { quote: fields, .. }
+        | BulletedListItem {
+            bulleted_list_item: fields,
+            ..
+        }
+        | NumberedListItem {
+            numbered_list_item: fields,
+            ..
+        }
+        | Toggle { toggle: fields, .. } => (None, rich_text(&fields.rich_text)),
+        Heading1 {
+            heading_1: fields, ..
+        }
+        | Heading2 {
+            heading_2: fields, ..
+        }
+        | Heading3 {
+            heading_3: fields, ..
+        } => (None, rich_text(&fields.rich_text)),
+        Callout { callout, .. } => (None, rich_text(&callout.rich_text)),
+        ToDo { to_do, .. } => (
+            Some(if to_do.checked { "[x]" } else { "[ ]" }.to_string()),
+            rich_text(&to_do.rich_text),
+        ),
+        Code { code, .. } => (
+            serde_json::to_value(&code.language)
+                .ok()
+                .and_then(|language| language.as_str().map(str::to_string)),
+            rich_text(&code.rich_text),
+        ),
+        Template { template, .. } => (None, rich_text(&template.rich_text)),
+        ChildPage { child_page, .. } => (None, Some(child_page.title.clone())),
+        ChildDatabase { child_database, .. } => (None, Some(child_database.title.clone())),
+        Image { image: file, .. }
+        | Video { video: file, .. }
+        | File { file, .. }
+        | Pdf { pdf: file, .. } => (Some(file_url(file).to_string()), None),
+        Embed { embed, .. } => (Some(embed.url.clone()), None),
+        Bookmark { bookmark, .. } => (Some(bookmark.url.clone()), None),
+        LinkPreview { link_preview, .. } => (Some(link_preview.url.clone()), None),
+        Equation { equation, .. } => (None, Some(equation.expression.clone())),
+        Table { table, .. } => (Some(format!("{} columns", table.table_width)), None),
+        TableRow { table_row, .. } => (
+            None,
+            Some(
+                table_row
+                    .cells
+                    .iter()
+                    .map(|cell| text::plain_text(cell))
+                    .collect::<Vec<_>>()
+                    .join(" | "),
+            ),
+        ),
+        LinkToPage { link_to_page, .. } => (
+            Some(match link_to_page {
+                LinkToPageFields::PageId { page_id } => page_id.to_string(),
+                LinkToPageFields::DatabaseId { database_id } => database_id.to_string(),
+            }),
+            None,
+        ),
+        SyncedBlock { synced_block, .. } => (
+            synced_block
+                .synced_from
+                .as_ref()
+                .map(|original| format!("from {}", original.block_id)),
+            None,
+        ),
+        Divider { .. }
+        | TableOfContents { .. }
+        | Breadcrumb { .. }
+        | ColumnList { .. }
+        | Column { .. }
+        | Unsupported { .. }
+        | Unknown => (None, None),
+    }
+}
+
+fn file_url(file: &FileFields) -> &str {
+    match &file.file {
+        FileObject::File { file } => &file.url,
+        FileObject::External { external } => &external.url,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Outline;
+    use crate::models::block::BlockTree;
+    use crate::test_util::BlockFixture;
+
+    #[test]
+    fn outline_blocks() {
+        let item = BlockFixture::new()
+            .id("9f6a1c2e-4b5d-4e8f-a1b2-c3d4e5f60718")
+            .has_children(true)
+            .numbered_list_item("Write the docs\nthen ask someone to review them");
+        let blocks = vec![
+            BlockTree {
+                block: BlockFixture::new().heading_1("Plan"),
+                children: vec![],
+            },
+            BlockTree {
+                block: item,
+                children: vec![BlockTree {
+                    block: BlockFixture::new().to_do("Outline", true),
+                    children: vec![],
+                }],
+            },
+            BlockTree {
+                block: BlockFixture::new().image("https://example.com/diagram.png"),
+                children: vec![],
+            },
+        ];
+
+        assert_eq!(
+            Outline::new(&blocks).max_text_length(19).to_string(),
+            "heading_1 \"Plan\"\n\
+             numbered_list_item \"Write the docs\\nthen…\"\n\
+             \x20 to_do [x] \"Outline\"\n\
+             image https://example.com/diagram.png\n"
+        );
+        let line = Outline::new(&blocks[1..2]).ids(true).to_string();
+        assert_eq!(
+            line.lines().next(),
+            Some(
+                "numbered_list_item \"Write the docs\\nthen ask someone to revie…\" \
+                 (9f6a1c2e-4b5d-4e8f-a1b2-c3d4e5f60718)"
+            )
+        );
+    }
+}
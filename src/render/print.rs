@@ -0,0 +1,422 @@
+//! Standalone, print-ready HTML documents of pages, for archiving and for
+//! converting to PDF, see [NotionApi::export_print_html()](crate::NotionApi::export_print_html()).
+//!
+//! The document has the page title, a table of the page's properties and the
+//! blocks, with a stylesheet laid out for A4 or Letter paper. Toggles are
+//! expanded, so their content is printed, and images link to their URLs,
+//! which for files uploaded to Notion expire after an hour.
+
+use crate::models::block::lists::{group_lists, Grouped, List, ListKind};
+use crate::models::block::{Block, BlockTree, FileFields, FileObject, FileOrEmojiObject};
+use crate::models::columns::Cell;
+use crate::models::text::{self, Annotations, RichText};
+use crate::models::Page;
+
+const STYLESHEET: &str = r#"
+@page { margin: 2cm; }
+body { font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; font-size: 11pt; line-height: 1.5; color: #37352f; max-width: 45em; margin: 0 auto; }
+h1, h2, h3, h4 { line-height: 1.25; page-break-after: avoid; }
+pre, blockquote, aside, figure, table, img { page-break-inside: avoid; }
+pre { background: #f7f6f3; padding: 0.75em; white-space: pre-wrap; font-size: 9.5pt; }
+blockquote { border-left: 3px solid #37352f; margin-left: 0; padding-left: 1em; }
+aside.callout { background: #f1f1ef; padding: 0.75em 1em; border-radius: 4px; }
+figure { margin: 1em 0; }
+img { max-width: 100%; }
+figcaption { color: #787774; font-size: 9.5pt; }
+table { border-collapse: collapse; width: 100%; }
+th, td { border: 1px solid #e9e9e7; padding: 0.3em 0.5em; text-align: left; vertical-align: top; }
+table.properties th { width: 30%; color: #787774; font-weight: normal; }
+ul.to-do { list-style: none; padding-left: 0.5em; }
+.columns { display: flex; gap: 1.5em; }
+.columns > div { flex: 1; }
+.equation { font-family: serif; font-style: italic; }
+"#;
+
+/// A complete HTML document of `page` and its `blocks`, see the [module docs](self).
+pub fn page_to_html(
+    page: &Page,
+    blocks: &[BlockTree],
+) -> String {
+    let title = escape(&page.to_string());
+    let mut properties: Vec<(&String, String)> = page
+        .properties
+        .properties
+        .iter()
+        .filter(|(name, _)| Some(name.as_str()) != page.title_property())
+        .map(|(name, value)| (name, cell_text(Cell::from(value))))
+        .filter(|(_, value)| !value.is_empty())
+        .collect();
+    properties.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>{}</style>\n</head>\n<body>\n<h1>{title}</h1>\n",
+        STYLESHEET,
+        title = title
+    );
+    if !properties.is_empty() {
+        html.push_str("<table class=\"properties\">\n");
+        for (name, value) in properties {
+            html.push_str(&format!(
+                "<tr><th>{}</th><td>{}</td></tr>\n",
+                escape(name),
+                escape(&value)
+            ));
+        }
+        html.push_str("</table>\n");
+    }
+    html.push_str(&blocks_to_html(blocks));
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// The HTML of `blocks`, without a document around it.
+pub fn blocks_to_html(blocks: &[BlockTree]) -> String {
+    grouped(&group_lists(blocks))
+}
+
+/// Renders rich text as inline HTML, keeping annotations, links and inline equations.
+pub fn rich_text_to_html(rich_text: &[RichText]) -> String {
+    rich_text.iter().map(span).collect()
+}
+
+fn grouped(grouped: &[Grouped<'_>]) -> String {
+    grouped
+        .iter()
+        .map(|group| match group {
+            Grouped::Block(tree) => block(tree),
+            Grouped::List(list) => self::list(list),
+        })
+        .collect()
+}
+
+fn children(tree: &BlockTree) -> String {
+    grouped(&tree.grouped_children())
+}
+
+fn block(tree: &BlockTree) -> String {
+    use Block::*;
+    match &tree.block {
+        Paragraph { paragraph, .. } => format!(
+            "<p>{}</p>\n{}",
+            rich_text_to_html(&paragraph.rich_text),
+            children(tree)
+        ),
+        Heading1 { heading_1, .. } => heading(2, &heading_1.rich_text, tree),
+        Heading2 { heading_2, .. } => heading(3, &heading_2.rich_text, tree),
+        Heading3 { heading_3, .. } => heading(4, &heading_3.rich_text, tree),
+        Quote { quote, .. } => format!(
+            "<blockquote>\n<p>{}</p>\n{}</blockquote>\n",
+            rich_text_to_html(&quote.rich_text),
+            children(tree)
+        ),
+        Callout { callout, .. } => {
+            let icon = match &callout.icon {
+                FileOrEmojiObject::Emoji { emoji } => format!("{} ", escape(emoji.as_ref())),
+                _ => String::new(),
+            };
+            format!(
+                "<aside class=\"callout\">\n<p>{}{}</p>\n{}</aside>\n",
+                icon,
+                rich_text_to_html(&callout.rich_text),
+                children(tree)
+            )
+        }
+        ToDo { to_do, .. } => format!(
+            "<ul class=\"to-do\"><li><input type=\"checkbox\" disabled{}> {}\n{}</li></ul>\n",
+            if to_do.checked { " checked" } else { "" },
+            rich_text_to_html(&to_do.rich_text),
+            children(tree)
+        ),
+        Toggle { toggle, .. } => format!(
+            "<details open>\n<summary>{}</summary>\n{}</details>\n",
+            rich_text_to_html(&toggle.rich_text),
+            children(tree)
+        ),
+        Code { code, .. } => format!(
+            "<pre><code>{}</code></pre>\n",
+            escape(&text::plain_text(&code.rich_text))
+        ),
+        Image { image, .. } => {
+            let caption = rich_text_to_html(&image.caption);
+            format!(
+                "<figure><img src=\"{}\" alt=\"{}\">{}</figure>\n",
+                escape(file_url(&image.file)),
+                escape(&text::plain_text(&image.caption)),
+                if caption.is_empty() {
+                    String::new()
+                } else {
+                    format!("<figcaption>{}</figcaption>", caption)
+                }
+            )
+        }
+        Video { video: file, .. } | File { file, .. } | Pdf { pdf: file, .. } => {
+            link(file_url(&file.file), &file_label(file))
+        }
+        Bookmark { bookmark, .. } => {
+            let caption = rich_text_to_html(&bookmark.caption);
+            let label = if caption.is_empty() {
+                escape(&bookmark.url)
+            } else {
+                caption
+            };
+            format!(
+                "<p><a href=\"{}\">{}</a></p>\n",
+                escape(&bookmark.url),
+                label
+            )
+        }
+        Embed { embed, .. } => link(&embed.url, &escape(&embed.url)),
+        LinkPreview { link_preview, .. } => link(&link_preview.url, &escape(&link_preview.url)),
+        Equation { equation, .. } => format!(
+            "<p class=\"equation\">{}</p>\n",
+            escape(&equation.expression)
+        ),
+        Divider { .. } => "<hr>\n".to_string(),
+        ChildPage { child_page, .. } => link(&notion_url(tree), &escape(&child_page.title)),
+        ChildDatabase { child_database, .. } => {
+            link(&notion_url(tree), &escape(&child_database.title))
+        }
+        Table { table, .. } => self::table(tree, table.has_column_header, table.has_row_header),
+        ColumnList { .. } => format!("<div class=\"columns\">\n{}</div>\n", children(tree)),
+        Column { .. } => format!("<div>\n{}</div>\n", children(tree)),
+        SyncedBlock { .. } | Template { .. } => children(tree),
+        LinkToPage { .. }
+        | TableRow { .. }
+        | TableOfContents { .. }
+        | Breadcrumb { .. }
+        | BulletedListItem { .. }
+        | NumberedListItem { .. }
+        | Unsupported { .. }
+        | Unknown => String::new(),
+    }
+}
+
+fn heading(
+    level: usize,
+    rich_text: &[RichText],
+    tree: &BlockTree,
+) -> String {
+    format!(
+        "<h{level}>{}</h{level}>\n{}",
+        rich_text_to_html(rich_text),
+        children(tree),
+        level = level
+    )
+}
+
+fn list(list: &List<'_>) -> String {
+    let tag = match list.kind {
+        ListKind::Bulleted => "ul",
+        ListKind::Numbered => "ol",
+    };
+    let mut html = format!("<{}>\n", tag);
+    for list_item in &list.items {
+        let text = match &list_item.item.block {
+            Block::BulletedListItem {
+                bulleted_list_item: fields,
+                ..
+            }
+            | Block::NumberedListItem {
+                numbered_list_item: fields,
+                ..
+            } => rich_text_to_html(&fields.rich_text),
+            _ => String::new(),
+        };
+        html.push_str(&format!(
+            "<li>{}\n{}</li>\n",
+            text,
+            grouped(&list_item.children)
+        ));
+    }
+    html.push_str(&format!("</{}>\n", tag));
+    html
+}
+
+fn table(
+    tree: &BlockTree,
+    has_column_header: bool,
+    has_row_header: bool,
+) -> String {
+    let mut html = "<table>\n".to_string();
+    let rows = tree.children.iter().filter_map(|row| match &row.block {
+        Block::TableRow { table_row, .. } => Some(&table_row.cells),
+        _ => None,
+    });
+    for (row, cells) in rows.enumerate() {
+        html.push_str("<tr>");
+        for (column, cell) in cells.iter().enumerate() {
+            let tag = if (row == 0 && has_column_header) || (column == 0 && has_row_header) {
+                "th"
+            } else {
+                "td"
+            };
+            html.push_str(&format!(
+                "<{tag}>{}</{tag}>",
+                rich_text_to_html(cell),
+                tag = tag
+            ));
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</table>\n");
+    html
+}
+
+fn link(
+    url: &str,
+    label: &str,
+) -> String {
+    format!("<p><a href=\"{}\">{}</a></p>\n", escape(url), label)
+}
+
+fn span(rich_text: &RichText) -> String {
+    match rich_text {
+        RichText::Text { rich_text, text } => decorate(
+            &text.content,
+            rich_text.annotations.as_ref(),
+            text.link
+                .as_ref()
+                .map(|link| link.url.as_str())
+                .or(rich_text.href.as_deref()),
+        ),
+        RichText::Mention { rich_text, .. } => decorate(
+            &rich_text.plain_text,
+            rich_text.annotations.as_ref(),
+            rich_text.href.as_deref(),
+        ),
+        RichText::Equation { equation, .. } => format!(
+            "<span class=\"equation\">{}</span>",
+            escape(&equation.expression)
+        ),
+    }
+}
+
+fn decorate(
+    content: &str,
+    annotations: Option<&Annotations>,
+    link: Option<&str>,
+) -> String {
+    let enabled =
+        |flag: fn(&Annotations) -> Option<bool>| annotations.and_then(flag).unwrap_or(false);
+
+    let mut html = escape(content).replace('\n', "<br>");
+    let tags = [
+        (enabled(|annotations| annotations.code), "code"),
+        (enabled(|annotations| annotations.italic), "em"),
+        (enabled(|annotations| annotations.bold), "strong"),
+        (enabled(|annotations| annotations.strikethrough), "s"),
+        (enabled(|annotations| annotations.underline), "u"),
+    ];
+    for (_, tag) in tags.iter().filter(|(enabled, _)| *enabled) {
+        html = format!("<{tag}>{}</{tag}>", html, tag = tag);
+    }
+    if let Some(link) = link {
+        html = format!("<a href=\"{}\">{}</a>", escape(link), html);
+    }
+    html
+}
+
+fn cell_text(cell: Cell) -> String {
+    match cell {
+        Cell::Null => String::new(),
+        Cell::Number(number) => number.to_string(),
+        Cell::Boolean(true) => "Yes".to_string(),
+        Cell::Boolean(false) => "No".to_string(),
+        Cell::Date(date) => date.to_string(),
+        Cell::Text(text) => text,
+        Cell::List(values) => values.join(", "),
+    }
+}
+
+fn file_url(file: &FileObject) -> &str {
+    match file {
+        FileObject::File { file } => &file.url,
+        FileObject::External { external } => &external.url,
+    }
+}
+
+/// The caption of a file block, or the last segment of its URL.
+fn file_label(file: &FileFields) -> String {
+    let caption = rich_text_to_html(&file.caption);
+    if !caption.is_empty() {
+        return caption;
+    }
+    let url = file_url(&file.file);
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    escape(
+        path.rsplit('/')
+            .next()
+            .filter(|name| !name.is_empty())
+            .unwrap_or(url),
+    )
+}
+
+fn notion_url(tree: &BlockTree) -> String {
+    let id = tree.block.id().map(ToString::to_string).unwrap_or_default();
+    format!("{}/{}", crate::NOTION_APP_URL, id.replace('-', ""))
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{blocks_to_html, page_to_html};
+    use crate::models::block::BlockTree;
+    use crate::test_util::{BlockFixture, PageFixture};
+
+    fn leaf(block: crate::models::block::Block) -> BlockTree {
+        BlockTree {
+            block,
+            children: vec![],
+        }
+    }
+
+    #[test]
+    fn renders_blocks_as_html() {
+        let blocks = vec![
+            leaf(BlockFixture::new().heading_1("Terms & conditions")),
+            leaf(BlockFixture::new().bulleted_list_item("First")),
+            leaf(BlockFixture::new().bulleted_list_item("Second")),
+            leaf(BlockFixture::new().to_do("Signed", true)),
+            leaf(BlockFixture::new().image("https://example.com/a.png")),
+        ];
+        assert_eq!(
+            blocks_to_html(&blocks),
+            "<h2>Terms &amp; conditions</h2>\n\
+             <ul>\n<li>First\n</li>\n<li>Second\n</li>\n</ul>\n\
+             <ul class=\"to-do\"><li><input type=\"checkbox\" disabled checked> Signed\n</li></ul>\n\
+             <figure><img src=\"https://example.com/a.png\" alt=\"\"></figure>\n"
+        );
+    }
+
+    #[test]
+    fn documents_have_title_and_properties() {
+        let page = PageFixture::new()
+            .title("Contract <draft>")
+            .select("Status", "Signed")
+            .checkbox("Archived", false)
+            .build();
+        let html = page_to_html(&page, &[leaf(BlockFixture::new().paragraph("Body"))]);
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>Contract &lt;draft&gt;</title>"));
+        assert!(html.contains("<h1>Contract &lt;draft&gt;</h1>"));
+        assert!(html.contains(
+            "<tr><th>Archived</th><td>No</td></tr>\n<tr><th>Status</th><td>Signed</td></tr>"
+        ));
+        assert!(html.contains("<p>Body</p>"));
+        assert!(html.ends_with("</body>\n</html>\n"));
+    }
+}
@@ -0,0 +1,107 @@
+//! Word counts and reading times of block trees, for stamping onto pages as properties.
+//!
+//! ```
+//! use notion_wasi::render::reading::{estimated_reading_time, word_count};
+//! # let blocks: Vec<notion_wasi::models::block::BlockTree> = vec![];
+//!
+//! println!(
+//!     "{} words, {} min read",
+//!     word_count(&blocks),
+//!     estimated_reading_time(&blocks).as_secs() / 60
+//! );
+//! ```
+//!
+//! Words are the text of the blocks separated by whitespace, which undercounts
+//! scripts written without spaces. The titles of child pages and databases
+//! are not part of the page and are left out, like urls of files and embeds.
+
+use std::time::Duration;
+
+use crate::models::block::{Block, BlockTree};
+use crate::render::outline::summary;
+
+/// The reading speed [estimated_reading_time()] assumes.
+pub const WORDS_PER_MINUTE: u32 = 200;
+
+/// The number of words in `blocks` and all of their fetched children.
+pub fn word_count(blocks: &[BlockTree]) -> usize {
+    blocks
+        .iter()
+        .flat_map(BlockTree::blocks)
+        .map(block_words)
+        .sum()
+}
+
+/// How long reading `blocks` takes at [WORDS_PER_MINUTE], rounded up to whole seconds.
+pub fn estimated_reading_time(blocks: &[BlockTree]) -> Duration {
+    reading_time(blocks, WORDS_PER_MINUTE)
+}
+
+/// How long reading `blocks` takes at `words_per_minute`, rounded up to whole seconds.
+pub fn reading_time(
+    blocks: &[BlockTree],
+    words_per_minute: u32,
+) -> Duration {
+    let words = word_count(blocks) as u64;
+    let words_per_minute = u64::from(words_per_minute.max(1));
+    Duration::from_secs((words * 60).div_ceil(words_per_minute))
+}
+
+fn block_words(block: &Block) -> usize {
+    match block {
+        Block::ChildPage { .. } | Block::ChildDatabase { .. } => 0,
+        block => summary(block)
+            .1
+            .map_or(0, |text| text.split_whitespace().count()),
+    }
+}
+
+impl BlockTree {
+    /// See [word_count()].
+    pub fn word_count(&self) -> usize {
+        word_count(std::slice::from_ref(self))
+    }
+
+    /// See [estimated_reading_time()].
+    pub fn estimated_reading_time(&self) -> Duration {
+        estimated_reading_time(std::slice::from_ref(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{estimated_reading_time, reading_time, word_count};
+    use crate::models::block::BlockTree;
+    use crate::test_util::BlockFixture;
+
+    #[test]
+    fn counts_words_of_nested_blocks() {
+        let blocks = vec![
+            BlockTree {
+                block: BlockFixture::new().heading_1("Release  plan"),
+                children: vec![],
+            },
+            BlockTree {
+                block: BlockFixture::new()
+                    .has_children(true)
+                    .paragraph("Ship it\non Friday"),
+                children: vec![BlockTree {
+                    block: BlockFixture::new().to_do("Write notes", false),
+                    children: vec![],
+                }],
+            },
+            BlockTree {
+                block: BlockFixture::new().image("https://example.com/diagram.png"),
+                children: vec![],
+            },
+        ];
+
+        assert_eq!(word_count(&blocks), 8);
+        assert_eq!(blocks[1].word_count(), 6);
+        assert_eq!(estimated_reading_time(&blocks), Duration::from_secs(3));
+        assert_eq!(reading_time(&blocks, 8), Duration::from_secs(60));
+        assert_eq!(estimated_reading_time(&[]), Duration::ZERO);
+    }
+}
@@ -0,0 +1,268 @@
+//! A local full-text index over fetched pages, for searching backups and
+//! exports offline.
+//!
+//! Notion's search endpoint only matches page titles. A [SearchIndex] also
+//! matches the text of properties and blocks, every word of the query has to
+//! appear in a page, and the last word also matches as a prefix, so results
+//! can be shown while the query is typed.
+//!
+//! ```
+//! use notion_wasi::search_index::SearchIndex;
+//! # let page: notion_wasi::models::Page = serde_json::from_str(include_str!("models/tests/page.json")).unwrap();
+//!
+//! let mut index = SearchIndex::new();
+//! index.add_page(&page, &[]);
+//! for hit in index.search("stuff") {
+//!     println!("{} {}", hit.title, hit.page_id);
+//! }
+//! ```
+//!
+//! Words are split at anything that isn't alphanumeric and compared in lowercase.
+//! The index is a plain in-memory structure that can be serialized, there is no
+//! stemming or typo tolerance.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::Bound;
+
+use serde::{Deserialize, Serialize};
+
+use crate::ids::PageId;
+use crate::models::block::BlockTree;
+use crate::models::columns::Cell;
+use crate::models::Page;
+use crate::render::outline::summary;
+
+/// Words of the title count this many times.
+const TITLE_WEIGHT: u32 = 3;
+
+/// A word that only starts with the last query word scores this much of a full match.
+const PREFIX_WEIGHT: f64 = 0.5;
+
+/// A page found by [SearchIndex::search()].
+#[derive(Debug, PartialEq, Clone)]
+pub struct SearchHit {
+    pub page_id: PageId,
+    pub title: String,
+    /// Higher for pages using the words of the query more often,
+    /// in the title, or rarer words of the query.
+    pub score: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+struct Document {
+    title: String,
+    /// How often each word appears, with title words counted [TITLE_WEIGHT] times.
+    words: HashMap<String, u32>,
+}
+
+/// An inverted index of pages, see the [module docs](self).
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Default)]
+pub struct SearchIndex {
+    documents: HashMap<PageId, Document>,
+    /// The pages each word appears in.
+    postings: BTreeMap<String, HashSet<PageId>>,
+}
+
+impl SearchIndex {
+    pub fn new() -> Self {
+        SearchIndex::default()
+    }
+
+    /// The number of indexed pages.
+    pub fn len(&self) -> usize {
+        self.documents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.documents.is_empty()
+    }
+
+    /// Indexes the title and properties of `page` and the text of `blocks`,
+    /// replacing what was indexed for the page before.
+    pub fn add_page(
+        &mut self,
+        page: &Page,
+        blocks: &[BlockTree],
+    ) {
+        self.remove_page(&page.id);
+
+        let title = page.title().unwrap_or_default();
+        let mut words: HashMap<String, u32> = HashMap::new();
+        for word in tokenize(&title) {
+            *words.entry(word).or_default() += TITLE_WEIGHT;
+        }
+        let title_property = page.title_property();
+        for (name, value) in &page.properties.properties {
+            if Some(name.as_str()) == title_property {
+                continue;
+            }
+            let texts = match Cell::from(value) {
+                Cell::Text(text) => vec![text],
+                Cell::List(texts) => texts,
+                _ => continue,
+            };
+            for word in texts.iter().flat_map(|text| tokenize(text)) {
+                *words.entry(word).or_default() += 1;
+            }
+        }
+        for block in blocks.iter().flat_map(BlockTree::blocks) {
+            if let (_, Some(text)) = summary(block) {
+                for word in tokenize(&text) {
+                    *words.entry(word).or_default() += 1;
+                }
+            }
+        }
+
+        for word in words.keys() {
+            self.postings
+                .entry(word.clone())
+                .or_default()
+                .insert(page.id.clone());
+        }
+        self.documents
+            .insert(page.id.clone(), Document { title, words });
+    }
+
+    /// Removes a page from the index.
+    pub fn remove_page(
+        &mut self,
+        page_id: &PageId,
+    ) {
+        let document = match self.documents.remove(page_id) {
+            Some(document) => document,
+            None => return,
+        };
+        for word in document.words.keys() {
+            if let Some(pages) = self.postings.get_mut(word) {
+                pages.remove(page_id);
+                if pages.is_empty() {
+                    self.postings.remove(word);
+                }
+            }
+        }
+    }
+
+    /// The pages containing every word of `query`, the best match first.
+    pub fn search(
+        &self,
+        query: &str,
+    ) -> Vec<SearchHit> {
+        let terms = tokenize(query);
+        let last = match terms.len().checked_sub(1) {
+            Some(last) => last,
+            None => return vec![],
+        };
+
+        let mut scores: Option<HashMap<&PageId, f64>> = None;
+        for (position, term) in terms.iter().enumerate() {
+            let mut matches: Vec<(&String, f64)> = vec![];
+            if self.postings.contains_key(term) {
+                matches.push((term, 1.0));
+            }
+            if position == last {
+                matches.extend(
+                    self.postings
+                        .range::<String, _>((Bound::Excluded(term), Bound::Unbounded))
+                        .take_while(|(word, _)| word.starts_with(term.as_str()))
+                        .map(|(word, _)| (word, PREFIX_WEIGHT)),
+                );
+            }
+
+            let mut term_scores: HashMap<&PageId, f64> = HashMap::new();
+            for (word, weight) in matches {
+                let pages = &self.postings[word];
+                let idf = (1.0 + self.documents.len() as f64 / pages.len() as f64).ln();
+                for page_id in pages {
+                    let count = self.documents[page_id].words[word];
+                    *term_scores.entry(page_id).or_default() += weight * idf * f64::from(count);
+                }
+            }
+
+            scores = Some(match scores {
+                None => term_scores,
+                Some(scores) => scores
+                    .into_iter()
+                    .filter_map(|(page_id, score)| {
+                        term_scores
+                            .get(page_id)
+                            .map(|term_score| (page_id, score + term_score))
+                    })
+                    .collect(),
+            });
+        }
+
+        let mut hits: Vec<SearchHit> = scores
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(page_id, score)| SearchHit {
+                page_id: page_id.clone(),
+                title: self.documents[page_id].title.clone(),
+                score,
+            })
+            .collect();
+        hits.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.title.cmp(&b.title))
+        });
+        hits
+    }
+}
+
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SearchIndex;
+    use crate::models::block::BlockTree;
+    use crate::test_util::{BlockFixture, PageFixture};
+
+    fn titles(
+        index: &SearchIndex,
+        query: &str,
+    ) -> Vec<String> {
+        index
+            .search(query)
+            .into_iter()
+            .map(|hit| hit.title)
+            .collect()
+    }
+
+    #[test]
+    fn searches_titles_properties_and_blocks() {
+        let deploy = PageFixture::new()
+            .title("Deploy checklist")
+            .multi_select("Tags", &["Ops"])
+            .build();
+        let notes = PageFixture::new().title("Meeting notes").build();
+        let blocks = vec![BlockTree {
+            block: BlockFixture::new().paragraph("We talked about the deploy window"),
+            children: vec![],
+        }];
+
+        let mut index = SearchIndex::new();
+        index.add_page(&deploy, &[]);
+        index.add_page(&notes, &blocks);
+
+        assert_eq!(
+            titles(&index, "deploy"),
+            vec!["Deploy checklist", "Meeting notes"]
+        );
+        assert_eq!(titles(&index, "DEPLOY window"), vec!["Meeting notes"]);
+        assert_eq!(titles(&index, "ops"), vec!["Deploy checklist"]);
+        assert_eq!(titles(&index, "check"), vec!["Deploy checklist"]);
+        assert!(titles(&index, "check ops").is_empty());
+        assert!(index.search("").is_empty());
+
+        index.add_page(&notes, &[]);
+        assert_eq!(titles(&index, "window"), Vec::<String>::new());
+        index.remove_page(&deploy.id);
+        assert_eq!(index.len(), 1);
+        assert!(titles(&index, "deploy").is_empty());
+    }
+}
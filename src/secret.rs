@@ -0,0 +1,67 @@
+//! Integration tokens, kept out of logs.
+
+use std::fmt;
+
+use serde::Deserialize;
+
+/// A token that is never printed.
+///
+/// `Debug` shows `[REDACTED]` instead of the value and there is no `Display`,
+/// so a token can't end up in logs, traces or error messages by accident.
+/// [expose_secret()](Self::expose_secret()) returns the value for the request headers.
+/// Tokens in responses, such as OAuth access tokens, deserialize from strings.
+///
+/// ```
+/// use notion_wasi::SecretString;
+///
+/// let token = SecretString::from("secret_abc");
+/// assert_eq!(format!("{:?}", token), "SecretString(\"[REDACTED]\")");
+/// assert_eq!(token.expose_secret(), "secret_abc");
+/// ```
+#[derive(Deserialize, Eq, PartialEq, Clone)]
+#[serde(transparent)]
+pub struct SecretString(String);
+
+impl SecretString {
+    pub fn new<S: Into<String>>(secret: S) -> Self {
+        SecretString(secret.into())
+    }
+
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(secret: String) -> Self {
+        SecretString(secret)
+    }
+}
+
+impl From<&str> for SecretString {
+    fn from(secret: &str) -> Self {
+        SecretString(secret.to_string())
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        f.debug_tuple("SecretString").field(&"[REDACTED]").finish()
+    }
+}
+
+#[cfg(all(test, feature = "client"))]
+mod tests {
+    use crate::NotionApi;
+
+    #[test]
+    fn debug_output_hides_token() {
+        let api = NotionApi::new("secret_abc").unwrap();
+        let debug = format!("{:?} {:#?}", api, api);
+        assert!(debug.contains("NotionApi"));
+        assert!(!debug.contains("secret_abc"));
+    }
+}
@@ -0,0 +1,653 @@
+//! Builders for realistic model values in tests.
+//!
+//! Enabled with the `test-util` feature.
+//!
+//! ```
+//! use notion_wasi::test_util::PageFixture;
+//!
+//! let page = PageFixture::new()
+//!     .title("Write docs")
+//!     .select("Status", "Done")
+//!     .build();
+//! assert_eq!(page.title(), Some("Write docs".to_string()));
+//! ```
+
+#[cfg(feature = "databases")]
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::ids::{DatabaseId, PageId, PropertyId, UserId};
+use crate::models::properties::{Color, PropertyValue, RelationValue, SelectedValue};
+use crate::models::text::RichText;
+use crate::models::users::{Person, User, UserCommon, UserReference};
+use crate::models::{DateTime, ListResponse, Number, Page, Parent, Properties, Utc};
+#[cfg(feature = "blocks")]
+use crate::{
+    ids::BlockId,
+    models::block::{
+        Block, BlockCommon, ChildPageFields, FileFields, FileObject, InternalFileObject,
+        Text as TextFields, TextAndChildren, ToDoFields,
+    },
+    models::text::TextColor,
+};
+#[cfg(feature = "databases")]
+use crate::{
+    models::properties::{PropertyConfiguration, Select, SelectOption, SelectOptionId},
+    models::Database,
+};
+
+#[cfg(feature = "client")]
+mod mock_client;
+#[cfg(all(
+    any(test, feature = "mock-server"),
+    feature = "client",
+    not(target_arch = "wasm32")
+))]
+mod mock_server;
+#[cfg(feature = "client")]
+pub use mock_client::{MockClient, MockRequest};
+#[cfg(all(
+    any(test, feature = "mock-server"),
+    feature = "client",
+    not(target_arch = "wasm32")
+))]
+pub use mock_server::{MockNotion, MOCK_API_TOKEN};
+
+/// Canned API payloads, as returned by Notion.
+pub mod json {
+    pub const PAGE: &str = include_str!("models/tests/page.json");
+    pub const QUERY_RESULT: &str = include_str!("models/tests/query_result.json");
+    pub const SEARCH_RESULTS: &str = include_str!("models/tests/search_results.json");
+    pub const ERROR: &str = include_str!("models/tests/error.json");
+    #[cfg(feature = "blocks")]
+    pub const HEADING_1: &str = include_str!("models/block/tests/heading_1.json");
+    #[cfg(feature = "blocks")]
+    pub const CALLOUT: &str = include_str!("models/block/tests/callout.json");
+}
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// A unique, UUID shaped id.
+fn next_id() -> String {
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    format!("00000000-0000-4000-8000-{:012x}", id)
+}
+
+fn timestamp() -> DateTime<Utc> {
+    DateTime::from_str("2022-01-01T00:00:00.000Z").unwrap()
+}
+
+fn property_id(name: &str) -> PropertyId {
+    PropertyId::from_str(name).unwrap()
+}
+
+fn user() -> UserCommon {
+    UserCommon {
+        id: UserId::from_str("00000000-0000-4000-8000-000000000000").unwrap(),
+        name: None,
+        avatar_url: None,
+    }
+}
+
+/// A plain, unannotated text element.
+pub fn rich_text(content: &str) -> RichText {
+    RichText::text(content)
+}
+
+/// A person with a fresh id.
+pub fn person(
+    name: &str,
+    email: &str,
+) -> User {
+    User::Person {
+        common: UserCommon {
+            id: UserId::from_str(&next_id()).unwrap(),
+            name: Some(name.to_string()),
+            avatar_url: None,
+        },
+        person: Person {
+            email: Some(email.to_string()),
+        },
+    }
+}
+
+/// Wrap `results` in a single, final page of results.
+pub fn list_response<T>(results: Vec<T>) -> ListResponse<T> {
+    ListResponse {
+        results,
+        next_cursor: None,
+        has_more: false,
+    }
+}
+
+/// Builds a [Page]. See the [module docs](self).
+#[derive(Debug, Clone)]
+pub struct PageFixture {
+    page: Page,
+}
+
+impl Default for PageFixture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PageFixture {
+    /// A page in the workspace with a fresh id and no properties.
+    pub fn new() -> Self {
+        PageFixture {
+            page: Page {
+                id: PageId::from_str(&next_id()).unwrap(),
+                created_time: timestamp(),
+                last_edited_time: timestamp(),
+                created_by: user(),
+                last_edited_by: user(),
+                archived: false,
+                icon: None,
+                cover: None,
+                in_trash: false,
+                properties: Properties::default(),
+                parent: Parent::Workspace,
+                url: String::new(),
+                public_url: None,
+            },
+        }
+        .url()
+    }
+
+    pub fn id(
+        mut self,
+        id: &str,
+    ) -> Self {
+        self.page.id = PageId::from_str(id).unwrap();
+        self.url()
+    }
+
+    /// Points the page url at the current id.
+    fn url(mut self) -> Self {
+        self.page.url = format!(
+            "{}/{}",
+            crate::NOTION_APP_URL,
+            self.page.id.to_string().replace('-', "")
+        );
+        self
+    }
+
+    pub fn database(
+        mut self,
+        database_id: &str,
+    ) -> Self {
+        self.page.parent = Parent::Database {
+            database_id: DatabaseId::from_str(database_id).unwrap(),
+        };
+        self
+    }
+
+    pub fn archived(
+        mut self,
+        archived: bool,
+    ) -> Self {
+        self.page.archived = archived;
+        self
+    }
+
+    pub fn last_edited_time(
+        mut self,
+        last_edited_time: DateTime<Utc>,
+    ) -> Self {
+        self.page.last_edited_time = last_edited_time;
+        self
+    }
+
+    /// Set the title, stored under the `Name` property.
+    pub fn title(
+        self,
+        title: &str,
+    ) -> Self {
+        self.property(
+            "Name",
+            PropertyValue::Title {
+                id: property_id("title"),
+                title: vec![rich_text(title)],
+            },
+        )
+    }
+
+    pub fn text(
+        self,
+        name: &str,
+        text: &str,
+    ) -> Self {
+        self.property(
+            name,
+            PropertyValue::Text {
+                id: property_id(name),
+                rich_text: vec![rich_text(text)],
+            },
+        )
+    }
+
+    pub fn number<N: Into<Number>>(
+        self,
+        name: &str,
+        number: N,
+    ) -> Self {
+        self.property(
+            name,
+            PropertyValue::Number {
+                id: property_id(name),
+                number: Some(number.into()),
+            },
+        )
+    }
+
+    pub fn select(
+        self,
+        name: &str,
+        option: &str,
+    ) -> Self {
+        self.property(
+            name,
+            PropertyValue::Select {
+                id: property_id(name),
+                select: Some(selected(option)),
+            },
+        )
+    }
+
+    pub fn multi_select(
+        self,
+        name: &str,
+        options: &[&str],
+    ) -> Self {
+        self.property(
+            name,
+            PropertyValue::MultiSelect {
+                id: property_id(name),
+                multi_select: Some(options.iter().map(|option| selected(option)).collect()),
+            },
+        )
+    }
+
+    pub fn checkbox(
+        self,
+        name: &str,
+        checked: bool,
+    ) -> Self {
+        self.property(
+            name,
+            PropertyValue::Checkbox {
+                id: property_id(name),
+                checkbox: checked,
+            },
+        )
+    }
+
+    pub fn people(
+        self,
+        name: &str,
+        people: Vec<UserReference>,
+    ) -> Self {
+        self.property(
+            name,
+            PropertyValue::People {
+                id: property_id(name),
+                people,
+            },
+        )
+    }
+
+    pub fn relation(
+        self,
+        name: &str,
+        related: &[&Page],
+    ) -> Self {
+        self.property(
+            name,
+            PropertyValue::Relation {
+                id: property_id(name),
+                relation: Some(
+                    related
+                        .iter()
+                        .map(|page| RelationValue {
+                            id: page.id.clone(),
+                        })
+                        .collect(),
+                ),
+                has_more: false,
+            },
+        )
+    }
+
+    /// Set any property value.
+    pub fn property(
+        mut self,
+        name: &str,
+        value: PropertyValue,
+    ) -> Self {
+        self.page
+            .properties
+            .properties
+            .insert(name.to_string(), value);
+        self
+    }
+
+    pub fn build(self) -> Page {
+        self.page
+    }
+}
+
+fn selected(name: &str) -> SelectedValue {
+    SelectedValue {
+        id: None,
+        name: Some(name.to_string()),
+        color: Color::Default,
+    }
+}
+
+/// Builds a [Database] with a `Name` title property.
+#[cfg(feature = "databases")]
+#[derive(Debug, Clone)]
+pub struct DatabaseFixture {
+    database: Database,
+}
+
+#[cfg(feature = "databases")]
+impl Default for DatabaseFixture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "databases")]
+impl DatabaseFixture {
+    pub fn new() -> Self {
+        let mut properties = HashMap::new();
+        properties.insert(
+            "Name".to_string(),
+            PropertyConfiguration::Title {
+                id: property_id("title"),
+            },
+        );
+
+        let id = DatabaseId::from_str(&next_id()).unwrap();
+        DatabaseFixture {
+            database: Database {
+                url: id.view_url(None),
+                id,
+                created_time: timestamp(),
+                last_edited_time: timestamp(),
+                title: vec![],
+                description: vec![],
+                parent: None,
+                is_inline: false,
+                properties,
+                data_sources: vec![],
+                archived: false,
+                in_trash: false,
+            },
+        }
+    }
+
+    pub fn id(
+        mut self,
+        id: &str,
+    ) -> Self {
+        self.database.id = DatabaseId::from_str(id).unwrap();
+        self.database.url = self.database.id.view_url(None);
+        self
+    }
+
+    pub fn title(
+        mut self,
+        title: &str,
+    ) -> Self {
+        self.database.title = vec![rich_text(title)];
+        self
+    }
+
+    /// Add a select property with the given options.
+    pub fn select(
+        self,
+        name: &str,
+        options: &[&str],
+    ) -> Self {
+        let select = Select {
+            options: options
+                .iter()
+                .map(|option| SelectOption {
+                    name: option.to_string(),
+                    id: SelectOptionId::from(next_id()),
+                    color: Color::Default,
+                })
+                .collect(),
+        };
+        self.property(
+            name,
+            PropertyConfiguration::Select {
+                id: property_id(name),
+                select,
+            },
+        )
+    }
+
+    /// Add any property configuration.
+    pub fn property(
+        mut self,
+        name: &str,
+        configuration: PropertyConfiguration,
+    ) -> Self {
+        self.database
+            .properties
+            .insert(name.to_string(), configuration);
+        self
+    }
+
+    pub fn build(self) -> Database {
+        self.database
+    }
+}
+
+/// Builds a [Block] with a fresh id.
+#[cfg(feature = "blocks")]
+#[derive(Debug, Clone)]
+pub struct BlockFixture {
+    common: BlockCommon,
+}
+
+#[cfg(feature = "blocks")]
+impl Default for BlockFixture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "blocks")]
+impl BlockFixture {
+    pub fn new() -> Self {
+        BlockFixture {
+            common: BlockCommon {
+                id: BlockId::from_str(&next_id()).unwrap(),
+                created_time: timestamp(),
+                last_edited_time: timestamp(),
+                has_children: false,
+                created_by: user(),
+                last_edited_by: user(),
+                parent: None,
+                archived: false,
+                in_trash: false,
+            },
+        }
+    }
+
+    pub fn id(
+        mut self,
+        id: &str,
+    ) -> Self {
+        self.common.id = BlockId::from_str(id).unwrap();
+        self
+    }
+
+    pub fn has_children(
+        mut self,
+        has_children: bool,
+    ) -> Self {
+        self.common.has_children = has_children;
+        self
+    }
+
+    /// Attribute the block to `user_id`, as its creator and last editor.
+    pub fn edited_by(
+        mut self,
+        user_id: &str,
+    ) -> Self {
+        self.common.created_by.id = UserId::from_str(user_id).unwrap();
+        self.common.last_edited_by.id = UserId::from_str(user_id).unwrap();
+        self
+    }
+
+    pub fn paragraph(
+        self,
+        text: &str,
+    ) -> Block {
+        Block::Paragraph {
+            common: self.common,
+            paragraph: text_and_children(text),
+        }
+    }
+
+    pub fn heading_1(
+        self,
+        text: &str,
+    ) -> Block {
+        Block::Heading1 {
+            common: self.common,
+            heading_1: TextFields {
+                rich_text: vec![rich_text(text)],
+                color: TextColor::Default,
+            },
+        }
+    }
+
+    pub fn bulleted_list_item(
+        self,
+        text: &str,
+    ) -> Block {
+        Block::BulletedListItem {
+            common: self.common,
+            bulleted_list_item: text_and_children(text),
+        }
+    }
+
+    pub fn numbered_list_item(
+        self,
+        text: &str,
+    ) -> Block {
+        Block::NumberedListItem {
+            common: self.common,
+            numbered_list_item: text_and_children(text),
+        }
+    }
+
+    pub fn to_do(
+        self,
+        text: &str,
+        checked: bool,
+    ) -> Block {
+        Block::ToDo {
+            common: self.common,
+            to_do: ToDoFields {
+                rich_text: vec![rich_text(text)],
+                checked,
+                children: None,
+                color: TextColor::Default,
+            },
+        }
+    }
+
+    /// An image uploaded to Notion, served from `url`.
+    pub fn image(
+        self,
+        url: &str,
+    ) -> Block {
+        Block::Image {
+            common: self.common,
+            image: FileFields {
+                caption: vec![],
+                file: FileObject::File {
+                    file: InternalFileObject {
+                        url: url.to_string(),
+                        expiry_time: timestamp(),
+                    },
+                },
+            },
+        }
+    }
+
+    /// The block of a child page, which shares the id of the page.
+    pub fn child_page(
+        self,
+        title: &str,
+    ) -> Block {
+        Block::ChildPage {
+            common: self.common,
+            child_page: ChildPageFields {
+                title: title.to_string(),
+            },
+        }
+    }
+}
+
+#[cfg(feature = "blocks")]
+fn text_and_children(text: &str) -> TextAndChildren {
+    TextAndChildren {
+        rich_text: vec![rich_text(text)],
+        children: None,
+        color: TextColor::Default,
+    }
+}
+
+#[cfg(all(test, feature = "blocks", feature = "databases"))]
+mod tests {
+    use crate::models::properties::PropertyValue;
+    use crate::models::{ListResponse, Object, Page};
+    use crate::test_util::{json, BlockFixture, DatabaseFixture, PageFixture};
+
+    #[test]
+    fn page_fixture() {
+        let page = PageFixture::new()
+            .title("X")
+            .select("Status", "Done")
+            .build();
+
+        assert_eq!(page.title(), Some("X".to_string()));
+        assert!(matches!(
+            page.properties.properties.get("Status"),
+            Some(PropertyValue::Select {
+                select: Some(_),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn fixtures_round_trip() {
+        let page = PageFixture::new().title("X").number("Budget", 3).build();
+        let json = serde_json::to_string(&page).unwrap();
+        assert_eq!(serde_json::from_str::<Page>(&json).unwrap(), page);
+
+        let database = DatabaseFixture::new().select("Status", &["Done"]).build();
+        assert_eq!(database.properties.len(), 2);
+
+        let a = BlockFixture::new().paragraph("a");
+        let b = BlockFixture::new().paragraph("a");
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn canned_json_parses() {
+        serde_json::from_str::<Page>(json::PAGE).unwrap();
+        serde_json::from_str::<ListResponse<Object>>(json::SEARCH_RESULTS).unwrap();
+    }
+}
@@ -0,0 +1,386 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+#[cfg(any(feature = "blocks", feature = "databases"))]
+use crate::ids::AsIdentifier;
+use crate::models::error::{ErrorCode, ErrorResponse, StatusCode};
+use crate::models::users::User;
+use crate::models::{ListResponse, Object, Page};
+#[cfg(feature = "blocks")]
+use crate::{ids::BlockId, models::block::Block};
+#[cfg(feature = "databases")]
+use crate::{ids::DatabaseId, models::Database};
+use crate::{ApiVersion, Error, MultipartForm, NotionApi, Result, TClient};
+
+/// A request received by a [MockClient].
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct MockRequest {
+    pub method: String,
+    /// The URI below the API base URL, such as `/pages/{id}`, with its query string.
+    pub path: String,
+    pub body: Option<Value>,
+}
+
+/// A [TClient] answering from canned responses in memory, without a server or network.
+///
+/// Responses are keyed by method and path below the API base URL. A path with
+/// a query string only matches requests with the same query string, a path
+/// without one matches regardless of the query string.
+/// Several responses for one route are served in order, and the last one repeats,
+/// which is how paginated results are mocked.
+/// Like [MockNotion](crate::test_util::MockNotion), unknown routes answer with an
+/// `object_not_found` error, and `GET /databases` and `POST /search` with an empty list.
+///
+/// ```
+/// # async fn example() -> notion_wasi::Result<()> {
+/// use notion_wasi::test_util::{MockClient, PageFixture};
+///
+/// let page = PageFixture::new().title("Canned").build();
+/// let api = MockClient::new().api();
+/// api.client().mount_page(&page);
+///
+/// assert_eq!(api.get_page(&page.id).await?, page);
+/// assert_eq!(api.client().requests()[0].path, format!("/pages/{}", page.id));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Responses captured from the real API with a [Recorder](crate::Recorder)
+/// are replayed by a [Replayer](crate::Replayer) instead.
+///
+/// Enabled with the `test-util` feature.
+#[derive(Debug, Default)]
+pub struct MockClient {
+    routes: Mutex<HashMap<(String, String), Vec<Object>>>,
+    requests: Mutex<Vec<MockRequest>>,
+    version: ApiVersion,
+}
+
+impl MockClient {
+    pub fn new() -> Self {
+        let client = Self::default();
+        client.mount("GET", "/databases", list(vec![]));
+        client.mount("POST", "/search", list(vec![]));
+        client
+    }
+
+    /// Answers as if the requests were sent with `Notion-Version: {version}`.
+    pub fn with_version(
+        mut self,
+        version: ApiVersion,
+    ) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// A NotionApi sending its requests to this client.
+    pub fn api(self) -> NotionApi<MockClient> {
+        NotionApi::with_client(self)
+    }
+
+    /// Answers `method` requests to `path` with `response` once the responses
+    /// mounted before are used up, see [MockClient].
+    /// [Object::Error] responses are returned as [Error::ApiError].
+    pub fn mount<S: Into<String>>(
+        &self,
+        method: &str,
+        path: S,
+        response: Object,
+    ) {
+        self.routes
+            .lock()
+            .unwrap()
+            .entry((method.to_string(), path.into()))
+            .or_default()
+            .push(response);
+    }
+
+    /// Answers `method` requests to `path` with a JSON fixture, such as
+    /// one of [test_util::json](crate::test_util::json).
+    pub fn mount_json<S: Into<String>>(
+        &self,
+        method: &str,
+        path: S,
+        json: &str,
+    ) -> serde_json::Result<()> {
+        self.mount(method, path, serde_json::from_str(json)?);
+        Ok(())
+    }
+
+    pub fn mount_page(
+        &self,
+        page: &Page,
+    ) {
+        let object = Object::Page { page: page.clone() };
+        self.mount("GET", format!("/pages/{}", page.id), object);
+    }
+
+    /// Serve `database` from `GET /databases/{id}`.
+    #[cfg(feature = "databases")]
+    pub fn mount_database(
+        &self,
+        database: &Database,
+    ) {
+        let object = Object::Database {
+            database: database.clone(),
+        };
+        self.mount("GET", format!("/databases/{}", database.id), object);
+    }
+
+    /// Answer `POST /databases/{id}/query` with `pages`.
+    #[cfg(feature = "databases")]
+    pub fn mount_query<D: AsIdentifier<DatabaseId>>(
+        &self,
+        database: D,
+        pages: Vec<Page>,
+    ) {
+        let results = pages
+            .into_iter()
+            .map(|page| Object::Page { page })
+            .collect();
+        self.mount(
+            "POST",
+            format!("/databases/{}/query", database.as_id()),
+            list(results),
+        );
+    }
+
+    /// Serve `blocks` from `GET /blocks/{id}/children`.
+    #[cfg(feature = "blocks")]
+    pub fn mount_block_children<B: AsIdentifier<BlockId>>(
+        &self,
+        block: B,
+        blocks: Vec<Block>,
+    ) {
+        let results = blocks
+            .into_iter()
+            .map(|block| Object::Block { block })
+            .collect();
+        self.mount(
+            "GET",
+            format!("/blocks/{}/children", block.as_id()),
+            list(results),
+        );
+    }
+
+    /// Serve `user` from `GET /users/{id}`.
+    pub fn mount_user(
+        &self,
+        user: &User,
+    ) {
+        let object = Object::User { user: user.clone() };
+        self.mount("GET", format!("/users/{}", user.id()), object);
+    }
+
+    /// The requests received so far, oldest first.
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn answer(
+        &self,
+        method: &str,
+        uri: String,
+        body: Option<Vec<u8>>,
+    ) -> Result<Object> {
+        let path = relative_path(&uri).to_string();
+        let body = body.and_then(|body| serde_json::from_slice(&body).ok());
+        self.requests.lock().unwrap().push(MockRequest {
+            method: method.to_string(),
+            path: path.clone(),
+            body,
+        });
+
+        let without_query = path.split('?').next().unwrap_or_default();
+        let mut routes = self.routes.lock().unwrap();
+        let key = [path.as_str(), without_query]
+            .iter()
+            .map(|path| (method.to_string(), path.to_string()))
+            .find(|key| routes.contains_key(key));
+        let responses = key.and_then(|key| routes.get_mut(&key));
+        let response = match responses {
+            Some(responses) if responses.len() > 1 => responses.remove(0),
+            Some(responses) => responses[0].clone(),
+            None => Object::Error { error: not_found() },
+        };
+        match response {
+            Object::Error { error } => Err(Error::ApiError { error }),
+            response => Ok(response),
+        }
+    }
+}
+
+#[async_trait]
+impl TClient for MockClient {
+    fn api_version(&self) -> ApiVersion {
+        self.version
+    }
+
+    async fn get<S: Into<String> + Send>(
+        &self,
+        uri: S,
+    ) -> Result<Object> {
+        self.answer("GET", uri.into(), None)
+    }
+
+    async fn post<S: Into<String> + Send>(
+        &self,
+        uri: S,
+    ) -> Result<Object> {
+        self.answer("POST", uri.into(), None)
+    }
+
+    async fn post_json<S: Into<String> + Send>(
+        &self,
+        uri: S,
+        body: Vec<u8>,
+    ) -> Result<Object> {
+        self.answer("POST", uri.into(), Some(body))
+    }
+
+    async fn patch_json<S: Into<String> + Send>(
+        &self,
+        uri: S,
+        body: Vec<u8>,
+    ) -> Result<Object> {
+        self.answer("PATCH", uri.into(), Some(body))
+    }
+
+    /// Recorded without a body, the form holds the contents of a file.
+    async fn post_multipart<S: Into<String> + Send>(
+        &self,
+        uri: S,
+        _form: MultipartForm,
+    ) -> Result<Object> {
+        self.answer("POST", uri.into(), None)
+    }
+
+    async fn delete<S: Into<String> + Send>(
+        &self,
+        uri: S,
+    ) -> Result<Object> {
+        self.answer("DELETE", uri.into(), None)
+    }
+}
+
+/// `uri` without the base URL, which ends in `/v1` unless it was changed.
+fn relative_path(uri: &str) -> &str {
+    match uri.find("/v1/") {
+        Some(start) => &uri[start + "/v1".len()..],
+        None => uri,
+    }
+}
+
+fn not_found() -> ErrorResponse {
+    ErrorResponse {
+        status: StatusCode::from(404),
+        code: ErrorCode::ObjectNotFound,
+        message: "Could not find object".to_string(),
+        request_id: None,
+        developer_survey: None,
+        additional_data: None,
+        retry_after: None,
+    }
+}
+
+fn list(results: Vec<Object>) -> Object {
+    Object::List {
+        list: ListResponse {
+            results,
+            next_cursor: None,
+            has_more: false,
+        },
+    }
+}
+
+#[cfg(all(test, feature = "databases"))]
+mod tests {
+    use super::MockClient;
+    use crate::models::search::{DatabaseQuery, SearchRequest, SortDirection};
+    use crate::models::Object;
+    use crate::test_util::{list_response, DatabaseFixture, PageFixture};
+    use crate::CollectAll;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn serves_pages_in_order_and_records_requests() {
+        let database = DatabaseFixture::new().build();
+        let first = PageFixture::new().title("first").build();
+        let second = PageFixture::new().title("second").build();
+        let client = MockClient::new();
+        for page in [&first, &second] {
+            let mut list = list_response(vec![Object::Page { page: page.clone() }]);
+            list.has_more = page == &first;
+            list.next_cursor = list
+                .has_more
+                .then(|| serde_json::from_value(json!("cursor")).unwrap());
+            client.mount(
+                "POST",
+                format!("/databases/{}/query", database.id),
+                Object::List { list },
+            );
+        }
+        let api = client.api();
+
+        let pages = api
+            .query_database_stream(&database.id, DatabaseQuery::default())
+            .collect_all()
+            .await
+            .unwrap();
+        assert_eq!(pages, vec![first, second]);
+
+        let requests = api.client().requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].body.as_ref().unwrap()["start_cursor"], "cursor");
+        assert!(api
+            .get_page(&pages[0].id)
+            .await
+            .unwrap_err()
+            .api_error_code()
+            .is_some());
+        assert!(api
+            .search_pages(SearchRequest::default())
+            .await
+            .unwrap()
+            .results
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn search_pages_stream_filters_and_follows_cursor() {
+        let first = PageFixture::new().title("Roadmap 2025").build();
+        let second = PageFixture::new().title("Roadmap 2026").build();
+        // Without the empty search results mounted by `new()`.
+        let client = MockClient::default();
+        for page in [&first, &second] {
+            let mut list = list_response(vec![Object::Page { page: page.clone() }]);
+            list.has_more = page == &first;
+            list.next_cursor = list
+                .has_more
+                .then(|| serde_json::from_value(json!("cursor")).unwrap());
+            client.mount("POST", "/search", Object::List { list });
+        }
+        let api = client.api();
+
+        let query = SearchRequest::default()
+            .query("Roadmap")
+            .sort_by_last_edited(SortDirection::Descending)
+            .page_size(1);
+        let pages = api.search_pages_stream(query).collect_all().await.unwrap();
+        assert_eq!(pages, vec![first, second]);
+
+        let requests = api.client().requests();
+        assert_eq!(requests.len(), 2);
+        let body = requests[1].body.as_ref().unwrap();
+        assert_eq!(body["query"], "Roadmap");
+        assert_eq!(body["filter"]["value"], "page");
+        assert_eq!(body["sort"]["timestamp"], "last_edited_time");
+        assert_eq!(body["sort"]["direction"], "descending");
+        assert_eq!(body["page_size"], 1);
+        assert_eq!(body["start_cursor"], "cursor");
+    }
+}
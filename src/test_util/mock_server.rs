@@ -0,0 +1,286 @@
+use wiremock::matchers::{bearer_token, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[cfg(any(feature = "blocks", feature = "databases"))]
+use crate::ids::AsIdentifier;
+use crate::ids::PropertyId;
+use crate::models::error::{ErrorCode, ErrorResponse, StatusCode};
+use crate::models::properties::PropertyItem;
+use crate::models::users::User;
+use crate::models::{ListResponse, Object, Page};
+use crate::NotionApi;
+#[cfg(feature = "blocks")]
+use crate::{ids::BlockId, models::block::Block};
+#[cfg(feature = "databases")]
+use crate::{
+    ids::DatabaseId,
+    models::{DataSource, Database},
+};
+
+/// The token accepted by [MockNotion].
+pub const MOCK_API_TOKEN: &str = "secret_mock_token";
+
+/// A local [wiremock] server answering like the Notion API.
+///
+/// Unknown routes answer with an `object_not_found` error,
+/// and `GET /databases` and `POST /search` answer with an empty list.
+/// Use the `mount_*` methods to serve specific objects.
+///
+/// Enabled with the `mock-server` feature.
+pub struct MockNotion {
+    server: MockServer,
+}
+
+impl MockNotion {
+    pub async fn start() -> Self {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/databases"))
+            .respond_with(ok(&list(vec![])))
+            .with_priority(u8::MAX - 1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/search"))
+            .respond_with(ok(&list(vec![])))
+            .with_priority(u8::MAX - 1)
+            .mount(&server)
+            .await;
+        Mock::given(wiremock::matchers::any())
+            .respond_with(ResponseTemplate::new(404).set_body_json(Object::Error {
+                error: ErrorResponse {
+                    status: StatusCode::from(404),
+                    code: ErrorCode::ObjectNotFound,
+                    message: "Could not find object".to_string(),
+                    request_id: None,
+                    developer_survey: None,
+                    additional_data: None,
+                    retry_after: None,
+                },
+            }))
+            .with_priority(u8::MAX)
+            .mount(&server)
+            .await;
+
+        MockNotion { server }
+    }
+
+    /// A client authenticated with [MOCK_API_TOKEN] that talks to this server.
+    pub fn api(&self) -> NotionApi {
+        NotionApi::new(MOCK_API_TOKEN)
+            .unwrap()
+            .with_base_url(self.base_url())
+    }
+
+    /// The API base URL, including the `/v1` prefix.
+    pub fn base_url(&self) -> String {
+        format!("{}/v1", self.server.uri())
+    }
+
+    /// The underlying server, for mounting custom stubs.
+    pub fn server(&self) -> &MockServer {
+        &self.server
+    }
+
+    /// Serve `page` from `GET /pages/{id}`.
+    pub async fn mount_page(
+        &self,
+        page: &Page,
+    ) {
+        self.mount(
+            "GET",
+            format!("/v1/pages/{}", page.id),
+            &Object::Page { page: page.clone() },
+        )
+        .await;
+    }
+
+    /// Serve `database` from `GET /databases/{id}`.
+    #[cfg(feature = "databases")]
+    pub async fn mount_database(
+        &self,
+        database: &Database,
+    ) {
+        self.mount(
+            "GET",
+            format!("/v1/databases/{}", database.id),
+            &Object::Database {
+                database: database.clone(),
+            },
+        )
+        .await;
+    }
+
+    /// Answer every `POST /databases/{id}/query` with `pages`.
+    #[cfg(feature = "databases")]
+    pub async fn mount_query<D: AsIdentifier<DatabaseId>>(
+        &self,
+        database: D,
+        pages: Vec<Page>,
+    ) {
+        let results = pages
+            .into_iter()
+            .map(|page| Object::Page { page })
+            .collect();
+        self.mount(
+            "POST",
+            format!("/v1/databases/{}/query", database.as_id()),
+            &list(results),
+        )
+        .await;
+    }
+
+    /// Serve `data_source` from `GET /data_sources/{id}`
+    /// and answer every `POST /data_sources/{id}/query` with `pages`.
+    #[cfg(feature = "databases")]
+    pub async fn mount_data_source(
+        &self,
+        data_source: &DataSource,
+        pages: Vec<Page>,
+    ) {
+        self.mount(
+            "GET",
+            format!("/v1/data_sources/{}", data_source.id),
+            &Object::DataSource {
+                data_source: data_source.clone(),
+            },
+        )
+        .await;
+        let results = pages
+            .into_iter()
+            .map(|page| Object::Page { page })
+            .collect();
+        self.mount(
+            "POST",
+            format!("/v1/data_sources/{}/query", data_source.id),
+            &list(results),
+        )
+        .await;
+    }
+
+    /// Serve `blocks` from `GET /blocks/{id}/children`.
+    #[cfg(feature = "blocks")]
+    pub async fn mount_block_children<B: AsIdentifier<BlockId>>(
+        &self,
+        block: B,
+        blocks: Vec<Block>,
+    ) {
+        let results = blocks
+            .into_iter()
+            .map(|block| Object::Block { block })
+            .collect();
+        self.mount(
+            "GET",
+            format!("/v1/blocks/{}/children", block.as_id()),
+            &list(results),
+        )
+        .await;
+    }
+
+    /// Answer every `POST /search` with `results`.
+    pub async fn mount_search(
+        &self,
+        results: Vec<Object>,
+    ) {
+        self.mount("POST", "/v1/search".to_string(), &list(results))
+            .await;
+    }
+
+    /// Answer every `GET /pages/{id}/properties/{property}` with `items`.
+    pub async fn mount_property_items(
+        &self,
+        page: &Page,
+        property_id: &PropertyId,
+        items: Vec<PropertyItem>,
+    ) {
+        let results = items
+            .into_iter()
+            .map(|property_item| Object::PropertyItem { property_item })
+            .collect();
+        self.mount(
+            "GET",
+            format!("/v1/pages/{}/properties/{}", page.id, property_id),
+            &list(results),
+        )
+        .await;
+    }
+
+    /// Serve `user` from `GET /users/{id}`.
+    pub async fn mount_user(
+        &self,
+        user: &User,
+    ) {
+        self.mount(
+            "GET",
+            format!("/v1/users/{}", user.id()),
+            &Object::User { user: user.clone() },
+        )
+        .await;
+    }
+
+    /// Echo `page` back from `POST /pages` and `PATCH /pages/{id}`.
+    pub async fn mount_page_writes(
+        &self,
+        page: &Page,
+    ) {
+        let object = Object::Page { page: page.clone() };
+        self.mount("POST", "/v1/pages".to_string(), &object).await;
+        self.mount("PATCH", format!("/v1/pages/{}", page.id), &object)
+            .await;
+    }
+
+    async fn mount(
+        &self,
+        http_method: &str,
+        route: String,
+        object: &Object,
+    ) {
+        Mock::given(method(http_method))
+            .and(path(route))
+            .and(bearer_token(MOCK_API_TOKEN))
+            .respond_with(ok(object))
+            .mount(&self.server)
+            .await;
+    }
+}
+
+fn ok(object: &Object) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(object)
+}
+
+fn list(results: Vec<Object>) -> Object {
+    Object::List {
+        list: ListResponse {
+            results,
+            next_cursor: None,
+            has_more: false,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ids::PageId;
+    use crate::test_util::{MockNotion, PageFixture};
+    use crate::Error;
+    use std::str::FromStr;
+
+    #[tokio::test]
+    async fn get_page() {
+        let mock = MockNotion::start().await;
+        let page = PageFixture::new().title("Mocked").build();
+        mock.mount_page(&page).await;
+
+        assert_eq!(mock.api().get_page(&page.id).await.unwrap(), page);
+    }
+
+    #[tokio::test]
+    async fn missing_objects_are_api_errors() {
+        let mock = MockNotion::start().await;
+        let missing = PageId::from_str("missing").unwrap();
+
+        let result = mock.api().get_page(missing).await;
+        assert!(matches!(result, Err(Error::ApiError { .. })));
+    }
+}
@@ -0,0 +1,96 @@
+//! Notion API versions.
+//!
+//! Notion versions its API by date, sent in the `Notion-Version` header.
+//! The models are shared between versions and accept the responses of each of them,
+//! fields that only some versions report are optional.
+//! Where versions expect different requests, the version of the client picks the shape,
+//! see [NotionApi::api_version()](crate::NotionApi::api_version()).
+
+use std::fmt;
+use std::str::FromStr;
+
+/// A supported `Notion-Version`.
+///
+/// The default is 2022-02-22, or 2022-06-28 with the `api-2022-06-28` feature.
+#[derive(Debug, Eq, PartialEq, Hash, Ord, PartialOrd, Copy, Clone)]
+pub enum ApiVersion {
+    V2022_02_22,
+    /// Adds block parents, inline databases, one-way relations
+    /// and paginated relation values.
+    V2022_06_28,
+    /// Splits databases into data sources, which hold the properties and pages.
+    /// Pages have `data_source_id` parents and are queried through their data source.
+    V2025_09_03,
+}
+
+impl ApiVersion {
+    /// Every supported version, oldest first.
+    pub const ALL: [ApiVersion; 3] = [
+        ApiVersion::V2022_02_22,
+        ApiVersion::V2022_06_28,
+        ApiVersion::V2025_09_03,
+    ];
+
+    /// The value of the `Notion-Version` header.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ApiVersion::V2022_02_22 => "2022-02-22",
+            ApiVersion::V2022_06_28 => "2022-06-28",
+            ApiVersion::V2025_09_03 => "2025-09-03",
+        }
+    }
+}
+
+impl Default for ApiVersion {
+    #[cfg(not(feature = "api-2022-06-28"))]
+    fn default() -> Self {
+        ApiVersion::V2022_02_22
+    }
+
+    #[cfg(feature = "api-2022-06-28")]
+    fn default() -> Self {
+        ApiVersion::V2022_06_28
+    }
+}
+
+impl fmt::Display for ApiVersion {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+/// A `Notion-Version` this crate doesn't support.
+#[derive(Debug, Eq, PartialEq, Clone, thiserror::Error)]
+#[error("Unsupported Notion-Version {0:?}")]
+pub struct UnsupportedApiVersion(pub String);
+
+impl FromStr for ApiVersion {
+    type Err = UnsupportedApiVersion;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ApiVersion::ALL
+            .iter()
+            .copied()
+            .find(|version| version.as_str() == s)
+            .ok_or_else(|| UnsupportedApiVersion(s.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ApiVersion, UnsupportedApiVersion};
+
+    #[test]
+    fn parse_versions() {
+        for version in ApiVersion::ALL.iter().copied() {
+            assert_eq!(version.as_str().parse(), Ok(version));
+        }
+        assert_eq!(
+            "2021-05-13".parse::<ApiVersion>(),
+            Err(UnsupportedApiVersion("2021-05-13".to_string()))
+        );
+    }
+}